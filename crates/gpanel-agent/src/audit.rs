@@ -0,0 +1,387 @@
+use axum::{
+    body::Body,
+    extract::{Query, Request, State},
+    http::Method,
+    middleware::Next,
+    response::{Json, Response},
+};
+use gpanel_core::{ApiErrorResponse, AuditConfig, AuditEvent, AuditOutcome, AuditPage, AuditQuery};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::auth::RequireAdmin;
+use crate::AppState;
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1000;
+
+/// Error bodies larger than this are not read for the event's detail
+const MAX_DETAIL_BODY: usize = 64 * 1024;
+
+/// POST endpoints whose last path segment names an action on the whole collection
+/// rather than a resource ID
+const COLLECTION_ACTIONS: &[&str] = &[
+    "batch", "copy", "login", "logout", "prune", "pull", "refresh", "registry", "reload-config", "restore", "search", "validate",
+];
+
+/// Who made a request, set on the response by whatever authenticated it
+#[derive(Debug, Clone)]
+pub struct AuditActor(pub String);
+
+/// Append-only JSON Lines audit log, rotated by size
+pub struct AuditLog {
+    path: PathBuf,
+    max_file_bytes: u64,
+    max_files: usize,
+    /// Held while writing, rotating or reading, so readers never see a half-rotated set
+    lock: Mutex<()>,
+}
+
+impl AuditLog {
+    pub fn new(config: &AuditConfig, data_dir: &str) -> Self {
+        let path = match &config.path {
+            Some(path) => PathBuf::from(path),
+            None => Path::new(data_dir).join("audit.jsonl"),
+        };
+        Self {
+            path,
+            max_file_bytes: config.max_file_bytes,
+            max_files: config.max_files,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// `audit.jsonl.N`, the Nth newest rotated file
+    fn rotated(&self, index: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    pub async fn record(&self, event: &AuditEvent) {
+        let _guard = self.lock.lock().await;
+        if let Err(e) = self.append(event).await {
+            warn!("Failed to write audit event to {}: {}", self.path.display(), e);
+        }
+    }
+
+    /// Record something done outside a request, such as a scheduled run, with the status
+    /// an equivalent request would have had
+    pub async fn record_action(
+        &self,
+        user: &str,
+        action: &str,
+        resource_type: &str,
+        resource_id: Option<&str>,
+        result: Result<(), String>,
+    ) {
+        let status = if result.is_ok() { 200 } else { 500 };
+        self.record(&AuditEvent {
+            timestamp: chrono::Utc::now(),
+            user: user.to_string(),
+            action: action.to_string(),
+            resource_type: resource_type.to_string(),
+            resource_id: resource_id.map(str::to_string),
+            outcome: AuditOutcome::from_status(status),
+            status,
+            detail: result.err(),
+        })
+        .await;
+    }
+
+    async fn append(&self, event: &AuditEvent) -> anyhow::Result<()> {
+        let mut line = serde_json::to_vec(event)?;
+        line.push(b'\n');
+
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let size = match tokio::fs::metadata(&self.path).await {
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        };
+        if size > 0 && size + line.len() as u64 > self.max_file_bytes {
+            self.rotate().await?;
+        }
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(&line).await?;
+        Ok(())
+    }
+
+    /// Shift `audit.jsonl` to `.1`, `.1` to `.2` and so on, dropping the oldest
+    async fn rotate(&self) -> std::io::Result<()> {
+        if self.max_files == 0 {
+            return tokio::fs::remove_file(&self.path).await;
+        }
+        for index in (1..self.max_files).rev() {
+            match tokio::fs::rename(self.rotated(index), self.rotated(index + 1)).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        tokio::fs::rename(&self.path, self.rotated(1)).await
+    }
+
+    /// Events matching `query`, newest first
+    pub async fn query(&self, query: &AuditQuery) -> AuditPage {
+        let offset = query.offset.unwrap_or(0);
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+        let _guard = self.lock.lock().await;
+        let files = std::iter::once(self.path.clone()).chain((1..=self.max_files).map(|index| self.rotated(index)));
+
+        let mut events = Vec::new();
+        let mut total = 0;
+        for file in files {
+            let contents = match tokio::fs::read_to_string(&file).await {
+                Ok(contents) => contents,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => {
+                    warn!("Failed to read audit log {}: {}", file.display(), e);
+                    continue;
+                }
+            };
+            for line in contents.lines().rev() {
+                let Ok(event) = serde_json::from_str::<AuditEvent>(line) else {
+                    continue;
+                };
+                if !query.matches(&event) {
+                    continue;
+                }
+                if total >= offset && events.len() < limit {
+                    events.push(event);
+                }
+                total += 1;
+            }
+        }
+
+        AuditPage {
+            events,
+            total,
+            offset,
+            limit,
+        }
+    }
+}
+
+/// `(action, resource_type, resource_id)` of a request, e.g. `DELETE /api/v1/containers/abc`
+/// is `containers.delete` on container `abc` and `POST /api/v1/containers/abc/start` is
+/// `containers.start`
+fn describe(method: &Method, path: &str) -> (String, String, Option<String>) {
+    let path = path.strip_prefix("/api/v1/").or_else(|| path.strip_prefix("/api/")).unwrap_or(path);
+    let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+
+    // Notification channels and rules sit one level deeper than other resources
+    let (resource_type, rest) = match segments.as_slice() {
+        ["notifications", kind, rest @ ..] => (format!("notifications.{}", kind), rest),
+        [kind, rest @ ..] => (kind.to_string(), rest),
+        [] => ("unknown".to_string(), &[][..]),
+    };
+
+    let by_method = match *method {
+        Method::POST => "create",
+        Method::DELETE => "delete",
+        _ => "update",
+    };
+    let (verb, id) = match rest {
+        [] => (by_method.to_string(), None),
+        [action] if *method == Method::POST && COLLECTION_ACTIONS.contains(action) => (action.to_string(), None),
+        [id] => (by_method.to_string(), Some(id.to_string())),
+        [id, action, ..] => (action.to_string(), Some(id.to_string())),
+    };
+
+    (format!("{}.{}", resource_type, verb), resource_type, id)
+}
+
+/// Message of an error response, which is put back together after reading it
+async fn error_detail(response: Response) -> (Response, Option<String>) {
+    let (parts, body) = response.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, MAX_DETAIL_BODY).await else {
+        return (Response::from_parts(parts, Body::empty()), None);
+    };
+
+    let detail = match serde_json::from_slice::<ApiErrorResponse>(&bytes) {
+        Ok(envelope) => envelope.error.message,
+        Err(_) => String::from_utf8_lossy(&bytes).chars().take(200).collect(),
+    };
+    let detail = (!detail.trim().is_empty()).then_some(detail);
+    (Response::from_parts(parts, Body::from(bytes)), detail)
+}
+
+/// Record every POST, PUT, PATCH and DELETE under `/api/`, whether it succeeded or not
+pub async fn record_mutations(State(audit): State<Arc<AuditLog>>, request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let mutating = matches!(method, Method::POST | Method::PUT | Method::PATCH | Method::DELETE);
+    if !mutating || !path.starts_with("/api/") {
+        return next.run(request).await;
+    }
+
+    let response = next.run(request).await;
+    let status = response.status();
+    let user = response
+        .extensions()
+        .get::<AuditActor>()
+        .map(|actor| actor.0.clone())
+        .unwrap_or_else(|| "anonymous".to_string());
+    let (response, detail) = if status.is_client_error() || status.is_server_error() {
+        error_detail(response).await
+    } else {
+        (response, None)
+    };
+
+    let (action, resource_type, resource_id) = describe(&method, &path);
+    audit
+        .record(&AuditEvent {
+            timestamp: chrono::Utc::now(),
+            user,
+            action,
+            resource_type,
+            resource_id,
+            outcome: AuditOutcome::from_status(status.as_u16()),
+            status: status.as_u16(),
+            detail,
+        })
+        .await;
+
+    response
+}
+
+/// Audit events, newest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit",
+    tag = "audit",
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "Matching audit events, newest first", body = AuditPage),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+    ),
+)]
+pub async fn list_audit_events(
+    _: RequireAdmin,
+    State(state): State<AppState>,
+    Query(query): Query<AuditQuery>,
+) -> Json<AuditPage> {
+    Json(state.audit.query(&query).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::hash_token;
+    use crate::testing::{agent, agent_with};
+    use axum::http::StatusCode;
+    use gpanel_core::ApiTokenConfig;
+
+    const TOKEN: &str = "ci-secret-token";
+
+    fn event(user: &str, action: &str, minutes_ago: i64) -> AuditEvent {
+        AuditEvent {
+            timestamp: chrono::Utc::now() - chrono::Duration::minutes(minutes_ago),
+            user: user.to_string(),
+            action: action.to_string(),
+            resource_type: action.split('.').next().unwrap().to_string(),
+            resource_id: None,
+            outcome: AuditOutcome::Success,
+            status: 200,
+            detail: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn mutations_are_recorded_with_their_caller_and_outcome() {
+        let agent = agent_with(
+            |config| {
+                config.auth.api_tokens.push(ApiTokenConfig {
+                    name: "ci".to_string(),
+                    sha256: hash_token(TOKEN),
+                })
+            },
+            true,
+        )
+        .await;
+
+        let volume = serde_json::json!({ "name": "scratch" });
+        let (status, _) = agent.request_as(Some(TOKEN), Method::POST, "/api/v1/volumes", Some(volume)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, _) = agent.request_as(Some(TOKEN), Method::DELETE, "/api/v1/volumes/scratch", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = agent.request_as(Some(TOKEN), Method::DELETE, "/api/v1/volumes/scratch", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = agent.request(Method::DELETE, "/api/v1/volumes/postgres_data", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        // Reads are not audited
+        agent.request_as(Some(TOKEN), Method::GET, "/api/v1/volumes", None).await;
+
+        let (status, page) = agent.request_as(Some(TOKEN), Method::GET, "/api/v1/audit", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["total"], 4);
+        let events: Vec<(&str, &str, &str, &str)> = page["events"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| {
+                let id = e["resource_id"].as_str().unwrap_or_default();
+                (e["user"].as_str().unwrap(), e["action"].as_str().unwrap(), id, e["outcome"].as_str().unwrap())
+            })
+            .collect();
+        assert_eq!(
+            events,
+            [
+                ("anonymous", "volumes.delete", "postgres_data", "denied"),
+                ("ci", "volumes.delete", "scratch", "failure"),
+                ("ci", "volumes.delete", "scratch", "success"),
+                ("ci", "volumes.create", "", "success"),
+            ]
+        );
+        assert!(page["events"][1]["detail"].as_str().unwrap().contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn events_are_filtered_and_paged() {
+        let agent = agent().await;
+        for event in [
+            event("alice", "containers.create", 90),
+            event("bob", "containers.delete", 60),
+            event("Alice", "volumes.delete", 30),
+            event("alice", "containers.start", 5),
+        ] {
+            agent.state.audit.record(&event).await;
+        }
+        let actions = |page: &serde_json::Value| -> Vec<String> {
+            page["events"].as_array().unwrap().iter().map(|e| e["action"].as_str().unwrap().to_string()).collect()
+        };
+
+        // Users match without case; newest first
+        let (_, page) = agent.request(Method::GET, "/api/v1/audit?user=alice", None).await;
+        assert_eq!(actions(&page), ["containers.start", "volumes.delete", "containers.create"]);
+
+        // A trailing dot matches every action on a resource type, otherwise actions are exact
+        let (_, page) = agent.request(Method::GET, "/api/v1/audit?action=containers.", None).await;
+        assert_eq!(actions(&page), ["containers.start", "containers.delete", "containers.create"]);
+        let (_, page) = agent.request(Method::GET, "/api/v1/audit?action=containers.delete", None).await;
+        assert_eq!(actions(&page), ["containers.delete"]);
+        let (_, page) = agent.request(Method::GET, "/api/v1/audit?action=containers", None).await;
+        assert_eq!(page["total"], 0);
+
+        let since = (chrono::Utc::now() - chrono::Duration::minutes(45)).format("%Y-%m-%dT%H:%M:%SZ");
+        let (_, page) = agent.request(Method::GET, &format!("/api/v1/audit?since={}", since), None).await;
+        assert_eq!(actions(&page), ["containers.start", "volumes.delete"]);
+
+        // The total counts every match, not just the page
+        let (_, page) = agent.request(Method::GET, "/api/v1/audit?user=alice&limit=1&offset=1", None).await;
+        assert_eq!(actions(&page), ["volumes.delete"]);
+        assert_eq!((page["total"].as_u64(), page["limit"].as_u64(), page["offset"].as_u64()), (Some(3), Some(1), Some(1)));
+        let (_, page) = agent.request(Method::GET, "/api/v1/audit?limit=0", None).await;
+        assert_eq!(page["limit"], 1);
+    }
+}
@@ -0,0 +1,799 @@
+use aes_gcm::aead::{rand_core::RngCore, OsRng};
+use axum::{
+    extract::{FromRequestParts, Query, Request, State},
+    http::{header, request::Parts, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+    Extension,
+};
+use gpanel_core::{
+    ApiErrorResponse, ApiTokenConfig, AuthConfig, LoginRequest, OperationResult, Role, SessionResponse, SessionUser,
+    WhoAmIResponse,
+};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use tracing::{info, warn};
+
+use crate::audit::AuditActor;
+use crate::error::ApiError;
+use crate::store::Store;
+use crate::AppState;
+
+/// Comma-separated plaintext tokens, hashed as the agent starts
+pub const API_TOKENS_ENV: &str = "GPANEL_API_TOKENS";
+
+const REVOKED_SESSION_STORE: &str = "revoked_sessions";
+
+/// Query parameter accepted in place of the header, for EventSource, WebSocket and
+/// download links, which cannot set one
+const TOKEN_QUERY_PARAM: &str = "access_token";
+
+/// Lowercase hex SHA-256 of a token, as stored in `AuthConfig`
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.trim().as_bytes()))
+}
+
+/// Who a request was authenticated as, set by `require_token`
+#[derive(Debug, Clone)]
+pub struct Caller {
+    pub user: SessionUser,
+    /// The session the request was made with; `None` for API tokens
+    pub session: Option<SessionClaims>,
+}
+
+/// Hashes of the tokens the agent accepts
+#[derive(Debug, Default)]
+pub struct ApiTokens {
+    /// (name, hex SHA-256), those from the config first
+    hashes: Vec<(String, String)>,
+    /// How many of `hashes` come from the config rather than `GPANEL_API_TOKENS`
+    configured: usize,
+}
+
+impl ApiTokens {
+    /// Tokens from the config plus those in `env`, the value of `GPANEL_API_TOKENS`
+    pub fn load(config: &AuthConfig, env: Option<&str>) -> Self {
+        let mut hashes: Vec<(String, String)> = config
+            .api_tokens
+            .iter()
+            .map(|token| (token.name.clone(), token.sha256.trim().to_ascii_lowercase()))
+            .collect();
+        let configured = hashes.len();
+
+        let from_env = env
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|token| !token.is_empty());
+        for (index, token) in from_env.enumerate() {
+            hashes.push((format!("env-{}", index + 1), hash_token(token)));
+        }
+
+        Self { hashes, configured }
+    }
+
+    /// Tokens from the config, as backed up; those from the environment stay with the host
+    pub fn configured(&self) -> Vec<ApiTokenConfig> {
+        self.hashes[..self.configured]
+            .iter()
+            .map(|(name, sha256)| ApiTokenConfig {
+                name: name.clone(),
+                sha256: sha256.clone(),
+            })
+            .collect()
+    }
+
+    /// Replace the tokens from the config, keeping those from the environment
+    pub fn replace_configured(&mut self, tokens: &[ApiTokenConfig]) {
+        let from_env = self.hashes.split_off(self.configured);
+        self.hashes = tokens
+            .iter()
+            .map(|token| (token.name.clone(), token.sha256.trim().to_ascii_lowercase()))
+            .collect();
+        self.configured = self.hashes.len();
+        self.hashes.extend(from_env);
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Name of the token matching `token`. Only hashes are compared, so timing
+    /// reveals nothing about the stored tokens.
+    pub fn verify(&self, token: &str) -> Option<&str> {
+        let hash = hash_token(token);
+        self.hashes
+            .iter()
+            .find(|(_, stored)| *stored == hash)
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+/// Claims of a session JWT minted after a sign-in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionClaims {
+    pub sub: String,
+    pub username: String,
+    pub email: String,
+    pub roles: Vec<String>,
+    /// `name` of the identity provider the user signed in with
+    pub provider: String,
+    /// Unique per session, so a signed-out session can be refused
+    pub jti: String,
+    /// When the user signed in; refreshed tokens keep it
+    #[serde(default)]
+    pub auth_time: i64,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+/// Signs and checks session JWTs. Only HS256 is accepted, so a token cannot pick
+/// its own algorithm.
+pub struct SessionKeys {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    ttl_secs: u64,
+    max_age_secs: u64,
+}
+
+impl SessionKeys {
+    pub fn new(config: &AuthConfig) -> Self {
+        let secret = match &config.session_secret {
+            Some(secret) => secret.as_bytes().to_vec(),
+            None => {
+                warn!("No auth.session_secret set; sessions will not survive an agent restart");
+                let mut secret = vec![0u8; 32];
+                OsRng.fill_bytes(&mut secret);
+                secret
+            }
+        };
+
+        Self {
+            encoding: EncodingKey::from_secret(&secret),
+            decoding: DecodingKey::from_secret(&secret),
+            ttl_secs: config.session_ttl_secs,
+            max_age_secs: config.session_max_age_secs,
+        }
+    }
+
+    pub fn ttl_secs(&self) -> u64 {
+        self.ttl_secs
+    }
+
+    /// Token for a user who has just signed in
+    pub fn mint(&self, user: &SessionUser, provider: &str) -> anyhow::Result<String> {
+        let now = chrono::Utc::now().timestamp();
+        self.sign(SessionClaims {
+            sub: user.id.clone(),
+            username: user.username.clone(),
+            email: user.email.clone(),
+            roles: user.roles.clone(),
+            provider: provider.to_string(),
+            jti: String::new(),
+            auth_time: now,
+            iat: now,
+            exp: now,
+        })
+    }
+
+    /// Whether the sign-in behind `claims` is recent enough to refresh
+    pub fn renewable(&self, claims: &SessionClaims) -> bool {
+        chrono::Utc::now().timestamp() < claims.auth_time + self.max_age_secs as i64
+    }
+
+    /// New token for the same sign-in, with a fresh expiry
+    pub fn renew(&self, claims: &SessionClaims) -> anyhow::Result<String> {
+        self.sign(claims.clone())
+    }
+
+    fn sign(&self, mut claims: SessionClaims) -> anyhow::Result<String> {
+        let now = chrono::Utc::now().timestamp();
+        claims.jti = uuid::Uuid::new_v4().to_string();
+        claims.iat = now;
+        claims.exp = now + self.ttl_secs as i64;
+        Ok(jsonwebtoken::encode(&Header::new(Algorithm::HS256), &claims, &self.encoding)?)
+    }
+
+    /// Claims of an unexpired session token signed with this key
+    pub fn verify(&self, token: &str) -> Option<SessionClaims> {
+        let validation = Validation::new(Algorithm::HS256);
+        jsonwebtoken::decode::<SessionClaims>(token, &self.decoding, &validation)
+            .ok()
+            .map(|data| data.claims)
+    }
+}
+
+/// Accepts API tokens and session JWTs
+pub struct Authenticator {
+    /// False when the agent runs with `--no-auth`, in which case every caller is an admin
+    pub enabled: bool,
+    /// Replaced when a backup is restored
+    pub tokens: RwLock<ApiTokens>,
+    pub sessions: SessionKeys,
+    /// `jti` to expiry of sessions signed out before they expired
+    revoked: Mutex<HashMap<String, i64>>,
+    store: Store,
+}
+
+impl Authenticator {
+    pub async fn load(enabled: bool, tokens: ApiTokens, sessions: SessionKeys, store: Store) -> Self {
+        let mut revoked: HashMap<String, i64> = store.load(REVOKED_SESSION_STORE).await;
+        let now = chrono::Utc::now().timestamp();
+        revoked.retain(|_, exp| *exp > now);
+
+        Self {
+            enabled,
+            tokens: RwLock::new(tokens),
+            sessions,
+            revoked: Mutex::new(revoked),
+            store,
+        }
+    }
+
+    /// Who a request carrying `token` comes from
+    pub fn authenticate(&self, token: &str) -> Option<Caller> {
+        let name = self
+            .tokens
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .verify(token)
+            .map(str::to_string);
+        if let Some(name) = name {
+            return Some(Caller {
+                user: SessionUser {
+                    id: format!("token:{}", name),
+                    username: name,
+                    email: String::new(),
+                    roles: vec!["admin".to_string()],
+                },
+                session: None,
+            });
+        }
+
+        let claims = self.sessions.verify(token)?;
+        if self.revoked.lock().ok()?.contains_key(&claims.jti) {
+            return None;
+        }
+        Some(Caller {
+            user: SessionUser {
+                id: claims.sub.clone(),
+                username: claims.username.clone(),
+                email: claims.email.clone(),
+                roles: claims.roles.clone(),
+            },
+            session: Some(claims),
+        })
+    }
+
+    /// Refuse a session from now until it would have expired
+    pub async fn revoke(&self, claims: &SessionClaims) -> anyhow::Result<()> {
+        let revoked = {
+            let mut revoked = self.revoked.lock().map_err(|_| anyhow::anyhow!("Revocation list is poisoned"))?;
+            let now = chrono::Utc::now().timestamp();
+            revoked.retain(|_, exp| *exp > now);
+            revoked.insert(claims.jti.clone(), claims.exp);
+            revoked.clone()
+        };
+        self.store.save(REVOKED_SESSION_STORE, &revoked).await
+    }
+}
+
+/// Paths that need a token: everything under `/api/v1/` except the health check, sign-in
+/// and registry notifications, which carry their own secret
+fn requires_token(path: &str) -> bool {
+    path.starts_with("/api/v1/")
+        && path != "/api/v1/health"
+        && path != "/api/v1/auth/login"
+        && path != "/api/v1/webhooks/registry"
+}
+
+fn request_token(request: &Request) -> Option<String> {
+    if let Some(value) = request.headers().get(header::AUTHORIZATION) {
+        let value = value.to_str().ok()?;
+        return value
+            .strip_prefix("Bearer ")
+            .or_else(|| value.strip_prefix("bearer "))
+            .map(|token| token.trim().to_string());
+    }
+
+    let Query(mut query) = Query::<HashMap<String, String>>::try_from_uri(request.uri()).ok()?;
+    query.remove(TOKEN_QUERY_PARAM)
+}
+
+fn unauthorized(code: &str, message: &str) -> Response {
+    let mut response = ApiError::new(StatusCode::UNAUTHORIZED, code, message).into_response();
+    response
+        .headers_mut()
+        .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+    response
+}
+
+/// Reject `/api/v1` requests without a valid API token or session
+pub async fn require_token(State(auth): State<Arc<Authenticator>>, mut request: Request, next: Next) -> Response {
+    if !requires_token(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let Some(token) = request_token(&request) else {
+        return unauthorized("UNAUTHORIZED", "Missing API token; send `Authorization: Bearer <token>`");
+    };
+    let Some(caller) = auth.authenticate(&token) else {
+        return unauthorized("INVALID_TOKEN", "Invalid or expired token");
+    };
+
+    let actor = AuditActor(caller.user.username.clone());
+    request.extensions_mut().insert(caller);
+    let mut response = next.run(request).await;
+    response.extensions_mut().insert(actor);
+    response
+}
+
+/// Handler argument admitting operators and admins
+pub struct RequireOperator;
+
+/// Handler argument admitting admins only
+pub struct RequireAdmin;
+
+fn authorize(parts: &Parts, auth: &Authenticator, role: Role) -> Result<(), ApiError> {
+    let user = match parts.extensions.get::<Caller>() {
+        Some(caller) => caller.user.clone(),
+        None if !auth.enabled => anonymous(),
+        None => return Err(ApiError::new(StatusCode::UNAUTHORIZED, "UNAUTHORIZED", "Not signed in")),
+    };
+
+    if !user.has_role(role) {
+        return Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            "INSUFFICIENT_ROLE",
+            format!("This action needs the {} role", role),
+        )
+        .with_details(serde_json::json!({ "required": role, "roles": user.roles })));
+    }
+    Ok(())
+}
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for RequireOperator {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        authorize(parts, &state.auth, Role::Operator).map(|()| Self)
+    }
+}
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for RequireAdmin {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        authorize(parts, &state.auth, Role::Admin).map(|()| Self)
+    }
+}
+
+/// Stand-in user when the agent runs without authentication
+fn anonymous() -> SessionUser {
+    SessionUser {
+        id: "anonymous".to_string(),
+        username: "anonymous".to_string(),
+        email: String::new(),
+        roles: vec!["admin".to_string()],
+    }
+}
+
+/// The token the request was made with, so clients can check a token before storing it
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/whoami",
+    tag = "auth",
+    responses((status = 200, description = "Name of the token or user the request was made with", body = WhoAmIResponse)),
+)]
+pub async fn whoami(caller: Option<Extension<Caller>>) -> Json<WhoAmIResponse> {
+    Json(match caller {
+        Some(Extension(caller)) => WhoAmIResponse {
+            name: caller.user.username,
+            auth_enabled: true,
+        },
+        None => WhoAmIResponse {
+            name: "anonymous".to_string(),
+            auth_enabled: false,
+        },
+    })
+}
+
+/// Sign in with a local account. The audit log records the username tried, whether or not it worked.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login",
+    tag = "auth",
+    security(()),
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Session token", body = SessionResponse),
+        (status = 401, description = "Wrong username or password", body = ApiErrorResponse),
+    ),
+)]
+pub async fn login(State(state): State<AppState>, Json(request): Json<LoginRequest>) -> Response {
+    let actor = AuditActor(request.username.trim().to_string());
+    let mut response = local_sign_in(&state, &request).await.into_response();
+    response.extensions_mut().insert(actor);
+    response
+}
+
+async fn local_sign_in(state: &AppState, request: &LoginRequest) -> Result<Json<SessionResponse>, ApiError> {
+    let Some(user) = state.users.verify(&request.username, &request.password).await else {
+        warn!("Failed sign-in for local user '{}'", request.username.trim());
+        return Err(ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "INVALID_CREDENTIALS",
+            "Invalid username or password",
+        ));
+    };
+
+    let token = state.auth.sessions.mint(&user, "local").map_err(|e| {
+        ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create session: {}", e))
+    })?;
+    info!("{} signed in with a local account", user.username);
+
+    Ok(Json(SessionResponse {
+        token,
+        expires_in: state.auth.sessions.ttl_secs(),
+        user,
+    }))
+}
+
+/// End the session the request was made with. API tokens stay valid.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/logout",
+    tag = "auth",
+    responses((status = 200, description = "Session revoked", body = OperationResult)),
+)]
+pub async fn logout(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+) -> Result<Json<OperationResult>, ApiError> {
+    let Some(claims) = caller.and_then(|Extension(caller)| caller.session) else {
+        return Ok(Json(OperationResult {
+            success: true,
+            message: "No session to sign out of".to_string(),
+        }));
+    };
+
+    state.auth.revoke(&claims).await.map_err(|e| {
+        ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to sign out: {}", e))
+    })?;
+    info!("{} signed out", claims.username);
+
+    Ok(Json(OperationResult {
+        success: true,
+        message: "Signed out".to_string(),
+    }))
+}
+
+/// Swap the session token the request was made with for a new one. The old token is
+/// revoked, and sessions older than `auth.session_max_age_secs` must sign in again.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/refresh",
+    tag = "auth",
+    responses(
+        (status = 200, description = "New session token; the old one is revoked", body = SessionResponse),
+        (status = 400, description = "Request was made with an API token, not a session", body = ApiErrorResponse),
+        (status = 401, description = "Session is past its maximum age", body = ApiErrorResponse),
+    ),
+)]
+pub async fn refresh(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+) -> Result<Json<SessionResponse>, ApiError> {
+    let Some(Extension(Caller { user, session: Some(claims) })) = caller else {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "NOT_A_SESSION",
+            "Only session tokens can be refreshed",
+        ));
+    };
+    if !state.auth.sessions.renewable(&claims) {
+        return Err(ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "SESSION_EXPIRED",
+            "Session is too old to refresh; sign in again",
+        ));
+    }
+
+    let token = state.auth.sessions.renew(&claims).map_err(|e| {
+        ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to refresh session: {}", e))
+    })?;
+    state.auth.revoke(&claims).await.map_err(|e| {
+        ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to refresh session: {}", e))
+    })?;
+
+    Ok(Json(SessionResponse {
+        token,
+        expires_in: state.auth.sessions.ttl_secs(),
+        user,
+    }))
+}
+
+/// The signed-in user
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/me",
+    tag = "auth",
+    responses((status = 200, description = "The signed-in user", body = SessionUser)),
+)]
+pub async fn me(caller: Option<Extension<Caller>>) -> Json<SessionUser> {
+    Json(caller.map_or_else(anonymous, |Extension(caller)| caller.user))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{agent_with, TestAgent};
+    use axum::{body::Body, http::Method};
+
+    const TOKEN: &str = "ci-secret-token";
+
+    async fn secured_agent() -> TestAgent {
+        agent_with(
+            |config| {
+                config.auth.api_tokens.push(ApiTokenConfig {
+                    name: "ci".to_string(),
+                    sha256: hash_token(TOKEN),
+                })
+            },
+            true,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn api_routes_need_a_valid_token() {
+        let agent = secured_agent().await;
+
+        let request = axum::http::Request::get("/api/v1/containers").body(Body::empty()).unwrap();
+        let mut app = agent.app.clone();
+        let response = tower::Service::call(&mut app, request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+
+        let (status, body) = agent.request(Method::GET, "/api/v1/containers", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"]["code"], "UNAUTHORIZED");
+
+        let (status, body) = agent
+            .request_as(Some("wrong-token"), Method::GET, "/api/v1/containers", None)
+            .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"]["code"], "INVALID_TOKEN");
+
+        let (status, _) = agent.request_as(Some(TOKEN), Method::GET, "/api/v1/containers", None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, body) = agent.request_as(Some(TOKEN), Method::GET, "/api/v1/auth/whoami", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["name"], "ci");
+        assert_eq!(body["auth_enabled"], true);
+    }
+
+    #[tokio::test]
+    async fn token_can_be_sent_as_a_query_parameter() {
+        let agent = secured_agent().await;
+
+        let uri = format!("/api/v1/containers?{}={}", TOKEN_QUERY_PARAM, TOKEN);
+        let (status, _) = agent.request(Method::GET, &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let uri = format!("/api/v1/containers?{}=wrong-token", TOKEN_QUERY_PARAM);
+        let (status, _) = agent.request(Method::GET, &uri, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn health_check_needs_no_token() {
+        let agent = secured_agent().await;
+
+        for uri in ["/health", "/api/v1/health"] {
+            let (status, _) = agent.request(Method::GET, uri, None).await;
+            assert_eq!(status, StatusCode::OK, "{}", uri);
+        }
+    }
+
+    #[test]
+    fn configured_tokens_are_replaced_without_dropping_env_tokens() {
+        let config = AuthConfig {
+            api_tokens: vec![ApiTokenConfig {
+                name: "ci".to_string(),
+                sha256: hash_token(TOKEN),
+            }],
+            ..Default::default()
+        };
+        let mut tokens = ApiTokens::load(&config, Some("from-env, ,"));
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens.verify(TOKEN), Some("ci"));
+        assert_eq!(tokens.verify("from-env"), Some("env-1"));
+
+        tokens.replace_configured(&[]);
+        assert!(tokens.configured().is_empty());
+        assert_eq!(tokens.verify(TOKEN), None);
+        assert_eq!(tokens.verify("from-env"), Some("env-1"));
+    }
+
+    /// Session token for a user holding `roles`
+    fn session(agent: &TestAgent, roles: &[&str]) -> String {
+        let user = SessionUser {
+            id: "local:tester".to_string(),
+            username: "tester".to_string(),
+            email: String::new(),
+            roles: roles.iter().map(|role| role.to_string()).collect(),
+        };
+        agent.state.auth.sessions.mint(&user, "local").unwrap()
+    }
+
+    #[tokio::test]
+    async fn routes_need_their_role() {
+        let agent = secured_agent().await;
+        let routes = [
+            (Method::GET, "/api/v1/containers", Role::Viewer),
+            (Method::GET, "/api/v1/volumes", Role::Viewer),
+            (Method::POST, "/api/v1/containers/mock_database_003/start", Role::Operator),
+            (Method::POST, "/api/v1/volumes", Role::Operator),
+            (Method::POST, "/api/v1/schedules", Role::Operator),
+            (Method::DELETE, "/api/v1/images/missing", Role::Operator),
+            (Method::POST, "/api/v1/registries", Role::Admin),
+            (Method::DELETE, "/api/v1/registries/missing", Role::Admin),
+            (Method::POST, "/api/v1/notifications/channels", Role::Admin),
+            (Method::POST, "/api/v1/webhooks", Role::Admin),
+            (Method::GET, "/api/v1/system/backup", Role::Admin),
+            (Method::GET, "/api/v1/audit", Role::Admin),
+        ];
+
+        for held in [Role::Viewer, Role::Operator, Role::Admin] {
+            let token = session(&agent, &[held.as_str()]);
+            for (method, uri, required) in &routes {
+                let (status, body) = agent
+                    .request_as(Some(&token), method.clone(), uri, Some(serde_json::json!({})))
+                    .await;
+                if held >= *required {
+                    assert_ne!(status, StatusCode::FORBIDDEN, "{} may {} {}", held, method, uri);
+                    assert_ne!(status, StatusCode::UNAUTHORIZED, "{} may {} {}", held, method, uri);
+                } else {
+                    assert_eq!(status, StatusCode::FORBIDDEN, "{} may not {} {}", held, method, uri);
+                    assert_eq!(body["error"]["code"], "INSUFFICIENT_ROLE");
+                    assert_eq!(body["error"]["details"]["required"], required.as_str());
+                    assert_eq!(body["error"]["details"]["roles"], serde_json::json!([held.as_str()]));
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_roles_grant_nothing() {
+        let agent = secured_agent().await;
+        let token = session(&agent, &["superuser"]);
+
+        let (status, _) = agent.request_as(Some(&token), Method::GET, "/api/v1/containers", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = agent
+            .request_as(Some(&token), Method::POST, "/api/v1/containers/mock_database_003/start", None)
+            .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn api_tokens_and_disabled_auth_act_as_admin() {
+        let agent = secured_agent().await;
+        let (status, _) = agent.request_as(Some(TOKEN), Method::GET, "/api/v1/audit", None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let open = crate::testing::agent().await;
+        let (status, _) = open.request(Method::GET, "/api/v1/audit", None).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    const SESSION_SECRET: &[u8] = b"test-session-secret";
+
+    fn claims(roles: &[&str], auth_time: i64, exp: i64) -> SessionClaims {
+        let now = chrono::Utc::now().timestamp();
+        SessionClaims {
+            sub: "local:tester".to_string(),
+            username: "tester".to_string(),
+            email: String::new(),
+            roles: roles.iter().map(|role| role.to_string()).collect(),
+            provider: "local".to_string(),
+            jti: uuid::Uuid::new_v4().to_string(),
+            auth_time,
+            iat: now,
+            exp,
+        }
+    }
+
+    fn sign(claims: &SessionClaims, algorithm: Algorithm, secret: &[u8]) -> String {
+        jsonwebtoken::encode(&Header::new(algorithm), claims, &EncodingKey::from_secret(secret)).unwrap()
+    }
+
+    async fn whoami_status(agent: &TestAgent, token: &str) -> StatusCode {
+        agent
+            .request_as(Some(token), Method::GET, "/api/v1/auth/whoami", None)
+            .await
+            .0
+    }
+
+    #[tokio::test]
+    async fn only_unexpired_hs256_tokens_signed_with_the_session_key_are_accepted() {
+        let agent = secured_agent().await;
+        let now = chrono::Utc::now().timestamp();
+
+        let valid = sign(&claims(&["viewer"], now, now + 600), Algorithm::HS256, SESSION_SECRET);
+        assert_eq!(whoami_status(&agent, &valid).await, StatusCode::OK);
+
+        // Well past the validation leeway
+        let expired = sign(&claims(&["viewer"], now - 7200, now - 3600), Algorithm::HS256, SESSION_SECRET);
+        assert_eq!(whoami_status(&agent, &expired).await, StatusCode::UNAUTHORIZED);
+
+        let other_key = sign(&claims(&["admin"], now, now + 600), Algorithm::HS256, b"attacker-secret");
+        assert_eq!(whoami_status(&agent, &other_key).await, StatusCode::UNAUTHORIZED);
+
+        let other_algorithm = sign(&claims(&["viewer"], now, now + 600), Algorithm::HS512, SESSION_SECRET);
+        assert_eq!(whoami_status(&agent, &other_algorithm).await, StatusCode::UNAUTHORIZED);
+
+        // Unsigned token: header {"alg":"none","typ":"JWT"} with the payload of a valid one
+        let payload = valid.split('.').nth(1).unwrap();
+        let unsigned = format!("eyJhbGciOiJub25lIiwidHlwIjoiSldUIn0.{}.", payload);
+        assert_eq!(whoami_status(&agent, &unsigned).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn tampered_claims_are_refused() {
+        let agent = secured_agent().await;
+        let now = chrono::Utc::now().timestamp();
+
+        let viewer = sign(&claims(&["viewer"], now, now + 600), Algorithm::HS256, SESSION_SECRET);
+        let forged = sign(&claims(&["admin"], now, now + 600), Algorithm::HS256, b"attacker-secret");
+        let [header, _, signature]: [&str; 3] = viewer.split('.').collect::<Vec<_>>().try_into().unwrap();
+        let admin_payload = forged.split('.').nth(1).unwrap();
+        let tampered = format!("{}.{}.{}", header, admin_payload, signature);
+
+        assert_eq!(whoami_status(&agent, &tampered).await, StatusCode::UNAUTHORIZED);
+        assert!(agent.state.auth.sessions.verify(&tampered).is_none());
+    }
+
+    #[tokio::test]
+    async fn signed_out_and_refreshed_sessions_are_revoked() {
+        let agent = secured_agent().await;
+        let token = session(&agent, &["viewer"]);
+
+        let (status, body) = agent.request_as(Some(&token), Method::POST, "/api/v1/auth/refresh", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let renewed = body["token"].as_str().unwrap().to_string();
+        assert_eq!(whoami_status(&agent, &token).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(whoami_status(&agent, &renewed).await, StatusCode::OK);
+
+        let (status, _) = agent.request_as(Some(&renewed), Method::POST, "/api/v1/auth/logout", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(whoami_status(&agent, &renewed).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn only_recent_sessions_can_be_refreshed() {
+        let agent = secured_agent().await;
+        let now = chrono::Utc::now().timestamp();
+        let max_age = agent.state.config.auth.session_max_age_secs as i64;
+
+        let old = sign(&claims(&["viewer"], now - max_age - 60, now + 600), Algorithm::HS256, SESSION_SECRET);
+        let (status, body) = agent.request_as(Some(&old), Method::POST, "/api/v1/auth/refresh", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"]["code"], "SESSION_EXPIRED");
+
+        let (status, body) = agent.request_as(Some(TOKEN), Method::POST, "/api/v1/auth/refresh", None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "NOT_A_SESSION");
+    }
+}
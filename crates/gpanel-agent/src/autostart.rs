@@ -0,0 +1,175 @@
+use axum::response::Json;
+use futures::StreamExt;
+use gpanel_core::{AutostartEntry, AutostartReport, ContainerFilter, ContainerRuntime, ContainerStatus};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{error, info, warn};
+
+use crate::environments::Env;
+
+/// Containers started at once, so a host with many of them doesn't start everything together
+const AUTOSTART_CONCURRENCY: usize = 4;
+
+/// How long to keep retrying a runtime that is still coming up after the reboot
+const RUNTIME_ATTEMPTS: u32 = 30;
+const RUNTIME_RETRY: Duration = Duration::from_secs(2);
+
+/// Result of the boot-time pass, shared with the report endpoint
+pub type AutostartState = Arc<RwLock<AutostartReport>>;
+
+pub fn autostart_state(enabled: bool) -> AutostartState {
+    Arc::new(RwLock::new(AutostartReport {
+        enabled,
+        ..AutostartReport::default()
+    }))
+}
+
+async fn wait_for_runtime(runtime: &dyn ContainerRuntime) -> bool {
+    for attempt in 1..=RUNTIME_ATTEMPTS {
+        match runtime.ping().await {
+            Ok(true) => return true,
+            Ok(false) => warn!("Runtime not ready for autostart (attempt {}/{})", attempt, RUNTIME_ATTEMPTS),
+            Err(e) => warn!("Runtime not reachable for autostart (attempt {}/{}): {}", attempt, RUNTIME_ATTEMPTS, e),
+        }
+        tokio::time::sleep(RUNTIME_RETRY).await;
+    }
+    false
+}
+
+/// Start exited containers whose restart policy says they should be running, once the
+/// runtime of `env` answers. Runs once at boot and records the outcome in the
+/// environment's report.
+pub async fn run_autostart(env: Env) {
+    if !wait_for_runtime(env.runtime.as_ref()).await {
+        error!("Skipping autostart in {}, the runtime did not become reachable", env.name);
+        let mut report = env.autostart.write().await;
+        report.error = Some("Container runtime was not reachable".to_string());
+        report.completed_at = Some(chrono::Utc::now());
+        return;
+    }
+
+    let filter = ContainerFilter {
+        status: Some(ContainerStatus::Exited { code: 0 }),
+        ..ContainerFilter::default()
+    };
+    let candidates = match env.runtime.list_containers(Some(filter)).await {
+        Ok(containers) => containers
+            .into_iter()
+            .filter(|container| container.restart_policy.starts_on_boot())
+            .collect::<Vec<_>>(),
+        Err(e) => {
+            error!("Skipping autostart in {}, failed to list containers: {}", env.name, e);
+            let mut report = env.autostart.write().await;
+            report.error = Some(format!("Failed to list containers: {}", e));
+            report.completed_at = Some(chrono::Utc::now());
+            return;
+        }
+    };
+
+    info!("Autostart: {} exited containers in {} should be running", candidates.len(), env.name);
+
+    let entries: Vec<AutostartEntry> = futures::stream::iter(candidates)
+        .map(|container| {
+            let env = env.clone();
+            async move {
+                let outcome = env.runtime.start_container(&container.id).await;
+                match &outcome {
+                    Ok(_) => {
+                        info!("Autostart: started {} ({:?})", container.name, container.restart_policy);
+                        env.publish_status(&container.id).await;
+                    }
+                    Err(e) => error!("Autostart: failed to start {}: {}", container.name, e),
+                }
+                AutostartEntry {
+                    container_id: container.id,
+                    name: container.name,
+                    restart_policy: container.restart_policy,
+                    error: outcome.err().map(|e| e.to_string()),
+                }
+            }
+        })
+        .buffer_unordered(AUTOSTART_CONCURRENCY)
+        .collect()
+        .await;
+
+    let (failed, started): (Vec<_>, Vec<_>) = entries.into_iter().partition(|entry| entry.error.is_some());
+    info!("Autostart in {} finished: {} started, {} failed", env.name, started.len(), failed.len());
+
+    let mut report = env.autostart.write().await;
+    report.started = started;
+    report.failed = failed;
+    report.completed_at = Some(chrono::Utc::now());
+}
+
+/// What the boot-time autostart pass started or failed to start
+#[utoipa::path(
+    get,
+    path = "/api/v1/system/autostart-report",
+    tag = "system",
+    responses((status = 200, description = "Containers started at boot and any that failed", body = AutostartReport)),
+)]
+pub async fn autostart_report(env: Env) -> Json<AutostartReport> {
+    Json(env.autostart.read().await.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::agent;
+    use axum::http::Method;
+    use gpanel_core::{CreateContainerRequest, RestartPolicy};
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn only_containers_meant_to_run_are_started() {
+        let agent = agent().await;
+        let env = agent.state.environments.default_env();
+        let runtime = env.runtime.clone();
+
+        let on_failure = runtime
+            .create_container(CreateContainerRequest {
+                name: Some("batch-job".to_string()),
+                image: "redis:7-alpine".to_string(),
+                ports: Vec::new(),
+                volumes: Vec::new(),
+                networks: Vec::new(),
+                env: HashMap::new(),
+                labels: HashMap::new(),
+                gaming_config: None,
+                gpu_allocation: None,
+                restart_policy: RestartPolicy::OnFailure { max_retries: Some(3) },
+                health: None,
+            })
+            .await
+            .unwrap();
+        runtime.start_container(&on_failure.id).await.unwrap();
+        // As after a reboot: the `Always` and `UnlessStopped` containers have exited too,
+        // next to the stopped `No` database
+        let (web, gaming, batch) = tokio::join!(
+            runtime.stop_container("mock_web_server_001", None),
+            runtime.stop_container("mock_gaming_container_002", None),
+            runtime.stop_container(&on_failure.id, None),
+        );
+        for stopped in [web, gaming, batch] {
+            stopped.unwrap();
+        }
+
+        run_autostart(env.clone()).await;
+
+        let status = async |id: &str| runtime.get_container(id).await.unwrap().status;
+        assert_eq!(status("mock_web_server_001").await, ContainerStatus::Running);
+        assert_eq!(status("mock_gaming_container_002").await, ContainerStatus::Running);
+        assert!(matches!(status("mock_database_003").await, ContainerStatus::Exited { .. }));
+        assert!(matches!(status(&on_failure.id).await, ContainerStatus::Exited { .. }));
+
+        let (_, report) = agent.request(Method::GET, "/api/v1/system/autostart-report", None).await;
+        let mut started: Vec<&str> =
+            report["started"].as_array().unwrap().iter().map(|entry| entry["container_id"].as_str().unwrap()).collect();
+        started.sort();
+        assert_eq!(started, ["mock_gaming_container_002", "mock_web_server_001"]);
+        assert_eq!(report["failed"], serde_json::json!([]));
+        assert!(report["error"].is_null());
+        assert!(!report["completed_at"].is_null());
+    }
+}
@@ -0,0 +1,611 @@
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Nonce,
+};
+use argon2::Argon2;
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use gpanel_core::{
+    ApiErrorResponse, ApiTokenConfig, ChangeKind, GhostPanelConfig, RegistryClient, RegistryConfig, RestoreChange,
+    RestoreReport,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+use utoipa::IntoParams;
+
+use crate::auth::RequireAdmin;
+use crate::error::ApiError;
+use crate::users::LocalUser;
+use crate::AppState;
+
+/// Current archive format; restores from newer formats are refused
+pub const BACKUP_FORMAT_VERSION: u16 = 1;
+
+const BACKUP_MAGIC: &[u8; 4] = b"GPBK";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = BACKUP_MAGIC.len() + 2 + SALT_LEN + NONCE_LEN;
+
+/// Header carrying the archive passphrase, kept out of URLs and access logs
+const PASSPHRASE_HEADER: &str = "x-backup-passphrase";
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Decrypted archive contents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PanelBackup {
+    pub format_version: u16,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub agent_version: String,
+    pub settings: GhostPanelConfig,
+    /// Registries as configured at backup time, including credentials
+    pub registries: Vec<RegistryConfig>,
+    /// Local accounts, with password hashes only
+    #[serde(default)]
+    pub users: Vec<LocalUser>,
+    /// Hashes of the API tokens from the config; those from `GPANEL_API_TOKENS` are left out
+    #[serde(default)]
+    pub api_tokens: Vec<ApiTokenConfig>,
+}
+
+/// Query parameters for POST /api/v1/system/restore
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RestoreQuery {
+    /// Report what would change without applying it (the default)
+    pub dry_run: Option<bool>,
+    /// Token from the dry run, required to apply
+    pub confirm: Option<String>,
+}
+
+fn passphrase(headers: &HeaderMap) -> Result<String, ApiError> {
+    let passphrase = headers
+        .get(PASSPHRASE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    if passphrase.len() < MIN_PASSPHRASE_LEN {
+        return Err(ApiError::from_status(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "A passphrase of at least {} characters is required in the {} header",
+                MIN_PASSPHRASE_LEN, PASSPHRASE_HEADER
+            ),
+        ));
+    }
+    Ok(passphrase.to_string())
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> anyhow::Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+/// Serialize and encrypt a backup: magic, format version, salt, nonce, then AES-256-GCM ciphertext
+pub fn encrypt_backup(backup: &PanelBackup, passphrase: &str) -> anyhow::Result<Vec<u8>> {
+    let plaintext = serde_json::to_vec(backup)?;
+
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt)?;
+
+    let cipher = Aes256Gcm::new_from_slice(&key)?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_slice())
+        .map_err(|_| anyhow::anyhow!("Backup encryption failed"))?;
+
+    let mut archive = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+    archive.extend_from_slice(BACKUP_MAGIC);
+    archive.extend_from_slice(&backup.format_version.to_be_bytes());
+    archive.extend_from_slice(&salt);
+    archive.extend_from_slice(&nonce);
+    archive.extend_from_slice(&ciphertext);
+    Ok(archive)
+}
+
+/// Check the archive header and decrypt it
+pub fn decrypt_backup(archive: &[u8], passphrase: &str) -> Result<PanelBackup, ApiError> {
+    if archive.len() < HEADER_LEN || &archive[..BACKUP_MAGIC.len()] != BACKUP_MAGIC {
+        return Err(ApiError::from_status(StatusCode::BAD_REQUEST, "Not a GhostPanel backup archive"));
+    }
+
+    let version = u16::from_be_bytes([archive[4], archive[5]]);
+    if version > BACKUP_FORMAT_VERSION {
+        return Err(ApiError::from_status(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!(
+                "Backup format version {} is newer than this agent supports ({}); upgrade the agent first",
+                version, BACKUP_FORMAT_VERSION
+            ),
+        ));
+    }
+
+    let salt = &archive[6..6 + SALT_LEN];
+    let nonce = Nonce::from_slice(&archive[6 + SALT_LEN..HEADER_LEN]);
+    let ciphertext = &archive[HEADER_LEN..];
+
+    let key = derive_key(passphrase, salt)
+        .map_err(|e| ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let cipher = Aes256Gcm::new_from_slice(&key)
+        .map_err(|e| ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let plaintext = cipher
+        .decrypt(nonce, ciphertext)
+        .map_err(|_| ApiError::from_status(StatusCode::BAD_REQUEST, "Wrong passphrase or corrupted archive"))?;
+
+    serde_json::from_slice(&plaintext)
+        .map_err(|e| ApiError::from_status(StatusCode::BAD_REQUEST, format!("Invalid backup contents: {}", e)))
+}
+
+/// Snapshot the restorable state of a running agent
+pub async fn snapshot(state: &AppState) -> PanelBackup {
+    let mut registries = state.registry_manager.read().await.registry_configs();
+    registries.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let api_tokens = state
+        .auth
+        .tokens
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .configured();
+
+    PanelBackup {
+        format_version: BACKUP_FORMAT_VERSION,
+        created_at: chrono::Utc::now(),
+        agent_version: env!("CARGO_PKG_VERSION").to_string(),
+        settings: state.config.clone(),
+        registries,
+        users: state.users.all().await,
+        api_tokens,
+    }
+}
+
+/// Compare a backup against the current state
+pub fn diff_backup(current: &PanelBackup, restored: &PanelBackup) -> Vec<RestoreChange> {
+    let mut changes = Vec::new();
+    let change = |section: &str, item: &str, kind| RestoreChange {
+        section: section.to_string(),
+        item: item.to_string(),
+        kind,
+    };
+
+    for registry in &restored.registries {
+        match current.registries.iter().find(|r| r.name == registry.name) {
+            None => changes.push(change("registries", &registry.name, ChangeKind::Added)),
+            Some(existing) if !same_registry(existing, registry) => {
+                changes.push(change("registries", &registry.name, ChangeKind::Modified))
+            }
+            Some(_) => {}
+        }
+    }
+    for registry in &current.registries {
+        if !restored.registries.iter().any(|r| r.name == registry.name) {
+            changes.push(change("registries", &registry.name, ChangeKind::Removed));
+        }
+    }
+
+    for user in &restored.users {
+        match current.users.iter().find(|u| u.username == user.username) {
+            None => changes.push(change("users", &user.username, ChangeKind::Added)),
+            Some(existing) if existing.password_hash != user.password_hash || existing.roles != user.roles => {
+                changes.push(change("users", &user.username, ChangeKind::Modified))
+            }
+            Some(_) => {}
+        }
+    }
+    for user in &current.users {
+        if !restored.users.iter().any(|u| u.username == user.username) {
+            changes.push(change("users", &user.username, ChangeKind::Removed));
+        }
+    }
+
+    for token in &restored.api_tokens {
+        match current.api_tokens.iter().find(|t| t.name == token.name) {
+            None => changes.push(change("api_tokens", &token.name, ChangeKind::Added)),
+            Some(existing) if existing != token => changes.push(change("api_tokens", &token.name, ChangeKind::Modified)),
+            Some(_) => {}
+        }
+    }
+    for token in &current.api_tokens {
+        if !restored.api_tokens.iter().any(|t| t.name == token.name) {
+            changes.push(change("api_tokens", &token.name, ChangeKind::Removed));
+        }
+    }
+
+    let settings = |config: &GhostPanelConfig| {
+        let mut value = serde_json::to_value(config).unwrap_or_default();
+        if let Some(object) = value.as_object_mut() {
+            object.remove("registries");
+        }
+        if let Some(auth) = value.get_mut("auth").and_then(|auth| auth.as_object_mut()) {
+            auth.remove("api_tokens");
+        }
+        value
+    };
+    let (current_settings, restored_settings) = (settings(&current.settings), settings(&restored.settings));
+    if let (Some(current), Some(restored)) = (current_settings.as_object(), restored_settings.as_object()) {
+        for (key, value) in restored {
+            if current.get(key) != Some(value) {
+                changes.push(change("settings", key, ChangeKind::Modified));
+            }
+        }
+    }
+
+    changes
+}
+
+fn same_registry(a: &RegistryConfig, b: &RegistryConfig) -> bool {
+    a.url == b.url && a.username == b.username && a.password == b.password && a.insecure == b.insecure
+}
+
+fn confirmation_token(archive: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(archive))[..16].to_string()
+}
+
+/// Download an encrypted backup of the panel configuration
+#[utoipa::path(
+    get,
+    path = "/api/v1/system/backup",
+    tag = "system",
+    params(("x-backup-passphrase" = String, Header, description = "Passphrase the archive is encrypted with, at least 8 characters")),
+    responses(
+        (status = 200, description = "Encrypted backup archive", content_type = "application/octet-stream", body = Vec<u8>),
+        (status = 400, description = "Passphrase missing or too short", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+    ),
+)]
+pub async fn download_backup(
+    _: RequireAdmin,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let passphrase = passphrase(&headers)?;
+    let backup = snapshot(&state).await;
+
+    let archive = encrypt_backup(&backup, &passphrase)
+        .map_err(|e| ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create backup: {}", e)))?;
+
+    info!(
+        "Created backup with {} registries and {} users",
+        backup.registries.len(),
+        backup.users.len()
+    );
+
+    let filename = format!("ghostpanel-backup-{}.gpbk", backup.created_at.format("%Y%m%d-%H%M%S"));
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        archive,
+    )
+        .into_response())
+}
+
+/// Restore panel configuration from a backup, as a dry run unless confirmed
+#[utoipa::path(
+    post,
+    path = "/api/v1/system/restore",
+    tag = "system",
+    params(
+        RestoreQuery,
+        ("x-backup-passphrase" = String, Header, description = "Passphrase the archive was encrypted with"),
+    ),
+    request_body(content = Vec<u8>, content_type = "application/octet-stream", description = "Backup archive"),
+    responses(
+        (status = 200, description = "Changes the archive makes, applied unless this was a dry run", body = RestoreReport),
+        (status = 400, description = "Wrong passphrase or not a backup archive", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+        (status = 412, description = "Confirmation token does not match the archive", body = ApiErrorResponse),
+    ),
+)]
+pub async fn restore_backup(
+    _: RequireAdmin,
+    State(state): State<AppState>,
+    Query(query): Query<RestoreQuery>,
+    headers: HeaderMap,
+    archive: Bytes,
+) -> Result<Json<RestoreReport>, ApiError> {
+    let passphrase = passphrase(&headers)?;
+    let restored = decrypt_backup(&archive, &passphrase)?;
+    let current = snapshot(&state).await;
+
+    let token = confirmation_token(&archive);
+    let dry_run = query.dry_run.unwrap_or(true);
+    let mut report = RestoreReport {
+        dry_run,
+        format_version: restored.format_version,
+        created_at: restored.created_at,
+        changes: diff_backup(&current, &restored),
+        confirmation_token: token.clone(),
+        warnings: Vec::new(),
+    };
+
+    if dry_run {
+        return Ok(Json(report));
+    }
+
+    if query.confirm.as_deref() != Some(token.as_str()) {
+        return Err(ApiError::from_status(
+            StatusCode::PRECONDITION_FAILED,
+            "Run a dry run first and pass its confirmation_token as confirm",
+        ));
+    }
+
+    // Authenticated before the manager is locked, so searches and pulls are not held up
+    // while each registry answers. A registry that fails is restored anyway, so its
+    // health check shows what is wrong.
+    let proxy = &state.config.proxy;
+    let clients = futures::future::join_all(
+        restored
+            .registries
+            .iter()
+            .filter(|registry| {
+                current
+                    .registries
+                    .iter()
+                    .find(|r| r.name == registry.name)
+                    .is_none_or(|existing| !same_registry(existing, registry))
+            })
+            .map(|registry| async move {
+                let client = RegistryClient::with_proxy(registry.clone(), proxy);
+                let result = client.authenticate().await;
+                (registry.name.as_str(), client, result)
+            }),
+    )
+    .await;
+    {
+        let mut manager = state.registry_manager.write().await;
+        for registry in &current.registries {
+            if !restored.registries.iter().any(|r| r.name == registry.name) {
+                manager.remove_registry(&registry.name);
+            }
+        }
+        for (name, client, result) in clients {
+            if let Err(e) = result {
+                warn!("Restored registry {} failed to authenticate: {}", name, e);
+                report
+                    .warnings
+                    .push(format!("Registry {} was restored but could not authenticate: {}", name, e));
+            }
+            manager.insert_client(client);
+        }
+    }
+    let (monitor, manager) = (state.registry_health.clone(), state.registry_manager.clone());
+    tokio::spawn(async move { monitor.check_all(&manager).await });
+
+    if let Err(e) = state.users.replace(restored.users).await {
+        warn!("Failed to restore users: {}", e);
+        report.warnings.push(format!("Users could not be restored: {}", e));
+    }
+    state
+        .auth
+        .tokens
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .replace_configured(&restored.api_tokens);
+
+    let mut settings = restored.settings;
+    settings.registries = restored.registries;
+    settings.auth.api_tokens = restored.api_tokens;
+    match state.config_reloader.replace(settings).await {
+        Ok(restart_required) => {
+            // The config file now lists every registry, including those added through the API
+            if let Err(e) = state.registry_store.clear().await {
+                warn!("Failed to clear saved registries: {:#}", e);
+                report.warnings.push(format!("Registries saved through the API could not be cleared: {:#}", e));
+            }
+            if !restart_required.is_empty() {
+                report.warnings.push(format!(
+                    "Settings were saved; restart the agent to apply {}",
+                    restart_required.join(", ")
+                ));
+            }
+        }
+        Err(e) => {
+            warn!("Failed to save restored settings: {:#}", e);
+            report.warnings.push(format!("Settings could not be saved: {:#}", e));
+        }
+    }
+
+    info!("Restored backup from {} with {} changes", report.created_at, report.changes.len());
+    Ok(Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::hash_token;
+    use crate::testing::{agent, agent_with, TestAgent};
+    use axum::{body::Body, http::Request};
+
+    const PASSPHRASE: &str = "correct horse battery";
+
+    fn registry(name: &str) -> RegistryConfig {
+        RegistryConfig {
+            name: name.to_string(),
+            // Nothing listens here, so authenticating fails
+            url: "http://127.0.0.1:1".to_string(),
+            username: Some("ci".to_string()),
+            password: Some("secret".to_string()),
+            insecure: true,
+            page_size: None,
+            max_list_entries: None,
+            search_url: None,
+            ca_cert_path: None,
+        }
+    }
+
+    async fn restore(agent: &TestAgent, archive: &[u8], query: &str) -> (StatusCode, serde_json::Value) {
+        let request = Request::post(format!("/api/v1/system/restore{}", query))
+            .header(PASSPHRASE_HEADER, PASSPHRASE)
+            .header(header::CONTENT_TYPE, "application/octet-stream")
+            .body(Body::from(archive.to_vec()))
+            .unwrap();
+        let (status, body) = agent.send(request).await;
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    fn has_change(report: &serde_json::Value, section: &str, item: &str, kind: &str) -> bool {
+        report["changes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|c| c["section"] == section && c["item"] == item && c["kind"] == kind)
+    }
+
+    #[test]
+    fn archives_refuse_wrong_passphrases_and_newer_formats() {
+        let backup = PanelBackup {
+            format_version: BACKUP_FORMAT_VERSION,
+            created_at: chrono::Utc::now(),
+            agent_version: "test".to_string(),
+            settings: GhostPanelConfig::default(),
+            registries: vec![registry("local")],
+            users: Vec::new(),
+            api_tokens: Vec::new(),
+        };
+        let mut archive = encrypt_backup(&backup, PASSPHRASE).unwrap();
+        let decrypted = decrypt_backup(&archive, PASSPHRASE).unwrap();
+        assert_eq!(decrypted.registries, backup.registries);
+
+        let wrong = decrypt_backup(&archive, "wrong passphrase").unwrap_err();
+        assert_eq!(wrong.status, StatusCode::BAD_REQUEST);
+
+        archive[4..6].copy_from_slice(&(BACKUP_FORMAT_VERSION + 1).to_be_bytes());
+        let newer = decrypt_backup(&archive, PASSPHRASE).unwrap_err();
+        assert_eq!(newer.status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn backup_round_trips_into_a_fresh_agent() {
+        let source = agent_with(
+            |config| {
+                config.agent_port = 9443;
+                config.auth.api_tokens.push(ApiTokenConfig {
+                    name: "ci".to_string(),
+                    sha256: hash_token("ci-token"),
+                });
+            },
+            false,
+        )
+        .await;
+        source.state.registry_manager.write().await.insert_registry(registry("local"));
+        let password = source.state.users.bootstrap().await.unwrap().unwrap();
+
+        let request = Request::get("/api/v1/system/backup")
+            .header(PASSPHRASE_HEADER, PASSPHRASE)
+            .body(Body::empty())
+            .unwrap();
+        let (status, archive) = source.send(request).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let target = agent().await;
+        target.state.registry_manager.write().await.insert_registry(registry("stale"));
+
+        let (status, report) = restore(&target, &archive, "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(report["dry_run"], true);
+        assert!(has_change(&report, "registries", "local", "Added"));
+        assert!(has_change(&report, "registries", "stale", "Removed"));
+        assert!(has_change(&report, "users", "admin", "Added"));
+        assert!(has_change(&report, "api_tokens", "ci", "Added"));
+        assert!(has_change(&report, "settings", "agent_port", "Modified"));
+        assert!(target.state.registry_manager.read().await.get_registry("local").is_none());
+
+        let (status, _) = restore(&target, &archive, "?dry_run=false&confirm=wrong").await;
+        assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+
+        let token = report["confirmation_token"].as_str().unwrap();
+        let (status, report) = restore(&target, &archive, &format!("?dry_run=false&confirm={}", token)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        // Kept even though it could not authenticate
+        let registries = target.state.registry_manager.read().await.registry_configs();
+        assert_eq!(registries, vec![registry("local")]);
+        let warnings = report["warnings"].as_array().unwrap();
+        assert!(warnings.iter().any(|w| w.as_str().unwrap().contains("Registry local")));
+        assert!(warnings.iter().any(|w| w.as_str().unwrap().contains("agent_port")));
+
+        assert!(target.state.users.verify("admin", &password).await.is_some());
+        assert!(target.state.auth.authenticate("ci-token").is_some());
+
+        let saved = GhostPanelConfig::from_file(&std::path::Path::new(&target.state.config.data_dir).join("gpanel.toml"))
+            .unwrap();
+        assert_eq!(saved.agent_port, 9443);
+        assert_eq!(saved.registries, vec![registry("local")]);
+        assert_eq!(saved.auth.api_tokens.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn registries_added_through_the_api_survive_a_restore_and_restart() {
+        let agent = agent().await;
+        let url = crate::testing::serve_stub(axum::Router::new().route("/v2/", axum::routing::get(|| async { "{}" }))).await;
+        let mirror = serde_json::json!({
+            "name": "mirror", "url": url, "username": "ci", "password": "secret", "insecure": true,
+        });
+        let (status, _) = agent.request(axum::http::Method::POST, "/api/v1/registries", Some(mirror)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let request = Request::get("/api/v1/system/backup")
+            .header(PASSPHRASE_HEADER, PASSPHRASE)
+            .body(Body::empty())
+            .unwrap();
+        let (_, archive) = agent.send(request).await;
+        agent.request(axum::http::Method::DELETE, "/api/v1/registries/mirror", None).await;
+
+        let (_, report) = restore(&agent, &archive, "").await;
+        assert!(has_change(&report, "registries", "mirror", "Added"));
+        let token = report["confirmation_token"].as_str().unwrap();
+        let (status, _) = restore(&agent, &archive, &format!("?dry_run=false&confirm={}", token)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let agent = agent.restart().await;
+        let manager = agent.state.registry_manager.read().await;
+        assert_eq!(manager.list_registries(), ["mirror"]);
+        assert_eq!(manager.get_registry("mirror").unwrap().config().password.as_deref(), Some("secret"));
+    }
+
+    #[tokio::test]
+    async fn restored_registries_connect_with_their_tls_settings() {
+        let (url, _) = crate::testing::serve_tls_stub(axum::Router::new().route("/v2/", axum::routing::get(|| async { "{}" }))).await;
+        let source = agent().await;
+        let harbor = RegistryConfig {
+            name: "harbor".to_string(),
+            url,
+            username: None,
+            password: None,
+            insecure: true,
+            ca_cert_path: None,
+            page_size: None,
+            max_list_entries: None,
+            search_url: None,
+        };
+        source.state.registry_manager.write().await.insert_registry(harbor);
+        let request = Request::get("/api/v1/system/backup")
+            .header(PASSPHRASE_HEADER, PASSPHRASE)
+            .body(Body::empty())
+            .unwrap();
+        let (_, archive) = source.send(request).await;
+
+        let target = agent().await;
+        let (_, report) = restore(&target, &archive, "").await;
+        let token = report["confirmation_token"].as_str().unwrap();
+        let (status, report) = restore(&target, &archive, &format!("?dry_run=false&confirm={}", token)).await;
+        assert_eq!(status, StatusCode::OK);
+        let warnings = report["warnings"].as_array().unwrap();
+        assert!(
+            !warnings.iter().any(|w| w.as_str().unwrap().contains("Registry harbor")),
+            "{:?}",
+            warnings
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use axum::http::{HeaderName, HeaderValue, Method};
+use gpanel_core::GhostPanelConfig;
+use std::time::Duration;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tracing::{info, warn};
+
+/// An allowed origin from `cors_allowed_origins`
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum OriginPattern {
+    Exact(String),
+    /// `https://*.example.com` as `("https://", ".example.com")`; matches any subdomain
+    /// of `example.com` over that scheme and port, but not `example.com` itself
+    Subdomain { scheme: String, suffix: String },
+}
+
+impl OriginPattern {
+    fn parse(origin: &str) -> Option<Self> {
+        let origin = origin.trim().trim_end_matches('/').to_ascii_lowercase();
+        let (scheme, host) = origin.split_once("://")?;
+        if scheme.is_empty() || host.is_empty() || host.contains('/') {
+            return None;
+        }
+        match host.strip_prefix('*') {
+            Some(rest) if rest.starts_with('.') && rest.len() > 1 && !rest.contains('*') => Some(Self::Subdomain {
+                scheme: format!("{}://", scheme),
+                suffix: rest.to_string(),
+            }),
+            Some(_) => None,
+            None if !host.contains('*') => Some(Self::Exact(origin)),
+            None => None,
+        }
+    }
+
+    fn matches(&self, origin: &str) -> bool {
+        let origin = origin.to_ascii_lowercase();
+        match self {
+            Self::Exact(allowed) => origin == *allowed,
+            Self::Subdomain { scheme, suffix } => origin
+                .strip_prefix(scheme.as_str())
+                .and_then(|host| host.strip_suffix(suffix.as_str()))
+                .is_some_and(|subdomain| {
+                    !subdomain.is_empty()
+                        && !subdomain.starts_with('.')
+                        && subdomain.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+                }),
+        }
+    }
+}
+
+/// The web UI on `web_port`, over HTTPS or plain HTTP
+fn web_ui_origins(config: &GhostPanelConfig) -> Vec<String> {
+    vec![
+        format!("https://localhost:{}", config.web_port),
+        format!("http://localhost:{}", config.web_port),
+    ]
+}
+
+/// CORS policy from the `cors_*` settings. A `*` origin is ignored while auth is enabled,
+/// since any site could then drive the API with a token the browser holds.
+pub fn layer(config: &GhostPanelConfig, auth_enabled: bool) -> CorsLayer {
+    let methods: Vec<Method> = config
+        .cors_allowed_methods
+        .iter()
+        .filter_map(|method| match Method::from_bytes(method.trim().to_ascii_uppercase().as_bytes()) {
+            Ok(method) => Some(method),
+            Err(_) => {
+                warn!("Ignoring invalid CORS method {:?}", method);
+                None
+            }
+        })
+        .collect();
+    let headers: Vec<HeaderName> = config
+        .cors_allowed_headers
+        .iter()
+        .filter_map(|header| match HeaderName::from_bytes(header.trim().as_bytes()) {
+            Ok(header) => Some(header),
+            Err(_) => {
+                warn!("Ignoring invalid CORS header {:?}", header);
+                None
+            }
+        })
+        .collect();
+    let max_age = Duration::from_secs(config.cors_max_age_secs);
+
+    let configured = if config.cors_allowed_origins.is_empty() {
+        web_ui_origins(config)
+    } else {
+        config.cors_allowed_origins.clone()
+    };
+    let wildcard = configured.iter().any(|origin| origin.trim() == "*");
+    if wildcard && auth_enabled {
+        warn!("Ignoring CORS origin \"*\", which is only allowed with --no-auth");
+    }
+
+    let cors = CorsLayer::new().allow_methods(methods.clone()).allow_headers(headers.clone()).max_age(max_age);
+    let method_names: Vec<&str> = methods.iter().map(Method::as_str).collect();
+    let header_names: Vec<&str> = headers.iter().map(HeaderName::as_str).collect();
+
+    if wildcard && !auth_enabled {
+        info!(
+            "CORS: any origin, methods [{}], headers [{}], max age {}s",
+            method_names.join(", "),
+            header_names.join(", "),
+            config.cors_max_age_secs
+        );
+        return cors.allow_origin(AllowOrigin::any());
+    }
+
+    let patterns: Vec<OriginPattern> = configured
+        .iter()
+        .filter(|origin| origin.trim() != "*")
+        .filter_map(|origin| {
+            let pattern = OriginPattern::parse(origin);
+            if pattern.is_none() {
+                warn!("Ignoring invalid CORS origin {:?}", origin);
+            }
+            pattern
+        })
+        .collect();
+    let origin_names: Vec<String> = patterns
+        .iter()
+        .map(|pattern| match pattern {
+            OriginPattern::Exact(origin) => origin.clone(),
+            OriginPattern::Subdomain { scheme, suffix } => format!("{}*{}", scheme, suffix),
+        })
+        .collect();
+    info!(
+        "CORS: origins [{}], methods [{}], headers [{}], max age {}s",
+        origin_names.join(", "),
+        method_names.join(", "),
+        header_names.join(", "),
+        config.cors_max_age_secs
+    );
+
+    cors.allow_origin(AllowOrigin::predicate(move |origin: &HeaderValue, _| {
+        origin
+            .to_str()
+            .is_ok_and(|origin| patterns.iter().any(|pattern| pattern.matches(origin)))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{agent_with, TestAgent};
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+    };
+    use tower::Service;
+
+    async fn agent_allowing(origins: &[&str], auth_enabled: bool) -> TestAgent {
+        let origins: Vec<String> = origins.iter().map(|origin| origin.to_string()).collect();
+        agent_with(|config| config.cors_allowed_origins = origins, auth_enabled).await
+    }
+
+    /// Send a preflight from `origin` and return the allowed origin, if any
+    async fn preflight(agent: &TestAgent, origin: &str) -> Option<String> {
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/api/v1/containers")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization")
+            .body(Body::empty())
+            .unwrap();
+        let mut app = agent.app.clone();
+        std::future::poll_fn(|cx| Service::<Request<Body>>::poll_ready(&mut app, cx)).await.unwrap();
+        let response = app.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "preflights are answered before auth");
+        response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .map(|value| value.to_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn exact_origins_are_allowed_and_others_are_not() {
+        let agent = agent_allowing(&["https://panel.example.com"], true).await;
+        let allowed = preflight(&agent, "https://panel.example.com").await;
+        assert_eq!(allowed.as_deref(), Some("https://panel.example.com"));
+        for origin in ["https://evil.example.com", "http://panel.example.com", "https://panel.example.com:8443"] {
+            assert_eq!(preflight(&agent, origin).await, None, "{}", origin);
+        }
+    }
+
+    #[tokio::test]
+    async fn the_web_ui_is_allowed_by_default() {
+        let agent = agent_with(|config| config.web_port = 3000, true).await;
+        assert!(preflight(&agent, "http://localhost:3000").await.is_some());
+        assert!(preflight(&agent, "https://localhost:3000").await.is_some());
+        assert_eq!(preflight(&agent, "http://localhost:4000").await, None);
+    }
+
+    #[tokio::test]
+    async fn wildcard_subdomains_match_subdomains_only() {
+        let agent = agent_allowing(&["https://*.example.com"], true).await;
+        for origin in ["https://a.example.com", "https://a.b.example.com", "https://A.Example.com"] {
+            assert_eq!(preflight(&agent, origin).await.as_deref(), Some(origin), "{}", origin);
+        }
+        for origin in [
+            "https://example.com",
+            "https://evil-example.com",
+            "https://a.example.com.evil.net",
+            "http://a.example.com",
+            "https://.example.com",
+        ] {
+            assert_eq!(preflight(&agent, origin).await, None, "{}", origin);
+        }
+    }
+
+    #[tokio::test]
+    async fn any_origin_is_ignored_while_auth_is_enabled() {
+        let agent = agent_allowing(&["*", "https://panel.example.com"], true).await;
+        assert_eq!(preflight(&agent, "https://evil.example.com").await, None);
+        assert!(preflight(&agent, "https://panel.example.com").await.is_some());
+
+        let agent = agent_allowing(&["*"], false).await;
+        assert_eq!(preflight(&agent, "https://anywhere.example").await.as_deref(), Some("*"));
+    }
+
+    #[test]
+    fn origin_patterns_parse() {
+        assert_eq!(
+            OriginPattern::parse("HTTPS://Panel.example.com/"),
+            Some(OriginPattern::Exact("https://panel.example.com".to_string()))
+        );
+        assert_eq!(
+            OriginPattern::parse("https://*.example.com"),
+            Some(OriginPattern::Subdomain {
+                scheme: "https://".to_string(),
+                suffix: ".example.com".to_string(),
+            })
+        );
+        for invalid in ["example.com", "https://", "https://*example.com", "https://*.", "https://a.*.com", "https://x/path"] {
+            assert_eq!(OriginPattern::parse(invalid), None, "{}", invalid);
+        }
+    }
+}
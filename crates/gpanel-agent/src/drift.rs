@@ -0,0 +1,180 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use gpanel_core::{ApiErrorResponse, DriftClient, DriftGcStatus, DriftInfo, DriftQuota};
+use tracing::error;
+
+use crate::auth::RequireAdmin;
+use crate::error::ApiError;
+use crate::AppState;
+
+/// Drift's endpoints on registry `name`, failing with 501 when it is not a Drift registry
+async fn drift_client(state: &AppState, name: &str) -> Result<DriftClient, ApiError> {
+    // Cloned so the registry list is not locked while the registry answers
+    let client = state
+        .registry_manager
+        .read()
+        .await
+        .get_registry(name)
+        .cloned()
+        .ok_or_else(|| ApiError::not_found("REGISTRY", format!("Registry '{}' not found", name)))?;
+
+    match client.drift().await {
+        Ok(Some(drift)) => Ok(drift),
+        Ok(None) => Err(ApiError::new(
+            StatusCode::NOT_IMPLEMENTED,
+            "REGISTRY_NOT_DRIFT",
+            format!("{} is not a Drift registry", name),
+        )),
+        Err(e) => Err(failed(name, "reach", e)),
+    }
+}
+
+fn failed(name: &str, action: &str, e: gpanel_core::Error) -> ApiError {
+    error!("Failed to {} Drift registry {}: {}", action, name, e);
+    ApiError::runtime(&e, format!("Failed to {} the registry: {}", action, e))
+}
+
+/// Drift's version and extensions; answers 501 for other registries, so it also tells
+/// whether a registry is Drift
+#[utoipa::path(
+    get,
+    path = "/api/v1/registries/{name}/drift/info",
+    tag = "registries",
+    params(("name" = String, Path, description = "Registry name")),
+    responses(
+        (status = 200, description = "The registry is Drift", body = DriftInfo),
+        (status = 404, description = "No such registry", body = ApiErrorResponse),
+        (status = 501, description = "The registry is not Drift", body = ApiErrorResponse),
+        (status = 502, description = "Registry request failed", body = ApiErrorResponse),
+    ),
+)]
+pub async fn drift_info(State(state): State<AppState>, Path(name): Path<String>) -> Result<Json<DriftInfo>, ApiError> {
+    let drift = drift_client(&state, &name).await?;
+    Ok(Json(drift.info().clone()))
+}
+
+/// Start a garbage collection, deleting blobs no manifest refers to; follow it at
+/// `GET /api/v1/registries/{name}/drift/gc`. While one runs, its status is returned instead.
+#[utoipa::path(
+    post,
+    path = "/api/v1/registries/{name}/drift/gc",
+    tag = "registries",
+    params(("name" = String, Path, description = "Registry name")),
+    responses(
+        (status = 202, description = "Collection started or already running", body = DriftGcStatus),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+        (status = 404, description = "No such registry", body = ApiErrorResponse),
+        (status = 501, description = "The registry is not Drift", body = ApiErrorResponse),
+        (status = 502, description = "Registry request failed", body = ApiErrorResponse),
+    ),
+)]
+pub async fn trigger_drift_gc(
+    _: RequireAdmin,
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<(StatusCode, Json<DriftGcStatus>), ApiError> {
+    let drift = drift_client(&state, &name).await?;
+    let status = drift.trigger_gc().await.map_err(|e| failed(&name, "start garbage collection on", e))?;
+    Ok((StatusCode::ACCEPTED, Json(status)))
+}
+
+/// The running garbage collection, or the last one
+#[utoipa::path(
+    get,
+    path = "/api/v1/registries/{name}/drift/gc",
+    tag = "registries",
+    params(("name" = String, Path, description = "Registry name")),
+    responses(
+        (status = 200, description = "Status of the running or last collection", body = DriftGcStatus),
+        (status = 404, description = "No such registry", body = ApiErrorResponse),
+        (status = 501, description = "The registry is not Drift", body = ApiErrorResponse),
+        (status = 502, description = "Registry request failed", body = ApiErrorResponse),
+    ),
+)]
+pub async fn drift_gc_status(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<DriftGcStatus>, ApiError> {
+    let drift = drift_client(&state, &name).await?;
+    let status = drift.gc_status().await.map_err(|e| failed(&name, "get garbage collection status from", e))?;
+    Ok(Json(status))
+}
+
+/// Storage the registry uses, and its limit
+#[utoipa::path(
+    get,
+    path = "/api/v1/registries/{name}/drift/quota",
+    tag = "registries",
+    params(("name" = String, Path, description = "Registry name")),
+    responses(
+        (status = 200, description = "Storage used and allowed", body = DriftQuota),
+        (status = 404, description = "No such registry", body = ApiErrorResponse),
+        (status = 501, description = "The registry is not Drift", body = ApiErrorResponse),
+        (status = 502, description = "Registry request failed", body = ApiErrorResponse),
+    ),
+)]
+pub async fn drift_quota(State(state): State<AppState>, Path(name): Path<String>) -> Result<Json<DriftQuota>, ApiError> {
+    let drift = drift_client(&state, &name).await?;
+    let quota = drift.get_quota().await.map_err(|e| failed(&name, "get the quota of", e))?;
+    Ok(Json(quota))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{agent, serve_stub};
+    use axum::{
+        http::{Method, StatusCode},
+        routing::get,
+        Router,
+    };
+
+    #[tokio::test]
+    async fn drift_routes_reach_drift_registries_only() {
+        let agent = agent().await;
+        let status = r#"{"state":"running","started_at":"2024-05-02T10:00:00Z","finished_at":null}"#;
+        let drift = Router::new()
+            .route("/drift/v1/info", get(|| async { r#"{"version":"0.4.2","features":["gc","quota"]}"# }))
+            .route("/drift/v1/gc", get(move || async move { status }).post(move || async move { (StatusCode::ACCEPTED, status) }))
+            .route("/drift/v1/quota", get(|| async { r#"{"used_bytes":1024,"limit_bytes":null}"# }));
+        let plain = Router::new().route("/v2/", get(|| async { "{}" }));
+        for (name, routes) in [("drift", drift), ("plain", plain)] {
+            let url = serve_stub(routes).await;
+            agent.state.registry_manager.write().await.insert_registry(gpanel_core::RegistryConfig {
+                name: name.to_string(),
+                url,
+                username: None,
+                password: None,
+                insecure: true,
+                page_size: None,
+                max_list_entries: None,
+                search_url: None,
+                ca_cert_path: None,
+            });
+        }
+
+        let (status, body) = agent.request(Method::GET, "/api/v1/registries/drift/drift/info", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["version"], "0.4.2");
+        let (status, body) = agent.request(Method::POST, "/api/v1/registries/drift/drift/gc", None).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert_eq!(body["state"], "running");
+        let (status, body) = agent.request(Method::GET, "/api/v1/registries/drift/drift/gc", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["blobs_removed"], 0);
+        let (status, body) = agent.request(Method::GET, "/api/v1/registries/drift/drift/quota", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!((&body["used_bytes"], &body["limit_bytes"]), (&serde_json::json!(1024), &serde_json::Value::Null));
+
+        for (method, route) in [(Method::GET, "info"), (Method::POST, "gc"), (Method::GET, "gc"), (Method::GET, "quota")] {
+            let uri = format!("/api/v1/registries/plain/drift/{}", route);
+            let (status, body) = agent.request(method, &uri, None).await;
+            assert_eq!(status, StatusCode::NOT_IMPLEMENTED, "{}", uri);
+            assert_eq!(body["error"]["code"], "REGISTRY_NOT_DRIFT");
+        }
+        let (status, _) = agent.request(Method::GET, "/api/v1/registries/missing/drift/info", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
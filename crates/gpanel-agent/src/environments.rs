@@ -0,0 +1,404 @@
+use axum::{
+    extract::{FromRequestParts, Query, State},
+    http::{request::Parts, StatusCode},
+    response::Json,
+};
+use gpanel_core::{
+    BoltClient, BoltClientConfig, ContainerEvent, ContainerRuntime, ContainerStatus, EnvironmentConfig,
+    EnvironmentStatus, GhostPanelConfig, MockBoltClient, ProxyConfig, ENVIRONMENT_HEADER,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tracing::{error, info, warn};
+
+use crate::autostart::{self, AutostartState};
+use crate::error::ApiError;
+use crate::events::{self, EventBus};
+use crate::metrics::StatsRecorder;
+use crate::store::Store;
+use crate::system::SystemInfoCache;
+use crate::watchdog::Watchdog;
+use crate::{AppState, HEALTH_PING_TIMEOUT};
+
+/// Directory under the data directory holding the state of environments other than the default
+const ENVIRONMENT_STATE_DIR: &str = "environments";
+
+struct Environment {
+    config: EnvironmentConfig,
+    env: Env,
+}
+
+/// One runtime client per configured Bolt host, each with its own watchdog, stats and
+/// autostart report
+pub struct Environments {
+    environments: Vec<Environment>,
+    default: usize,
+}
+
+impl Environments {
+    /// Connect to every environment. Only the default one falls back to the mock runtime
+    /// when Bolt is unreachable; the others keep their client and show as unreachable.
+    /// Events from every environment go to `events`.
+    pub async fn connect(
+        config: &GhostPanelConfig,
+        force_mock: bool,
+        simulate_reboot: bool,
+        store: &Store,
+        events: &EventBus,
+    ) -> Self {
+        let mut environments = Vec::new();
+        let mut default = 0;
+        for (index, environment) in config.resolved_environments().into_iter().enumerate() {
+            let runtime = if environment.default {
+                default = index;
+                connect_runtime(&environment.bolt_api_url, &config.bolt_client, &config.proxy, force_mock, simulate_reboot).await
+            } else {
+                connect_secondary(&environment, &config.bolt_client, &config.proxy, force_mock).await
+            };
+            // The default environment keeps its state where it was before there were others
+            let store = if environment.default {
+                store.clone()
+            } else {
+                store.scoped(ENVIRONMENT_STATE_DIR).scoped(&environment.name)
+            };
+
+            let env = Env {
+                name: environment.name.clone(),
+                runtime,
+                watchdog: Arc::new(Watchdog::load(store, config.watchdog.clone()).await),
+                stats: Arc::new(StatsRecorder::new()),
+                autostart: autostart::autostart_state(config.autostart_on_boot),
+                system_info: Arc::new(SystemInfoCache::new()),
+                default: environment.default,
+                events: events.clone(),
+            };
+            environments.push(Environment { config: environment, env });
+        }
+        Self { environments, default }
+    }
+
+    /// The default environment, for agent-wide checks such as the health endpoint
+    pub fn default_env(&self) -> Env {
+        self.environments[self.default].env.clone()
+    }
+
+    /// Every environment, for starting the background tasks of each
+    pub fn all(&self) -> impl Iterator<Item = &Env> {
+        self.environments.iter().map(|environment| &environment.env)
+    }
+
+    /// The environment called `name`, or the default one for `None`
+    fn select(&self, name: Option<&str>) -> Result<Env, ApiError> {
+        let Some(name) = name else {
+            return Ok(self.default_env());
+        };
+        self.all()
+            .find(|env| env.name == name)
+            .cloned()
+            .ok_or_else(|| ApiError::not_found("ENVIRONMENT", format!("Environment '{}' not found", name)))
+    }
+}
+
+/// Runtime for the default environment: Bolt if it answers, otherwise the mock
+async fn connect_runtime(
+    bolt_api_url: &str,
+    client_config: &BoltClientConfig,
+    proxy: &ProxyConfig,
+    force_mock: bool,
+    simulate_reboot: bool,
+) -> Arc<dyn ContainerRuntime> {
+    if force_mock {
+        info!("Container runtime: mock (--mock)");
+        let mock = MockBoltClient::new();
+        if simulate_reboot {
+            info!("Simulating a host reboot, running mock containers are now exited");
+            mock.simulate_reboot().await;
+        }
+        return Arc::new(mock);
+    }
+
+    let bolt = match BoltClient::with_proxy(bolt_api_url, client_config.clone(), proxy) {
+        Ok(bolt) => bolt,
+        Err(e) => {
+            error!("Invalid Bolt API URL {}: {}, using the mock runtime", bolt_api_url, e);
+            return Arc::new(MockBoltClient::new());
+        }
+    };
+    match bolt.ping().await {
+        Ok(true) => {
+            info!("Container runtime: Bolt at {} over {}", bolt_api_url, bolt.transport());
+            Arc::new(bolt)
+        }
+        _ => {
+            warn!("Bolt is not reachable at {} over {}, using the mock runtime", bolt_api_url, bolt.transport());
+            Arc::new(MockBoltClient::new())
+        }
+    }
+}
+
+/// Runtime for another environment. A mock standing in for a real host would show made-up
+/// containers as if they ran there, so an unreachable host keeps its Bolt client.
+async fn connect_secondary(
+    environment: &EnvironmentConfig,
+    client_config: &BoltClientConfig,
+    proxy: &ProxyConfig,
+    force_mock: bool,
+) -> Arc<dyn ContainerRuntime> {
+    if force_mock {
+        info!("Environment {}: mock (--mock)", environment.name);
+        return Arc::new(MockBoltClient::new());
+    }
+
+    let bolt = match BoltClient::with_proxy(&environment.bolt_api_url, client_config.clone(), proxy) {
+        Ok(bolt) => bolt,
+        Err(e) => {
+            // Config validation already parsed the URL, so this is not expected
+            error!("Invalid Bolt API URL {} for environment {}: {}", environment.bolt_api_url, environment.name, e);
+            return Arc::new(MockBoltClient::new());
+        }
+    };
+    match bolt.ping().await {
+        Ok(true) => info!("Environment {}: Bolt at {} over {}", environment.name, environment.bolt_api_url, bolt.transport()),
+        _ => warn!("Environment {}: Bolt is not reachable at {}", environment.name, environment.bolt_api_url),
+    }
+    Arc::new(bolt)
+}
+
+#[derive(Deserialize)]
+struct EnvironmentQuery {
+    env: Option<String>,
+}
+
+/// An environment's runtime and the agent state kept for it. As a handler argument, the
+/// environment a request is for, from the `X-GPanel-Env` header or `?env=`, falling back
+/// to the default.
+#[derive(Clone)]
+pub struct Env {
+    pub name: String,
+    pub runtime: Arc<dyn ContainerRuntime>,
+    pub watchdog: Arc<Watchdog>,
+    pub stats: Arc<StatsRecorder>,
+    pub autostart: AutostartState,
+    pub system_info: Arc<SystemInfoCache>,
+    default: bool,
+    events: EventBus,
+}
+
+impl Env {
+    pub fn is_default(&self) -> bool {
+        self.default
+    }
+
+    /// Publish a container event of this environment
+    pub fn publish(&self, mut event: ContainerEvent) {
+        event.environment = self.name.clone();
+        events::publish(&self.events, event);
+    }
+
+    /// Publish the event for a container's status after an operation changed it
+    pub async fn publish_status(&self, id: &str) {
+        match self.runtime.get_container(id).await {
+            Ok(container) => {
+                if let Some(action) = events::transition(&ContainerStatus::Unknown, &container.status) {
+                    self.publish(ContainerEvent::for_container(&container, action));
+                }
+            }
+            Err(e) => warn!("No event for container {} in {}, failed to look it up: {}", id, self.name, e),
+        }
+    }
+
+    /// Events published from now on, from every environment; `ContainerEvent::environment`
+    /// tells them apart
+    pub fn subscribe(&self) -> broadcast::Receiver<ContainerEvent> {
+        self.events.subscribe()
+    }
+}
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for Env {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let name = match parts.headers.get(ENVIRONMENT_HEADER) {
+            Some(value) => Some(
+                value
+                    .to_str()
+                    .map_err(|_| ApiError::from_status(StatusCode::BAD_REQUEST, "Invalid X-GPanel-Env header"))?
+                    .to_string(),
+            ),
+            None => Query::<EnvironmentQuery>::try_from_uri(&parts.uri)
+                .ok()
+                .and_then(|Query(query)| query.env),
+        };
+        let name = name.filter(|name| !name.is_empty());
+
+        state.environments.select(name.as_deref())
+    }
+}
+
+/// Configured environments, each pinged to show whether its Bolt host answers
+#[utoipa::path(
+    get,
+    path = "/api/v1/environments",
+    tag = "environments",
+    responses((status = 200, description = "Environments, the default one marked", body = Vec<EnvironmentStatus>)),
+)]
+pub async fn list_environments(State(state): State<AppState>) -> Json<Vec<EnvironmentStatus>> {
+    let pings = state.environments.environments.iter().map(|environment| async move {
+        let reachable = matches!(
+            tokio::time::timeout(HEALTH_PING_TIMEOUT, environment.env.runtime.ping()).await,
+            Ok(Ok(true))
+        );
+        EnvironmentStatus {
+            name: environment.config.name.clone(),
+            bolt_api_url: environment.config.bolt_api_url.clone(),
+            default: environment.config.default,
+            reachable,
+        }
+    });
+    Json(futures::future::join_all(pings).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{agent_with, TestAgent};
+    use axum::http::Method;
+    use gpanel_core::{ContainerEventAction, Schedule, ScheduleAction};
+
+    const CONTAINER: &str = "mock_web_server_001";
+
+    /// Agent managing `a`, the default, and `b`, each over its own mock runtime
+    async fn two_environments() -> TestAgent {
+        agent_with(
+            |config| {
+                config.environments = ["a", "b"]
+                    .into_iter()
+                    .map(|name| EnvironmentConfig {
+                        name: name.to_string(),
+                        bolt_api_url: format!("http://{}.invalid:8080", name),
+                        default: name == "a",
+                    })
+                    .collect();
+            },
+            false,
+        )
+        .await
+    }
+
+    fn env(agent: &TestAgent, name: &str) -> Env {
+        agent.state.environments.all().find(|env| env.name == name).unwrap().clone()
+    }
+
+    #[tokio::test]
+    async fn requests_reach_the_environment_they_name() {
+        let agent = two_environments().await;
+        let mut events = env(&agent, "a").subscribe();
+
+        let (status, _) = agent
+            .request(
+                Method::POST,
+                &format!("/api/v1/containers/{}/stop?env=b", CONTAINER),
+                Some(serde_json::json!({ "action": "stop" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let stopped = env(&agent, "b").runtime.get_container(CONTAINER).await.unwrap();
+        assert!(stopped.status.is_stopped());
+        let untouched = env(&agent, "a").runtime.get_container(CONTAINER).await.unwrap();
+        assert!(matches!(untouched.status, ContainerStatus::Running));
+
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.action, ContainerEventAction::Stopped);
+        assert_eq!(event.environment, "b");
+    }
+
+    #[tokio::test]
+    async fn volumes_are_kept_apart() {
+        let agent = two_environments().await;
+        let volume = serde_json::json!({ "name": "saves" });
+        let (status, _) = agent.request(Method::POST, "/api/v1/volumes?env=b", Some(volume)).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let names = |body: serde_json::Value| -> Vec<String> {
+            body["volumes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|volume| volume["name"].as_str().unwrap().to_string())
+                .collect()
+        };
+        let (_, in_b) = agent.request(Method::GET, "/api/v1/volumes?env=b", None).await;
+        assert!(names(in_b).contains(&"saves".to_string()));
+        let (_, in_a) = agent.request(Method::GET, "/api/v1/volumes", None).await;
+        assert!(!names(in_a).contains(&"saves".to_string()));
+    }
+
+    #[tokio::test]
+    async fn unknown_environments_are_not_found() {
+        let agent = two_environments().await;
+        let (status, body) = agent.request(Method::GET, "/api/v1/system/info?env=c", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "ENVIRONMENT_NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn the_monitor_stamps_its_environment() {
+        let agent = two_environments().await;
+        let b = env(&agent, "b");
+        let mut events = b.subscribe();
+        tokio::spawn(events::monitor_containers(b.clone(), std::time::Duration::from_millis(20)));
+
+        // Let the first poll record the starting state, then change it behind the monitor's back
+        tokio::time::sleep(std::time::Duration::from_millis(60)).await;
+        b.runtime.stop_container(CONTAINER, None).await.unwrap();
+
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), events.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.id, CONTAINER);
+        assert_eq!(event.environment, "b");
+    }
+
+    #[tokio::test]
+    async fn schedules_run_in_their_environment() {
+        let agent = two_environments().await;
+        let state = &agent.state;
+        let now = chrono::Utc::now();
+        state
+            .schedules
+            .save(Schedule {
+                id: "stop-b".to_string(),
+                name: None,
+                container_id: Some(CONTAINER.to_string()),
+                label_selector: None,
+                action: ScheduleAction::Stop,
+                cron: "* * * * *".to_string(),
+                enabled: true,
+                last_run: None,
+                last_result: None,
+                next_run: Some(now),
+                environment: Some("b".to_string()),
+            })
+            .await
+            .unwrap();
+
+        crate::schedules::tick(&state.schedules, &env(&agent, "a"), &state.audit, now).await;
+        assert_eq!(state.schedules.get("stop-b").await.unwrap().next_run, Some(now));
+
+        crate::schedules::tick(&state.schedules, &env(&agent, "b"), &state.audit, now).await;
+        let b = env(&agent, "b");
+        for _ in 0..100 {
+            if b.runtime.get_container(CONTAINER).await.unwrap().status.is_stopped() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert!(b.runtime.get_container(CONTAINER).await.unwrap().status.is_stopped());
+        let a = env(&agent, "a").runtime.get_container(CONTAINER).await.unwrap();
+        assert!(matches!(a.status, ContainerStatus::Running));
+    }
+}
@@ -0,0 +1,108 @@
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use gpanel_core::{ApiErrorBody, ApiErrorResponse};
+use serde::Serialize;
+
+/// Error returned by every handler, sent as an `ApiErrorResponse` with a matching status
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub body: ApiErrorBody,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: ApiErrorBody {
+                code: code.into(),
+                message: message.into(),
+                details: None,
+            },
+        }
+    }
+
+    /// Error with the generic code for its status, e.g. `NOT_FOUND` for a 404
+    pub fn from_status(status: StatusCode, message: impl Into<String>) -> Self {
+        Self::new(status, default_code(status), message)
+    }
+
+    /// 404 with a code naming what is missing, e.g. `not_found("REGISTRY", ...)` gives `REGISTRY_NOT_FOUND`
+    pub fn not_found(resource: &str, message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, format!("{}_NOT_FOUND", resource), message)
+    }
+
+    /// Failure of a runtime or registry call, with the status and code the error maps to,
+    /// e.g. 504 `BOLT_TIMEOUT` when Bolt did not answer in time
+    pub fn runtime(error: &gpanel_core::Error, message: impl Into<String>) -> Self {
+        Self::new(core_error_status(error), error.code(), message)
+    }
+
+    pub fn with_details(mut self, details: impl Serialize) -> Self {
+        self.body.details = serde_json::to_value(details).ok();
+        self
+    }
+}
+
+impl From<gpanel_core::Error> for ApiError {
+    fn from(error: gpanel_core::Error) -> Self {
+        Self::new(core_error_status(&error), error.code(), error.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(ApiErrorResponse { error: self.body })).into_response()
+    }
+}
+
+/// Largest rejection body read back when wrapping it
+const REJECTION_BODY_LIMIT: usize = 16 * 1024;
+
+/// Put axum's plain-text rejections (malformed JSON, bad query strings, unknown routes)
+/// in the same envelope the handlers use, keeping their status and headers
+pub async fn wrap_rejections(response: Response) -> Response {
+    let status = response.status();
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+    if !(status.is_client_error() || status.is_server_error()) || is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let message = match axum::body::to_bytes(body, REJECTION_BODY_LIMIT).await {
+        Ok(bytes) if !bytes.is_empty() => String::from_utf8_lossy(&bytes).into_owned(),
+        _ => status.canonical_reason().unwrap_or("Request failed").to_string(),
+    };
+
+    let mut wrapped = ApiError::from_status(status, message).into_response();
+    parts.headers.remove(header::CONTENT_TYPE);
+    parts.headers.remove(header::CONTENT_LENGTH);
+    wrapped.headers_mut().extend(parts.headers);
+    wrapped
+}
+
+fn core_error_status(error: &gpanel_core::Error) -> StatusCode {
+    StatusCode::from_u16(error.status_code()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn default_code(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::BAD_REQUEST => "INVALID_REQUEST",
+        StatusCode::NOT_FOUND => "NOT_FOUND",
+        StatusCode::REQUEST_TIMEOUT => "TIMEOUT",
+        StatusCode::CONFLICT => "CONFLICT",
+        StatusCode::LENGTH_REQUIRED => "LENGTH_REQUIRED",
+        StatusCode::PAYLOAD_TOO_LARGE => "PAYLOAD_TOO_LARGE",
+        StatusCode::UNPROCESSABLE_ENTITY => "VALIDATION_FAILED",
+        StatusCode::BAD_GATEWAY => "UPSTREAM_ERROR",
+        StatusCode::SERVICE_UNAVAILABLE => "UNAVAILABLE",
+        StatusCode::GATEWAY_TIMEOUT => "UPSTREAM_TIMEOUT",
+        status if status.is_client_error() => "INVALID_REQUEST",
+        _ => "INTERNAL_ERROR",
+    }
+}
@@ -0,0 +1,154 @@
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::Stream;
+use gpanel_core::{Container, ContainerEvent, ContainerEventAction, ContainerStatus, SystemEvent};
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::{RecvError, TryRecvError}};
+use tracing::{debug, error, warn};
+
+use crate::environments::Env;
+use crate::AppState;
+
+/// Internal channel carrying container events to webhooks and other consumers
+pub type EventBus = broadcast::Sender<ContainerEvent>;
+
+pub fn event_bus() -> EventBus {
+    broadcast::channel(256).0
+}
+
+/// Agent-wide changes, such as registries reloaded from the config file
+pub type SystemEventBus = broadcast::Sender<SystemEvent>;
+
+pub fn system_event_bus() -> SystemEventBus {
+    broadcast::channel(16).0
+}
+
+/// Publish an event; having no subscribers is not an error
+pub fn publish(events: &EventBus, event: ContainerEvent) {
+    debug!("Container event: {:?} {}", event.action, event.name);
+    let _ = events.send(event);
+}
+
+/// Work out which event a status change represents, if any
+pub fn transition(previous: &ContainerStatus, current: &ContainerStatus) -> Option<ContainerEventAction> {
+    match (previous, current) {
+        (ContainerStatus::Running, ContainerStatus::Running) => None,
+        (ContainerStatus::Paused, ContainerStatus::Paused) => None,
+        (ContainerStatus::Exited { .. }, ContainerStatus::Exited { .. }) => None,
+        (ContainerStatus::Dead, ContainerStatus::Dead) => None,
+        (_, ContainerStatus::Running) => Some(ContainerEventAction::Started),
+        (_, ContainerStatus::Paused) => Some(ContainerEventAction::Paused),
+        (_, ContainerStatus::Exited { code: 0 }) => Some(ContainerEventAction::Stopped),
+        (_, ContainerStatus::Exited { .. }) | (_, ContainerStatus::Dead) => Some(ContainerEventAction::Died),
+        _ => None,
+    }
+}
+
+/// Poll an environment's runtime and publish events for containers that changed state
+/// since the last poll
+pub async fn monitor_containers(env: Env, interval: Duration) {
+    let mut known: Option<HashMap<String, Container>> = None;
+    let mut ticker = tokio::time::interval(interval);
+    let mut published = env.subscribe();
+
+    loop {
+        ticker.tick().await;
+
+        let containers = match env.runtime.list_containers(None).await {
+            Ok(containers) => containers,
+            Err(e) => {
+                warn!("Container monitor failed to list containers in {}: {}", env.name, e);
+                continue;
+            }
+        };
+
+        let current: HashMap<String, Container> = containers.into_iter().map(|c| (c.id.clone(), c)).collect();
+
+        // Handlers publish their own changes straight away, so those are not announced again
+        let mut announced = HashSet::new();
+        loop {
+            match published.try_recv() {
+                Ok(event) if event.environment == env.name => {
+                    announced.insert((event.id, event.action));
+                }
+                Ok(_) => continue,
+                Err(TryRecvError::Lagged(_)) => continue,
+                Err(_) => break,
+            }
+        }
+
+        // The first poll only records the starting state
+        if let Some(previous) = &known {
+            for container in current.values() {
+                let action = match previous.get(&container.id) {
+                    Some(before) => transition(&before.status, &container.status),
+                    None => Some(ContainerEventAction::Created),
+                };
+                if let Some(action) = action.filter(|a| !announced.contains(&(container.id.clone(), *a))) {
+                    env.publish(ContainerEvent::for_container(container, action));
+                }
+            }
+            let removed = previous.values().filter(|c| {
+                !current.contains_key(&c.id) && !announced.contains(&(c.id.clone(), ContainerEventAction::Removed))
+            });
+            for container in removed {
+                env.publish(ContainerEvent::for_container(container, ContainerEventAction::Removed));
+            }
+        }
+
+        known = Some(current);
+    }
+}
+
+/// GET /api/v1/events: container events of the selected environment as server-sent events
+/// named `container`, and agent-wide changes named `system`. A client that falls behind on container events gets
+/// a `resync` event and should reload its container list.
+#[utoipa::path(
+    get,
+    path = "/api/v1/events",
+    tag = "events",
+    responses(
+        (status = 200, description = "Server-sent events: `container` events carry a `ContainerEvent`, `system` events a `SystemEvent`, `resync` events the number of events skipped", content_type = "text/event-stream", body = ContainerEvent),
+    ),
+)]
+pub async fn stream_events(
+    State(state): State<AppState>,
+    env: Env,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let containers = futures::stream::unfold((env.subscribe(), env.name), |(mut receiver, name)| async move {
+        let event = loop {
+            match receiver.recv().await {
+                Ok(event) if event.environment != name => continue,
+                Ok(event) => match Event::default().event("container").json_data(&event) {
+                    Ok(sse) => break sse,
+                    Err(e) => {
+                        error!("Failed to encode container event: {}", e);
+                        return None;
+                    }
+                },
+                Err(RecvError::Lagged(skipped)) => break Event::default().event("resync").data(skipped.to_string()),
+                Err(RecvError::Closed) => return None,
+            }
+        };
+        Some((Ok(event), (receiver, name)))
+    });
+    // Rare enough that a lagging client just misses some
+    let system = futures::stream::unfold(state.system_events.subscribe(), |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => match Event::default().event("system").json_data(&event) {
+                    Ok(sse) => return Some((Ok(sse), receiver)),
+                    Err(e) => error!("Failed to encode system event: {}", e),
+                },
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Sse::new(futures::stream::select(containers, system)).keep_alive(KeepAlive::default())
+}
@@ -0,0 +1,258 @@
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use futures::StreamExt;
+use gpanel_core::{
+    normalize_volume_path, single_file_archive, ApiErrorResponse, ContainerRuntime, OperationResult, VolumeEntry,
+    VolumeEntryType, VolumeFileStream,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::IntoParams;
+
+use crate::auth::RequireOperator;
+use crate::environments::Env;
+use crate::error::ApiError;
+use crate::AppState;
+
+/// Query parameters for GET and PUT /api/v1/containers/:id/files
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ContainerFileQuery {
+    pub path: String,
+}
+
+/// Resolve an absolute path inside the container, rejecting paths that climb above `/`
+fn resolve_path(requested: &str) -> Result<String, ApiError> {
+    normalize_volume_path(requested).ok_or_else(|| {
+        ApiError::from_status(StatusCode::BAD_REQUEST, format!("Path '{}' is outside the container filesystem", requested))
+    })
+}
+
+async fn stat(runtime: &dyn ContainerRuntime, id: &str, path: &str) -> Result<Option<VolumeEntry>, ApiError> {
+    runtime.stat_container_path(id, path).await.map_err(|e| {
+        error!("Failed to stat {} in container {}: {}", path, id, e);
+        ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read container filesystem: {}", e))
+    })
+}
+
+/// End the stream with an error once more than `limit` bytes have passed through
+fn cap_stream(stream: VolumeFileStream, limit: u64) -> impl futures::Stream<Item = std::io::Result<axum::body::Bytes>> {
+    let mut sent = 0u64;
+    stream.map(move |chunk| {
+        let chunk = chunk?;
+        sent += chunk.len() as u64;
+        if sent > limit {
+            return Err(std::io::Error::other("transfer exceeds the size limit"));
+        }
+        Ok(chunk)
+    })
+}
+
+/// Download a file from a container, or a tar of it if the path is a directory
+#[utoipa::path(
+    get,
+    path = "/api/v1/containers/{id}/files",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name"), ContainerFileQuery),
+    responses(
+        (status = 200, description = "File contents, or a tar archive when the path is a directory", content_type = "application/octet-stream", body = Vec<u8>),
+        (status = 404, description = "No such container or path", body = ApiErrorResponse),
+        (status = 413, description = "File is too large to download", body = ApiErrorResponse),
+    ),
+)]
+pub async fn download_container_file(
+    State(state): State<AppState>,
+    env: Env,
+    Path(id): Path<String>,
+    Query(query): Query<ContainerFileQuery>,
+) -> Result<Response, ApiError> {
+    env
+        .runtime
+        .get_container(&id)
+        .await
+        .map_err(|_| ApiError::not_found("CONTAINER", format!("Container '{}' not found", id)))?;
+
+    let path = resolve_path(&query.path)?;
+    let entry = stat(env.runtime.as_ref(), &id, &path)
+        .await?
+        .ok_or_else(|| ApiError::from_status(StatusCode::NOT_FOUND, format!("Path '{}' not found in container {}", path, id)))?;
+    let limit = state.config.file_transfer.max_download_bytes;
+
+    if entry.entry_type == VolumeEntryType::Directory {
+        let archive = env.runtime.get_archive(&id, &entry.path).await.map_err(|e| {
+            error!("Failed to archive {} in container {}: {}", entry.path, id, e);
+            ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to archive directory: {}", e))
+        })?;
+
+        // The archive size is unknown up front, so the limit can only cut it off mid-stream
+        let name = if entry.name.is_empty() { "rootfs" } else { entry.name.as_str() };
+        info!("Downloading {} from container {} as a tar archive", entry.path, id);
+
+        return Ok((
+            [
+                (header::CONTENT_TYPE, "application/x-tar".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}.tar\"", name.replace('"', "")),
+                ),
+            ],
+            Body::from_stream(cap_stream(archive, limit)),
+        )
+            .into_response());
+    }
+
+    if entry.size > limit {
+        return Err(ApiError::from_status(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("'{}' is {} bytes, larger than the {} byte download limit", entry.path, entry.size, limit),
+        ));
+    }
+
+    let stream = env.runtime.read_container_file(&id, &entry.path).await.map_err(|e| {
+        error!("Failed to read {} from container {}: {}", entry.path, id, e);
+        ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read file: {}", e))
+    })?;
+
+    info!("Downloading {} from container {}", entry.path, id);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+            (header::CONTENT_LENGTH, entry.size.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", entry.name.replace('"', "")),
+            ),
+        ],
+        // The file may grow between stat and read, so enforce the limit on the stream too
+        Body::from_stream(cap_stream(stream, limit)),
+    )
+        .into_response())
+}
+
+/// Stream the container's whole filesystem as a tar, as it arrives from Bolt
+#[utoipa::path(
+    get,
+    path = "/api/v1/containers/{id}/export",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name")),
+    responses(
+        (status = 200, description = "Tar archive of the container filesystem", content_type = "application/x-tar", body = Vec<u8>),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+    ),
+)]
+pub async fn export_container(env: Env, Path(id): Path<String>) -> Result<Response, ApiError> {
+    let container = env
+        .runtime
+        .get_container(&id)
+        .await
+        .map_err(|_| ApiError::not_found("CONTAINER", format!("Container '{}' not found", id)))?;
+
+    let archive = env.runtime.export_container(&id).await.map_err(|e| {
+        error!("Failed to export container {}: {}", id, e);
+        ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to export container: {}", e))
+    })?;
+
+    info!("Exporting container {}", id);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-tar".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.tar\"", container.name.replace('"', "")),
+            ),
+        ],
+        Body::from_stream(archive),
+    )
+        .into_response())
+}
+
+/// Write the request body to a file in a container, replacing any file already there.
+/// The body is streamed through to Bolt as a single-entry tar, never buffered.
+#[utoipa::path(
+    put,
+    path = "/api/v1/containers/{id}/files",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name"), ContainerFileQuery),
+    request_body(content = Vec<u8>, content_type = "application/octet-stream", description = "File contents; Content-Length is required"),
+    responses(
+        (status = 200, description = "File written", body = OperationResult),
+        (status = 400, description = "Invalid path, or the path is a directory", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such container or parent directory", body = ApiErrorResponse),
+        (status = 411, description = "Content-Length missing", body = ApiErrorResponse),
+        (status = 413, description = "File is too large to upload", body = ApiErrorResponse),
+    ),
+)]
+pub async fn upload_container_file(
+    _: RequireOperator,
+    State(state): State<AppState>,
+    env: Env,
+    Path(id): Path<String>,
+    Query(query): Query<ContainerFileQuery>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<Json<OperationResult>, ApiError> {
+    // The tar header needs the size before any data is sent
+    let size = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
+        .ok_or_else(|| ApiError::from_status(StatusCode::LENGTH_REQUIRED, "Uploads need a Content-Length header"))?;
+
+    let limit = state.config.file_transfer.max_upload_bytes;
+    if size > limit {
+        return Err(ApiError::from_status(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("Upload is {} bytes, larger than the {} byte upload limit", size, limit),
+        ));
+    }
+
+    env
+        .runtime
+        .get_container(&id)
+        .await
+        .map_err(|_| ApiError::not_found("CONTAINER", format!("Container '{}' not found", id)))?;
+
+    let path = resolve_path(&query.path)?;
+    let Some((parent, name)) = path.rsplit_once('/').filter(|(_, name)| !name.is_empty()) else {
+        return Err(ApiError::from_status(StatusCode::BAD_REQUEST, "Upload path must name a file"));
+    };
+    let parent = if parent.is_empty() { "/" } else { parent };
+
+    match stat(env.runtime.as_ref(), &id, parent).await? {
+        Some(entry) if entry.entry_type == VolumeEntryType::Directory => {}
+        _ => {
+            return Err(ApiError::from_status(
+                StatusCode::NOT_FOUND,
+                format!("Directory '{}' not found in container {}", parent, id),
+            ))
+        }
+    }
+    if let Some(VolumeEntry { entry_type: VolumeEntryType::Directory, .. }) = stat(env.runtime.as_ref(), &id, &path).await? {
+        return Err(ApiError::from_status(StatusCode::BAD_REQUEST, format!("'{}' is a directory", path)));
+    }
+
+    let body: VolumeFileStream = Box::pin(body.into_data_stream().map(|chunk| chunk.map_err(std::io::Error::other)));
+    let archive = single_file_archive(name, size, body)
+        .map_err(|e| ApiError::from_status(StatusCode::BAD_REQUEST, format!("Invalid file name '{}': {}", name, e)))?;
+
+    match env.runtime.put_archive(&id, parent, archive).await {
+        Ok(_) => {
+            info!("Uploaded {} bytes to {} in container {}", size, path, id);
+            Ok(Json(OperationResult {
+                success: true,
+                message: format!("Uploaded {} to container {}", path, id),
+            }))
+        }
+        Err(e) => {
+            error!("Failed to upload {} to container {}: {}", path, id, e);
+            Err(ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to upload file: {}", e)))
+        }
+    }
+}
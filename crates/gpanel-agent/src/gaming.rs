@@ -0,0 +1,265 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use gpanel_core::{
+    ApiErrorResponse, CreateContainerRequest, FieldError, GamingProfile, GamingProfileRequest, OperationResult,
+    OptimizationProfile,
+};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use tracing::{error, info};
+
+use crate::auth::RequireOperator;
+use crate::error::ApiError;
+use crate::store::Store;
+use crate::AppState;
+
+/// Store collection holding custom gaming profiles; the built-in ones are never stored
+const PROFILE_STORE: &str = "gaming_profiles";
+
+/// Built-in profiles plus the ones users defined
+pub struct GamingProfileRegistry {
+    builtin: Vec<GamingProfile>,
+    custom: RwLock<HashMap<String, GamingProfile>>,
+    store: Store,
+}
+
+impl GamingProfileRegistry {
+    pub async fn load(store: Store) -> Self {
+        let builtin: Vec<GamingProfile> = OptimizationProfile::ALL.iter().map(|profile| profile.builtin_profile()).collect();
+        let stored: Vec<GamingProfile> = store.load(PROFILE_STORE).await;
+        // A stored profile shadowing a built-in one could only come from a hand-edited file
+        let custom = stored
+            .into_iter()
+            .filter(|profile| !builtin.iter().any(|b| b.name == profile.name))
+            .map(|profile| (profile.name.clone(), GamingProfile { builtin: false, ..profile }))
+            .collect();
+
+        Self {
+            builtin,
+            custom: RwLock::new(custom),
+            store,
+        }
+    }
+
+    /// Built-in profiles first, then custom ones by name
+    pub async fn list(&self) -> Vec<GamingProfile> {
+        let mut custom: Vec<GamingProfile> = self.custom.read().await.values().cloned().collect();
+        custom.sort_by(|a, b| a.name.cmp(&b.name));
+        self.builtin.iter().cloned().chain(custom).collect()
+    }
+
+    pub async fn get(&self, name: &str) -> Option<GamingProfile> {
+        match self.builtin.iter().find(|profile| profile.name == name) {
+            Some(profile) => Some(profile.clone()),
+            None => self.custom.read().await.get(name).cloned(),
+        }
+    }
+
+    fn is_builtin(&self, name: &str) -> bool {
+        self.builtin.iter().any(|profile| profile.name == name)
+    }
+
+    /// Save `profile`, replacing the custom profile called `previous` if given
+    async fn save(&self, previous: Option<&str>, profile: GamingProfile) -> Result<GamingProfile, ApiError> {
+        if let Some(name) = previous
+            && self.is_builtin(name)
+        {
+            return Err(read_only(name));
+        }
+
+        let mut custom = self.custom.write().await;
+        if let Some(name) = previous
+            && !custom.contains_key(name)
+        {
+            return Err(not_found(name));
+        }
+        let renamed = previous != Some(profile.name.as_str());
+        if renamed && (self.is_builtin(&profile.name) || custom.contains_key(&profile.name)) {
+            return Err(ApiError::new(
+                StatusCode::CONFLICT,
+                "PROFILE_EXISTS",
+                format!("Gaming profile '{}' already exists", profile.name),
+            ));
+        }
+
+        if let Some(name) = previous {
+            custom.remove(name);
+        }
+        custom.insert(profile.name.clone(), profile.clone());
+        self.persist(&custom).await?;
+        Ok(profile)
+    }
+
+    async fn remove(&self, name: &str) -> Result<(), ApiError> {
+        if self.is_builtin(name) {
+            return Err(read_only(name));
+        }
+        let mut custom = self.custom.write().await;
+        if custom.remove(name).is_none() {
+            return Err(not_found(name));
+        }
+        self.persist(&custom).await
+    }
+
+    async fn persist(&self, custom: &HashMap<String, GamingProfile>) -> Result<(), ApiError> {
+        let mut stored: Vec<&GamingProfile> = custom.values().collect();
+        stored.sort_by(|a, b| a.name.cmp(&b.name));
+        self.store.save(PROFILE_STORE, &stored).await.map_err(|e| {
+            error!("Failed to save gaming profiles: {}", e);
+            ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save gaming profiles: {}", e))
+        })
+    }
+
+    /// Embed the profile a create request names, so the container keeps the settings it was
+    /// created with even if the profile later changes
+    pub async fn resolve(&self, request: &mut CreateContainerRequest) -> Result<(), FieldError> {
+        let Some(gaming) = request.gaming_config.as_mut() else {
+            return Ok(());
+        };
+        let Some(name) = gaming.profile_name.as_deref() else {
+            gaming.profile = None;
+            return Ok(());
+        };
+        let profile = self.get(name).await.ok_or_else(|| {
+            FieldError::new("gaming_config.profile_name", format!("Unknown gaming profile '{}'", name))
+        })?;
+        if let Some(variant) = OptimizationProfile::ALL.iter().find(|variant| variant.profile_name() == profile.name) {
+            gaming.optimization_profile = *variant;
+        }
+        gaming.profile = Some(profile);
+        Ok(())
+    }
+}
+
+fn not_found(name: &str) -> ApiError {
+    ApiError::not_found("PROFILE", format!("Gaming profile '{}' not found", name))
+}
+
+fn read_only(name: &str) -> ApiError {
+    ApiError::new(
+        StatusCode::CONFLICT,
+        "PROFILE_READ_ONLY",
+        format!("Gaming profile '{}' is built in and cannot be changed", name),
+    )
+}
+
+fn validate(request: &GamingProfileRequest) -> Result<(), ApiError> {
+    let errors = request.validate();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(ApiError::from_status(
+        StatusCode::UNPROCESSABLE_ENTITY,
+        format!("Gaming profile has {} invalid field(s)", errors.len()),
+    )
+    .with_details(serde_json::json!({ "errors": errors })))
+}
+
+/// Built-in and custom gaming profiles
+#[utoipa::path(
+    get,
+    path = "/api/v1/gaming/profiles",
+    tag = "gaming",
+    responses((status = 200, description = "Built-in profiles first, then custom ones by name", body = Vec<GamingProfile>)),
+)]
+pub async fn list_profiles(State(state): State<AppState>) -> Json<Vec<GamingProfile>> {
+    Json(state.gaming_profiles.list().await)
+}
+
+/// Get a gaming profile
+#[utoipa::path(
+    get,
+    path = "/api/v1/gaming/profiles/{name}",
+    tag = "gaming",
+    params(("name" = String, Path, description = "Profile name")),
+    responses(
+        (status = 200, description = "The profile", body = GamingProfile),
+        (status = 404, description = "No such profile", body = ApiErrorResponse),
+    ),
+)]
+pub async fn get_profile(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<GamingProfile>, ApiError> {
+    state.gaming_profiles.get(&name).await.map(Json).ok_or_else(|| not_found(&name))
+}
+
+/// Create a gaming profile
+#[utoipa::path(
+    post,
+    path = "/api/v1/gaming/profiles",
+    tag = "gaming",
+    request_body = GamingProfileRequest,
+    responses(
+        (status = 201, description = "Profile created", body = GamingProfile),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 409, description = "A profile with this name exists", body = ApiErrorResponse),
+        (status = 422, description = "Invalid profile", body = ApiErrorResponse),
+    ),
+)]
+pub async fn create_profile(
+    _: RequireOperator,
+    State(state): State<AppState>,
+    Json(request): Json<GamingProfileRequest>,
+) -> Result<(StatusCode, Json<GamingProfile>), ApiError> {
+    validate(&request)?;
+    let profile = state.gaming_profiles.save(None, request.into_profile()).await?;
+    info!("Created gaming profile {}", profile.name);
+    Ok((StatusCode::CREATED, Json(profile)))
+}
+
+/// Replace a custom gaming profile, renaming it if the body has a different name
+#[utoipa::path(
+    put,
+    path = "/api/v1/gaming/profiles/{name}",
+    tag = "gaming",
+    params(("name" = String, Path, description = "Profile name")),
+    request_body = GamingProfileRequest,
+    responses(
+        (status = 200, description = "Profile updated", body = GamingProfile),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such profile", body = ApiErrorResponse),
+        (status = 409, description = "Profile is built in, or the new name is taken", body = ApiErrorResponse),
+        (status = 422, description = "Invalid profile", body = ApiErrorResponse),
+    ),
+)]
+pub async fn update_profile(
+    _: RequireOperator,
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(request): Json<GamingProfileRequest>,
+) -> Result<Json<GamingProfile>, ApiError> {
+    validate(&request)?;
+    let profile = state.gaming_profiles.save(Some(&name), request.into_profile()).await?;
+    info!("Updated gaming profile {}", profile.name);
+    Ok(Json(profile))
+}
+
+/// Delete a custom gaming profile. Containers created with it keep their embedded copy.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/gaming/profiles/{name}",
+    tag = "gaming",
+    params(("name" = String, Path, description = "Profile name")),
+    responses(
+        (status = 200, description = "Profile deleted", body = OperationResult),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such profile", body = ApiErrorResponse),
+        (status = 409, description = "Profile is built in", body = ApiErrorResponse),
+    ),
+)]
+pub async fn delete_profile(
+    _: RequireOperator,
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<OperationResult>, ApiError> {
+    state.gaming_profiles.remove(&name).await?;
+    info!("Deleted gaming profile {}", name);
+    Ok(Json(OperationResult {
+        success: true,
+        message: format!("Gaming profile {} deleted", name),
+    }))
+}
@@ -0,0 +1,319 @@
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use gpanel_core::{ApiErrorResponse, GpuClaim, GpuDevice, GpuType};
+use std::fs;
+use std::path::Path as FsPath;
+use tracing::error;
+
+use crate::environments::Env;
+use crate::error::ApiError;
+use crate::AppState;
+
+/// DRM devices, one `cardN` directory per GPU alongside its connectors
+const DRM_CLASS_DIR: &str = "/sys/class/drm";
+
+/// Per-GPU information written by the NVIDIA kernel module, used when NVML is not built in
+const NVIDIA_PROC_GPUS_DIR: &str = "/proc/driver/nvidia/gpus";
+
+const PCI_VENDOR_AMD: &str = "0x1002";
+const PCI_VENDOR_INTEL: &str = "0x8086";
+
+const MIB: u64 = 1024 * 1024;
+
+/// Finds the GPUs on the agent host
+pub struct GpuInventory {
+    #[cfg(feature = "nvml")]
+    nvml: Option<nvml_wrapper::Nvml>,
+}
+
+impl Default for GpuInventory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GpuInventory {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(feature = "nvml")]
+            nvml: nvml_wrapper::Nvml::init().ok(),
+        }
+    }
+
+    /// NVIDIA GPUs first, then AMD and Intel ones by card number. `in_use_by` is left empty.
+    pub fn discover(&self) -> Vec<GpuDevice> {
+        let mut gpus = self.nvidia_gpus();
+        gpus.extend(drm_gpus());
+        gpus
+    }
+
+    #[cfg(feature = "nvml")]
+    fn nvidia_gpus(&self) -> Vec<GpuDevice> {
+        let Some(nvml) = &self.nvml else {
+            return nvidia_proc_gpus();
+        };
+        let driver_version = nvml.sys_driver_version().ok();
+        (0..nvml.device_count().unwrap_or(0))
+            .filter_map(|index| {
+                let device = nvml.device_by_index(index).ok()?;
+                Some(GpuDevice {
+                    id: format!("nvidia{}", device.minor_number().unwrap_or(index)),
+                    vendor: GpuType::Nvidia,
+                    name: device.name().unwrap_or_else(|_| "NVIDIA GPU".to_string()),
+                    memory_total_mb: device.memory_info().map(|memory| memory.total / MIB).unwrap_or(0),
+                    driver_version: driver_version.clone(),
+                    in_use_by: Vec::new(),
+                })
+            })
+            .collect()
+    }
+
+    #[cfg(not(feature = "nvml"))]
+    fn nvidia_gpus(&self) -> Vec<GpuDevice> {
+        nvidia_proc_gpus()
+    }
+}
+
+fn read_trimmed(path: impl AsRef<FsPath>) -> Option<String> {
+    fs::read_to_string(path).ok().map(|text| text.trim().to_string()).filter(|text| !text.is_empty())
+}
+
+/// Version of a loaded kernel module, for drivers built out of tree
+fn module_version(module: &str) -> Option<String> {
+    read_trimmed(format!("/sys/module/{}/version", module))
+}
+
+/// NVIDIA GPUs as the kernel module reports them. Memory size is only available through NVML.
+fn nvidia_proc_gpus() -> Vec<GpuDevice> {
+    let Ok(entries) = fs::read_dir(NVIDIA_PROC_GPUS_DIR) else {
+        return Vec::new();
+    };
+    let driver_version = module_version("nvidia");
+    let mut gpus: Vec<(u32, GpuDevice)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let information = fs::read_to_string(entry.path().join("information")).ok()?;
+            let field = |name: &str| {
+                information
+                    .lines()
+                    .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+                    .map(|value| value.trim().to_string())
+            };
+            let minor: u32 = field("Device Minor")?.parse().ok()?;
+            Some((
+                minor,
+                GpuDevice {
+                    id: format!("nvidia{}", minor),
+                    vendor: GpuType::Nvidia,
+                    name: field("Model").unwrap_or_else(|| "NVIDIA GPU".to_string()),
+                    memory_total_mb: 0,
+                    driver_version: driver_version.clone(),
+                    in_use_by: Vec::new(),
+                },
+            ))
+        })
+        .collect();
+    gpus.sort_by_key(|(minor, _)| *minor);
+    gpus.into_iter().map(|(_, gpu)| gpu).collect()
+}
+
+/// AMD and Intel GPUs from sysfs
+fn drm_gpus() -> Vec<GpuDevice> {
+    let Ok(entries) = fs::read_dir(DRM_CLASS_DIR) else {
+        return Vec::new();
+    };
+    let mut gpus: Vec<(u32, GpuDevice)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry.file_name().to_string_lossy().into_owned();
+            // `card0-HDMI-A-1` and the like are connectors, not cards
+            let number: u32 = id.strip_prefix("card")?.parse().ok()?;
+            let device = entry.path().join("device");
+
+            let vendor = match read_trimmed(device.join("vendor"))?.as_str() {
+                PCI_VENDOR_AMD => GpuType::Amd,
+                PCI_VENDOR_INTEL => GpuType::Intel,
+                // NVIDIA cards are listed by `nvidia_gpus`
+                _ => return None,
+            };
+            let driver = fs::read_link(device.join("driver"))
+                .ok()
+                .and_then(|link| link.file_name().map(|name| name.to_string_lossy().into_owned()));
+            let name = read_trimmed(device.join("product_name")).unwrap_or_else(|| {
+                let model = read_trimmed(device.join("device")).unwrap_or_default();
+                format!("{:?} GPU {}", vendor, model).trim_end().to_string()
+            });
+            // amdgpu reports VRAM; integrated GPUs share system memory and have none
+            let memory_total_mb = read_trimmed(device.join("mem_info_vram_total"))
+                .and_then(|bytes| bytes.parse::<u64>().ok())
+                .map(|bytes| bytes / MIB)
+                .unwrap_or(0);
+
+            Some((
+                number,
+                GpuDevice {
+                    id,
+                    vendor,
+                    name,
+                    memory_total_mb,
+                    // In-tree drivers have no version of their own, so fall back to the kernel's
+                    driver_version: driver
+                        .as_deref()
+                        .and_then(module_version)
+                        .or_else(|| read_trimmed("/proc/sys/kernel/osrelease")),
+                    in_use_by: Vec::new(),
+                },
+            ))
+        })
+        .collect();
+    gpus.sort_by_key(|(number, _)| *number);
+    gpus.into_iter().map(|(_, gpu)| gpu).collect()
+}
+
+/// GPUs on the agent host and the running containers allocated each of them
+#[utoipa::path(
+    get,
+    path = "/api/v1/system/gpus",
+    tag = "system",
+    responses(
+        (status = 200, description = "Discovered GPUs", body = Vec<GpuDevice>),
+        (status = 500, description = "Runtime error", body = ApiErrorResponse),
+    ),
+)]
+pub async fn list_gpus(State(state): State<AppState>, env: Env) -> Result<Json<Vec<GpuDevice>>, ApiError> {
+    let containers = env.runtime.list_containers(None).await.map_err(|e| {
+        error!("Failed to list containers for GPU allocations: {}", e);
+        ApiError::runtime(&e, format!("Failed to list containers: {}", e))
+    })?;
+
+    let mut gpus = state.gpus.discover();
+    for gpu in &mut gpus {
+        gpu.in_use_by = containers
+            .iter()
+            .filter(|container| !container.status.is_stopped())
+            .filter(|container| container.gpu_allocation.as_ref().is_some_and(|allocation| allocation.device_id == gpu.id))
+            .map(|container| container.id.clone())
+            .collect();
+    }
+    Ok(Json(gpus))
+}
+
+/// Claims on one GPU: every container allocated it, with the running ones holding it first
+#[utoipa::path(
+    get,
+    path = "/api/v1/system/gpus/{id}/allocations",
+    tag = "system",
+    params(("id" = String, Path, description = "GPU ID, e.g. nvidia0 or card1")),
+    responses(
+        (status = 200, description = "Claims on the GPU", body = Vec<GpuClaim>),
+        (status = 404, description = "The host has no such GPU and no container claims it", body = ApiErrorResponse),
+        (status = 500, description = "Runtime error", body = ApiErrorResponse),
+    ),
+)]
+pub async fn list_allocations(
+    State(state): State<AppState>,
+    env: Env,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<GpuClaim>>, ApiError> {
+    let containers = env.runtime.list_containers(None).await.map_err(|e| {
+        error!("Failed to list containers for GPU allocations: {}", e);
+        ApiError::runtime(&e, format!("Failed to list containers: {}", e))
+    })?;
+
+    let claims = GpuClaim::for_device(&id, &containers);
+    if claims.is_empty() && !state.gpus.discover().iter().any(|gpu| gpu.id == id) {
+        return Err(ApiError::not_found("GPU", format!("GPU '{}' not found", id)));
+    }
+    Ok(Json(claims))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::agent;
+    use axum::http::{Method, StatusCode};
+    use gpanel_core::{CreateContainerRequest, GpuAllocation, GpuType, IsolationLevel, RestartPolicy};
+    use std::collections::HashMap;
+
+    /// The mock's `steam-gaming` holds nvidia0 exclusively while it runs
+    const HOLDER: &str = "mock_gaming_container_002";
+
+    fn request(isolation_level: IsolationLevel) -> serde_json::Value {
+        serde_json::to_value(CreateContainerRequest {
+            name: Some("second-game".to_string()),
+            image: "gaming/steam-proton:latest".to_string(),
+            ports: Vec::new(),
+            volumes: Vec::new(),
+            networks: Vec::new(),
+            env: HashMap::new(),
+            labels: HashMap::new(),
+            gaming_config: None,
+            gpu_allocation: Some(GpuAllocation {
+                device_id: "nvidia0".to_string(),
+                gpu_type: GpuType::Nvidia,
+                memory_mb: None,
+                compute_units: None,
+                isolation_level,
+            }),
+            restart_policy: RestartPolicy::No,
+            health: None,
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn running_holders_block_create_and_stopped_ones_do_not() {
+        let agent = agent().await;
+
+        let (status, body) = agent
+            .request(Method::POST, "/api/v1/containers", Some(request(IsolationLevel::Shared)))
+            .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"]["code"], "GPU_IN_USE");
+        assert_eq!(body["error"]["details"]["container_name"], "steam-gaming");
+        assert!(body["error"]["message"].as_str().unwrap().contains("exclusively"));
+
+        let runtime = agent.state.environments.default_env().runtime;
+        runtime.stop_container(HOLDER, None).await.unwrap();
+        let (status, _) = agent
+            .request(Method::POST, "/api/v1/containers", Some(request(IsolationLevel::Exclusive)))
+            .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, claims) = agent.request(Method::GET, "/api/v1/system/gpus/nvidia0/allocations", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let claims = claims.as_array().unwrap();
+        assert_eq!(claims.len(), 2);
+        assert!(claims.iter().all(|claim| claim["active"] == false));
+    }
+
+    #[tokio::test]
+    async fn starting_a_container_checks_its_claim() {
+        let agent = agent().await;
+        let runtime = agent.state.environments.default_env().runtime;
+        runtime.stop_container(HOLDER, None).await.unwrap();
+        let (status, _) = agent
+            .request(Method::POST, "/api/v1/containers", Some(request(IsolationLevel::Shared)))
+            .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let containers = runtime.list_containers(None).await.unwrap();
+        let created = containers.iter().find(|container| container.name == "second-game").unwrap();
+        runtime.start_container(&created.id).await.unwrap();
+
+        // The exclusive holder cannot come back while a shared claim runs
+        let (status, body) = agent
+            .request(Method::POST, &format!("/api/v1/containers/{}/start", HOLDER), None)
+            .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"]["details"]["container_name"], "second-game");
+    }
+
+    #[tokio::test]
+    async fn unknown_gpus_without_claims_are_not_found() {
+        let agent = agent().await;
+        let (status, body) = agent.request(Method::GET, "/api/v1/system/gpus/nvidia9/allocations", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "GPU_NOT_FOUND");
+    }
+}
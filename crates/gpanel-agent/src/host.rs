@@ -0,0 +1,164 @@
+use axum::{extract::State, response::Json};
+use gpanel_core::{FilesystemUsage, HostCpu, HostGpu, HostMemory, HostMetrics, LoadAverage};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use sysinfo::{Disks, System};
+
+use crate::AppState;
+
+/// Requests within this long of the last sample get that sample back. CPU usage is the
+/// change since the previous refresh, so sampling much faster would only add noise.
+const MIN_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+struct Sampler {
+    system: System,
+    disks: Disks,
+    last: Option<(Instant, HostMetrics)>,
+}
+
+/// Samples the agent's own machine, at most once per `MIN_SAMPLE_INTERVAL`
+pub struct HostMetricsSampler {
+    sampler: Mutex<Sampler>,
+    data_dir: PathBuf,
+    #[cfg(feature = "nvml")]
+    nvml: Option<nvml_wrapper::Nvml>,
+}
+
+impl HostMetricsSampler {
+    pub fn new(bolt_data_dir: &str) -> Self {
+        let mut system = System::new();
+        // The first CPU reading has nothing to compare with; take it now so the first
+        // request already gets a real figure
+        system.refresh_cpu();
+
+        Self {
+            sampler: Mutex::new(Sampler {
+                system,
+                disks: Disks::new_with_refreshed_list(),
+                last: None,
+            }),
+            data_dir: PathBuf::from(bolt_data_dir),
+            #[cfg(feature = "nvml")]
+            nvml: match nvml_wrapper::Nvml::init() {
+                Ok(nvml) => Some(nvml),
+                Err(e) => {
+                    tracing::info!("NVML unavailable, host metrics will not include GPUs: {}", e);
+                    None
+                }
+            },
+        }
+    }
+
+    pub fn sample(&self) -> HostMetrics {
+        let mut sampler = self.sampler.lock().unwrap();
+        if let Some((_, metrics)) = sampler.last.as_ref().filter(|(sampled_at, _)| sampled_at.elapsed() < MIN_SAMPLE_INTERVAL) {
+            return metrics.clone();
+        }
+
+        sampler.system.refresh_cpu();
+        sampler.system.refresh_memory();
+        // Refreshing the list rather than the sizes picks up filesystems mounted since
+        sampler.disks.refresh_list();
+
+        let system = &sampler.system;
+        let load = System::load_average();
+        let metrics = HostMetrics {
+            timestamp: chrono::Utc::now(),
+            hostname: System::host_name(),
+            os: System::long_os_version(),
+            kernel_version: System::kernel_version(),
+            uptime_secs: System::uptime(),
+            cpu: HostCpu {
+                cores: system.cpus().len(),
+                usage_percent: system.global_cpu_info().cpu_usage() as f64,
+                per_core_percent: system.cpus().iter().map(|cpu| cpu.cpu_usage() as f64).collect(),
+            },
+            load_average: LoadAverage {
+                one: load.one,
+                five: load.five,
+                fifteen: load.fifteen,
+            },
+            memory: HostMemory {
+                total_bytes: system.total_memory(),
+                used_bytes: system.used_memory(),
+                available_bytes: system.available_memory(),
+                swap_total_bytes: system.total_swap(),
+                swap_used_bytes: system.used_swap(),
+            },
+            data_filesystem: filesystem_usage(&sampler.disks, &self.data_dir),
+            gpus: self.gpus(),
+            image_cache: Default::default(),
+        };
+
+        sampler.last = Some((Instant::now(), metrics.clone()));
+        metrics
+    }
+
+    #[cfg(feature = "nvml")]
+    fn gpus(&self) -> Vec<HostGpu> {
+        use nvml_wrapper::enum_wrappers::device::TemperatureSensor;
+
+        let Some(nvml) = &self.nvml else {
+            return Vec::new();
+        };
+        let count = nvml.device_count().unwrap_or_else(|e| {
+            tracing::warn!("Failed to count GPUs: {}", e);
+            0
+        });
+        (0..count)
+            .filter_map(|index| {
+                let device = nvml.device_by_index(index).ok()?;
+                let memory = device.memory_info().ok();
+                Some(HostGpu {
+                    index,
+                    name: device.name().unwrap_or_default(),
+                    utilization_percent: device.utilization_rates().map(|rates| rates.gpu as f64).unwrap_or(0.0),
+                    memory_used_bytes: memory.as_ref().map(|memory| memory.used).unwrap_or(0),
+                    memory_total_bytes: memory.as_ref().map(|memory| memory.total).unwrap_or(0),
+                    temperature_celsius: device.temperature(TemperatureSensor::Gpu).ok(),
+                    // NVML reports milliwatts
+                    power_watts: device.power_usage().ok().map(|milliwatts| milliwatts as f64 / 1000.0),
+                })
+            })
+            .collect()
+    }
+
+    #[cfg(not(feature = "nvml"))]
+    fn gpus(&self) -> Vec<HostGpu> {
+        Vec::new()
+    }
+}
+
+/// Usage of the filesystem `path` lives on: the mount with the longest matching prefix
+fn filesystem_usage(disks: &Disks, path: &Path) -> Option<FilesystemUsage> {
+    // Resolve symlinks so a data directory linked onto another disk is reported correctly
+    let resolved = path.canonicalize().ok()?;
+    let disk = disks
+        .list()
+        .iter()
+        .filter(|disk| resolved.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())?;
+
+    Some(FilesystemUsage {
+        path: path.display().to_string(),
+        mount_point: disk.mount_point().display().to_string(),
+        total_bytes: disk.total_space(),
+        used_bytes: disk.total_space().saturating_sub(disk.available_space()),
+        available_bytes: disk.available_space(),
+    })
+}
+
+/// CPU, memory, load, uptime and GPUs of the machine the agent runs on, and the hit and
+/// miss counts of the registry image cache
+#[utoipa::path(
+    get,
+    path = "/api/v1/system/metrics",
+    tag = "system",
+    responses((status = 200, description = "Host metrics, sampled at most once per second", body = HostMetrics)),
+)]
+pub async fn host_metrics(State(state): State<AppState>) -> Json<HostMetrics> {
+    let mut metrics = state.host_metrics.sample();
+    metrics.image_cache = state.registry_manager.read().await.image_cache_stats();
+    Json(metrics)
+}
@@ -0,0 +1,33 @@
+use gpanel_core::{CachedImage, RegistryManager};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use crate::store::Store;
+
+/// Where the image cache is kept when `image_cache.persist` is set
+pub const IMAGE_CACHE_STORE: &str = "image_cache";
+
+/// How often a changed image cache is written out
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Fill `manager`'s image cache with what an earlier run saved
+pub async fn restore(manager: &RegistryManager, store: &Store) {
+    let cached: Vec<CachedImage> = store.load(IMAGE_CACHE_STORE).await;
+    manager.restore_image_cache(cached);
+}
+
+/// Save the image cache each `SAVE_INTERVAL` it changed in
+pub async fn run_saver(manager: Arc<RwLock<RegistryManager>>, store: Store) {
+    let mut ticker = tokio::time::interval(SAVE_INTERVAL);
+    loop {
+        ticker.tick().await;
+        let Some(cached) = manager.read().await.image_cache_changes() else {
+            continue;
+        };
+        if let Err(e) = store.save(IMAGE_CACHE_STORE, &cached).await {
+            warn!("Failed to save the image cache: {}", e);
+        }
+    }
+}
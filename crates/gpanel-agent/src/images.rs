@@ -0,0 +1,317 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use gpanel_core::{
+    is_valid_repository, is_valid_tag, local_manifest_digest, tag_local_manifest, ApiErrorResponse, Container,
+    ImagePruneFilter, ImagePruneReport, ImagePruneRequest, ImageTagRequest, LocalImage, LocalImageListResponse,
+    OperationResult,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::IntoParams;
+
+use crate::auth::RequireOperator;
+use crate::error::ApiError;
+use crate::environments::Env;
+use crate::{util::parse_duration, AppState};
+
+/// Query parameters for GET /api/v1/images
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LocalImageQuery {
+    pub dangling: Option<bool>,
+    pub unused: Option<bool>,
+}
+
+/// Query parameters for DELETE /api/v1/images/:id
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RemoveImageQuery {
+    pub force: Option<bool>,
+}
+
+/// Fill in which containers reference each image
+pub fn cross_reference(images: &mut [LocalImage], containers: &[Container]) {
+    for image in images.iter_mut() {
+        image.containers = containers
+            .iter()
+            .filter(|c| image.matches_reference(&c.image))
+            .map(|c| c.id.clone())
+            .collect();
+    }
+}
+
+/// List images stored on the Bolt host
+#[utoipa::path(
+    get,
+    path = "/api/v1/images",
+    tag = "images",
+    params(LocalImageQuery),
+    responses((status = 200, description = "Images stored on this host", body = LocalImageListResponse)),
+)]
+pub async fn list_local_images(
+    env: Env,
+    Query(query): Query<LocalImageQuery>,
+) -> Result<Json<LocalImageListResponse>, ApiError> {
+    let mut images = env.runtime.list_images().await.map_err(|e| {
+        error!("Failed to list local images: {}", e);
+        ApiError::runtime(&e, format!("Failed to list images: {}", e))
+    })?;
+
+    let containers = env.runtime.list_containers(None).await.map_err(|e| {
+        error!("Failed to list containers for image cross-reference: {}", e);
+        ApiError::runtime(&e, format!("Failed to list containers: {}", e))
+    })?;
+
+    cross_reference(&mut images, &containers);
+
+    if let Some(dangling) = query.dangling {
+        images.retain(|image| image.dangling == dangling);
+    }
+    if let Some(unused) = query.unused {
+        images.retain(|image| image.containers.is_empty() == unused);
+    }
+
+    let total_size = images.iter().map(|image| image.size).sum();
+
+    Ok(Json(LocalImageListResponse { images, total_size }))
+}
+
+/// Remove a local image, refusing while containers still use it unless forced
+#[utoipa::path(
+    delete,
+    path = "/api/v1/images/{id}",
+    tag = "images",
+    params(("id" = String, Path, description = "Image ID or `repository:tag`"), RemoveImageQuery),
+    responses(
+        (status = 200, description = "Image removed", body = OperationResult),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such image", body = ApiErrorResponse),
+        (status = 409, description = "Image is used by a container", body = ApiErrorResponse),
+    ),
+)]
+pub async fn remove_local_image(
+    _: RequireOperator,
+    env: Env,
+    Path(id): Path<String>,
+    Query(query): Query<RemoveImageQuery>,
+) -> Result<Json<OperationResult>, ApiError> {
+    let force = query.force.unwrap_or(false);
+
+    let mut images = env.runtime.list_images().await.map_err(|e| {
+        error!("Failed to list local images: {}", e);
+        ApiError::runtime(&e, format!("Failed to list images: {}", e))
+    })?;
+
+    let containers = env.runtime.list_containers(None).await.map_err(|e| {
+        error!("Failed to list containers for image cross-reference: {}", e);
+        ApiError::runtime(&e, format!("Failed to list containers: {}", e))
+    })?;
+
+    cross_reference(&mut images, &containers);
+
+    let Some(image) = images.iter().find(|image| image.matches_reference(&id)) else {
+        return Err(ApiError::not_found("IMAGE", format!("Image '{}' not found", id)));
+    };
+
+    if !image.containers.is_empty() && !force {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "IMAGE_IN_USE",
+            format!("Image '{}' is used by containers: {}", id, image.containers.join(", ")),
+        )
+        .with_details(serde_json::json!({ "containers": image.containers })));
+    }
+
+    match env.runtime.remove_image(&image.id, force).await {
+        Ok(_) => {
+            info!("Removed local image: {}", image.id);
+            Ok(Json(OperationResult {
+                success: true,
+                message: format!("Image {} removed successfully", id),
+            }))
+        }
+        Err(e) => {
+            error!("Failed to remove image {}: {}", id, e);
+            Err(ApiError::runtime(&e, format!("Failed to remove image: {}", e)))
+        }
+    }
+}
+
+/// Tag a local image, optionally pushing the new tag to a registry
+#[utoipa::path(
+    post,
+    path = "/api/v1/images/{id}/tag",
+    tag = "images",
+    params(("id" = String, Path, description = "Image ID or `repository:tag`")),
+    request_body = ImageTagRequest,
+    responses(
+        (status = 200, description = "Image tagged", body = OperationResult),
+        (status = 400, description = "Invalid repository or tag", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such image", body = ApiErrorResponse),
+        (status = 409, description = "Tag is held by another image", body = ApiErrorResponse),
+        (status = 502, description = "Push to the registry failed", body = ApiErrorResponse),
+    ),
+)]
+pub async fn tag_local_image(
+    _: RequireOperator,
+    State(state): State<AppState>,
+    env: Env,
+    Path(id): Path<String>,
+    Json(request): Json<ImageTagRequest>,
+) -> Result<Json<OperationResult>, ApiError> {
+    if !is_valid_repository(&request.repository) || !is_valid_tag(&request.tag) {
+        return Err(ApiError::from_status(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid image reference '{}:{}'", request.repository, request.tag),
+        ));
+    }
+
+    let images = env.runtime.list_images().await.map_err(|e| {
+        error!("Failed to list local images: {}", e);
+        ApiError::runtime(&e, format!("Failed to list images: {}", e))
+    })?;
+
+    let Some(source) = images.iter().find(|image| image.matches_reference(&id)) else {
+        return Err(ApiError::not_found("IMAGE", format!("Image '{}' not found", id)));
+    };
+
+    let target = format!("{}:{}", request.repository, request.tag);
+    let existing = images
+        .iter()
+        .find(|image| image.id != source.id && image.matches_reference(&target));
+
+    if let Some(existing) = existing
+        && !request.force
+    {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "TAG_EXISTS",
+            format!("Tag '{}' already points at image {}", target, existing.id),
+        ));
+    }
+
+    if let Err(e) = env.runtime.tag_image(&source.id, &request.repository, &request.tag).await {
+        error!("Failed to tag image {} as {}: {}", id, target, e);
+        return Err(ApiError::runtime(&e, format!("Failed to tag image: {}", e)));
+    }
+
+    info!("Tagged image {} as {}", source.id, target);
+
+    let Some(registry) = request.push_registry else {
+        return Ok(Json(OperationResult {
+            success: true,
+            message: format!("Tagged {} as {}", id, target),
+        }));
+    };
+
+    // Cloned so a long push does not hold the registry list locked
+    let client = state.registry_manager.read().await.get_registry(&registry).cloned();
+    let Some(client) = client else {
+        return Err(ApiError::not_found(
+            "REGISTRY",
+            format!("Tagged {} as {}, but registry '{}' was not found", id, target, registry),
+        ));
+    };
+
+    // Only images pulled through the agent have their manifest and blobs at hand to push
+    let blob_dir = std::path::Path::new(&state.config.data_dir).join("blobs");
+    let mut stored = None;
+    for reference in &source.repo_tags {
+        if let Some((repository, tag)) = split_reference(reference)
+            && let Ok(Some(digest)) = local_manifest_digest(&blob_dir, repository, tag).await
+        {
+            stored = Some(digest);
+            break;
+        }
+    }
+    let pushed = match stored {
+        Some(digest) => match tag_local_manifest(&blob_dir, &request.repository, &request.tag, &digest).await {
+            Ok(()) => client.push_image(&request.repository, &request.tag, &blob_dir).await,
+            Err(e) => Err(e),
+        },
+        None => Err(gpanel_core::Error::NotFound(format!("{} was not pulled through GhostPanel, so its layers are not available to push", id))),
+    };
+
+    match pushed {
+        Ok(_) => {
+            info!("Pushed {} to {}", target, registry);
+            Ok(Json(OperationResult {
+                success: true,
+                message: format!("Tagged {} as {} and pushed to {}", id, target, registry),
+            }))
+        }
+        Err(e) => {
+            error!("Failed to push {} to {}: {}", target, registry, e);
+            Err(ApiError::new(
+                StatusCode::BAD_GATEWAY,
+                "PUSH_FAILED",
+                format!("Tagged {} as {}, but push to {} failed: {}", id, target, registry, e),
+            ))
+        }
+    }
+}
+
+/// Repository and tag of a local image's `repository:tag`, the tag defaulting to `latest`
+fn split_reference(reference: &str) -> Option<(&str, &str)> {
+    if reference.contains('@') {
+        return None;
+    }
+    let name_start = reference.rfind('/').map_or(0, |slash| slash + 1);
+    match reference[name_start..].find(':') {
+        Some(colon) => Some((&reference[..name_start + colon], &reference[name_start + colon + 1..])),
+        None => Some((reference, "latest")),
+    }
+}
+
+/// Remove unused local images and report the space reclaimed
+#[utoipa::path(
+    post,
+    path = "/api/v1/images/prune",
+    tag = "images",
+    request_body = ImagePruneRequest,
+    responses(
+        (status = 200, description = "Removed images and reclaimed space", body = ImagePruneReport),
+        (status = 400, description = "Invalid filter", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+    ),
+)]
+pub async fn prune_images(
+    _: RequireOperator,
+    env: Env,
+    Json(request): Json<ImagePruneRequest>,
+) -> Result<Json<ImagePruneReport>, ApiError> {
+    let until = match request.until.as_deref() {
+        Some(until) => {
+            let age = parse_duration(until).ok_or_else(|| {
+                ApiError::from_status(StatusCode::BAD_REQUEST, format!("Invalid duration '{}'", until))
+            })?;
+            Some(chrono::Utc::now() - age)
+        }
+        None => None,
+    };
+
+    let filter = ImagePruneFilter {
+        dangling_only: request.dangling_only,
+        until,
+        label: request.label_filter,
+    };
+
+    match env.runtime.prune_images(&filter).await {
+        Ok(report) => {
+            info!(
+                "Pruned {} images, reclaimed {} bytes",
+                report.deleted.len(),
+                report.reclaimed_bytes
+            );
+            Ok(Json(report))
+        }
+        Err(e) => {
+            error!("Failed to prune images: {}", e);
+            Err(ApiError::runtime(&e, format!("Failed to prune images: {}", e)))
+        }
+    }
+}
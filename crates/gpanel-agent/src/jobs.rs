@@ -0,0 +1,419 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use gpanel_core::{ApiErrorResponse, Job, JobKind, JobStatus, PullProgress};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::task::AbortHandle;
+use tracing::info;
+
+use crate::auth::RequireOperator;
+use crate::error::ApiError;
+use crate::AppState;
+
+struct Entry {
+    job: Job,
+    /// Set while the job's task runs
+    abort: Option<AbortHandle>,
+}
+
+/// Background jobs by ID. A std mutex, since progress is reported from synchronous
+/// callbacks and the lock is never held across an await.
+pub struct JobManager {
+    jobs: Mutex<HashMap<String, Entry>>,
+    /// Finished jobs are dropped this long after they end
+    ttl: chrono::Duration,
+}
+
+/// Lets a running job report how far it has got
+#[derive(Clone)]
+pub struct JobProgress {
+    manager: Arc<JobManager>,
+    id: String,
+}
+
+impl JobProgress {
+    pub fn update(&self, percent: u8, message: impl Into<String>) {
+        self.with_running(|job| {
+            job.percent = percent.min(100);
+            job.message = message.into();
+        });
+    }
+
+    /// Record how much of an image has been downloaded
+    pub fn transfer(&self, progress: PullProgress) {
+        self.with_running(|job| job.progress = Some(progress));
+    }
+
+    fn with_running(&self, update: impl FnOnce(&mut Job)) {
+        let mut jobs = self.manager.jobs();
+        if let Some(entry) = jobs.get_mut(&self.id)
+            && entry.job.status == JobStatus::Running
+        {
+            update(&mut entry.job);
+        }
+    }
+}
+
+impl JobManager {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            jobs: Mutex::new(HashMap::new()),
+            ttl: chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::MAX),
+        }
+    }
+
+    /// Run `work` in its own task. It gets a `JobProgress` to report with, and its
+    /// result becomes the job's final message or error.
+    pub fn start<F, Fut>(self: &Arc<Self>, kind: JobKind, target: String, work: F) -> Job
+    where
+        F: FnOnce(JobProgress) -> Fut,
+        Fut: Future<Output = anyhow::Result<String>> + Send + 'static,
+    {
+        self.prune();
+
+        let job = Job {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind,
+            status: JobStatus::Running,
+            percent: 0,
+            progress: None,
+            message: "Starting".to_string(),
+            target,
+            started_at: chrono::Utc::now(),
+            finished_at: None,
+        };
+        let work = work(JobProgress {
+            manager: self.clone(),
+            id: job.id.clone(),
+        });
+        let manager = self.clone();
+        let id = job.id.clone();
+
+        // Spawned under the lock, so the task cannot finish, and the job cannot be
+        // cancelled, before its abort handle is stored
+        let mut jobs = self.jobs();
+        let task = tokio::spawn(async move {
+            let result = work.await;
+            manager.finish(&id, result);
+        });
+        jobs.insert(
+            job.id.clone(),
+            Entry {
+                job: job.clone(),
+                abort: Some(task.abort_handle()),
+            },
+        );
+        job
+    }
+
+    /// The jobs, even if a thread panicked holding the lock; every update leaves an
+    /// entry consistent, so there is nothing to recover
+    fn jobs(&self) -> MutexGuard<'_, HashMap<String, Entry>> {
+        self.jobs.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn finish(&self, id: &str, result: anyhow::Result<String>) {
+        let mut jobs = self.jobs();
+        let Some(entry) = jobs.get_mut(id) else {
+            return;
+        };
+        if entry.job.status != JobStatus::Running {
+            return;
+        }
+        match result {
+            Ok(message) => {
+                entry.job.status = JobStatus::Succeeded;
+                entry.job.percent = 100;
+                entry.job.message = message;
+            }
+            Err(e) => {
+                entry.job.status = JobStatus::Failed;
+                entry.job.message = e.to_string();
+            }
+        }
+        entry.job.finished_at = Some(chrono::Utc::now());
+        entry.abort = None;
+    }
+
+    /// Stop a running job. Returns the job as it now stands, or `None` for an unknown ID.
+    pub fn cancel(&self, id: &str) -> Option<Job> {
+        self.prune();
+        let mut jobs = self.jobs();
+        let entry = jobs.get_mut(id)?;
+        if entry.job.status == JobStatus::Running {
+            if let Some(abort) = entry.abort.take() {
+                abort.abort();
+            }
+            entry.job.status = JobStatus::Cancelled;
+            entry.job.message = "Cancelled".to_string();
+            entry.job.finished_at = Some(chrono::Utc::now());
+            info!("Cancelled job {} ({})", id, entry.job.target);
+        }
+        Some(entry.job.clone())
+    }
+
+    pub fn get(&self, id: &str) -> Option<Job> {
+        self.prune();
+        self.jobs().get(id).map(|entry| entry.job.clone())
+    }
+
+    /// Every job, newest first
+    pub fn list(&self) -> Vec<Job> {
+        self.prune();
+        let mut jobs: Vec<Job> = self.jobs().values().map(|entry| entry.job.clone()).collect();
+        jobs.sort_by_key(|job| std::cmp::Reverse(job.started_at));
+        jobs
+    }
+
+    /// Drop jobs that finished more than `ttl` ago
+    fn prune(&self) {
+        let cutoff = chrono::Utc::now() - self.ttl;
+        self.jobs()
+            .retain(|_, entry| entry.job.finished_at.is_none_or(|finished_at| finished_at > cutoff));
+    }
+}
+
+/// Background jobs, newest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/jobs",
+    tag = "jobs",
+    responses((status = 200, description = "Running jobs and recently finished ones", body = Vec<Job>)),
+)]
+pub async fn list_jobs(State(state): State<AppState>) -> Json<Vec<Job>> {
+    Json(state.jobs.list())
+}
+
+/// Status and progress of a job
+#[utoipa::path(
+    get,
+    path = "/api/v1/jobs/{id}",
+    tag = "jobs",
+    params(("id" = String, Path, description = "Job ID")),
+    responses(
+        (status = 200, description = "The job", body = Job),
+        (status = 404, description = "No such job, or it finished long enough ago to have expired", body = ApiErrorResponse),
+    ),
+)]
+pub async fn get_job(State(state): State<AppState>, Path(id): Path<String>) -> Result<Json<Job>, ApiError> {
+    state
+        .jobs
+        .get(&id)
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found("JOB", format!("Job '{}' not found", id)))
+}
+
+/// Cancel a running job
+#[utoipa::path(
+    delete,
+    path = "/api/v1/jobs/{id}",
+    tag = "jobs",
+    params(("id" = String, Path, description = "Job ID")),
+    responses(
+        (status = 200, description = "The cancelled job", body = Job),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such job", body = ApiErrorResponse),
+        (status = 409, description = "Job already finished", body = ApiErrorResponse),
+    ),
+)]
+pub async fn cancel_job(
+    _: RequireOperator,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Job>, ApiError> {
+    let Some(job) = state.jobs.cancel(&id) else {
+        return Err(ApiError::not_found("JOB", format!("Job '{}' not found", id)));
+    };
+    match job.status {
+        JobStatus::Succeeded | JobStatus::Failed => Err(ApiError::from_status(
+            StatusCode::CONFLICT,
+            format!("Job '{}' already finished", id),
+        )),
+        JobStatus::Running | JobStatus::Cancelled => Ok(Json(job)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::agent;
+    use axum::http::Method;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tokio::sync::oneshot;
+
+    fn manager() -> Arc<JobManager> {
+        Arc::new(JobManager::new(Duration::from_secs(60)))
+    }
+
+    /// Poll until the job is no longer running
+    async fn settled(manager: &JobManager, id: &str) -> Job {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let job = manager.get(id).unwrap();
+                if job.status != JobStatus::Running {
+                    return job;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("job did not finish")
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_jobs_run_and_finish_independently() {
+        let manager = manager();
+        let mut releases = Vec::new();
+        let mut ids = Vec::new();
+        for index in 0..8 {
+            let (release, released) = oneshot::channel::<()>();
+            releases.push(release);
+            let job = manager.start(JobKind::ImagePull, format!("image-{}", index), move |progress| async move {
+                progress.update(50, "Waiting");
+                released.await?;
+                if index % 2 == 0 {
+                    Ok(format!("Pulled image-{}", index))
+                } else {
+                    anyhow::bail!("image-{} not found", index)
+                }
+            });
+            ids.push(job.id);
+        }
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let running = manager.list();
+        assert_eq!(running.len(), 8);
+        assert!(running.iter().all(|job| job.status == JobStatus::Running && job.percent == 50));
+
+        for release in releases {
+            release.send(()).unwrap();
+        }
+        for (index, id) in ids.iter().enumerate() {
+            let job = settled(&manager, id).await;
+            if index % 2 == 0 {
+                assert_eq!(job.status, JobStatus::Succeeded);
+                assert_eq!(job.percent, 100);
+                assert_eq!(job.message, format!("Pulled image-{}", index));
+            } else {
+                assert_eq!(job.status, JobStatus::Failed);
+                assert_eq!(job.message, format!("image-{} not found", index));
+            }
+            assert!(job.finished_at.is_some());
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn jobs_finishing_at_once_are_recorded() {
+        let manager = manager();
+        let ids: Vec<String> = (0..50)
+            .map(|index| {
+                manager
+                    .start(JobKind::ImagePull, format!("image-{}", index), |_| async { Ok("Done".to_string()) })
+                    .id
+            })
+            .collect();
+        for id in &ids {
+            assert_eq!(settled(&manager, id).await.status, JobStatus::Succeeded);
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelling_stops_the_task() {
+        let manager = manager();
+        let completed = Arc::new(AtomicBool::new(false));
+        let (dropped_tx, dropped) = oneshot::channel::<()>();
+
+        let flag = completed.clone();
+        let job = manager.start(JobKind::ProtonInstall, "GE-Proton9-1".to_string(), move |_| async move {
+            // Dropped with the task, telling the test it was aborted
+            let _guard = dropped_tx;
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            flag.store(true, Ordering::SeqCst);
+            Ok("Installed".to_string())
+        });
+        tokio::task::yield_now().await;
+
+        let cancelled = manager.cancel(&job.id).unwrap();
+        assert_eq!(cancelled.status, JobStatus::Cancelled);
+        assert!(cancelled.finished_at.is_some());
+        tokio::time::timeout(Duration::from_secs(5), dropped)
+            .await
+            .expect("task was not aborted")
+            .unwrap_err();
+        assert!(!completed.load(Ordering::SeqCst));
+        assert_eq!(manager.get(&job.id).unwrap().status, JobStatus::Cancelled);
+
+        // Cancelling again, or cancelling a finished job, changes nothing
+        assert_eq!(manager.cancel(&job.id).unwrap().status, JobStatus::Cancelled);
+        assert!(manager.cancel("missing").is_none());
+    }
+
+    #[tokio::test]
+    async fn cancelling_before_the_task_runs_stops_it() {
+        let manager = manager();
+        let ran = Arc::new(AtomicBool::new(false));
+        let flag = ran.clone();
+        let job = manager.start(JobKind::ImagePull, "nginx".to_string(), move |_| async move {
+            flag.store(true, Ordering::SeqCst);
+            Ok("Pulled".to_string())
+        });
+        // The current-thread runtime has not polled the task yet
+        manager.cancel(&job.id).unwrap();
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!ran.load(Ordering::SeqCst));
+        assert_eq!(manager.get(&job.id).unwrap().status, JobStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn finished_jobs_expire_after_the_ttl() {
+        let manager = Arc::new(JobManager::new(Duration::ZERO));
+        let job = manager.start(JobKind::ImagePull, "nginx".to_string(), |_| async { Ok("Pulled".to_string()) });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(manager.get(&job.id).is_none());
+        assert!(manager.list().is_empty());
+    }
+
+    #[tokio::test]
+    async fn jobs_survive_a_poisoned_lock() {
+        let manager = manager();
+        let job = manager.start(JobKind::ImagePull, "nginx".to_string(), |_| async { Ok("Pulled".to_string()) });
+
+        let poisoner = manager.clone();
+        let _ = std::thread::spawn(move || {
+            let _jobs = poisoner.jobs.lock().unwrap();
+            panic!("poison the job lock");
+        })
+        .join();
+        assert!(manager.jobs.is_poisoned());
+
+        assert_eq!(settled(&manager, &job.id).await.status, JobStatus::Succeeded);
+        assert_eq!(manager.list().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn cancel_route_refuses_finished_jobs() {
+        let agent = agent().await;
+        let job = agent
+            .state
+            .jobs
+            .start(JobKind::ImagePull, "nginx".to_string(), |_| async { Ok("Pulled".to_string()) });
+        settled(&agent.state.jobs, &job.id).await;
+
+        let uri = format!("/api/v1/jobs/{}", job.id);
+        let (status, body) = agent.request(Method::GET, &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "succeeded");
+
+        let (status, _) = agent.request(Method::DELETE, &uri, None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, body) = agent.request(Method::DELETE, "/api/v1/jobs/missing", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "JOB_NOT_FOUND");
+    }
+}
@@ -0,0 +1,821 @@
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+};
+use chrono::{DateTime, Duration, Utc};
+use futures::{Stream, StreamExt};
+use gpanel_core::{
+    ApiErrorResponse, ContainerLogsRequest, ContainerRuntime, ContainerStatus, LogCacheConfig, LogCacheStats, LogLineStream,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+use utoipa::IntoParams;
+
+use crate::environments::Env;
+use crate::error::ApiError;
+use crate::AppState;
+
+/// Lines returned when the request does not ask for a specific tail
+const DEFAULT_TAIL: usize = 100;
+
+/// Most cached lines compared against a delta when removing overlap
+const STITCH_WINDOW: usize = 1000;
+
+/// Block size for reading cached log files
+const READ_CHUNK: usize = 64 * 1024;
+
+/// How often a followed container is checked for having exited
+const FOLLOW_STATUS_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Query parameters for GET /api/v1/containers/:id/logs
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LogsQuery {
+    /// Number of lines from the end of the log, a positive integer
+    pub tail: Option<String>,
+    /// Return the whole log instead of a tail
+    #[serde(default)]
+    pub all: bool,
+    /// Only lines logged at or after this RFC 3339 time
+    pub since: Option<String>,
+    /// Prefix each line with its timestamp (default true)
+    pub timestamps: Option<bool>,
+}
+
+impl LogsQuery {
+    /// Validate the query into a Bolt logs request; a `tail` of `None` means the whole log
+    pub fn to_request(&self, container_id: &str, follow: bool) -> Result<ContainerLogsRequest, String> {
+        let tail = match (&self.tail, self.all) {
+            (_, true) => None,
+            (None, false) => Some(DEFAULT_TAIL as u32),
+            (Some(tail), false) => match tail.parse::<u32>() {
+                Ok(lines) if lines > 0 => Some(lines),
+                _ => return Err(format!("Invalid tail '{}': expected a positive integer", tail)),
+            },
+        };
+
+        let since = self
+            .since
+            .as_deref()
+            .map(|since| {
+                DateTime::parse_from_rfc3339(since)
+                    .map(|time| time.with_timezone(&Utc))
+                    .map_err(|_| format!("Invalid since '{}': expected an RFC 3339 time", since))
+            })
+            .transpose()?;
+
+        Ok(ContainerLogsRequest {
+            container_id: container_id.to_string(),
+            follow,
+            tail,
+            timestamps: self.timestamps.unwrap_or(true),
+            since,
+        })
+    }
+}
+
+/// Portion of a container log being requested
+#[derive(Debug, Clone, Copy)]
+pub enum LogRange {
+    Tail(usize),
+    Full,
+}
+
+/// Log contents, either in memory or as an open cache file
+pub enum CachedLog {
+    Text(String),
+    /// The handle stays readable if the entry is evicted while it is streamed
+    File(tokio::fs::File),
+}
+
+/// Index entry for one spooled log file
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    size: u64,
+    lines: usize,
+    /// Whether the file holds the whole log rather than only a tail of it
+    complete: bool,
+    /// Start of the last fetch from Bolt, used as `since` for the next delta
+    fetched_at: DateTime<Utc>,
+    last_access: DateTime<Utc>,
+}
+
+impl CacheEntry {
+    fn covers(&self, range: LogRange) -> bool {
+        match range {
+            LogRange::Tail(lines) => self.complete || lines <= self.lines,
+            LogRange::Full => self.complete,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    total_bytes: u64,
+    stats: LogCacheStats,
+}
+
+/// Container logs spooled to `{data_dir}/logs`, one file per container, with LRU eviction
+pub struct LogCache {
+    dir: PathBuf,
+    config: LogCacheConfig,
+    /// Requests go through one lock so the index never disagrees with the files
+    state: Mutex<CacheState>,
+}
+
+impl LogCache {
+    /// Open the cache; the index lives in memory, so files left by a previous run are discarded
+    pub async fn open(data_dir: &str, config: LogCacheConfig) -> Self {
+        let dir = FsPath::new(data_dir).join("logs");
+        if let Err(e) = tokio::fs::remove_dir_all(&dir).await
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!("Failed to clear log cache {}: {}", dir.display(), e);
+        }
+        if config.enabled
+            && let Err(e) = tokio::fs::create_dir_all(&dir).await
+        {
+            warn!("Failed to create log cache {}: {}", dir.display(), e);
+        }
+
+        Self {
+            dir,
+            state: Mutex::new(CacheState {
+                stats: LogCacheStats {
+                    max_total_bytes: config.max_total_bytes,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            config,
+        }
+    }
+
+    pub async fn stats(&self) -> LogCacheStats {
+        let state = self.state.lock().await;
+        LogCacheStats {
+            entries: state.entries.len(),
+            total_bytes: state.total_bytes,
+            ..state.stats.clone()
+        }
+    }
+
+    fn path(&self, container_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.log", container_id))
+    }
+
+    /// Read part of a container log, serving from cache where possible
+    pub async fn read(
+        &self,
+        bolt_client: &dyn ContainerRuntime,
+        container_id: &str,
+        range: LogRange,
+        now: DateTime<Utc>,
+    ) -> gpanel_core::Result<CachedLog> {
+        if !self.config.enabled || !is_cacheable(container_id) {
+            let logs = fetch(bolt_client, container_id, range, None).await?;
+            return Ok(CachedLog::Text(logs));
+        }
+
+        let mut state = self.state.lock().await;
+        self.expire(&mut state, now).await;
+
+        let path = self.path(container_id);
+        let cached = state.entries.get(container_id).filter(|e| e.covers(range)).cloned();
+        let served_from_cache = match cached {
+            Some(entry) if now - entry.fetched_at < Duration::seconds(self.config.fresh_secs as i64) => {
+                state.stats.hits += 1;
+                true
+            }
+            Some(entry) => match fetch(bolt_client, container_id, LogRange::Full, Some(entry.fetched_at)).await {
+                Ok(delta) => {
+                    state.stats.delta_fetches += 1;
+                    self.append(&mut state, container_id, &delta, now).await?
+                }
+                Err(e) => {
+                    // Stale lines are more useful than an error page
+                    warn!("Serving cached logs for {} after delta fetch failed: {}", container_id, e);
+                    state.stats.hits += 1;
+                    true
+                }
+            },
+            None => false,
+        };
+
+        if !served_from_cache {
+            let logs = fetch(bolt_client, container_id, range, None).await?;
+            state.stats.misses += 1;
+            if !self.replace(&mut state, container_id, &logs, matches!(range, LogRange::Full), now).await {
+                return Ok(CachedLog::Text(logs));
+            }
+        }
+
+        if let Some(entry) = state.entries.get_mut(container_id) {
+            entry.last_access = now;
+        }
+        self.evict(&mut state, container_id).await;
+
+        match range {
+            LogRange::Tail(lines) => {
+                let tail = tokio::task::spawn_blocking(move || read_tail(&path, lines)).await.map_err(std::io::Error::from)??;
+                Ok(CachedLog::Text(spool(&tail)))
+            }
+            LogRange::Full => Ok(CachedLog::File(tokio::fs::File::open(&path).await?)),
+        }
+    }
+
+    /// Replace a container's cache file; returns false if the log is too large to cache
+    async fn replace(
+        &self,
+        state: &mut CacheState,
+        container_id: &str,
+        logs: &str,
+        complete: bool,
+        now: DateTime<Utc>,
+    ) -> bool {
+        self.remove(state, container_id).await;
+
+        let lines: Vec<&str> = logs.lines().collect();
+        let content = spool(&lines);
+        if content.len() as u64 > self.config.max_total_bytes {
+            info!("Logs for {} exceed the log cache size, not caching", container_id);
+            return false;
+        }
+        if let Err(e) = tokio::fs::write(self.path(container_id), &content).await {
+            error!("Failed to write log cache for {}: {}", container_id, e);
+            return false;
+        }
+
+        state.total_bytes += content.len() as u64;
+        state.entries.insert(
+            container_id.to_string(),
+            CacheEntry {
+                size: content.len() as u64,
+                lines: lines.len(),
+                complete,
+                fetched_at: now,
+                last_access: now,
+            },
+        );
+        true
+    }
+
+    /// Append the new lines of a delta fetch; returns false if the entry had to be dropped
+    async fn append(
+        &self,
+        state: &mut CacheState,
+        container_id: &str,
+        delta: &str,
+        now: DateTime<Utc>,
+    ) -> gpanel_core::Result<bool> {
+        let path = self.path(container_id);
+        let delta: Vec<&str> = delta.lines().collect();
+        let window = delta.len().min(STITCH_WINDOW);
+        let cached_tail = {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || read_tail(&path, window)).await.map_err(std::io::Error::from)??
+        };
+        let content = spool(new_lines(&cached_tail, &delta));
+
+        let Some(entry) = state.entries.get(container_id).cloned() else {
+            return Ok(false);
+        };
+        if entry.size + content.len() as u64 > self.config.max_total_bytes {
+            self.remove(state, container_id).await;
+            return Ok(false);
+        }
+
+        if !content.is_empty() {
+            let mut file = tokio::fs::OpenOptions::new().append(true).open(&path).await?;
+            file.write_all(content.as_bytes()).await?;
+        }
+
+        state.total_bytes += content.len() as u64;
+        if let Some(entry) = state.entries.get_mut(container_id) {
+            entry.size += content.len() as u64;
+            entry.lines += content.matches('\n').count();
+            entry.fetched_at = now;
+        }
+        Ok(true)
+    }
+
+    async fn remove(&self, state: &mut CacheState, container_id: &str) {
+        let Some(entry) = state.entries.remove(container_id) else {
+            return;
+        };
+        state.total_bytes = state.total_bytes.saturating_sub(entry.size);
+        if let Err(e) = tokio::fs::remove_file(self.path(container_id)).await {
+            warn!("Failed to remove cached logs for {}: {}", container_id, e);
+        }
+    }
+
+    /// Drop logs that have not been read within the retention period
+    async fn expire(&self, state: &mut CacheState, now: DateTime<Utc>) {
+        let retention = Duration::seconds(self.config.retention_secs as i64);
+        let expired: Vec<String> = state
+            .entries
+            .iter()
+            .filter(|(_, entry)| now - entry.last_access > retention)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for container_id in expired {
+            self.remove(state, &container_id).await;
+            state.stats.evictions += 1;
+        }
+    }
+
+    /// Evict least recently read logs until the cache fits, keeping the one just served
+    async fn evict(&self, state: &mut CacheState, keep: &str) {
+        while state.total_bytes > self.config.max_total_bytes {
+            let Some(oldest) = state
+                .entries
+                .iter()
+                .filter(|(id, _)| id.as_str() != keep)
+                .min_by_key(|(_, entry)| entry.last_access)
+                .map(|(id, _)| id.clone())
+            else {
+                break;
+            };
+            self.remove(state, &oldest).await;
+            state.stats.evictions += 1;
+        }
+    }
+}
+
+async fn fetch(
+    bolt_client: &dyn ContainerRuntime,
+    container_id: &str,
+    range: LogRange,
+    since: Option<DateTime<Utc>>,
+) -> gpanel_core::Result<String> {
+    bolt_client
+        .get_container_logs(ContainerLogsRequest {
+            container_id: container_id.to_string(),
+            follow: false,
+            tail: match range {
+                LogRange::Tail(lines) => Some(lines.min(u32::MAX as usize) as u32),
+                LogRange::Full => None,
+            },
+            timestamps: true,
+            since,
+        })
+        .await
+}
+
+/// IDs become file names, so anything that could escape the cache directory bypasses it
+fn is_cacheable(container_id: &str) -> bool {
+    !container_id.is_empty()
+        && container_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Lines of a delta not already at the end of the cache. Bolt's `since` has one-second
+/// resolution, so a delta repeats whatever was logged in the second of the previous fetch.
+fn new_lines<'a, 'b>(cached_tail: &[String], delta: &'b [&'a str]) -> &'b [&'a str] {
+    let max = cached_tail.len().min(delta.len());
+    let overlap = (1..=max)
+        .rev()
+        .find(|&k| {
+            cached_tail[cached_tail.len() - k..]
+                .iter()
+                .zip(&delta[..k])
+                .all(|(cached, new)| cached == new)
+        })
+        .unwrap_or(0);
+    &delta[overlap..]
+}
+
+/// Newline-terminated file content for a list of lines
+fn spool<S: AsRef<str>>(lines: &[S]) -> String {
+    let mut content = String::new();
+    for line in lines {
+        content.push_str(line.as_ref());
+        content.push('\n');
+    }
+    content
+}
+
+/// Last `lines` lines of a cache file, reading backwards from the end
+fn read_tail(path: &FsPath, lines: usize) -> std::io::Result<Vec<String>> {
+    if lines == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut file = std::fs::File::open(path)?;
+    let mut pos = file.metadata()?.len();
+    let mut buf = Vec::new();
+    let mut newlines = 0;
+
+    // Every line ends with a newline, so one more than `lines` marks the start of the first wanted line
+    while pos > 0 && newlines <= lines {
+        let size = (READ_CHUNK as u64).min(pos);
+        pos -= size;
+        file.seek(SeekFrom::Start(pos))?;
+        let mut block = vec![0; size as usize];
+        file.read_exact(&mut block)?;
+        newlines += block.iter().filter(|&&b| b == b'\n').count();
+        block.extend_from_slice(&buf);
+        buf = block;
+    }
+
+    let text = String::from_utf8_lossy(&buf);
+    let all: Vec<&str> = text.lines().collect();
+    Ok(all[all.len().saturating_sub(lines)..].iter().map(|line| line.to_string()).collect())
+}
+
+fn file_chunks(file: tokio::fs::File) -> impl Stream<Item = std::io::Result<Vec<u8>>> {
+    futures::stream::try_unfold(file, |mut file| async move {
+        let mut chunk = vec![0; READ_CHUNK];
+        let read = file.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        chunk.truncate(read);
+        Ok(Some((chunk, file)))
+    })
+}
+
+/// Get container logs: the last `tail` lines, the whole log with `all=true`, or those logged
+/// after `since`. The cache holds timestamped logs, so `since` and `timestamps=false` go to Bolt.
+#[utoipa::path(
+    get,
+    path = "/api/v1/containers/{id}/logs",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name"), LogsQuery),
+    responses(
+        (status = 200, description = "Log lines", content_type = "text/plain", body = String),
+        (status = 400, description = "Invalid tail or since", body = ApiErrorResponse),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+    ),
+)]
+pub async fn get_container_logs(
+    State(state): State<AppState>,
+    env: Env,
+    Path(id): Path<String>,
+    Query(query): Query<LogsQuery>,
+) -> Result<Response, ApiError> {
+    let request = query
+        .to_request(&id, false)
+        .map_err(|message| ApiError::from_status(StatusCode::BAD_REQUEST, message))?;
+
+    if env.runtime.get_container(&id).await.is_err() {
+        return Err(ApiError::not_found("CONTAINER", format!("Container '{}' not found", id)));
+    }
+
+    // The cache is keyed by container ID, so it only holds the default environment's logs
+    let result = if request.since.is_none() && request.timestamps && env.is_default() {
+        let range = match request.tail {
+            Some(lines) => LogRange::Tail(lines as usize),
+            None => LogRange::Full,
+        };
+        state.logs.read(env.runtime.as_ref(), &id, range, Utc::now()).await
+    } else {
+        env.runtime.get_container_logs(request).await.map(CachedLog::Text)
+    };
+
+    match result {
+        Ok(CachedLog::Text(logs)) => Ok(logs.into_response()),
+        Ok(CachedLog::File(file)) => Ok((
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+            Body::from_stream(file_chunks(file)),
+        )
+            .into_response()),
+        Err(e) => {
+            error!("Failed to get logs for container {}: {}", id, e);
+            Err(ApiError::runtime(&e, format!("Failed to get logs: {}", e),
+            ))
+        }
+    }
+}
+
+/// Follow container logs as server-sent events, one `message` per line. An `end` event is
+/// sent when the container exits, so the browser closes its EventSource instead of reconnecting.
+/// The stream is dropped, closing the Bolt request, when the client disconnects.
+#[utoipa::path(
+    get,
+    path = "/api/v1/containers/{id}/logs/stream",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name"), LogsQuery),
+    responses(
+        (status = 200, description = "Server-sent events, one log line per event and an `end` event once the container exits", content_type = "text/event-stream", body = String),
+        (status = 400, description = "Invalid tail or since", body = ApiErrorResponse),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+    ),
+)]
+pub async fn stream_container_logs(
+    env: Env,
+    Path(id): Path<String>,
+    Query(query): Query<LogsQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let request = query
+        .to_request(&id, true)
+        .map_err(|message| ApiError::from_status(StatusCode::BAD_REQUEST, message))?;
+
+    if env.runtime.get_container(&id).await.is_err() {
+        return Err(ApiError::not_found("CONTAINER", format!("Container '{}' not found", id)));
+    }
+
+    let lines = env.runtime.follow_container_logs(request).await.map_err(|e| {
+        error!("Failed to follow logs for container {}: {}", id, e);
+        ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to follow logs: {}", e))
+    })?;
+
+    Ok(Sse::new(follow_events(lines, env.runtime.clone(), id)).keep_alive(KeepAlive::default()))
+}
+
+struct Follow {
+    lines: LogLineStream,
+    ticker: tokio::time::Interval,
+    runtime: Arc<dyn ContainerRuntime>,
+    container_id: String,
+    ended: bool,
+}
+
+fn follow_events(
+    lines: LogLineStream,
+    runtime: Arc<dyn ContainerRuntime>,
+    container_id: String,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    let follow = Follow {
+        lines,
+        ticker: tokio::time::interval(FOLLOW_STATUS_INTERVAL),
+        runtime,
+        container_id,
+        ended: false,
+    };
+
+    futures::stream::unfold(follow, |mut follow| async move {
+        if follow.ended {
+            return None;
+        }
+
+        let end = |follow: Follow, reason: &str| {
+            let event = Event::default().event("end").data(reason);
+            Some((Ok(event), Follow { ended: true, ..follow }))
+        };
+
+        loop {
+            tokio::select! {
+                line = follow.lines.next() => {
+                    return match line {
+                        Some(Ok(line)) => Some((Ok(Event::default().data(line)), follow)),
+                        Some(Err(e)) => {
+                            warn!("Log stream for container {} failed: {}", follow.container_id, e);
+                            end(follow, "Log stream failed")
+                        }
+                        None => end(follow, "Container exited"),
+                    };
+                }
+                _ = follow.ticker.tick() => {
+                    let running = follow
+                        .runtime
+                        .get_container(&follow.container_id)
+                        .await
+                        .is_ok_and(|c| matches!(c.status, ContainerStatus::Running | ContainerStatus::Paused));
+                    if !running {
+                        return end(follow, "Container exited");
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpanel_core::MockBoltClient;
+
+    const WEB: &str = "mock_web_server_001";
+    const DATABASE: &str = "mock_database_003";
+
+    async fn cache(dir: &tempfile::TempDir, max_total_bytes: u64) -> LogCache {
+        let config = LogCacheConfig {
+            enabled: true,
+            max_total_bytes,
+            retention_secs: 3600,
+            fresh_secs: 2,
+        };
+        LogCache::open(&dir.path().display().to_string(), config).await
+    }
+
+    /// Size of the mock's canned log once spooled, the same for every container
+    async fn log_bytes(runtime: &MockBoltClient) -> u64 {
+        let logs = fetch(runtime, WEB, LogRange::Full, None).await.unwrap();
+        spool(&logs.lines().collect::<Vec<_>>()).len() as u64
+    }
+
+    async fn text(log: CachedLog) -> String {
+        match log {
+            CachedLog::Text(text) => text,
+            CachedLog::File(mut file) => {
+                let mut text = String::new();
+                file.read_to_string(&mut text).await.unwrap();
+                text
+            }
+        }
+    }
+
+    fn query(tail: Option<&str>, all: bool, since: Option<&str>, timestamps: Option<bool>) -> LogsQuery {
+        LogsQuery {
+            tail: tail.map(str::to_string),
+            all,
+            since: since.map(str::to_string),
+            timestamps,
+        }
+    }
+
+    #[test]
+    fn queries_map_to_bolt_requests() {
+        let request = query(None, false, None, None).to_request(WEB, false).unwrap();
+        assert_eq!(request.container_id, WEB);
+        assert_eq!((request.tail, request.timestamps, request.since, request.follow), (Some(100), true, None, false));
+
+        let request = query(Some("500"), false, Some("2024-01-15T12:30:00+02:00"), Some(false))
+            .to_request(WEB, true)
+            .unwrap();
+        assert_eq!(request.tail, Some(500));
+        assert!(!request.timestamps);
+        assert!(request.follow);
+        // Offsets are converted to UTC
+        assert_eq!(request.since.unwrap().to_rfc3339(), "2024-01-15T10:30:00+00:00");
+
+        // `all` wins over any tail
+        assert_eq!(query(Some("20"), true, None, None).to_request(WEB, false).unwrap().tail, None);
+    }
+
+    #[test]
+    fn invalid_tails_and_times_are_rejected() {
+        for tail in ["0", "-5", "ten", "", "4294967296"] {
+            let error = query(Some(tail), false, None, None).to_request(WEB, false).unwrap_err();
+            assert!(error.starts_with(&format!("Invalid tail '{}'", tail)), "{}", error);
+        }
+        for since in ["yesterday", "2024-01-15", "2024-01-15 10:30:00", "1705314600"] {
+            let error = query(None, false, Some(since), None).to_request(WEB, false).unwrap_err();
+            assert!(error.contains("expected an RFC 3339 time"), "{}", error);
+        }
+    }
+
+    #[tokio::test]
+    async fn invalid_log_queries_are_answered_with_400() {
+        let agent = crate::testing::agent().await;
+        let logs = |query: &str| format!("/api/v1/containers/{}/logs?{}", WEB, query);
+
+        let (status, body) = agent.request(axum::http::Method::GET, &logs("tail=0"), None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "INVALID_REQUEST");
+        let (status, _) = agent.request(axum::http::Method::GET, &logs("since=yesterday"), None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = agent.request(axum::http::Method::GET, &logs("timestamps=maybe"), None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let request = axum::http::Request::get(logs("tail=500&since=2024-01-01T00:00:00Z&timestamps=false"))
+            .body(Body::empty())
+            .unwrap();
+        let (status, bytes) = agent.send(request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(String::from_utf8(bytes.to_vec()).unwrap().contains("Container started successfully"));
+        let (status, _) = agent.request(axum::http::Method::GET, "/api/v1/containers/missing/logs?tail=5", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    /// Read the SSE body until `count` frames have arrived, giving up after five seconds
+    async fn frames(response: &mut reqwest::Response, count: usize) -> Vec<String> {
+        let mut buffer = String::new();
+        let read = async {
+            while buffer.matches("\n\n").count() < count {
+                let chunk = response.chunk().await.unwrap().expect("stream ended early");
+                buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+            }
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(5), read).await.expect("frames in time");
+        buffer.split("\n\n").take(count).map(str::to_string).collect()
+    }
+
+    #[tokio::test]
+    async fn followed_logs_stream_the_tail_then_end_when_the_container_stops() {
+        let agent = crate::testing::agent().await;
+        let addr = agent.serve().await;
+        let stream = |id: &str, query: &str| format!("http://{}/api/v1/containers/{}/logs/stream?{}", addr, id, query);
+
+        let backlog = MockBoltClient::new()
+            .get_container_logs(query(None, true, None, None).to_request(WEB, false).unwrap())
+            .await
+            .unwrap();
+        let last_two: Vec<&str> = backlog.lines().skip(backlog.lines().count() - 2).collect();
+
+        let mut response = reqwest::get(stream(WEB, "tail=2")).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/event-stream");
+        let data = frames(&mut response, 2).await;
+        assert_eq!(data, last_two.iter().map(|line| format!("data: {}", line)).collect::<Vec<_>>());
+
+        // The status check notices the stop and closes the stream with an `end` event
+        agent.state.environments.default_env().runtime.stop_container(WEB, None).await.unwrap();
+        let end = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            loop {
+                let chunk = response.chunk().await.unwrap().expect("end event before the stream closes");
+                let text = String::from_utf8(chunk.to_vec()).unwrap();
+                if text.contains("event: end") {
+                    return text;
+                }
+            }
+        })
+        .await
+        .expect("end event in time");
+        assert!(end.contains("data: Container exited"), "{}", end);
+
+        let response = reqwest::get(stream("missing", "tail=2")).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+        let response = reqwest::get(stream(WEB, "tail=0")).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn deltas_drop_lines_already_cached() {
+        let cached = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        assert_eq!(new_lines(&cached, &["b", "c", "d"]), ["d"]);
+        assert_eq!(new_lines(&cached, &["d", "e"]), ["d", "e"]);
+        assert!(new_lines(&cached, &["a", "b", "c"]).is_empty());
+        assert!(new_lines(&[], &["a"]) == ["a"]);
+
+        // Repeated lines keep the ones beyond the longest overlap
+        let repeated = vec!["x".to_string(), "x".to_string()];
+        assert_eq!(new_lines(&repeated, &["x", "x", "x"]), ["x"]);
+    }
+
+    #[tokio::test]
+    async fn stale_entries_are_stitched_without_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = cache(&dir, 1024 * 1024).await;
+        let runtime = MockBoltClient::new();
+        let now = Utc::now();
+
+        let first = text(cache.read(&runtime, WEB, LogRange::Full, now).await.unwrap()).await;
+        let fresh = text(cache.read(&runtime, WEB, LogRange::Full, now + Duration::seconds(1)).await.unwrap()).await;
+        // The mock answers a delta with its whole log, which overlaps the cache completely
+        let stitched =
+            text(cache.read(&runtime, WEB, LogRange::Full, now + Duration::seconds(10)).await.unwrap()).await;
+        assert_eq!(first, fresh);
+        assert_eq!(first, stitched);
+
+        let tail = text(cache.read(&runtime, WEB, LogRange::Tail(2), now + Duration::seconds(11)).await.unwrap()).await;
+        let last_two: Vec<&str> = first.lines().skip(first.lines().count() - 2).collect();
+        assert_eq!(tail, spool(&last_two));
+
+        let stats = cache.stats().await;
+        assert_eq!((stats.misses, stats.hits, stats.delta_fetches), (1, 2, 1));
+        assert_eq!(stats.total_bytes, first.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn least_recently_read_logs_are_evicted() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = MockBoltClient::new();
+        let bytes = log_bytes(&runtime).await;
+        let cache = cache(&dir, bytes * 3 / 2).await;
+        let now = Utc::now();
+
+        cache.read(&runtime, WEB, LogRange::Full, now).await.unwrap();
+        assert_eq!(cache.stats().await.total_bytes, bytes);
+        cache.read(&runtime, DATABASE, LogRange::Full, now + Duration::seconds(1)).await.unwrap();
+
+        let stats = cache.stats().await;
+        assert_eq!((stats.entries, stats.evictions), (1, 1));
+        assert!(!cache.path(WEB).exists());
+        assert!(cache.path(DATABASE).exists());
+    }
+
+    #[tokio::test]
+    async fn unread_logs_expire_and_oversized_logs_are_not_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = MockBoltClient::new();
+        let cache = cache(&dir, log_bytes(&runtime).await).await;
+        let now = Utc::now();
+
+        cache.read(&runtime, WEB, LogRange::Full, now).await.unwrap();
+        cache.read(&runtime, DATABASE, LogRange::Full, now + Duration::seconds(3601)).await.unwrap();
+        let stats = cache.stats().await;
+        assert_eq!(stats.evictions, 1);
+        assert!(!cache.path(WEB).exists());
+
+        let small = self::cache(&dir, 100).await;
+        let log = small.read(&runtime, WEB, LogRange::Full, now).await.unwrap();
+        assert!(matches!(log, CachedLog::Text(_)));
+        assert_eq!(small.stats().await.entries, 0);
+    }
+}
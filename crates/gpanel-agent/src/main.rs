@@ -1,119 +1,327 @@
 use anyhow::Result;
 use axum::{
+    body::Body,
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::Json,
-    routing::{delete, get, post},
+    http::{header, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
+    routing::{delete, get, post, put},
     Router,
 };
 use gpanel_core::{
     GhostPanelConfig, RegistryConfig, RegistryManager,
-    ImageInfo, RepositoryList, TagList,
-    BoltClient, MockBoltClient, Container, CreateContainerRequest, ContainerFilter,
-    ContainerLogsRequest, ContainerStats
+    ImageInfo, ImageReference, DeleteImageError, TagImageError, ImageImportResult, ImportImageError, RepositoryList, TagList, TagSort, merge_image_details, rank_search_results, sort_tags,
+    Container, ContainerFilter, ContainerListResponse, ContainerOperationRequest, ContainerRuntime, CreateContainerRequest,
+    ContainerStats, ContainerStatus, ContainerTop, ExecRequest, ExecResponse,
+    ContainerBatchAction, ContainerBatchRequest, ContainerBatchResult,
+    ContainerPruneFilter, ContainerPruneReport, ContainerEvent, ContainerEventAction,
+    ContainerCommitRequest, ImageSummary, JobAccepted, JobKind, is_valid_repository, is_valid_tag,
+    is_valid_container_name, validate_create_request,
+    ContainerWaitResponse, WaitCondition, group_projects,
+    ContainerValidationResponse, PortConflict, GpuAllocation, find_gpu_conflict, find_port_conflicts,
+    ApiErrorResponse, AddRegistryRequest, ImageCopyRequest, ImageDeleteResponse, ImagePullRequest, ImageSearchRequest,
+    ImageSearchResponse, ImageSearchResult, ImageTagResponse, LatestStableTag, OperationResult, ProjectListResponse,
+    KillContainerRequest, RegistryConfigResponse, RegistryListResponse, RegistryTagRequest, RenameContainerRequest,
+    ValidationErrorDetails,
 };
+use clap::Parser;
+use futures::{Stream, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::Infallible;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tower::ServiceBuilder;
-use tower_http::cors::CorsLayer;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+use utoipa::IntoParams;
+
+use crate::auth::{RequireAdmin, RequireOperator};
+use crate::environments::Env;
+use crate::error::ApiError;
+use crate::repositories::{RepositoryLatestStable, RepositoryTag, RepositoryTagExport, RepositoryTags};
+
+mod audit;
+mod auth;
+mod autostart;
+mod backup;
+mod cors;
+mod drift;
+mod environments;
+mod error;
+mod events;
+mod files;
+mod gaming;
+mod gpus;
+mod host;
+mod image_cache;
+mod images;
+mod jobs;
+mod logs;
+mod metrics;
+mod networks;
+mod notifications;
+mod oidc;
+mod openapi;
+mod proton;
+mod registry_health;
+mod registry_stats;
+mod registry_webhooks;
+mod registry_store;
+mod reload;
+mod repositories;
+mod schedules;
+mod steam;
+mod store;
+mod system;
+mod terminal;
+#[cfg(test)]
+mod testing;
+mod tls;
+mod users;
+mod util;
+mod volumes;
+mod watchdog;
+mod webhooks;
+
+#[derive(Parser)]
+#[command(name = "gpanel-agent")]
+#[command(about = "GhostPanel management agent")]
+struct Args {
+    /// Config file; defaults to `GPANEL_CONFIG`, then `gpanel.toml` if present
+    #[arg(long, value_name = "PATH")]
+    config: Option<std::path::PathBuf>,
+    /// Port to serve the API on, overriding `agent_port`
+    #[arg(long)]
+    port: Option<u16>,
+    /// Bolt API URL, overriding `bolt_api_url`
+    #[arg(long)]
+    bolt_api_url: Option<String>,
+    /// Directory for agent state, overriding `data_dir`
+    #[arg(long)]
+    data_dir: Option<String>,
+    /// Use the mock runtime even when Bolt is reachable
+    #[arg(long)]
+    mock: bool,
+    /// With the mock runtime, start as if the host had just rebooted: every running
+    /// container is left exited, so the autostart pass has work to do
+    #[arg(long)]
+    simulate_reboot: bool,
+    /// Serve the API without requiring a token. For development only: anyone who can
+    /// reach the agent controls every container.
+    #[arg(long)]
+    no_auth: bool,
+    /// Print the hash to put in `auth.api_tokens` for a token, then exit
+    #[arg(long, value_name = "TOKEN")]
+    hash_token: Option<String>,
+    /// PEM certificate chain to serve HTTPS with, together with --key-path
+    #[arg(long)]
+    cert_path: Option<String>,
+    /// PEM private key (PKCS#8, RSA or EC) for --cert-path
+    #[arg(long)]
+    key_path: Option<String>,
+    /// With TLS enabled, redirect plain HTTP on this port to the TLS port
+    #[arg(long)]
+    redirect_port: Option<u16>,
+}
+
+impl Args {
+    /// Override the settings given as flags, which win over the file and environment
+    fn apply(&self, config: &mut GhostPanelConfig) {
+        if let Some(port) = self.port {
+            config.agent_port = port;
+        }
+        if let Some(url) = &self.bolt_api_url {
+            config.bolt_api_url = url.clone();
+        }
+        if let Some(data_dir) = &self.data_dir {
+            config.data_dir = data_dir.clone();
+        }
+        if self.cert_path.is_some() {
+            config.tls_cert_path = self.cert_path.clone();
+        }
+        if self.key_path.is_some() {
+            config.tls_key_path = self.key_path.clone();
+        }
+        if self.redirect_port.is_some() {
+            config.tls_redirect_port = self.redirect_port;
+        }
+    }
+}
 
 /// Application state shared across handlers
 #[derive(Clone)]
 pub struct AppState {
     pub config: GhostPanelConfig,
     pub registry_manager: Arc<RwLock<RegistryManager>>,
-    pub bolt_client: Arc<MockBoltClient>, // Use MockBoltClient for now
+    pub registry_health: Arc<registry_health::RegistryHealthMonitor>,
+    pub registry_stats: Arc<registry_stats::RegistryStatsCache>,
+    pub registry_store: Arc<registry_store::RegistryStore>,
+    pub environments: Arc<environments::Environments>,
+    pub store: store::Store,
+    pub events: events::EventBus,
+    pub webhooks: Arc<webhooks::WebhookRegistry>,
+    pub schedules: Arc<schedules::ScheduleRegistry>,
+    pub gaming_profiles: Arc<gaming::GamingProfileRegistry>,
+    pub proton: Arc<proton::ProtonManager>,
+    pub steam: Arc<steam::SteamCatalog>,
+    pub notifier: Arc<notifications::Notifier>,
+    pub logs: Arc<logs::LogCache>,
+    pub host_metrics: Arc<host::HostMetricsSampler>,
+    pub gpus: Arc<gpus::GpuInventory>,
+    pub auth: Arc<auth::Authenticator>,
+    pub audit: Arc<audit::AuditLog>,
+    pub jobs: Arc<jobs::JobManager>,
+    pub system_events: events::SystemEventBus,
+    pub config_reloader: Arc<reload::ConfigReloader>,
+    pub oidc: Arc<oidc::OidcClient>,
+    pub users: Arc<users::LocalUsers>,
 }
 
-/// Registry list response for API
-#[derive(Debug, Serialize, Deserialize)]
-pub struct RegistryListResponse {
-    pub registries: Vec<RegistryConfigResponse>,
+/// Query parameters for GET /api/v1/registries/:name/repositories. Without either, every
+/// repository is listed.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RepositoryListQuery {
+    /// Repositories per page; defaults to the registry's `page_size`
+    pub n: Option<u32>,
+    /// Start after this repository, the `next` of the previous page
+    pub last: Option<String>,
 }
 
-/// Registry configuration response (without credentials)
-#[derive(Debug, Serialize, Deserialize)]
-pub struct RegistryConfigResponse {
-    pub name: String,
-    pub url: String,
-    pub has_auth: bool,
-    pub insecure: bool,
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImageInfoQuery {
+    /// Platform of a multi-arch image as `os/architecture[/variant]`, `linux/amd64` if unset
+    pub platform: Option<String>,
 }
 
-/// Add registry request
-#[derive(Debug, Serialize, Deserialize)]
-pub struct AddRegistryRequest {
-    pub name: String,
-    pub url: String,
-    pub username: Option<String>,
-    pub password: Option<String>,
-    pub insecure: bool,
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImageExportQuery {
+    /// Platform to narrow a multi-arch image to, as `os/architecture[/variant]`; every
+    /// platform is exported if unset
+    pub platform: Option<String>,
 }
 
-/// Image search request
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ImageSearchRequest {
-    pub query: String,
-    pub registry: Option<String>,
+/// Query parameters for POST /api/v1/images/import, whose body is the archive
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImageImportQuery {
+    pub registry: String,
+    pub repository: String,
+    /// Tag to push the image as; defaults to its tag in the archive's `index.json`
+    pub tag: Option<String>,
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TagListQuery {
+    /// `semver` for highest versions first after `latest`, `alpha` by name, or `none`
+    /// (the default) for the registry's own order
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    pub sort: TagSort,
+}
+
+/// Images listed per repository in grouped search results
+const DEFAULT_SEARCH_TAGS: usize = 5;
+
 /// Query parameters for GET image search
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ImageSearchQuery {
     pub q: String,
     pub registry: Option<String>,
 }
 
-/// Image search response
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ImageSearchResponse {
-    pub images: Vec<ImageSearchResult>,
+/// Query parameters for GET /api/v1/containers; without any, every container is returned
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ContainerListQuery {
+    /// created, running, paused, restarting, exited or dead
+    pub status: Option<String>,
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub gaming: Option<bool>,
+    pub gpu: Option<bool>,
+    pub network: Option<String>,
+    /// `key=value` label matches, comma separated, e.g. `gpanel.project=minecraft`
+    pub label: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ImageSearchResult {
-    pub registry: String,
-    pub repository: String,
-    pub tag: String,
-    pub digest: String,
-    pub size: u64,
-    pub created: chrono::DateTime<chrono::Utc>,
-}
+impl ContainerListQuery {
+    /// The runtime filter for this query, or `None` when no filter parameter is set
+    fn filter(&self) -> Result<Option<ContainerFilter>, String> {
+        let status = self.status.as_deref().map(parse_status).transpose()?;
+        let labels = self.label.as_deref().map(parse_labels).transpose()?;
+        let filter = ContainerFilter {
+            status,
+            name_contains: self.name.clone(),
+            image_contains: self.image.clone(),
+            has_gaming_config: self.gaming,
+            has_gpu: self.gpu,
+            network: self.network.clone(),
+            labels,
+        };
 
-/// Image pull request
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ImagePullRequest {
-    pub registry: String,
-    pub repository: String,
-    pub tag: String,
+        let any = filter.status.is_some()
+            || filter.name_contains.is_some()
+            || filter.image_contains.is_some()
+            || filter.has_gaming_config.is_some()
+            || filter.has_gpu.is_some()
+            || filter.network.is_some()
+            || filter.labels.is_some();
+        Ok(any.then_some(filter))
+    }
 }
 
-/// Operation result response
-#[derive(Debug, Serialize, Deserialize)]
-pub struct OperationResult {
-    pub success: bool,
-    pub message: String,
+/// Parse `key=value[,key=value...]` label filters
+fn parse_labels(labels: &str) -> Result<HashMap<String, String>, String> {
+    labels
+        .split(',')
+        .map(|label| match label.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.trim().to_string())),
+            _ => Err(format!("Invalid label filter '{}': expected key=value", label)),
+        })
+        .collect()
 }
 
-/// Container list response
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ContainerListResponse {
-    pub containers: Vec<Container>,
+/// Status filter names; `exited` matches any exit code
+fn parse_status(status: &str) -> Result<ContainerStatus, String> {
+    match status.to_lowercase().as_str() {
+        "created" => Ok(ContainerStatus::Created),
+        "running" => Ok(ContainerStatus::Running),
+        "paused" => Ok(ContainerStatus::Paused),
+        "restarting" => Ok(ContainerStatus::Restarting),
+        "exited" => Ok(ContainerStatus::Exited { code: 0 }),
+        "dead" => Ok(ContainerStatus::Dead),
+        _ => Err(format!(
+            "Unknown status '{}': expected created, running, paused, restarting, exited or dead",
+            status
+        )),
+    }
 }
 
-/// Container operation request
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ContainerOperationRequest {
-    pub action: String,
-    pub timeout: Option<u32>,
-    pub force: Option<bool>,
-    pub remove_volumes: Option<bool>,
+/// Signal names accepted by the kill endpoint, besides plain signal numbers
+const KILL_SIGNALS: &[&str] = &["SIGTERM", "SIGKILL", "SIGHUP", "SIGINT", "SIGUSR1", "SIGUSR2"];
+
+fn valid_signal(signal: &str) -> bool {
+    KILL_SIGNALS.contains(&signal) || signal.parse::<u8>().is_ok_and(|n| (1..=64).contains(&n))
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args = Args::parse();
+
+    if let Some(token) = &args.hash_token {
+        println!("{}", auth::hash_token(token));
+        return Ok(());
+    }
+
     // Initialize tracing
     tracing_subscriber::fmt()
         .with_target(false)
@@ -122,121 +330,475 @@ async fn main() -> Result<()> {
 
     info!("Starting GhostPanel Agent...");
 
-    // Load configuration
-    let config = GhostPanelConfig::default();
+    // Load configuration from the file and environment, then the flags
+    let loaded = GhostPanelConfig::load(args.config.as_deref())?;
+    let mut config = loaded.clone();
+    args.apply(&mut config);
+    config.validate()?;
+    info!("Configuration: {}", config.redacted());
 
-    // Initialize registry manager with default registries
-    let mut registry_manager = RegistryManager::new();
+    // Fail before anything starts if TLS is configured but unusable
+    let tls = match (&config.tls_cert_path, &config.tls_key_path) {
+        (Some(cert_path), Some(key_path)) => {
+            Some((tls::load(cert_path, key_path).await?, cert_path.clone(), key_path.clone()))
+        }
+        _ => None,
+    };
+
+    let state = build_state(
+        &config,
+        loaded,
+        GhostPanelConfig::config_path(args.config.as_deref()),
+        args.mock,
+        args.simulate_reboot,
+        !args.no_auth,
+    )
+    .await;
+    spawn_background_tasks(&state);
+
+    match state.users.bootstrap().await {
+        Ok(Some(password)) => warn!(
+            "Created local user 'admin' with one-time password: {} (it will not be shown again)",
+            password
+        ),
+        Ok(None) => {}
+        Err(e) => error!("Failed to create the initial admin user: {}", e),
+    }
+
+    if args.no_auth {
+        warn!("Authentication is disabled (--no-auth); anyone who can reach the agent controls every container");
+    } else {
+        info!(
+            "API authentication enabled with local accounts, {} token(s) and {} OIDC provider(s)",
+            state.auth.tokens.read().map_or(0, |tokens| tokens.len()),
+            config.auth.oidc.len()
+        );
+    }
+    let app = app(state, !args.no_auth);
+
+    // Start the server
+    let bind_addr = format!("0.0.0.0:{}", config.agent_port);
+    match tls {
+        Some((tls_config, cert_path, key_path)) => {
+            tokio::spawn(tls::watch_certificate(tls_config.clone(), cert_path.clone(), key_path));
+
+            if let Some(redirect_port) = config.tls_redirect_port {
+                let redirect_addr = format!("0.0.0.0:{}", redirect_port);
+                let listener = tokio::net::TcpListener::bind(&redirect_addr).await?;
+                info!("Redirecting plain HTTP on {} to HTTPS", redirect_addr);
+                let redirect = tls::redirect_routes(config.agent_port);
+                tokio::spawn(async move {
+                    if let Err(e) = axum::serve(listener, redirect).await {
+                        error!("HTTP redirect listener failed: {}", e);
+                    }
+                });
+            }
+
+            info!("GhostPanel Agent listening on {} (HTTPS, certificate {})", bind_addr, cert_path);
+            axum_server::bind_rustls(bind_addr.parse()?, tls_config)
+                .serve(app.into_make_service())
+                .await?;
+        }
+        None => {
+            if config.tls_redirect_port.is_some() {
+                warn!("Ignoring the HTTP redirect port, which needs TLS to be enabled");
+            }
+            info!("GhostPanel Agent listening on {}", bind_addr);
+            let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
+            axum::serve(listener, app).await?;
+        }
+    }
 
-    // Add configured registries
-    for registry_config in &config.registries {
+    Ok(())
+}
+
+/// Everything the handlers share. Registries that cannot be reached are kept, to show up
+/// as unhealthy rather than missing.
+async fn build_state(
+    config: &GhostPanelConfig,
+    loaded: GhostPanelConfig,
+    config_path: std::path::PathBuf,
+    mock: bool,
+    simulate_reboot: bool,
+    auth_enabled: bool,
+) -> AppState {
+    let store = store::Store::new(&config.data_dir);
+    let registry_store = registry_store::RegistryStore::load(store.clone(), config.registry_secret.as_deref()).await;
+    let mut registry_manager = RegistryManager::with_image_cache(config.image_cache.clone());
+    registry_manager.set_proxy(config.proxy.clone());
+    if config.image_cache.persist {
+        image_cache::restore(&registry_manager, &store).await;
+    }
+    for registry_config in &registry_store.registries(&config.registries).await {
         match registry_manager.add_registry(registry_config.clone()).await {
             Ok(_) => info!("Added registry: {}", registry_config.name),
-            Err(e) => error!("Failed to add registry {}: {}", registry_config.name, e),
+            Err(e) => {
+                error!("Failed to add registry {}: {}", registry_config.name, e);
+                registry_manager.insert_registry(registry_config.clone());
+            }
         }
     }
 
-    // Initialize Bolt client (using mock for now)
-    let bolt_client = Arc::new(MockBoltClient::new());
+    let events = events::event_bus();
+    let environments =
+        Arc::new(environments::Environments::connect(config, mock, simulate_reboot, &store, &events).await);
 
-    let state = AppState {
+    AppState {
         config: config.clone(),
         registry_manager: Arc::new(RwLock::new(registry_manager)),
-        bolt_client,
-    };
+        registry_health: Arc::new(registry_health::RegistryHealthMonitor::new()),
+        registry_stats: Arc::new(registry_stats::RegistryStatsCache::new()),
+        registry_store: Arc::new(registry_store),
+        environments,
+        events,
+        webhooks: Arc::new(webhooks::WebhookRegistry::load(store.clone(), &config.webhooks).await),
+        schedules: Arc::new(schedules::ScheduleRegistry::load(store.clone(), chrono::Utc::now()).await),
+        gaming_profiles: Arc::new(gaming::GamingProfileRegistry::load(store.clone()).await),
+        proton: Arc::new(proton::ProtonManager::new(&config.proton)),
+        steam: Arc::new(steam::SteamCatalog::load(&config.steam, store.clone()).await),
+        notifier: Arc::new(notifications::Notifier::load(store.clone()).await),
+        logs: Arc::new(logs::LogCache::open(&config.data_dir, config.log_cache.clone()).await),
+        host_metrics: Arc::new(host::HostMetricsSampler::new(&config.bolt_data_dir)),
+        gpus: Arc::new(gpus::GpuInventory::new()),
+        auth: Arc::new(
+            auth::Authenticator::load(
+                auth_enabled,
+                auth::ApiTokens::load(&config.auth, std::env::var(auth::API_TOKENS_ENV).ok().as_deref()),
+                auth::SessionKeys::new(&config.auth),
+                store.clone(),
+            )
+            .await,
+        ),
+        audit: Arc::new(audit::AuditLog::new(&config.audit, &config.data_dir)),
+        jobs: Arc::new(jobs::JobManager::new(std::time::Duration::from_secs(config.job_ttl_secs))),
+        system_events: events::system_event_bus(),
+        config_reloader: Arc::new(reload::ConfigReloader::new(config_path, loaded)),
+        oidc: Arc::new(oidc::OidcClient::new(&config.auth.oidc)),
+        users: Arc::new(users::LocalUsers::load(store.clone()).await),
+        store,
+    }
+}
+
+/// Event monitoring, schedules, the watchdog, stats and autostart for each environment;
+/// webhooks, notifications, registry health checks and the config file watcher once
+fn spawn_background_tasks(state: &AppState) {
+    for env in state.environments.all() {
+        tokio::spawn(events::monitor_containers(env.clone(), std::time::Duration::from_secs(5)));
+        tokio::spawn(schedules::run_scheduler(state.schedules.clone(), env.clone(), state.audit.clone()));
+        tokio::spawn(watchdog::run_watchdog(env.clone(), env.subscribe()));
+        tokio::spawn(metrics::run_stats_recorder(env.clone()));
+        if state.config.autostart_on_boot {
+            tokio::spawn(autostart::run_autostart(env.clone()));
+        }
+    }
+    if !state.config.autostart_on_boot {
+        info!("Autostart on boot is disabled");
+    }
+
+    tokio::spawn(webhooks::run_dispatcher(state.webhooks.clone(), state.events.subscribe()));
+    // Alerts cover the default environment, the one the agent runs next to
+    tokio::spawn(notifications::run_notifier(
+        state.notifier.clone(),
+        state.environments.default_env().runtime,
+        state.config.clone(),
+        state.events.subscribe(),
+    ));
+    tokio::spawn(registry_health::run_health_checks(
+        state.registry_health.clone(),
+        state.registry_manager.clone(),
+    ));
+    if state.config.image_cache.persist {
+        tokio::spawn(image_cache::run_saver(state.registry_manager.clone(), state.store.clone()));
+    }
+
+    // Apply registry changes from the config file without a restart
+    tokio::spawn(reload::watch_config(state.clone()));
+}
+
+/// Every route, behind the token check (unless disabled), the audit log and CORS
+fn app(state: AppState, auth_enabled: bool) -> Router {
+    let auth = state.auth.clone();
+    let audit = state.audit.clone();
+    let cors = cors::layer(&state.config, auth_enabled);
 
-    // Build the router
     let app = Router::new()
-        // Container management endpoints
-        .route("/api/v1/containers", get(list_containers))
-        .route("/api/v1/containers", post(create_container))
-        .route("/api/v1/containers/:id", get(get_container))
-        .route("/api/v1/containers/:id", delete(delete_container))
-        .route("/api/v1/containers/:id/start", post(start_container))
-        .route("/api/v1/containers/:id/stop", post(stop_container))
-        .route("/api/v1/containers/:id/restart", post(restart_container))
-        .route("/api/v1/containers/:id/logs", get(get_container_logs))
-        .route("/api/v1/containers/:id/stats", get(get_container_stats))
-
-        // Registry management endpoints
-        .route("/api/v1/registries", get(list_registries))
-        .route("/api/v1/registries", post(add_registry))
-        .route("/api/v1/registries/:name", delete(remove_registry))
-
-        // Image operations
-        .route("/api/v1/registries/:name/repositories", get(list_repositories))
-        .route("/api/v1/registries/:name/repositories/:repo/tags", get(list_tags))
-        .route("/api/v1/registries/:name/repositories/:repo/tags/:tag", get(get_image_info))
-
-        // Image management
-        .route("/api/v1/images/search", get(search_images_get))
-        .route("/api/v1/images/search", post(search_images))
-        .route("/api/v1/images/pull", post(pull_image))
-
-        // Health check
-        .route("/health", get(health_check))
-        .route("/api/v1/health", get(health_check))
-
-        // Add state and middleware
-        .with_state(state)
+    // Container management endpoints
+    .route("/api/v1/containers", get(list_containers))
+    .route("/api/v1/containers", post(create_container))
+    .route("/api/v1/containers/batch", post(batch_containers))
+    .route("/api/v1/containers/validate", post(validate_container))
+    .route("/api/v1/containers/prune", post(prune_containers))
+    .route("/api/v1/containers/:id", get(get_container))
+    .route("/api/v1/containers/:id", delete(delete_container))
+    .route("/api/v1/containers/:id/start", post(start_container))
+    .route("/api/v1/containers/:id/stop", post(stop_container))
+    .route("/api/v1/containers/:id/restart", post(restart_container))
+    .route("/api/v1/containers/:id/pause", post(pause_container))
+    .route("/api/v1/containers/:id/unpause", post(unpause_container))
+    .route("/api/v1/containers/:id/kill", post(kill_container))
+    .route("/api/v1/containers/:id/exec", post(exec_container))
+    .route("/api/v1/containers/:id/rename", post(rename_container))
+    .route("/api/v1/containers/:id/logs", get(logs::get_container_logs))
+    .route("/api/v1/containers/:id/logs/stream", get(logs::stream_container_logs))
+    .route("/api/v1/containers/:id/stats", get(get_container_stats))
+    .route("/api/v1/containers/:id/stats/stream", get(stream_container_stats))
+    .route("/api/v1/containers/:id/top", get(top_container))
+    .route("/api/v1/containers/:id/wait", post(wait_container))
+    .route("/api/v1/containers/:id/terminal", get(terminal::container_terminal))
+    .route("/api/v1/containers/:id/files", get(files::download_container_file))
+    .route("/api/v1/containers/:id/files", put(files::upload_container_file))
+    .route("/api/v1/containers/:id/export", get(files::export_container))
+    .route("/api/v1/containers/:id/commit", post(commit_container))
+    .route("/api/v1/containers/:id/watchdog", get(watchdog::get_container_watchdog))
+
+    // Container event stream
+    .route("/api/v1/events", get(events::stream_events))
+
+    // Bolt hosts managed by this agent
+    .route("/api/v1/environments", get(environments::list_environments))
+
+    // Projects (containers grouped by label)
+    .route("/api/v1/projects", get(list_projects))
+
+    // Registry management endpoints
+    .route("/api/v1/registries", get(list_registries))
+    .route("/api/v1/registries", post(add_registry))
+    .route("/api/v1/registries/:name", put(update_registry).delete(remove_registry))
+    .route("/api/v1/registries/:name/test", post(registry_health::test_registry))
+    .route("/api/v1/registries/:name/stats", get(registry_stats::registry_stats))
+    .route("/api/v1/registries/:name/drift/info", get(drift::drift_info))
+    .route("/api/v1/registries/:name/drift/gc", get(drift::drift_gc_status).post(drift::trigger_drift_gc))
+    .route("/api/v1/registries/:name/drift/quota", get(drift::drift_quota))
+
+    // Image operations
+    .route("/api/v1/registries/:name/repositories", get(list_repositories))
+    // Repository names contain `/`, so these routes are told apart by `repositories`
+    .route(
+        "/api/v1/registries/:name/repositories/*path",
+        get(repositories::get_repository_route)
+            .post(repositories::post_repository_route)
+            .delete(repositories::delete_repository_route),
+    )
+
+    // Image management
+    .route("/api/v1/images", get(images::list_local_images))
+    .route("/api/v1/images/:id", delete(images::remove_local_image))
+    .route("/api/v1/images/:id/tag", post(images::tag_local_image))
+    .route("/api/v1/images/search", get(search_images_get))
+    .route("/api/v1/images/search", post(search_images))
+    .route("/api/v1/images/pull", post(pull_image))
+    .route("/api/v1/images/copy", post(copy_image))
+    .route("/api/v1/images/import", post(import_image))
+    .route("/api/v1/images/prune", post(images::prune_images))
+
+    // Network management
+    .route("/api/v1/networks", get(networks::list_networks))
+    .route("/api/v1/networks", post(networks::create_network))
+    .route("/api/v1/networks/:id", get(networks::get_network))
+    .route("/api/v1/networks/:id", delete(networks::delete_network))
+    .route("/api/v1/networks/:id/connect", post(networks::connect_network))
+    .route("/api/v1/networks/:id/disconnect", post(networks::disconnect_network))
+
+    // Volume management
+    .route("/api/v1/volumes", get(volumes::list_volumes))
+    .route("/api/v1/volumes", post(volumes::create_volume))
+    .route("/api/v1/volumes/prune", post(volumes::prune_volumes))
+    .route("/api/v1/volumes/:name", get(volumes::get_volume))
+    .route("/api/v1/volumes/:name", delete(volumes::delete_volume))
+    .route("/api/v1/volumes/:name/browse", get(volumes::browse_volume))
+    .route("/api/v1/volumes/:name/file", get(volumes::download_volume_file))
+
+    // System
+    .route("/api/v1/system/info", get(system::system_info))
+    .route("/api/v1/system/df", get(system::disk_usage))
+    .route("/api/v1/system/metrics", get(host::host_metrics))
+    .route("/api/v1/system/gpus", get(gpus::list_gpus))
+    .route("/api/v1/system/gpus/:id/allocations", get(gpus::list_allocations))
+    .route("/api/v1/system/backup", get(backup::download_backup))
+    .route("/api/v1/system/restore", post(backup::restore_backup))
+    .route("/api/v1/system/autostart-report", get(autostart::autostart_report))
+    .route("/api/v1/system/reload-config", post(reload::reload_config))
+
+    // Background jobs
+    .route("/api/v1/jobs", get(jobs::list_jobs))
+    .route("/api/v1/jobs/:id", get(jobs::get_job))
+    .route("/api/v1/jobs/:id", delete(jobs::cancel_job))
+
+    // Metrics
+    .route("/api/v1/metrics/summary", get(metrics::metrics_summary))
+
+    // Notifications
+    .route("/api/v1/notifications/channels", get(notifications::list_channels))
+    .route("/api/v1/notifications/channels", post(notifications::create_channel))
+    .route("/api/v1/notifications/channels/:id", delete(notifications::delete_channel))
+    .route("/api/v1/notifications/channels/:id/test", post(notifications::test_channel))
+    .route("/api/v1/notifications/rules", get(notifications::list_rules))
+    .route("/api/v1/notifications/rules", post(notifications::create_rule))
+    .route("/api/v1/notifications/rules/:id", put(notifications::update_rule))
+    .route("/api/v1/notifications/rules/:id", delete(notifications::delete_rule))
+
+    // Schedules
+    .route("/api/v1/schedules", get(schedules::list_schedules))
+    .route("/api/v1/schedules", post(schedules::create_schedule))
+    .route("/api/v1/schedules/:id", get(schedules::get_schedule))
+    .route("/api/v1/schedules/:id", put(schedules::update_schedule))
+    .route("/api/v1/schedules/:id", delete(schedules::delete_schedule))
+    .route("/api/v1/gaming/profiles", get(gaming::list_profiles))
+    .route("/api/v1/gaming/profiles", post(gaming::create_profile))
+    .route("/api/v1/gaming/profiles/:name", get(gaming::get_profile))
+    .route("/api/v1/gaming/profiles/:name", put(gaming::update_profile))
+    .route("/api/v1/gaming/profiles/:name", delete(gaming::delete_profile))
+    .route("/api/v1/gaming/proton/versions", get(proton::list_versions))
+    .route("/api/v1/gaming/proton/install", post(proton::install_version))
+    .route("/api/v1/gaming/steam/apps", get(steam::search_apps))
+    .route("/api/v1/gaming/steam/apps/:appid", get(steam::get_app))
+
+    // Webhooks
+    .route("/api/v1/webhooks", get(webhooks::list_webhooks))
+    .route("/api/v1/webhooks", post(webhooks::create_webhook))
+    .route("/api/v1/webhooks/:id", delete(webhooks::delete_webhook))
+    .route("/api/v1/webhooks/:id/test", post(webhooks::test_webhook))
+    .route("/api/v1/webhooks/:id/failures", get(webhooks::list_webhook_failures))
+    .route("/api/v1/webhooks/registry", post(registry_webhooks::receive_registry_webhook))
+
+    // Health check
+    .route("/health", get(health_check))
+    .route("/api/v1/health", get(health_check))
+
+    .route("/api/v1/auth/whoami", get(auth::whoami))
+    .route("/api/v1/auth/login", post(auth::login))
+    .route("/api/v1/auth/logout", post(auth::logout))
+    .route("/api/v1/auth/refresh", post(auth::refresh))
+    .route("/api/v1/auth/me", get(auth::me))
+    .route("/api/v1/audit", get(audit::list_audit_events))
+    .route("/api/auth/oidc/callback", post(oidc::oidc_callback))
+
+    // API description and Swagger UI
+    .merge(openapi::routes())
+
+        .with_state(state);
+
+    // Token check sits inside CORS so preflight requests are answered without one
+    let app = if auth_enabled {
+        app.layer(axum::middleware::from_fn_with_state(auth, auth::require_token))
+    } else {
+        app
+    };
+
+    // Outside the token check, so refused requests are recorded too
+    app.layer(axum::middleware::from_fn_with_state(audit, audit::record_mutations))
         .layer(
             ServiceBuilder::new()
-                .layer(CorsLayer::permissive())
+                .layer(cors)
+                .layer(axum::middleware::map_response(error::wrap_rejections))
                 .into_inner()
-        );
-
-    // Start the server
-    let bind_addr = format!("0.0.0.0:{}", config.agent_port);
-    info!("GhostPanel Agent listening on {}", bind_addr);
+        )
+}
 
-    let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
-    axum::serve(listener, app).await?;
+/// How long the health check waits for Bolt before reporting it unreachable
+const HEALTH_PING_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
 
-    Ok(())
-}
+/// Health check endpoint. The agent answering means it is alive; `bolt_reachable`
+/// separately reports whether the runtime of the default environment responds.
+#[utoipa::path(
+    get,
+    path = "/api/v1/health",
+    tag = "system",
+    security(()),
+    responses((status = 200, description = "Agent is up; `bolt_reachable` reports the runtime", body = Object)),
+)]
+async fn health_check(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let bolt_reachable = matches!(
+        tokio::time::timeout(HEALTH_PING_TIMEOUT, state.environments.default_env().runtime.ping()).await,
+        Ok(Ok(true))
+    );
 
-/// Health check endpoint
-async fn health_check() -> Json<serde_json::Value> {
     Json(serde_json::json!({
         "status": "healthy",
         "service": "gpanel-agent",
+        "bolt_reachable": bolt_reachable,
         "timestamp": chrono::Utc::now()
     }))
 }
 
 /// List all configured registries
-async fn list_registries(State(state): State<AppState>) -> Result<Json<RegistryListResponse>, StatusCode> {
-    let registries: Vec<RegistryConfigResponse> = state.config.registries
-        .iter()
-        .map(|r| RegistryConfigResponse {
-            name: r.name.clone(),
-            url: r.url.clone(),
-            has_auth: r.username.is_some() && r.password.is_some(),
-            insecure: r.insecure,
-        })
-        .collect();
+#[utoipa::path(
+    get,
+    path = "/api/v1/registries",
+    tag = "registries",
+    responses((status = 200, description = "Configured registries, without credentials", body = RegistryListResponse)),
+)]
+async fn list_registries(State(state): State<AppState>) -> Json<RegistryListResponse> {
+    // From the manager rather than the startup config, so added and reloaded ones show up
+    let mut configs = state.registry_manager.read().await.registry_configs();
+    configs.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut registries = Vec::with_capacity(configs.len());
+    for r in &configs {
+        registries.push(registry_response(&state, r).await);
+    }
+
+    Json(RegistryListResponse { registries })
+}
 
-    Ok(Json(RegistryListResponse { registries }))
+/// A registry's settings without its password, with its last health check
+async fn registry_response(state: &AppState, config: &RegistryConfig) -> RegistryConfigResponse {
+    let health = state.registry_health.get(&config.name).await;
+    RegistryConfigResponse {
+        name: config.name.clone(),
+        url: config.url.clone(),
+        username: config.username.clone(),
+        has_auth: config.username.is_some() && config.password.is_some(),
+        insecure: config.insecure,
+        page_size: config.page_size,
+        max_list_entries: config.max_list_entries,
+        search_url: config.search_url.clone(),
+        ca_cert_path: config.ca_cert_path.clone(),
+        status: health.as_ref().map(|health| health.status),
+        last_checked: health.as_ref().map(|health| health.checked_at),
+        last_error: health.and_then(|health| health.message),
+    }
 }
 
 /// Add a new registry
+#[utoipa::path(
+    post,
+    path = "/api/v1/registries",
+    tag = "registries",
+    request_body = AddRegistryRequest,
+    responses(
+        (status = 200, description = "Registry added", body = OperationResult),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+        (status = 502, description = "Registry could not be reached", body = ApiErrorResponse),
+    ),
+)]
 async fn add_registry(
+    _: RequireAdmin,
     State(state): State<AppState>,
     Json(request): Json<AddRegistryRequest>,
-) -> Result<Json<OperationResult>, StatusCode> {
+) -> Result<Json<OperationResult>, ApiError> {
     let registry_config = RegistryConfig {
         name: request.name.clone(),
         url: request.url,
         username: request.username,
         password: request.password,
         insecure: request.insecure,
+        page_size: request.page_size,
+        max_list_entries: request.max_list_entries,
+        search_url: request.search_url,
+        ca_cert_path: request.ca_cert_path,
     };
 
     let mut manager = state.registry_manager.write().await;
 
-    match manager.add_registry(registry_config).await {
+    match manager.add_registry(registry_config.clone()).await {
         Ok(_) => {
             info!("Successfully added registry: {}", request.name);
+            state.registry_store.save(&registry_config).await.map_err(registry_not_saved)?;
+            if let Some(client) = manager.get_registry(&request.name).cloned() {
+                let monitor = state.registry_health.clone();
+                let name = request.name.clone();
+                tokio::spawn(async move { monitor.check(&name, &client).await });
+            }
             Ok(Json(OperationResult {
                 success: true,
                 message: format!("Registry '{}' added successfully", request.name),
@@ -244,305 +806,1024 @@ async fn add_registry(
         }
         Err(e) => {
             error!("Failed to add registry {}: {}", request.name, e);
-            Ok(Json(OperationResult {
-                success: false,
-                message: format!("Failed to add registry: {}", e),
-            }))
+            Err(ApiError::from_status(StatusCode::BAD_GATEWAY, format!("Failed to add registry: {}", e)))
         }
     }
 }
 
+/// Change a registry's settings, or rename it. The registry is contacted with the new
+/// settings before they replace the old ones.
+#[utoipa::path(
+    put,
+    path = "/api/v1/registries/{name}",
+    tag = "registries",
+    params(("name" = String, Path, description = "Registry name")),
+    request_body(content = AddRegistryRequest, description = "New settings; a missing password keeps the current one"),
+    responses(
+        (status = 200, description = "Registry updated", body = RegistryConfigResponse),
+        (status = 400, description = "URL is not an http or https URL", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+        (status = 404, description = "No such registry", body = ApiErrorResponse),
+        (status = 409, description = "Another registry has the new name", body = ApiErrorResponse),
+        (status = 502, description = "Registry could not be reached with the new settings", body = ApiErrorResponse),
+    ),
+)]
+async fn update_registry(
+    _: RequireAdmin,
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(request): Json<AddRegistryRequest>,
+) -> Result<Json<RegistryConfigResponse>, ApiError> {
+    if !reqwest::Url::parse(&request.url).is_ok_and(|url| matches!(url.scheme(), "http" | "https")) {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "INVALID_REGISTRY_URL",
+            format!("'{}' is not an http or https URL", request.url),
+        ));
+    }
+
+    let mut manager = state.registry_manager.write().await;
+    let Some(current) = manager.get_registry(&name).map(|client| client.config().clone()) else {
+        return Err(ApiError::not_found("REGISTRY", format!("Registry '{}' not found", name)));
+    };
+    if request.name != name && manager.get_registry(&request.name).is_some() {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "REGISTRY_EXISTS",
+            format!("Registry '{}' already exists", request.name),
+        ));
+    }
+
+    let registry_config = RegistryConfig {
+        name: request.name,
+        url: request.url,
+        username: request.username,
+        password: request.password.or(current.password),
+        insecure: request.insecure,
+        page_size: request.page_size,
+        max_list_entries: request.max_list_entries,
+        search_url: request.search_url,
+        ca_cert_path: request.ca_cert_path,
+    };
+    if let Err(e) = manager.update_registry(&name, registry_config.clone()).await {
+        error!("Failed to update registry {}: {}", name, e);
+        return Err(ApiError::from_status(StatusCode::BAD_GATEWAY, format!("Failed to update registry: {}", e)));
+    }
+    info!("Successfully updated registry: {}", name);
+    state.registry_stats.forget(&name);
+    if registry_config.name != name {
+        state.registry_store.remove(&name).await.map_err(registry_not_saved)?;
+    }
+    state.registry_store.save(&registry_config).await.map_err(registry_not_saved)?;
+    let client = manager.get_registry(&registry_config.name).cloned();
+    drop(manager);
+
+    if let Some(client) = client {
+        state.registry_health.check(&registry_config.name, &client).await;
+    }
+    Ok(Json(registry_response(&state, &registry_config).await))
+}
+
+/// The change was made but will be lost when the agent restarts
+fn registry_not_saved(e: anyhow::Error) -> ApiError {
+    error!("Failed to save registries: {:#}", e);
+    ApiError::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "REGISTRY_NOT_SAVED",
+        format!("The change was applied but could not be saved, so a restart will undo it: {:#}", e),
+    )
+}
+
 /// Remove a registry
+#[utoipa::path(
+    delete,
+    path = "/api/v1/registries/{name}",
+    tag = "registries",
+    params(("name" = String, Path, description = "Registry name")),
+    responses(
+        (status = 200, description = "Registry removed", body = OperationResult),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+        (status = 404, description = "No such registry", body = ApiErrorResponse),
+    ),
+)]
 async fn remove_registry(
+    _: RequireAdmin,
     State(state): State<AppState>,
     Path(name): Path<String>,
-) -> Result<Json<OperationResult>, StatusCode> {
+) -> Result<Json<OperationResult>, ApiError> {
     let mut manager = state.registry_manager.write().await;
 
     if manager.remove_registry(&name) {
         info!("Successfully removed registry: {}", name);
+        state.registry_stats.forget(&name);
+        state.registry_store.remove(&name).await.map_err(registry_not_saved)?;
         Ok(Json(OperationResult {
             success: true,
             message: format!("Registry '{}' removed successfully", name),
         }))
     } else {
-        Ok(Json(OperationResult {
-            success: false,
-            message: format!("Registry '{}' not found", name),
-        }))
+        Err(ApiError::not_found("REGISTRY", format!("Registry '{}' not found", name)))
     }
 }
 
-/// List repositories in a specific registry
+/// List repositories in a specific registry, all of them or one page
+#[utoipa::path(
+    get,
+    path = "/api/v1/registries/{name}/repositories",
+    tag = "registries",
+    params(("name" = String, Path, description = "Registry name"), RepositoryListQuery),
+    responses(
+        (status = 200, description = "Repositories in the registry", body = RepositoryList),
+        (status = 400, description = "Page size of 0", body = ApiErrorResponse),
+        (status = 404, description = "No such registry", body = ApiErrorResponse),
+        (status = 502, description = "Registry request failed", body = ApiErrorResponse),
+    ),
+)]
 async fn list_repositories(
     State(state): State<AppState>,
     Path(name): Path<String>,
-) -> Result<Json<RepositoryList>, StatusCode> {
+    Query(query): Query<RepositoryListQuery>,
+) -> Result<Json<RepositoryList>, ApiError> {
+    if query.n == Some(0) {
+        return Err(ApiError::from_status(StatusCode::BAD_REQUEST, "n must be at least 1"));
+    }
     let manager = state.registry_manager.read().await;
 
-    if let Some(client) = manager.get_registry(&name) {
-        match client.list_repositories().await {
-            Ok(repositories) => Ok(Json(RepositoryList { repositories })),
-            Err(e) => {
-                error!("Failed to list repositories for {}: {}", name, e);
-                Err(StatusCode::INTERNAL_SERVER_ERROR)
-            }
-        }
+    let Some(client) = manager.get_registry(&name) else {
+        return Err(ApiError::not_found("REGISTRY", format!("Registry '{}' not found", name)));
+    };
+
+    let listing = if query.n.is_some() || query.last.is_some() {
+        client.list_repositories_page(query.n, query.last.as_deref()).await
     } else {
-        error!("Registry not found: {}", name);
-        Err(StatusCode::NOT_FOUND)
+        client
+            .list_repositories()
+            .await
+            .map(|repositories| RepositoryList { repositories, next: None })
+    };
+    match listing {
+        Ok(list) => Ok(Json(list)),
+        Err(e) => {
+            error!("Failed to list repositories for {}: {}", name, e);
+            Err(ApiError::from_status(StatusCode::BAD_GATEWAY, format!("Failed to list repositories: {}", e)))
+        }
     }
 }
 
 /// List tags for a repository
+#[utoipa::path(
+    get,
+    path = "/api/v1/registries/{name}/repositories/{repo}/tags",
+    tag = "registries",
+    params(
+        ("name" = String, Path, description = "Registry name"),
+        ("repo" = String, Path, description = "Repository name, which may contain `/`"),
+        TagListQuery,
+    ),
+    responses(
+        (status = 200, description = "Tags of the repository", body = TagList),
+        (status = 404, description = "No such registry", body = ApiErrorResponse),
+        (status = 502, description = "Registry request failed", body = ApiErrorResponse),
+    ),
+)]
 async fn list_tags(
     State(state): State<AppState>,
-    Path((name, repo)): Path<(String, String)>,
-) -> Result<Json<TagList>, StatusCode> {
+    RepositoryTags { registry: name, repository: repo }: RepositoryTags,
+    Query(query): Query<TagListQuery>,
+) -> Result<Json<TagList>, ApiError> {
+    let mut tags = repository_tags(&state, &name, &repo).await?;
+    sort_tags(&mut tags, query.sort);
+    Ok(Json(TagList { name: repo, tags }))
+}
+
+/// Every tag of `repo` on registry `name`, in the registry's order
+async fn repository_tags(state: &AppState, name: &str, repo: &str) -> Result<Vec<String>, ApiError> {
     let manager = state.registry_manager.read().await;
 
-    if let Some(client) = manager.get_registry(&name) {
-        match client.list_tags(&repo).await {
-            Ok(tags) => Ok(Json(TagList { name: repo, tags })),
-            Err(e) => {
-                error!("Failed to list tags for {}/{}: {}", name, repo, e);
-                Err(StatusCode::INTERNAL_SERVER_ERROR)
-            }
-        }
-    } else {
-        error!("Registry not found: {}", name);
-        Err(StatusCode::NOT_FOUND)
+    let Some(client) = manager.get_registry(name) else {
+        return Err(ApiError::not_found("REGISTRY", format!("Registry '{}' not found", name)));
+    };
+
+    client.list_tags(repo).await.map_err(|e| {
+        error!("Failed to list tags for {}/{}: {}", name, repo, e);
+        ApiError::from_status(StatusCode::BAD_GATEWAY, format!("Failed to list tags: {}", e))
+    })
+}
+
+/// The highest version tag of a repository that is not a prerelease, for automation that
+/// follows releases
+#[utoipa::path(
+    get,
+    path = "/api/v1/registries/{name}/repositories/{repo}/latest-stable",
+    tag = "registries",
+    params(
+        ("name" = String, Path, description = "Registry name"),
+        ("repo" = String, Path, description = "Repository name, which may contain `/`"),
+    ),
+    responses(
+        (status = 200, description = "Highest release tag, such as `1.25.3` from `1.25.3`, `1.25.3-alpine` and `1.26.0-rc.1`", body = LatestStableTag),
+        (status = 404, description = "No such registry, or no tag is a release version", body = ApiErrorResponse),
+        (status = 502, description = "Registry request failed", body = ApiErrorResponse),
+    ),
+)]
+async fn latest_stable_tag(
+    State(state): State<AppState>,
+    RepositoryLatestStable { registry: name, repository: repo }: RepositoryLatestStable,
+) -> Result<Json<LatestStableTag>, ApiError> {
+    let tags = repository_tags(&state, &name, &repo).await?;
+    match gpanel_core::latest_stable_tag(&tags) {
+        Some(tag) => Ok(Json(LatestStableTag { tag: tag.clone(), repository: repo })),
+        None => Err(ApiError::not_found("TAG", format!("{} has no release version tags", repo))),
     }
 }
 
 /// Get detailed image information
+#[utoipa::path(
+    get,
+    path = "/api/v1/registries/{name}/repositories/{repo}/tags/{tag}",
+    tag = "registries",
+    params(
+        ("name" = String, Path, description = "Registry name"),
+        ("repo" = String, Path, description = "Repository name, which may contain `/`"),
+        ("tag" = String, Path, description = "Image tag"),
+        ImageInfoQuery,
+    ),
+    responses(
+        (status = 200, description = "Manifest details of the image", body = ImageInfo),
+        (status = 404, description = "No such registry", body = ApiErrorResponse),
+        (status = 502, description = "Registry request failed, or the image is not built for the platform", body = ApiErrorResponse),
+    ),
+)]
 async fn get_image_info(
     State(state): State<AppState>,
-    Path((name, repo, tag)): Path<(String, String, String)>,
-) -> Result<Json<ImageInfo>, StatusCode> {
+    RepositoryTag { registry: name, repository: repo, tag }: RepositoryTag,
+    Query(query): Query<ImageInfoQuery>,
+) -> Result<Json<ImageInfo>, ApiError> {
     let manager = state.registry_manager.read().await;
 
-    if let Some(client) = manager.get_registry(&name) {
-        match client.get_image_info(&repo, &tag).await {
-            Ok(image_info) => Ok(Json(image_info)),
-            Err(e) => {
-                error!("Failed to get image info for {}/{}:{}: {}", name, repo, tag, e);
-                Err(StatusCode::INTERNAL_SERVER_ERROR)
-            }
+    if manager.get_registry(&name).is_none() {
+        return Err(ApiError::not_found("REGISTRY", format!("Registry '{}' not found", name)));
+    }
+
+    match manager.image_info(&name, &repo, &tag, query.platform.as_deref()).await {
+        Ok(image_info) => Ok(Json(image_info)),
+        Err(e) => {
+            error!("Failed to get image info for {}/{}:{}: {}", name, repo, tag, e);
+            Err(ApiError::from_status(StatusCode::BAD_GATEWAY, format!("Failed to get image info: {}", e)))
+        }
+    }
+}
+
+/// Tag an image in a registry under a new tag, such as promoting `staging` to `prod`. Only
+/// the manifest is sent again, so no layers move.
+#[utoipa::path(
+    post,
+    path = "/api/v1/registries/{name}/repositories/{repo}/tags",
+    tag = "registries",
+    params(
+        ("name" = String, Path, description = "Registry name"),
+        ("repo" = String, Path, description = "Repository name, which may contain `/`"),
+    ),
+    request_body = RegistryTagRequest,
+    responses(
+        (status = 201, description = "Tag created, naming the same manifest as the source", body = ImageTagResponse),
+        (status = 400, description = "The new tag is not a valid tag", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such registry or source image", body = ApiErrorResponse),
+        (status = 502, description = "Registry request failed", body = ApiErrorResponse),
+    ),
+)]
+async fn tag_registry_image(
+    _: RequireOperator,
+    State(state): State<AppState>,
+    RepositoryTags { registry: name, repository: repo }: RepositoryTags,
+    Json(request): Json<RegistryTagRequest>,
+) -> Result<(StatusCode, Json<ImageTagResponse>), ApiError> {
+    let manager = state.registry_manager.read().await;
+
+    let Some(client) = manager.get_registry(&name) else {
+        return Err(ApiError::not_found("REGISTRY", format!("Registry '{}' not found", name)));
+    };
+
+    match client.tag_image(&repo, &request.source, &request.target).await {
+        Ok(digest) => Ok((
+            StatusCode::CREATED,
+            Json(ImageTagResponse {
+                repository: repo,
+                tag: request.target,
+                digest,
+            }),
+        )),
+        Err(TagImageError::NotFound(message)) => Err(ApiError::not_found("IMAGE", message)),
+        Err(TagImageError::InvalidTag(message)) => Err(ApiError::from_status(StatusCode::BAD_REQUEST, message)),
+        Err(TagImageError::Failed(e)) => {
+            error!("Failed to tag {}/{}:{} as {}: {}", name, repo, request.source, request.target, e);
+            Err(ApiError::runtime(&e, format!("Failed to tag image: {}", e)))
+        }
+    }
+}
+
+/// Download an image as a tar of an OCI image layout, for moving it to a host without
+/// access to the registry. Blobs are streamed from the registry as the archive is sent.
+#[utoipa::path(
+    get,
+    path = "/api/v1/registries/{name}/repositories/{repo}/tags/{tag}/export",
+    tag = "registries",
+    params(
+        ("name" = String, Path, description = "Registry name"),
+        ("repo" = String, Path, description = "Repository name, which may contain `/`"),
+        ("tag" = String, Path, description = "Image tag or digest"),
+        ImageExportQuery,
+    ),
+    responses(
+        (status = 200, description = "OCI layout tar of the image", content_type = "application/x-tar", body = Vec<u8>),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such registry or image", body = ApiErrorResponse),
+        (status = 502, description = "Registry request failed, or the image is not built for the platform", body = ApiErrorResponse),
+    ),
+)]
+async fn export_registry_image(
+    _: RequireOperator,
+    State(state): State<AppState>,
+    RepositoryTagExport { registry: name, repository: repo, tag }: RepositoryTagExport,
+    Query(query): Query<ImageExportQuery>,
+) -> Result<Response, ApiError> {
+    // The archive outlives the request's hold on the registry list
+    let client = state
+        .registry_manager
+        .read()
+        .await
+        .get_registry(&name)
+        .cloned()
+        .ok_or_else(|| ApiError::not_found("REGISTRY", format!("Registry '{}' not found", name)))?;
+
+    let failed = |e: gpanel_core::Error| {
+        error!("Failed to export {}/{}:{}: {}", name, repo, tag, e);
+        ApiError::runtime(&e, format!("Failed to export image: {}", e))
+    };
+    if client.manifest_digest(&repo, &tag).await.map_err(failed)?.is_none() {
+        return Err(ApiError::not_found("IMAGE", format!("{}:{} does not exist", repo, tag)));
+    }
+    let archive = client.export_image(&repo, &tag, query.platform.as_deref()).await.map_err(failed)?;
+
+    info!("Exporting {}/{}:{}", name, repo, tag);
+    let filename = format!("{}_{}.tar", repo.replace('/', "_"), tag.replace(':', "_"));
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-tar".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        Body::from_stream(archive),
+    )
+        .into_response())
+}
+
+/// Delete an image from a registry by its tag
+#[utoipa::path(
+    delete,
+    path = "/api/v1/registries/{name}/repositories/{repo}/tags/{tag}",
+    tag = "registries",
+    params(
+        ("name" = String, Path, description = "Registry name"),
+        ("repo" = String, Path, description = "Repository name, which may contain `/`"),
+        ("tag" = String, Path, description = "Image tag"),
+    ),
+    responses(
+        (status = 200, description = "Image deleted, with every tag naming the same digest", body = ImageDeleteResponse),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+        (status = 404, description = "No such registry or image", body = ApiErrorResponse),
+        (status = 409, description = "Deleting is disabled in the registry's configuration", body = ApiErrorResponse),
+        (status = 501, description = "The registry does not delete images through the registry API", body = ApiErrorResponse),
+        (status = 502, description = "Registry request failed", body = ApiErrorResponse),
+    ),
+)]
+async fn delete_registry_image(
+    _: RequireAdmin,
+    State(state): State<AppState>,
+    RepositoryTag { registry: name, repository: repo, tag }: RepositoryTag,
+) -> Result<Json<ImageDeleteResponse>, ApiError> {
+    let manager = state.registry_manager.read().await;
+
+    let Some(client) = manager.get_registry(&name) else {
+        return Err(ApiError::not_found("REGISTRY", format!("Registry '{}' not found", name)));
+    };
+
+    match client.delete_image(&repo, &tag).await {
+        Ok(digest) => {
+            info!("Deleted {}/{}:{} ({})", name, repo, tag, digest);
+            Ok(Json(ImageDeleteResponse {
+                repository: repo,
+                tag,
+                digest,
+            }))
+        }
+        Err(DeleteImageError::NotFound(message)) => Err(ApiError::not_found("IMAGE", message)),
+        Err(DeleteImageError::Disabled(message)) => {
+            Err(ApiError::new(StatusCode::CONFLICT, "REGISTRY_DELETE_DISABLED", message))
+        }
+        Err(DeleteImageError::Unsupported(message)) => {
+            Err(ApiError::new(StatusCode::NOT_IMPLEMENTED, "REGISTRY_DELETE_UNSUPPORTED", message))
+        }
+        Err(DeleteImageError::Failed(e)) => {
+            error!("Failed to delete {}/{}:{}: {}", name, repo, tag, e);
+            Err(ApiError::runtime(&e, format!("Failed to delete image: {}", e)))
         }
-    } else {
-        error!("Registry not found: {}", name);
-        Err(StatusCode::NOT_FOUND)
     }
 }
 
 /// Search for images across registries
+#[utoipa::path(
+    post,
+    path = "/api/v1/images/search",
+    tag = "images",
+    request_body = ImageSearchRequest,
+    responses(
+        (status = 200, description = "Matching images, flat and grouped by repository", body = ImageSearchResponse),
+        (status = 404, description = "No such registry", body = ApiErrorResponse),
+    ),
+)]
 async fn search_images(
     State(state): State<AppState>,
     Json(request): Json<ImageSearchRequest>,
-) -> Result<Json<ImageSearchResponse>, StatusCode> {
+) -> Result<Json<ImageSearchResponse>, ApiError> {
     let manager = state.registry_manager.read().await;
+    if let Some(registry_name) = &request.registry
+        && manager.get_registry(registry_name).is_none()
+    {
+        return Err(ApiError::not_found("REGISTRY", format!("Registry '{}' not found", registry_name)));
+    }
 
-    let results = if let Some(registry_name) = &request.registry {
-        // Search in specific registry
-        if let Some(client) = manager.get_registry(registry_name) {
-            if let Ok(repositories) = client.list_repositories().await {
-                let mut images = Vec::new();
-                for repo in repositories {
-                    if repo.contains(&request.query) {
-                        if let Ok(tags) = client.list_tags(&repo).await {
-                            for tag in tags {
-                                if let Ok(image_info) = client.get_image_info(&repo, &tag).await {
-                                    images.push(ImageSearchResult {
-                                        registry: registry_name.clone(),
-                                        repository: image_info.repository,
-                                        tag: image_info.tag,
-                                        digest: image_info.digest,
-                                        size: image_info.size,
-                                        created: image_info.created,
-                                    });
-                                }
-                            }
-                        }
-                    }
-                }
-                images
-            } else {
-                Vec::new()
-            }
-        } else {
-            Vec::new()
-        }
-    } else {
-        // Search across all registries
-        match manager.search_images(&request.query).await {
-            Ok(results) => results.into_iter().map(|(registry, image_info)| {
-                ImageSearchResult {
-                    registry,
-                    repository: image_info.repository,
-                    tag: image_info.tag,
-                    digest: image_info.digest,
-                    size: image_info.size,
-                    created: image_info.created,
-                }
-            }).collect(),
-            Err(e) => {
-                error!("Failed to search images: {}", e);
-                Vec::new()
-            }
-        }
-    };
+    let max_tags = request.max_tags.unwrap_or(DEFAULT_SEARCH_TAGS);
+    let found = manager.search(&request.query, request.registry.as_deref(), max_tags).await;
 
-    Ok(Json(ImageSearchResponse { images: results }))
+    let images = found
+        .catalog
+        .iter()
+        .flat_map(|(registry, found)| {
+            found.tags.iter().map(|tag| ImageSearchResult {
+                registry: registry.clone(),
+                repository: found.repository.clone(),
+                tag: tag.clone(),
+                digest: None,
+                size: None,
+                created: None,
+                description: None,
+                stars: None,
+                official: None,
+            })
+        })
+        .chain(found.repositories.iter().map(|(registry, found)| ImageSearchResult {
+            registry: registry.clone(),
+            repository: found.repository.clone(),
+            tag: "latest".to_string(),
+            digest: None,
+            size: None,
+            created: None,
+            description: found.description.clone(),
+            stars: Some(found.stars),
+            official: Some(found.official),
+        }))
+        .collect();
+    let mut groups = rank_search_results(
+        &request.query,
+        &found.catalog,
+        &found.repositories,
+        &manager.local_registries(),
+        max_tags,
+    );
+    if request.inspect {
+        let references: Vec<ImageReference> = groups.iter().flat_map(|group| group.tags.clone()).collect();
+        merge_image_details(&mut groups, manager.image_details(&references).await);
+    }
+
+    Ok(Json(ImageSearchResponse {
+        images,
+        groups,
+        warnings: found.warnings,
+    }))
 }
 
 /// Search for images via GET request (for wizard)
+#[utoipa::path(
+    get,
+    path = "/api/v1/images/search",
+    tag = "images",
+    params(ImageSearchQuery),
+    responses(
+        (status = 200, description = "Matching images", body = Vec<ImageInfo>),
+        (status = 404, description = "No such registry", body = ApiErrorResponse),
+    ),
+)]
 async fn search_images_get(
     State(state): State<AppState>,
     Query(params): Query<ImageSearchQuery>,
-) -> Result<Json<Vec<ImageInfo>>, StatusCode> {
+) -> Result<Json<Vec<ImageInfo>>, ApiError> {
     let manager = state.registry_manager.read().await;
+    if let Some(registry_name) = &params.registry
+        && manager.get_registry(registry_name).is_none()
+    {
+        return Err(ApiError::not_found("REGISTRY", format!("Registry '{}' not found", registry_name)));
+    }
 
-    // Convert search results to ImageInfo format expected by wizard
-    let results = if let Some(registry_name) = &params.registry {
-        // Search in specific registry
-        if let Some(client) = manager.get_registry(registry_name) {
-            if let Ok(repositories) = client.list_repositories().await {
-                let mut images = Vec::new();
-                for repo in repositories {
-                    if repo.contains(&params.q) {
-                        if let Ok(tags) = client.list_tags(&repo).await {
-                            for tag in tags {
-                                if let Ok(image_info) = client.get_image_info(&repo, &tag).await {
-                                    images.push(image_info);
-                                }
-                            }
-                        }
-                    }
-                }
-                images
-            } else {
-                Vec::new()
-            }
-        } else {
-            Vec::new()
-        }
-    } else {
-        // Search across all registries
-        match manager.search_images(&params.q).await {
-            Ok(results) => results.into_iter().map(|(_, image_info)| image_info).collect(),
-            Err(e) => {
-                error!("Failed to search images: {}", e);
-                Vec::new()
-            }
-        }
-    };
+    // The wizard shows image details, so the newest tags found are inspected
+    let found = manager.search(&params.q, params.registry.as_deref(), DEFAULT_SEARCH_TAGS).await;
+    let references: Vec<ImageReference> = found
+        .catalog
+        .into_iter()
+        .flat_map(|(registry, found)| {
+            found.tags.into_iter().map(move |tag| ImageReference {
+                registry: registry.clone(),
+                repository: found.repository.clone(),
+                tag,
+            })
+        })
+        .collect();
 
-    Ok(Json(results))
+    Ok(Json(manager.image_details(&references).await.into_iter().map(|(_, info)| info).collect()))
 }
 
-/// Pull an image from a registry
+/// Start pulling an image from a registry; follow it at `/api/v1/jobs/{id}`
+#[utoipa::path(
+    post,
+    path = "/api/v1/images/pull",
+    tag = "images",
+    request_body = ImagePullRequest,
+    responses(
+        (status = 202, description = "Pull started as a background job", body = JobAccepted),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such registry", body = ApiErrorResponse),
+    ),
+)]
 async fn pull_image(
+    _: RequireOperator,
     State(state): State<AppState>,
+    env: Env,
     Json(request): Json<ImagePullRequest>,
-) -> Result<Json<OperationResult>, StatusCode> {
-    let manager = state.registry_manager.read().await;
+) -> Result<(StatusCode, Json<JobAccepted>), ApiError> {
+    // Clone the client so the registry list is not locked for the whole pull
+    let Some(client) = state.registry_manager.read().await.get_registry(&request.registry).cloned() else {
+        error!("Registry not found: {}", request.registry);
+        return Err(ApiError::not_found("REGISTRY", format!("Registry '{}' not found", request.registry)));
+    };
 
-    if let Some(client) = manager.get_registry(&request.registry) {
-        match client.pull_image(&request.repository, &request.tag).await {
-            Ok(_) => {
-                info!("Successfully pulled image {}:{} from {}", request.repository, request.tag, request.registry);
-                Ok(Json(OperationResult {
-                    success: true,
-                    message: format!("Successfully pulled {}:{}", request.repository, request.tag),
-                }))
-            }
-            Err(e) => {
-                error!("Failed to pull image {}:{} from {}: {}", request.repository, request.tag, request.registry, e);
-                Ok(Json(OperationResult {
-                    success: false,
-                    message: format!("Failed to pull image: {}", e),
-                }))
-            }
+    let reference = format!("{}:{}", request.repository, request.tag);
+    let runtime = env.runtime;
+    let blob_dir = std::path::Path::new(&state.config.data_dir).join("blobs");
+    let job = state.jobs.start(JobKind::ImagePull, reference.clone(), move |progress| async move {
+        progress.update(0, format!("Fetching the manifest from {}", request.registry));
+        client
+            .pull_image_with_progress(&request.repository, &request.tag, &blob_dir, |pulled| {
+                let percent = (pulled.current_bytes * 85).checked_div(pulled.total_bytes).map_or(90, |share| 5 + share);
+                let message = match pulled.current_layer {
+                    0 => "Downloading layers".to_string(),
+                    layer => format!("Downloading layer {} of {}", layer, pulled.total_layers),
+                };
+                progress.update(percent as u8, message);
+                progress.transfer(*pulled);
+            })
+            .await
+            .inspect_err(|e| error!("Failed to pull image {} from {}: {}", reference, request.registry, e))?;
+        info!("Successfully pulled image {} from {}", reference, request.registry);
+
+        // Make the image available to the runtime so it shows up in the local image list
+        progress.update(90, "Adding the image to the runtime");
+        if let Err(e) = runtime.pull_image(&reference).await {
+            warn!("Bolt in {} failed to pull {}: {}", env.name, reference, e);
+        }
+
+        Ok(format!("Successfully pulled {}", reference))
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(JobAccepted { job_id: job.id })))
+}
+
+/// Start copying an image between repositories, mounting blobs when both are on the same
+/// registry host; follow it at `/api/v1/jobs/{id}`
+#[utoipa::path(
+    post,
+    path = "/api/v1/images/copy",
+    tag = "images",
+    request_body = ImageCopyRequest,
+    responses(
+        (status = 202, description = "Copy started as a background job", body = JobAccepted),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such registry", body = ApiErrorResponse),
+    ),
+)]
+async fn copy_image(
+    _: RequireOperator,
+    State(state): State<AppState>,
+    Json(request): Json<ImageCopyRequest>,
+) -> Result<(StatusCode, Json<JobAccepted>), ApiError> {
+    // Clone the clients so the registry list is not locked for the whole transfer
+    let (source, destination) = {
+        let manager = state.registry_manager.read().await;
+        let client = |name: &str| {
+            manager
+                .get_registry(name)
+                .cloned()
+                .ok_or_else(|| ApiError::not_found("REGISTRY", format!("Registry '{}' not found", name)))
+        };
+        (client(&request.source_registry)?, client(&request.destination_registry)?)
+    };
+
+    let source_ref = format!("{}/{}:{}", request.source_registry, request.source_repository, request.source_tag);
+    let destination_ref = format!(
+        "{}/{}:{}",
+        request.destination_registry, request.destination_repository, request.destination_tag
+    );
+    let job = state.jobs.start(JobKind::ImageCopy, destination_ref.clone(), move |progress| async move {
+        progress.update(0, format!("Fetching the manifest of {}", source_ref));
+        let result = destination
+            .copy_image_with_progress(
+                &source,
+                &request.source_repository,
+                &request.source_tag,
+                &request.destination_repository,
+                &request.destination_tag,
+                |copied| {
+                    let percent = (copied.current_bytes * 95).checked_div(copied.total_bytes).unwrap_or(95);
+                    let message = match copied.current_layer {
+                        0 => "Copying blobs".to_string(),
+                        blob => format!("Copying blob {} of {}", blob, copied.total_layers),
+                    };
+                    progress.update(percent as u8, message);
+                    progress.transfer(*copied);
+                },
+            )
+            .await
+            .inspect_err(|e| error!("Failed to copy {} to {}: {}", source_ref, destination_ref, e))?;
+
+        Ok(format!(
+            "Copied {} to {} ({} blobs mounted, {} uploaded, {} already present)",
+            source_ref, destination_ref, result.blobs_mounted, result.blobs_uploaded, result.blobs_existing
+        ))
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(JobAccepted { job_id: job.id })))
+}
+
+/// Push an image uploaded as a tar of an OCI image layout, as the export route writes, to
+/// a registry. The archive is read as it arrives, so large layers are not held in memory.
+#[utoipa::path(
+    post,
+    path = "/api/v1/images/import",
+    tag = "images",
+    params(ImageImportQuery),
+    request_body(content = Vec<u8>, content_type = "application/x-tar", description = "OCI layout tar"),
+    responses(
+        (status = 201, description = "Image pushed with the digests it has in the archive", body = ImageImportResult),
+        (status = 400, description = "Invalid repository or tag, or the archive is not a complete image", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such registry", body = ApiErrorResponse),
+        (status = 502, description = "Registry request failed", body = ApiErrorResponse),
+    ),
+)]
+async fn import_image(
+    _: RequireOperator,
+    State(state): State<AppState>,
+    Query(query): Query<ImageImportQuery>,
+    body: Body,
+) -> Result<(StatusCode, Json<ImageImportResult>), ApiError> {
+    if !is_valid_repository(&query.repository) || !query.tag.as_deref().is_none_or(is_valid_tag) {
+        return Err(ApiError::from_status(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid image reference '{}:{}'", query.repository, query.tag.as_deref().unwrap_or("")),
+        ));
+    }
+
+    // Clone the client so the registry list is not locked for the whole upload
+    let client = state
+        .registry_manager
+        .read()
+        .await
+        .get_registry(&query.registry)
+        .cloned()
+        .ok_or_else(|| ApiError::not_found("REGISTRY", format!("Registry '{}' not found", query.registry)))?;
+
+    let archive = body.into_data_stream().map_err(std::io::Error::other);
+    match client.import_image(&query.repository, query.tag.as_deref(), archive).await {
+        Ok(result) => Ok((StatusCode::CREATED, Json(result))),
+        Err(ImportImageError::InvalidArchive(message)) => {
+            Err(ApiError::new(StatusCode::BAD_REQUEST, "INVALID_IMAGE_ARCHIVE", message))
+        }
+        Err(ImportImageError::Failed(e)) => {
+            error!("Failed to import into {}/{}: {}", query.registry, query.repository, e);
+            Err(ApiError::runtime(&e, format!("Failed to import image: {}", e)))
         }
-    } else {
-        error!("Registry not found: {}", request.registry);
-        Ok(Json(OperationResult {
-            success: false,
-            message: format!("Registry '{}' not found", request.registry),
-        }))
     }
 }
 
 /// List all containers
-async fn list_containers(State(state): State<AppState>) -> Result<Json<ContainerListResponse>, StatusCode> {
-    match state.bolt_client.list_containers(None).await {
+#[utoipa::path(
+    get,
+    path = "/api/v1/containers",
+    tag = "containers",
+    params(ContainerListQuery),
+    responses(
+        (status = 200, description = "Containers matching the filters", body = ContainerListResponse),
+        (status = 400, description = "Invalid filter", body = ApiErrorResponse),
+        (status = 502, description = "Bolt request failed", body = ApiErrorResponse),
+    ),
+)]
+async fn list_containers(
+    env: Env,
+    Query(query): Query<ContainerListQuery>,
+) -> Result<Json<ContainerListResponse>, ApiError> {
+    let filter = query
+        .filter()
+        .map_err(|message| ApiError::from_status(StatusCode::BAD_REQUEST, message))?;
+    if query.limit == Some(0) {
+        return Err(ApiError::from_status(StatusCode::BAD_REQUEST, "limit must be at least 1"));
+    }
+
+    match env.runtime.list_containers(filter).await {
         Ok(containers) => {
-            info!("Retrieved {} containers", containers.len());
-            Ok(Json(ContainerListResponse { containers }))
+            let total = containers.len();
+            let offset = query.offset.unwrap_or(0);
+            let containers: Vec<Container> = containers
+                .into_iter()
+                .skip(offset)
+                .take(query.limit.unwrap_or(usize::MAX))
+                .collect();
+
+            info!("Retrieved {} of {} containers", containers.len(), total);
+            Ok(Json(ContainerListResponse {
+                containers,
+                total,
+                limit: query.limit,
+                offset,
+            }))
         }
         Err(e) => {
             error!("Failed to list containers: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(ApiError::runtime(&e, format!("Failed to list containers: {}", e)))
         }
     }
 }
 
-/// Get detailed container information
-async fn get_container(
-    State(state): State<AppState>,
-    Path(id): Path<String>,
-) -> Result<Json<Container>, StatusCode> {
-    // For mock client, we'll get the container from the list
-    match state.bolt_client.list_containers(None).await {
-        Ok(containers) => {
-            if let Some(container) = containers.into_iter().find(|c| c.id == id) {
-                Ok(Json(container))
-            } else {
-                error!("Container not found: {}", id);
-                Err(StatusCode::NOT_FOUND)
-            }
+/// List projects: containers grouped by their `gpanel.project` label
+#[utoipa::path(
+    get,
+    path = "/api/v1/projects",
+    tag = "containers",
+    responses((status = 200, description = "Containers grouped by project", body = ProjectListResponse)),
+)]
+async fn list_projects(env: Env) -> Result<Json<ProjectListResponse>, ApiError> {
+    let containers = env.runtime.list_containers(None).await.map_err(|e| {
+        error!("Failed to list containers: {}", e);
+        ApiError::runtime(&e, format!("Failed to list containers: {}", e))
+    })?;
+
+    Ok(Json(ProjectListResponse {
+        projects: group_projects(&containers),
+    }))
+}
+
+/// Look up a container by full ID, falling back to a unique ID prefix such as a 12-character short ID
+async fn resolve_container(runtime: &dyn ContainerRuntime, id: &str) -> Result<Container, ApiError> {
+    match runtime.get_container(id).await {
+        Ok(container) => return Ok(container),
+        Err(e) if e.is_timeout() => {
+            error!("Timed out looking up container {}: {}", id, e);
+            return Err(ApiError::runtime(&e, format!("Failed to get container: {}", e)));
         }
-        Err(e) => {
-            error!("Failed to get container {}: {}", id, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        Err(_) => {}
+    }
+
+    // Only prefixes need the full list
+    let containers = runtime.list_containers(None).await.map_err(|e| {
+        error!("Failed to list containers: {}", e);
+        ApiError::runtime(&e, format!("Failed to list containers: {}", e))
+    })?;
+    let mut matches: Vec<Container> = containers.into_iter().filter(|c| c.id.starts_with(id)).collect();
+
+    match matches.len() {
+        0 => Err(ApiError::not_found("CONTAINER", format!("Container '{}' not found", id))),
+        1 => Ok(matches.remove(0)),
+        _ => {
+            let candidates: Vec<&str> = matches.iter().map(|c| c.id.as_str()).collect();
+            Err(ApiError::new(
+                StatusCode::CONFLICT,
+                "AMBIGUOUS_CONTAINER_ID",
+                format!("Container ID prefix '{}' is ambiguous: {}", id, candidates.join(", ")),
+            )
+            .with_details(serde_json::json!({ "candidates": candidates })))
         }
     }
 }
 
+/// Get detailed container information
+#[utoipa::path(
+    get,
+    path = "/api/v1/containers/{id}",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID, ID prefix or name")),
+    responses(
+        (status = 200, description = "The container", body = Container),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+        (status = 502, description = "Bolt request failed", body = ApiErrorResponse),
+    ),
+)]
+async fn get_container(
+    env: Env,
+    Path(id): Path<String>,
+) -> Result<Json<Container>, ApiError> {
+    resolve_container(env.runtime.as_ref(), &id).await.map(Json)
+}
+
+/// Query parameters for POST /api/v1/containers
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CreateContainerQuery {
+    /// Create even if a requested host port is already bound
+    pub force: Option<bool>,
+}
+
+/// Host ports in the request that running containers already bind
+async fn port_conflicts(runtime: &dyn ContainerRuntime, request: &CreateContainerRequest) -> Result<Vec<PortConflict>, ApiError> {
+    if request.ports.iter().all(|port| port.host_port.is_none()) {
+        return Ok(Vec::new());
+    }
+
+    let containers = runtime.list_containers(None).await.map_err(|e| {
+        error!("Failed to list containers: {}", e);
+        ApiError::runtime(&e, format!("Failed to list containers: {}", e))
+    })?;
+    Ok(find_port_conflicts(request, &containers))
+}
+
+/// 409 `GPU_IN_USE` when `allocation` conflicts with the GPU claim of a running container
+/// other than `own_id`
+async fn check_gpu_claims(
+    runtime: &dyn ContainerRuntime,
+    allocation: &GpuAllocation,
+    own_id: Option<&str>,
+) -> Result<(), ApiError> {
+    let mut containers = runtime.list_containers(None).await.map_err(|e| {
+        error!("Failed to list containers: {}", e);
+        ApiError::runtime(&e, format!("Failed to list containers: {}", e))
+    })?;
+    containers.retain(|container| Some(container.id.as_str()) != own_id);
+
+    match find_gpu_conflict(allocation, &containers) {
+        Some(conflict) => Err(ApiError::new(StatusCode::CONFLICT, "GPU_IN_USE", conflict.message(allocation))
+            .with_details(serde_json::json!({
+                "device_id": allocation.device_id,
+                "container_id": conflict.holder.id,
+                "container_name": conflict.holder.name,
+                "reason": conflict.reason,
+            }))),
+        None => Ok(()),
+    }
+}
+
+/// Check that starting container `id` would not break another container's GPU claim
+async fn check_gpu_for_start(runtime: &dyn ContainerRuntime, id: &str) -> Result<(), ApiError> {
+    let container = runtime
+        .get_container(id)
+        .await
+        .map_err(|e| container_error(id, &e, format!("Failed to get container: {}", e)))?;
+    match &container.gpu_allocation {
+        Some(allocation) => check_gpu_claims(runtime, allocation, Some(&container.id)).await,
+        None => Ok(()),
+    }
+}
+
+/// Failure of an operation on one container: a missing container is `CONTAINER_NOT_FOUND`,
+/// other errors keep the status they map to, e.g. 502 when Bolt fails
+fn container_error(id: &str, error: &gpanel_core::Error, message: String) -> ApiError {
+    if error.status_code() == 404 {
+        ApiError::not_found("CONTAINER", format!("Container '{}' not found", id))
+    } else {
+        ApiError::runtime(error, message)
+    }
+}
+
+/// Check a create request for invalid fields and port conflicts without creating anything
+#[utoipa::path(
+    post,
+    path = "/api/v1/containers/validate",
+    tag = "containers",
+    request_body = CreateContainerRequest,
+    responses((status = 200, description = "Validation errors and port conflicts, if any", body = ContainerValidationResponse)),
+)]
+async fn validate_container(
+    State(state): State<AppState>,
+    env: Env,
+    Json(mut request): Json<CreateContainerRequest>,
+) -> Result<Json<ContainerValidationResponse>, ApiError> {
+    let mut errors = validate_create_request(&request);
+    if let Err(error) = state.gaming_profiles.resolve(&mut request).await {
+        errors.push(error);
+    }
+    let conflicts = port_conflicts(env.runtime.as_ref(), &request).await?;
+
+    Ok(Json(ContainerValidationResponse {
+        valid: errors.is_empty() && conflicts.is_empty(),
+        errors,
+        conflicts,
+    }))
+}
+
 /// Create a new container
+#[utoipa::path(
+    post,
+    path = "/api/v1/containers",
+    tag = "containers",
+    params(CreateContainerQuery),
+    request_body = CreateContainerRequest,
+    responses(
+        (status = 201, description = "Container created", body = OperationResult),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 409, description = "A requested host port is already bound, or the GPU allocation conflicts with a running container's", body = ApiErrorResponse),
+        (status = 422, description = "Invalid container definition", body = ApiErrorResponse),
+    ),
+)]
 async fn create_container(
+    _: RequireOperator,
     State(state): State<AppState>,
-    Json(request): Json<CreateContainerRequest>,
-) -> Result<(StatusCode, Json<OperationResult>), StatusCode> {
-    info!("Creating container '{}' with image: {}", request.name, request.image);
+    env: Env,
+    Query(query): Query<CreateContainerQuery>,
+    Json(mut request): Json<CreateContainerRequest>,
+) -> Result<(StatusCode, Json<OperationResult>), ApiError> {
+    let mut errors = validate_create_request(&request);
+    if let Err(error) = state.gaming_profiles.resolve(&mut request).await {
+        errors.push(error);
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::from_status(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Container request has {} invalid field(s)", errors.len()),
+        )
+        .with_details(ValidationErrorDetails { errors }));
+    }
 
-    match state.bolt_client.create_container(request).await {
-        Ok(container_id) => {
-            info!("Created container: {}", container_id);
+    if !query.force.unwrap_or(false) {
+        let conflicts = port_conflicts(env.runtime.as_ref(), &request).await?;
+        if let Some(conflict) = conflicts.first() {
+            return Err(ApiError::new(
+                StatusCode::CONFLICT,
+                "PORT_CONFLICT",
+                format!("{}; pass force=true to create it anyway", conflict.message()),
+            )
+            .with_details(serde_json::json!({ "conflicts": conflicts })));
+        }
+    }
+
+    // Unlike a port, a GPU claim cannot be overridden by forcing it
+    if let Some(allocation) = &request.gpu_allocation {
+        check_gpu_claims(env.runtime.as_ref(), allocation, None).await?;
+    }
+
+    info!(
+        "Creating container '{}' with image: {}",
+        request.name.as_deref().unwrap_or("<unnamed>"),
+        request.image
+    );
+
+    match env.runtime.create_container(request).await {
+        Ok(container) => {
+            info!("Created container: {} ({})", container.name, container.id);
+            env.publish(ContainerEvent::for_container(&container, ContainerEventAction::Created));
             Ok((StatusCode::CREATED, Json(OperationResult {
                 success: true,
-                message: format!("Container created successfully with ID: {}", container_id),
+                message: format!("Container created successfully with ID: {}", container.id),
             })))
         }
         Err(e) => {
             error!("Failed to create container: {}", e);
-            Ok((StatusCode::INTERNAL_SERVER_ERROR, Json(OperationResult {
-                success: false,
-                message: format!("Failed to create container: {}", e),
-            })))
+            Err(ApiError::runtime(&e, format!("Failed to create container: {}", e)))
         }
     }
 }
 
 /// Start a container
+#[utoipa::path(
+    post,
+    path = "/api/v1/containers/{id}/start",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name")),
+    responses(
+        (status = 200, description = "Container started", body = OperationResult),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+        (status = 409, description = "The container's GPU allocation conflicts with a running container's", body = ApiErrorResponse),
+        (status = 500, description = "Runtime error", body = ApiErrorResponse),
+        (status = 502, description = "Bolt request failed", body = ApiErrorResponse),
+    ),
+)]
 async fn start_container(
-    State(state): State<AppState>,
+    _: RequireOperator,
+    env: Env,
     Path(id): Path<String>,
-) -> Result<Json<OperationResult>, StatusCode> {
-    match state.bolt_client.start_container(&id).await {
+) -> Result<Json<OperationResult>, ApiError> {
+    check_gpu_for_start(env.runtime.as_ref(), &id).await?;
+
+    match env.runtime.start_container(&id).await {
         Ok(_) => {
             info!("Started container: {}", id);
+            env.publish_status(&id).await;
             Ok(Json(OperationResult {
                 success: true,
                 message: format!("Container {} started successfully", id),
@@ -550,23 +1831,36 @@ async fn start_container(
         }
         Err(e) => {
             error!("Failed to start container {}: {}", id, e);
-            Ok(Json(OperationResult {
-                success: false,
-                message: format!("Failed to start container: {}", e),
-            }))
+            Err(container_error(&id, &e, format!("Failed to start container: {}", e)))
         }
     }
 }
 
 /// Stop a container
+#[utoipa::path(
+    post,
+    path = "/api/v1/containers/{id}/stop",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name")),
+    request_body = ContainerOperationRequest,
+    responses(
+        (status = 200, description = "Container stopped", body = OperationResult),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+        (status = 500, description = "Runtime error", body = ApiErrorResponse),
+        (status = 502, description = "Bolt request failed", body = ApiErrorResponse),
+    ),
+)]
 async fn stop_container(
-    State(state): State<AppState>,
+    _: RequireOperator,
+    env: Env,
     Path(id): Path<String>,
     Json(request): Json<ContainerOperationRequest>,
-) -> Result<Json<OperationResult>, StatusCode> {
-    match state.bolt_client.stop_container(&id, request.timeout).await {
+) -> Result<Json<OperationResult>, ApiError> {
+    match env.runtime.stop_container(&id, request.timeout).await {
         Ok(_) => {
             info!("Stopped container: {}", id);
+            env.publish_status(&id).await;
             Ok(Json(OperationResult {
                 success: true,
                 message: format!("Container {} stopped successfully", id),
@@ -574,23 +1868,36 @@ async fn stop_container(
         }
         Err(e) => {
             error!("Failed to stop container {}: {}", id, e);
-            Ok(Json(OperationResult {
-                success: false,
-                message: format!("Failed to stop container: {}", e),
-            }))
+            Err(container_error(&id, &e, format!("Failed to stop container: {}", e)))
         }
     }
 }
 
 /// Restart a container
+#[utoipa::path(
+    post,
+    path = "/api/v1/containers/{id}/restart",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name")),
+    request_body = ContainerOperationRequest,
+    responses(
+        (status = 200, description = "Container restarted", body = OperationResult),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+        (status = 500, description = "Runtime error", body = ApiErrorResponse),
+        (status = 502, description = "Bolt request failed", body = ApiErrorResponse),
+    ),
+)]
 async fn restart_container(
-    State(state): State<AppState>,
+    _: RequireOperator,
+    env: Env,
     Path(id): Path<String>,
     Json(request): Json<ContainerOperationRequest>,
-) -> Result<Json<OperationResult>, StatusCode> {
-    match state.bolt_client.restart_container(&id, request.timeout).await {
+) -> Result<Json<OperationResult>, ApiError> {
+    match env.runtime.restart_container(&id, request.timeout).await {
         Ok(_) => {
             info!("Restarted container: {}", id);
+            env.publish_status(&id).await;
             Ok(Json(OperationResult {
                 success: true,
                 message: format!("Container {} restarted successfully", id),
@@ -598,26 +1905,334 @@ async fn restart_container(
         }
         Err(e) => {
             error!("Failed to restart container {}: {}", id, e);
+            Err(container_error(&id, &e, format!("Failed to restart container: {}", e)))
+        }
+    }
+}
+
+/// Pause a running container
+#[utoipa::path(
+    post,
+    path = "/api/v1/containers/{id}/pause",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name")),
+    responses(
+        (status = 200, description = "Container paused", body = OperationResult),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+        (status = 409, description = "Container is not running", body = ApiErrorResponse),
+    ),
+)]
+async fn pause_container(
+    _: RequireOperator,
+    env: Env,
+    Path(id): Path<String>,
+) -> Result<Json<OperationResult>, ApiError> {
+    let container = env
+        .runtime
+        .get_container(&id)
+        .await
+        .map_err(|_| ApiError::not_found("CONTAINER", format!("Container '{}' not found", id)))?;
+    if container.status != ContainerStatus::Running {
+        return Err(ApiError::from_status(StatusCode::CONFLICT, format!("Container '{}' is not running", id)));
+    }
+
+    match env.runtime.pause_container(&id).await {
+        Ok(_) => {
+            info!("Paused container: {}", id);
+            env.publish_status(&id).await;
+            Ok(Json(OperationResult {
+                success: true,
+                message: format!("Container {} paused successfully", id),
+            }))
+        }
+        Err(e) => {
+            error!("Failed to pause container {}: {}", id, e);
+            Err(container_error(&id, &e, format!("Failed to pause container: {}", e)))
+        }
+    }
+}
+
+/// Resume a paused container
+#[utoipa::path(
+    post,
+    path = "/api/v1/containers/{id}/unpause",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name")),
+    responses(
+        (status = 200, description = "Container resumed", body = OperationResult),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+        (status = 409, description = "Container is not paused", body = ApiErrorResponse),
+    ),
+)]
+async fn unpause_container(
+    _: RequireOperator,
+    env: Env,
+    Path(id): Path<String>,
+) -> Result<Json<OperationResult>, ApiError> {
+    let container = env
+        .runtime
+        .get_container(&id)
+        .await
+        .map_err(|_| ApiError::not_found("CONTAINER", format!("Container '{}' not found", id)))?;
+    if container.status != ContainerStatus::Paused {
+        return Err(ApiError::from_status(StatusCode::CONFLICT, format!("Container '{}' is not paused", id)));
+    }
+
+    match env.runtime.unpause_container(&id).await {
+        Ok(_) => {
+            info!("Unpaused container: {}", id);
+            env.publish_status(&id).await;
+            Ok(Json(OperationResult {
+                success: true,
+                message: format!("Container {} resumed successfully", id),
+            }))
+        }
+        Err(e) => {
+            error!("Failed to unpause container {}: {}", id, e);
+            Err(container_error(&id, &e, format!("Failed to unpause container: {}", e)))
+        }
+    }
+}
+
+/// Send a signal to a container, e.g. SIGHUP to reload its config
+#[utoipa::path(
+    post,
+    path = "/api/v1/containers/{id}/kill",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name")),
+    request_body = KillContainerRequest,
+    responses(
+        (status = 200, description = "Signal sent", body = OperationResult),
+        (status = 400, description = "Unknown signal", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+    ),
+)]
+async fn kill_container(
+    _: RequireOperator,
+    env: Env,
+    Path(id): Path<String>,
+    Json(request): Json<KillContainerRequest>,
+) -> Result<Json<OperationResult>, ApiError> {
+    if !valid_signal(&request.signal) {
+        return Err(ApiError::from_status(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Unknown signal '{}': expected one of {} or a signal number from 1 to 64",
+                request.signal,
+                KILL_SIGNALS.join(", ")
+            ),
+        ));
+    }
+
+    if env.runtime.get_container(&id).await.is_err() {
+        return Err(ApiError::not_found("CONTAINER", format!("Container '{}' not found", id)));
+    }
+
+    match env.runtime.kill_container(&id, Some(&request.signal)).await {
+        Ok(_) => {
+            info!("Sent {} to container: {}", request.signal, id);
+            env.publish_status(&id).await;
+            Ok(Json(OperationResult {
+                success: true,
+                message: format!("Sent {} to container {}", request.signal, id),
+            }))
+        }
+        Err(e) => {
+            error!("Failed to send {} to container {}: {}", request.signal, id, e);
+            Err(container_error(&id, &e, format!("Failed to signal container: {}", e)))
+        }
+    }
+}
+
+/// Run a command in a running container and return its output
+#[utoipa::path(
+    post,
+    path = "/api/v1/containers/{id}/exec",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name")),
+    request_body = ExecRequest,
+    responses(
+        (status = 200, description = "Command output and exit code", body = ExecResponse),
+        (status = 400, description = "Empty command", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+        (status = 409, description = "Container is not running", body = ApiErrorResponse),
+    ),
+)]
+async fn exec_container(
+    _: RequireOperator,
+    env: Env,
+    Path(id): Path<String>,
+    Json(request): Json<ExecRequest>,
+) -> Result<Json<ExecResponse>, ApiError> {
+    if request.cmd.is_empty() {
+        return Err(ApiError::from_status(StatusCode::BAD_REQUEST, "Command must not be empty"));
+    }
+
+    let container = env
+        .runtime
+        .get_container(&id)
+        .await
+        .map_err(|_| ApiError::not_found("CONTAINER", format!("Container '{}' not found", id)))?;
+
+    if !matches!(container.status, ContainerStatus::Running) {
+        return Err(ApiError::from_status(
+            StatusCode::CONFLICT,
+            format!("Container '{}' is not running; start it before running commands", id),
+        ));
+    }
+
+    info!("Exec in container {}: {:?}", id, request.cmd);
+    match env.runtime.exec_container(&id, &request).await {
+        Ok(output) => Ok(Json(output)),
+        Err(e) => {
+            error!("Failed to exec in container {}: {}", id, e);
+            Err(container_error(&id, &e, format!("Failed to exec in container: {}", e)))
+        }
+    }
+}
+
+/// Rename a container
+#[utoipa::path(
+    post,
+    path = "/api/v1/containers/{id}/rename",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name")),
+    request_body = RenameContainerRequest,
+    responses(
+        (status = 200, description = "Container renamed", body = OperationResult),
+        (status = 400, description = "Invalid name", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+        (status = 409, description = "Name already in use", body = ApiErrorResponse),
+    ),
+)]
+async fn rename_container(
+    _: RequireOperator,
+    env: Env,
+    Path(id): Path<String>,
+    Json(request): Json<RenameContainerRequest>,
+) -> Result<Json<OperationResult>, ApiError> {
+    if !is_valid_container_name(&request.name) {
+        return Err(ApiError::from_status(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Invalid container name '{}': use letters, digits, '_', '.' and '-', starting with a letter or digit",
+                request.name
+            ),
+        ));
+    }
+
+    let containers = env.runtime.list_containers(None).await.map_err(|e| {
+        error!("Failed to list containers: {}", e);
+        ApiError::runtime(&e, format!("Failed to list containers: {}", e))
+    })?;
+    if !containers.iter().any(|c| c.id == id) {
+        return Err(ApiError::not_found("CONTAINER", format!("Container '{}' not found", id)));
+    }
+    if let Some(existing) = containers.iter().find(|c| c.name == request.name && c.id != id) {
+        return Err(ApiError::from_status(
+            StatusCode::CONFLICT,
+            format!("Container name '{}' is already used by {}", request.name, existing.id),
+        ));
+    }
+
+    match env.runtime.rename_container(&id, &request.name).await {
+        Ok(_) => {
+            info!("Renamed container {} to {}", id, request.name);
             Ok(Json(OperationResult {
-                success: false,
-                message: format!("Failed to restart container: {}", e),
+                success: true,
+                message: format!("Container renamed to {}", request.name),
             }))
         }
+        Err(e) => {
+            error!("Failed to rename container {}: {}", id, e);
+            Err(container_error(&id, &e, format!("Failed to rename container: {}", e)))
+        }
+    }
+}
+
+/// Save a container's filesystem as a new local image
+#[utoipa::path(
+    post,
+    path = "/api/v1/containers/{id}/commit",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name")),
+    request_body = ContainerCommitRequest,
+    responses(
+        (status = 200, description = "Image created from the container", body = ImageSummary),
+        (status = 400, description = "Invalid repository or tag", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+    ),
+)]
+async fn commit_container(
+    _: RequireOperator,
+    env: Env,
+    Path(id): Path<String>,
+    Json(request): Json<ContainerCommitRequest>,
+) -> Result<Json<ImageSummary>, ApiError> {
+    let tag = request.tag.as_deref().unwrap_or("latest");
+    if !is_valid_repository(&request.repository) || !is_valid_tag(tag) {
+        return Err(ApiError::from_status(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid image reference '{}:{}'", request.repository, tag),
+        ));
+    }
+
+    env
+        .runtime
+        .get_container(&id)
+        .await
+        .map_err(|_| ApiError::not_found("CONTAINER", format!("Container '{}' not found", id)))?;
+
+    match env.runtime.commit_container(&id, &request).await {
+        Ok(image) => {
+            info!("Committed container {} as {}:{} ({})", id, request.repository, tag, image.id);
+            Ok(Json(image))
+        }
+        Err(e) => {
+            error!("Failed to commit container {}: {}", id, e);
+            Err(container_error(&id, &e, format!("Failed to commit container: {}", e)))
+        }
     }
 }
 
 /// Delete a container
+#[utoipa::path(
+    delete,
+    path = "/api/v1/containers/{id}",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name")),
+    request_body = ContainerOperationRequest,
+    responses(
+        (status = 200, description = "Container removed", body = OperationResult),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+        (status = 409, description = "Container is running and `force` was not set", body = ApiErrorResponse),
+        (status = 500, description = "Runtime error", body = ApiErrorResponse),
+        (status = 502, description = "Bolt request failed", body = ApiErrorResponse),
+    ),
+)]
 async fn delete_container(
-    State(state): State<AppState>,
+    _: RequireOperator,
+    env: Env,
     Path(id): Path<String>,
     Json(request): Json<ContainerOperationRequest>,
-) -> Result<Json<OperationResult>, StatusCode> {
+) -> Result<Json<OperationResult>, ApiError> {
     let force = request.force.unwrap_or(false);
     let remove_volumes = request.remove_volumes.unwrap_or(false);
+    let container = env.runtime.get_container(&id).await.ok();
 
-    match state.bolt_client.remove_container(&id, force, remove_volumes).await {
+    match env.runtime.remove_container(&id, force, remove_volumes).await {
         Ok(_) => {
             info!("Removed container: {}", id);
+            if let Some(container) = &container {
+                env.publish(ContainerEvent::for_container(container, ContainerEventAction::Removed));
+            }
             Ok(Json(OperationResult {
                 success: true,
                 message: format!("Container {} removed successfully", id),
@@ -625,54 +2240,1191 @@ async fn delete_container(
         }
         Err(e) => {
             error!("Failed to remove container {}: {}", id, e);
-            Ok(Json(OperationResult {
-                success: false,
-                message: format!("Failed to remove container: {}", e),
-            }))
+            Err(container_error(&id, &e, format!("Failed to remove container: {}", e)))
         }
     }
 }
 
-/// Get container logs
-async fn get_container_logs(
-    State(state): State<AppState>,
-    Path(id): Path<String>,
-) -> Result<String, StatusCode> {
-    let logs_request = ContainerLogsRequest {
-        container_id: id.clone(),
-        follow: false,
-        tail: Some(100),
-        timestamps: true,
-        since: None,
+/// Containers operated on at once by a batch request
+const BATCH_CONCURRENCY: usize = 5;
+
+/// Apply one action to many containers; a failure is reported in its result and the rest go on
+#[utoipa::path(
+    post,
+    path = "/api/v1/containers/batch",
+    tag = "containers",
+    request_body = ContainerBatchRequest,
+    responses(
+        (status = 200, description = "Result for each container", body = Vec<ContainerBatchResult>),
+        (status = 400, description = "No container IDs given", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+    ),
+)]
+async fn batch_containers(
+    _: RequireOperator,
+    env: Env,
+    Json(request): Json<ContainerBatchRequest>,
+) -> Result<Json<Vec<ContainerBatchResult>>, ApiError> {
+    if request.ids.is_empty() {
+        return Err(ApiError::from_status(StatusCode::BAD_REQUEST, "No container IDs given"));
+    }
+
+    let ContainerBatchRequest { action, ids, timeout, force } = request;
+    info!("Batch {:?} on {} containers in {}", action, ids.len(), env.name);
+
+    // `buffered` rather than `buffer_unordered` keeps results in request order
+    let results = futures::stream::iter(ids)
+        .map(|id| {
+            let env = env.clone();
+            let runtime = env.runtime.clone();
+            async move {
+                let outcome = match action {
+                    ContainerBatchAction::Start => match check_gpu_for_start(runtime.as_ref(), &id).await {
+                        Ok(()) => runtime.start_container(&id).await,
+                        Err(e) => Err(gpanel_core::Error::Conflict(e.body.message)),
+                    },
+                    ContainerBatchAction::Stop => runtime.stop_container(&id, timeout).await,
+                    ContainerBatchAction::Restart => runtime.restart_container(&id, timeout).await,
+                    ContainerBatchAction::Remove => {
+                        let container = runtime.get_container(&id).await;
+                        let removed = runtime.remove_container(&id, force, false).await;
+                        if let (Ok(container), Ok(_)) = (&container, &removed) {
+                            env.publish(ContainerEvent::for_container(container, ContainerEventAction::Removed));
+                        }
+                        removed
+                    }
+                };
+                if outcome.is_ok() && action != ContainerBatchAction::Remove {
+                    env.publish_status(&id).await;
+                }
+
+                match outcome {
+                    Ok(_) => ContainerBatchResult {
+                        message: format!("{:?} succeeded", action),
+                        id,
+                        success: true,
+                    },
+                    Err(e) => {
+                        error!("Batch {:?} failed for container {}: {}", action, id, e);
+                        ContainerBatchResult {
+                            message: format!("{:?} failed: {}", action, e),
+                            id,
+                            success: false,
+                        }
+                    }
+                }
+            }
+        })
+        .buffered(BATCH_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+
+    Ok(Json(results))
+}
+
+/// Query parameters for POST /api/v1/containers/prune
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ContainerPruneQuery {
+    /// Only prune containers older than this duration (e.g. `24h`, `7d`)
+    pub until: Option<String>,
+    /// Only prune containers carrying this label (`key` or `key=value`)
+    pub label: Option<String>,
+}
+
+/// Remove exited and dead containers; one that fails to go is logged and left out of the report
+#[utoipa::path(
+    post,
+    path = "/api/v1/containers/prune",
+    tag = "containers",
+    params(ContainerPruneQuery),
+    responses(
+        (status = 200, description = "Removed containers and reclaimed space", body = ContainerPruneReport),
+        (status = 400, description = "Invalid filter", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+    ),
+)]
+async fn prune_containers(
+    _: RequireOperator,
+    env: Env,
+    Query(query): Query<ContainerPruneQuery>,
+) -> Result<Json<ContainerPruneReport>, ApiError> {
+    let until = match query.until.as_deref() {
+        Some(until) => {
+            let age = util::parse_duration(until).ok_or_else(|| {
+                ApiError::from_status(StatusCode::BAD_REQUEST, format!("Invalid duration '{}'", until))
+            })?;
+            Some(chrono::Utc::now() - age)
+        }
+        None => None,
     };
+    let filter = ContainerPruneFilter { until, label: query.label };
 
-    match state.bolt_client.get_container_logs(logs_request).await {
-        Ok(logs) => Ok(logs),
-        Err(e) => {
-            error!("Failed to get logs for container {}: {}", id, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+    let containers = env.runtime.list_containers(None).await.map_err(|e| {
+        error!("Failed to list containers for prune: {}", e);
+        ApiError::runtime(&e, format!("Failed to list containers: {}", e))
+    })?;
+    let candidates = containers.into_iter().filter(|container| filter.matches(container));
+
+    let removed: Vec<String> = futures::stream::iter(candidates)
+        .map(|container| {
+            let env = env.clone();
+            async move {
+                match env.runtime.remove_container(&container.id, false, false).await {
+                    Ok(_) => {
+                        env.publish(ContainerEvent::for_container(&container, ContainerEventAction::Removed));
+                        Some(container.id)
+                    }
+                    Err(e) => {
+                        warn!("Prune could not remove container {}: {}", container.id, e);
+                        None
+                    }
+                }
+            }
+        })
+        .buffer_unordered(BATCH_CONCURRENCY)
+        .filter_map(futures::future::ready)
+        .collect()
+        .await;
+
+    info!("Pruned {} containers", removed.len());
+    Ok(Json(ContainerPruneReport {
+        reclaimed_count: removed.len(),
+        removed,
+    }))
+}
+
+/// Seconds between samples on the stats stream unless `interval` is given
+const DEFAULT_STATS_INTERVAL: u64 = 2;
+
+/// Longest a wait request is held open before it gives up with 408
+const MAX_WAIT: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+/// Query parameters for POST /api/v1/containers/:id/wait
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WaitQuery {
+    pub condition: Option<WaitCondition>,
+}
+
+/// Block until a container stops, exits again or is removed, and return its exit code
+#[utoipa::path(
+    post,
+    path = "/api/v1/containers/{id}/wait",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name"), WaitQuery),
+    responses(
+        (status = 200, description = "Exit code once the condition is met", body = ContainerWaitResponse),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+        (status = 408, description = "Condition not met in time", body = ApiErrorResponse),
+    ),
+)]
+async fn wait_container(
+    env: Env,
+    Path(id): Path<String>,
+    Query(query): Query<WaitQuery>,
+) -> Result<Json<ContainerWaitResponse>, ApiError> {
+    let condition = query.condition.unwrap_or_default();
+
+    env
+        .runtime
+        .get_container(&id)
+        .await
+        .map_err(|_| ApiError::not_found("CONTAINER", format!("Container '{}' not found", id)))?;
+
+    // Only the runtime handle is used here; no agent locks are held across the wait
+    match tokio::time::timeout(MAX_WAIT, env.runtime.wait_container(&id, condition)).await {
+        Ok(Ok(status_code)) => {
+            info!("Container {} reached {}: exit code {}", id, condition.as_str(), status_code);
+            Ok(Json(ContainerWaitResponse { status_code }))
         }
+        Ok(Err(e)) => {
+            error!("Failed to wait for container {}: {}", id, e);
+            Err(ApiError::from_status(StatusCode::BAD_GATEWAY, format!("Failed to wait for container: {}", e)))
+        }
+        Err(_) => Err(ApiError::from_status(
+            StatusCode::REQUEST_TIMEOUT,
+            format!(
+                "Container '{}' did not reach '{}' within {} seconds",
+                id,
+                condition.as_str(),
+                MAX_WAIT.as_secs()
+            ),
+        )),
+    }
+}
+
+/// Query parameters for GET /api/v1/containers/:id/top
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TopQuery {
+    /// Arguments for `ps` inside the container, e.g. `aux`
+    pub ps_args: Option<String>,
+}
+
+/// Check `ps` arguments before handing them to Bolt: flags and column lists only
+fn valid_ps_args(args: &str) -> bool {
+    args.len() <= 64 && args.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | ',' | '=' | '%'))
+}
+
+/// List the processes running in a container
+#[utoipa::path(
+    get,
+    path = "/api/v1/containers/{id}/top",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name"), TopQuery),
+    responses(
+        (status = 200, description = "Processes in the container", body = ContainerTop),
+        (status = 400, description = "Invalid ps arguments", body = ApiErrorResponse),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+        (status = 409, description = "Container is not running", body = ApiErrorResponse),
+    ),
+)]
+async fn top_container(
+    env: Env,
+    Path(id): Path<String>,
+    Query(query): Query<TopQuery>,
+) -> Result<Json<ContainerTop>, ApiError> {
+    let ps_args = query.ps_args.as_deref().map(str::trim).filter(|args| !args.is_empty());
+    if let Some(args) = ps_args.filter(|args| !valid_ps_args(args)) {
+        return Err(ApiError::from_status(StatusCode::BAD_REQUEST, format!("Invalid ps arguments '{}'", args)));
+    }
+
+    let container = env
+        .runtime
+        .get_container(&id)
+        .await
+        .map_err(|_| ApiError::not_found("CONTAINER", format!("Container '{}' not found", id)))?;
+
+    if !matches!(container.status, ContainerStatus::Running | ContainerStatus::Paused) {
+        return Err(ApiError::from_status(StatusCode::CONFLICT, format!("Container '{}' is not running", id)));
     }
+
+    let top = env.runtime.top_container(&id, ps_args).await.map_err(|e| {
+        error!("Failed to list processes in container {}: {}", id, e);
+        ApiError::runtime(&e, format!("Failed to list processes: {}", e))
+    })?;
+
+    Ok(Json(top))
+}
+
+/// Query parameters for GET /api/v1/containers/:id/stats/stream
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatsStreamQuery {
+    /// Seconds between samples, 1 to 60
+    pub interval: Option<u64>,
 }
 
 /// Get container stats
+#[utoipa::path(
+    get,
+    path = "/api/v1/containers/{id}/stats",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name")),
+    responses(
+        (status = 200, description = "Current resource usage", body = ContainerStats),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+        (status = 409, description = "Container is not running", body = ApiErrorResponse),
+    ),
+)]
 async fn get_container_stats(
-    State(state): State<AppState>,
+    env: Env,
     Path(id): Path<String>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    // For mock implementation, return mock stats
-    let mock_stats = serde_json::json!({
-        "container_id": id,
-        "timestamp": chrono::Utc::now(),
-        "cpu_percent": 15.2,
-        "memory_usage": 134217728, // 128MB
-        "memory_limit": 536870912, // 512MB
-        "network_rx": 1024000,
-        "network_tx": 2048000,
-        "block_read": 512000,
-        "block_write": 256000,
-        "pid_count": 12
-    });
+) -> Result<Json<ContainerStats>, ApiError> {
+    let container = env
+        .runtime
+        .get_container(&id)
+        .await
+        .map_err(|_| ApiError::not_found("CONTAINER", format!("Container '{}' not found", id)))?;
+
+    if !matches!(container.status, ContainerStatus::Running | ContainerStatus::Paused) {
+        return Err(ApiError::from_status(StatusCode::CONFLICT, format!("Container '{}' is not running", id)));
+    }
+
+    let stats = env.runtime.get_container_stats(&id).await.map_err(|e| {
+        error!("Failed to get stats for container {}: {}", id, e);
+        ApiError::runtime(&e, format!("Failed to get container stats: {}", e))
+    })?;
+
+    Ok(Json(stats))
+}
+
+/// Stream `ContainerStats` samples as server-sent events. Sampling stops when the client
+/// disconnects; an `end` event is sent if the container stops or its stats become unavailable.
+#[utoipa::path(
+    get,
+    path = "/api/v1/containers/{id}/stats/stream",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name"), StatsStreamQuery),
+    responses(
+        (status = 200, description = "Server-sent events, one `ContainerStats` per sample", content_type = "text/event-stream", body = ContainerStats),
+        (status = 400, description = "Interval out of range", body = ApiErrorResponse),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+        (status = 409, description = "Container is not running", body = ApiErrorResponse),
+    ),
+)]
+async fn stream_container_stats(
+    env: Env,
+    Path(id): Path<String>,
+    Query(query): Query<StatsStreamQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let interval = query.interval.unwrap_or(DEFAULT_STATS_INTERVAL);
+    if !(1..=60).contains(&interval) {
+        return Err(ApiError::from_status(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid interval {}: expected 1 to 60 seconds", interval),
+        ));
+    }
+
+    let container = env
+        .runtime
+        .get_container(&id)
+        .await
+        .map_err(|_| ApiError::not_found("CONTAINER", format!("Container '{}' not found", id)))?;
+
+    if !matches!(container.status, ContainerStatus::Running | ContainerStatus::Paused) {
+        return Err(ApiError::from_status(StatusCode::CONFLICT, format!("Container '{}' is not running", id)));
+    }
+
+    let ticker = tokio::time::interval(std::time::Duration::from_secs(interval));
+    let samples = futures::stream::unfold(
+        Some((ticker, env.runtime.clone(), id)),
+        |sampler| async move {
+            let (mut ticker, runtime, id) = sampler?;
+            ticker.tick().await;
+
+            let event = match runtime.get_container_stats(&id).await {
+                Ok(stats) => match Event::default().json_data(&stats) {
+                    Ok(event) => event,
+                    Err(e) => {
+                        error!("Failed to encode stats for container {}: {}", id, e);
+                        return None;
+                    }
+                },
+                Err(e) => {
+                    info!("Ending stats stream for container {}: {}", id, e);
+                    return Some((Ok(Event::default().event("end").data(e.to_string())), None));
+                }
+            };
+            Some((Ok(event), Some((ticker, runtime, id))))
+        },
+    );
+
+    Ok(Sse::new(samples).keep_alive(KeepAlive::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::agent;
+    use axum::http::Method;
+
+    #[tokio::test]
+    async fn stats_are_served_for_running_containers_only() {
+        let agent = agent().await;
+
+        let (status, body) = agent
+            .request(Method::GET, "/api/v1/containers/mock_web_server_001/stats", None)
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        let stats: ContainerStats = serde_json::from_value(body).unwrap();
+        assert_eq!(stats.container_id, "mock_web_server_001");
+
+        let (status, body) = agent
+            .request(Method::GET, "/api/v1/containers/mock_database_003/stats", None)
+            .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(body["error"]["message"].as_str().unwrap().contains("not running"));
+
+        let (status, body) = agent.request(Method::GET, "/api/v1/containers/missing/stats", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "CONTAINER_NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn containers_are_paused_and_resumed() {
+        let agent = agent().await;
+        let runtime = agent.state.environments.default_env().runtime;
+        let container = |action: &str| format!("/api/v1/containers/mock_web_server_001/{}", action);
+
+        let (status, body) = agent.request(Method::POST, &container("pause"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["success"], true);
+        assert_eq!(runtime.get_container("mock_web_server_001").await.unwrap().status, ContainerStatus::Paused);
+
+        let (status, body) = agent.request(Method::POST, &container("pause"), None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(body["error"]["message"].as_str().unwrap().contains("not running"));
+
+        let (status, _) = agent.request(Method::POST, &container("unpause"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(runtime.get_container("mock_web_server_001").await.unwrap().status, ContainerStatus::Running);
+
+        let (status, body) = agent.request(Method::POST, &container("unpause"), None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(body["error"]["message"].as_str().unwrap().contains("not paused"));
+
+        let (status, body) = agent.request(Method::POST, "/api/v1/containers/mock_database_003/pause", None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"]["code"], "CONFLICT");
+        assert!(matches!(
+            runtime.get_container("mock_database_003").await.unwrap().status,
+            ContainerStatus::Exited { .. }
+        ));
+
+        for action in ["pause", "unpause"] {
+            let uri = format!("/api/v1/containers/missing/{}", action);
+            let (status, body) = agent.request(Method::POST, &uri, None).await;
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            assert_eq!(body["error"]["code"], "CONTAINER_NOT_FOUND");
+        }
+    }
+
+    #[tokio::test]
+    async fn container_operations_answer_failures_with_error_statuses() {
+        let agent = agent().await;
+        let operation = |action: &str| serde_json::json!({ "action": action });
+
+        for action in ["start", "stop", "restart"] {
+            let uri = format!("/api/v1/containers/missing/{}", action);
+            let (status, body) = agent.request(Method::POST, &uri, Some(operation(action))).await;
+            assert_eq!(status, StatusCode::NOT_FOUND, "{}", action);
+            assert_eq!(body["error"]["code"], "CONTAINER_NOT_FOUND");
+            assert_eq!(body["error"]["message"], "Container 'missing' not found");
+        }
+        let (status, body) = agent
+            .request(Method::DELETE, "/api/v1/containers/missing", Some(operation("remove")))
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "CONTAINER_NOT_FOUND");
+
+        // A running container is only removed when forced
+        let (status, body) = agent
+            .request(Method::DELETE, "/api/v1/containers/mock_web_server_001", Some(operation("remove")))
+            .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"]["code"], "CONTAINER_ERROR");
+        assert!(body["error"]["message"].as_str().unwrap().starts_with("Failed to remove container"));
+
+        let (status, body) = agent
+            .request(Method::POST, "/api/v1/containers/mock_web_server_001/stop", Some(operation("stop")))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            serde_json::json!({ "success": true, "message": "Container mock_web_server_001 stopped successfully" })
+        );
+        let (status, body) = agent
+            .request(Method::DELETE, "/api/v1/containers/mock_web_server_001", Some(operation("remove")))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["success"], true);
+    }
+
+    #[tokio::test]
+    async fn containers_are_created_from_compact_port_strings() {
+        let agent = agent().await;
+        let request = |port: &str| {
+            serde_json::json!({
+                "name": "compact",
+                "image": "nginx:latest",
+                "ports": [port],
+                "volumes": ["/srv/www:/usr/share/nginx/html:ro"],
+                "networks": [],
+                "env": {},
+                "labels": {},
+                "gaming_config": null,
+                "gpu_allocation": null,
+                "restart_policy": "no",
+            })
+        };
+
+        // The mock web server holds host port 8080
+        let (status, body) = agent.request(Method::POST, "/api/v1/containers", Some(request("8080:80"))).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"]["code"], "PORT_CONFLICT");
+        let (status, _) = agent.request(Method::POST, "/api/v1/containers", Some(request("127.0.0.1:8081:80/udp"))).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, _) = agent.request(Method::POST, "/api/v1/containers", Some(request("8000-8010:80"))).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn containers_resolve_by_id_or_unique_prefix() {
+        let agent = agent().await;
+
+        let (status, body) = agent.request(Method::GET, "/api/v1/containers/mock_web_server_001", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["id"], "mock_web_server_001");
+
+        let (status, body) = agent.request(Method::GET, "/api/v1/containers/mock_data", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["id"], "mock_database_003");
+
+        let (status, body) = agent.request(Method::GET, "/api/v1/containers/mock_", None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"]["code"], "AMBIGUOUS_CONTAINER_ID");
+        assert_eq!(body["error"]["details"]["candidates"].as_array().unwrap().len(), 3);
+
+        let (status, body) = agent.request(Method::GET, "/api/v1/containers/nothing_like_it", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "CONTAINER_NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn not_found_and_invalid_requests_get_error_envelopes() {
+        use axum::http::Request;
+
+        let agent = agent().await;
+
+        let (status, body) = agent.request(Method::GET, "/api/v1/containers/missing", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            body,
+            serde_json::json!({ "error": { "code": "CONTAINER_NOT_FOUND", "message": "Container 'missing' not found" } })
+        );
+        let (status, body) = agent.request(Method::GET, "/api/v1/registries/gone/repositories", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "REGISTRY_NOT_FOUND");
+
+        let (status, body) = agent.request(Method::GET, "/api/v1/containers?limit=0", None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "INVALID_REQUEST");
+        assert_eq!(body["error"]["message"], "limit must be at least 1");
+
+        // Rejections from the extractors are wrapped in the same envelope
+        let malformed = Request::post("/api/v1/images/pull")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from("{"))
+            .unwrap();
+        let (status, bytes) = agent.send(malformed).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["error"]["code"], "INVALID_REQUEST");
+        let incomplete = serde_json::json!({ "registry": "hub", "repository": "library/nginx" });
+        let (status, body) = agent.request(Method::POST, "/api/v1/images/pull", Some(incomplete)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["error"]["code"], "VALIDATION_FAILED");
+        assert!(body["error"]["message"].as_str().unwrap().contains("tag"));
+
+        let invalid = serde_json::json!({
+            "name": "-web",
+            "image": "nginx:latest",
+            "ports": [],
+            "volumes": [],
+            "networks": [],
+            "env": {},
+            "labels": {},
+            "gaming_config": null,
+            "gpu_allocation": null,
+            "restart_policy": "no",
+        });
+        let (status, body) = agent.request(Method::POST, "/api/v1/containers", Some(invalid)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["error"]["code"], "VALIDATION_FAILED");
+        assert_eq!(body["error"]["details"]["errors"][0]["field"], "name");
+    }
+
+    #[tokio::test]
+    async fn bolt_failures_are_answered_with_502() {
+        let bolt = Router::new()
+            .route("/ping", get(|| async { "OK" }))
+            .fallback(|| async { (StatusCode::INTERNAL_SERVER_ERROR, "runtime unavailable") });
+        let agent = crate::testing::agent_on_bolt(&crate::testing::serve_stub(bolt).await).await;
+
+        let (status, body) = agent.request(Method::GET, "/api/v1/containers", None).await;
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        assert_eq!(body["error"]["code"], "BOLT_ERROR");
+        assert!(body["error"]["message"].as_str().unwrap().starts_with("Failed to list containers"));
+
+        let (status, body) = agent.request(Method::GET, "/api/v1/containers/web", None).await;
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        assert_eq!(body["error"]["code"], "BOLT_ERROR");
+
+        let stop = serde_json::json!({ "action": "stop" });
+        let (status, body) = agent.request(Method::POST, "/api/v1/containers/web/stop", Some(stop)).await;
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        assert_eq!(body["error"]["code"], "BOLT_ERROR");
+        assert!(body["error"]["message"].as_str().unwrap().starts_with("Failed to stop container"));
+    }
+
+    /// A registry that lets anyone in, under `name`
+    async fn open_registry(agent: &crate::testing::TestAgent, name: &str) -> String {
+        stub_registry(agent, name, Router::new()).await
+    }
+
+    /// Add registry `name` to the agent, served by `routes` and open to anyone
+    async fn stub_registry(agent: &crate::testing::TestAgent, name: &str, routes: Router) -> String {
+        let url = crate::testing::serve_stub(routes.route("/v2/", get(|| async { "{}" }))).await;
+        agent.state.registry_manager.write().await.insert_registry(RegistryConfig {
+            name: name.to_string(),
+            url: url.clone(),
+            username: Some("ci".to_string()),
+            password: Some("secret".to_string()),
+            insecure: true,
+            page_size: None,
+            max_list_entries: None,
+            search_url: None,
+            ca_cert_path: None,
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn registries_are_edited_in_place_keeping_the_password() {
+        let agent = agent().await;
+        let url = open_registry(&agent, "mirror").await;
+
+        let edit = serde_json::json!({ "name": "cache", "url": url, "username": "ci", "insecure": true });
+        let (status, body) = agent.request(Method::PUT, "/api/v1/registries/mirror", Some(edit)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["name"], "cache");
+        assert_eq!(body["insecure"], true);
+        assert_eq!(body["has_auth"], true);
+        assert_eq!(body["status"], "ok");
+        assert!(body.get("password").is_none());
 
-    Ok(Json(mock_stats))
-}
\ No newline at end of file
+        let manager = agent.state.registry_manager.read().await;
+        assert!(manager.get_registry("mirror").is_none());
+        assert_eq!(manager.get_registry("cache").unwrap().config().password.as_deref(), Some("secret"));
+    }
+
+    #[tokio::test]
+    async fn registry_edits_are_validated() {
+        let agent = agent().await;
+        let url = open_registry(&agent, "mirror").await;
+        open_registry(&agent, "hub").await;
+        let edit = |name: &str, url: &str| serde_json::json!({ "name": name, "url": url, "insecure": false });
+
+        let (status, body) = agent.request(Method::PUT, "/api/v1/registries/mirror", Some(edit("hub", &url))).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"]["code"], "REGISTRY_EXISTS");
+
+        let (status, body) = agent
+            .request(Method::PUT, "/api/v1/registries/mirror", Some(edit("mirror", "registry.local")))
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "INVALID_REGISTRY_URL");
+
+        let (status, body) = agent.request(Method::PUT, "/api/v1/registries/gone", Some(edit("gone", &url))).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "REGISTRY_NOT_FOUND");
+
+        let (status, _) = agent
+            .request(Method::PUT, "/api/v1/registries/mirror", Some(edit("mirror", "http://127.0.0.1:1")))
+            .await;
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        let manager = agent.state.registry_manager.read().await;
+        assert_eq!(manager.get_registry("mirror").unwrap().config().url, url, "failed edits change nothing");
+    }
+
+    #[tokio::test]
+    async fn registries_are_added_and_removed_with_error_statuses() {
+        let agent = agent().await;
+        let url = crate::testing::serve_stub(Router::new().route("/v2/", get(|| async { "{}" }))).await;
+        let registry = |url: &str| serde_json::json!({ "name": "mirror", "url": url, "insecure": true });
+
+        let (status, body) = agent.request(Method::POST, "/api/v1/registries", Some(registry(&url))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, serde_json::json!({ "success": true, "message": "Registry 'mirror' added successfully" }));
+
+        let (status, body) = agent
+            .request(Method::POST, "/api/v1/registries", Some(registry("http://127.0.0.1:1")))
+            .await;
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        assert_eq!(body["error"]["code"], "UPSTREAM_ERROR");
+        assert!(body["error"]["message"].as_str().unwrap().starts_with("Failed to add registry"));
+
+        let (status, body) = agent.request(Method::DELETE, "/api/v1/registries/mirror", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["success"], true);
+        let (status, body) = agent.request(Method::DELETE, "/api/v1/registries/mirror", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "REGISTRY_NOT_FOUND");
+        assert_eq!(body["error"]["message"], "Registry 'mirror' not found");
+    }
+
+    #[tokio::test]
+    async fn images_are_not_pulled_from_unknown_registries() {
+        let agent = agent().await;
+        let pull = serde_json::json!({ "registry": "gone", "repository": "library/nginx", "tag": "latest" });
+
+        let (status, body) = agent.request(Method::POST, "/api/v1/images/pull", Some(pull)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "REGISTRY_NOT_FOUND");
+        assert_eq!(body["error"]["message"], "Registry 'gone' not found");
+    }
+
+    #[tokio::test]
+    async fn registry_images_are_deleted_by_tag() {
+        use axum::routing::head;
+
+        let agent = agent().await;
+        let manifest = || async { ([("Docker-Content-Digest", "sha256:v1")], "") };
+        let routes = Router::new()
+            .route("/v2/app/manifests/v1", head(manifest))
+            .route("/v2/app/manifests/sha256:v1", delete(|| async { StatusCode::ACCEPTED }));
+        stub_registry(&agent, "mirror", routes).await;
+        let locked = Router::new()
+            .route("/v2/app/manifests/v1", head(manifest))
+            .route("/v2/app/manifests/sha256:v1", delete(|| async { StatusCode::METHOD_NOT_ALLOWED }));
+        stub_registry(&agent, "locked", locked).await;
+
+        let (status, body) = agent
+            .request(Method::DELETE, "/api/v1/registries/mirror/repositories/app/tags/v1", None)
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["digest"], "sha256:v1");
+
+        let (status, body) = agent
+            .request(Method::DELETE, "/api/v1/registries/mirror/repositories/app/tags/v2", None)
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "IMAGE_NOT_FOUND");
+
+        let (status, body) = agent
+            .request(Method::DELETE, "/api/v1/registries/locked/repositories/app/tags/v1", None)
+            .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"]["code"], "REGISTRY_DELETE_DISABLED");
+
+        agent.state.registry_manager.write().await.insert_registry(RegistryConfig {
+            name: "hub".to_string(),
+            url: "https://registry-1.docker.io".to_string(),
+            username: None,
+            password: None,
+            insecure: false,
+            page_size: None,
+            max_list_entries: None,
+            search_url: None,
+            ca_cert_path: None,
+        });
+        let (status, body) = agent
+            .request(Method::DELETE, "/api/v1/registries/hub/repositories/library%2Fnginx/tags/latest", None)
+            .await;
+        assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+        assert_eq!(body["error"]["code"], "REGISTRY_DELETE_UNSUPPORTED");
+    }
+
+    #[tokio::test]
+    async fn registry_images_are_retagged_by_their_manifest() {
+        use axum::{http::HeaderMap, routing::put};
+        use std::sync::{Arc, Mutex};
+
+        let agent = agent().await;
+        let media_type = "application/vnd.oci.image.index.v1+json";
+        let manifest = r#"{"schemaVersion":2,"manifests":[]}"#;
+        let pushed = Arc::new(Mutex::new(Vec::new()));
+        let recorded = pushed.clone();
+        let routes = Router::new()
+            .route(
+                "/v2/app/manifests/staging",
+                get(move || async move { ([("Content-Type", media_type), ("Docker-Content-Digest", "sha256:staging")], manifest) }),
+            )
+            .route(
+                "/v2/app/manifests/prod",
+                put(move |headers: HeaderMap, body: String| async move {
+                    let content_type = headers.get("content-type").and_then(|value| value.to_str().ok()).map(str::to_string);
+                    recorded.lock().unwrap().push((content_type, body));
+                    StatusCode::CREATED
+                }),
+            );
+        stub_registry(&agent, "mirror", routes).await;
+
+        let retag = |source: &str, target: &str| serde_json::json!({ "source": source, "target": target });
+        let (status, body) = agent
+            .request(Method::POST, "/api/v1/registries/mirror/repositories/app/tags", Some(retag("staging", "prod")))
+            .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body, serde_json::json!({ "repository": "app", "tag": "prod", "digest": "sha256:staging" }));
+        assert_eq!(*pushed.lock().unwrap(), [(Some(media_type.to_string()), manifest.to_string())]);
+
+        let (status, body) = agent
+            .request(Method::POST, "/api/v1/registries/mirror/repositories/app/tags", Some(retag("missing", "prod")))
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "IMAGE_NOT_FOUND");
+
+        let (status, _) = agent
+            .request(Method::POST, "/api/v1/registries/mirror/repositories/app/tags", Some(retag("staging", "-prod")))
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = agent
+            .request(Method::POST, "/api/v1/registries/mirror/repositories/app/tags/prod", Some(retag("staging", "prod")))
+            .await;
+        assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(pushed.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn registry_stats_are_counted_as_a_job_then_cached() {
+        let agent = agent().await;
+        let manifest = serde_json::json!({
+            "schemaVersion": 2,
+            "mediaType": "application/vnd.oci.image.manifest.v1+json",
+            "config": { "mediaType": "application/vnd.oci.image.config.v1+json", "size": 2, "digest": "sha256:config" },
+            "layers": [
+                { "mediaType": "application/vnd.oci.image.layer.v1.tar", "size": 10, "digest": "sha256:base" },
+                { "mediaType": "application/vnd.oci.image.layer.v1.tar", "size": 20, "digest": "sha256:app" },
+            ],
+        })
+        .to_string();
+        let routes = Router::new()
+            .route("/v2/_catalog", get(|| async { r#"{"repositories":["app"]}"# }))
+            .route("/v2/app/tags/list", get(|| async { r#"{"name":"app","tags":["v1","v2"]}"# }))
+            .route("/v2/app/manifests/:tag", get(move || async move { manifest }));
+        stub_registry(&agent, "mirror", routes).await;
+
+        let (status, body) = agent.request(Method::GET, "/api/v1/registries/mirror/stats", None).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let id = body["job_id"].as_str().unwrap().to_string();
+        let job = tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            loop {
+                let job = agent.state.jobs.get(&id).unwrap();
+                if job.status.is_finished() {
+                    return job;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(job.status, gpanel_core::JobStatus::Succeeded, "{}", job.message);
+        assert_eq!(job.kind, JobKind::RegistryStats);
+
+        let (status, body) = agent.request(Method::GET, "/api/v1/registries/mirror/stats", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            (&body["repositories"], &body["tags"], &body["unique_layers"], &body["total_bytes"]),
+            (&serde_json::json!(1), &serde_json::json!(2), &serde_json::json!(2), &serde_json::json!(30))
+        );
+
+        let (status, _) = agent.request(Method::GET, "/api/v1/registries/mirror/stats?refresh=true", None).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let (status, _) = agent.request(Method::GET, "/api/v1/registries/missing/stats", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    /// The manifest of a one-layer image, and a registry serving it as `app:v1`
+    fn stub_source_image() -> (String, Router) {
+        use sha2::{Digest, Sha256};
+
+        let digest = |content: &str| format!("sha256:{:x}", Sha256::digest(content.as_bytes()));
+        let (config, layer) = (r#"{"os":"linux"}"#, "layer");
+        let manifest = serde_json::json!({
+            "schemaVersion": 2,
+            "mediaType": "application/vnd.oci.image.manifest.v1+json",
+            "config": { "mediaType": "application/vnd.oci.image.config.v1+json", "size": config.len(), "digest": digest(config) },
+            "layers": [{ "mediaType": "application/vnd.oci.image.layer.v1.tar", "size": layer.len(), "digest": digest(layer) }],
+        })
+        .to_string();
+
+        let served = manifest.clone();
+        let blobs = [(digest(config), config), (digest(layer), layer)];
+        let source = Router::new()
+            .route(
+                "/v2/app/manifests/v1",
+                get(move || async move { ([("Content-Type", "application/vnd.oci.image.manifest.v1+json")], served) }),
+            )
+            .route(
+                "/v2/app/blobs/:digest",
+                get(move |Path(wanted): Path<String>| async move {
+                    blobs.iter().find(|(digest, _)| *digest == wanted).map(|(_, content)| *content).unwrap_or_default()
+                }),
+            );
+        (manifest, source)
+    }
+
+    /// A registry accepting blob uploads and manifests for `promoted:stable`, recording
+    /// the manifests pushed
+    fn stub_destination() -> (std::sync::Arc<std::sync::Mutex<Vec<String>>>, Router) {
+        use axum::routing::head;
+        use std::sync::{Arc, Mutex};
+
+        let pushed = Arc::new(Mutex::new(Vec::new()));
+        let recorded = pushed.clone();
+        let destination = Router::new()
+            .route("/v2/promoted/blobs/:digest", head(|| async { StatusCode::NOT_FOUND }))
+            .route(
+                "/v2/promoted/blobs/uploads/",
+                post(|| async { (StatusCode::ACCEPTED, [("Location", "/v2/promoted/blobs/uploads/session")]) }),
+            )
+            .route("/v2/promoted/blobs/uploads/session", put(|| async { StatusCode::CREATED }))
+            .route(
+                "/v2/promoted/manifests/stable",
+                put(move |body: String| async move {
+                    recorded.lock().unwrap().push(body);
+                    StatusCode::CREATED
+                }),
+            );
+        (pushed, destination)
+    }
+
+    #[tokio::test]
+    async fn images_are_copied_between_registries_as_a_job() {
+        let agent = agent().await;
+        let (manifest, source) = stub_source_image();
+        stub_registry(&agent, "local", source).await;
+        let (pushed, destination) = stub_destination();
+        stub_registry(&agent, "remote", destination).await;
+
+        let copy = serde_json::json!({
+            "source_registry": "local", "source_repository": "app", "source_tag": "v1",
+            "destination_registry": "remote", "destination_repository": "promoted", "destination_tag": "stable",
+        });
+        let (status, body) = agent.request(Method::POST, "/api/v1/images/copy", Some(copy.clone())).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let id = body["job_id"].as_str().unwrap().to_string();
+
+        let job = tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            loop {
+                let job = agent.state.jobs.get(&id).unwrap();
+                if job.status.is_finished() {
+                    return job;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .expect("copy did not finish");
+        assert_eq!(job.status, gpanel_core::JobStatus::Succeeded, "{}", job.message);
+        assert_eq!(job.kind, JobKind::ImageCopy);
+        assert!(job.message.contains("2 uploaded"), "{}", job.message);
+        assert_eq!(job.progress.unwrap().total_layers, 2);
+        assert_eq!(*pushed.lock().unwrap(), [manifest]);
+
+        let mut missing = copy;
+        missing["destination_registry"] = "gone".into();
+        let (status, body) = agent.request(Method::POST, "/api/v1/images/copy", Some(missing)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "REGISTRY_NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn inspected_searches_merge_tags_of_the_same_image() {
+        let agent = agent().await;
+        let (manifest, source) = stub_source_image();
+        let source = source
+            .route("/v2/_catalog", get(|| async { r#"{"repositories":["app"]}"# }))
+            .route("/v2/app/tags/list", get(|| async { r#"{"name":"app","tags":["stable","v1"]}"# }))
+            .route(
+                "/v2/app/manifests/stable",
+                get(move || async move { ([("Content-Type", "application/vnd.oci.image.manifest.v1+json")], manifest) }),
+            );
+        stub_registry(&agent, "local", source).await;
+
+        let search = |inspect: bool| serde_json::json!({ "query": "app", "registry": "local", "inspect": inspect });
+        let (status, body) = agent.request(Method::POST, "/api/v1/images/search", Some(search(false))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["groups"][0]["tags"].as_array().unwrap().len(), 2);
+        assert_eq!(body["groups"][0]["images"], serde_json::json!([]));
+
+        let (status, body) = agent.request(Method::POST, "/api/v1/images/search", Some(search(true))).await;
+        assert_eq!(status, StatusCode::OK);
+        let images = body["groups"][0]["images"].as_array().unwrap();
+        assert_eq!(images.len(), 1, "{}", body);
+        let tags: Vec<&str> = images[0]["references"].as_array().unwrap().iter().map(|r| r["tag"].as_str().unwrap()).collect();
+        assert_eq!(tags, ["v1", "stable"]);
+    }
+
+    #[tokio::test]
+    async fn local_tags_are_validated_and_not_stolen_unless_forced() {
+        let agent = agent().await;
+        let tag = |id: &str, body: serde_json::Value| {
+            let uri = format!("/api/v1/images/{}/tag", id);
+            let agent = &agent;
+            async move { agent.request(Method::POST, &uri, Some(body)).await }
+        };
+
+        for (repository, tag_name) in [("Nginx", "v1"), ("nginx", ".v1"), ("nginx", ""), ("nginx//web", "v1")] {
+            let (status, _) = tag("nginx:latest", serde_json::json!({ "repository": repository, "tag": tag_name })).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}:{}", repository, tag_name);
+        }
+        let (status, body) = tag("missing:latest", serde_json::json!({ "repository": "a", "tag": "b" })).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "IMAGE_NOT_FOUND");
+
+        let (status, _) = tag("nginx:latest", serde_json::json!({ "repository": "web", "tag": "prod" })).await;
+        assert_eq!(status, StatusCode::OK);
+        // Tagging the same image again is not a conflict
+        let (status, _) = tag("nginx:latest", serde_json::json!({ "repository": "web", "tag": "prod" })).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, body) = tag("postgres:15", serde_json::json!({ "repository": "web", "tag": "prod" })).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"]["code"], "TAG_EXISTS");
+        let (status, _) = tag("postgres:15", serde_json::json!({ "repository": "web", "tag": "prod", "force": true })).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let runtime = agent.state.environments.default_env().runtime;
+        let images = runtime.list_images().await.unwrap();
+        let holder = images.iter().find(|image| image.repo_tags.iter().any(|t| t == "web:prod")).unwrap();
+        assert!(holder.repo_tags.iter().any(|t| t == "postgres:15"));
+    }
+
+    #[tokio::test]
+    async fn tagged_images_are_pushed_from_the_local_store() {
+        let agent = agent().await;
+        let (manifest, source) = stub_source_image();
+        stub_registry(&agent, "local", source).await;
+        let (pushed, destination) = stub_destination();
+        stub_registry(&agent, "remote", destination).await;
+
+        // Pulled through GhostPanel, so its manifest and layers are stored locally
+        let blob_dir = std::path::Path::new(&agent.state.config.data_dir).join("blobs");
+        let client = agent.state.registry_manager.read().await.get_registry("local").cloned().unwrap();
+        client.pull_image("app", "v1", &blob_dir).await.unwrap();
+        agent.state.environments.default_env().runtime.pull_image("app:v1").await.unwrap();
+
+        let request = serde_json::json!({ "repository": "promoted", "tag": "stable", "push_registry": "remote" });
+        let (status, body) = agent.request(Method::POST, "/api/v1/images/app:v1/tag", Some(request.clone())).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert!(body["message"].as_str().unwrap().contains("pushed to remote"));
+        assert_eq!(*pushed.lock().unwrap(), [manifest]);
+
+        // Tagged locally first, even when the push cannot happen
+        let mut missing = request.clone();
+        missing["tag"] = "next".into();
+        missing["push_registry"] = "gone".into();
+        let (status, body) = agent.request(Method::POST, "/api/v1/images/app:v1/tag", Some(missing)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "REGISTRY_NOT_FOUND");
+        let images = agent.state.environments.default_env().runtime.list_images().await.unwrap();
+        assert!(images.iter().any(|image| image.repo_tags.iter().any(|t| t == "promoted:next")));
+
+        // Images Bolt pulled on its own have nothing stored to push
+        let mut unstored = request;
+        unstored["tag"] = "web".into();
+        let (status, body) = agent.request(Method::POST, "/api/v1/images/nginx:latest/tag", Some(unstored)).await;
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        assert!(body["error"]["message"].as_str().unwrap().contains("not pulled through GhostPanel"), "{}", body);
+    }
+
+    #[tokio::test]
+    async fn exported_images_are_imported_into_another_registry() {
+        use axum::{http::Request, routing::head};
+        use sha2::{Digest, Sha256};
+        use std::sync::{Arc, Mutex};
+
+        let digest = |content: &str| format!("sha256:{:x}", Sha256::digest(content.as_bytes()));
+        let (config, layer) = (r#"{"os":"linux"}"#, "layer");
+        let manifest = serde_json::json!({
+            "schemaVersion": 2,
+            "mediaType": "application/vnd.oci.image.manifest.v1+json",
+            "config": { "mediaType": "application/vnd.oci.image.config.v1+json", "size": config.len(), "digest": digest(config) },
+            "layers": [{ "mediaType": "application/vnd.oci.image.layer.v1.tar", "size": layer.len(), "digest": digest(layer) }],
+        })
+        .to_string();
+        let manifest_digest = digest(&manifest);
+
+        let agent = agent().await;
+        let (served, served_digest) = (manifest.clone(), manifest_digest.clone());
+        let blobs = [(digest(config), config), (digest(layer), layer)];
+        let source = Router::new()
+            .route(
+                "/v2/team/app/manifests/v1",
+                get(move || async move {
+                    (
+                        [("Content-Type", "application/vnd.oci.image.manifest.v1+json"), ("Docker-Content-Digest", served_digest.as_str())],
+                        served.clone(),
+                    )
+                        .into_response()
+                }),
+            )
+            .route("/v2/team/app/manifests/missing", get(|| async { StatusCode::NOT_FOUND }))
+            .route(
+                "/v2/team/app/blobs/:digest",
+                get(move |Path(wanted): Path<String>| async move {
+                    blobs.iter().find(|(digest, _)| *digest == wanted).map(|(_, content)| *content).unwrap_or_default()
+                }),
+            );
+        stub_registry(&agent, "local", source).await;
+
+        let pushed = Arc::new(Mutex::new(Vec::new()));
+        let recorded = pushed.clone();
+        let destination = Router::new()
+            .route("/v2/offline/blobs/:digest", head(|| async { StatusCode::NOT_FOUND }))
+            .route(
+                "/v2/offline/blobs/uploads/",
+                post(|| async { (StatusCode::ACCEPTED, [("Location", "/v2/offline/blobs/uploads/session")]) }),
+            )
+            .route("/v2/offline/blobs/uploads/session", put(|| async { StatusCode::CREATED }))
+            .route(
+                "/v2/offline/manifests/v1",
+                put(move |body: String| async move {
+                    recorded.lock().unwrap().push(body);
+                    StatusCode::CREATED
+                }),
+            );
+        stub_registry(&agent, "airgap", destination).await;
+
+        let export = Request::builder()
+            .uri("/api/v1/registries/local/repositories/team/app/tags/v1/export")
+            .body(Body::empty())
+            .unwrap();
+        let (status, archive) = agent.send(export).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(archive.len() % 512, 0);
+
+        let import = Request::builder()
+            .method(Method::POST)
+            .uri("/api/v1/images/import?registry=airgap&repository=offline")
+            .header("Content-Type", "application/x-tar")
+            .body(Body::from(archive.clone()))
+            .unwrap();
+        let (status, body) = agent.send(import).await;
+        assert_eq!(status, StatusCode::CREATED, "{}", String::from_utf8_lossy(&body));
+        let result: ImageImportResult = serde_json::from_slice(&body).unwrap();
+        assert_eq!((result.tag.as_str(), result.digest.as_str(), result.blobs_uploaded), ("v1", manifest_digest.as_str(), 2));
+        assert_eq!(*pushed.lock().unwrap(), [manifest]);
+
+        let (status, body) = agent
+            .request(Method::GET, "/api/v1/registries/local/repositories/team/app/tags/missing/export", None)
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "IMAGE_NOT_FOUND");
+
+        let truncated = Request::builder()
+            .method(Method::POST)
+            .uri("/api/v1/images/import?registry=airgap&repository=offline")
+            .body(Body::from(archive.slice(..1000)))
+            .unwrap();
+        let (status, body) = agent.send(truncated).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&body).unwrap()["error"]["code"], "INVALID_IMAGE_ARCHIVE");
+
+        let (status, _) = agent
+            .request(Method::POST, "/api/v1/images/import?registry=airgap&repository=Offline", None)
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn flags_override_the_environment_which_overrides_the_file() {
+        let mut config = GhostPanelConfig::from_toml(
+            r#"
+            agent_port = 9000
+            data_dir = "/srv/file"
+            bolt_api_url = "http://bolt.file:7000"
+            "#,
+        )
+        .unwrap();
+        config
+            .apply_env([
+                ("GPANEL_AGENT_PORT".to_string(), "9100".to_string()),
+                ("GPANEL_DATA_DIR".to_string(), "/srv/env".to_string()),
+            ])
+            .unwrap();
+        let args = Args::try_parse_from(["gpanel-agent", "--port", "9200", "--cert-path", "/tls/agent.crt"]).unwrap();
+        args.apply(&mut config);
+
+        assert_eq!(config.agent_port, 9200, "flag wins over env and file");
+        assert_eq!(config.data_dir, "/srv/env", "env wins over file");
+        assert_eq!(config.bolt_api_url, "http://bolt.file:7000", "file wins over default");
+        assert_eq!(config.web_port, GhostPanelConfig::default().web_port);
+        assert_eq!(config.tls_cert_path.as_deref(), Some("/tls/agent.crt"));
+        assert!(config.validate().is_err(), "a certificate without a key is refused");
+    }
+}
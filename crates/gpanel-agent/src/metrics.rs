@@ -0,0 +1,388 @@
+use axum::{extract::State, response::Json};
+use chrono::{DateTime, Utc};
+use gpanel_core::{
+    Container, ContainerCounts, ContainerMetric, ContainerStatus, GpuAggregate, GpuUsage,
+    MetricsSummary,
+};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use crate::environments::Env;
+use crate::AppState;
+
+/// How often container stats are sampled
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Entries in each top-N list
+const TOP_CONTAINERS: usize = 5;
+
+const MIB: u64 = 1024 * 1024;
+
+/// Cumulative network and block I/O counters
+#[derive(Debug, Clone, Copy, Default)]
+struct IoCounters {
+    network_rx: u64,
+    network_tx: u64,
+    disk_read: u64,
+    disk_write: u64,
+}
+
+/// Per-second rates derived from two consecutive counter readings
+#[derive(Debug, Clone, Copy, Default)]
+struct IoRates {
+    network_rx: f64,
+    network_tx: f64,
+    disk_read: f64,
+    disk_write: f64,
+}
+
+/// Latest sample for one container
+#[derive(Debug, Clone)]
+struct Sample {
+    name: String,
+    status: ContainerStatus,
+    cpu_percent: f64,
+    memory_used: u64,
+    memory_limit: u64,
+    counters: IoCounters,
+    rates: IoRates,
+    gpu: Option<GpuUsage>,
+    timestamp: DateTime<Utc>,
+}
+
+impl Sample {
+    fn from_container(container: &Container, previous: Option<&Sample>, now: DateTime<Utc>) -> Self {
+        // Only running or paused containers hold resources
+        let metrics = container
+            .performance_metrics
+            .as_ref()
+            .filter(|_| matches!(container.status, ContainerStatus::Running | ContainerStatus::Paused));
+
+        let counters = metrics
+            .map(|m| IoCounters {
+                network_rx: m.network_io.rx_bytes,
+                network_tx: m.network_io.tx_bytes,
+                disk_read: m.disk_io.read_bytes,
+                disk_write: m.disk_io.write_bytes,
+            })
+            .unwrap_or_default();
+
+        // Counters reset when a container restarts, so a decrease yields no rate
+        let rates = match previous {
+            Some(previous) if now > previous.timestamp => {
+                let secs = (now - previous.timestamp).num_milliseconds() as f64 / 1000.0;
+                let rate = |new: u64, old: u64| new.saturating_sub(old) as f64 / secs;
+                IoRates {
+                    network_rx: rate(counters.network_rx, previous.counters.network_rx),
+                    network_tx: rate(counters.network_tx, previous.counters.network_tx),
+                    disk_read: rate(counters.disk_read, previous.counters.disk_read),
+                    disk_write: rate(counters.disk_write, previous.counters.disk_write),
+                }
+            }
+            _ => IoRates::default(),
+        };
+
+        Self {
+            name: container.name.clone(),
+            status: container.status.clone(),
+            cpu_percent: metrics.map(|m| m.cpu_usage).unwrap_or(0.0),
+            memory_used: metrics.map(|m| m.memory_usage.used_mb * MIB).unwrap_or(0),
+            memory_limit: metrics.map(|m| m.memory_usage.limit_mb * MIB).unwrap_or(0),
+            counters,
+            rates,
+            gpu: metrics.and_then(|m| m.gpu_usage.clone()),
+            timestamp: now,
+        }
+    }
+}
+
+/// Totals over the latest samples
+#[derive(Debug, Clone, Default)]
+struct Totals {
+    counts: ContainerCounts,
+    cpu_percent: f64,
+    memory_used: u64,
+    memory_limit: u64,
+    rates: IoRates,
+    gpu_containers: usize,
+    gpu_utilization: f64,
+    gpu_memory_used_mb: u64,
+    gpu_memory_total_mb: u64,
+}
+
+impl Totals {
+    fn status_count(&mut self, status: &ContainerStatus) -> &mut usize {
+        match status {
+            ContainerStatus::Running => &mut self.counts.running,
+            ContainerStatus::Paused => &mut self.counts.paused,
+            status if status.is_stopped() => &mut self.counts.stopped,
+            _ => &mut self.counts.other,
+        }
+    }
+
+    fn add(&mut self, sample: &Sample) {
+        self.counts.total += 1;
+        *self.status_count(&sample.status) += 1;
+        self.cpu_percent += sample.cpu_percent;
+        self.memory_used += sample.memory_used;
+        self.memory_limit += sample.memory_limit;
+        self.rates.network_rx += sample.rates.network_rx;
+        self.rates.network_tx += sample.rates.network_tx;
+        self.rates.disk_read += sample.rates.disk_read;
+        self.rates.disk_write += sample.rates.disk_write;
+        if let Some(gpu) = &sample.gpu {
+            self.gpu_containers += 1;
+            self.gpu_utilization += gpu.utilization;
+            self.gpu_memory_used_mb += gpu.memory_used_mb;
+            self.gpu_memory_total_mb += gpu.memory_total_mb;
+        }
+    }
+
+    fn of<'a>(samples: impl IntoIterator<Item = &'a Sample>) -> Self {
+        let mut totals = Self::default();
+        for sample in samples {
+            totals.add(sample);
+        }
+        totals
+    }
+}
+
+#[derive(Debug, Default)]
+struct RecorderState {
+    samples: HashMap<String, Sample>,
+    totals: Totals,
+}
+
+/// Keeps the latest stats sample per container and the totals over them
+#[derive(Debug, Default)]
+pub struct StatsRecorder {
+    state: RwLock<RecorderState>,
+}
+
+impl StatsRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a sample for every listed container and forget containers that are gone
+    pub async fn record(&self, containers: &[Container], now: DateTime<Utc>) {
+        let mut state = self.state.write().await;
+        let RecorderState { samples, totals } = &mut *state;
+
+        for container in containers {
+            let sample = Sample::from_container(container, samples.get(&container.id), now);
+            samples.insert(container.id.clone(), sample);
+        }
+        let present: HashSet<&str> = containers.iter().map(|c| c.id.as_str()).collect();
+        samples.retain(|id, _| present.contains(id.as_str()));
+
+        // Summed afresh rather than adjusted, so float totals cannot drift
+        *totals = Totals::of(samples.values());
+    }
+
+    /// Build the summary from the totals and the latest samples
+    pub async fn summary(&self) -> MetricsSummary {
+        let state = self.state.read().await;
+        let totals = &state.totals;
+
+        let top = |key: fn(&Sample) -> f64| {
+            let mut samples: Vec<(&String, &Sample)> = state.samples.iter().collect();
+            samples.sort_by(|a, b| key(b.1).total_cmp(&key(a.1)));
+            samples
+                .into_iter()
+                .filter(|(_, sample)| key(sample) > 0.0)
+                .take(TOP_CONTAINERS)
+                .map(|(id, sample)| ContainerMetric {
+                    id: id.clone(),
+                    name: sample.name.clone(),
+                    cpu_percent: sample.cpu_percent,
+                    memory_used_bytes: sample.memory_used,
+                })
+                .collect()
+        };
+
+        MetricsSummary {
+            timestamp: state.samples.values().map(|s| s.timestamp).max().unwrap_or_else(Utc::now),
+            containers: totals.counts.clone(),
+            cpu_percent: totals.cpu_percent,
+            memory_used_bytes: totals.memory_used,
+            memory_limit_bytes: totals.memory_limit,
+            network_rx_bytes_per_sec: totals.rates.network_rx,
+            network_tx_bytes_per_sec: totals.rates.network_tx,
+            disk_read_bytes_per_sec: totals.rates.disk_read,
+            disk_write_bytes_per_sec: totals.rates.disk_write,
+            top_cpu: top(|s| s.cpu_percent),
+            top_memory: top(|s| s.memory_used as f64),
+            gpu: (totals.gpu_containers > 0).then(|| GpuAggregate {
+                containers: totals.gpu_containers,
+                average_utilization: totals.gpu_utilization / totals.gpu_containers as f64,
+                memory_used_mb: totals.gpu_memory_used_mb,
+                memory_total_mb: totals.gpu_memory_total_mb,
+            }),
+            // Filled in by the handler from the log cache
+            log_cache: Default::default(),
+        }
+    }
+}
+
+/// Background task sampling the container stats of an environment
+pub async fn run_stats_recorder(env: Env) {
+    let mut ticker = tokio::time::interval(SAMPLE_INTERVAL);
+    loop {
+        ticker.tick().await;
+        match env.runtime.list_containers(None).await {
+            Ok(containers) => env.stats.record(&containers, Utc::now()).await,
+            Err(e) => warn!("Stats recorder failed to list containers in {}: {}", env.name, e),
+        }
+    }
+}
+
+/// Aggregate metrics across the containers of an environment, plus log cache usage
+#[utoipa::path(
+    get,
+    path = "/api/v1/metrics/summary",
+    tag = "system",
+    responses((status = 200, description = "Aggregate of the latest container stats", body = MetricsSummary)),
+)]
+pub async fn metrics_summary(State(state): State<AppState>, env: Env) -> Json<MetricsSummary> {
+    let mut summary = env.stats.summary().await;
+    summary.log_cache = state.logs.stats().await;
+    Json(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpanel_core::{DiskIo, MemoryUsage, NetworkIo, PerformanceMetrics, RestartPolicy};
+
+    fn container(id: &str, status: ContainerStatus, cpu: f64, memory_mb: u64, rx: u64, gpu: Option<f64>) -> Container {
+        Container {
+            id: id.to_string(),
+            name: format!("{}-name", id),
+            image: "nginx:latest".to_string(),
+            status,
+            ports: Vec::new(),
+            volumes: Vec::new(),
+            networks: Vec::new(),
+            env: HashMap::new(),
+            labels: HashMap::new(),
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+            health_status: None,
+            restart_policy: RestartPolicy::No,
+            gaming_config: None,
+            gpu_allocation: None,
+            performance_metrics: Some(PerformanceMetrics {
+                cpu_usage: cpu,
+                memory_usage: MemoryUsage {
+                    used_mb: memory_mb,
+                    limit_mb: 1024,
+                    percentage: memory_mb as f64 / 10.24,
+                },
+                gpu_usage: gpu.map(|utilization| GpuUsage {
+                    utilization,
+                    memory_used_mb: 2048,
+                    memory_total_mb: 8192,
+                    temperature: None,
+                    power_usage: None,
+                }),
+                network_io: NetworkIo {
+                    rx_bytes: rx,
+                    tx_bytes: rx / 2,
+                    rx_packets: 0,
+                    tx_packets: 0,
+                },
+                disk_io: DiskIo {
+                    read_bytes: 0,
+                    write_bytes: rx,
+                    read_ops: 0,
+                    write_ops: 0,
+                },
+                gaming_metrics: None,
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn totals_sum_running_containers_and_count_every_status() {
+        let recorder = StatsRecorder::new();
+        let now = Utc::now();
+        recorder
+            .record(
+                &[
+                    container("web", ContainerStatus::Running, 12.5, 256, 0, None),
+                    container("game", ContainerStatus::Running, 80.0, 512, 0, Some(60.0)),
+                    container("held", ContainerStatus::Paused, 0.0, 128, 0, Some(20.0)),
+                    // Metrics of stopped containers are stale, so they count for nothing
+                    container("old", ContainerStatus::Exited { code: 0 }, 50.0, 900, 0, None),
+                    container("looping", ContainerStatus::Restarting, 0.0, 0, 0, None),
+                ],
+                now,
+            )
+            .await;
+
+        let summary = recorder.summary().await;
+        let counts = &summary.containers;
+        assert_eq!((counts.total, counts.running, counts.paused, counts.stopped, counts.other), (5, 2, 1, 1, 1));
+        assert_eq!(summary.cpu_percent, 92.5);
+        assert_eq!(summary.memory_used_bytes, (256 + 512 + 128) * MIB);
+        assert_eq!(summary.memory_limit_bytes, 3 * 1024 * MIB);
+        let gpu = summary.gpu.unwrap();
+        assert_eq!((gpu.containers, gpu.average_utilization), (2, 40.0));
+        assert_eq!((gpu.memory_used_mb, gpu.memory_total_mb), (4096, 16384));
+
+        let top_cpu: Vec<&str> = summary.top_cpu.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(top_cpu, ["game", "web"]);
+        let top_memory: Vec<&str> = summary.top_memory.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(top_memory, ["game", "web", "held"]);
+        assert_eq!(summary.timestamp, now);
+    }
+
+    #[tokio::test]
+    async fn rates_come_from_consecutive_samples() {
+        let recorder = StatsRecorder::new();
+        let start = Utc::now();
+        let web = |rx| container("web", ContainerStatus::Running, 1.0, 64, rx, None);
+
+        recorder.record(&[web(1_000)], start).await;
+        assert_eq!(recorder.summary().await.network_rx_bytes_per_sec, 0.0);
+
+        recorder.record(&[web(21_000)], start + chrono::Duration::seconds(10)).await;
+        let summary = recorder.summary().await;
+        assert_eq!(summary.network_rx_bytes_per_sec, 2_000.0);
+        assert_eq!(summary.network_tx_bytes_per_sec, 1_000.0);
+        assert_eq!(summary.disk_write_bytes_per_sec, 2_000.0);
+
+        // A restart resets the counters, which gives no rate rather than a negative one
+        recorder.record(&[web(500)], start + chrono::Duration::seconds(20)).await;
+        assert_eq!(recorder.summary().await.network_rx_bytes_per_sec, 0.0);
+    }
+
+    #[tokio::test]
+    async fn removed_containers_leave_the_totals() {
+        let recorder = StatsRecorder::new();
+        let now = Utc::now();
+        let web = container("web", ContainerStatus::Running, 0.1, 64, 0, None);
+        let db = container("db", ContainerStatus::Running, 0.2, 64, 0, Some(33.3));
+
+        // Many samples of fractional values would leave residue if totals were adjusted
+        for i in 0..1_000 {
+            recorder.record(&[web.clone(), db.clone()], now + chrono::Duration::seconds(i)).await;
+        }
+        recorder.record(std::slice::from_ref(&web), now + chrono::Duration::seconds(1_000)).await;
+
+        let summary = recorder.summary().await;
+        assert_eq!(summary.containers.total, 1);
+        assert_eq!(summary.cpu_percent, 0.1);
+        assert_eq!(summary.memory_used_bytes, 64 * MIB);
+        assert!(summary.gpu.is_none());
+
+        recorder.record(&[], now + chrono::Duration::seconds(1_001)).await;
+        let summary = recorder.summary().await;
+        assert_eq!((summary.containers.total, summary.cpu_percent), (0, 0.0));
+        assert!(summary.top_cpu.is_empty());
+    }
+}
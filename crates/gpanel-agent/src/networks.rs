@@ -0,0 +1,368 @@
+use axum::{
+    extract::Path,
+    http::StatusCode,
+    response::Json,
+};
+use gpanel_core::{
+    cidr_contains, parse_cidr, ApiErrorResponse, CreateNetworkRequest, Network, NetworkConnectRequest,
+    NetworkListResponse, OperationResult,
+};
+use std::net::IpAddr;
+use tracing::{error, info};
+
+use crate::auth::RequireOperator;
+use crate::error::ApiError;
+use crate::environments::Env;
+
+/// Look up a network by ID or name
+async fn find_network(env: &Env, id: &str) -> Result<Network, ApiError> {
+    let networks = env.runtime.list_networks().await.map_err(|e| {
+        error!("Failed to list networks: {}", e);
+        ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list networks: {}", e))
+    })?;
+
+    networks
+        .into_iter()
+        .find(|network| network.id == id || network.name == id)
+        .ok_or_else(|| ApiError::not_found("NETWORK", format!("Network '{}' not found", id)))
+}
+
+/// Validate a create request's name, subnet and gateway
+fn validate_create_request(request: &CreateNetworkRequest) -> Result<(), String> {
+    let valid_name = request.name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && request
+            .name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+    if !valid_name {
+        return Err(format!("Invalid network name '{}'", request.name));
+    }
+
+    if let Some(subnet) = &request.subnet
+        && parse_cidr(subnet).is_none()
+    {
+        return Err(format!("Invalid subnet '{}', expected CIDR notation", subnet));
+    }
+
+    if let Some(gateway) = &request.gateway {
+        let address: IpAddr = gateway
+            .parse()
+            .map_err(|_| format!("Invalid gateway address '{}'", gateway))?;
+
+        match &request.subnet {
+            Some(subnet) if !cidr_contains(subnet, &address) => {
+                return Err(format!("Gateway {} is outside subnet {}", gateway, subnet));
+            }
+            None => return Err("A gateway requires a subnet".to_string()),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// List networks
+#[utoipa::path(
+    get,
+    path = "/api/v1/networks",
+    tag = "networks",
+    responses((status = 200, description = "All networks", body = NetworkListResponse)),
+)]
+pub async fn list_networks(env: Env) -> Result<Json<NetworkListResponse>, ApiError> {
+    match env.runtime.list_networks().await {
+        Ok(networks) => Ok(Json(NetworkListResponse { networks })),
+        Err(e) => {
+            error!("Failed to list networks: {}", e);
+            Err(ApiError::runtime(&e, format!("Failed to list networks: {}", e)))
+        }
+    }
+}
+
+/// Create a network
+#[utoipa::path(
+    post,
+    path = "/api/v1/networks",
+    tag = "networks",
+    request_body = CreateNetworkRequest,
+    responses(
+        (status = 201, description = "Network created", body = Network),
+        (status = 400, description = "Invalid name or subnet", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 409, description = "Network already exists", body = ApiErrorResponse),
+    ),
+)]
+pub async fn create_network(
+    _: RequireOperator,
+    env: Env,
+    Json(request): Json<CreateNetworkRequest>,
+) -> Result<(StatusCode, Json<Network>), ApiError> {
+    validate_create_request(&request).map_err(|message| ApiError::from_status(StatusCode::BAD_REQUEST, message))?;
+
+    let networks = env.runtime.list_networks().await.map_err(|e| {
+        error!("Failed to list networks: {}", e);
+        ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list networks: {}", e))
+    })?;
+
+    if networks.iter().any(|network| network.name == request.name) {
+        return Err(ApiError::from_status(
+            StatusCode::CONFLICT,
+            format!("Network '{}' already exists", request.name),
+        ));
+    }
+
+    match env.runtime.create_network(request).await {
+        Ok(network) => {
+            info!("Created network {} ({})", network.name, network.id);
+            Ok((StatusCode::CREATED, Json(network)))
+        }
+        Err(e) => {
+            error!("Failed to create network: {}", e);
+            Err(ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create network: {}", e)))
+        }
+    }
+}
+
+/// Get a network by ID or name
+#[utoipa::path(
+    get,
+    path = "/api/v1/networks/{id}",
+    tag = "networks",
+    params(("id" = String, Path, description = "Network ID or name")),
+    responses(
+        (status = 200, description = "The network", body = Network),
+        (status = 404, description = "No such network", body = ApiErrorResponse),
+    ),
+)]
+pub async fn get_network(
+    env: Env,
+    Path(id): Path<String>,
+) -> Result<Json<Network>, ApiError> {
+    find_network(&env, &id).await.map(Json)
+}
+
+/// Delete a network, refusing while containers are attached
+#[utoipa::path(
+    delete,
+    path = "/api/v1/networks/{id}",
+    tag = "networks",
+    params(("id" = String, Path, description = "Network ID or name")),
+    responses(
+        (status = 200, description = "Network removed", body = OperationResult),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such network", body = ApiErrorResponse),
+        (status = 409, description = "Network has containers attached", body = ApiErrorResponse),
+    ),
+)]
+pub async fn delete_network(
+    _: RequireOperator,
+    env: Env,
+    Path(id): Path<String>,
+) -> Result<Json<OperationResult>, ApiError> {
+    let network = find_network(&env, &id).await?;
+
+    if !network.containers.is_empty() {
+        return Err(ApiError::from_status(
+            StatusCode::CONFLICT,
+            format!(
+                "Network '{}' has attached containers: {}",
+                network.name,
+                network.containers.join(", ")
+            ),
+        ));
+    }
+
+    match env.runtime.remove_network(&network.id).await {
+        Ok(_) => {
+            info!("Removed network {} ({})", network.name, network.id);
+            Ok(Json(OperationResult {
+                success: true,
+                message: format!("Network {} removed successfully", network.name),
+            }))
+        }
+        Err(e) => {
+            error!("Failed to remove network {}: {}", network.name, e);
+            Err(ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to remove network: {}", e)))
+        }
+    }
+}
+
+/// Attach a container to a network
+#[utoipa::path(
+    post,
+    path = "/api/v1/networks/{id}/connect",
+    tag = "networks",
+    params(("id" = String, Path, description = "Network ID or name")),
+    request_body = NetworkConnectRequest,
+    responses(
+        (status = 200, description = "Container connected", body = OperationResult),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such network or container", body = ApiErrorResponse),
+    ),
+)]
+pub async fn connect_network(
+    _: RequireOperator,
+    env: Env,
+    Path(id): Path<String>,
+    Json(request): Json<NetworkConnectRequest>,
+) -> Result<Json<OperationResult>, ApiError> {
+    let network = find_network(&env, &id).await?;
+    ensure_container_exists(&env, &request.container_id).await?;
+
+    match env.runtime.connect_network(&network.id, &request).await {
+        Ok(_) => {
+            info!("Connected container {} to network {}", request.container_id, network.name);
+            Ok(Json(OperationResult {
+                success: true,
+                message: format!("Container {} connected to {}", request.container_id, network.name),
+            }))
+        }
+        Err(e) => {
+            error!("Failed to connect {} to {}: {}", request.container_id, network.name, e);
+            Err(ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to connect container: {}", e)))
+        }
+    }
+}
+
+/// Detach a container from a network
+#[utoipa::path(
+    post,
+    path = "/api/v1/networks/{id}/disconnect",
+    tag = "networks",
+    params(("id" = String, Path, description = "Network ID or name")),
+    request_body = NetworkConnectRequest,
+    responses(
+        (status = 200, description = "Container disconnected", body = OperationResult),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such network, or the container is not attached to it", body = ApiErrorResponse),
+    ),
+)]
+pub async fn disconnect_network(
+    _: RequireOperator,
+    env: Env,
+    Path(id): Path<String>,
+    Json(request): Json<NetworkConnectRequest>,
+) -> Result<Json<OperationResult>, ApiError> {
+    let network = find_network(&env, &id).await?;
+
+    if !network.containers.contains(&request.container_id) {
+        return Err(ApiError::from_status(
+            StatusCode::NOT_FOUND,
+            format!("Container {} is not attached to {}", request.container_id, network.name),
+        ));
+    }
+
+    match env.runtime.disconnect_network(&network.id, &request).await {
+        Ok(_) => {
+            info!("Disconnected container {} from network {}", request.container_id, network.name);
+            Ok(Json(OperationResult {
+                success: true,
+                message: format!("Container {} disconnected from {}", request.container_id, network.name),
+            }))
+        }
+        Err(e) => {
+            error!("Failed to disconnect {} from {}: {}", request.container_id, network.name, e);
+            Err(ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to disconnect container: {}", e)))
+        }
+    }
+}
+
+async fn ensure_container_exists(env: &Env, container_id: &str) -> Result<(), ApiError> {
+    let containers = env.runtime.list_containers(None).await.map_err(|e| {
+        error!("Failed to list containers: {}", e);
+        ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list containers: {}", e))
+    })?;
+
+    if containers.iter().any(|c| c.id == container_id || c.name == container_id) {
+        Ok(())
+    } else {
+        Err(ApiError::not_found("CONTAINER", format!("Container '{}' not found", container_id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Method;
+    use serde_json::json;
+
+    use crate::testing;
+
+    fn request(name: &str, subnet: Option<&str>, gateway: Option<&str>) -> CreateNetworkRequest {
+        CreateNetworkRequest {
+            name: name.to_string(),
+            driver: None,
+            subnet: subnet.map(str::to_string),
+            gateway: gateway.map(str::to_string),
+            internal: false,
+            driver_options: Default::default(),
+            labels: Default::default(),
+            gaming: false,
+        }
+    }
+
+    #[test]
+    fn create_request_validation() {
+        assert!(validate_create_request(&request("lan", Some("10.1.0.0/24"), Some("10.1.0.1"))).is_ok());
+        assert!(validate_create_request(&request("-lan", None, None)).is_err());
+        assert!(validate_create_request(&request("lan", Some("10.1.0.0/33"), None)).is_err());
+        assert!(validate_create_request(&request("lan", Some("10.1.0.0/24"), Some("10.2.0.1"))).is_err());
+        assert!(validate_create_request(&request("lan", None, Some("10.1.0.1"))).is_err());
+    }
+
+    #[tokio::test]
+    async fn create_rejects_duplicate_names_and_bad_subnets() {
+        let agent = testing::agent().await;
+
+        let body = json!({ "name": "lan", "subnet": "10.1.0.0/24", "gateway": "10.1.0.1", "gaming": true });
+        let (status, network) = agent.request(Method::POST, "/api/v1/networks", Some(body.clone())).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(network["name"], "lan");
+        assert!(!network["driver_options"].as_object().unwrap().is_empty(), "gaming options applied");
+        let stored = agent.state.environments.default_env().runtime.list_networks().await.unwrap();
+        assert!(stored.iter().any(|stored| stored.name == "lan"));
+
+        let (status, _) = agent.request(Method::POST, "/api/v1/networks", Some(body)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, _) = agent
+            .request(Method::POST, "/api/v1/networks", Some(json!({ "name": "bad", "subnet": "10.1.0.0" })))
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn delete_refuses_networks_with_containers() {
+        let agent = testing::agent().await;
+
+        let (status, error) = agent.request(Method::DELETE, "/api/v1/networks/bridge", None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(error["error"]["message"].as_str().unwrap().contains("mock_web_server_001"));
+
+        let disconnect = json!({ "container_id": "mock_web_server_001" });
+        let (status, _) = agent
+            .request(Method::POST, "/api/v1/networks/bridge/disconnect", Some(disconnect))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, _) = agent.request(Method::DELETE, "/api/v1/networks/bridge", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = agent.request(Method::GET, "/api/v1/networks/bridge", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn connect_needs_an_existing_container() {
+        let agent = testing::agent().await;
+
+        let (status, _) = agent
+            .request(Method::POST, "/api/v1/networks/gaming/connect", Some(json!({ "container_id": "nope" })))
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let connect = json!({ "container_id": "mock_database_003", "aliases": ["db"] });
+        let (status, _) = agent.request(Method::POST, "/api/v1/networks/gaming/connect", Some(connect)).await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, network) = agent.request(Method::GET, "/api/v1/networks/gaming", None).await;
+        assert!(network["containers"].as_array().unwrap().contains(&json!("mock_database_003")));
+    }
+}
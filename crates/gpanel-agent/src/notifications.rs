@@ -0,0 +1,709 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use gpanel_core::{
+    Alert, AlertCondition, ApiErrorResponse, AlertSignal, ChannelListResponse, ContainerEvent, ContainerRuntime,
+    CreateChannelRequest, GhostPanelConfig, NotificationChannel, NotificationChannelKind, NotificationRule, OperationResult,
+    RuleListResponse, RuleRequest,
+};
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, Mutex, RwLock};
+use tracing::{error, info, warn};
+
+use crate::auth::RequireAdmin;
+use crate::store::Store;
+use crate::error::ApiError;
+use crate::AppState;
+
+/// Store collection holding channels and rules
+const NOTIFICATION_STORE: &str = "notifications";
+
+/// How often Bolt, disks and certificates are checked
+const PROBE_INTERVAL: Duration = Duration::from_secs(60);
+
+const DEFAULT_COOLDOWN_SECS: u64 = 3600;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct NotificationSettings {
+    channels: Vec<NotificationChannel>,
+    rules: Vec<NotificationRule>,
+}
+
+/// Alert rules, their channels and per-rule cooldowns
+pub struct Notifier {
+    settings: RwLock<NotificationSettings>,
+    last_sent: Mutex<HashMap<String, DateTime<Utc>>>,
+    store: Store,
+    client: reqwest::Client,
+}
+
+impl Notifier {
+    pub async fn load(store: Store) -> Self {
+        Self {
+            settings: RwLock::new(store.load(NOTIFICATION_STORE).await),
+            last_sent: Mutex::new(HashMap::new()),
+            store,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn persist(&self, settings: &NotificationSettings) -> anyhow::Result<()> {
+        self.store.save(NOTIFICATION_STORE, settings).await
+    }
+
+    pub async fn channels(&self) -> Vec<NotificationChannel> {
+        self.settings.read().await.channels.clone()
+    }
+
+    pub async fn channel(&self, id: &str) -> Option<NotificationChannel> {
+        self.settings.read().await.channels.iter().find(|c| c.id == id).cloned()
+    }
+
+    pub async fn add_channel(&self, channel: NotificationChannel) -> anyhow::Result<()> {
+        let mut settings = self.settings.write().await;
+        settings.channels.push(channel);
+        self.persist(&settings).await
+    }
+
+    /// Remove a channel and drop it from every rule
+    pub async fn remove_channel(&self, id: &str) -> anyhow::Result<bool> {
+        let mut settings = self.settings.write().await;
+        let before = settings.channels.len();
+        settings.channels.retain(|c| c.id != id);
+        if settings.channels.len() == before {
+            return Ok(false);
+        }
+        for rule in &mut settings.rules {
+            rule.channels.retain(|c| c != id);
+        }
+        self.persist(&settings).await?;
+        Ok(true)
+    }
+
+    pub async fn rules(&self) -> Vec<NotificationRule> {
+        self.settings.read().await.rules.clone()
+    }
+
+    /// Insert or replace a rule
+    pub async fn save_rule(&self, rule: NotificationRule) -> anyhow::Result<()> {
+        let mut settings = self.settings.write().await;
+        match settings.rules.iter_mut().find(|r| r.id == rule.id) {
+            Some(existing) => *existing = rule,
+            None => settings.rules.push(rule),
+        }
+        self.persist(&settings).await
+    }
+
+    pub async fn remove_rule(&self, id: &str) -> anyhow::Result<bool> {
+        let mut settings = self.settings.write().await;
+        let before = settings.rules.len();
+        settings.rules.retain(|r| r.id != id);
+        if settings.rules.len() == before {
+            return Ok(false);
+        }
+        self.last_sent.lock().await.remove(id);
+        self.persist(&settings).await?;
+        Ok(true)
+    }
+
+    /// Evaluate a signal against every rule, returning the alerts that passed their cooldown
+    pub async fn evaluate(&self, signal: &AlertSignal, now: DateTime<Utc>) -> Vec<(Alert, Vec<NotificationChannel>)> {
+        let settings = self.settings.read().await;
+        let mut last_sent = self.last_sent.lock().await;
+        let mut alerts = Vec::new();
+
+        for rule in &settings.rules {
+            let Some(alert) = rule.evaluate(signal, now) else {
+                continue;
+            };
+            if !rule.cooldown_elapsed(last_sent.get(&rule.id).copied(), now) {
+                continue;
+            }
+            last_sent.insert(rule.id.clone(), now);
+
+            let channels = settings
+                .channels
+                .iter()
+                .filter(|c| c.enabled && rule.channels.contains(&c.id))
+                .cloned()
+                .collect();
+            alerts.push((alert, channels));
+        }
+        alerts
+    }
+
+    /// Evaluate a signal and deliver any resulting alerts
+    pub async fn process(&self, signal: &AlertSignal, now: DateTime<Utc>) {
+        for (alert, channels) in self.evaluate(signal, now).await {
+            info!("Alert from rule {}: {}", alert.rule_name, alert.title);
+            for channel in channels {
+                if let Err(e) = self.send(&channel, &alert).await {
+                    warn!("Failed to notify channel {}: {}", channel.name, e);
+                }
+            }
+        }
+    }
+
+    /// Deliver an alert to one channel
+    pub async fn send(&self, channel: &NotificationChannel, alert: &Alert) -> anyhow::Result<()> {
+        match &channel.kind {
+            NotificationChannelKind::Email {
+                smtp_host,
+                smtp_port,
+                username,
+                password,
+                from,
+                to,
+                starttls,
+            } => {
+                let mut builder = Message::builder()
+                    .from(from.parse::<Mailbox>()?)
+                    .subject(format!("[GhostPanel] {}", alert.title))
+                    .header(ContentType::TEXT_PLAIN);
+                for recipient in to {
+                    builder = builder.to(recipient.parse::<Mailbox>()?);
+                }
+                let email = builder.body(render_text(alert))?;
+
+                let mut transport = if *starttls {
+                    AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(smtp_host)?
+                } else {
+                    AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(smtp_host.as_str())
+                }
+                .port(*smtp_port);
+                if let (Some(username), Some(password)) = (username, password) {
+                    transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+                }
+
+                transport.build::<Tokio1Executor>().send(email).await?;
+            }
+            NotificationChannelKind::Webhook { url } => {
+                self.post_json(url, &serde_json::to_value(alert)?).await?;
+            }
+            NotificationChannelKind::Discord { url } => {
+                let body = serde_json::json!({
+                    "username": "GhostPanel",
+                    "content": format!("**{}**\n{}", alert.title, alert.message),
+                });
+                self.post_json(url, &body).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn post_json(&self, url: &str, body: &serde_json::Value) -> anyhow::Result<()> {
+        let response = self
+            .client
+            .post(url)
+            .timeout(Duration::from_secs(10))
+            .json(body)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Target responded with {}", response.status()));
+        }
+        Ok(())
+    }
+}
+
+/// Plain-text body used for email
+fn render_text(alert: &Alert) -> String {
+    format!(
+        "{}\n\n{}\n\nRule: {}\nRaised at: {}\n",
+        alert.title,
+        alert.message,
+        alert.rule_name,
+        alert.raised_at.format("%Y-%m-%d %H:%M:%S UTC")
+    )
+}
+
+/// Read one DER element, returning its tag, contents and the remaining input
+fn der_element(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+
+    let (length, rest) = if first & 0x80 == 0 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 || rest.len() < count {
+            return None;
+        }
+        let length = rest[..count].iter().fold(0usize, |acc, &b| (acc << 8) | b as usize);
+        (length, &rest[count..])
+    };
+
+    if rest.len() < length {
+        return None;
+    }
+    Some((tag, &rest[..length], &rest[length..]))
+}
+
+/// Extract the notAfter time from a DER-encoded X.509 certificate
+fn certificate_not_after(der: &[u8]) -> Option<DateTime<Utc>> {
+    let (_, certificate, _) = der_element(der)?;
+    let (_, mut tbs, _) = der_element(certificate)?;
+
+    // Skip the optional [0] version, then serial, signature algorithm and issuer
+    let (tag, _, rest) = der_element(tbs)?;
+    if tag == 0xa0 {
+        tbs = rest;
+    }
+    for _ in 0..3 {
+        tbs = der_element(tbs)?.2;
+    }
+
+    let (_, validity, _) = der_element(tbs)?;
+    let (_, _, rest) = der_element(validity)?;
+    let (tag, not_after, _) = der_element(rest)?;
+
+    let text = std::str::from_utf8(not_after).ok()?;
+    let text = match tag {
+        // UTCTime: two-digit years from 50 are in the 1900s
+        0x17 => format!("{}{}", if &text[..2] >= "50" { "19" } else { "20" }, text),
+        0x18 => text.to_string(),
+        _ => return None,
+    };
+    NaiveDateTime::parse_from_str(&text, "%Y%m%d%H%M%SZ").ok().map(|time| time.and_utc())
+}
+
+/// Expiry of the first certificate in a PEM file
+async fn certificate_expiry(path: &str) -> Option<DateTime<Utc>> {
+    let pem = tokio::fs::read(path).await.ok()?;
+    let certificates = rustls_pemfile::certs(&mut pem.as_slice()).ok()?;
+    certificate_not_after(certificates.first()?)
+}
+
+/// Gather the periodic signals: Bolt reachability, disk usage and certificate expiry
+async fn probe(bolt_client: &dyn ContainerRuntime, config: &GhostPanelConfig) -> Vec<AlertSignal> {
+    let mut signals = Vec::new();
+
+    let error = bolt_client.list_containers(None).await.err().map(|e| e.to_string());
+    signals.push(AlertSignal::BoltStatus { reachable: error.is_none(), error });
+
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    for disk in disks.list() {
+        if disk.total_space() == 0 {
+            continue;
+        }
+        let used = disk.total_space() - disk.available_space();
+        signals.push(AlertSignal::DiskUsage {
+            mount_point: disk.mount_point().display().to_string(),
+            used_percent: used as f64 * 100.0 / disk.total_space() as f64,
+        });
+    }
+
+    if let Some(path) = &config.tls_cert_path {
+        match certificate_expiry(path).await {
+            Some(expires_at) => signals.push(AlertSignal::CertificateExpiry { path: path.clone(), expires_at }),
+            None => warn!("Could not read certificate expiry from {}", path),
+        }
+    }
+
+    signals
+}
+
+/// Background task feeding container events and periodic probes to the notifier
+pub async fn run_notifier(
+    notifier: Arc<Notifier>,
+    bolt_client: Arc<dyn ContainerRuntime>,
+    config: GhostPanelConfig,
+    mut events: broadcast::Receiver<ContainerEvent>,
+) {
+    let mut ticker = tokio::time::interval(PROBE_INTERVAL);
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => notifier.process(&AlertSignal::Event(event), Utc::now()).await,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Notifier fell behind, skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = ticker.tick() => {
+                for signal in probe(bolt_client.as_ref(), &config).await {
+                    notifier.process(&signal, Utc::now()).await;
+                }
+            }
+        }
+    }
+}
+
+fn is_http_url(url: &str) -> bool {
+    reqwest::Url::parse(url)
+        .map(|url| matches!(url.scheme(), "http" | "https"))
+        .unwrap_or(false)
+}
+
+fn validate_channel(kind: &NotificationChannelKind) -> Result<(), String> {
+    match kind {
+        NotificationChannelKind::Email { smtp_host, from, to, .. } => {
+            if smtp_host.is_empty() {
+                return Err("SMTP host is required".to_string());
+            }
+            if to.is_empty() {
+                return Err("At least one recipient is required".to_string());
+            }
+            for address in std::iter::once(from).chain(to) {
+                if address.parse::<Mailbox>().is_err() {
+                    return Err(format!("Invalid email address '{}'", address));
+                }
+            }
+        }
+        NotificationChannelKind::Webhook { url } | NotificationChannelKind::Discord { url } => {
+            if !is_http_url(url) {
+                return Err(format!("Invalid URL '{}', expected http or https", url));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Hide the SMTP password from API responses
+fn redact(mut channel: NotificationChannel) -> NotificationChannel {
+    if let NotificationChannelKind::Email { password, .. } = &mut channel.kind {
+        *password = None;
+    }
+    channel
+}
+
+/// List notification channels
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/channels",
+    tag = "notifications",
+    responses((status = 200, description = "Notification channels, without email passwords", body = ChannelListResponse)),
+)]
+pub async fn list_channels(State(state): State<AppState>) -> Json<ChannelListResponse> {
+    let channels = state.notifier.channels().await.into_iter().map(redact).collect();
+    Json(ChannelListResponse { channels })
+}
+
+/// Create a notification channel
+#[utoipa::path(
+    post,
+    path = "/api/v1/notifications/channels",
+    tag = "notifications",
+    request_body = CreateChannelRequest,
+    responses(
+        (status = 201, description = "Channel created", body = NotificationChannel),
+        (status = 400, description = "Invalid channel settings", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+    ),
+)]
+pub async fn create_channel(
+    _: RequireAdmin,
+    State(state): State<AppState>,
+    Json(request): Json<CreateChannelRequest>,
+) -> Result<(StatusCode, Json<NotificationChannel>), ApiError> {
+    if request.name.trim().is_empty() {
+        return Err(ApiError::from_status(StatusCode::BAD_REQUEST, "Channel name is required"));
+    }
+    validate_channel(&request.kind).map_err(|e| ApiError::from_status(StatusCode::BAD_REQUEST, e))?;
+
+    let channel = NotificationChannel {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: request.name,
+        kind: request.kind,
+        enabled: request.enabled.unwrap_or(true),
+    };
+
+    if let Err(e) = state.notifier.add_channel(channel.clone()).await {
+        error!("Failed to save notification channel: {}", e);
+        return Err(ApiError::from_status(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save channel: {}", e),
+        ));
+    }
+
+    info!("Created notification channel {}", channel.name);
+    Ok((StatusCode::CREATED, Json(redact(channel))))
+}
+
+/// Delete a notification channel
+#[utoipa::path(
+    delete,
+    path = "/api/v1/notifications/channels/{id}",
+    tag = "notifications",
+    params(("id" = String, Path, description = "Channel ID")),
+    responses(
+        (status = 200, description = "Channel removed", body = OperationResult),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+        (status = 404, description = "No such channel", body = ApiErrorResponse),
+    ),
+)]
+pub async fn delete_channel(
+    _: RequireAdmin,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<OperationResult>, ApiError> {
+    match state.notifier.remove_channel(&id).await {
+        Ok(true) => Ok(Json(OperationResult {
+            success: true,
+            message: format!("Channel {} deleted", id),
+        })),
+        Ok(false) => Err(ApiError::not_found("NOTIFICATION_CHANNEL", format!("Channel '{}' not found", id))),
+        Err(e) => Err(ApiError::from_status(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to delete channel: {}", e),
+        )),
+    }
+}
+
+/// Send a test alert through a channel
+#[utoipa::path(
+    post,
+    path = "/api/v1/notifications/channels/{id}/test",
+    tag = "notifications",
+    params(("id" = String, Path, description = "Channel ID")),
+    responses(
+        (status = 200, description = "Test notification sent", body = OperationResult),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+        (status = 404, description = "No such channel", body = ApiErrorResponse),
+        (status = 502, description = "Delivery failed", body = ApiErrorResponse),
+    ),
+)]
+pub async fn test_channel(
+    _: RequireAdmin,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<OperationResult>, ApiError> {
+    let channel = state
+        .notifier
+        .channel(&id)
+        .await
+        .ok_or_else(|| ApiError::not_found("NOTIFICATION_CHANNEL", format!("Channel '{}' not found", id)))?;
+
+    let alert = Alert {
+        rule_id: "test".to_string(),
+        rule_name: "Test".to_string(),
+        title: "Test notification".to_string(),
+        message: format!("This is a test notification for the {} channel.", channel.name),
+        raised_at: Utc::now(),
+    };
+
+    match state.notifier.send(&channel, &alert).await {
+        Ok(()) => Ok(Json(OperationResult {
+            success: true,
+            message: format!("Test notification sent to {}", channel.name),
+        })),
+        Err(e) => Err(ApiError::from_status(
+            StatusCode::BAD_GATEWAY,
+            format!("Test notification to {} failed: {}", channel.name, e),
+        )),
+    }
+}
+
+async fn build_rule(state: &AppState, id: String, request: RuleRequest) -> Result<NotificationRule, ApiError> {
+    if request.name.trim().is_empty() {
+        return Err(ApiError::from_status(StatusCode::BAD_REQUEST, "Rule name is required"));
+    }
+    if let AlertCondition::DiskUsageAbove { percent } = request.condition
+        && !(0.0..=100.0).contains(&percent)
+    {
+        return Err(ApiError::from_status(
+            StatusCode::BAD_REQUEST,
+            "Disk usage threshold must be between 0 and 100".to_string(),
+        ));
+    }
+
+    let channels = state.notifier.channels().await;
+    if let Some(unknown) = request.channels.iter().find(|id| !channels.iter().any(|c| &c.id == *id)) {
+        return Err(ApiError::from_status(StatusCode::BAD_REQUEST, format!("Unknown channel '{}'", unknown)));
+    }
+
+    Ok(NotificationRule {
+        id,
+        name: request.name,
+        condition: request.condition,
+        channels: request.channels,
+        cooldown_secs: request.cooldown_secs.unwrap_or(DEFAULT_COOLDOWN_SECS),
+        enabled: request.enabled.unwrap_or(true),
+    })
+}
+
+async fn save_rule(state: &AppState, rule: NotificationRule) -> Result<NotificationRule, ApiError> {
+    if let Err(e) = state.notifier.save_rule(rule.clone()).await {
+        error!("Failed to save notification rule: {}", e);
+        return Err(ApiError::from_status(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save rule: {}", e),
+        ));
+    }
+    Ok(rule)
+}
+
+/// List notification rules
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/rules",
+    tag = "notifications",
+    responses((status = 200, description = "Alert rules", body = RuleListResponse)),
+)]
+pub async fn list_rules(State(state): State<AppState>) -> Json<RuleListResponse> {
+    Json(RuleListResponse { rules: state.notifier.rules().await })
+}
+
+/// Create a notification rule
+#[utoipa::path(
+    post,
+    path = "/api/v1/notifications/rules",
+    tag = "notifications",
+    request_body = RuleRequest,
+    responses(
+        (status = 201, description = "Rule created", body = NotificationRule),
+        (status = 400, description = "Invalid rule or unknown channel", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+    ),
+)]
+pub async fn create_rule(
+    _: RequireAdmin,
+    State(state): State<AppState>,
+    Json(request): Json<RuleRequest>,
+) -> Result<(StatusCode, Json<NotificationRule>), ApiError> {
+    let rule = build_rule(&state, uuid::Uuid::new_v4().to_string(), request).await?;
+    let rule = save_rule(&state, rule).await?;
+    info!("Created notification rule {}", rule.name);
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// Replace a notification rule
+#[utoipa::path(
+    put,
+    path = "/api/v1/notifications/rules/{id}",
+    tag = "notifications",
+    params(("id" = String, Path, description = "Rule ID")),
+    request_body = RuleRequest,
+    responses(
+        (status = 200, description = "Rule updated", body = NotificationRule),
+        (status = 400, description = "Invalid rule or unknown channel", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+        (status = 404, description = "No such rule", body = ApiErrorResponse),
+    ),
+)]
+pub async fn update_rule(
+    _: RequireAdmin,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<RuleRequest>,
+) -> Result<Json<NotificationRule>, ApiError> {
+    if !state.notifier.rules().await.iter().any(|r| r.id == id) {
+        return Err(ApiError::not_found("NOTIFICATION_RULE", format!("Rule '{}' not found", id)));
+    }
+    let rule = build_rule(&state, id, request).await?;
+    Ok(Json(save_rule(&state, rule).await?))
+}
+
+/// Delete a notification rule
+#[utoipa::path(
+    delete,
+    path = "/api/v1/notifications/rules/{id}",
+    tag = "notifications",
+    params(("id" = String, Path, description = "Rule ID")),
+    responses(
+        (status = 200, description = "Rule removed", body = OperationResult),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+        (status = 404, description = "No such rule", body = ApiErrorResponse),
+    ),
+)]
+pub async fn delete_rule(
+    _: RequireAdmin,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<OperationResult>, ApiError> {
+    match state.notifier.remove_rule(&id).await {
+        Ok(true) => Ok(Json(OperationResult {
+            success: true,
+            message: format!("Rule {} deleted", id),
+        })),
+        Ok(false) => Err(ApiError::not_found("NOTIFICATION_RULE", format!("Rule '{}' not found", id))),
+        Err(e) => Err(ApiError::from_status(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to delete rule: {}", e),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+
+    fn channel(id: &str, enabled: bool) -> NotificationChannel {
+        NotificationChannel {
+            id: id.to_string(),
+            name: id.to_string(),
+            kind: NotificationChannelKind::Webhook { url: format!("http://127.0.0.1:9/{}", id) },
+            enabled,
+        }
+    }
+
+    fn rule(id: &str, percent: f64) -> NotificationRule {
+        NotificationRule {
+            id: id.to_string(),
+            name: id.to_string(),
+            condition: AlertCondition::DiskUsageAbove { percent },
+            channels: vec!["ops".to_string(), "muted".to_string()],
+            cooldown_secs: 300,
+            enabled: true,
+        }
+    }
+
+    fn disk(used_percent: f64) -> AlertSignal {
+        AlertSignal::DiskUsage { mount_point: "/var".to_string(), used_percent }
+    }
+
+    async fn notifier(dir: &tempfile::TempDir) -> Notifier {
+        let notifier = Notifier::load(Store::new(dir.path())).await;
+        notifier.add_channel(channel("ops", true)).await.unwrap();
+        notifier.add_channel(channel("muted", false)).await.unwrap();
+        notifier
+    }
+
+    #[tokio::test]
+    async fn alerts_go_to_the_rule_enabled_channels() {
+        let dir = tempfile::tempdir().unwrap();
+        let notifier = notifier(&dir).await;
+        notifier.save_rule(rule("disk", 90.0)).await.unwrap();
+
+        assert!(notifier.evaluate(&disk(50.0), Utc::now()).await.is_empty());
+
+        let alerts = notifier.evaluate(&disk(95.0), Utc::now()).await;
+        assert_eq!(alerts.len(), 1);
+        let (alert, channels) = &alerts[0];
+        assert_eq!(alert.rule_id, "disk");
+        assert_eq!(channels.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(), ["ops"]);
+    }
+
+    #[tokio::test]
+    async fn cooldown_is_tracked_per_rule() {
+        let dir = tempfile::tempdir().unwrap();
+        let notifier = notifier(&dir).await;
+        notifier.save_rule(rule("warning", 80.0)).await.unwrap();
+        let start = Utc::now();
+
+        assert_eq!(notifier.evaluate(&disk(85.0), start).await.len(), 1);
+        let repeat = start + ChronoDuration::seconds(60);
+        assert!(notifier.evaluate(&disk(85.0), repeat).await.is_empty());
+
+        // A rule added later has its own cooldown and fires straight away
+        notifier.save_rule(rule("critical", 95.0)).await.unwrap();
+        let alerts = notifier.evaluate(&disk(97.0), repeat).await;
+        assert_eq!(alerts.iter().map(|(a, _)| a.rule_id.as_str()).collect::<Vec<_>>(), ["critical"]);
+
+        let later = start + ChronoDuration::seconds(300);
+        let alerts = notifier.evaluate(&disk(97.0), later).await;
+        assert_eq!(alerts.iter().map(|(a, _)| a.rule_id.as_str()).collect::<Vec<_>>(), ["warning"]);
+    }
+}
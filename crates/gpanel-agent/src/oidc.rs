@@ -0,0 +1,437 @@
+use axum::{extract::State, http::StatusCode, response::Json, Extension};
+use gpanel_core::{ApiErrorResponse, OidcCallbackRequest, OidcConfig, OidcProvider, SessionResponse, SessionUser};
+use jsonwebtoken::{DecodingKey, Validation};
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::audit::AuditActor;
+use crate::error::ApiError;
+use crate::AppState;
+
+/// How long to wait on each call to an identity provider
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// GitHub rejects API calls without a User-Agent
+const USER_AGENT: &str = "gpanel-agent";
+
+/// Token endpoint response. GitHub answers failures with 200 and an `error` field.
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    id_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+/// Userinfo fields used across providers; GitHub's `/user` has `id` and `login` instead of `sub`
+#[derive(Debug, Default, Deserialize)]
+struct UserInfo {
+    sub: Option<String>,
+    id: Option<serde_json::Value>,
+    email: Option<String>,
+    preferred_username: Option<String>,
+    login: Option<String>,
+    upn: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubEmail {
+    email: String,
+    primary: bool,
+    verified: bool,
+}
+
+/// Exchanges sign-in codes with the configured identity providers
+pub struct OidcClient {
+    client: reqwest::Client,
+}
+
+impl OidcClient {
+    pub fn new(providers: &[OidcConfig]) -> Self {
+        for provider in providers {
+            let open = matches!(provider.provider, OidcProvider::Google | OidcProvider::GitHub);
+            if open && provider.allowed_users.is_empty() {
+                warn!(
+                    "OIDC provider '{}' has no allowed_users; any {:?} account can sign in",
+                    provider.name, provider.provider
+                );
+            }
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(PROVIDER_TIMEOUT)
+            .user_agent(USER_AGENT)
+            .build()
+            .unwrap_or_default();
+        Self { client }
+    }
+
+    /// Trade an authorization code for the provider's tokens
+    async fn exchange_code(&self, config: &OidcConfig, code: &str, redirect_uri: &str) -> Result<TokenResponse, ApiError> {
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("client_id", config.client_id.as_str()),
+        ];
+        if let Some(secret) = &config.client_secret {
+            form.push(("client_secret", secret.as_str()));
+        }
+
+        let response = self
+            .client
+            .post(config.provider.token_url())
+            .header(reqwest::header::ACCEPT, "application/json")
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| ApiError::new(StatusCode::BAD_GATEWAY, "OIDC_PROVIDER_UNREACHABLE", format!("Token request failed: {}", e)))?;
+
+        let status = response.status();
+        let tokens: TokenResponse = response.json().await.map_err(|e| {
+            ApiError::new(
+                StatusCode::BAD_GATEWAY,
+                "OIDC_PROVIDER_ERROR",
+                format!("Unreadable token response ({}): {}", status, e),
+            )
+        })?;
+
+        if let Some(error) = &tokens.error {
+            let detail = tokens.error_description.as_deref().unwrap_or(error);
+            return Err(ApiError::new(
+                StatusCode::UNAUTHORIZED,
+                "OIDC_CODE_REJECTED",
+                format!("The identity provider rejected the sign-in: {}", detail),
+            ));
+        }
+        if !status.is_success() || tokens.access_token.is_none() {
+            return Err(ApiError::new(
+                StatusCode::BAD_GATEWAY,
+                "OIDC_PROVIDER_ERROR",
+                format!("Token endpoint answered {} without an access token", status),
+            ));
+        }
+        Ok(tokens)
+    }
+
+    async fn get_json<T: for<'de> Deserialize<'de>>(&self, url: &str, access_token: &str) -> Result<T, ApiError> {
+        let response = self
+            .client
+            .get(url)
+            .bearer_auth(access_token)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send()
+            .await
+            .map_err(|e| ApiError::new(StatusCode::BAD_GATEWAY, "OIDC_PROVIDER_UNREACHABLE", format!("Userinfo request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(ApiError::new(
+                StatusCode::BAD_GATEWAY,
+                "OIDC_PROVIDER_ERROR",
+                format!("Userinfo endpoint answered {}", response.status()),
+            ));
+        }
+        response.json().await.map_err(|e| {
+            ApiError::new(StatusCode::BAD_GATEWAY, "OIDC_PROVIDER_ERROR", format!("Unreadable userinfo: {}", e))
+        })
+    }
+
+    /// Look up who signed in. GitHub has no id_token and may hide the email on `/user`,
+    /// so its verified primary address comes from `/user/emails`.
+    async fn fetch_user(&self, config: &OidcConfig, tokens: &TokenResponse) -> Result<SessionUser, ApiError> {
+        let access_token = tokens.access_token.as_deref().unwrap_or_default();
+        let mut info: UserInfo = self.get_json(&config.provider.userinfo_url(), access_token).await?;
+
+        if config.provider == OidcProvider::GitHub && info.email.is_none() {
+            let emails: Vec<GitHubEmail> = self.get_json("https://api.github.com/user/emails", access_token).await?;
+            info.email = emails.into_iter().find(|e| e.primary && e.verified).map(|e| e.email);
+        }
+
+        // Azure's userinfo can leave out the email that the id_token carries
+        if info.email.is_none()
+            && let Some(claims) = tokens.id_token.as_deref().and_then(id_token_claims)
+        {
+            info.email = claims.email.or(claims.preferred_username).or(claims.upn);
+        }
+
+        let subject = info
+            .sub
+            .clone()
+            .or_else(|| info.id.as_ref().map(|id| id.to_string().trim_matches('"').to_string()))
+            .ok_or_else(|| ApiError::new(StatusCode::BAD_GATEWAY, "OIDC_PROVIDER_ERROR", "Userinfo has no subject"))?;
+        let email = info.email.clone().unwrap_or_default();
+        let username = info
+            .login
+            .or(info.preferred_username)
+            .or(info.upn)
+            .or_else(|| (!email.is_empty()).then(|| email.clone()))
+            .unwrap_or_else(|| subject.clone());
+
+        Ok(SessionUser {
+            id: format!("{}:{}", config.name, subject),
+            username,
+            email,
+            roles: config.roles.clone(),
+        })
+    }
+}
+
+/// Claims of an id_token received straight from the token endpoint over TLS, which
+/// OIDC allows in place of checking its signature
+fn id_token_claims(id_token: &str) -> Option<UserInfo> {
+    let mut validation = Validation::default();
+    validation.insecure_disable_signature_validation();
+    validation.validate_exp = false;
+    validation.validate_aud = false;
+    validation.required_spec_claims.clear();
+    jsonwebtoken::decode::<UserInfo>(id_token, &DecodingKey::from_secret(&[]), &validation)
+        .ok()
+        .map(|data| data.claims)
+}
+
+fn find_provider<'a>(providers: &'a [OidcConfig], name: Option<&str>) -> Result<&'a OidcConfig, ApiError> {
+    match (name, providers) {
+        (Some(name), _) => providers.iter().find(|p| p.name == name).ok_or_else(|| {
+            ApiError::not_found("OIDC_PROVIDER", format!("Identity provider '{}' is not configured", name))
+        }),
+        (None, [only]) => Ok(only),
+        (None, []) => Err(ApiError::not_found("OIDC_PROVIDER", "No identity providers are configured")),
+        (None, _) => Err(ApiError::from_status(
+            StatusCode::BAD_REQUEST,
+            "Several identity providers are configured; name one in `provider`",
+        )),
+    }
+}
+
+fn is_allowed(config: &OidcConfig, user: &SessionUser) -> bool {
+    config.allowed_users.is_empty()
+        || config
+            .allowed_users
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(&user.email) || allowed.eq_ignore_ascii_case(&user.username))
+}
+
+/// Finish an SSO sign-in: exchange the code, look up the user and mint a session.
+/// `state` was already checked by the web client against the value it stored.
+#[utoipa::path(
+    post,
+    path = "/api/auth/oidc/callback",
+    tag = "auth",
+    security(()),
+    request_body = OidcCallbackRequest,
+    responses(
+        (status = 200, description = "Session token", body = SessionResponse),
+        (status = 400, description = "Redirect URI does not match the provider's", body = ApiErrorResponse),
+        (status = 401, description = "Provider rejected the code", body = ApiErrorResponse),
+        (status = 403, description = "User is not allowed to sign in", body = ApiErrorResponse),
+        (status = 404, description = "No such provider", body = ApiErrorResponse),
+        (status = 502, description = "Provider could not be reached", body = ApiErrorResponse),
+    ),
+)]
+pub async fn oidc_callback(
+    State(state): State<AppState>,
+    Json(request): Json<OidcCallbackRequest>,
+) -> Result<(Extension<AuditActor>, Json<SessionResponse>), ApiError> {
+    let config = find_provider(&state.config.auth.oidc, request.provider.as_deref())?;
+
+    let redirect_uri = match request.redirect_uri.as_deref() {
+        Some(uri) if config.redirect_uris.iter().any(|allowed| allowed == uri) => uri,
+        Some(uri) => {
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "REDIRECT_URI_MISMATCH",
+                format!("Redirect URI '{}' is not registered for '{}'", uri, config.name),
+            ))
+        }
+        None => config.redirect_uris.first().map(String::as_str).ok_or_else(|| {
+            ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "REDIRECT_URI_MISMATCH",
+                format!("No redirect URIs are registered for '{}'", config.name),
+            )
+        })?,
+    };
+
+    let tokens = state.oidc.exchange_code(config, &request.code, redirect_uri).await?;
+    let user = state.oidc.fetch_user(config, &tokens).await?;
+
+    if !is_allowed(config, &user) {
+        warn!("Refused sign-in of {} through {}: not in allowed_users", user.username, config.name);
+        return Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            "USER_NOT_ALLOWED",
+            format!("{} is not allowed to sign in", user.username),
+        ));
+    }
+
+    let token = state.auth.sessions.mint(&user, &config.name).map_err(|e| {
+        ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create session: {}", e))
+    })?;
+    info!("{} signed in through {}", user.username, config.name);
+
+    Ok((
+        Extension(AuditActor(user.username.clone())),
+        Json(SessionResponse {
+            token,
+            expires_in: state.auth.sessions.ttl_secs(),
+            user,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{agent_with, TestAgent};
+    use axum::{http::Method, routing::get, routing::post, Form, Router};
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    const REDIRECT_URI: &str = "https://panel.example.com/auth/callback";
+
+    /// Identity provider accepting the code `good-code` and answering userinfo for the
+    /// access token it hands out
+    async fn stub_provider() -> String {
+        async fn token(Form(form): Form<HashMap<String, String>>) -> Json<Value> {
+            let valid = form.get("grant_type").map(String::as_str) == Some("authorization_code")
+                && form.get("code").map(String::as_str) == Some("good-code")
+                && form.get("redirect_uri").map(String::as_str) == Some(REDIRECT_URI)
+                && form.get("client_secret").map(String::as_str) == Some("client-secret");
+            Json(if valid {
+                json!({ "access_token": "stub-access", "token_type": "Bearer" })
+            } else {
+                json!({ "error": "invalid_grant", "error_description": "Code is expired" })
+            })
+        }
+
+        async fn userinfo(headers: axum::http::HeaderMap) -> Result<Json<Value>, StatusCode> {
+            if headers.get("authorization").and_then(|v| v.to_str().ok()) != Some("Bearer stub-access") {
+                return Err(StatusCode::UNAUTHORIZED);
+            }
+            Ok(Json(json!({
+                "sub": "42",
+                "email": "ada@example.com",
+                "preferred_username": "ada",
+            })))
+        }
+
+        let app = Router::new()
+            .route("/token", post(token))
+            .route("/userinfo", get(userinfo));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        url
+    }
+
+    fn provider_config(url: &str, allowed_users: &[&str]) -> OidcConfig {
+        OidcConfig {
+            name: "stub".to_string(),
+            provider: OidcProvider::Generic {
+                auth_url: format!("{}/authorize", url),
+                token_url: format!("{}/token", url),
+                userinfo_url: format!("{}/userinfo", url),
+            },
+            client_id: "gpanel".to_string(),
+            client_secret: Some("client-secret".to_string()),
+            redirect_uris: vec![REDIRECT_URI.to_string()],
+            allowed_users: allowed_users.iter().map(|user| user.to_string()).collect(),
+            roles: vec!["operator".to_string()],
+        }
+    }
+
+    async fn agent_for(config: OidcConfig) -> TestAgent {
+        agent_with(|panel| panel.auth.oidc.push(config), true).await
+    }
+
+    fn callback(code: &str, redirect_uri: Option<&str>) -> Option<Value> {
+        Some(json!({ "code": code, "state": "opaque", "redirect_uri": redirect_uri }))
+    }
+
+    #[tokio::test]
+    async fn code_is_exchanged_for_a_session() {
+        let url = stub_provider().await;
+        let agent = agent_for(provider_config(&url, &["ADA@example.com"])).await;
+
+        let (status, body) = agent
+            .request(Method::POST, "/api/auth/oidc/callback", callback("good-code", None))
+            .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["user"]["id"], "stub:42");
+        assert_eq!(body["user"]["username"], "ada");
+        assert_eq!(body["user"]["email"], "ada@example.com");
+        assert_eq!(body["user"]["roles"], json!(["operator"]));
+
+        let token = body["token"].as_str().unwrap();
+        let (status, me) = agent.request_as(Some(token), Method::GET, "/api/v1/auth/me", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(me["id"], "stub:42");
+    }
+
+    #[tokio::test]
+    async fn rejected_code_is_unauthorized() {
+        let url = stub_provider().await;
+        let agent = agent_for(provider_config(&url, &[])).await;
+
+        let (status, body) = agent
+            .request(Method::POST, "/api/auth/oidc/callback", callback("stale-code", Some(REDIRECT_URI)))
+            .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"]["code"], "OIDC_CODE_REJECTED");
+        assert!(body["error"]["message"].as_str().unwrap().contains("Code is expired"));
+    }
+
+    #[tokio::test]
+    async fn unregistered_redirect_uri_is_refused() {
+        let url = stub_provider().await;
+        let agent = agent_for(provider_config(&url, &[])).await;
+
+        let (status, body) = agent
+            .request(
+                Method::POST,
+                "/api/auth/oidc/callback",
+                callback("good-code", Some("https://evil.example.com/callback")),
+            )
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "REDIRECT_URI_MISMATCH");
+    }
+
+    #[tokio::test]
+    async fn users_outside_allowed_users_are_refused() {
+        let url = stub_provider().await;
+        let agent = agent_for(provider_config(&url, &["grace@example.com"])).await;
+
+        let (status, body) = agent
+            .request(Method::POST, "/api/auth/oidc/callback", callback("good-code", None))
+            .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["error"]["code"], "USER_NOT_ALLOWED");
+    }
+
+    #[tokio::test]
+    async fn unreachable_provider_is_a_bad_gateway() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        let agent = agent_for(provider_config(&url, &[])).await;
+
+        let (status, body) = agent
+            .request(Method::POST, "/api/auth/oidc/callback", callback("good-code", None))
+            .await;
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        assert_eq!(body["error"]["code"], "OIDC_PROVIDER_UNREACHABLE");
+    }
+
+    #[tokio::test]
+    async fn unknown_provider_is_not_found() {
+        let url = stub_provider().await;
+        let agent = agent_for(provider_config(&url, &[])).await;
+
+        let request = json!({ "code": "good-code", "state": "opaque", "provider": "azure" });
+        let (status, body) = agent.request(Method::POST, "/api/auth/oidc/callback", Some(request)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "OIDC_PROVIDER_NOT_FOUND");
+    }
+}
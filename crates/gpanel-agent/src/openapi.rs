@@ -0,0 +1,287 @@
+use axum::Router;
+use gpanel_core::{ApiErrorResponse, SystemEvent};
+use utoipa::{
+    openapi::{
+        security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+        ContentBuilder, OpenApi as OpenApiSpec, Ref, RefOr, ResponseBuilder,
+    },
+    Modify, OpenApi,
+};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::{audit, auth, autostart, backup, drift, environments, events, files, gaming, gpus, host, images, jobs, logs};
+use crate::{metrics, networks, notifications, oidc, proton, reload, schedules, system, terminal, volumes, watchdog};
+use crate::{registry_health, registry_stats, registry_webhooks, steam, webhooks, AppState};
+
+/// Name of the bearer scheme in the spec's security schemes, as used in `security(...)` above
+const BEARER_SCHEME: &str = "bearer_token";
+
+/// OpenAPI description of every agent route
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "GhostPanel Agent API", license(name = "MIT")),
+    paths(
+        crate::list_containers,
+        crate::create_container,
+        crate::batch_containers,
+        crate::validate_container,
+        crate::prune_containers,
+        crate::get_container,
+        crate::delete_container,
+        crate::start_container,
+        crate::stop_container,
+        crate::restart_container,
+        crate::pause_container,
+        crate::unpause_container,
+        crate::kill_container,
+        crate::exec_container,
+        crate::rename_container,
+        logs::get_container_logs,
+        logs::stream_container_logs,
+        crate::get_container_stats,
+        crate::stream_container_stats,
+        crate::top_container,
+        crate::wait_container,
+        terminal::container_terminal,
+        files::download_container_file,
+        files::upload_container_file,
+        files::export_container,
+        crate::commit_container,
+        watchdog::get_container_watchdog,
+        events::stream_events,
+        crate::list_projects,
+        environments::list_environments,
+        crate::list_registries,
+        crate::add_registry,
+        crate::update_registry,
+        crate::remove_registry,
+        registry_health::test_registry,
+        registry_stats::registry_stats,
+        drift::drift_info,
+        drift::trigger_drift_gc,
+        drift::drift_gc_status,
+        drift::drift_quota,
+        crate::list_repositories,
+        crate::list_tags,
+        crate::latest_stable_tag,
+        crate::get_image_info,
+        crate::tag_registry_image,
+        crate::export_registry_image,
+        crate::delete_registry_image,
+        images::list_local_images,
+        images::remove_local_image,
+        images::tag_local_image,
+        crate::search_images_get,
+        crate::search_images,
+        crate::pull_image,
+        crate::copy_image,
+        crate::import_image,
+        images::prune_images,
+        jobs::list_jobs,
+        jobs::get_job,
+        jobs::cancel_job,
+        networks::list_networks,
+        networks::create_network,
+        networks::get_network,
+        networks::delete_network,
+        networks::connect_network,
+        networks::disconnect_network,
+        volumes::list_volumes,
+        volumes::create_volume,
+        volumes::prune_volumes,
+        volumes::get_volume,
+        volumes::delete_volume,
+        volumes::browse_volume,
+        volumes::download_volume_file,
+        system::system_info,
+        system::disk_usage,
+        host::host_metrics,
+        gpus::list_gpus,
+        gpus::list_allocations,
+        backup::download_backup,
+        backup::restore_backup,
+        autostart::autostart_report,
+        reload::reload_config,
+        metrics::metrics_summary,
+        notifications::list_channels,
+        notifications::create_channel,
+        notifications::delete_channel,
+        notifications::test_channel,
+        notifications::list_rules,
+        notifications::create_rule,
+        notifications::update_rule,
+        notifications::delete_rule,
+        schedules::list_schedules,
+        schedules::create_schedule,
+        schedules::get_schedule,
+        schedules::update_schedule,
+        schedules::delete_schedule,
+        gaming::list_profiles,
+        gaming::create_profile,
+        gaming::get_profile,
+        gaming::update_profile,
+        gaming::delete_profile,
+        proton::list_versions,
+        proton::install_version,
+        steam::search_apps,
+        steam::get_app,
+        webhooks::list_webhooks,
+        webhooks::create_webhook,
+        webhooks::delete_webhook,
+        webhooks::test_webhook,
+        webhooks::list_webhook_failures,
+        registry_webhooks::receive_registry_webhook,
+        crate::health_check,
+        auth::whoami,
+        auth::login,
+        auth::logout,
+        auth::refresh,
+        auth::me,
+        audit::list_audit_events,
+        oidc::oidc_callback,
+    ),
+    components(schemas(ApiErrorResponse, SystemEvent)),
+    modifiers(&BearerAuth),
+    security(("bearer_token" = [])),
+    tags(
+        (name = "containers", description = "Container lifecycle, logs, stats, files and terminals, in the environment named by the `X-GPanel-Env` header or `env` query parameter"),
+        (name = "environments", description = "Bolt hosts managed by this agent"),
+        (name = "images", description = "Local images and registry search, pull and copy"),
+        (name = "jobs", description = "Background operations such as image pulls and Proton installs"),
+        (name = "registries", description = "Configured registries and their repositories"),
+        (name = "networks", description = "Networks and container attachments"),
+        (name = "volumes", description = "Volumes and their contents"),
+        (name = "system", description = "Host information, disk usage, backups, config reloads and health"),
+        (name = "events", description = "Live container events"),
+        (name = "notifications", description = "Alert channels and rules"),
+        (name = "schedules", description = "Scheduled container actions"),
+        (name = "gaming", description = "Gaming profiles applied to containers at creation, Proton builds and Steam game lookups"),
+        (name = "webhooks", description = "Outbound event webhooks"),
+        (name = "auth", description = "Sign-in, sessions and the current caller"),
+        (name = "audit", description = "Record of changes made through the API"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Declare the bearer scheme and document the 401 of every operation that needs it.
+/// Public operations opt out with `security(())`, which leaves their `security` set.
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut OpenApiSpec) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            BEARER_SCHEME,
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("An API token, or a session token from /api/v1/auth/login"))
+                    .build(),
+            ),
+        );
+
+        let unauthorized = ResponseBuilder::new()
+            .description("Missing, invalid or expired token")
+            .content(
+                "application/json",
+                ContentBuilder::new()
+                    .schema(Some(Ref::from_schema_name("ApiErrorResponse")))
+                    .build(),
+            )
+            .build();
+        for item in openapi.paths.paths.values_mut() {
+            let operations = [
+                &mut item.get,
+                &mut item.put,
+                &mut item.post,
+                &mut item.delete,
+                &mut item.patch,
+            ];
+            for operation in operations.into_iter().flatten() {
+                if operation.security.is_none() {
+                    operation
+                        .responses
+                        .responses
+                        .entry("401".to_string())
+                        .or_insert_with(|| RefOr::T(unauthorized.clone()));
+                }
+            }
+        }
+    }
+}
+
+/// The spec at `/api/v1/openapi.json` and Swagger UI at `/api/v1/docs`. Both sit behind
+/// the token check like the rest of `/api/v1`, so with auth enabled the UI's own requests
+/// need an `Authorization` header.
+pub fn routes() -> Router<AppState> {
+    SwaggerUi::new("/api/v1/docs")
+        .url("/api/v1/openapi.json", ApiDoc::openapi())
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::agent;
+    use axum::http::{Method, StatusCode};
+
+    /// Every route under `/api/v1/containers`, as mounted in `app`
+    const CONTAINER_ROUTES: &[(&str, &str)] = &[
+        ("get", "/api/v1/containers"),
+        ("post", "/api/v1/containers"),
+        ("post", "/api/v1/containers/batch"),
+        ("post", "/api/v1/containers/validate"),
+        ("post", "/api/v1/containers/prune"),
+        ("get", "/api/v1/containers/{id}"),
+        ("delete", "/api/v1/containers/{id}"),
+        ("post", "/api/v1/containers/{id}/start"),
+        ("post", "/api/v1/containers/{id}/stop"),
+        ("post", "/api/v1/containers/{id}/restart"),
+        ("post", "/api/v1/containers/{id}/pause"),
+        ("post", "/api/v1/containers/{id}/unpause"),
+        ("post", "/api/v1/containers/{id}/kill"),
+        ("post", "/api/v1/containers/{id}/exec"),
+        ("post", "/api/v1/containers/{id}/rename"),
+        ("get", "/api/v1/containers/{id}/logs"),
+        ("get", "/api/v1/containers/{id}/logs/stream"),
+        ("get", "/api/v1/containers/{id}/stats"),
+        ("get", "/api/v1/containers/{id}/stats/stream"),
+        ("get", "/api/v1/containers/{id}/top"),
+        ("post", "/api/v1/containers/{id}/wait"),
+        ("get", "/api/v1/containers/{id}/terminal"),
+        ("get", "/api/v1/containers/{id}/files"),
+        ("put", "/api/v1/containers/{id}/files"),
+        ("get", "/api/v1/containers/{id}/export"),
+        ("post", "/api/v1/containers/{id}/commit"),
+        ("get", "/api/v1/containers/{id}/watchdog"),
+    ];
+
+    #[tokio::test]
+    async fn spec_documents_every_container_route() {
+        let agent = agent().await;
+        let (status, spec) = agent.request(Method::GET, "/api/v1/openapi.json", None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        for (method, path) in CONTAINER_ROUTES {
+            let operation = &spec["paths"][path][method];
+            assert!(operation.is_object(), "{} {} is missing from the spec", method, path);
+            assert_eq!(operation["tags"][0], "containers", "{} {}", method, path);
+            assert!(operation["responses"]["401"].is_object(), "{} {} lacks its 401", method, path);
+        }
+    }
+
+    #[tokio::test]
+    async fn sign_in_operations_need_no_token() {
+        let agent = agent().await;
+        let (_, spec) = agent.request(Method::GET, "/api/v1/openapi.json", None).await;
+
+        assert!(spec["components"]["securitySchemes"][super::BEARER_SCHEME].is_object());
+        for (method, path) in [
+            ("post", "/api/v1/auth/login"),
+            ("post", "/api/auth/oidc/callback"),
+            ("post", "/api/v1/webhooks/registry"),
+        ] {
+            let operation = &spec["paths"][path][method];
+            assert_eq!(operation["security"], serde_json::json!([{}]), "{} {}", method, path);
+        }
+    }
+}
@@ -0,0 +1,559 @@
+use anyhow::{Context, bail};
+use axum::{extract::State, http::StatusCode, response::Json};
+use chrono::{DateTime, Utc};
+use gpanel_core::{
+    ApiErrorResponse, InstalledProton, JobAccepted, JobKind, JobStatus, ProtonConfig, ProtonInstallRequest,
+    ProtonRelease, ProtonVersions,
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha512};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+
+use crate::auth::RequireOperator;
+use crate::error::ApiError;
+use crate::jobs::JobProgress;
+use crate::AppState;
+
+/// GitHub rejects API calls without a User-Agent
+const USER_AGENT: &str = "gpanel-agent";
+
+/// How long to wait for the release list; downloads have no overall limit
+const GITHUB_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Releases asked for per request; older GE-Proton builds are rarely wanted
+const RELEASES_PER_PAGE: u32 = 30;
+
+/// Wait used when GitHub refuses a request without saying for how long
+const DEFAULT_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+const MIB: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    published_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+    size: u64,
+}
+
+impl GithubRelease {
+    fn archive(&self) -> Option<&GithubAsset> {
+        self.assets.iter().find(|asset| asset.name.ends_with(".tar.gz"))
+    }
+
+    fn checksum(&self) -> Option<&GithubAsset> {
+        self.assets.iter().find(|asset| asset.name.ends_with(".sha512sum"))
+    }
+}
+
+#[derive(Default)]
+struct ReleaseCache {
+    fetched: Option<(Instant, Vec<GithubRelease>)>,
+    rate_limited_until: Option<DateTime<Utc>>,
+}
+
+/// Installed Proton builds and the GE-Proton releases that can be installed
+pub struct ProtonManager {
+    config: ProtonConfig,
+    client: reqwest::Client,
+    /// Held while GitHub is asked, so concurrent requests share one fetch
+    cache: Mutex<ReleaseCache>,
+}
+
+impl ProtonManager {
+    pub fn new(config: &ProtonConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(GITHUB_TIMEOUT)
+            .user_agent(USER_AGENT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            config: config.clone(),
+            client,
+            cache: Mutex::new(ReleaseCache::default()),
+        }
+    }
+
+    fn install_dir(&self) -> &Path {
+        Path::new(&self.config.install_dir)
+    }
+
+    /// Builds in the install directory, each a directory holding a `proton` script or a
+    /// `compatibilitytool.vdf`. Dot directories are unfinished installs.
+    pub fn installed(&self) -> Vec<InstalledProton> {
+        let Ok(entries) = std::fs::read_dir(self.install_dir()) else {
+            return Vec::new();
+        };
+        let mut installed: Vec<InstalledProton> = entries
+            .flatten()
+            .filter(|entry| entry.path().join("proton").is_file() || entry.path().join("compatibilitytool.vdf").is_file())
+            .map(|entry| InstalledProton {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                path: entry.path().display().to_string(),
+            })
+            .filter(|proton| !proton.name.starts_with('.'))
+            .collect();
+        installed.sort_by(|a, b| version_key(&b.name).cmp(&version_key(&a.name)).then_with(|| a.name.cmp(&b.name)));
+        installed
+    }
+
+    /// Releases from the cache, refreshed once it is older than `releases_cache_secs`. A
+    /// failed refresh keeps the previous list and reports why.
+    async fn releases(&self) -> (Vec<GithubRelease>, Option<String>, Option<DateTime<Utc>>) {
+        let mut cache = self.cache.lock().await;
+        let max_age = Duration::from_secs(self.config.releases_cache_secs);
+        let stale = cache.fetched.as_ref().is_none_or(|(fetched_at, _)| fetched_at.elapsed() >= max_age);
+
+        let mut refresh_error = None;
+        if stale {
+            match cache.rate_limited_until.filter(|until| *until > Utc::now()) {
+                Some(until) => refresh_error = Some(format!("GitHub rate limit reached, retrying after {}", until.to_rfc3339())),
+                None => match self.fetch_releases(&mut cache).await {
+                    Ok(releases) => cache.fetched = Some((Instant::now(), releases)),
+                    Err(e) => {
+                        warn!("Failed to list GE-Proton releases: {:#}", e);
+                        refresh_error = Some(format!("{:#}", e));
+                    }
+                },
+            }
+        }
+
+        let releases = cache.fetched.as_ref().map(|(_, releases)| releases.clone()).unwrap_or_default();
+        let rate_limited_until = cache.rate_limited_until.filter(|until| *until > Utc::now());
+        (releases, refresh_error, rate_limited_until)
+    }
+
+    /// Ask GitHub for the release list, noting in `cache` when the rate limit runs out
+    async fn fetch_releases(&self, cache: &mut ReleaseCache) -> anyhow::Result<Vec<GithubRelease>> {
+        let response = self
+            .client
+            .get(&self.config.releases_url)
+            .query(&[("per_page", RELEASES_PER_PAGE)])
+            .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+            .timeout(GITHUB_TIMEOUT)
+            .send()
+            .await
+            .context("Failed to reach GitHub")?;
+
+        let status = response.status();
+        if let Some(until) = rate_limit_reset(status, response.headers()) {
+            cache.rate_limited_until = Some(until);
+            bail!("GitHub rate limit reached, retrying after {}", until.to_rfc3339());
+        }
+        if !status.is_success() {
+            bail!("GitHub answered {}", status);
+        }
+        cache.rate_limited_until = None;
+
+        let releases: Vec<GithubRelease> = response.json().await.context("Unexpected release list from GitHub")?;
+        Ok(releases
+            .into_iter()
+            .filter(|release| !release.draft && release.archive().is_some())
+            .collect())
+    }
+
+    pub async fn versions(&self) -> ProtonVersions {
+        let installed = self.installed();
+        let (releases, available_error, rate_limited_until) = self.releases().await;
+        let available = releases
+            .iter()
+            .map(|release| ProtonRelease {
+                tag: release.tag_name.clone(),
+                published_at: release.published_at,
+                size_bytes: release.archive().map(|archive| archive.size).unwrap_or(0),
+                installed: installed.iter().any(|proton| proton.name == release.tag_name),
+            })
+            .collect();
+        ProtonVersions {
+            installed,
+            available,
+            available_error,
+            rate_limited_until,
+        }
+    }
+
+    /// Download a release, check it against its published SHA-512 sum and unpack it
+    async fn install(&self, release: GithubRelease, progress: JobProgress) -> anyhow::Result<String> {
+        let tag = release.tag_name.clone();
+        let (Some(archive), Some(checksum)) = (release.archive(), release.checksum()) else {
+            bail!("Release {} publishes no checksum, refusing to install it", tag);
+        };
+
+        progress.update(0, "Fetching the checksum");
+        let expected = self.expected_checksum(checksum, &archive.name).await?;
+
+        let install_dir = self.install_dir().to_path_buf();
+        tokio::fs::create_dir_all(&install_dir)
+            .await
+            .with_context(|| format!("Failed to create {}", install_dir.display()))?;
+        // Dot names keep unfinished downloads out of the installed list
+        let download = install_dir.join(format!(".{}.partial", archive.name));
+        self.download(archive, &download, &expected, &progress).await?;
+
+        progress.update(90, format!("Unpacking {}", archive.name));
+        let target = install_dir.join(&tag);
+        let unpack = {
+            let download = download.clone();
+            let staging = install_dir.join(format!(".{}.staging", tag));
+            let target = target.clone();
+            tokio::task::spawn_blocking(move || unpack_release(&download, &staging, &target))
+        };
+        let result = unpack.await.context("Unpacking stopped unexpectedly").and_then(|result| result);
+        let _ = tokio::fs::remove_file(&download).await;
+        result?;
+
+        info!("Installed Proton {} in {}", tag, target.display());
+        Ok(format!("Installed {}", tag))
+    }
+
+    /// The hex digest a `.sha512sum` asset gives for `archive_name`
+    async fn expected_checksum(&self, checksum: &GithubAsset, archive_name: &str) -> anyhow::Result<String> {
+        let response = self
+            .client
+            .get(&checksum.browser_download_url)
+            .timeout(GITHUB_TIMEOUT)
+            .send()
+            .await
+            .with_context(|| format!("Failed to download {}", checksum.name))?;
+        if !response.status().is_success() {
+            bail!("Failed to download {}: {}", checksum.name, response.status());
+        }
+        let text = response.text().await?;
+
+        // `<digest>  <file>` lines; a file holding a bare digest applies to the archive
+        text.lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                Some((fields.next()?, fields.next()))
+            })
+            .find(|(_, file)| file.is_none_or(|file| file.trim_start_matches('*') == archive_name))
+            .map(|(digest, _)| digest.to_ascii_lowercase())
+            .filter(|digest| digest.len() == 128 && digest.chars().all(|c| c.is_ascii_hexdigit()))
+            .with_context(|| format!("{} has no SHA-512 sum for {}", checksum.name, archive_name))
+    }
+
+    /// Stream `archive` to `path`, hashing as it goes
+    async fn download(&self, archive: &GithubAsset, path: &Path, expected: &str, progress: &JobProgress) -> anyhow::Result<()> {
+        let mut response = self
+            .client
+            .get(&archive.browser_download_url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to download {}", archive.name))?;
+        if !response.status().is_success() {
+            bail!("Failed to download {}: {}", archive.name, response.status());
+        }
+        let total = response.content_length().unwrap_or(archive.size);
+
+        let mut file = tokio::fs::File::create(path)
+            .await
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        let mut hasher = Sha512::new();
+        let mut written = 0u64;
+        while let Some(chunk) = response.chunk().await.with_context(|| format!("Failed to download {}", archive.name))? {
+            hasher.update(&chunk);
+            file.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+            let percent = (written * 85).checked_div(total).map_or(5, |share| 5 + share.min(85));
+            progress.update(percent as u8, format!("Downloaded {} of {} MiB", written / MIB, total / MIB));
+        }
+        file.flush().await?;
+        drop(file);
+
+        let actual = format!("{:x}", hasher.finalize());
+        if actual != expected {
+            let _ = tokio::fs::remove_file(path).await;
+            bail!("{} failed verification: SHA-512 is {}, expected {}", archive.name, actual, expected);
+        }
+        Ok(())
+    }
+}
+
+/// Unpack into `staging` and move the release's top directory to `target`, so a failed
+/// unpack never leaves a half-installed build behind
+fn unpack_release(archive: &Path, staging: &Path, target: &Path) -> anyhow::Result<()> {
+    if staging.exists() {
+        std::fs::remove_dir_all(staging).with_context(|| format!("Failed to clear {}", staging.display()))?;
+    }
+    std::fs::create_dir_all(staging)?;
+
+    let result = (|| {
+        let file = std::fs::File::open(archive)?;
+        // `unpack` refuses entries that would land outside `staging`
+        tar::Archive::new(flate2::read::GzDecoder::new(file))
+            .unpack(staging)
+            .with_context(|| format!("Failed to unpack {}", archive.display()))?;
+
+        let mut top: Vec<PathBuf> = std::fs::read_dir(staging)?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        let [release_dir] = top.as_mut_slice() else {
+            bail!("Expected one directory in the archive, found {}", top.len());
+        };
+        std::fs::rename(&*release_dir, target).with_context(|| format!("Failed to move the build to {}", target.display()))
+    })();
+    let _ = std::fs::remove_dir_all(staging);
+    result
+}
+
+/// When a refused request may be retried: GitHub answers 403 or 429 with either
+/// `retry-after` seconds or an exhausted `x-ratelimit-remaining` and its reset time
+fn rate_limit_reset(status: reqwest::StatusCode, headers: &reqwest::header::HeaderMap) -> Option<DateTime<Utc>> {
+    if status != reqwest::StatusCode::FORBIDDEN && status != reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+    let header = |name: &str| headers.get(name)?.to_str().ok()?.trim().parse::<i64>().ok();
+
+    if let Some(seconds) = header("retry-after") {
+        return Some(Utc::now() + chrono::Duration::seconds(seconds.max(0)));
+    }
+    if header("x-ratelimit-remaining") == Some(0) {
+        return header("x-ratelimit-reset")
+            .and_then(|reset| DateTime::from_timestamp(reset, 0))
+            .or_else(|| Some(Utc::now() + DEFAULT_RATE_LIMIT_WAIT));
+    }
+    // A plain 429 is a rate limit even without the headers
+    (status == reqwest::StatusCode::TOO_MANY_REQUESTS).then(|| Utc::now() + DEFAULT_RATE_LIMIT_WAIT)
+}
+
+/// The numbers in a build name, so `GE-Proton10-3` sorts after `GE-Proton9-20`
+fn version_key(name: &str) -> Vec<u64> {
+    name.split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+/// Installed Proton builds and the GE-Proton releases on GitHub
+#[utoipa::path(
+    get,
+    path = "/api/v1/gaming/proton/versions",
+    tag = "gaming",
+    responses((
+        status = 200,
+        description = "Installed builds, and releases that may be cached or empty when GitHub is unavailable",
+        body = ProtonVersions,
+    )),
+)]
+pub async fn list_versions(State(state): State<AppState>) -> Json<ProtonVersions> {
+    Json(state.proton.versions().await)
+}
+
+/// Start installing a GE-Proton release; follow it at `/api/v1/jobs/{id}`
+#[utoipa::path(
+    post,
+    path = "/api/v1/gaming/proton/install",
+    tag = "gaming",
+    request_body = ProtonInstallRequest,
+    responses(
+        (status = 202, description = "Install started as a background job", body = JobAccepted),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such release", body = ApiErrorResponse),
+        (status = 409, description = "Already installed or being installed", body = ApiErrorResponse),
+        (status = 422, description = "Invalid tag", body = ApiErrorResponse),
+        (status = 502, description = "GitHub is unavailable", body = ApiErrorResponse),
+        (status = 503, description = "GitHub rate limit reached", body = ApiErrorResponse),
+    ),
+)]
+pub async fn install_version(
+    _: RequireOperator,
+    State(state): State<AppState>,
+    Json(request): Json<ProtonInstallRequest>,
+) -> Result<(StatusCode, Json<JobAccepted>), ApiError> {
+    let tag = request.tag;
+    // The tag becomes a directory name
+    if tag.is_empty() || tag.starts_with('.') || !tag.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')) {
+        return Err(ApiError::from_status(StatusCode::UNPROCESSABLE_ENTITY, format!("Invalid release tag '{}'", tag)));
+    }
+    if state.proton.installed().iter().any(|proton| proton.name == tag) {
+        return Err(ApiError::new(StatusCode::CONFLICT, "PROTON_INSTALLED", format!("{} is already installed", tag)));
+    }
+    let installing = state
+        .jobs
+        .list()
+        .iter()
+        .any(|job| job.kind == JobKind::ProtonInstall && job.status == JobStatus::Running && job.target == tag);
+    if installing {
+        return Err(ApiError::new(StatusCode::CONFLICT, "PROTON_INSTALLING", format!("{} is already being installed", tag)));
+    }
+
+    let (releases, refresh_error, rate_limited_until) = state.proton.releases().await;
+    let Some(release) = releases.into_iter().find(|release| release.tag_name == tag) else {
+        return Err(match (rate_limited_until, refresh_error) {
+            (Some(until), _) => ApiError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "GITHUB_RATE_LIMITED",
+                format!("GitHub rate limit reached, retry after {}", until.to_rfc3339()),
+            )
+            .with_details(serde_json::json!({ "retry_after": until })),
+            (None, Some(e)) => ApiError::from_status(StatusCode::BAD_GATEWAY, format!("Failed to list GE-Proton releases: {}", e)),
+            (None, None) => ApiError::not_found("PROTON_RELEASE", format!("GE-Proton release '{}' not found", tag)),
+        });
+    };
+
+    let proton = state.proton.clone();
+    let job = state.jobs.start(JobKind::ProtonInstall, tag.clone(), move |progress| async move {
+        proton
+            .install(release, progress)
+            .await
+            .inspect_err(|e| error!("Failed to install Proton {}: {:#}", tag, e))
+    });
+    Ok((StatusCode::ACCEPTED, Json(JobAccepted { job_id: job.id })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{agent_with, serve_stub, TestAgent};
+    use axum::{
+        extract::Path as UrlPath,
+        http::{HeaderMap, Method},
+        routing::get,
+        Router,
+    };
+    use gpanel_core::Job;
+
+    const GOOD: &str = "GE-Proton9-20";
+    const TAMPERED: &str = "GE-Proton9-21";
+
+    /// A gzipped tarball holding `<tag>/proton`, as GE-Proton releases do
+    fn release_archive(tag: &str) -> Vec<u8> {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast()));
+        let script = b"#!/usr/bin/env python3\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(script.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder.append_data(&mut header, format!("{}/proton", tag), &script[..]).unwrap();
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    /// A GitHub releases API whose assets are served from the same stub. The tampered
+    /// release's checksum is of a different archive.
+    fn github() -> Router {
+        let releases = |headers: HeaderMap| async move {
+            let host = headers["host"].to_str().unwrap().to_string();
+            let release = |tag: &str, draft: bool| {
+                let asset = |name: String| {
+                    serde_json::json!({ "name": name, "browser_download_url": format!("http://{}/download/{}", host, name), "size": 1024 })
+                };
+                serde_json::json!({
+                    "tag_name": tag,
+                    "published_at": "2025-01-10T12:00:00Z",
+                    "draft": draft,
+                    "assets": [asset(format!("{}.tar.gz", tag)), asset(format!("{}.sha512sum", tag))],
+                })
+            };
+            Json(serde_json::json!([
+                release(TAMPERED, false),
+                release(GOOD, false),
+                release("GE-Proton10-1", true),
+                { "tag_name": "notes-only", "assets": [] },
+            ]))
+        };
+        let download = |UrlPath(name): UrlPath<String>| async move {
+            if let Some(tag) = name.strip_suffix(".tar.gz") {
+                return release_archive(tag);
+            }
+            let tag = name.strip_suffix(".sha512sum").unwrap();
+            let summed = if tag == TAMPERED { release_archive(GOOD) } else { release_archive(tag) };
+            format!("{:x}  {}.tar.gz\n", Sha512::digest(&summed), tag).into_bytes()
+        };
+        Router::new()
+            .route("/releases", get(releases))
+            .route("/download/:name", get(download))
+    }
+
+    async fn proton_agent() -> TestAgent {
+        let url = serve_stub(github()).await;
+        agent_with(
+            |config| {
+                config.proton.install_dir = format!("{}/compatibilitytools.d", config.data_dir);
+                config.proton.releases_url = format!("{}/releases", url);
+            },
+            false,
+        )
+        .await
+    }
+
+    async fn install(agent: &TestAgent, tag: &str) -> Job {
+        let request = serde_json::json!({ "tag": tag });
+        let (status, body) = agent.request(Method::POST, "/api/v1/gaming/proton/install", Some(request)).await;
+        assert_eq!(status, StatusCode::ACCEPTED, "{}", body);
+        let id = body["job_id"].as_str().unwrap().to_string();
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let job = agent.state.jobs.get(&id).unwrap();
+                if job.status.is_finished() {
+                    return job;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .expect("install did not finish")
+    }
+
+    #[tokio::test]
+    async fn releases_are_listed_and_verified_installs_unpacked() {
+        let agent = proton_agent().await;
+
+        let (status, versions) = agent.request(Method::GET, "/api/v1/gaming/proton/versions", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(versions["available_error"].is_null(), "{}", versions);
+        // Drafts and releases without an archive are left out
+        let tags: Vec<&str> = versions["available"].as_array().unwrap().iter().map(|r| r["tag"].as_str().unwrap()).collect();
+        assert_eq!(tags, [TAMPERED, GOOD]);
+        assert_eq!(versions["installed"], serde_json::json!([]));
+
+        let job = install(&agent, GOOD).await;
+        assert_eq!(job.status, JobStatus::Succeeded, "{}", job.message);
+        let install_dir = PathBuf::from(&agent.state.config.proton.install_dir);
+        assert!(install_dir.join(GOOD).join("proton").is_file());
+
+        let (_, versions) = agent.request(Method::GET, "/api/v1/gaming/proton/versions", None).await;
+        assert_eq!(versions["installed"][0]["name"], GOOD);
+        assert_eq!(versions["available"][1]["installed"], true);
+
+        let (status, body) = agent
+            .request(Method::POST, "/api/v1/gaming/proton/install", Some(serde_json::json!({ "tag": GOOD })))
+            .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"]["code"], "PROTON_INSTALLED");
+        let (status, body) = agent
+            .request(Method::POST, "/api/v1/gaming/proton/install", Some(serde_json::json!({ "tag": "GE-Proton1-1" })))
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "PROTON_RELEASE_NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn archives_failing_their_checksum_are_not_installed() {
+        let agent = proton_agent().await;
+
+        let job = install(&agent, TAMPERED).await;
+        assert_eq!(job.status, JobStatus::Failed);
+        assert!(job.message.contains("failed verification"), "{}", job.message);
+
+        // Neither the build nor the partial download is left behind
+        let install_dir = PathBuf::from(&agent.state.config.proton.install_dir);
+        let left: Vec<_> = std::fs::read_dir(&install_dir).unwrap().flatten().map(|entry| entry.file_name()).collect();
+        assert!(left.is_empty(), "{:?}", left);
+        let (_, versions) = agent.request(Method::GET, "/api/v1/gaming/proton/versions", None).await;
+        assert_eq!(versions["installed"], serde_json::json!([]));
+    }
+}
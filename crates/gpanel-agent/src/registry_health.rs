@@ -0,0 +1,195 @@
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use futures::StreamExt;
+use gpanel_core::{ApiErrorResponse, RegistryClient, RegistryConfig, RegistryHealth, RegistryManager, RegistryStatus};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use crate::auth::RequireAdmin;
+use crate::error::ApiError;
+use crate::{AddRegistryRequest, AppState};
+
+/// How often every registry is checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Registries checked at once
+const CHECK_CONCURRENCY: usize = 4;
+
+/// Last health check of each registry
+#[derive(Default)]
+pub struct RegistryHealthMonitor {
+    checks: RwLock<HashMap<String, RegistryHealth>>,
+}
+
+impl RegistryHealthMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The last check of `name`, if it has been checked yet
+    pub async fn get(&self, name: &str) -> Option<RegistryHealth> {
+        self.checks.read().await.get(name).cloned()
+    }
+
+    /// Check one registry and keep the result
+    pub async fn check(&self, name: &str, client: &RegistryClient) -> RegistryHealth {
+        let health = client.health().await;
+        self.record(name, &health).await;
+        health
+    }
+
+    /// Check every registry of the manager, dropping results of registries since removed
+    pub async fn check_all(&self, manager: &RwLock<RegistryManager>) {
+        let clients: Vec<(String, RegistryClient)> = {
+            let manager = manager.read().await;
+            manager
+                .list_registries()
+                .into_iter()
+                .filter_map(|name| Some((name.to_string(), manager.get_registry(name)?.clone())))
+                .collect()
+        };
+        let checks: Vec<_> = clients
+            .iter()
+            .map(|(name, client)| async move { (name, client.health().await) })
+            .collect();
+        let results: Vec<_> = futures::stream::iter(checks)
+            .buffer_unordered(CHECK_CONCURRENCY)
+            .collect()
+            .await;
+
+        for (name, health) in &results {
+            self.record(name, health).await;
+        }
+        self.checks
+            .write()
+            .await
+            .retain(|name, _| clients.iter().any(|(configured, _)| configured == name));
+    }
+
+    async fn record(&self, name: &str, health: &RegistryHealth) {
+        let previous = self.checks.write().await.insert(name.to_string(), health.clone());
+        // Log changes only, so a registry that stays down does not fill the log
+        if health.status != RegistryStatus::Ok && previous.is_none_or(|previous| previous.status != health.status) {
+            warn!(
+                "Registry {} is {:?}: {}",
+                name,
+                health.status,
+                health.message.as_deref().unwrap_or_default()
+            );
+        }
+    }
+}
+
+/// Check every registry each `CHECK_INTERVAL`, starting right away
+pub async fn run_health_checks(monitor: Arc<RegistryHealthMonitor>, manager: Arc<RwLock<RegistryManager>>) {
+    let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+    loop {
+        ticker.tick().await;
+        monitor.check_all(&manager).await;
+    }
+}
+
+/// Check a registry now. With a body, the given settings are checked instead of the saved
+/// ones, without saving them, e.g. before adding the registry.
+#[utoipa::path(
+    post,
+    path = "/api/v1/registries/{name}/test",
+    tag = "registries",
+    params(("name" = String, Path, description = "Registry name")),
+    request_body(content = Option<AddRegistryRequest>, description = "Settings to check instead of the saved ones"),
+    responses(
+        (status = 200, description = "How the registry answered", body = RegistryHealth),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+        (status = 404, description = "No such registry and no settings given", body = ApiErrorResponse),
+    ),
+)]
+pub async fn test_registry(
+    _: RequireAdmin,
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    request: Option<Json<AddRegistryRequest>>,
+) -> Result<Json<RegistryHealth>, ApiError> {
+    if let Some(Json(request)) = request {
+        let config = RegistryConfig {
+            name,
+            url: request.url,
+            username: request.username,
+            password: request.password,
+            insecure: request.insecure,
+            page_size: request.page_size,
+            max_list_entries: request.max_list_entries,
+            search_url: request.search_url,
+            ca_cert_path: request.ca_cert_path,
+        };
+        let client = RegistryClient::with_proxy(config, &state.config.proxy);
+        return Ok(Json(client.health().await));
+    }
+
+    let client = state
+        .registry_manager
+        .read()
+        .await
+        .get_registry(&name)
+        .cloned()
+        .ok_or_else(|| ApiError::not_found("REGISTRY", format!("Registry '{}' not found", name)))?;
+    Ok(Json(state.registry_health.check(&name, &client).await))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::serve_tls_stub;
+    use axum::{routing::get, Router};
+
+    fn registry(url: &str, configure: impl FnOnce(&mut RegistryConfig)) -> RegistryClient {
+        let mut config = RegistryConfig {
+            name: "harbor".to_string(),
+            url: url.to_string(),
+            username: None,
+            password: None,
+            insecure: false,
+            ca_cert_path: None,
+            page_size: None,
+            max_list_entries: None,
+            search_url: None,
+        };
+        configure(&mut config);
+        RegistryClient::new(config)
+    }
+
+    #[tokio::test]
+    async fn self_signed_registries_need_insecure_or_their_ca() {
+        let (url, pem) = serve_tls_stub(Router::new().route("/v2/", get(|| async { "{}" }))).await;
+
+        let health = registry(&url, |_| {}).health().await;
+        assert_eq!(health.status, RegistryStatus::TlsError, "{:?}", health.message);
+
+        let health = registry(&url, |config| config.insecure = true).health().await;
+        assert_eq!(health.status, RegistryStatus::Ok, "{:?}", health.message);
+
+        let dir = tempfile::tempdir().unwrap();
+        let ca_path = dir.path().join("ca.pem");
+        std::fs::write(&ca_path, pem).unwrap();
+        let health = registry(&url, |config| config.ca_cert_path = Some(ca_path.display().to_string()))
+            .health()
+            .await;
+        assert_eq!(health.status, RegistryStatus::Ok, "{:?}", health.message);
+    }
+
+    #[tokio::test]
+    async fn plain_http_needs_insecure() {
+        let url = crate::testing::serve_stub(Router::new().route("/v2/", get(|| async { "{}" }))).await;
+
+        let health = registry(&url, |_| {}).health().await;
+        assert_eq!(health.status, RegistryStatus::TlsError);
+        assert!(registry(&url, |_| {}).authenticate().await.is_err());
+
+        let health = registry(&url, |config| config.insecure = true).health().await;
+        assert_eq!(health.status, RegistryStatus::Ok);
+    }
+}
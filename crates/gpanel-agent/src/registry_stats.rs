@@ -0,0 +1,148 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use gpanel_core::{ApiErrorResponse, JobAccepted, JobKind, JobStatus, RegistryStats};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tracing::{error, info};
+use utoipa::IntoParams;
+
+use crate::error::ApiError;
+use crate::AppState;
+
+/// How long stats are served before they are counted again
+const STATS_TTL_SECS: i64 = 60 * 60;
+
+/// Last stats counted for each registry. A std mutex, as the lock is never held across
+/// an await.
+#[derive(Default)]
+pub struct RegistryStatsCache {
+    stats: Mutex<HashMap<String, RegistryStats>>,
+}
+
+impl RegistryStatsCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stats of `name` counted within the last `STATS_TTL_SECS`
+    pub fn get(&self, name: &str) -> Option<RegistryStats> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::seconds(STATS_TTL_SECS);
+        self.stats()
+            .get(name)
+            .filter(|stats| stats.computed_at > cutoff)
+            .cloned()
+    }
+
+    fn insert(&self, name: &str, stats: RegistryStats) {
+        self.stats().insert(name.to_string(), stats);
+    }
+
+    /// Drop the stats of `name`, as when it is edited or removed
+    pub fn forget(&self, name: &str) {
+        self.stats().remove(name);
+    }
+
+    fn stats(&self) -> std::sync::MutexGuard<'_, HashMap<String, RegistryStats>> {
+        self.stats.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RegistryStatsQuery {
+    /// Count again even if the cached stats are recent
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+/// Repositories, tags and distinct layers of a registry. Counting walks the whole catalog,
+/// so it runs as a job followed at `/api/v1/jobs/{id}`; once that has finished, the stats
+/// are served from a cache for an hour.
+#[utoipa::path(
+    get,
+    path = "/api/v1/registries/{name}/stats",
+    tag = "registries",
+    params(("name" = String, Path, description = "Registry name"), RegistryStatsQuery),
+    responses(
+        (status = 200, description = "Stats counted within the last hour", body = RegistryStats),
+        (status = 202, description = "Counting started, or already running, as a background job", body = JobAccepted),
+        (status = 404, description = "No such registry", body = ApiErrorResponse),
+    ),
+)]
+pub async fn registry_stats(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(query): Query<RegistryStatsQuery>,
+) -> Result<Response, ApiError> {
+    let Some(client) = state.registry_manager.read().await.get_registry(&name).cloned() else {
+        return Err(ApiError::not_found("REGISTRY", format!("Registry '{}' not found", name)));
+    };
+
+    if !query.refresh
+        && let Some(stats) = state.registry_stats.get(&name)
+    {
+        return Ok(Json(stats).into_response());
+    }
+    // Counting a large registry takes a while, so one count at a time is enough
+    let running = state
+        .jobs
+        .list()
+        .into_iter()
+        .find(|job| job.kind == JobKind::RegistryStats && job.status == JobStatus::Running && job.target == name);
+    if let Some(job) = running {
+        return Ok((StatusCode::ACCEPTED, Json(JobAccepted { job_id: job.id })).into_response());
+    }
+
+    let cache = state.registry_stats.clone();
+    let job = state.jobs.start(JobKind::RegistryStats, name.clone(), move |progress| async move {
+        progress.update(0, "Listing repositories");
+        let stats = client
+            .stats(|done, total| {
+                let percent = (done * 100).checked_div(total).unwrap_or(100);
+                progress.update(percent as u8, format!("Counted {} of {} repositories", done, total));
+            })
+            .await
+            .inspect_err(|e| error!("Failed to count the contents of registry {}: {}", name, e))?;
+        info!(
+            "Registry {} has {} repositories, {} tags and {} layers",
+            name, stats.repositories, stats.tags, stats.unique_layers
+        );
+        let message = format!(
+            "Counted {} repositories, {} tags and {} layers of {} bytes",
+            stats.repositories, stats.tags, stats.unique_layers, stats.total_bytes
+        );
+        cache.insert(&name, stats);
+        Ok(message)
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(JobAccepted { job_id: job.id })).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(computed_at: chrono::DateTime<chrono::Utc>) -> RegistryStats {
+        RegistryStats {
+            repositories: 1,
+            computed_at,
+            ..RegistryStats::default()
+        }
+    }
+
+    #[test]
+    fn cached_stats_expire_and_are_forgotten() {
+        let cache = RegistryStatsCache::new();
+        cache.insert("mirror", stats(chrono::Utc::now()));
+        cache.insert("hub", stats(chrono::Utc::now() - chrono::Duration::seconds(STATS_TTL_SECS + 1)));
+
+        assert_eq!(cache.get("mirror").map(|stats| stats.repositories), Some(1));
+        assert!(cache.get("hub").is_none());
+        cache.forget("mirror");
+        assert!(cache.get("mirror").is_none());
+    }
+}
@@ -0,0 +1,326 @@
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Nonce,
+};
+use argon2::Argon2;
+use gpanel_core::RegistryConfig;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, OnceCell};
+use tracing::warn;
+
+use crate::store::Store;
+
+const REGISTRY_STORE: &str = "registries";
+
+/// Generated secret, used when `registry_secret` is not configured
+const SECRET_STORE: &str = "registry-secret";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Changes made to the registry list through the API, on top of the config file
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SavedRegistries {
+    /// Salt the encryption key is derived from the secret with, in hex
+    #[serde(default)]
+    salt: String,
+    /// Registries added or edited through the API
+    #[serde(default)]
+    registries: Vec<SavedRegistry>,
+    /// Registries from the config file removed through the API
+    #[serde(default)]
+    removed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SavedRegistry {
+    /// Settings without the password
+    config: RegistryConfig,
+    /// Nonce and AES-256-GCM ciphertext of the password, in hex
+    password: Option<String>,
+}
+
+/// Registries added, edited and removed through the API, kept in `registries.json` under
+/// the data directory so they survive a restart. Edits to the config file win over them:
+/// a registry the file adds, changes or removes is no longer tracked here.
+pub struct RegistryStore {
+    store: Store,
+    /// The configured secret; a generated one is used when unset
+    secret: Option<String>,
+    /// Derived on first use, as most agents never save a password
+    cipher: OnceCell<Aes256Gcm>,
+    saved: Mutex<SavedRegistries>,
+}
+
+impl RegistryStore {
+    /// Load the saved changes. Passwords are encrypted with a key derived from `secret`, or
+    /// from a generated secret kept in the data directory when none is configured.
+    pub async fn load(store: Store, secret: Option<&str>) -> Self {
+        let mut saved: SavedRegistries = store.load(REGISTRY_STORE).await;
+        if decode_hex(&saved.salt).is_none_or(|salt| salt.len() != SALT_LEN) {
+            let mut salt = [0u8; SALT_LEN];
+            OsRng.fill_bytes(&mut salt);
+            saved.salt = encode_hex(&salt);
+        }
+
+        Self {
+            store,
+            secret: secret.map(str::to_string),
+            cipher: OnceCell::new(),
+            saved: Mutex::new(saved),
+        }
+    }
+
+    /// The cipher for passwords, deriving its key off the async workers the first time
+    async fn cipher(&self, salt: &str) -> &Aes256Gcm {
+        self.cipher
+            .get_or_init(|| async {
+                let secret = match &self.secret {
+                    Some(secret) => secret.clone(),
+                    None => generated_secret(&self.store).await,
+                };
+                let salt = decode_hex(salt).unwrap_or_default();
+                let key = tokio::task::spawn_blocking(move || {
+                    let mut key = [0u8; 32];
+                    Argon2::default()
+                        .hash_password_into(secret.as_bytes(), &salt, &mut key)
+                        .map(|_| key)
+                })
+                .await
+                .expect("key derivation does not panic")
+                .expect("secret and salt lengths are valid");
+                Aes256Gcm::new(&key.into())
+            })
+            .await
+    }
+
+    /// `configured` with the saved changes applied, as the agent starts with
+    pub async fn registries(&self, configured: &[RegistryConfig]) -> Vec<RegistryConfig> {
+        let saved = self.saved.lock().await;
+        let mut registries: Vec<RegistryConfig> = configured
+            .iter()
+            .filter(|registry| {
+                !saved.removed.contains(&registry.name)
+                    && !saved.registries.iter().any(|saved| saved.config.name == registry.name)
+            })
+            .cloned()
+            .collect();
+
+        for registry in &saved.registries {
+            let mut config = registry.config.clone();
+            if let Some(password) = &registry.password {
+                config.password = decrypt(self.cipher(&saved.salt).await, password);
+                if config.password.is_none() {
+                    warn!(
+                        "Cannot decrypt the saved password of registry {}; was registry_secret changed?",
+                        config.name
+                    );
+                }
+            }
+            registries.push(config);
+        }
+        registries
+    }
+
+    /// Record a registry added or edited through the API
+    pub async fn save(&self, registry: &RegistryConfig) -> anyhow::Result<()> {
+        let mut saved = self.saved.lock().await;
+        let password = match &registry.password {
+            Some(password) => Some(encrypt(self.cipher(&saved.salt).await, password)?),
+            None => None,
+        };
+        saved.removed.retain(|name| *name != registry.name);
+        saved.registries.retain(|saved| saved.config.name != registry.name);
+        saved.registries.push(SavedRegistry {
+            config: RegistryConfig {
+                password: None,
+                ..registry.clone()
+            },
+            password,
+        });
+        self.store.save(REGISTRY_STORE, &*saved).await
+    }
+
+    /// Record a registry removed through the API
+    pub async fn remove(&self, name: &str) -> anyhow::Result<()> {
+        let mut saved = self.saved.lock().await;
+        saved.registries.retain(|saved| saved.config.name != name);
+        if !saved.removed.iter().any(|removed| removed == name) {
+            saved.removed.push(name.to_string());
+        }
+        self.store.save(REGISTRY_STORE, &*saved).await
+    }
+
+    /// Stop tracking registries the config file has just added, changed or removed
+    pub async fn forget(&self, names: &[String]) -> anyhow::Result<()> {
+        let mut saved = self.saved.lock().await;
+        let tracked = saved.registries.len() + saved.removed.len();
+        saved.registries.retain(|saved| !names.contains(&saved.config.name));
+        saved.removed.retain(|name| !names.contains(name));
+        if saved.registries.len() + saved.removed.len() == tracked {
+            return Ok(());
+        }
+        self.store.save(REGISTRY_STORE, &*saved).await
+    }
+
+    /// Drop every saved change, once a restore has written the whole list to the config file
+    pub async fn clear(&self) -> anyhow::Result<()> {
+        let mut saved = self.saved.lock().await;
+        saved.registries.clear();
+        saved.removed.clear();
+        self.store.save(REGISTRY_STORE, &*saved).await
+    }
+}
+
+/// Nonce and ciphertext of `password`, in hex
+fn encrypt(cipher: &Aes256Gcm, password: &str) -> anyhow::Result<String> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, password.as_bytes())
+        .map_err(|_| anyhow::anyhow!("Registry password encryption failed"))?;
+    Ok(encode_hex(&[nonce.as_slice(), &ciphertext].concat()))
+}
+
+fn decrypt(cipher: &Aes256Gcm, encrypted: &str) -> Option<String> {
+    let data = decode_hex(encrypted).filter(|data| data.len() > NONCE_LEN)?;
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let plaintext = cipher.decrypt(Nonce::from_slice(nonce), ciphertext).ok()?;
+    String::from_utf8(plaintext).ok()
+}
+
+/// The secret generated on first use, creating it if there is none yet
+async fn generated_secret(store: &Store) -> String {
+    if let Some(secret) = store.load::<Option<String>>(SECRET_STORE).await {
+        return secret;
+    }
+    let mut secret = [0u8; 32];
+    OsRng.fill_bytes(&mut secret);
+    let secret = encode_hex(&secret);
+    if let Err(e) = store.save(SECRET_STORE, &secret).await {
+        warn!("Failed to save the generated registry secret; saved passwords will not survive a restart: {}", e);
+    }
+    secret
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{agent, serve_stub};
+    use axum::{http::Method, http::StatusCode, routing::get, Router};
+
+    async fn open_registry() -> String {
+        serve_stub(Router::new().route("/v2/", get(|| async { "{}" }))).await
+    }
+
+    async fn registry_names(agent: &crate::testing::TestAgent) -> Vec<String> {
+        let (_, body) = agent.request(Method::GET, "/api/v1/registries", None).await;
+        body["registries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|registry| registry["name"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn registries_from_the_api_survive_a_restart() {
+        let agent = agent().await;
+        let url = open_registry().await;
+        let registry = serde_json::json!({
+            "name": "mirror", "url": url, "username": "ci", "password": "hunter2", "insecure": true,
+        });
+        let (status, _) = agent.request(Method::POST, "/api/v1/registries", Some(registry)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let saved = std::fs::read_to_string(agent.data_dir().join("registries.json")).unwrap();
+        assert!(saved.contains("mirror"));
+        assert!(!saved.contains("hunter2"), "passwords are saved encrypted");
+
+        let agent = agent.restart().await;
+        assert_eq!(registry_names(&agent).await, ["mirror"]);
+        let manager = agent.state.registry_manager.read().await;
+        assert_eq!(manager.get_registry("mirror").unwrap().config().password.as_deref(), Some("hunter2"));
+    }
+
+    #[tokio::test]
+    async fn edits_and_removals_of_configured_registries_survive_a_restart() {
+        let url = open_registry().await;
+        let configured = |name: &str| RegistryConfig {
+            name: name.to_string(),
+            url: url.clone(),
+            username: None,
+            password: None,
+            insecure: true,
+            page_size: None,
+            max_list_entries: None,
+            search_url: None,
+            ca_cert_path: None,
+        };
+        let (hub, mirror) = (configured("hub"), configured("mirror"));
+        let agent = crate::testing::agent_with(move |config| config.registries = vec![hub, mirror], false).await;
+
+        let (status, _) = agent.request(Method::DELETE, "/api/v1/registries/hub", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let edit = serde_json::json!({ "name": "cache", "url": url, "insecure": true });
+        let (status, _) = agent.request(Method::PUT, "/api/v1/registries/mirror", Some(edit)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        // Reloading an unchanged config file leaves the API's changes alone
+        agent.state.config.save(&agent.data_dir().join("gpanel.toml")).unwrap();
+        agent.request(Method::POST, "/api/v1/system/reload-config", None).await;
+        assert_eq!(registry_names(&agent).await, ["cache"]);
+
+        let agent = agent.restart().await;
+        assert_eq!(registry_names(&agent).await, ["cache"]);
+        assert!(agent.state.registry_manager.read().await.get_registry("cache").unwrap().config().insecure);
+    }
+
+    #[tokio::test]
+    async fn config_file_edits_win_over_saved_changes() {
+        let agent = agent().await;
+        let url = open_registry().await;
+        let store = &agent.state.registry_store;
+        let mirror = RegistryConfig {
+            name: "mirror".to_string(),
+            url,
+            username: None,
+            password: None,
+            insecure: true,
+            page_size: None,
+            max_list_entries: None,
+            search_url: None,
+            ca_cert_path: None,
+        };
+        store.save(&mirror).await.unwrap();
+        store.remove("hub").await.unwrap();
+
+        store.forget(&["mirror".to_string(), "hub".to_string()]).await.unwrap();
+        let configured = [RegistryConfig {
+            name: "hub".to_string(),
+            ..mirror
+        }];
+        let names: Vec<String> = store.registries(&configured).await.into_iter().map(|r| r.name).collect();
+        assert_eq!(names, ["hub"]);
+    }
+
+    #[test]
+    fn hex_round_trips() {
+        assert_eq!(decode_hex(&encode_hex(&[0, 15, 255])), Some(vec![0, 15, 255]));
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("zz"), None);
+    }
+}
@@ -0,0 +1,650 @@
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::Json,
+    Extension,
+};
+use gpanel_core::{
+    ApiErrorResponse, Container, ContainerEvent, ContainerEventAction, CreateContainerRequest, ImageRef, JobKind,
+    RegistryWebhookResult, SystemEvent, AUTO_UPDATE_LABEL,
+};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::collections::BTreeSet;
+use tracing::{error, info, warn};
+use utoipa::IntoParams;
+
+use crate::audit::AuditActor;
+use crate::environments::Env;
+use crate::error::ApiError;
+use crate::AppState;
+
+/// What a registry reported happening to an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationAction {
+    Push,
+    Delete,
+}
+
+/// One image event from a registry's notification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryNotification {
+    pub action: NotificationAction,
+    pub repository: String,
+    pub tag: Option<String>,
+    pub digest: Option<String>,
+}
+
+/// Both formats are told apart by their fields: the Docker registry sends an envelope of
+/// `events`, Harbor a single event with a `type` and `event_data`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Payload {
+    Distribution { events: Vec<DistributionEvent> },
+    Harbor {
+        #[serde(rename = "type")]
+        kind: String,
+        event_data: HarborEventData,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct DistributionEvent {
+    action: String,
+    target: DistributionTarget,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DistributionTarget {
+    #[serde(default)]
+    media_type: String,
+    repository: String,
+    #[serde(default)]
+    digest: Option<String>,
+    #[serde(default)]
+    tag: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HarborEventData {
+    #[serde(default)]
+    resources: Vec<HarborResource>,
+    repository: HarborRepository,
+}
+
+#[derive(Debug, Deserialize)]
+struct HarborResource {
+    #[serde(default)]
+    digest: Option<String>,
+    #[serde(default)]
+    tag: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HarborRepository {
+    /// With the project, as in `library/nginx`
+    repo_full_name: String,
+}
+
+/// The image events in a Docker registry or Harbor notification. The Docker registry
+/// reports every blob and manifest; only manifest events are kept, and of pushes only
+/// those by tag, as the platforms of a multi-arch image are pushed by digest before the
+/// tagged index. Pulls and other events are left out.
+pub fn parse_notifications(body: &[u8]) -> Result<Vec<RegistryNotification>, String> {
+    let payload: Payload = serde_json::from_slice(body)
+        .map_err(|_| "Not a Docker registry or Harbor notification".to_string())?;
+    let notifications = match payload {
+        Payload::Distribution { events } => events
+            .into_iter()
+            .filter_map(|event| {
+                let action = match event.action.as_str() {
+                    "push" => NotificationAction::Push,
+                    "delete" => NotificationAction::Delete,
+                    _ => return None,
+                };
+                let target = event.target;
+                // Deletes by digest may not say what was deleted
+                let is_manifest = target.media_type.contains("manifest")
+                    || target.media_type.contains("image.index")
+                    || (target.media_type.is_empty() && action == NotificationAction::Delete);
+                let tag = target.tag.filter(|tag| !tag.is_empty());
+                (is_manifest && (action == NotificationAction::Delete || tag.is_some())).then_some(RegistryNotification {
+                    action,
+                    repository: target.repository,
+                    tag,
+                    digest: target.digest,
+                })
+            })
+            .collect(),
+        Payload::Harbor { kind, event_data } => {
+            let action = match kind.as_str() {
+                // Harbor 1.x named them after images
+                "PUSH_ARTIFACT" | "pushImage" => NotificationAction::Push,
+                "DELETE_ARTIFACT" | "deleteImage" => NotificationAction::Delete,
+                _ => return Ok(Vec::new()),
+            };
+            let repository = event_data.repository.repo_full_name;
+            event_data
+                .resources
+                .into_iter()
+                .map(|resource| RegistryNotification {
+                    action,
+                    repository: repository.clone(),
+                    tag: resource.tag.filter(|tag| !tag.is_empty()),
+                    digest: resource.digest.filter(|digest| !digest.is_empty()),
+                })
+                .collect()
+        }
+    };
+    Ok(notifications)
+}
+
+/// Whether `given`, the notification's `Authorization` header, carries `secret`. Both are
+/// compared through an HMAC so the time taken does not tell how much matched.
+fn has_secret(given: &str, secret: &str) -> bool {
+    let given = given.strip_prefix("Bearer ").unwrap_or(given).trim();
+    let keyed = || Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    let mut expected = keyed();
+    expected.update(secret.as_bytes());
+    let mut received = keyed();
+    received.update(given.as_bytes());
+    received.verify(&expected.finalize().into_bytes()).is_ok()
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RegistryWebhookQuery {
+    /// Name of the configured registry sending the notification
+    pub registry: String,
+}
+
+/// Receive a push or delete notification from a Docker registry or Harbor. Cached details
+/// of the repository are dropped and a `system` event is sent to event stream clients;
+/// with `registry_webhook.auto_update`, containers running a pushed tag and labelled
+/// `gpanel.autoupdate=true` are pulled and recreated. Authenticated by the
+/// `registry_webhook.secret` in the `Authorization` header rather than an API token.
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks/registry",
+    tag = "webhooks",
+    security(()),
+    params(RegistryWebhookQuery),
+    request_body(content = Object, description = "Docker registry notification envelope or Harbor webhook event"),
+    responses(
+        (status = 200, description = "Notification handled", body = RegistryWebhookResult),
+        (status = 400, description = "Not a notification the agent reads", body = ApiErrorResponse),
+        (status = 401, description = "Missing or wrong secret", body = ApiErrorResponse),
+        (status = 404, description = "No such registry", body = ApiErrorResponse),
+        (status = 503, description = "No secret is configured, so notifications are refused", body = ApiErrorResponse),
+    ),
+)]
+pub async fn receive_registry_webhook(
+    State(state): State<AppState>,
+    Query(query): Query<RegistryWebhookQuery>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Result<(Extension<AuditActor>, Json<RegistryWebhookResult>), ApiError> {
+    let config = &state.config.registry_webhook;
+    let Some(secret) = config.secret.as_deref() else {
+        return Err(ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "REGISTRY_WEBHOOK_DISABLED",
+            "Set registry_webhook.secret to receive registry notifications",
+        ));
+    };
+    let given = headers.get(header::AUTHORIZATION).and_then(|value| value.to_str().ok()).unwrap_or("");
+    if !has_secret(given, secret) {
+        return Err(ApiError::from_status(StatusCode::UNAUTHORIZED, "Missing or wrong registry webhook secret"));
+    }
+
+    let name = query.registry;
+    let client = state
+        .registry_manager
+        .read()
+        .await
+        .get_registry(&name)
+        .cloned()
+        .ok_or_else(|| ApiError::not_found("REGISTRY", format!("Registry '{}' not found", name)))?;
+    let notifications = parse_notifications(&body).map_err(|message| ApiError::from_status(StatusCode::BAD_REQUEST, message))?;
+
+    let repositories: BTreeSet<&str> = notifications.iter().map(|notification| notification.repository.as_str()).collect();
+    {
+        let manager = state.registry_manager.read().await;
+        for repository in &repositories {
+            manager.invalidate_image_cache(&name, repository);
+        }
+    }
+    if !repositories.is_empty() {
+        state.registry_stats.forget(&name);
+    }
+
+    let actor = format!("registry:{}", name);
+    let mut update_jobs = Vec::new();
+    for notification in &notifications {
+        let RegistryNotification { action, repository, tag, digest } = notification.clone();
+        let reference = match (&tag, &digest) {
+            (Some(tag), _) => format!("{}/{}:{}", name, repository, tag),
+            (None, Some(digest)) => format!("{}/{}@{}", name, repository, digest),
+            (None, None) => format!("{}/{}", name, repository),
+        };
+        info!("Registry {} reported {:?} of {}", name, action, reference);
+        let (event, audit_action) = match action {
+            NotificationAction::Push => (
+                SystemEvent::ImagePushed { registry: name.clone(), repository: repository.clone(), tag: tag.clone(), digest },
+                "images.push",
+            ),
+            NotificationAction::Delete => (
+                SystemEvent::ImageDeleted { registry: name.clone(), repository: repository.clone(), tag: tag.clone(), digest },
+                "images.delete",
+            ),
+        };
+        let _ = state.system_events.send(event);
+        state.audit.record_action(&actor, audit_action, "images", Some(&reference), Ok(())).await;
+
+        if config.auto_update
+            && action == NotificationAction::Push
+            && let Some(tag) = tag
+        {
+            match client.image_ref(&repository, &tag) {
+                Ok(image) => update_jobs.extend(start_auto_update(&state, image).await),
+                Err(e) => warn!("Cannot auto-update from {}: {}", reference, e),
+            }
+        }
+    }
+
+    Ok((
+        Extension(AuditActor(actor)),
+        Json(RegistryWebhookResult {
+            notifications: notifications.len(),
+            update_jobs,
+        }),
+    ))
+}
+
+/// Whether `container` is labelled for auto-update and runs `image` by its tag
+fn runs_for_update(container: &Container, image: &ImageRef) -> bool {
+    container.labels.get(AUTO_UPDATE_LABEL).is_some_and(|value| value == "true")
+        && ImageRef::parse(&container.image).is_ok_and(|running| {
+            running.digest().is_none()
+                && running.registry() == image.registry()
+                && running.repository() == image.repository()
+                && running.tag() == image.tag()
+        })
+}
+
+/// Start a job pulling `image` and recreating the labelled containers running it, in
+/// every environment; `None` when no container is labelled
+async fn start_auto_update(state: &AppState, image: ImageRef) -> Option<String> {
+    let mut updates: Vec<(Env, Vec<Container>)> = Vec::new();
+    for env in state.environments.all() {
+        match env.runtime.list_containers(None).await {
+            Ok(containers) => {
+                let containers: Vec<Container> = containers.into_iter().filter(|container| runs_for_update(container, &image)).collect();
+                if !containers.is_empty() {
+                    updates.push((env.clone(), containers));
+                }
+            }
+            Err(e) => warn!("Cannot auto-update {} in {}, failed to list containers: {}", image, env.name, e),
+        }
+    }
+    if updates.is_empty() {
+        return None;
+    }
+
+    let job = state.jobs.start(JobKind::ContainerUpdate, image.to_string(), move |progress| async move {
+        let mut recreated = Vec::new();
+        let mut failed = Vec::new();
+        for (env, containers) in updates {
+            // Pulled by the name the containers use, so the runtime moves that tag
+            progress.update(10, format!("Pulling {} in {}", containers[0].image, env.name));
+            env.runtime.pull_image(&containers[0].image).await?;
+            for container in containers {
+                progress.update(50, format!("Recreating {}", container.name));
+                match recreate(&env, &container).await {
+                    Ok(()) => recreated.push(container.name),
+                    Err(e) => {
+                        error!("Failed to recreate {} in {} after a push of {}: {}", container.name, env.name, image, e);
+                        failed.push(format!("{} ({})", container.name, e));
+                    }
+                }
+            }
+        }
+        if !failed.is_empty() {
+            anyhow::bail!("Failed to recreate {}", failed.join(", "));
+        }
+        Ok(format!("Recreated {} with the new {}", recreated.join(", "), image))
+    });
+    info!("Started auto-update job {}", job.id);
+    Some(job.id)
+}
+
+/// Replace `container` with one created from the same settings, so it runs the image the
+/// runtime now has under its tag, and start it if it was running
+async fn recreate(env: &Env, container: &Container) -> anyhow::Result<()> {
+    let was_running = matches!(container.status, gpanel_core::ContainerStatus::Running);
+    let request = CreateContainerRequest {
+        name: Some(container.name.clone()),
+        image: container.image.clone(),
+        ports: container.ports.clone(),
+        volumes: container.volumes.clone(),
+        networks: container.networks.clone(),
+        env: container.env.clone(),
+        labels: container.labels.clone(),
+        gaming_config: container.gaming_config.clone(),
+        gpu_allocation: container.gpu_allocation.clone(),
+        restart_policy: container.restart_policy.clone(),
+        health: None,
+    };
+
+    env.runtime.remove_container(&container.id, true, false).await?;
+    env.publish(ContainerEvent::for_container(container, ContainerEventAction::Removed));
+    let created = env.runtime.create_container(request).await?;
+    env.publish(ContainerEvent::for_container(&created, ContainerEventAction::Created));
+    if was_running {
+        env.runtime.start_container(&created.id).await?;
+        env.publish_status(&created.id).await;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::agent_with;
+    use axum::{body::Body, http::Request};
+
+    /// A `docker push` of a multi-arch image to the Docker registry: layer blobs, the
+    /// platform manifests by digest, then the tagged index, with a pull and a delete
+    const DISTRIBUTION_PUSH: &str = r#"{
+       "events": [
+          {
+             "id": "3d6a1a3e-5a40-4e4b-9b5a-0f6c0e2e8d11",
+             "timestamp": "2024-05-02T09:12:41.882417011Z",
+             "action": "push",
+             "target": {
+                "mediaType": "application/octet-stream",
+                "size": 3408729,
+                "digest": "sha256:4abcf20661432fb2d719aaf90656f55c287f8ca915dc1c92ec14ff61e67fbaf8",
+                "length": 3408729,
+                "repository": "team/app",
+                "url": "http://registry.example.com:5000/v2/team/app/blobs/sha256:4abcf20661432fb2d719aaf90656f55c287f8ca915dc1c92ec14ff61e67fbaf8"
+             },
+             "request": { "id": "8a1f", "addr": "10.0.0.7:51234", "host": "registry.example.com:5000", "method": "PUT", "useragent": "docker/26.1.1 go/go1.21.9" },
+             "actor": {},
+             "source": { "addr": "registry:5000", "instanceID": "c3c6e1b7-6f8e-4c02-a86e-0d1ff2a0a5b3" }
+          },
+          {
+             "id": "0b6f8a3e-6d2a-4f8a-8b1e-4c4c2d7e9f20",
+             "timestamp": "2024-05-02T09:12:42.105227140Z",
+             "action": "push",
+             "target": {
+                "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                "size": 1025,
+                "digest": "sha256:9c2f1f8e6a2b0c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5",
+                "length": 1025,
+                "repository": "team/app",
+                "url": "http://registry.example.com:5000/v2/team/app/manifests/sha256:9c2f1f8e6a2b0c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5"
+             },
+             "request": { "id": "8a20", "addr": "10.0.0.7:51234", "host": "registry.example.com:5000", "method": "PUT", "useragent": "docker/26.1.1 go/go1.21.9" },
+             "actor": {},
+             "source": { "addr": "registry:5000", "instanceID": "c3c6e1b7-6f8e-4c02-a86e-0d1ff2a0a5b3" }
+          },
+          {
+             "id": "5e7c9b1a-2f3d-4e5f-9a8b-7c6d5e4f3a21",
+             "timestamp": "2024-05-02T09:12:42.311904556Z",
+             "action": "push",
+             "target": {
+                "mediaType": "application/vnd.oci.image.index.v1+json",
+                "size": 856,
+                "digest": "sha256:e3b6f9a2c1d0e8f7a6b5c4d3e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3",
+                "length": 856,
+                "repository": "team/app",
+                "url": "http://registry.example.com:5000/v2/team/app/manifests/sha256:e3b6f9a2c1d0e8f7a6b5c4d3e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3",
+                "tag": "v1"
+             },
+             "request": { "id": "8a21", "addr": "10.0.0.7:51234", "host": "registry.example.com:5000", "method": "PUT", "useragent": "docker/26.1.1 go/go1.21.9" },
+             "actor": {},
+             "source": { "addr": "registry:5000", "instanceID": "c3c6e1b7-6f8e-4c02-a86e-0d1ff2a0a5b3" }
+          },
+          {
+             "id": "320678d8-ca14-430f-8bb6-4ca139cd83f7",
+             "timestamp": "2024-05-02T09:13:05.402973972Z",
+             "action": "pull",
+             "target": {
+                "mediaType": "application/vnd.docker.distribution.manifest.v2+json",
+                "size": 708,
+                "digest": "sha256:fea8895f450959fa676bcc1df0611ea93823a735a01205fd8622846041d0c7cf",
+                "length": 708,
+                "repository": "hello-world",
+                "url": "http://registry.example.com:5000/v2/hello-world/manifests/latest",
+                "tag": "latest"
+             },
+             "request": { "id": "6df2", "addr": "10.0.0.9:42961", "host": "registry.example.com:5000", "method": "GET", "useragent": "curl/8.5.0" },
+             "actor": {},
+             "source": { "addr": "registry:5000", "instanceID": "c3c6e1b7-6f8e-4c02-a86e-0d1ff2a0a5b3" }
+          },
+          {
+             "id": "a9d3c2b1-0e1f-4a5b-8c7d-6e5f4a3b2c10",
+             "timestamp": "2024-05-02T09:20:11.018273645Z",
+             "action": "delete",
+             "target": {
+                "digest": "sha256:fea8895f450959fa676bcc1df0611ea93823a735a01205fd8622846041d0c7cf",
+                "repository": "hello-world"
+             },
+             "request": { "id": "6df3", "addr": "10.0.0.9:42990", "host": "registry.example.com:5000", "method": "DELETE", "useragent": "curl/8.5.0" },
+             "actor": {},
+             "source": { "addr": "registry:5000", "instanceID": "c3c6e1b7-6f8e-4c02-a86e-0d1ff2a0a5b3" }
+          }
+       ]
+    }"#;
+
+    /// Harbor 2.x, pushing `library/nginx:1.25` with an extra tag
+    const HARBOR_PUSH: &str = r#"{
+      "type": "PUSH_ARTIFACT",
+      "occur_at": 1714641161,
+      "operator": "admin",
+      "event_data": {
+        "resources": [
+          {
+            "digest": "sha256:32e76d4f34f80e479964a0fbd4c5b4f6967b5322c8d004e9cf0cb81c93510766",
+            "tag": "1.25",
+            "resource_url": "harbor.example.com/library/nginx:1.25"
+          },
+          {
+            "digest": "sha256:32e76d4f34f80e479964a0fbd4c5b4f6967b5322c8d004e9cf0cb81c93510766",
+            "tag": "stable",
+            "resource_url": "harbor.example.com/library/nginx:stable"
+          }
+        ],
+        "repository": {
+          "date_created": 1714640012,
+          "name": "nginx",
+          "namespace": "library",
+          "repo_full_name": "library/nginx",
+          "repo_type": "private"
+        }
+      }
+    }"#;
+
+    const HARBOR_DELETE: &str = r#"{
+      "type": "DELETE_ARTIFACT",
+      "occur_at": 1714642277,
+      "operator": "admin",
+      "event_data": {
+        "resources": [
+          {
+            "digest": "sha256:32e76d4f34f80e479964a0fbd4c5b4f6967b5322c8d004e9cf0cb81c93510766",
+            "tag": "",
+            "resource_url": "harbor.example.com/library/nginx@sha256:32e76d4f34f80e479964a0fbd4c5b4f6967b5322c8d004e9cf0cb81c93510766"
+          }
+        ],
+        "repository": {
+          "date_created": 1714640012,
+          "name": "nginx",
+          "namespace": "library",
+          "repo_full_name": "library/nginx",
+          "repo_type": "private"
+        }
+      }
+    }"#;
+
+    fn notification(action: NotificationAction, repository: &str, tag: Option<&str>, digest: &str) -> RegistryNotification {
+        RegistryNotification {
+            action,
+            repository: repository.to_string(),
+            tag: tag.map(str::to_string),
+            digest: Some(digest.to_string()),
+        }
+    }
+
+    #[test]
+    fn distribution_envelopes_keep_tagged_pushes_and_deletes() {
+        assert_eq!(
+            parse_notifications(DISTRIBUTION_PUSH.as_bytes()).unwrap(),
+            [
+                notification(
+                    NotificationAction::Push,
+                    "team/app",
+                    Some("v1"),
+                    "sha256:e3b6f9a2c1d0e8f7a6b5c4d3e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3"
+                ),
+                notification(
+                    NotificationAction::Delete,
+                    "hello-world",
+                    None,
+                    "sha256:fea8895f450959fa676bcc1df0611ea93823a735a01205fd8622846041d0c7cf"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn harbor_events_give_one_notification_per_resource() {
+        let digest = "sha256:32e76d4f34f80e479964a0fbd4c5b4f6967b5322c8d004e9cf0cb81c93510766";
+        assert_eq!(
+            parse_notifications(HARBOR_PUSH.as_bytes()).unwrap(),
+            [
+                notification(NotificationAction::Push, "library/nginx", Some("1.25"), digest),
+                notification(NotificationAction::Push, "library/nginx", Some("stable"), digest),
+            ]
+        );
+        assert_eq!(
+            parse_notifications(HARBOR_DELETE.as_bytes()).unwrap(),
+            [notification(NotificationAction::Delete, "library/nginx", None, digest)]
+        );
+
+        let scan = HARBOR_PUSH.replace("PUSH_ARTIFACT", "SCANNING_COMPLETED");
+        assert_eq!(parse_notifications(scan.as_bytes()).unwrap(), []);
+        for invalid in ["", "{}", r#"{"type":"PUSH_ARTIFACT"}"#, r#"{"events":{}}"#, "[]"] {
+            assert!(parse_notifications(invalid.as_bytes()).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn secrets_are_accepted_bare_or_as_bearer_tokens() {
+        assert!(has_secret("s3cret", "s3cret"));
+        assert!(has_secret("Bearer s3cret", "s3cret"));
+        for wrong in ["", "s3cre", "s3cret2", "Bearer ", "Basic s3cret"] {
+            assert!(!has_secret(wrong, "s3cret"), "{}", wrong);
+        }
+    }
+
+    #[tokio::test]
+    async fn pushes_are_announced_audited_and_update_labelled_containers() {
+        let agent = agent_with(
+            |config| {
+                config.registry_webhook.secret = Some("s3cret".to_string());
+                config.registry_webhook.auto_update = true;
+            },
+            true,
+        )
+        .await;
+        agent.state.registry_manager.write().await.insert_registry(gpanel_core::RegistryConfig {
+            name: "builds".to_string(),
+            url: "http://registry.example.com:5000".to_string(),
+            username: None,
+            password: None,
+            insecure: true,
+            page_size: None,
+            max_list_entries: None,
+            search_url: None,
+            ca_cert_path: None,
+        });
+        let runtime = agent.state.environments.default_env().runtime;
+        let mut labelled = CreateContainerRequest {
+            name: Some("app".to_string()),
+            image: "registry.example.com:5000/team/app:v1".to_string(),
+            ports: Vec::new(),
+            volumes: Vec::new(),
+            networks: Vec::new(),
+            env: Default::default(),
+            labels: [(AUTO_UPDATE_LABEL.to_string(), "true".to_string())].into(),
+            gaming_config: None,
+            gpu_allocation: None,
+            restart_policy: Default::default(),
+            health: None,
+        };
+        let old = runtime.create_container(labelled.clone()).await.unwrap();
+        runtime.start_container(&old.id).await.unwrap();
+        labelled.name = Some("pinned".to_string());
+        labelled.labels.clear();
+        let pinned = runtime.create_container(labelled).await.unwrap();
+
+        let notify = |secret: &str, registry: &str| {
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/webhooks/registry?registry={}", registry))
+                .header("Authorization", format!("Bearer {}", secret))
+                .header("Content-Type", "application/vnd.docker.distribution.events.v1+json")
+                .body(Body::from(DISTRIBUTION_PUSH))
+                .unwrap()
+        };
+        let (status, _) = agent.send(notify("wrong", "builds")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = agent.send(notify("s3cret", "missing")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let mut events = agent.state.system_events.subscribe();
+        let (status, body) = agent.send(notify("s3cret", "builds")).await;
+        assert_eq!(status, StatusCode::OK);
+        let result: RegistryWebhookResult = serde_json::from_slice(&body).unwrap();
+        assert_eq!((result.notifications, result.update_jobs.len()), (2, 1));
+        match events.recv().await.unwrap() {
+            SystemEvent::ImagePushed { registry, repository, tag, .. } => {
+                assert_eq!((registry.as_str(), repository.as_str(), tag.as_deref()), ("builds", "team/app", Some("v1")))
+            }
+            other => panic!("expected a push, got {:?}", other),
+        }
+        assert!(matches!(events.recv().await.unwrap(), SystemEvent::ImageDeleted { .. }));
+
+        let job = tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            loop {
+                let job = agent.state.jobs.get(&result.update_jobs[0]).unwrap();
+                if job.status.is_finished() {
+                    return job;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .expect("update did not finish");
+        assert_eq!(job.status, gpanel_core::JobStatus::Succeeded, "{}", job.message);
+        let containers = runtime.list_containers(None).await.unwrap();
+        let app = containers.iter().find(|container| container.name == "app").unwrap();
+        assert_ne!(app.id, old.id, "the labelled container was recreated");
+        assert!(matches!(app.status, gpanel_core::ContainerStatus::Running));
+        assert!(containers.iter().any(|container| container.id == pinned.id), "unlabelled containers are left alone");
+
+        let audit = agent.state.audit.query(&Default::default()).await;
+        let actions: Vec<(&str, Option<&str>)> = audit
+            .events
+            .iter()
+            .filter(|event| event.user == "registry:builds")
+            .map(|event| (event.action.as_str(), event.resource_id.as_deref()))
+            .collect();
+        assert!(actions.contains(&("images.push", Some("builds/team/app:v1"))), "{:?}", actions);
+        assert!(actions.contains(&("webhooks.registry", None)), "{:?}", actions);
+    }
+}
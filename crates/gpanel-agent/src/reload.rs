@@ -0,0 +1,306 @@
+use anyhow::Result;
+use axum::{extract::State, http::StatusCode, response::Json};
+use gpanel_core::{ApiErrorResponse, ConfigReloadResult, GhostPanelConfig, RegistryConfig, SystemEvent};
+use notify::{RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+use tracing::{info, warn};
+
+use crate::auth::RequireAdmin;
+use crate::error::ApiError;
+use crate::AppState;
+
+/// Editors write a file in several steps; changes this close together are one reload
+const SETTLE_DELAY: Duration = Duration::from_millis(500);
+
+/// Re-reads the config file, applying registry changes to the running agent
+pub struct ConfigReloader {
+    path: PathBuf,
+    /// The file as last read, before command-line flags were applied. Held while
+    /// reloading, so reloads from the watcher and the API never interleave.
+    loaded: Mutex<GhostPanelConfig>,
+}
+
+impl ConfigReloader {
+    pub fn new(path: PathBuf, loaded: GhostPanelConfig) -> Self {
+        Self {
+            path,
+            loaded: Mutex::new(loaded),
+        }
+    }
+
+    /// Read the file again, add, replace and remove registries to match it, and list
+    /// any other setting that changed, since those need a restart
+    pub async fn reload(&self, state: &AppState) -> Result<ConfigReloadResult> {
+        let mut loaded = self.loaded.lock().await;
+        let config = GhostPanelConfig::load(Some(&self.path))?;
+
+        let previous: HashMap<&str, &RegistryConfig> =
+            loaded.registries.iter().map(|registry| (registry.name.as_str(), registry)).collect();
+        let current: HashMap<&str, &RegistryConfig> =
+            config.registries.iter().map(|registry| (registry.name.as_str(), registry)).collect();
+
+        let mut result = ConfigReloadResult {
+            path: self.path.display().to_string(),
+            ..Default::default()
+        };
+        {
+            let mut manager = state.registry_manager.write().await;
+            for (name, registry) in &previous {
+                match current.get(name) {
+                    None => {
+                        manager.remove_registry(name);
+                        result.registries_removed.push(name.to_string());
+                    }
+                    Some(updated) if updated != registry => {
+                        manager.remove_registry(name);
+                        result.registries_updated.push(name.to_string());
+                    }
+                    Some(_) => {}
+                }
+            }
+            for registry in &config.registries {
+                let name = registry.name.as_str();
+                let added = !previous.contains_key(name);
+                if !added && !result.registries_updated.iter().any(|updated| updated == name) {
+                    continue;
+                }
+                if let Err(e) = manager.add_registry(registry.clone()).await {
+                    // Kept, so its health check shows what is wrong
+                    manager.insert_registry(registry.clone());
+                    result.registry_errors.push(format!("{}: {}", name, e));
+                }
+                if added {
+                    result.registries_added.push(name.to_string());
+                }
+            }
+        }
+        result.registries_added.sort();
+        result.registries_removed.sort();
+        result.registries_updated.sort();
+
+        // The file's own edits win over earlier changes made through the API
+        let touched: Vec<String> = [&result.registries_added, &result.registries_removed, &result.registries_updated]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        if let Err(e) = state.registry_store.forget(&touched).await {
+            warn!("Failed to save registries after reloading {}: {:#}", result.path, e);
+        }
+
+        result.restart_required = changed_settings(&loaded, &config);
+        *loaded = config;
+
+        info!(
+            "Reloaded {}: registries added {:?}, removed {:?}, updated {:?}",
+            result.path, result.registries_added, result.registries_removed, result.registries_updated
+        );
+        for error in &result.registry_errors {
+            warn!("Failed to add registry from {}: {}", result.path, error);
+        }
+        if !result.restart_required.is_empty() {
+            warn!(
+                "Changed settings in {} need a restart to take effect: {}",
+                result.path,
+                result.restart_required.join(", ")
+            );
+        }
+
+        let changed = !(result.registries_added.is_empty()
+            && result.registries_removed.is_empty()
+            && result.registries_updated.is_empty());
+        if changed {
+            let _ = state.system_events.send(SystemEvent::RegistriesChanged {
+                added: result.registries_added.clone(),
+                removed: result.registries_removed.clone(),
+                updated: result.registries_updated.clone(),
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Write `config` to the file, as a restore does, and list the settings that need a
+    /// restart to take effect. Registries are left to the caller.
+    pub async fn replace(&self, config: GhostPanelConfig) -> Result<Vec<String>> {
+        let mut loaded = self.loaded.lock().await;
+        config.save(&self.path)?;
+        let restart_required = changed_settings(&loaded, &config);
+        *loaded = config;
+        Ok(restart_required)
+    }
+}
+
+/// Top-level settings other than `registries` that differ between two configs
+fn changed_settings(previous: &GhostPanelConfig, current: &GhostPanelConfig) -> Vec<String> {
+    let (Ok(serde_json::Value::Object(previous)), Ok(serde_json::Value::Object(current))) =
+        (serde_json::to_value(previous), serde_json::to_value(current))
+    else {
+        return Vec::new();
+    };
+    current
+        .iter()
+        .filter(|(key, value)| key.as_str() != "registries" && previous.get(key.as_str()) != Some(value))
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+/// Background task reloading the config whenever its file changes. The directory is
+/// watched rather than the file, so editors that save by replacing it are seen too.
+pub async fn watch_config(state: AppState) {
+    let reloader = state.config_reloader.clone();
+    let Some(directory) = reloader.path.parent().map(|parent| {
+        if parent.as_os_str().is_empty() { PathBuf::from(".") } else { parent.to_path_buf() }
+    }) else {
+        return;
+    };
+    let file_name = reloader.path.file_name().map(|name| name.to_os_string());
+
+    let (changes, mut received) = mpsc::unbounded_channel();
+    let mut watcher = match notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event
+            && (event.kind.is_create() || event.kind.is_modify())
+            && event.paths.iter().any(|path| path.file_name().map(|name| name.to_os_string()) == file_name)
+        {
+            let _ = changes.send(());
+        }
+    }) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            warn!("Failed to watch {} for changes: {}", reloader.path.display(), e);
+            return;
+        }
+    };
+    if let Err(e) = watcher.watch(&directory, RecursiveMode::NonRecursive) {
+        warn!("Failed to watch {} for changes: {}", directory.display(), e);
+        return;
+    }
+    info!("Watching {} for registry changes", reloader.path.display());
+
+    while received.recv().await.is_some() {
+        tokio::time::sleep(SETTLE_DELAY).await;
+        while received.try_recv().is_ok() {}
+
+        if let Err(e) = reloader.reload(&state).await {
+            warn!("Keeping the current configuration, {} could not be loaded: {:#}", reloader.path.display(), e);
+        }
+    }
+}
+
+/// Re-read the config file now, as the watcher does when it changes
+#[utoipa::path(
+    post,
+    path = "/api/v1/system/reload-config",
+    tag = "system",
+    responses(
+        (status = 200, description = "Registries updated from the config file", body = ConfigReloadResult),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+        (status = 422, description = "Config file is missing or invalid; the running config is unchanged", body = ApiErrorResponse),
+    ),
+)]
+pub async fn reload_config(_: RequireAdmin, State(state): State<AppState>) -> Result<Json<ConfigReloadResult>, ApiError> {
+    state
+        .config_reloader
+        .reload(&state)
+        .await
+        .map(Json)
+        .map_err(|e| ApiError::new(StatusCode::UNPROCESSABLE_ENTITY, "CONFIG_ERROR", format!("{:#}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{agent, TestAgent};
+    use axum::http::Method;
+
+    fn registry(name: &str) -> RegistryConfig {
+        RegistryConfig {
+            name: name.to_string(),
+            // Nothing listens here, so authenticating fails and the error is reported
+            url: "http://127.0.0.1:1".to_string(),
+            username: None,
+            password: None,
+            insecure: true,
+            page_size: None,
+            max_list_entries: None,
+            search_url: None,
+            ca_cert_path: None,
+        }
+    }
+
+    /// Write the agent's config file with `registries` and `agent_port`
+    fn write_config(agent: &TestAgent, registries: &[&str], agent_port: u16) {
+        let config = GhostPanelConfig {
+            registries: registries.iter().map(|name| registry(name)).collect(),
+            agent_port,
+            ..agent.state.config.clone()
+        };
+        config.save(&agent.state.config_reloader.path).unwrap();
+    }
+
+    async fn registry_names(agent: &TestAgent) -> Vec<String> {
+        let manager = agent.state.registry_manager.read().await;
+        let mut names: Vec<String> = manager.list_registries().into_iter().map(str::to_string).collect();
+        names.sort();
+        names
+    }
+
+    /// The next `RegistriesChanged` event, failing the test if none comes soon
+    async fn next_change(events: &mut tokio::sync::broadcast::Receiver<SystemEvent>) -> (Vec<String>, Vec<String>) {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                if let Ok(SystemEvent::RegistriesChanged { added, removed, .. }) = events.recv().await {
+                    return (added, removed);
+                }
+            }
+        })
+        .await
+        .expect("config file change was not reloaded")
+    }
+
+    #[tokio::test]
+    async fn editing_the_file_reloads_registries() {
+        let agent = agent().await;
+        let mut events = agent.state.system_events.subscribe();
+        write_config(&agent, &[], agent.state.config.agent_port);
+        tokio::spawn(watch_config(agent.state.clone()));
+        // Let the watcher start before the file changes
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        write_config(&agent, &["mirror"], agent.state.config.agent_port);
+        assert_eq!(next_change(&mut events).await, (vec!["mirror".to_string()], vec![]));
+        assert_eq!(registry_names(&agent).await, vec!["mirror"]);
+
+        write_config(&agent, &[], agent.state.config.agent_port);
+        assert_eq!(next_change(&mut events).await, (vec![], vec!["mirror".to_string()]));
+        assert!(registry_names(&agent).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn reload_reports_changes_and_settings_needing_a_restart() {
+        let agent = agent().await;
+        write_config(&agent, &["mirror"], agent.state.config.agent_port + 1);
+
+        let (status, body) = agent.request(Method::POST, "/api/v1/system/reload-config", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["registries_added"], serde_json::json!(["mirror"]));
+        assert_eq!(body["registry_errors"].as_array().unwrap().len(), 1);
+        assert_eq!(body["restart_required"], serde_json::json!(["agent_port"]));
+    }
+
+    #[tokio::test]
+    async fn invalid_file_keeps_the_running_config() {
+        let agent = agent().await;
+        write_config(&agent, &["mirror"], agent.state.config.agent_port);
+        agent.request(Method::POST, "/api/v1/system/reload-config", None).await;
+
+        std::fs::write(&agent.state.config_reloader.path, "registries = [").unwrap();
+        let (status, body) = agent.request(Method::POST, "/api/v1/system/reload-config", None).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["error"]["code"], "CONFIG_ERROR");
+        assert_eq!(registry_names(&agent).await, vec!["mirror"]);
+    }
+}
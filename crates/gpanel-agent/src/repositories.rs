@@ -0,0 +1,335 @@
+use axum::{
+    extract::{FromRequestParts, Path, Request, State},
+    handler::Handler,
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+use crate::error::ApiError;
+use crate::AppState;
+
+/// What a path under `/api/v1/registries/{name}/repositories/` names. Repository names
+/// are usually namespaced, like `library/nginx`, so the router captures the rest of the
+/// path whole and it is split here, from the end, since tags never contain `/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepositoryPath {
+    /// `{repo}/tags`
+    Tags { repository: String },
+    /// `{repo}/tags/{tag}`
+    Tag { repository: String, tag: String },
+    /// `{repo}/latest-stable`
+    LatestStable { repository: String },
+    /// `{repo}/tags/{tag}/export`
+    Export { repository: String, tag: String },
+}
+
+impl RepositoryPath {
+    pub fn parse(path: &str) -> Option<Self> {
+        let path = path.trim_start_matches('/');
+        if let Some(repository) = path.strip_suffix("/tags") {
+            return is_repository(repository).then(|| RepositoryPath::Tags {
+                repository: repository.to_string(),
+            });
+        }
+        if let Some((repository, tag)) = path.rsplit_once("/tags/")
+            && !tag.contains('/')
+        {
+            return (is_repository(repository) && !tag.is_empty()).then(|| RepositoryPath::Tag {
+                repository: repository.to_string(),
+                tag: tag.to_string(),
+            });
+        }
+        if let Some(image) = path.strip_suffix("/export")
+            && let Some(RepositoryPath::Tag { repository, tag }) = Self::parse(image)
+        {
+            return Some(RepositoryPath::Export { repository, tag });
+        }
+        let repository = path.strip_suffix("/latest-stable")?;
+        is_repository(repository).then(|| RepositoryPath::LatestStable {
+            repository: repository.to_string(),
+        })
+    }
+}
+
+/// Non-empty, without empty segments such as from `a//b`
+fn is_repository(name: &str) -> bool {
+    !name.is_empty() && name.split('/').all(|segment| !segment.is_empty())
+}
+
+/// The registry name and the rest of the path, as the wildcard route captures them
+async fn repository_path(parts: &mut Parts, state: &AppState) -> Result<(String, Option<RepositoryPath>), ApiError> {
+    let Path((registry, path)) = Path::<(String, String)>::from_request_parts(parts, state)
+        .await
+        .map_err(|e| ApiError::from_status(StatusCode::BAD_REQUEST, e.body_text()))?;
+    Ok((registry, RepositoryPath::parse(&path)))
+}
+
+fn no_route() -> ApiError {
+    ApiError::from_status(StatusCode::NOT_FOUND, "No such repository route")
+}
+
+/// A repository's tag list: `{repo}/tags`
+pub struct RepositoryTags {
+    pub registry: String,
+    pub repository: String,
+}
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for RepositoryTags {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        match repository_path(parts, state).await? {
+            (registry, Some(RepositoryPath::Tags { repository })) => Ok(Self { registry, repository }),
+            _ => Err(no_route()),
+        }
+    }
+}
+
+/// One tag of a repository: `{repo}/tags/{tag}`
+pub struct RepositoryTag {
+    pub registry: String,
+    pub repository: String,
+    pub tag: String,
+}
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for RepositoryTag {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        match repository_path(parts, state).await? {
+            (registry, Some(RepositoryPath::Tag { repository, tag })) => Ok(Self { registry, repository, tag }),
+            _ => Err(no_route()),
+        }
+    }
+}
+
+/// A repository's highest release: `{repo}/latest-stable`
+pub struct RepositoryLatestStable {
+    pub registry: String,
+    pub repository: String,
+}
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for RepositoryLatestStable {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        match repository_path(parts, state).await? {
+            (registry, Some(RepositoryPath::LatestStable { repository })) => Ok(Self { registry, repository }),
+            _ => Err(no_route()),
+        }
+    }
+}
+
+/// An image to download as an archive: `{repo}/tags/{tag}/export`
+pub struct RepositoryTagExport {
+    pub registry: String,
+    pub repository: String,
+    pub tag: String,
+}
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for RepositoryTagExport {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        match repository_path(parts, state).await? {
+            (registry, Some(RepositoryPath::Export { repository, tag })) => Ok(Self { registry, repository, tag }),
+            _ => Err(no_route()),
+        }
+    }
+}
+
+/// GET under `/repositories/`, handed to the handler for what the path names
+pub async fn get_repository_route(
+    State(state): State<AppState>,
+    Path((_, path)): Path<(String, String)>,
+    request: Request,
+) -> Response {
+    match RepositoryPath::parse(&path) {
+        Some(RepositoryPath::Tags { .. }) => crate::list_tags.call(request, state).await,
+        Some(RepositoryPath::Tag { .. }) => crate::get_image_info.call(request, state).await,
+        Some(RepositoryPath::LatestStable { .. }) => crate::latest_stable_tag.call(request, state).await,
+        Some(RepositoryPath::Export { .. }) => crate::export_registry_image.call(request, state).await,
+        None => no_route().into_response(),
+    }
+}
+
+/// POST under `/repositories/`
+pub async fn post_repository_route(
+    State(state): State<AppState>,
+    Path((_, path)): Path<(String, String)>,
+    request: Request,
+) -> Response {
+    match RepositoryPath::parse(&path) {
+        Some(RepositoryPath::Tags { .. }) => crate::tag_registry_image.call(request, state).await,
+        _ => StatusCode::METHOD_NOT_ALLOWED.into_response(),
+    }
+}
+
+/// DELETE under `/repositories/`
+pub async fn delete_repository_route(
+    State(state): State<AppState>,
+    Path((_, path)): Path<(String, String)>,
+    request: Request,
+) -> Response {
+    match RepositoryPath::parse(&path) {
+        Some(RepositoryPath::Tag { .. }) => crate::delete_registry_image.call(request, state).await,
+        _ => StatusCode::METHOD_NOT_ALLOWED.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::agent;
+    use axum::{http::Method, routing::get, Router};
+
+    #[test]
+    fn paths_split_at_the_last_tags_segment() {
+        let tags = |repository: &str| Some(RepositoryPath::Tags { repository: repository.to_string() });
+        let tag = |repository: &str, tag: &str| {
+            Some(RepositoryPath::Tag {
+                repository: repository.to_string(),
+                tag: tag.to_string(),
+            })
+        };
+        assert_eq!(RepositoryPath::parse("nginx/tags"), tags("nginx"));
+        assert_eq!(RepositoryPath::parse("/library/nginx/tags"), tags("library/nginx"));
+        assert_eq!(RepositoryPath::parse("a/b/c/tags/1.0"), tag("a/b/c", "1.0"));
+        assert_eq!(RepositoryPath::parse("org/tags/app/tags/v1"), tag("org/tags/app", "v1"));
+        assert_eq!(
+            RepositoryPath::parse("library/nginx/latest-stable"),
+            Some(RepositoryPath::LatestStable { repository: "library/nginx".to_string() })
+        );
+        // A tag may be called `latest-stable` too
+        assert_eq!(RepositoryPath::parse("nginx/tags/latest-stable"), tag("nginx", "latest-stable"));
+        assert_eq!(
+            RepositoryPath::parse("org/tags/app/latest-stable"),
+            Some(RepositoryPath::LatestStable { repository: "org/tags/app".to_string() })
+        );
+        let export = |repository: &str, tag: &str| {
+            Some(RepositoryPath::Export {
+                repository: repository.to_string(),
+                tag: tag.to_string(),
+            })
+        };
+        assert_eq!(RepositoryPath::parse("library/nginx/tags/1.25/export"), export("library/nginx", "1.25"));
+        assert_eq!(RepositoryPath::parse("org/tags/app/tags/v1/export"), export("org/tags/app", "v1"));
+        // As can `export`
+        assert_eq!(RepositoryPath::parse("nginx/tags/export"), tag("nginx", "export"));
+        assert_eq!(RepositoryPath::parse("nginx/tags/export/export"), export("nginx", "export"));
+        for invalid in ["nginx/export", "nginx/tags/export/", "nginx/tags//export", "nginx/tags/1.0/export/export"] {
+            assert_eq!(RepositoryPath::parse(invalid), None, "{}", invalid);
+        }
+        for invalid in ["nginx", "tags", "/tags", "nginx/tags/", "a//b/tags", "nginx/manifests/1.0", "latest-stable", "/latest-stable"] {
+            assert_eq!(RepositoryPath::parse(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[tokio::test]
+    async fn namespaced_repositories_reach_the_registry_whole() {
+        let agent = agent().await;
+        let routes = Router::new()
+            .route("/v2/nginx/tags/list", get(|| async { r#"{"name":"nginx","tags":["1"]}"# }))
+            .route("/v2/library/nginx/tags/list", get(|| async { r#"{"name":"library/nginx","tags":["1","2"]}"# }))
+            .route("/v2/org/team/app/tags/list", get(|| async { r#"{"name":"org/team/app","tags":["1","2","3"]}"# }))
+            .route("/v2/", get(|| async { "{}" }));
+        let url = crate::testing::serve_stub(routes).await;
+        agent.state.registry_manager.write().await.insert_registry(gpanel_core::RegistryConfig {
+            name: "mirror".to_string(),
+            url,
+            username: None,
+            password: None,
+            insecure: true,
+            page_size: None,
+            max_list_entries: None,
+            search_url: None,
+            ca_cert_path: None,
+        });
+
+        for (path, repository, count) in [
+            ("nginx", "nginx", 1),
+            ("library/nginx", "library/nginx", 2),
+            ("org/team/app", "org/team/app", 3),
+            // Encoded names, as the web sends them, work too
+            ("library%2Fnginx", "library/nginx", 2),
+        ] {
+            let uri = format!("/api/v1/registries/mirror/repositories/{}/tags", path);
+            let (status, body) = agent.request(Method::GET, &uri, None).await;
+            assert_eq!(status, StatusCode::OK, "{}", uri);
+            assert_eq!(body["name"], repository);
+            assert_eq!(body["tags"].as_array().unwrap().len(), count);
+        }
+
+        // The image routes get the whole name; this registry has no manifests
+        let (status, _) = agent
+            .request(Method::GET, "/api/v1/registries/mirror/repositories/org/team/app/tags/3", None)
+            .await;
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        let (status, body) = agent
+            .request(Method::DELETE, "/api/v1/registries/mirror/repositories/org/team/app/tags/3", None)
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["message"], "org/team/app:3 does not exist");
+
+        let (status, _) = agent
+            .request(Method::GET, "/api/v1/registries/mirror/repositories/org/team/app", None)
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn tags_are_sorted_on_request_and_the_latest_release_found() {
+        let agent = agent().await;
+        let routes = Router::new()
+            .route(
+                "/v2/library/nginx/tags/list",
+                get(|| async { r#"{"name":"library/nginx","tags":["1.9","1.25.3-alpine","latest","1.10","stable","1.26.0-rc.1"]}"# }),
+            )
+            .route("/v2/edge/tags/list", get(|| async { r#"{"name":"edge","tags":["latest","2.0.0-beta"]}"# }))
+            .route("/v2/", get(|| async { "{}" }));
+        let url = crate::testing::serve_stub(routes).await;
+        agent.state.registry_manager.write().await.insert_registry(gpanel_core::RegistryConfig {
+            name: "mirror".to_string(),
+            url,
+            username: None,
+            password: None,
+            insecure: true,
+            page_size: None,
+            max_list_entries: None,
+            search_url: None,
+            ca_cert_path: None,
+        });
+
+        let tags = |sort: &'static str| {
+            let agent = &agent;
+            async move {
+                let uri = format!("/api/v1/registries/mirror/repositories/library/nginx/tags{}", sort);
+                let (status, body) = agent.request(Method::GET, &uri, None).await;
+                assert_eq!(status, StatusCode::OK, "{}", uri);
+                serde_json::from_value::<Vec<String>>(body["tags"].clone()).unwrap()
+            }
+        };
+        assert_eq!(tags("").await, ["1.9", "1.25.3-alpine", "latest", "1.10", "stable", "1.26.0-rc.1"]);
+        assert_eq!(tags("?sort=none").await, tags("").await);
+        assert_eq!(tags("?sort=semver").await, ["latest", "1.26.0-rc.1", "1.25.3-alpine", "1.10", "1.9", "stable"]);
+        assert_eq!(tags("?sort=alpha").await, ["1.10", "1.25.3-alpine", "1.26.0-rc.1", "1.9", "latest", "stable"]);
+        let (status, _) = agent
+            .request(Method::GET, "/api/v1/registries/mirror/repositories/library/nginx/tags?sort=newest", None)
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, body) = agent
+            .request(Method::GET, "/api/v1/registries/mirror/repositories/library/nginx/latest-stable", None)
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, serde_json::json!({ "repository": "library/nginx", "tag": "1.10" }));
+
+        let (status, body) = agent.request(Method::GET, "/api/v1/registries/mirror/repositories/edge/latest-stable", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "TAG_NOT_FOUND");
+    }
+}
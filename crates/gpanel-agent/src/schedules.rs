@@ -0,0 +1,602 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use gpanel_core::{
+    ApiErrorResponse, Container, ContainerRuntime, CronErrorDetails, CronSchedule, OperationResult, Schedule,
+    ScheduleAction, ScheduleListResponse, ScheduleRequest, ScheduleRunResult,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{error, info, warn};
+use utoipa::IntoParams;
+
+use crate::audit::AuditLog;
+use crate::auth::RequireOperator;
+use crate::environments::Env;
+use crate::error::ApiError;
+use crate::store::Store;
+use crate::AppState;
+
+/// Store collection holding schedules
+const SCHEDULE_STORE: &str = "schedules";
+
+/// Who scheduled runs are attributed to in the audit log
+const AUDIT_USER: &str = "scheduler";
+
+/// How often the scheduler checks for due schedules
+const TICK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Query parameters for GET /api/v1/schedules
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ScheduleQuery {
+    pub container_id: Option<String>,
+}
+
+/// Schedules and the set of runs in progress
+pub struct ScheduleRegistry {
+    schedules: RwLock<HashMap<String, Schedule>>,
+    running: Mutex<HashSet<String>>,
+    store: Store,
+}
+
+impl ScheduleRegistry {
+    /// Load stored schedules; runs missed while the agent was down are not replayed
+    pub async fn load(store: Store, now: DateTime<Utc>) -> Self {
+        let stored: Vec<Schedule> = store.load(SCHEDULE_STORE).await;
+
+        let schedules = stored
+            .into_iter()
+            .map(|mut schedule| {
+                if schedule.next_run.map(|next| next < now).unwrap_or(true) {
+                    schedule.next_run = next_run(&schedule, now);
+                }
+                (schedule.id.clone(), schedule)
+            })
+            .collect();
+
+        Self {
+            schedules: RwLock::new(schedules),
+            running: Mutex::new(HashSet::new()),
+            store,
+        }
+    }
+
+    pub async fn list(&self) -> Vec<Schedule> {
+        let mut schedules: Vec<Schedule> = self.schedules.read().await.values().cloned().collect();
+        schedules.sort_by(|a, b| a.next_run.cmp(&b.next_run).then_with(|| a.id.cmp(&b.id)));
+        schedules
+    }
+
+    pub async fn get(&self, id: &str) -> Option<Schedule> {
+        self.schedules.read().await.get(id).cloned()
+    }
+
+    pub async fn save(&self, schedule: Schedule) -> anyhow::Result<()> {
+        let mut schedules = self.schedules.write().await;
+        schedules.insert(schedule.id.clone(), schedule);
+        self.persist(&schedules).await
+    }
+
+    pub async fn remove(&self, id: &str) -> anyhow::Result<bool> {
+        let mut schedules = self.schedules.write().await;
+        if schedules.remove(id).is_none() {
+            return Ok(false);
+        }
+        self.persist(&schedules).await?;
+        Ok(true)
+    }
+
+    async fn persist(&self, schedules: &HashMap<String, Schedule>) -> anyhow::Result<()> {
+        let mut stored: Vec<&Schedule> = schedules.values().collect();
+        stored.sort_by(|a, b| a.id.cmp(&b.id));
+        self.store.save(SCHEDULE_STORE, &stored).await
+    }
+
+    /// Take the schedules of `env` due at `now`, advancing each to its following run
+    pub async fn take_due(&self, env: &Env, now: DateTime<Utc>) -> Vec<Schedule> {
+        let mut schedules = self.schedules.write().await;
+        let mut due = Vec::new();
+
+        for schedule in schedules.values_mut() {
+            if !runs_in(schedule, env) || !schedule.enabled || schedule.next_run.map(|next| next > now).unwrap_or(true) {
+                continue;
+            }
+            due.push(schedule.clone());
+            schedule.next_run = next_run(schedule, now);
+        }
+
+        if !due.is_empty()
+            && let Err(e) = self.persist(&schedules).await
+        {
+            error!("Failed to save schedules: {}", e);
+        }
+        due
+    }
+
+    async fn record_run(&self, id: &str, ran_at: DateTime<Utc>, result: ScheduleRunResult) {
+        let mut schedules = self.schedules.write().await;
+        // The schedule may have been deleted while it ran
+        if let Some(schedule) = schedules.get_mut(id) {
+            schedule.last_run = Some(ran_at);
+            schedule.last_result = Some(result);
+            if let Err(e) = self.persist(&schedules).await {
+                error!("Failed to save schedules: {}", e);
+            }
+        }
+    }
+}
+
+fn next_run(schedule: &Schedule, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if !schedule.enabled {
+        return None;
+    }
+    CronSchedule::parse(&schedule.cron).ok()?.next_after(now)
+}
+
+/// Whether a schedule targets containers of `env`; those saved without an environment
+/// belong to the default one
+fn runs_in(schedule: &Schedule, env: &Env) -> bool {
+    schedule.environment.as_deref().map_or(env.is_default(), |name| name == env.name)
+}
+
+fn label_matches(container: &Container, selector: &str) -> bool {
+    match selector.split_once('=') {
+        Some((key, value)) => container.labels.get(key).map(|v| v == value).unwrap_or(false),
+        None => container.labels.contains_key(selector),
+    }
+}
+
+/// Resolve the containers a schedule applies to
+fn schedule_targets(schedule: &Schedule, containers: Vec<Container>) -> Vec<Container> {
+    containers
+        .into_iter()
+        .filter(|container| match (&schedule.container_id, &schedule.label_selector) {
+            (Some(id), _) => &container.id == id || &container.name == id,
+            (None, Some(selector)) => label_matches(container, selector),
+            (None, None) => false,
+        })
+        .collect()
+}
+
+/// Apply a schedule's action to its containers
+async fn run_schedule(bolt_client: &dyn ContainerRuntime, schedule: &Schedule) -> ScheduleRunResult {
+    let containers = match bolt_client.list_containers(None).await {
+        Ok(containers) => schedule_targets(schedule, containers),
+        Err(e) => {
+            return ScheduleRunResult {
+                success: false,
+                message: format!("Failed to list containers: {}", e),
+                containers: Vec::new(),
+            }
+        }
+    };
+
+    if containers.is_empty() {
+        return ScheduleRunResult {
+            success: false,
+            message: "No matching containers".to_string(),
+            containers: Vec::new(),
+        };
+    }
+
+    let mut failures = Vec::new();
+    for container in &containers {
+        let result = match schedule.action {
+            ScheduleAction::Start => bolt_client.start_container(&container.id).await,
+            ScheduleAction::Stop => bolt_client.stop_container(&container.id, None).await,
+            ScheduleAction::Restart => bolt_client.restart_container(&container.id, None).await,
+        };
+        if let Err(e) = result {
+            failures.push(format!("{}: {}", container.name, e));
+        }
+    }
+
+    ScheduleRunResult {
+        success: failures.is_empty(),
+        message: if failures.is_empty() {
+            format!("{:?} applied to {} containers", schedule.action, containers.len())
+        } else {
+            failures.join("; ")
+        },
+        containers: containers.into_iter().map(|c| c.name).collect(),
+    }
+}
+
+/// Start every schedule of `env` due at `now`, skipping any whose previous run is still
+/// going. Runs and skips are recorded in the audit log.
+pub async fn tick(registry: &Arc<ScheduleRegistry>, env: &Env, audit: &Arc<AuditLog>, now: DateTime<Utc>) {
+    for schedule in registry.take_due(env, now).await {
+        if !registry.running.lock().await.insert(schedule.id.clone()) {
+            warn!("Skipping schedule {}: previous run is still in progress", schedule.id);
+            audit
+                .record_action(
+                    AUDIT_USER,
+                    "schedules.run",
+                    "schedules",
+                    Some(&schedule.id),
+                    Err("Skipped, the previous run is still in progress".to_string()),
+                )
+                .await;
+            continue;
+        }
+
+        let registry = registry.clone();
+        let runtime = env.runtime.clone();
+        let audit = audit.clone();
+        tokio::spawn(async move {
+            let result = run_schedule(runtime.as_ref(), &schedule).await;
+
+            if result.success {
+                info!("Schedule {} ran: {}", schedule.id, result.message);
+            } else {
+                warn!("Schedule {} failed: {}", schedule.id, result.message);
+            }
+            let outcome = if result.success { Ok(()) } else { Err(result.message.clone()) };
+            audit
+                .record_action(AUDIT_USER, "schedules.run", "schedules", Some(&schedule.id), outcome)
+                .await;
+
+            registry.record_run(&schedule.id, now, result).await;
+            registry.running.lock().await.remove(&schedule.id);
+        });
+    }
+}
+
+/// Background task evaluating the schedules of an environment against the wall clock
+pub async fn run_scheduler(registry: Arc<ScheduleRegistry>, env: Env, audit: Arc<AuditLog>) {
+    let mut ticker = tokio::time::interval(TICK_INTERVAL);
+    loop {
+        ticker.tick().await;
+        tick(&registry, &env, &audit, Utc::now()).await;
+    }
+}
+
+/// Check a schedule's target, action and cron expression
+fn validate_schedule(schedule: &Schedule) -> Result<(), ApiError> {
+    match (&schedule.container_id, &schedule.label_selector) {
+        (Some(_), None) | (None, Some(_)) => {}
+        _ => {
+            return Err(ApiError::from_status(
+                StatusCode::BAD_REQUEST,
+                "Exactly one of container_id or label_selector is required".to_string(),
+            ))
+        }
+    }
+
+    CronSchedule::parse(&schedule.cron).map_err(|e| {
+        ApiError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "INVALID_CRON",
+            format!("Invalid cron expression: {}", e.message),
+        )
+        .with_details(CronErrorDetails { position: e.position })
+    })?;
+
+    Ok(())
+}
+
+async fn save_schedule(state: &AppState, schedule: Schedule) -> Result<Schedule, ApiError> {
+    if let Err(e) = state.schedules.save(schedule.clone()).await {
+        error!("Failed to save schedule {}: {}", schedule.id, e);
+        return Err(ApiError::from_status(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save schedule: {}", e),
+        ));
+    }
+    Ok(schedule)
+}
+
+/// List schedules, optionally only those targeting one container
+#[utoipa::path(
+    get,
+    path = "/api/v1/schedules",
+    tag = "schedules",
+    params(ScheduleQuery),
+    responses((status = 200, description = "Schedules, optionally for one container", body = ScheduleListResponse)),
+)]
+pub async fn list_schedules(
+    State(state): State<AppState>,
+    Query(query): Query<ScheduleQuery>,
+) -> Json<ScheduleListResponse> {
+    let schedules = state
+        .schedules
+        .list()
+        .await
+        .into_iter()
+        .filter(|schedule| query.container_id.is_none() || schedule.container_id == query.container_id)
+        .collect();
+    Json(ScheduleListResponse { schedules })
+}
+
+/// Get a schedule
+#[utoipa::path(
+    get,
+    path = "/api/v1/schedules/{id}",
+    tag = "schedules",
+    params(("id" = String, Path, description = "Schedule ID")),
+    responses(
+        (status = 200, description = "The schedule", body = Schedule),
+        (status = 404, description = "No such schedule", body = ApiErrorResponse),
+    ),
+)]
+pub async fn get_schedule(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Schedule>, ApiError> {
+    state
+        .schedules
+        .get(&id)
+        .await
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found("SCHEDULE", format!("Schedule '{}' not found", id)))
+}
+
+/// Create a schedule
+#[utoipa::path(
+    post,
+    path = "/api/v1/schedules",
+    tag = "schedules",
+    request_body = ScheduleRequest,
+    responses(
+        (status = 201, description = "Schedule created", body = Schedule),
+        (status = 400, description = "Neither or both of container_id and label_selector given", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 422, description = "Invalid cron expression", body = ApiErrorResponse),
+    ),
+)]
+pub async fn create_schedule(
+    _: RequireOperator,
+    State(state): State<AppState>,
+    env: Env,
+    Json(request): Json<ScheduleRequest>,
+) -> Result<(StatusCode, Json<Schedule>), ApiError> {
+    let (Some(action), Some(cron)) = (request.action, request.cron) else {
+        return Err(ApiError::from_status(
+            StatusCode::BAD_REQUEST,
+            "Both action and cron are required".to_string(),
+        ));
+    };
+
+    let mut schedule = Schedule {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: request.name,
+        container_id: request.container_id,
+        label_selector: request.label_selector,
+        action,
+        cron,
+        enabled: request.enabled.unwrap_or(true),
+        last_run: None,
+        last_result: None,
+        next_run: None,
+        environment: Some(env.name),
+    };
+    validate_schedule(&schedule)?;
+    schedule.next_run = next_run(&schedule, Utc::now());
+
+    let schedule = save_schedule(&state, schedule).await?;
+    info!("Created schedule {} ({:?} at '{}')", schedule.id, schedule.action, schedule.cron);
+    Ok((StatusCode::CREATED, Json(schedule)))
+}
+
+/// Update a schedule; omitted fields keep their current values
+#[utoipa::path(
+    put,
+    path = "/api/v1/schedules/{id}",
+    tag = "schedules",
+    params(("id" = String, Path, description = "Schedule ID")),
+    request_body = ScheduleRequest,
+    responses(
+        (status = 200, description = "Schedule updated", body = Schedule),
+        (status = 400, description = "Neither or both of container_id and label_selector given", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such schedule", body = ApiErrorResponse),
+        (status = 422, description = "Invalid cron expression", body = ApiErrorResponse),
+    ),
+)]
+pub async fn update_schedule(
+    _: RequireOperator,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<ScheduleRequest>,
+) -> Result<Json<Schedule>, ApiError> {
+    let mut schedule = state
+        .schedules
+        .get(&id)
+        .await
+        .ok_or_else(|| ApiError::not_found("SCHEDULE", format!("Schedule '{}' not found", id)))?;
+
+    if request.name.is_some() {
+        schedule.name = request.name;
+    }
+    // Setting one target clears the other
+    if request.container_id.is_some() {
+        schedule.container_id = request.container_id;
+        schedule.label_selector = None;
+    } else if request.label_selector.is_some() {
+        schedule.label_selector = request.label_selector;
+        schedule.container_id = None;
+    }
+    if let Some(action) = request.action {
+        schedule.action = action;
+    }
+    if let Some(cron) = request.cron {
+        schedule.cron = cron;
+    }
+    if let Some(enabled) = request.enabled {
+        schedule.enabled = enabled;
+    }
+
+    validate_schedule(&schedule)?;
+    schedule.next_run = next_run(&schedule, Utc::now());
+
+    let schedule = save_schedule(&state, schedule).await?;
+    info!("Updated schedule {}", schedule.id);
+    Ok(Json(schedule))
+}
+
+/// Delete a schedule
+#[utoipa::path(
+    delete,
+    path = "/api/v1/schedules/{id}",
+    tag = "schedules",
+    params(("id" = String, Path, description = "Schedule ID")),
+    responses(
+        (status = 200, description = "Schedule removed", body = OperationResult),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such schedule", body = ApiErrorResponse),
+    ),
+)]
+pub async fn delete_schedule(
+    _: RequireOperator,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<OperationResult>, ApiError> {
+    match state.schedules.remove(&id).await {
+        Ok(true) => {
+            info!("Deleted schedule {}", id);
+            Ok(Json(OperationResult {
+                success: true,
+                message: format!("Schedule {} deleted", id),
+            }))
+        }
+        Ok(false) => Err(ApiError::not_found("SCHEDULE", format!("Schedule '{}' not found", id))),
+        Err(e) => {
+            error!("Failed to delete schedule {}: {}", id, e);
+            Err(ApiError::from_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to delete schedule: {}", e),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::agent;
+    use gpanel_core::{AuditOutcome, AuditQuery, ContainerStatus};
+
+    fn at(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text).unwrap().with_timezone(&Utc)
+    }
+
+    fn nightly_stop(container_id: &str, now: DateTime<Utc>) -> Schedule {
+        let mut schedule = Schedule {
+            id: "nightly".to_string(),
+            name: None,
+            container_id: Some(container_id.to_string()),
+            label_selector: None,
+            action: ScheduleAction::Stop,
+            cron: "0 3 * * *".to_string(),
+            enabled: true,
+            last_run: None,
+            last_result: None,
+            next_run: None,
+            environment: None,
+        };
+        schedule.next_run = next_run(&schedule, now);
+        schedule
+    }
+
+    async fn audited_runs(audit: &AuditLog) -> Vec<gpanel_core::AuditEvent> {
+        let query = AuditQuery {
+            action: Some("schedules.run".to_string()),
+            ..Default::default()
+        };
+        audit.query(&query).await.events
+    }
+
+    #[tokio::test]
+    async fn due_schedules_run_once_and_are_audited() {
+        let agent = agent().await;
+        let state = &agent.state;
+        let env = state.environments.default_env();
+        state
+            .schedules
+            .save(nightly_stop("nginx-web", at("2024-03-01T12:00:00Z")))
+            .await
+            .unwrap();
+
+        tick(&state.schedules, &env, &state.audit, at("2024-03-02T02:59:00Z")).await;
+        assert!(state.schedules.running.lock().await.is_empty());
+
+        let due = at("2024-03-02T03:00:00Z");
+        tick(&state.schedules, &env, &state.audit, due).await;
+        let schedule = loop {
+            let schedule = state.schedules.get("nightly").await.unwrap();
+            if schedule.last_result.is_some() {
+                break schedule;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        };
+
+        assert_eq!(schedule.last_run, Some(due));
+        assert!(schedule.last_result.unwrap().success);
+        assert_eq!(schedule.next_run, Some(at("2024-03-03T03:00:00Z")));
+        let container = env.runtime.get_container("mock_web_server_001").await.unwrap();
+        assert!(matches!(container.status, ContainerStatus::Exited { .. }));
+
+        let runs = audited_runs(&state.audit).await;
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].user, AUDIT_USER);
+        assert_eq!(runs[0].resource_id.as_deref(), Some("nightly"));
+        assert_eq!(runs[0].outcome, AuditOutcome::Success);
+    }
+
+    #[tokio::test]
+    async fn runs_are_skipped_while_the_previous_one_is_going() {
+        let agent = agent().await;
+        let state = &agent.state;
+        let env = state.environments.default_env();
+        state
+            .schedules
+            .save(nightly_stop("nginx-web", at("2024-03-01T12:00:00Z")))
+            .await
+            .unwrap();
+        state.schedules.running.lock().await.insert("nightly".to_string());
+
+        tick(&state.schedules, &env, &state.audit, at("2024-03-02T03:00:00Z")).await;
+
+        let schedule = state.schedules.get("nightly").await.unwrap();
+        assert_eq!(schedule.last_run, None);
+        assert_eq!(schedule.next_run, Some(at("2024-03-03T03:00:00Z")));
+        let container = env.runtime.get_container("mock_web_server_001").await.unwrap();
+        assert!(matches!(container.status, ContainerStatus::Running));
+
+        let runs = audited_runs(&state.audit).await;
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].outcome, AuditOutcome::Failure);
+        assert!(runs[0].detail.as_deref().unwrap().contains("still in progress"));
+    }
+
+    #[tokio::test]
+    async fn failed_runs_are_audited_as_failures() {
+        let agent = agent().await;
+        let state = &agent.state;
+        let env = state.environments.default_env();
+        state
+            .schedules
+            .save(nightly_stop("no-such-container", at("2024-03-01T12:00:00Z")))
+            .await
+            .unwrap();
+
+        tick(&state.schedules, &env, &state.audit, at("2024-03-02T03:00:00Z")).await;
+        let runs = loop {
+            let runs = audited_runs(&state.audit).await;
+            if !runs.is_empty() {
+                break runs;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        };
+
+        assert_eq!(runs[0].outcome, AuditOutcome::Failure);
+        assert_eq!(runs[0].detail.as_deref(), Some("No matching containers"));
+    }
+}
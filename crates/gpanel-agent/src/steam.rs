@@ -0,0 +1,348 @@
+use anyhow::{Context, bail};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use gpanel_core::{ApiErrorResponse, SteamApp, SteamAppSummary, SteamConfig, SteamPlatforms};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{error, info, warn};
+use utoipa::IntoParams;
+
+use crate::error::ApiError;
+use crate::store::Store;
+use crate::AppState;
+
+/// Store collection of looked-up apps, kept past their TTL as a fallback
+const APPS_STORE: &str = "steam_apps";
+
+/// Store collection holding Steam's full app list for search
+const APP_LIST_STORE: &str = "steam_app_list";
+
+/// How long to wait on each call to Steam
+const STEAM_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The full app list is several megabytes
+const APP_LIST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// After a failed app list refresh, searches use the cached list this long before retrying
+const APP_LIST_RETRY: chrono::Duration = chrono::Duration::minutes(5);
+
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+const MAX_SEARCH_LIMIT: usize = 100;
+
+/// Queries shorter than this only match names containing them, not scattered letters
+const MIN_SUBSEQUENCE_QUERY: usize = 3;
+
+/// One entry of an `appdetails` response, keyed by app ID
+#[derive(Debug, Deserialize)]
+struct AppDetails {
+    success: bool,
+    data: Option<AppDetailsData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AppDetailsData {
+    name: String,
+    header_image: Option<String>,
+    #[serde(default)]
+    platforms: SteamPlatforms,
+}
+
+#[derive(Debug, Deserialize)]
+struct AppListResponse {
+    applist: AppList,
+}
+
+#[derive(Debug, Deserialize)]
+struct AppList {
+    apps: Vec<AppListEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AppListEntry {
+    appid: u32,
+    name: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoredAppList {
+    fetched_at: Option<DateTime<Utc>>,
+    apps: Vec<SteamAppSummary>,
+    #[serde(skip)]
+    failed_at: Option<DateTime<Utc>>,
+}
+
+/// Steam app details and the app list, cached on disk
+pub struct SteamCatalog {
+    config: SteamConfig,
+    client: reqwest::Client,
+    store: Store,
+    apps: RwLock<HashMap<u32, SteamApp>>,
+    /// Read from disk on the first search, and held while Steam is asked for a new one
+    app_list: Mutex<Option<StoredAppList>>,
+}
+
+impl SteamCatalog {
+    pub async fn load(config: &SteamConfig, store: Store) -> Self {
+        let stored: Vec<SteamApp> = store.load(APPS_STORE).await;
+        let client = reqwest::Client::builder()
+            .connect_timeout(STEAM_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            config: config.clone(),
+            client,
+            store,
+            apps: RwLock::new(stored.into_iter().map(|app| (app.app_id, app)).collect()),
+            app_list: Mutex::new(None),
+        }
+    }
+
+    fn is_fresh(&self, fetched_at: DateTime<Utc>) -> bool {
+        let ttl = chrono::Duration::seconds(self.config.cache_ttl_secs.try_into().unwrap_or(i64::MAX));
+        Utc::now() < fetched_at + ttl
+    }
+
+    /// Details of an app, from the cache while fresh. When Steam fails, an expired entry
+    /// is returned marked `stale`.
+    pub async fn app(&self, app_id: u32) -> Result<SteamApp, ApiError> {
+        let cached = self.apps.read().await.get(&app_id).cloned();
+        if let Some(app) = &cached
+            && self.is_fresh(app.fetched_at)
+        {
+            return Ok(app.clone());
+        }
+
+        match self.fetch_app(app_id).await {
+            Ok(Some(app)) => {
+                let mut apps = self.apps.write().await;
+                apps.insert(app_id, app.clone());
+                let mut stored: Vec<&SteamApp> = apps.values().collect();
+                stored.sort_by_key(|app| app.app_id);
+                if let Err(e) = self.store.save(APPS_STORE, &stored).await {
+                    warn!("Failed to save the Steam app cache: {}", e);
+                }
+                Ok(app)
+            }
+            Ok(None) => Err(ApiError::not_found("STEAM_APP", format!("Steam has no app {}", app_id))),
+            Err(e) => match cached {
+                Some(app) => {
+                    warn!("Failed to look up Steam app {}, using the cached details: {:#}", app_id, e);
+                    Ok(SteamApp { stale: true, ..app })
+                }
+                None => {
+                    error!("Failed to look up Steam app {}: {:#}", app_id, e);
+                    Err(ApiError::from_status(StatusCode::BAD_GATEWAY, format!("Failed to look up Steam app {}: {}", app_id, e)))
+                }
+            },
+        }
+    }
+
+    /// The app's store details, or `None` if Steam does not know it
+    async fn fetch_app(&self, app_id: u32) -> anyhow::Result<Option<SteamApp>> {
+        let response = self
+            .client
+            .get(&self.config.store_api_url)
+            .query(&[("appids", app_id.to_string()), ("filters", "basic,platforms".to_string())])
+            .timeout(STEAM_TIMEOUT)
+            .send()
+            .await
+            .context("Failed to reach the Steam store")?;
+        if !response.status().is_success() {
+            bail!("Steam store answered {}", response.status());
+        }
+        let mut details: HashMap<String, AppDetails> =
+            response.json().await.context("Unexpected response from the Steam store")?;
+
+        let Some(AppDetails { success: true, data: Some(data) }) = details.remove(&app_id.to_string()) else {
+            return Ok(None);
+        };
+        Ok(Some(SteamApp {
+            app_id,
+            name: data.name,
+            header_image: data.header_image,
+            platforms: data.platforms,
+            fetched_at: Utc::now(),
+            stale: false,
+        }))
+    }
+
+    /// Apps whose names best match `query`, or the app with that ID
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<SteamAppSummary>, ApiError> {
+        let mut app_list = self.app_list.lock().await;
+        if app_list.is_none() {
+            *app_list = Some(self.store.load(APP_LIST_STORE).await);
+        }
+        let list = app_list.as_mut().expect("app list was just loaded");
+
+        let stale = list.fetched_at.is_none_or(|fetched_at| !self.is_fresh(fetched_at));
+        let retry_due = list.failed_at.is_none_or(|failed_at| Utc::now() >= failed_at + APP_LIST_RETRY);
+        if stale && (retry_due || list.apps.is_empty()) {
+            match self.fetch_app_list().await {
+                Ok(apps) => {
+                    info!("Fetched the Steam app list ({} apps)", apps.len());
+                    *list = StoredAppList {
+                        fetched_at: Some(Utc::now()),
+                        apps,
+                        failed_at: None,
+                    };
+                    if let Err(e) = self.store.save(APP_LIST_STORE, &*list).await {
+                        warn!("Failed to save the Steam app list: {}", e);
+                    }
+                }
+                Err(e) if list.apps.is_empty() => {
+                    error!("Failed to fetch the Steam app list: {:#}", e);
+                    return Err(ApiError::from_status(
+                        StatusCode::BAD_GATEWAY,
+                        format!("Failed to fetch the Steam app list: {}", e),
+                    ));
+                }
+                Err(e) => {
+                    warn!("Failed to refresh the Steam app list, searching the cached one: {:#}", e);
+                    list.failed_at = Some(Utc::now());
+                }
+            }
+        }
+
+        Ok(rank_apps(&list.apps, query, limit))
+    }
+
+    async fn fetch_app_list(&self) -> anyhow::Result<Vec<SteamAppSummary>> {
+        let response = self
+            .client
+            .get(&self.config.app_list_url)
+            .timeout(APP_LIST_TIMEOUT)
+            .send()
+            .await
+            .context("Failed to reach the Steam Web API")?;
+        if !response.status().is_success() {
+            bail!("Steam Web API answered {}", response.status());
+        }
+        let list: AppListResponse = response.json().await.context("Unexpected app list from Steam")?;
+        Ok(list
+            .applist
+            .apps
+            .into_iter()
+            .filter(|app| !app.name.trim().is_empty())
+            .map(|app| SteamAppSummary {
+                app_id: app.appid,
+                name: app.name,
+            })
+            .collect())
+    }
+}
+
+/// Lowercase words, punctuation dropped, so `Half-Life: Alyx` reads `half life alyx`
+fn normalize(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|h| h == c))
+}
+
+/// How well `name` matches the normalized `query`: exact, then prefix, then every word
+/// starting a word of the name, then substring, then the letters in order
+fn match_score(query: &str, name: &str) -> Option<u8> {
+    let name = normalize(name);
+    if name == query {
+        return Some(100);
+    }
+    if name.starts_with(query) {
+        return Some(80);
+    }
+    if query
+        .split(' ')
+        .all(|word| name.split(' ').any(|name_word| name_word.starts_with(word)))
+    {
+        return Some(60);
+    }
+    if name.contains(query) {
+        return Some(50);
+    }
+    let compact = query.replace(' ', "");
+    (compact.len() >= MIN_SUBSEQUENCE_QUERY && is_subsequence(&compact, &name.replace(' ', ""))).then_some(20)
+}
+
+/// Best matches first; among equal matches, shorter names first
+fn rank_apps(apps: &[SteamAppSummary], query: &str, limit: usize) -> Vec<SteamAppSummary> {
+    let app_id: Option<u32> = query.trim().parse().ok();
+    let query = normalize(query);
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut matches: Vec<(u8, &SteamAppSummary)> = apps
+        .iter()
+        .filter_map(|app| {
+            let score = if Some(app.app_id) == app_id { Some(100) } else { match_score(&query, &app.name) };
+            score.map(|score| (score, app))
+        })
+        .collect();
+    matches.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then_with(|| a.name.len().cmp(&b.name.len()))
+            .then_with(|| a.app_id.cmp(&b.app_id))
+    });
+    matches.into_iter().take(limit).map(|(_, app)| app.clone()).collect()
+}
+
+/// Query parameters for GET /api/v1/gaming/steam/apps
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SteamSearchQuery {
+    /// Game name, or part of it; an app ID also matches that app
+    pub search: String,
+    /// Most results to return, up to 100
+    pub limit: Option<usize>,
+}
+
+/// Name, artwork and platforms of a Steam app
+#[utoipa::path(
+    get,
+    path = "/api/v1/gaming/steam/apps/{appid}",
+    tag = "gaming",
+    params(("appid" = u32, Path, description = "Steam app ID")),
+    responses(
+        (status = 200, description = "The app; `stale` when Steam was unavailable and an expired cache entry was used", body = SteamApp),
+        (status = 404, description = "Steam has no such app", body = ApiErrorResponse),
+        (status = 502, description = "Steam is unavailable and the app is not cached", body = ApiErrorResponse),
+    ),
+)]
+pub async fn get_app(State(state): State<AppState>, Path(app_id): Path<u32>) -> Result<Json<SteamApp>, ApiError> {
+    state.steam.app(app_id).await.map(Json)
+}
+
+/// Search Steam apps by name
+#[utoipa::path(
+    get,
+    path = "/api/v1/gaming/steam/apps",
+    tag = "gaming",
+    params(SteamSearchQuery),
+    responses(
+        (status = 200, description = "Matching apps, best first", body = Vec<SteamAppSummary>),
+        (status = 422, description = "Empty search", body = ApiErrorResponse),
+        (status = 502, description = "Steam is unavailable and no app list is cached", body = ApiErrorResponse),
+    ),
+)]
+pub async fn search_apps(
+    State(state): State<AppState>,
+    Query(query): Query<SteamSearchQuery>,
+) -> Result<Json<Vec<SteamAppSummary>>, ApiError> {
+    if query.search.trim().is_empty() {
+        return Err(ApiError::from_status(StatusCode::UNPROCESSABLE_ENTITY, "search must not be empty"));
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_SEARCH_LIMIT);
+    state.steam.search(&query.search, limit).await.map(Json)
+}
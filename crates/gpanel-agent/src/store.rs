@@ -0,0 +1,51 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::PathBuf;
+use tracing::warn;
+
+/// JSON file store for agent state, one file per collection under the data directory
+#[derive(Debug, Clone)]
+pub struct Store {
+    dir: PathBuf,
+}
+
+impl Store {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Store in the subdirectory `name`, for state kept apart from the rest
+    pub fn scoped(&self, name: &str) -> Self {
+        Self::new(self.dir.join(name))
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", name))
+    }
+
+    /// Load a collection, falling back to the default if it is missing or unreadable
+    pub async fn load<T: DeserializeOwned + Default>(&self, name: &str) -> T {
+        let path = self.path(name);
+        match tokio::fs::read(&path).await {
+            Ok(data) => serde_json::from_slice(&data).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable state file {}: {}", path.display(), e);
+                T::default()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => T::default(),
+            Err(e) => {
+                warn!("Failed to read state file {}: {}", path.display(), e);
+                T::default()
+            }
+        }
+    }
+
+    /// Save a collection, replacing the file atomically
+    pub async fn save<T: Serialize>(&self, name: &str, value: &T) -> anyhow::Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+
+        let path = self.path(name);
+        let tmp = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, serde_json::to_vec_pretty(value)?).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+}
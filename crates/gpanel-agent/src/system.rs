@@ -0,0 +1,261 @@
+use axum::{
+    extract::Query,
+    response::Json,
+};
+use gpanel_core::{
+    ApiErrorResponse, BoltDiskUsage, BoltSystemInfo, Container, ContainerDiskUsage, ContainerRuntime, DiskUsageDetails,
+    DiskUsageSummary, ImageDiskUsage, ImageDiskUsageRow, LocalImage, Volume, VolumeDiskUsage, VolumeDiskUsageRow,
+};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::{error, warn};
+use utoipa::IntoParams;
+
+use crate::error::ApiError;
+use crate::environments::Env;
+use crate::{images, volumes};
+
+/// How long Bolt's system info is served from memory before it is asked again
+const SYSTEM_INFO_TTL: Duration = Duration::from_secs(30);
+
+/// Last system info fetched from the runtime, so dashboards polling the agent don't poll Bolt
+#[derive(Default)]
+pub struct SystemInfoCache {
+    cached: Mutex<Option<(Instant, BoltSystemInfo)>>,
+}
+
+impl SystemInfoCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cached info if it is fresh, otherwise fetch it. The lock is held while fetching,
+    /// so concurrent requests wait for one call to Bolt instead of each making their own.
+    pub async fn get(&self, runtime: &dyn ContainerRuntime) -> gpanel_core::Result<BoltSystemInfo> {
+        let mut cached = self.cached.lock().await;
+        if let Some((_, info)) = cached.as_ref().filter(|(fetched_at, _)| fetched_at.elapsed() < SYSTEM_INFO_TTL) {
+            return Ok(info.clone());
+        }
+
+        let info = runtime.system_info().await?;
+        *cached = Some((Instant::now(), info.clone()));
+        Ok(info)
+    }
+}
+
+/// Query parameters for GET /api/v1/system/df
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DiskUsageQuery {
+    /// Include a row per image and per volume
+    #[serde(default)]
+    pub verbose: bool,
+}
+
+/// Combine the image, container and volume lists with Bolt's native report
+///
+/// Reclaimable figures use the same rules as the prune endpoints, so pruning frees what is shown here.
+pub fn summarize_disk_usage(
+    mut images: Vec<LocalImage>,
+    containers: &[Container],
+    mut volumes: Vec<Volume>,
+    native: BoltDiskUsage,
+    verbose: bool,
+) -> DiskUsageSummary {
+    images::cross_reference(&mut images, containers);
+    volumes::cross_reference(&mut volumes, containers);
+
+    let unused_images = images.iter().filter(|image| image.containers.is_empty());
+    let image_usage = ImageDiskUsage {
+        count: images.len(),
+        total_bytes: images.iter().map(|image| image.size).sum(),
+        dangling_bytes: unused_images
+            .clone()
+            .filter(|image| image.dangling)
+            .map(|image| image.unique_size())
+            .sum(),
+        reclaimable_bytes: unused_images.map(|image| image.unique_size()).sum(),
+    };
+
+    let layer_size = |c: &Container| native.container_layers.get(&c.id).copied().unwrap_or(0);
+    let container_usage = ContainerDiskUsage {
+        count: containers.len(),
+        writable_bytes: containers.iter().map(layer_size).sum(),
+        reclaimable_bytes: containers
+            .iter()
+            .filter(|c| c.status.is_stopped())
+            .map(layer_size)
+            .sum(),
+    };
+
+    let volume_usage = VolumeDiskUsage {
+        count: volumes.len(),
+        total_bytes: volumes.iter().filter_map(|volume| volume.size).sum(),
+        unused_bytes: volumes
+            .iter()
+            .filter(|volume| volume.containers.is_empty())
+            .filter_map(|volume| volume.size)
+            .sum(),
+    };
+
+    let details = verbose.then(|| disk_usage_details(&images, &volumes));
+
+    let build_cache = native.build_cache;
+    let cache_total = build_cache.as_ref().map(|cache| cache.total_bytes).unwrap_or(0);
+    let cache_reclaimable = build_cache.as_ref().map(|cache| cache.reclaimable_bytes).unwrap_or(0);
+
+    DiskUsageSummary {
+        total_bytes: image_usage.total_bytes
+            + container_usage.writable_bytes
+            + volume_usage.total_bytes
+            + cache_total,
+        reclaimable_bytes: image_usage.reclaimable_bytes
+            + container_usage.reclaimable_bytes
+            + volume_usage.unused_bytes
+            + cache_reclaimable,
+        images: image_usage,
+        containers: container_usage,
+        volumes: volume_usage,
+        build_cache,
+        details,
+    }
+}
+
+fn disk_usage_details(images: &[LocalImage], volumes: &[Volume]) -> DiskUsageDetails {
+    let mut image_rows: Vec<ImageDiskUsageRow> = images
+        .iter()
+        .map(|image| ImageDiskUsageRow {
+            id: image.id.clone(),
+            repo_tags: image.repo_tags.clone(),
+            size: image.size,
+            shared_size: image.shared_size,
+            unique_size: image.unique_size(),
+            dangling: image.dangling,
+            containers: image.containers.len(),
+        })
+        .collect();
+    image_rows.sort_by_key(|row| Reverse(row.unique_size));
+
+    let mut volume_rows: Vec<VolumeDiskUsageRow> = volumes
+        .iter()
+        .map(|volume| VolumeDiskUsageRow {
+            name: volume.name.clone(),
+            driver: volume.driver.clone(),
+            size: volume.size,
+            containers: volume.containers.len(),
+        })
+        .collect();
+    volume_rows.sort_by_key(|row| Reverse(row.size));
+
+    DiskUsageDetails {
+        images: image_rows,
+        volumes: volume_rows,
+    }
+}
+
+/// Disk usage across images, containers, volumes and build cache
+#[utoipa::path(
+    get,
+    path = "/api/v1/system/df",
+    tag = "system",
+    params(DiskUsageQuery),
+    responses((status = 200, description = "Disk used by images, containers and volumes", body = DiskUsageSummary)),
+)]
+pub async fn disk_usage(
+    env: Env,
+    Query(query): Query<DiskUsageQuery>,
+) -> Result<Json<DiskUsageSummary>, ApiError> {
+    let images = env.runtime.list_images().await.map_err(|e| {
+        error!("Failed to list images for disk usage: {}", e);
+        ApiError::runtime(&e, format!("Failed to list images: {}", e))
+    })?;
+
+    let containers = env.runtime.list_containers(None).await.map_err(|e| {
+        error!("Failed to list containers for disk usage: {}", e);
+        ApiError::runtime(&e, format!("Failed to list containers: {}", e))
+    })?;
+
+    let volumes = env.runtime.list_volumes().await.map_err(|e| {
+        error!("Failed to list volumes for disk usage: {}", e);
+        ApiError::runtime(&e, format!("Failed to list volumes: {}", e))
+    })?;
+
+    // Older Bolt versions have no native df; fall back to what the lists provide
+    let native = env.runtime.disk_usage().await.unwrap_or_else(|e| {
+        warn!("Bolt disk usage unavailable: {}", e);
+        BoltDiskUsage::default()
+    });
+
+    Ok(Json(summarize_disk_usage(images, &containers, volumes, native, query.verbose)))
+}
+
+/// Version, host and object counts reported by Bolt, cached for up to 30 seconds
+#[utoipa::path(
+    get,
+    path = "/api/v1/system/info",
+    tag = "system",
+    responses(
+        (status = 200, description = "Runtime and host information", body = BoltSystemInfo),
+        (status = 500, description = "Runtime error", body = ApiErrorResponse),
+    ),
+)]
+pub async fn system_info(env: Env) -> Result<Json<BoltSystemInfo>, ApiError> {
+    env.system_info.get(env.runtime.as_ref()).await.map(Json).map_err(|e| {
+        error!("Failed to get system info: {}", e);
+        ApiError::runtime(&e, format!("Failed to get system info: {}", e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::agent;
+    use axum::http::{Method, StatusCode};
+
+    const MB: u64 = 1024 * 1024;
+
+    #[tokio::test]
+    async fn disk_usage_adds_up_the_mock_host() {
+        let agent = agent().await;
+
+        let (status, df) = agent.request(Method::GET, "/api/v1/system/df", None).await;
+        assert_eq!(status, StatusCode::OK);
+        // Redis and the dangling build are unused; only their unique layers are freed
+        assert_eq!(df["images"]["count"], 5);
+        assert_eq!(df["images"]["total_bytes"], (187 + 4210 + 412 + 41 + 3980) * MB);
+        assert_eq!(df["images"]["reclaimable_bytes"], (34 + 80) * MB);
+        assert_eq!(df["images"]["dangling_bytes"], 80 * MB);
+        // Only the stopped database's writable layer is reclaimable
+        assert_eq!(df["containers"]["count"], 3);
+        assert_eq!(df["containers"]["writable_bytes"], (12 + 1536 + 48) * MB);
+        assert_eq!(df["containers"]["reclaimable_bytes"], 48 * MB);
+        // `postgres_data` is mounted and `nfs_backups` has no size
+        assert_eq!(df["volumes"]["count"], 4);
+        assert_eq!(df["volumes"]["total_bytes"], (256 + 1240 + 880) * MB);
+        assert_eq!(df["volumes"]["unused_bytes"], (1240 + 880) * MB);
+        assert_eq!(df["build_cache"]["total_bytes"], 1200 * MB);
+
+        assert_eq!(df["total_bytes"], (8830 + 1596 + 2376 + 1200) * MB);
+        assert_eq!(df["reclaimable_bytes"], (114 + 48 + 2120 + 800) * MB);
+        assert!(df.get("details").is_none());
+    }
+
+    #[tokio::test]
+    async fn verbose_disk_usage_lists_the_largest_first() {
+        let agent = agent().await;
+
+        let (status, df) = agent.request(Method::GET, "/api/v1/system/df?verbose=true", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let images: Vec<(u64, u64)> = df["details"]["images"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|row| (row["unique_size"].as_u64().unwrap() / MB, row["containers"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(images, [(4136, 1), (338, 1), (113, 1), (80, 0), (34, 0)]);
+        let volumes: Vec<&str> =
+            df["details"]["volumes"].as_array().unwrap().iter().map(|row| row["name"].as_str().unwrap()).collect();
+        assert_eq!(volumes, ["minecraft_world", "shader_cache", "postgres_data", "nfs_backups"]);
+    }
+}
@@ -0,0 +1,226 @@
+use axum::{
+    body::Bytes,
+    extract::{
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
+    http::StatusCode,
+    response::Response,
+    Extension,
+};
+use futures::StreamExt;
+use gpanel_core::{
+    ApiErrorResponse, AuditEvent, AuditOutcome, ContainerStatus, ExecRequest, TerminalInput, TerminalSession,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+use utoipa::IntoParams;
+
+use crate::auth::{Caller, RequireOperator};
+use crate::environments::Env;
+use crate::error::ApiError;
+use crate::AppState;
+
+/// Shell run when the client does not ask for one
+const DEFAULT_SHELL: &str = "/bin/sh";
+
+/// Close a terminal after this long without input from the client
+const IDLE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// Query parameters for GET /api/v1/containers/:id/terminal
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TerminalQuery {
+    pub shell: Option<String>,
+}
+
+/// Control message sent in a text frame; terminal I/O travels in binary frames
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum TerminalControl {
+    Resize { cols: u16, rows: u16 },
+}
+
+/// Open an interactive shell in a running container over a WebSocket
+#[utoipa::path(
+    get,
+    path = "/api/v1/containers/{id}/terminal",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name"), TerminalQuery),
+    responses(
+        (status = 101, description = "WebSocket upgrade; terminal I/O travels in binary frames and resize messages in text frames"),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+        (status = 409, description = "Container is not running", body = ApiErrorResponse),
+    ),
+)]
+pub async fn container_terminal(
+    _: RequireOperator,
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    ws: WebSocketUpgrade,
+    env: Env,
+    Path(id): Path<String>,
+    Query(query): Query<TerminalQuery>,
+) -> Result<Response, ApiError> {
+    let shell = query.shell.unwrap_or_else(|| DEFAULT_SHELL.to_string());
+    let opened = open_session(&env, &id, &shell).await;
+
+    // The upgrade is a GET, which the audit middleware skips, so sessions are recorded here
+    let status = opened.as_ref().map_or_else(|e| e.status, |_| StatusCode::SWITCHING_PROTOCOLS);
+    state
+        .audit
+        .record(&AuditEvent {
+            timestamp: chrono::Utc::now(),
+            user: caller.map_or_else(|| "anonymous".to_string(), |Extension(caller)| caller.user.username),
+            action: "containers.terminal".to_string(),
+            resource_type: "containers".to_string(),
+            resource_id: Some(id.clone()),
+            outcome: AuditOutcome::from_status(status.as_u16()),
+            status: status.as_u16(),
+            detail: Some(match &opened {
+                Ok(_) => format!("Opened {}", shell),
+                Err(e) => e.body.message.clone(),
+            }),
+        })
+        .await;
+
+    let session = opened?;
+    info!("Terminal opened in container {}: {}", id, shell);
+    Ok(ws.on_upgrade(move |socket| bridge(socket, session, id)))
+}
+
+async fn open_session(env: &Env, id: &str, shell: &str) -> Result<TerminalSession, ApiError> {
+    let container = env
+        .runtime
+        .get_container(id)
+        .await
+        .map_err(|_| ApiError::not_found("CONTAINER", format!("Container '{}' not found", id)))?;
+
+    if !matches!(container.status, ContainerStatus::Running) {
+        return Err(ApiError::from_status(
+            StatusCode::CONFLICT,
+            format!("Container '{}' is not running; start it before opening a terminal", id),
+        ));
+    }
+
+    let request = ExecRequest {
+        cmd: vec![shell.to_string()],
+        interactive: true,
+        working_dir: None,
+        env: HashMap::new(),
+    };
+    env.runtime.exec_terminal(id, &request).await.map_err(|e| {
+        error!("Failed to open terminal in container {}: {}", id, e);
+        ApiError::from_status(StatusCode::BAD_GATEWAY, format!("Failed to open terminal: {}", e))
+    })
+}
+
+/// Pass input from the socket to the session and output back, until either side ends
+/// or the client goes quiet for `IDLE_TIMEOUT`
+async fn bridge(mut socket: WebSocket, session: TerminalSession, id: String) {
+    let TerminalSession { input, mut output } = session;
+    let idle = tokio::time::sleep(IDLE_TIMEOUT);
+    tokio::pin!(idle);
+
+    let reason = loop {
+        tokio::select! {
+            message = socket.recv() => {
+                let command = match message {
+                    Some(Ok(Message::Binary(data))) => TerminalInput::Data(Bytes::from(data)),
+                    Some(Ok(Message::Text(text))) => match serde_json::from_str::<TerminalControl>(&text) {
+                        Ok(TerminalControl::Resize { cols, rows }) => TerminalInput::Resize { cols, rows },
+                        Err(e) => {
+                            debug!("Ignoring terminal control message for {}: {}", id, e);
+                            continue;
+                        }
+                    },
+                    // Pings are answered by axum
+                    Some(Ok(Message::Ping(_) | Message::Pong(_))) => continue,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break None,
+                };
+
+                idle.as_mut().reset(tokio::time::Instant::now() + IDLE_TIMEOUT);
+                if input.send(command).await.is_err() {
+                    break Some("Session ended");
+                }
+            }
+            chunk = output.next() => match chunk {
+                Some(Ok(data)) => {
+                    if socket.send(Message::Binary(data.to_vec())).await.is_err() {
+                        break None;
+                    }
+                }
+                Some(Err(e)) => {
+                    warn!("Terminal output from container {} failed: {}", id, e);
+                    break Some("Session failed");
+                }
+                None => break Some("Session ended"),
+            },
+            _ = &mut idle => break Some("Idle timeout"),
+        }
+    };
+
+    if let Some(reason) = reason {
+        let _ = socket
+            .send(Message::Close(Some(CloseFrame {
+                code: close_code::NORMAL,
+                reason: reason.into(),
+            })))
+            .await;
+    }
+
+    // Dropping `input` closes the exec's stdin, which ends the session in the container
+    info!("Terminal closed in container {}: {}", id, reason.unwrap_or("client disconnected"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::agent;
+    use gpanel_core::AuditQuery;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Status code of a WebSocket upgrade request for `path`
+    async fn upgrade(addr: std::net::SocketAddr, path: &str) -> u16 {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: Upgrade\r\nUpgrade: websocket\r\n\
+             Sec-WebSocket-Version: 13\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+            path, addr
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut response = vec![0; 1024];
+        let read = stream.read(&mut response).await.unwrap();
+        let status_line = String::from_utf8_lossy(&response[..read]).lines().next().unwrap().to_string();
+        status_line.split(' ').nth(1).unwrap().parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn terminal_sessions_are_audited() {
+        let agent = agent().await;
+        let addr = agent.serve().await;
+
+        assert_eq!(upgrade(addr, "/api/v1/containers/mock_web_server_001/terminal").await, 101);
+        assert_eq!(upgrade(addr, "/api/v1/containers/mock_database_003/terminal").await, 409);
+
+        let query = AuditQuery {
+            action: Some("containers.terminal".to_string()),
+            ..Default::default()
+        };
+        let events = agent.state.audit.query(&query).await.events;
+        assert_eq!(events.len(), 2);
+
+        // Newest first
+        assert_eq!(events[0].resource_id.as_deref(), Some("mock_database_003"));
+        assert_eq!(events[0].outcome, AuditOutcome::Failure);
+        assert!(events[0].detail.as_deref().unwrap().contains("not running"));
+        assert_eq!(events[1].resource_id.as_deref(), Some("mock_web_server_001"));
+        assert_eq!(events[1].outcome, AuditOutcome::Success);
+        assert_eq!(events[1].user, "anonymous");
+        assert_eq!(events[1].detail.as_deref(), Some("Opened /bin/sh"));
+    }
+}
@@ -0,0 +1,173 @@
+//! An agent over the mock runtime, for handler tests
+
+use axum::{
+    body::{Body, Bytes},
+    http::{header, Method, Request, StatusCode},
+    Router,
+};
+use gpanel_core::GhostPanelConfig;
+use serde_json::Value;
+use tempfile::TempDir;
+use tower::Service;
+
+use crate::AppState;
+
+pub struct TestAgent {
+    pub state: AppState,
+    pub app: Router,
+    auth_enabled: bool,
+    mock: bool,
+    _dir: TempDir,
+}
+
+/// Agent with authentication off, so every caller is an admin
+pub async fn agent() -> TestAgent {
+    agent_with(|_| {}, false).await
+}
+
+/// Agent with a config changed by `configure`. It has no registries unless `configure`
+/// adds some, and keeps its data in a temporary directory.
+pub async fn agent_with(configure: impl FnOnce(&mut GhostPanelConfig), auth_enabled: bool) -> TestAgent {
+    let (config, dir) = test_config(configure);
+    start(config, dir, auth_enabled, true).await
+}
+
+/// Agent whose default environment is the Bolt at `bolt_api_url` rather than the mock, e.g.
+/// a stub standing in for a failing host. The stub must answer `/ping`.
+pub async fn agent_on_bolt(bolt_api_url: &str) -> TestAgent {
+    let (config, dir) = test_config(|config| config.bolt_api_url = bolt_api_url.to_string());
+    start(config, dir, false, false).await
+}
+
+fn test_config(configure: impl FnOnce(&mut GhostPanelConfig)) -> (GhostPanelConfig, TempDir) {
+    let dir = tempfile::tempdir().expect("temporary directory");
+    let mut config = GhostPanelConfig {
+        data_dir: dir.path().display().to_string(),
+        registries: Vec::new(),
+        ..Default::default()
+    };
+    config.auth.session_secret = Some("test-session-secret".to_string());
+    configure(&mut config);
+    (config, dir)
+}
+
+async fn start(config: GhostPanelConfig, dir: TempDir, auth_enabled: bool, mock: bool) -> TestAgent {
+    let state = crate::build_state(
+        &config,
+        config.clone(),
+        dir.path().join("gpanel.toml"),
+        mock,
+        false,
+        auth_enabled,
+    )
+    .await;
+    let app = crate::app(state.clone(), auth_enabled);
+    TestAgent {
+        state,
+        app,
+        auth_enabled,
+        mock,
+        _dir: dir,
+    }
+}
+
+/// Serve `router` on a local port, e.g. as a stand-in registry, and return its URL
+pub async fn serve_stub(router: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("local port");
+    let url = format!("http://{}", listener.local_addr().expect("bound address"));
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    url
+}
+
+/// Serve `router` over TLS with a self-signed certificate for `localhost`, and return its
+/// URL and the certificate's PEM
+pub async fn serve_tls_stub(router: Router) -> (String, String) {
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).expect("certificate");
+    let pem = cert.serialize_pem().expect("certificate PEM");
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    let config = axum_server::tls_rustls::RustlsConfig::from_pem(
+        pem.clone().into_bytes(),
+        cert.serialize_private_key_pem().into_bytes(),
+    )
+    .await
+    .expect("TLS config");
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("local port");
+    let port = listener.local_addr().expect("bound address").port();
+    tokio::spawn(async move { axum_server::from_tcp_rustls(listener, config).serve(router.into_make_service()).await });
+    (format!("https://localhost:{}", port), pem)
+}
+
+impl TestAgent {
+    /// Directory the agent keeps its data and config file in
+    pub fn data_dir(&self) -> &std::path::Path {
+        self._dir.path()
+    }
+
+    /// A new agent on the same data directory, as after a restart. Like the real agent, it
+    /// reads the config file when one was written, e.g. by a restore.
+    pub async fn restart(self) -> TestAgent {
+        let TestAgent {
+            state,
+            auth_enabled,
+            mock,
+            _dir,
+            ..
+        } = self;
+        let config = GhostPanelConfig::from_file(&_dir.path().join("gpanel.toml")).unwrap_or(state.config);
+        start(config, _dir, auth_enabled, mock).await
+    }
+
+    /// Send a request and return the status and the JSON body, `Null` when there is none
+    pub async fn request(&self, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+        self.request_as(None, method, uri, body).await
+    }
+
+    /// Like `request`, with `Authorization: Bearer <token>` when a token is given
+    pub async fn request_as(
+        &self,
+        token: Option<&str>,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let request = match body {
+            Some(body) => builder
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => builder.body(Body::empty()),
+        }
+        .expect("valid request");
+
+        let (status, bytes) = self.send(request).await;
+        (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+    }
+
+    /// Serve the agent on a local port, for clients that need a real connection such as
+    /// WebSocket upgrades
+    pub async fn serve(&self) -> std::net::SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("local port");
+        let addr = listener.local_addr().expect("bound address");
+        let app = self.app.clone();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        addr
+    }
+
+    /// Send a request built by the caller and return the status and the raw body
+    pub async fn send(&self, request: Request<Body>) -> (StatusCode, Bytes) {
+        let mut app = self.app.clone();
+        std::future::poll_fn(|cx| Service::<Request<Body>>::poll_ready(&mut app, cx))
+            .await
+            .expect("router is always ready");
+        let response = app.call(request).await.expect("infallible router");
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("readable body");
+        (status, bytes)
+    }
+}
@@ -0,0 +1,163 @@
+use anyhow::{Context, Result};
+use axum::{
+    Router,
+    http::{HeaderMap, StatusCode, Uri, header},
+    response::{IntoResponse, Redirect, Response},
+};
+use axum_server::tls_rustls::RustlsConfig;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+/// How often the certificate and key are checked for changes
+const RELOAD_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Load a PEM certificate chain and its PKCS#8, PKCS#1 (RSA) or SEC1 (EC) private key,
+/// failing if either file is unreadable or the key does not belong to the certificate
+pub async fn load(cert_path: &str, key_path: &str) -> Result<RustlsConfig> {
+    // Several crypto backends may be linked in; the agent uses ring. Only fails if a
+    // provider was already installed, which is fine.
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    RustlsConfig::from_pem_file(cert_path, key_path)
+        .await
+        .with_context(|| format!("Failed to load TLS certificate {} with key {}", cert_path, key_path))
+}
+
+/// Latest modification time of the certificate and key, or `None` if either is missing
+async fn modified(cert_path: &PathBuf, key_path: &PathBuf) -> Option<SystemTime> {
+    let cert = tokio::fs::metadata(cert_path).await.ok()?.modified().ok()?;
+    let key = tokio::fs::metadata(key_path).await.ok()?.modified().ok()?;
+    Some(cert.max(key))
+}
+
+/// Background task reloading the certificate when its files change. New connections get
+/// the new certificate; open ones keep the one they were established with. A renewal that
+/// fails to load is logged and the previous certificate stays in use.
+pub async fn watch_certificate(config: RustlsConfig, cert_path: String, key_path: String) {
+    let cert = PathBuf::from(&cert_path);
+    let key = PathBuf::from(&key_path);
+    let mut last_modified = modified(&cert, &key).await;
+
+    let mut ticker = tokio::time::interval(RELOAD_POLL_INTERVAL);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        let current = modified(&cert, &key).await;
+        if current.is_none() || current == last_modified {
+            continue;
+        }
+        // Wait for both files to be written before reading them
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        last_modified = modified(&cert, &key).await;
+
+        match config.reload_from_pem_file(&cert, &key).await {
+            Ok(()) => info!("Reloaded TLS certificate from {}", cert_path),
+            Err(e) => warn!(
+                "Failed to reload TLS certificate {} with key {}, keeping the previous one: {}",
+                cert_path, key_path, e
+            ),
+        }
+    }
+}
+
+/// Plain HTTP listener that sends every request to the same path on the TLS port
+pub fn redirect_routes(tls_port: u16) -> Router {
+    Router::new().fallback(move |headers: HeaderMap, uri: Uri| async move {
+        redirect_to_https(&headers, &uri, tls_port)
+    })
+}
+
+fn redirect_to_https(headers: &HeaderMap, uri: &Uri, tls_port: u16) -> Response {
+    let Some(host) = headers.get(header::HOST).and_then(|host| host.to_str().ok()) else {
+        return (StatusCode::BAD_REQUEST, "Missing Host header").into_response();
+    };
+    // Drop the plaintext port, keeping bracketed IPv6 addresses intact
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) if !port.contains(']') => name,
+        _ => host,
+    };
+    let path = uri.path_and_query().map(|path| path.as_str()).unwrap_or("/");
+    let location = if tls_port == 443 {
+        format!("https://{}{}", host, path)
+    } else {
+        format!("https://{}:{}{}", host, tls_port, path)
+    };
+    Redirect::permanent(&location).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::agent;
+
+    /// Self-signed certificate for `localhost` written to `dir`: its path, its key's path and its PEM
+    fn self_signed(dir: &std::path::Path, name: &str) -> (String, String, String) {
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let pem = cert.serialize_pem().unwrap();
+        let cert_path = dir.join(format!("{}.crt", name));
+        let key_path = dir.join(format!("{}.key", name));
+        std::fs::write(&cert_path, &pem).unwrap();
+        std::fs::write(&key_path, cert.serialize_private_key_pem()).unwrap();
+        (cert_path.display().to_string(), key_path.display().to_string(), pem)
+    }
+
+    #[tokio::test]
+    async fn serves_https_with_a_self_signed_certificate() {
+        let dir = tempfile::tempdir().unwrap();
+        let (cert_path, key_path, pem) = self_signed(dir.path(), "agent");
+        let config = load(&cert_path, &key_path).await.unwrap();
+
+        let agent = agent().await;
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = agent.app.clone();
+        tokio::spawn(async move { axum_server::from_tcp_rustls(listener, config).serve(app.into_make_service()).await });
+
+        let url = format!("https://localhost:{}/api/v1/health", addr.port());
+        let trusting = reqwest::Client::builder()
+            .add_root_certificate(reqwest::Certificate::from_pem(pem.as_bytes()).unwrap())
+            .resolve("localhost", addr)
+            .build()
+            .unwrap();
+        let response = trusting.get(&url).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+        let untrusting = reqwest::Client::builder().resolve("localhost", addr).build().unwrap();
+        assert!(untrusting.get(&url).send().await.is_err(), "self-signed certificate was trusted");
+
+        let plain = reqwest::get(format!("http://{}/api/v1/health", addr)).await;
+        assert!(plain.is_err(), "plain HTTP was answered on the TLS port");
+    }
+
+    #[tokio::test]
+    async fn key_must_match_the_certificate() {
+        let dir = tempfile::tempdir().unwrap();
+        let (cert_path, _, _) = self_signed(dir.path(), "one");
+        let (_, other_key, _) = self_signed(dir.path(), "two");
+
+        assert!(load(&cert_path, &other_key).await.is_err());
+        assert!(load(&cert_path, &dir.path().join("missing.key").display().to_string()).await.is_err());
+    }
+
+    #[test]
+    fn plain_http_is_redirected_to_the_tls_port() {
+        let uri: Uri = "/api/v1/containers?all=true".parse().unwrap();
+        let mut headers = HeaderMap::new();
+
+        headers.insert(header::HOST, "panel.local:8080".parse().unwrap());
+        let response = redirect_to_https(&headers, &uri, 8443);
+        assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            response.headers()[header::LOCATION],
+            "https://panel.local:8443/api/v1/containers?all=true"
+        );
+
+        headers.insert(header::HOST, "[::1]:8080".parse().unwrap());
+        let response = redirect_to_https(&headers, &uri, 443);
+        assert_eq!(response.headers()[header::LOCATION], "https://[::1]/api/v1/containers?all=true");
+
+        let response = redirect_to_https(&HeaderMap::new(), &uri, 443);
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,146 @@
+use aes_gcm::aead::{rand_core::RngCore, OsRng};
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use chrono::{DateTime, Utc};
+use gpanel_core::SessionUser;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use crate::store::Store;
+
+const USER_STORE: &str = "users";
+
+/// Username of the account made on first run
+const BOOTSTRAP_USERNAME: &str = "admin";
+
+/// Unambiguous characters for generated passwords; 32 of them, so each byte maps without bias
+const PASSWORD_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const GENERATED_PASSWORD_LEN: usize = 20;
+
+/// Account that signs in with a username and password
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalUser {
+    pub id: String,
+    pub username: String,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// Argon2id hash in PHC string format
+    pub password_hash: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl LocalUser {
+    fn session_user(&self) -> SessionUser {
+        SessionUser {
+            id: self.id.clone(),
+            username: self.username.clone(),
+            email: self.email.clone(),
+            roles: self.roles.clone(),
+        }
+    }
+}
+
+/// Local accounts, kept in `users.json` under the data directory
+pub struct LocalUsers {
+    users: RwLock<Vec<LocalUser>>,
+    store: Store,
+    /// Checked against when the username is unknown, so a miss takes as long as a wrong password
+    dummy_hash: String,
+}
+
+impl LocalUsers {
+    pub async fn load(store: Store) -> Self {
+        let users: Vec<LocalUser> = store.load(USER_STORE).await;
+        let dummy_hash = hash_password(&generate_password()).unwrap_or_default();
+        Self {
+            users: RwLock::new(users),
+            store,
+            dummy_hash,
+        }
+    }
+
+    /// On first run, create an admin account and return its generated password.
+    /// Only the hash is kept, so the password cannot be shown again.
+    pub async fn bootstrap(&self) -> anyhow::Result<Option<String>> {
+        let mut users = self.users.write().await;
+        if !users.is_empty() {
+            return Ok(None);
+        }
+
+        let password = generate_password();
+        users.push(LocalUser {
+            id: uuid::Uuid::new_v4().to_string(),
+            username: BOOTSTRAP_USERNAME.to_string(),
+            email: String::new(),
+            roles: vec!["admin".to_string()],
+            password_hash: hash_password(&password)?,
+            created_at: Utc::now(),
+        });
+        self.store.save(USER_STORE, &*users).await?;
+        Ok(Some(password))
+    }
+
+    /// Every account, with its password hash, for backups
+    pub async fn all(&self) -> Vec<LocalUser> {
+        self.users.read().await.clone()
+    }
+
+    /// Replace every account, as a restore does
+    pub async fn replace(&self, accounts: Vec<LocalUser>) -> anyhow::Result<()> {
+        let mut users = self.users.write().await;
+        self.store.save(USER_STORE, &accounts).await?;
+        *users = accounts;
+        Ok(())
+    }
+
+    /// The account matching `username` and `password`. Hashing runs off the async workers.
+    pub async fn verify(&self, username: &str, password: &str) -> Option<SessionUser> {
+        let user = self
+            .users
+            .read()
+            .await
+            .iter()
+            .find(|user| user.username.eq_ignore_ascii_case(username.trim()))
+            .cloned();
+
+        let hash = user.as_ref().map_or(self.dummy_hash.clone(), |user| user.password_hash.clone());
+        let password = password.to_string();
+        let matches = tokio::task::spawn_blocking(move || verify_password(&password, &hash))
+            .await
+            .unwrap_or(false);
+
+        user.filter(|_| matches).map(|user| user.session_user())
+    }
+}
+
+fn hash_password(password: &str) -> anyhow::Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| anyhow::anyhow!("Password hashing failed: {}", e))
+}
+
+fn verify_password(password: &str, hash: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(parsed) => Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok(),
+        Err(e) => {
+            warn!("Ignoring unreadable password hash: {}", e);
+            false
+        }
+    }
+}
+
+fn generate_password() -> String {
+    let mut bytes = [0u8; GENERATED_PASSWORD_LEN];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+        .iter()
+        .map(|byte| PASSWORD_ALPHABET[(*byte as usize) % PASSWORD_ALPHABET.len()] as char)
+        .collect()
+}
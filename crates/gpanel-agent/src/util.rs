@@ -0,0 +1,16 @@
+/// Parse a short duration such as `30s`, `15m`, `24h`, `7d` or `2w`
+pub fn parse_duration(value: &str) -> Option<chrono::Duration> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount.parse().ok()?;
+
+    match unit {
+        "s" => Some(chrono::Duration::seconds(amount)),
+        "m" => Some(chrono::Duration::minutes(amount)),
+        "h" => Some(chrono::Duration::hours(amount)),
+        "d" => Some(chrono::Duration::days(amount)),
+        "w" => Some(chrono::Duration::weeks(amount)),
+        _ => None,
+    }
+}
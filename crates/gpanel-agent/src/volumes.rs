@@ -0,0 +1,499 @@
+use axum::{
+    body::Body,
+    extract::{Path, Query},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use futures::StreamExt;
+use gpanel_core::{
+    is_valid_volume_name, normalize_volume_path, ApiErrorResponse, Container, CreateVolumeRequest, OperationResult,
+    Volume, VolumeBrowseResponse, VolumeEntry, VolumeEntryType, VolumeListResponse, VolumePruneFilter, VolumePruneReport,
+    VolumePruneRequest,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::IntoParams;
+
+use crate::auth::RequireOperator;
+use crate::error::ApiError;
+use crate::environments::Env;
+
+/// Query parameters for DELETE /api/v1/volumes/:name
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RemoveVolumeQuery {
+    pub force: Option<bool>,
+}
+
+/// Query parameters for the volume browse and file endpoints
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct VolumePathQuery {
+    pub path: Option<String>,
+}
+
+/// Largest file that can be downloaded through the agent
+pub const MAX_VOLUME_DOWNLOAD_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Fill in which containers mount each volume
+pub fn cross_reference(volumes: &mut [Volume], containers: &[Container]) {
+    for volume in volumes.iter_mut() {
+        volume.containers = containers
+            .iter()
+            .filter(|c| volume.is_mounted_by(c))
+            .map(|c| c.id.clone())
+            .collect();
+    }
+}
+
+async fn list_all_containers(env: &Env) -> Result<Vec<Container>, ApiError> {
+    env.runtime.list_containers(None).await.map_err(|e| {
+        error!("Failed to list containers for volume cross-reference: {}", e);
+        ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list containers: {}", e))
+    })
+}
+
+/// Look up a single volume with its size and mounting containers
+pub async fn find_volume(env: &Env, name: &str) -> Result<Volume, ApiError> {
+    let volume = env.runtime.inspect_volume(name).await.map_err(|e| {
+        error!("Failed to inspect volume {}: {}", name, e);
+        ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to inspect volume: {}", e))
+    })?;
+
+    let Some(mut volume) = volume else {
+        return Err(ApiError::not_found("VOLUME", format!("Volume '{}' not found", name)));
+    };
+
+    let containers = list_all_containers(env).await?;
+    cross_reference(std::slice::from_mut(&mut volume), &containers);
+
+    Ok(volume)
+}
+
+/// List volumes
+#[utoipa::path(
+    get,
+    path = "/api/v1/volumes",
+    tag = "volumes",
+    responses((status = 200, description = "All volumes with the containers mounting them", body = VolumeListResponse)),
+)]
+pub async fn list_volumes(env: Env) -> Result<Json<VolumeListResponse>, ApiError> {
+    let mut volumes = env.runtime.list_volumes().await.map_err(|e| {
+        error!("Failed to list volumes: {}", e);
+        ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list volumes: {}", e))
+    })?;
+
+    let containers = list_all_containers(&env).await?;
+    cross_reference(&mut volumes, &containers);
+
+    Ok(Json(VolumeListResponse { volumes }))
+}
+
+/// Create a volume
+#[utoipa::path(
+    post,
+    path = "/api/v1/volumes",
+    tag = "volumes",
+    request_body = CreateVolumeRequest,
+    responses(
+        (status = 201, description = "Volume created", body = Volume),
+        (status = 400, description = "Invalid name", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 409, description = "Volume already exists", body = ApiErrorResponse),
+    ),
+)]
+pub async fn create_volume(
+    _: RequireOperator,
+    env: Env,
+    Json(request): Json<CreateVolumeRequest>,
+) -> Result<(StatusCode, Json<Volume>), ApiError> {
+    if !is_valid_volume_name(&request.name) {
+        return Err(ApiError::from_status(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid volume name '{}'", request.name),
+        ));
+    }
+
+    let existing = env.runtime.inspect_volume(&request.name).await.map_err(|e| {
+        error!("Failed to inspect volume {}: {}", request.name, e);
+        ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to inspect volume: {}", e))
+    })?;
+
+    if existing.is_some() {
+        return Err(ApiError::from_status(
+            StatusCode::CONFLICT,
+            format!("Volume '{}' already exists", request.name),
+        ));
+    }
+
+    match env.runtime.create_volume(request).await {
+        Ok(volume) => {
+            info!("Created volume {}", volume.name);
+            Ok((StatusCode::CREATED, Json(volume)))
+        }
+        Err(e) => {
+            error!("Failed to create volume: {}", e);
+            Err(ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create volume: {}", e)))
+        }
+    }
+}
+
+/// Get a volume with its size on disk and mounting containers
+#[utoipa::path(
+    get,
+    path = "/api/v1/volumes/{name}",
+    tag = "volumes",
+    params(("name" = String, Path, description = "Volume name")),
+    responses(
+        (status = 200, description = "The volume", body = Volume),
+        (status = 404, description = "No such volume", body = ApiErrorResponse),
+    ),
+)]
+pub async fn get_volume(
+    env: Env,
+    Path(name): Path<String>,
+) -> Result<Json<Volume>, ApiError> {
+    find_volume(&env, &name).await.map(Json)
+}
+
+/// Delete a volume, refusing while containers mount it unless forced
+#[utoipa::path(
+    delete,
+    path = "/api/v1/volumes/{name}",
+    tag = "volumes",
+    params(("name" = String, Path, description = "Volume name"), RemoveVolumeQuery),
+    responses(
+        (status = 200, description = "Volume removed", body = OperationResult),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+        (status = 404, description = "No such volume", body = ApiErrorResponse),
+        (status = 409, description = "Volume is in use", body = ApiErrorResponse),
+    ),
+)]
+pub async fn delete_volume(
+    _: RequireOperator,
+    env: Env,
+    Path(name): Path<String>,
+    Query(query): Query<RemoveVolumeQuery>,
+) -> Result<Json<OperationResult>, ApiError> {
+    let force = query.force.unwrap_or(false);
+    let volume = find_volume(&env, &name).await?;
+
+    if !volume.containers.is_empty() && !force {
+        return Err(ApiError::from_status(
+            StatusCode::CONFLICT,
+            format!(
+                "Volume '{}' is used by containers: {}",
+                name,
+                volume.containers.join(", ")
+            ),
+        ));
+    }
+
+    match env.runtime.remove_volume(&volume.name, force).await {
+        Ok(_) => {
+            info!("Removed volume {}", volume.name);
+            Ok(Json(OperationResult {
+                success: true,
+                message: format!("Volume {} removed successfully", volume.name),
+            }))
+        }
+        Err(e) => {
+            error!("Failed to remove volume {}: {}", volume.name, e);
+            Err(ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to remove volume: {}", e)))
+        }
+    }
+}
+
+/// Remove volumes not mounted by any container
+#[utoipa::path(
+    post,
+    path = "/api/v1/volumes/prune",
+    tag = "volumes",
+    request_body = VolumePruneRequest,
+    responses(
+        (status = 200, description = "Removed volumes and reclaimed space", body = VolumePruneReport),
+        (status = 403, description = "Caller is not an operator", body = ApiErrorResponse),
+    ),
+)]
+pub async fn prune_volumes(
+    _: RequireOperator,
+    env: Env,
+    Json(request): Json<VolumePruneRequest>,
+) -> Result<Json<VolumePruneReport>, ApiError> {
+    let filter = VolumePruneFilter {
+        label: request.label_filter,
+    };
+
+    match env.runtime.prune_volumes(&filter).await {
+        Ok(report) => {
+            info!(
+                "Pruned {} volumes, reclaimed {} bytes",
+                report.deleted.len(),
+                report.reclaimed_bytes
+            );
+            Ok(Json(report))
+        }
+        Err(e) => {
+            error!("Failed to prune volumes: {}", e);
+            Err(ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to prune volumes: {}", e)))
+        }
+    }
+}
+
+/// Resolve the requested path and stat it, rejecting anything outside the volume root
+async fn stat_path(env: &Env, name: &str, path: Option<&str>) -> Result<VolumeEntry, ApiError> {
+    let requested = path.unwrap_or("/");
+    let Some(path) = normalize_volume_path(requested) else {
+        return Err(ApiError::from_status(
+            StatusCode::BAD_REQUEST,
+            format!("Path '{}' is outside the volume", requested),
+        ));
+    };
+
+    let entry = env.runtime.stat_volume_path(name, &path).await.map_err(|e| {
+        error!("Failed to stat {} in volume {}: {}", path, name, e);
+        ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read volume: {}", e))
+    })?;
+
+    entry.ok_or_else(|| ApiError::from_status(StatusCode::NOT_FOUND, format!("Path '{}' not found in volume {}", path, name)))
+}
+
+/// List a directory inside a volume
+#[utoipa::path(
+    get,
+    path = "/api/v1/volumes/{name}/browse",
+    tag = "volumes",
+    params(("name" = String, Path, description = "Volume name"), VolumePathQuery),
+    responses(
+        (status = 200, description = "Entries of a directory in the volume", body = VolumeBrowseResponse),
+        (status = 400, description = "Path is not a directory or leaves the volume", body = ApiErrorResponse),
+        (status = 404, description = "No such volume or path", body = ApiErrorResponse),
+    ),
+)]
+pub async fn browse_volume(
+    env: Env,
+    Path(name): Path<String>,
+    Query(query): Query<VolumePathQuery>,
+) -> Result<Json<VolumeBrowseResponse>, ApiError> {
+    let volume = find_volume(&env, &name).await?;
+    let entry = stat_path(&env, &volume.name, query.path.as_deref()).await?;
+
+    if entry.entry_type != VolumeEntryType::Directory {
+        return Err(ApiError::from_status(
+            StatusCode::BAD_REQUEST,
+            format!("'{}' is not a directory", entry.path),
+        ));
+    }
+
+    let entries = env
+        .runtime
+        .list_volume_directory(&volume.name, &entry.path)
+        .await
+        .map_err(|e| {
+            error!("Failed to list {} in volume {}: {}", entry.path, volume.name, e);
+            ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to list directory: {}", e))
+        })?;
+
+    Ok(Json(VolumeBrowseResponse {
+        volume: volume.name,
+        path: entry.path,
+        entries,
+    }))
+}
+
+/// Download a single file from a volume, refusing files over the size cap
+#[utoipa::path(
+    get,
+    path = "/api/v1/volumes/{name}/file",
+    tag = "volumes",
+    params(("name" = String, Path, description = "Volume name"), VolumePathQuery),
+    responses(
+        (status = 200, description = "File contents", content_type = "application/octet-stream", body = Vec<u8>),
+        (status = 400, description = "Path is not a file or leaves the volume", body = ApiErrorResponse),
+        (status = 404, description = "No such volume or path", body = ApiErrorResponse),
+        (status = 413, description = "File is larger than 100 MiB", body = ApiErrorResponse),
+    ),
+)]
+pub async fn download_volume_file(
+    env: Env,
+    Path(name): Path<String>,
+    Query(query): Query<VolumePathQuery>,
+) -> Result<Response, ApiError> {
+    let volume = find_volume(&env, &name).await?;
+    let entry = stat_path(&env, &volume.name, query.path.as_deref()).await?;
+
+    if entry.entry_type != VolumeEntryType::File {
+        return Err(ApiError::from_status(
+            StatusCode::BAD_REQUEST,
+            format!("'{}' is not a regular file", entry.path),
+        ));
+    }
+
+    if entry.size > MAX_VOLUME_DOWNLOAD_BYTES {
+        return Err(ApiError::from_status(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!(
+                "'{}' is {} bytes, larger than the {} byte download limit",
+                entry.path, entry.size, MAX_VOLUME_DOWNLOAD_BYTES
+            ),
+        ));
+    }
+
+    let stream = env
+        .runtime
+        .read_volume_file(&volume.name, &entry.path)
+        .await
+        .map_err(|e| {
+            error!("Failed to read {} from volume {}: {}", entry.path, volume.name, e);
+            ApiError::from_status(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read file: {}", e))
+        })?;
+
+    // The file may grow between stat and read, so enforce the cap on the stream too
+    let mut sent = 0u64;
+    let capped = stream.map(move |chunk| {
+        let chunk = chunk?;
+        sent += chunk.len() as u64;
+        if sent > MAX_VOLUME_DOWNLOAD_BYTES {
+            return Err(std::io::Error::other("file exceeds the download limit"));
+        }
+        Ok(chunk)
+    });
+
+    info!("Downloading {} from volume {}", entry.path, volume.name);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+            (header::CONTENT_LENGTH, entry.size.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", entry.name.replace('"', "")),
+            ),
+        ],
+        Body::from_stream(capped),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::agent;
+    use axum::{
+        body::Body,
+        http::{Method, Request, StatusCode},
+    };
+
+    const VOLUME: &str = "/api/v1/volumes/minecraft_world";
+
+    #[tokio::test]
+    async fn volumes_are_browsed_and_files_downloaded() {
+        let agent = agent().await;
+
+        let (status, body) = agent.request(Method::GET, &format!("{}/browse?path=/", VOLUME), None).await;
+        assert_eq!(status, StatusCode::OK);
+        let names: Vec<&str> = body["entries"].as_array().unwrap().iter().map(|e| e["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["backups", "world", "server.properties"]);
+        let (status, body) = agent
+            .request(Method::GET, &format!("{}/browse?path=world/./region/", VOLUME), None)
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["path"], "/world/region");
+
+        let request = Request::get(format!("{}/file?path=/server.properties", VOLUME)).body(Body::empty()).unwrap();
+        let (status, bytes) = agent.send(request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(bytes.starts_with(b"motd=GhostPanel Minecraft\n"));
+    }
+
+    #[tokio::test]
+    async fn paths_outside_the_volume_are_refused() {
+        let agent = agent().await;
+
+        for path in ["..", "../../etc/passwd", "/world/../../etc/shadow", "world/..%2F..%2Fetc", "%2Fserver.properties%00"] {
+            for route in ["browse", "file"] {
+                let uri = format!("{}/{}?path={}", VOLUME, route, path);
+                let (status, body) = agent.request(Method::GET, &uri, None).await;
+                assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+                assert!(body["error"]["message"].as_str().unwrap().contains("outside the volume"), "{}", uri);
+            }
+        }
+
+        // Absolute paths are looked up inside the volume, not on the host
+        let (status, _) = agent.request(Method::GET, &format!("{}/file?path=/etc/passwd", VOLUME), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn files_over_the_download_limit_are_refused_before_reading() {
+        let agent = agent().await;
+
+        // 700 MiB in the mock volume; refused from its size, so no body is streamed
+        let uri = format!("{}/file?path=/backups/world-full.tar.gz", VOLUME);
+        let (status, body) = agent.request(Method::GET, &uri, None).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        let message = body["error"]["message"].as_str().unwrap();
+        assert!(message.contains(&super::MAX_VOLUME_DOWNLOAD_BYTES.to_string()), "{}", message);
+
+        // Under the limit, a large file streams through whole
+        let request = Request::get(format!("{}/file?path=/world/region/r.0.0.mca", VOLUME)).body(Body::empty()).unwrap();
+        let (status, bytes) = agent.send(request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(bytes.len(), 9 * 1024 * 1024);
+
+        let (status, _) = agent.request(Method::GET, &format!("{}/file?path=/world", VOLUME), None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    async fn volume_names(agent: &crate::testing::TestAgent) -> Vec<String> {
+        let (_, body) = agent.request(Method::GET, "/api/v1/volumes", None).await;
+        let mut names: Vec<String> =
+            body["volumes"].as_array().unwrap().iter().map(|v| v["name"].as_str().unwrap().to_string()).collect();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn volumes_in_use_are_only_deleted_when_forced() {
+        let agent = agent().await;
+
+        let (status, body) = agent.request(Method::DELETE, "/api/v1/volumes/postgres_data", None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"]["code"], "CONFLICT");
+        assert!(body["error"]["message"].as_str().unwrap().contains("mock_database_003"), "{}", body);
+        assert!(volume_names(&agent).await.contains(&"postgres_data".to_string()));
+
+        let (status, _) = agent.request(Method::DELETE, "/api/v1/volumes/postgres_data?force=true", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = agent.request(Method::DELETE, "/api/v1/volumes/shader_cache", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(volume_names(&agent).await, ["minecraft_world", "nfs_backups"]);
+
+        let (status, body) = agent.request(Method::DELETE, "/api/v1/volumes/shader_cache", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "VOLUME_NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn prune_reports_the_volumes_removed_and_space_reclaimed() {
+        const MB: u64 = 1024 * 1024;
+        let agent = agent().await;
+
+        let labeled = serde_json::json!({ "label_filter": "gpanel.game=minecraft" });
+        let (status, body) = agent.request(Method::POST, "/api/v1/volumes/prune", Some(labeled)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["deleted"], serde_json::json!(["minecraft_world"]));
+        assert_eq!(body["reclaimed_bytes"], 1240 * MB);
+
+        // The mounted volume stays; a volume of unknown size reclaims nothing
+        let (status, body) = agent.request(Method::POST, "/api/v1/volumes/prune", Some(serde_json::json!({}))).await;
+        assert_eq!(status, StatusCode::OK);
+        let mut deleted: Vec<&str> = body["deleted"].as_array().unwrap().iter().map(|v| v.as_str().unwrap()).collect();
+        deleted.sort();
+        assert_eq!(deleted, ["nfs_backups", "shader_cache"]);
+        assert_eq!(body["reclaimed_bytes"], 880 * MB);
+        assert_eq!(volume_names(&agent).await, ["postgres_data"]);
+
+        let (_, body) = agent.request(Method::POST, "/api/v1/volumes/prune", Some(serde_json::json!({}))).await;
+        assert_eq!((body["deleted"].as_array().unwrap().len(), body["reclaimed_bytes"].as_u64()), (0, Some(0)));
+    }
+}
@@ -0,0 +1,294 @@
+use axum::{
+    extract::Path,
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use gpanel_core::{
+    ApiErrorResponse, ContainerEvent, ContainerEventAction, WatchdogConfig, WatchdogResponse, WatchdogState,
+    WATCHDOG_LABEL,
+};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+use tracing::{error, info, warn};
+
+use crate::environments::Env;
+use crate::error::ApiError;
+use crate::store::Store;
+
+/// Store collection holding per-container watchdog state
+const WATCHDOG_STORE: &str = "watchdog";
+
+/// How often pending restarts are checked
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Restart supervisor for containers labeled `gpanel.watchdog=true`
+pub struct Watchdog {
+    config: WatchdogConfig,
+    states: RwLock<HashMap<String, WatchdogState>>,
+    store: Store,
+}
+
+impl Watchdog {
+    /// Load state saved before the last agent restart, including pending retries
+    pub async fn load(store: Store, config: WatchdogConfig) -> Self {
+        let states = store.load(WATCHDOG_STORE).await;
+        Self {
+            config,
+            states: RwLock::new(states),
+            store,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    pub async fn state(&self, container_id: &str) -> Option<WatchdogState> {
+        self.states.read().await.get(container_id).cloned()
+    }
+
+    async fn persist(&self, states: &HashMap<String, WatchdogState>) {
+        if let Err(e) = self.store.save(WATCHDOG_STORE, states).await {
+            error!("Failed to save watchdog state: {}", e);
+        }
+    }
+
+    /// Update state for an event at `now`, publishing a crash-loop event to `env` when
+    /// retries stop
+    pub async fn handle_event(&self, event: &ContainerEvent, now: DateTime<Utc>, env: &Env) {
+        if !is_supervised(&event.attributes) {
+            return;
+        }
+
+        let mut states = self.states.write().await;
+        match event.action {
+            ContainerEventAction::Died => {
+                let exit_code = event.attributes.get("exit_code").and_then(|code| code.parse().ok());
+                let state = states
+                    .entry(event.id.clone())
+                    .or_insert_with(|| WatchdogState::new(&event.id));
+
+                if state.record_crash(&self.config, exit_code, now) {
+                    publish_crash_loop(env, event, state);
+                } else if let Some(next_retry) = state.next_retry {
+                    info!("Watchdog will restart {} at {}", event.name, next_retry);
+                }
+            }
+            // A clean stop or removal is deliberate, so stop supervising
+            ContainerEventAction::Stopped | ContainerEventAction::Removed => {
+                if states.remove(&event.id).is_none() {
+                    return;
+                }
+            }
+            _ => return,
+        }
+        self.persist(&states).await;
+    }
+
+    /// Restart every container of `env` whose retry is due at `now`
+    pub async fn restart_due(&self, env: &Env, now: DateTime<Utc>) {
+        let due: Vec<String> = self
+            .states
+            .read()
+            .await
+            .values()
+            .filter(|state| state.restart_due(now))
+            .map(|state| state.container_id.clone())
+            .collect();
+        if due.is_empty() {
+            return;
+        }
+
+        let mut states = self.states.write().await;
+        for container_id in due {
+            let Some(state) = states.get_mut(&container_id) else {
+                continue;
+            };
+
+            // Count the attempt even if it fails, so a broken runtime cannot retry forever
+            state.record_restart(now);
+            match env.runtime.start_container(&container_id).await {
+                Ok(()) => info!("Watchdog restarted {} (attempt {})", container_id, state.attempts),
+                Err(e) => {
+                    warn!("Watchdog failed to restart {}: {}", container_id, e);
+                    if state.record_crash(&self.config, state.last_exit_code, now) {
+                        let event = ContainerEvent::new(&container_id, &container_id, ContainerEventAction::Died);
+                        publish_crash_loop(env, &event, state);
+                    }
+                }
+            }
+        }
+        self.persist(&states).await;
+    }
+}
+
+fn publish_crash_loop(env: &Env, event: &ContainerEvent, state: &WatchdogState) {
+    warn!(
+        "Container {} is crash looping after {} restarts, watchdog stopped retrying",
+        event.name, state.attempts
+    );
+    let mut crash_loop = ContainerEvent::new(&event.id, &event.name, ContainerEventAction::CrashLoop);
+    crash_loop.attributes = event.attributes.clone();
+    crash_loop.attributes.insert("restarts".to_string(), state.attempts.to_string());
+    env.publish(crash_loop);
+}
+
+fn is_supervised(labels: &HashMap<String, String>) -> bool {
+    labels.get(WATCHDOG_LABEL).map(|value| value == "true").unwrap_or(false)
+}
+
+/// Background task feeding an environment's container events to its watchdog and running
+/// due restarts
+pub async fn run_watchdog(env: Env, mut events: broadcast::Receiver<ContainerEvent>) {
+    let watchdog = env.watchdog.clone();
+    if !watchdog.enabled() {
+        info!("Container watchdog is disabled in {}", env.name);
+        return;
+    }
+
+    let mut ticker = tokio::time::interval(TICK_INTERVAL);
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) if event.environment == env.name => watchdog.handle_event(&event, Utc::now(), &env).await,
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Watchdog fell behind, skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = ticker.tick() => watchdog.restart_due(&env, Utc::now()).await,
+        }
+    }
+}
+
+/// Get the watchdog state of a container
+#[utoipa::path(
+    get,
+    path = "/api/v1/containers/{id}/watchdog",
+    tag = "containers",
+    params(("id" = String, Path, description = "Container ID or name")),
+    responses(
+        (status = 200, description = "Watchdog supervision and restart state", body = WatchdogResponse),
+        (status = 404, description = "No such container", body = ApiErrorResponse),
+    ),
+)]
+pub async fn get_container_watchdog(env: Env, Path(id): Path<String>) -> Result<Json<WatchdogResponse>, ApiError> {
+    let containers = env.runtime.list_containers(None).await.map_err(|e| {
+        error!("Failed to list containers: {}", e);
+        ApiError::runtime(&e, format!("Failed to list containers: {}", e))
+    })?;
+
+    let container = containers
+        .into_iter()
+        .find(|c| c.id == id)
+        .ok_or_else(|| ApiError::not_found("CONTAINER", format!("Container '{}' not found", id)))?;
+
+    Ok(Json(WatchdogResponse {
+        supervised: is_supervised(&container.labels),
+        enabled: env.watchdog.enabled(),
+        state: env.watchdog.state(&container.id).await,
+        container_id: container.id,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpanel_core::ContainerStatus;
+
+    const CONTAINER: &str = "mock_web_server_001";
+
+    fn config() -> WatchdogConfig {
+        WatchdogConfig {
+            enabled: true,
+            initial_backoff_secs: 5,
+            max_backoff_secs: 60,
+            max_retries: 2,
+            window_secs: 600,
+        }
+    }
+
+    fn died(supervised: bool) -> ContainerEvent {
+        let mut event = ContainerEvent::new(CONTAINER, "nginx-web", ContainerEventAction::Died);
+        event.attributes.insert("exit_code".to_string(), "1".to_string());
+        if supervised {
+            event.attributes.insert(WATCHDOG_LABEL.to_string(), "true".to_string());
+        }
+        event
+    }
+
+    async fn watchdog(dir: &tempfile::TempDir) -> Watchdog {
+        Watchdog::load(Store::new(dir.path()), config()).await
+    }
+
+    #[tokio::test]
+    async fn only_labeled_containers_are_supervised() {
+        let agent = crate::testing::agent().await;
+        let env = agent.state.environments.default_env();
+        let dir = tempfile::tempdir().unwrap();
+        let watchdog = watchdog(&dir).await;
+
+        watchdog.handle_event(&died(false), Utc::now(), &env).await;
+        assert!(watchdog.state(CONTAINER).await.is_none());
+
+        watchdog.handle_event(&died(true), Utc::now(), &env).await;
+        assert!(watchdog.state(CONTAINER).await.is_some());
+
+        let mut stopped = died(true);
+        stopped.action = ContainerEventAction::Stopped;
+        watchdog.handle_event(&stopped, Utc::now(), &env).await;
+        assert!(watchdog.state(CONTAINER).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn restarts_after_the_backoff_and_survives_a_reload() {
+        let agent = crate::testing::agent().await;
+        let env = agent.state.environments.default_env();
+        let runtime = env.runtime.as_ref();
+        runtime.stop_container(CONTAINER, None).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let watchdog = watchdog(&dir).await;
+        let crashed = Utc::now();
+        watchdog.handle_event(&died(true), crashed, &env).await;
+
+        watchdog.restart_due(&env, crashed + chrono::Duration::seconds(4)).await;
+        let container = runtime.get_container(CONTAINER).await.unwrap();
+        assert!(!matches!(container.status, ContainerStatus::Running));
+
+        // The pending retry is kept across an agent restart
+        let watchdog = self::watchdog(&dir).await;
+        watchdog.restart_due(&env, crashed + chrono::Duration::seconds(5)).await;
+        let container = runtime.get_container(CONTAINER).await.unwrap();
+        assert!(matches!(container.status, ContainerStatus::Running));
+
+        let state = watchdog.state(CONTAINER).await.unwrap();
+        assert_eq!(state.attempts, 1);
+        assert_eq!(state.next_retry, None);
+    }
+
+    #[tokio::test]
+    async fn crash_loops_are_published_once() {
+        let agent = crate::testing::agent().await;
+        let env = agent.state.environments.default_env();
+        let dir = tempfile::tempdir().unwrap();
+        let watchdog = watchdog(&dir).await;
+        let mut received = env.subscribe();
+
+        let now = Utc::now();
+        for _ in 0..config().max_retries {
+            watchdog.handle_event(&died(true), now, &env).await;
+            watchdog.states.write().await.get_mut(CONTAINER).unwrap().record_restart(now);
+        }
+        watchdog.handle_event(&died(true), now, &env).await;
+        watchdog.handle_event(&died(true), now, &env).await;
+
+        let event = received.try_recv().unwrap();
+        assert_eq!(event.action, ContainerEventAction::CrashLoop);
+        assert_eq!(event.attributes.get("restarts").map(String::as_str), Some("2"));
+        assert!(received.try_recv().is_err());
+        assert!(watchdog.state(CONTAINER).await.unwrap().crash_looping);
+    }
+}
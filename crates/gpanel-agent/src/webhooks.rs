@@ -0,0 +1,635 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use gpanel_core::{
+    ApiErrorResponse, ContainerEvent, ContainerEventAction, CreateWebhookRequest, FailedDelivery, OperationResult,
+    WebhookConfig, WebhookFailuresResponse, WebhookFormat, WebhookListResponse, WebhookResponse,
+};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+use tracing::{error, info, warn};
+
+use crate::auth::RequireAdmin;
+use crate::store::Store;
+use crate::error::ApiError;
+use crate::AppState;
+
+/// Store collection holding webhooks created through the API
+const WEBHOOK_STORE: &str = "webhooks";
+
+/// Header carrying the hex HMAC-SHA256 of the request body
+const SIGNATURE_HEADER: &str = "x-ghostpanel-signature";
+const EVENT_HEADER: &str = "x-ghostpanel-event";
+
+const MAX_ATTEMPTS: u32 = 4;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Failed deliveries kept per webhook
+const MAX_FAILURES: usize = 50;
+
+/// Configured webhooks and their dead-letter logs
+pub struct WebhookRegistry {
+    webhooks: RwLock<HashMap<String, WebhookConfig>>,
+    failures: RwLock<HashMap<String, VecDeque<FailedDelivery>>>,
+    store: Store,
+    client: reqwest::Client,
+    /// Wait before the first retry, doubled before each later one
+    initial_backoff: Duration,
+}
+
+impl WebhookRegistry {
+    /// Load stored webhooks; webhooks from the config file are added back if missing
+    pub async fn load(store: Store, configured: &[WebhookConfig]) -> Self {
+        let stored: Vec<WebhookConfig> = store.load(WEBHOOK_STORE).await;
+
+        let mut webhooks: HashMap<String, WebhookConfig> =
+            stored.into_iter().map(|w| (w.id.clone(), w)).collect();
+        for webhook in configured {
+            webhooks.entry(webhook.id.clone()).or_insert_with(|| webhook.clone());
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(DELIVERY_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            webhooks: RwLock::new(webhooks),
+            failures: RwLock::new(HashMap::new()),
+            store,
+            client,
+            initial_backoff: INITIAL_BACKOFF,
+        }
+    }
+
+    pub async fn list(&self) -> Vec<WebhookConfig> {
+        let mut webhooks: Vec<WebhookConfig> = self.webhooks.read().await.values().cloned().collect();
+        webhooks.sort_by(|a, b| a.name.cmp(&b.name));
+        webhooks
+    }
+
+    pub async fn get(&self, id: &str) -> Option<WebhookConfig> {
+        self.webhooks.read().await.get(id).cloned()
+    }
+
+    pub async fn add(&self, webhook: WebhookConfig) -> anyhow::Result<()> {
+        let mut webhooks = self.webhooks.write().await;
+        webhooks.insert(webhook.id.clone(), webhook);
+        self.persist(&webhooks).await
+    }
+
+    pub async fn remove(&self, id: &str) -> anyhow::Result<bool> {
+        let mut webhooks = self.webhooks.write().await;
+        if webhooks.remove(id).is_none() {
+            return Ok(false);
+        }
+        self.failures.write().await.remove(id);
+        self.persist(&webhooks).await?;
+        Ok(true)
+    }
+
+    async fn persist(&self, webhooks: &HashMap<String, WebhookConfig>) -> anyhow::Result<()> {
+        let mut stored: Vec<&WebhookConfig> = webhooks.values().collect();
+        stored.sort_by(|a, b| a.id.cmp(&b.id));
+        self.store.save(WEBHOOK_STORE, &stored).await
+    }
+
+    pub async fn failures(&self, id: &str) -> Vec<FailedDelivery> {
+        self.failures
+            .read()
+            .await
+            .get(id)
+            .map(|failures| failures.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    async fn failure_count(&self, id: &str) -> usize {
+        self.failures.read().await.get(id).map(|f| f.len()).unwrap_or(0)
+    }
+
+    async fn record_failure(&self, id: &str, failure: FailedDelivery) {
+        let mut failures = self.failures.write().await;
+        let log = failures.entry(id.to_string()).or_default();
+        if log.len() >= MAX_FAILURES {
+            log.pop_front();
+        }
+        log.push_back(failure);
+    }
+
+    /// Send a single delivery attempt
+    async fn send(&self, webhook: &WebhookConfig, event: &ContainerEvent) -> Result<(), String> {
+        let body = render_payload(webhook.format, event).map_err(|e| e.to_string())?;
+
+        let mut request = self
+            .client
+            .post(&webhook.url)
+            .header("content-type", "application/json")
+            .header(EVENT_HEADER, format!("{:?}", event.action));
+        if let Some(secret) = &webhook.secret {
+            request = request.header(SIGNATURE_HEADER, format!("sha256={}", sign(secret, &body)));
+        }
+
+        let response = request.body(body).send().await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("Target responded with {}", response.status()));
+        }
+        Ok(())
+    }
+
+    /// Deliver an event, retrying with exponential backoff before dead-lettering it
+    pub async fn deliver(&self, webhook: &WebhookConfig, event: &ContainerEvent) {
+        let mut backoff = self.initial_backoff;
+        let mut last_error = String::new();
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            match self.send(webhook, event).await {
+                Ok(()) => return,
+                Err(e) => {
+                    warn!("Webhook {} delivery attempt {} failed: {}", webhook.name, attempt, e);
+                    last_error = e;
+                }
+            }
+            if attempt < MAX_ATTEMPTS {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+        }
+
+        error!("Webhook {} gave up after {} attempts", webhook.name, MAX_ATTEMPTS);
+        self.record_failure(
+            &webhook.id,
+            FailedDelivery {
+                event: event.clone(),
+                attempts: MAX_ATTEMPTS,
+                last_error,
+                failed_at: chrono::Utc::now(),
+            },
+        )
+        .await;
+    }
+}
+
+/// Hex HMAC-SHA256 of a payload
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// One-line summary used by the chat formats
+fn describe_event(event: &ContainerEvent) -> String {
+    let action = match event.action {
+        ContainerEventAction::Created => "was created",
+        ContainerEventAction::Started => "started",
+        ContainerEventAction::Stopped => "stopped",
+        ContainerEventAction::Died => "died",
+        ContainerEventAction::Removed => "was removed",
+        ContainerEventAction::Paused => "was paused",
+        ContainerEventAction::CrashLoop => "is crash looping, automatic restarts stopped",
+    };
+
+    let mut message = format!("Container **{}** {}", event.name, action);
+    if let Some(code) = event.attributes.get("exit_code") {
+        message.push_str(&format!(" (exit code {})", code));
+    }
+    if let Some(image) = event.attributes.get("image") {
+        message.push_str(&format!(" - {}", image));
+    }
+    message
+}
+
+/// Render the request body for a webhook's format
+pub fn render_payload(format: WebhookFormat, event: &ContainerEvent) -> serde_json::Result<Vec<u8>> {
+    match format {
+        WebhookFormat::Json => serde_json::to_vec(event),
+        WebhookFormat::Discord => serde_json::to_vec(&serde_json::json!({
+            "username": "GhostPanel",
+            "content": describe_event(event),
+        })),
+        // Slack uses single asterisks for bold
+        WebhookFormat::Slack => serde_json::to_vec(&serde_json::json!({
+            "text": describe_event(event).replace("**", "*"),
+        })),
+    }
+}
+
+/// Deliver events from the bus to every matching webhook
+pub async fn run_dispatcher(registry: Arc<WebhookRegistry>, mut events: broadcast::Receiver<ContainerEvent>) {
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                warn!("Webhook dispatcher fell behind, skipped {} events", skipped);
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        for webhook in registry.list().await.into_iter().filter(|w| w.matches(&event)) {
+            let registry = registry.clone();
+            let event = event.clone();
+            tokio::spawn(async move { registry.deliver(&webhook, &event).await });
+        }
+    }
+}
+
+async fn webhook_response(registry: &WebhookRegistry, webhook: WebhookConfig) -> WebhookResponse {
+    WebhookResponse {
+        failure_count: registry.failure_count(&webhook.id).await,
+        id: webhook.id,
+        name: webhook.name,
+        url: webhook.url,
+        has_secret: webhook.secret.is_some(),
+        format: webhook.format,
+        events: webhook.events,
+        containers: webhook.containers,
+        labels: webhook.labels,
+        enabled: webhook.enabled,
+    }
+}
+
+async fn find_webhook(state: &AppState, id: &str) -> Result<WebhookConfig, ApiError> {
+    state
+        .webhooks
+        .get(id)
+        .await
+        .ok_or_else(|| ApiError::not_found("WEBHOOK", format!("Webhook '{}' not found", id)))
+}
+
+/// List webhooks
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks",
+    tag = "webhooks",
+    responses((status = 200, description = "Webhooks, without secrets", body = WebhookListResponse)),
+)]
+pub async fn list_webhooks(State(state): State<AppState>) -> Json<WebhookListResponse> {
+    let mut webhooks = Vec::new();
+    for webhook in state.webhooks.list().await {
+        webhooks.push(webhook_response(&state.webhooks, webhook).await);
+    }
+    Json(WebhookListResponse { webhooks })
+}
+
+/// Create a webhook
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks",
+    tag = "webhooks",
+    request_body = CreateWebhookRequest,
+    responses(
+        (status = 201, description = "Webhook created", body = WebhookResponse),
+        (status = 400, description = "Invalid name or URL", body = ApiErrorResponse),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+    ),
+)]
+pub async fn create_webhook(
+    _: RequireAdmin,
+    State(state): State<AppState>,
+    Json(request): Json<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<WebhookResponse>), ApiError> {
+    if request.name.trim().is_empty() {
+        return Err(ApiError::from_status(StatusCode::BAD_REQUEST, "Webhook name is required"));
+    }
+
+    let valid_url = reqwest::Url::parse(&request.url)
+        .map(|url| matches!(url.scheme(), "http" | "https"))
+        .unwrap_or(false);
+    if !valid_url {
+        return Err(ApiError::from_status(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid webhook URL '{}', expected http or https", request.url),
+        ));
+    }
+
+    let webhook = WebhookConfig {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: request.name,
+        url: request.url,
+        secret: request.secret.filter(|s| !s.is_empty()),
+        format: request.format,
+        events: request.events,
+        containers: request.containers,
+        labels: request.labels,
+        enabled: request.enabled.unwrap_or(true),
+    };
+
+    if let Err(e) = state.webhooks.add(webhook.clone()).await {
+        error!("Failed to save webhook {}: {}", webhook.name, e);
+        return Err(ApiError::from_status(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save webhook: {}", e),
+        ));
+    }
+
+    info!("Created webhook {} -> {}", webhook.name, webhook.url);
+    Ok((StatusCode::CREATED, Json(webhook_response(&state.webhooks, webhook).await)))
+}
+
+/// Delete a webhook
+#[utoipa::path(
+    delete,
+    path = "/api/v1/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = String, Path, description = "Webhook ID")),
+    responses(
+        (status = 200, description = "Webhook removed", body = OperationResult),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+        (status = 404, description = "No such webhook", body = ApiErrorResponse),
+    ),
+)]
+pub async fn delete_webhook(
+    _: RequireAdmin,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<OperationResult>, ApiError> {
+    match state.webhooks.remove(&id).await {
+        Ok(true) => {
+            info!("Deleted webhook {}", id);
+            Ok(Json(OperationResult {
+                success: true,
+                message: format!("Webhook {} deleted", id),
+            }))
+        }
+        Ok(false) => Err(ApiError::not_found("WEBHOOK", format!("Webhook '{}' not found", id))),
+        Err(e) => {
+            error!("Failed to delete webhook {}: {}", id, e);
+            Err(ApiError::from_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to delete webhook: {}", e),
+            ))
+        }
+    }
+}
+
+/// Send a test event to a webhook once, without retries
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks/{id}/test",
+    tag = "webhooks",
+    params(("id" = String, Path, description = "Webhook ID")),
+    responses(
+        (status = 200, description = "Test event delivered", body = OperationResult),
+        (status = 403, description = "Caller is not an admin", body = ApiErrorResponse),
+        (status = 404, description = "No such webhook", body = ApiErrorResponse),
+        (status = 502, description = "Delivery failed", body = ApiErrorResponse),
+    ),
+)]
+pub async fn test_webhook(
+    _: RequireAdmin,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<OperationResult>, ApiError> {
+    let webhook = find_webhook(&state, &id).await?;
+
+    let mut event = ContainerEvent::new("ghostpanel-test", "ghostpanel-test", ContainerEventAction::Started);
+    event.attributes.insert("image".to_string(), "ghostpanel/test:latest".to_string());
+
+    match state.webhooks.send(&webhook, &event).await {
+        Ok(()) => Ok(Json(OperationResult {
+            success: true,
+            message: format!("Test event delivered to {}", webhook.url),
+        })),
+        Err(e) => Err(ApiError::from_status(
+            StatusCode::BAD_GATEWAY,
+            format!("Test delivery to {} failed: {}", webhook.url, e),
+        )),
+    }
+}
+
+/// List deliveries that failed after all retries
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks/{id}/failures",
+    tag = "webhooks",
+    params(("id" = String, Path, description = "Webhook ID")),
+    responses(
+        (status = 200, description = "Deliveries that failed after every retry, newest last", body = WebhookFailuresResponse),
+        (status = 404, description = "No such webhook", body = ApiErrorResponse),
+    ),
+)]
+pub async fn list_webhook_failures(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<WebhookFailuresResponse>, ApiError> {
+    find_webhook(&state, &id).await?;
+    Ok(Json(WebhookFailuresResponse {
+        failures: state.webhooks.failures(&id).await,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Bytes, extract::Request, http::HeaderMap, routing::post, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::sync::mpsc;
+
+    /// A request the receiver got: its path, headers and body
+    type Received = (String, HeaderMap, Bytes);
+
+    /// Receiver answering 500 to the first `failures` requests and 200 after, returning
+    /// its URL and the requests it got
+    async fn receiver(failures: usize) -> (String, mpsc::UnboundedReceiver<Received>) {
+        let (send, receive) = mpsc::unbounded_channel();
+        let seen = Arc::new(AtomicUsize::new(0));
+        let router = Router::new().route(
+            "/*path",
+            post(move |request: Request| async move {
+                let (parts, body) = request.into_parts();
+                let body = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+                let _ = send.send((parts.uri.path().to_string(), parts.headers, body));
+                if seen.fetch_add(1, Ordering::SeqCst) < failures {
+                    StatusCode::INTERNAL_SERVER_ERROR
+                } else {
+                    StatusCode::OK
+                }
+            }),
+        );
+        (crate::testing::serve_stub(router).await, receive)
+    }
+
+    async fn registry(dir: &tempfile::TempDir) -> WebhookRegistry {
+        WebhookRegistry {
+            initial_backoff: Duration::from_millis(10),
+            ..WebhookRegistry::load(Store::new(dir.path()), &[]).await
+        }
+    }
+
+    fn webhook(id: &str, url: &str) -> WebhookConfig {
+        WebhookConfig {
+            id: id.to_string(),
+            name: id.to_string(),
+            url: url.to_string(),
+            secret: None,
+            format: WebhookFormat::Json,
+            events: Vec::new(),
+            containers: Vec::new(),
+            labels: HashMap::new(),
+            enabled: true,
+        }
+    }
+
+    fn event(name: &str, action: ContainerEventAction, attributes: &[(&str, &str)]) -> ContainerEvent {
+        let mut event = ContainerEvent::new(&format!("{}-id", name), name, action);
+        event.attributes = attributes.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        event
+    }
+
+    #[test]
+    fn signatures_are_hex_hmac_sha256() {
+        assert_eq!(
+            sign("key", b"The quick brown fox jumps over the lazy dog"),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+
+    #[tokio::test]
+    async fn deliveries_are_signed_with_the_secret() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = registry(&dir).await;
+        let (url, mut received) = receiver(0).await;
+        let signed = WebhookConfig {
+            secret: Some("s3cret".to_string()),
+            ..webhook("signed", &format!("{}/signed", url))
+        };
+
+        registry.deliver(&signed, &event("web", ContainerEventAction::Died, &[])).await;
+        let (_, headers, body) = received.recv().await.unwrap();
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"s3cret").unwrap();
+        mac.update(&body);
+        let signature = headers[SIGNATURE_HEADER].to_str().unwrap().strip_prefix("sha256=").unwrap();
+        let signature: Vec<u8> = (0..signature.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&signature[i..i + 2], 16).unwrap())
+            .collect();
+        mac.verify_slice(&signature).expect("signature matches the body");
+        assert_eq!(headers[EVENT_HEADER], "Died");
+        let delivered: ContainerEvent = serde_json::from_slice(&body).unwrap();
+        assert_eq!(delivered.name, "web");
+
+        let unsigned = webhook("unsigned", &format!("{}/unsigned", url));
+        registry.deliver(&unsigned, &event("web", ContainerEventAction::Died, &[])).await;
+        let (_, headers, _) = received.recv().await.unwrap();
+        assert!(!headers.contains_key(SIGNATURE_HEADER));
+    }
+
+    #[tokio::test]
+    async fn the_dispatcher_filters_by_event_container_and_label() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = Arc::new(registry(&dir).await);
+        let (url, mut received) = receiver(0).await;
+        let hooks = [
+            WebhookConfig {
+                events: vec![ContainerEventAction::Died, ContainerEventAction::CrashLoop],
+                ..webhook("crashes", &format!("{}/crashes", url))
+            },
+            WebhookConfig {
+                labels: HashMap::from([("tier".to_string(), "web".to_string())]),
+                ..webhook("web-tier", &format!("{}/web-tier", url))
+            },
+            WebhookConfig {
+                containers: vec!["db-id".to_string()],
+                ..webhook("db", &format!("{}/db", url))
+            },
+            WebhookConfig {
+                enabled: false,
+                ..webhook("disabled", &format!("{}/disabled", url))
+            },
+        ];
+        for hook in hooks {
+            registry.add(hook).await.unwrap();
+        }
+
+        let (events, listener) = broadcast::channel(16);
+        let dispatcher = tokio::spawn(run_dispatcher(registry.clone(), listener));
+        for event in [
+            event("web", ContainerEventAction::Started, &[("tier", "web")]),
+            event("web", ContainerEventAction::Died, &[("tier", "web"), ("exit_code", "1")]),
+            event("db", ContainerEventAction::Stopped, &[("tier", "db")]),
+            event("cache", ContainerEventAction::Started, &[("tier", "cache")]),
+        ] {
+            events.send(event).unwrap();
+        }
+        drop(events);
+        dispatcher.await.unwrap();
+
+        let mut deliveries = Vec::new();
+        while deliveries.len() < 4 {
+            let (path, _, body) = tokio::time::timeout(Duration::from_secs(5), received.recv()).await.unwrap().unwrap();
+            let event: ContainerEvent = serde_json::from_slice(&body).unwrap();
+            deliveries.push(format!("{} {} {:?}", path, event.name, event.action));
+        }
+        deliveries.sort();
+        assert_eq!(
+            deliveries,
+            ["/crashes web Died", "/db db Stopped", "/web-tier web Died", "/web-tier web Started"]
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(received.try_recv().is_err(), "nothing else was delivered");
+    }
+
+    #[tokio::test]
+    async fn deliveries_retry_then_dead_letter() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = registry(&dir).await;
+        let died = event("web", ContainerEventAction::Died, &[]);
+
+        // Recovers on the last attempt
+        let (url, mut received) = receiver(MAX_ATTEMPTS as usize - 1).await;
+        let flaky = webhook("flaky", &format!("{}/hook", url));
+        registry.deliver(&flaky, &died).await;
+        for _ in 0..MAX_ATTEMPTS {
+            received.recv().await.unwrap();
+        }
+        assert!(registry.failures("flaky").await.is_empty());
+
+        let (url, mut received) = receiver(usize::MAX).await;
+        let down = webhook("down", &format!("{}/hook", url));
+        registry.deliver(&down, &died).await;
+        registry.deliver(&down, &died).await;
+        for _ in 0..2 * MAX_ATTEMPTS {
+            received.recv().await.unwrap();
+        }
+        assert!(received.try_recv().is_err(), "no attempts after the last");
+        let failures = registry.failures("down").await;
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].attempts, MAX_ATTEMPTS);
+        assert!(failures[0].last_error.contains("500"), "{}", failures[0].last_error);
+        assert_eq!(failures[0].event.name, "web");
+        assert_eq!(registry.failure_count("down").await, 2);
+    }
+
+    #[test]
+    fn chat_formats_summarize_the_event() {
+        let died = event("web", ContainerEventAction::Died, &[("exit_code", "137"), ("image", "nginx:latest")]);
+
+        let discord: serde_json::Value = serde_json::from_slice(&render_payload(WebhookFormat::Discord, &died).unwrap()).unwrap();
+        assert_eq!(
+            discord,
+            serde_json::json!({
+                "username": "GhostPanel",
+                "content": "Container **web** died (exit code 137) - nginx:latest",
+            })
+        );
+        let slack: serde_json::Value = serde_json::from_slice(&render_payload(WebhookFormat::Slack, &died).unwrap()).unwrap();
+        assert_eq!(slack, serde_json::json!({ "text": "Container *web* died (exit code 137) - nginx:latest" }));
+
+        let created = event("db", ContainerEventAction::Created, &[]);
+        let slack: serde_json::Value = serde_json::from_slice(&render_payload(WebhookFormat::Slack, &created).unwrap()).unwrap();
+        assert_eq!(slack["text"], "Container *db* was created");
+        let json: ContainerEvent = serde_json::from_slice(&render_payload(WebhookFormat::Json, &created).unwrap()).unwrap();
+        assert_eq!((json.id.as_str(), json.action), ("db-id", ContainerEventAction::Created));
+    }
+}
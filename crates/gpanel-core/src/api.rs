@@ -1 +1,807 @@
-// API types for communication between components
\ No newline at end of file
+// API types for communication between components: the request and response bodies of the
+// agent's routes, shared by the agent and the web frontend
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::container::ProjectSummary;
+use crate::event::{ContainerEvent, ContainerEventAction};
+use crate::image::LocalImage;
+use crate::network::Network;
+use crate::notification::{AlertCondition, NotificationChannel, NotificationChannelKind, NotificationRule};
+use crate::schedule::Schedule;
+use crate::validation::FieldError;
+use crate::volume::{Volume, VolumeEntry};
+use crate::watchdog::WatchdogState;
+use crate::webhook::WebhookFormat;
+
+/// Body of every error response from the agent:
+/// `{ "error": { "code": "CONTAINER_NOT_FOUND", "message": "...", "details": {...} } }`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiErrorResponse {
+    pub error: ApiErrorBody,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiErrorBody {
+    /// Stable, machine-readable code, e.g. `REGISTRY_NOT_FOUND` or `VALIDATION_FAILED`
+    pub code: String,
+    /// Human-readable description, fit to show to the user
+    pub message: String,
+    /// Extra structured data, such as the invalid fields of a request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+/// `details` of a `VALIDATION_FAILED` error from container creation, listing each invalid field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ValidationErrorDetails {
+    pub errors: Vec<FieldError>,
+}
+
+/// `details` of a cron parse error from the schedule endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CronErrorDetails {
+    /// Character offset of the offending token in the expression
+    pub position: usize,
+}
+
+/// Operation result response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct OperationResult {
+    pub success: bool,
+    pub message: String,
+}
+
+/// Registry list response for API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RegistryListResponse {
+    pub registries: Vec<RegistryConfigResponse>,
+}
+
+/// Registry configuration response (without credentials)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RegistryConfigResponse {
+    pub name: String,
+    pub url: String,
+    pub username: Option<String>,
+    pub has_auth: bool,
+    pub insecure: bool,
+    pub ca_cert_path: Option<String>,
+    pub page_size: Option<u32>,
+    pub max_list_entries: Option<usize>,
+    pub search_url: Option<String>,
+    /// Result of the last health check; unset until the first one
+    pub status: Option<RegistryStatus>,
+    pub last_checked: Option<chrono::DateTime<chrono::Utc>>,
+    /// What the last check found wrong
+    pub last_error: Option<String>,
+}
+
+/// Add registry request, also used to edit one. When editing, a missing password keeps
+/// the current one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AddRegistryRequest {
+    pub name: String,
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub insecure: bool,
+    /// PEM file of CA certificates on the agent's host to trust for this registry
+    #[serde(default)]
+    pub ca_cert_path: Option<String>,
+    #[serde(default)]
+    pub page_size: Option<u32>,
+    #[serde(default)]
+    pub max_list_entries: Option<usize>,
+    #[serde(default)]
+    pub search_url: Option<String>,
+}
+
+/// Repositories of a registry, from GET /api/v1/registries/{name}/repositories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RepositoryList {
+    pub repositories: Vec<String>,
+    /// More repositories follow; pass this as `last` to get them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+}
+
+/// Tag list response for a specific repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TagList {
+    pub name: String,
+    pub tags: Vec<String>,
+}
+
+/// How a registry answered on `/v2/`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum RegistryStatus {
+    Ok,
+    /// Reachable, but it refused the configured credentials or needs some
+    AuthRequired,
+    /// No connection, no answer in time, or an error status
+    Unreachable,
+    /// The connection failed on the certificate or the TLS handshake
+    TlsError,
+}
+
+/// Result of checking a registry, from POST /api/v1/registries/{name}/test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RegistryHealth {
+    pub status: RegistryStatus,
+    /// What went wrong; unset when the status is `ok`
+    pub message: Option<String>,
+    pub checked_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// How much a registry holds, from GET /api/v1/registries/{name}/stats
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RegistryStats {
+    pub repositories: usize,
+    /// Tags of the repositories counted
+    pub tags: usize,
+    /// Layers counted once however many images share them
+    pub unique_layers: usize,
+    /// Size of those layers, compressed as the registry stores them
+    pub total_bytes: u64,
+    /// Repositories left out because their tags or manifests could not all be read in time
+    #[serde(default)]
+    pub skipped_repositories: usize,
+    pub computed_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A Drift registry's version and the extensions it offers, from `GET /drift/v1/info`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DriftInfo {
+    pub version: String,
+    /// Such as `gc` and `quota`
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum DriftGcState {
+    /// Never run since the registry started
+    Idle,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// A Drift registry's running or last garbage collection
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DriftGcStatus {
+    pub state: DriftGcState,
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Blobs no manifest referred to, deleted so far
+    #[serde(default)]
+    pub blobs_removed: u64,
+    #[serde(default)]
+    pub bytes_freed: u64,
+    /// Why the collection failed
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Storage a Drift registry uses, and how much it may
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DriftQuota {
+    pub used_bytes: u64,
+    /// Unset when storage is not limited
+    #[serde(default)]
+    pub limit_bytes: Option<u64>,
+}
+
+/// Image information with metadata for UI display
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageInfo {
+    pub repository: String,
+    pub tag: String,
+    /// Digest of the image config
+    pub digest: String,
+    /// Digest of the manifest, or of the multi-arch index, the tag points to; deleting the
+    /// tag removes this
+    #[serde(default)]
+    pub manifest_digest: Option<String>,
+    pub size: u64,
+    pub created: chrono::DateTime<chrono::Utc>,
+    pub author: Option<String>,
+    pub layers: Vec<LayerInfo>,
+    /// Platform the digest, size and layers describe, as `os/architecture[/variant]`
+    #[serde(default)]
+    pub platform: Option<String>,
+    /// Every platform the image is built for
+    #[serde(default)]
+    pub platforms: Vec<String>,
+}
+
+/// Layer information for image inspection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LayerInfo {
+    pub digest: String,
+    pub size: u64,
+    pub media_type: String,
+    pub created_by: Option<String>,
+}
+
+/// Search results for one repository, ranked against the query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageSearchGroup {
+    /// Repository name with any `library/` prefix removed
+    pub repository: String,
+    /// Match quality plus boosts; higher ranks first
+    pub score: u32,
+    pub official: bool,
+    /// From the registry's search API; catalog matches have none
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub stars: Option<u64>,
+    /// What to pull for a repository found by a search API, which lists no images
+    #[serde(default)]
+    pub latest: Option<ImageReference>,
+    /// `latest`, then the last tags by name of the repository in each registry, capped to
+    /// the requested number. They are not inspected; get an image's details from the registry's tag route.
+    pub tags: Vec<ImageReference>,
+    /// Tags across all registries, including those left out of `tags`
+    pub total_tags: usize,
+    /// Tags omitted by the cap; request a larger `max_tags` to see them
+    pub more_tags: usize,
+    /// Distinct images among `tags`, newest first; only filled when the search inspects them
+    #[serde(default)]
+    pub images: Vec<ImageSearchEntry>,
+}
+
+/// One image digest and every listed registry tag that points at it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageSearchEntry {
+    /// Manifest or index digest, or the config digest when the registry reported none
+    pub digest: String,
+    pub size: u64,
+    pub created: chrono::DateTime<chrono::Utc>,
+    pub references: Vec<ImageReference>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageReference {
+    pub registry: String,
+    pub repository: String,
+    pub tag: String,
+}
+
+/// An image pushed from an OCI layout archive, from POST /api/v1/images/import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageImportResult {
+    pub repository: String,
+    pub tag: String,
+    /// Digest of the manifest or index the tag now names, as in the archive
+    pub digest: String,
+    pub blobs_uploaded: usize,
+    /// Blobs the repository already had
+    pub blobs_existing: usize,
+}
+
+/// Blob counts from copying an image between repositories
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageCopyResult {
+    /// Blobs linked from the source repository without transferring data
+    pub blobs_mounted: usize,
+    pub blobs_uploaded: usize,
+    /// Blobs the destination repository already had
+    pub blobs_existing: usize,
+}
+
+/// Image search request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageSearchRequest {
+    pub query: String,
+    pub registry: Option<String>,
+    /// Images listed per repository group; defaults to 5
+    pub max_tags: Option<usize>,
+    /// Inspect the tags listed for each group and merge those naming the same image into
+    /// the group's `images`; slower, as every tag's manifest is read
+    #[serde(default)]
+    pub inspect: bool,
+}
+
+/// Image search response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageSearchResponse {
+    pub images: Vec<ImageSearchResult>,
+    /// Results grouped by repository, best match first
+    pub groups: Vec<ImageSearchGroup>,
+    /// Registries that failed or timed out, whose results are missing
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+/// A tag found in a registry's catalog, or a repository from a search API such as Docker
+/// Hub's, where `tag` is `latest`. Images are not inspected while searching, so `digest`,
+/// `size` and `created` are unset; get them from the registry's tag route.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageSearchResult {
+    pub registry: String,
+    pub repository: String,
+    pub tag: String,
+    pub digest: Option<String>,
+    pub size: Option<u64>,
+    pub created: Option<chrono::DateTime<chrono::Utc>>,
+    /// Set for search API results only
+    pub description: Option<String>,
+    pub stars: Option<u64>,
+    pub official: Option<bool>,
+}
+
+/// Image pull request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImagePullRequest {
+    pub registry: String,
+    pub repository: String,
+    pub tag: String,
+}
+
+/// Request to tag an image in a registry under another tag of the same repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RegistryTagRequest {
+    /// Tag or digest of the image
+    pub source: String,
+    /// New tag, which is moved if it already names another image
+    pub target: String,
+}
+
+/// A tag created in a registry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageTagResponse {
+    pub repository: String,
+    pub tag: String,
+    /// Digest of the manifest or index the tag names
+    pub digest: String,
+}
+
+/// Request to copy an image between repositories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageCopyRequest {
+    pub source_registry: String,
+    pub source_repository: String,
+    pub source_tag: String,
+    pub destination_registry: String,
+    pub destination_repository: String,
+    pub destination_tag: String,
+}
+
+/// An image deleted from a registry. Every tag pointing to the same digest went with it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageDeleteResponse {
+    pub repository: String,
+    pub tag: String,
+    /// Digest of the deleted manifest or index
+    pub digest: String,
+}
+
+/// The highest release among a repository's tags
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LatestStableTag {
+    pub repository: String,
+    pub tag: String,
+}
+
+/// Local image list response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LocalImageListResponse {
+    pub images: Vec<LocalImage>,
+    pub total_size: u64,
+}
+
+/// Request body for POST /api/v1/images/:id/tag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageTagRequest {
+    pub repository: String,
+    pub tag: String,
+    /// Move the tag even if another image already holds it
+    #[serde(default)]
+    pub force: bool,
+    /// Push the new tag to this registry after tagging
+    pub push_registry: Option<String>,
+}
+
+/// Request body for POST /api/v1/images/prune
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImagePruneRequest {
+    #[serde(default = "default_dangling_only")]
+    pub dangling_only: bool,
+    /// Only prune images older than this duration (e.g. `24h`, `7d`)
+    pub until: Option<String>,
+    /// Only prune images carrying this label (`key` or `key=value`)
+    pub label_filter: Option<String>,
+}
+
+fn default_dangling_only() -> bool {
+    true
+}
+
+/// Kill container request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct KillContainerRequest {
+    pub signal: String,
+}
+
+/// Rename container request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RenameContainerRequest {
+    pub name: String,
+}
+
+/// Watchdog status for GET /api/v1/containers/:id/watchdog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WatchdogResponse {
+    pub container_id: String,
+    /// Whether the container carries the watchdog label
+    pub supervised: bool,
+    pub enabled: bool,
+    pub state: Option<WatchdogState>,
+}
+
+/// Delivery that failed after all retries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FailedDelivery {
+    pub event: ContainerEvent,
+    pub attempts: u32,
+    pub last_error: String,
+    pub failed_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Create webhook request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateWebhookRequest {
+    pub name: String,
+    pub url: String,
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    #[serde(default)]
+    pub events: Vec<ContainerEventAction>,
+    #[serde(default)]
+    pub containers: Vec<String>,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    pub enabled: Option<bool>,
+}
+
+/// Webhook response (without the secret)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookResponse {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub has_secret: bool,
+    pub format: WebhookFormat,
+    pub events: Vec<ContainerEventAction>,
+    pub containers: Vec<String>,
+    pub labels: HashMap<String, String>,
+    pub enabled: bool,
+    pub failure_count: usize,
+}
+
+/// Webhook list response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookListResponse {
+    pub webhooks: Vec<WebhookResponse>,
+}
+
+/// Failed delivery list response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookFailuresResponse {
+    pub failures: Vec<FailedDelivery>,
+}
+
+/// What the agent did with a notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RegistryWebhookResult {
+    /// Image pushes and deletes read from the notification
+    pub notifications: usize,
+    /// Jobs recreating containers labelled `gpanel.autoupdate=true`, when auto-update is on
+    pub update_jobs: Vec<String>,
+}
+
+/// Project list response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProjectListResponse {
+    pub projects: Vec<ProjectSummary>,
+}
+
+/// Network list response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct NetworkListResponse {
+    pub networks: Vec<Network>,
+}
+
+/// Volume list response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VolumeListResponse {
+    pub volumes: Vec<Volume>,
+}
+
+/// Request body for POST /api/v1/volumes/prune
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VolumePruneRequest {
+    /// Only prune volumes carrying this label (`key` or `key=value`)
+    pub label_filter: Option<String>,
+}
+
+/// Directory listing response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VolumeBrowseResponse {
+    pub volume: String,
+    pub path: String,
+    pub entries: Vec<VolumeEntry>,
+}
+
+/// System information from Bolt, from GET /api/v1/system/info
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BoltSystemInfo {
+    pub version: String,
+    pub api_version: String,
+    pub runtime: String,
+    pub kernel_version: String,
+    pub os: String,
+    pub architecture: String,
+    pub cpus: u32,
+    pub memory_total: u64,
+    pub storage_driver: String,
+    pub containers_running: u32,
+    pub containers_paused: u32,
+    pub containers_stopped: u32,
+    pub images_count: u32,
+}
+
+/// Schedule list response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ScheduleListResponse {
+    pub schedules: Vec<Schedule>,
+}
+
+/// Create channel request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateChannelRequest {
+    pub name: String,
+    pub kind: NotificationChannelKind,
+    pub enabled: Option<bool>,
+}
+
+/// Channel list response; email passwords are never returned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ChannelListResponse {
+    pub channels: Vec<NotificationChannel>,
+}
+
+/// Create or update rule request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RuleRequest {
+    pub name: String,
+    pub condition: AlertCondition,
+    pub channels: Vec<String>,
+    pub cooldown_secs: Option<u64>,
+    pub enabled: Option<bool>,
+}
+
+/// Rule list response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RuleListResponse {
+    pub rules: Vec<NotificationRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// Single difference between a backup archive and the running agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RestoreChange {
+    pub section: String,
+    pub item: String,
+    pub kind: ChangeKind,
+}
+
+/// Restore result, for both dry runs and applied restores
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RestoreReport {
+    pub dry_run: bool,
+    pub format_version: u16,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub changes: Vec<RestoreChange>,
+    /// Pass back as `confirm` to apply this exact archive
+    pub confirmation_token: String,
+    pub warnings: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::{
+        ContainerStatus, CreateContainerRequest, PortMapping, Protocol, RestartPolicy, VolumeMount, VolumeType,
+    };
+    use serde::de::DeserializeOwned;
+    use serde_json::{json, Value};
+
+    /// Parse `wire` as the agent would receive it and check it serializes back unchanged
+    fn round_trip<T: Serialize + DeserializeOwned>(wire: Value) -> T {
+        let value: T = serde_json::from_value(wire.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&value).unwrap(), wire);
+        value
+    }
+
+    #[test]
+    fn container_requests_keep_their_wire_format() {
+        let request: CreateContainerRequest = round_trip(json!({
+            "name": "web",
+            "image": "nginx:latest",
+            "ports": [{ "container_port": 80, "host_port": 8080, "protocol": "Udp", "host_ip": null }],
+            "volumes": [{ "source": "/srv", "target": "/data", "read_only": true, "volume_type": "Bind" }],
+            "networks": [],
+            "env": {},
+            "labels": {},
+            "gaming_config": null,
+            "gpu_allocation": null,
+            "restart_policy": { "on-failure": { "max_retries": 3 } },
+            "health": null,
+        }));
+        assert_eq!(request.ports[0].protocol, Protocol::Udp);
+        assert_eq!(request.volumes[0].volume_type, VolumeType::Bind);
+        assert_eq!(request.restart_policy, RestartPolicy::OnFailure { max_retries: Some(3) });
+
+        let status: ContainerStatus = round_trip(json!({ "Exited": { "code": 137 } }));
+        assert_eq!(status.to_string(), "Exited (137)");
+        assert_eq!(Protocol::Quic.to_string(), "quic");
+        // Variant names are used as they are, unlike the lower case `Display` forms
+        assert!(serde_json::from_value::<PortMapping>(json!({
+            "container_port": 80, "host_port": null, "protocol": "tcp", "host_ip": null
+        }))
+        .is_err());
+        assert!(serde_json::from_value::<VolumeMount>(json!({
+            "source": "data", "target": "/data", "read_only": false, "volume_type": "volume"
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn registry_responses_use_snake_case_states() {
+        let registry: RegistryConfigResponse = round_trip(json!({
+            "name": "drift",
+            "url": "https://drift.local",
+            "username": null,
+            "has_auth": false,
+            "insecure": false,
+            "ca_cert_path": null,
+            "page_size": 100,
+            "max_list_entries": null,
+            "search_url": null,
+            "status": "tls_error",
+            "last_checked": "2024-05-02T10:00:00Z",
+            "last_error": "certificate has expired",
+        }));
+        assert_eq!(registry.status, Some(RegistryStatus::TlsError));
+
+        let gc: DriftGcStatus = round_trip(json!({
+            "state": "succeeded",
+            "started_at": "2024-05-02T10:00:00Z",
+            "finished_at": "2024-05-02T10:01:00Z",
+            "blobs_removed": 12,
+            "bytes_freed": 4096,
+            "error": null,
+        }));
+        assert_eq!(gc.state, DriftGcState::Succeeded);
+
+        // Only the page that has more repositories says so
+        let page: RepositoryList = round_trip(json!({ "repositories": ["library/nginx"] }));
+        assert!(page.next.is_none());
+    }
+
+    #[test]
+    fn optional_fields_take_their_defaults() {
+        let prune: ImagePruneRequest = serde_json::from_value(json!({})).unwrap();
+        assert!(prune.dangling_only);
+        let tag: ImageTagRequest = serde_json::from_value(json!({ "repository": "app", "tag": "v1" })).unwrap();
+        assert!(!tag.force);
+        let search: ImageSearchResponse = serde_json::from_value(json!({ "images": [], "groups": [] })).unwrap();
+        assert!(search.warnings.is_empty());
+        let search: ImageSearchRequest = serde_json::from_value(json!({ "query": "postgres", "registry": null })).unwrap();
+        assert!(!search.inspect);
+        let webhook: CreateWebhookRequest =
+            serde_json::from_value(json!({ "name": "ci", "url": "https://ci.local/hook" })).unwrap();
+        assert!(webhook.events.is_empty() && webhook.labels.is_empty());
+
+        let error: ApiErrorResponse = round_trip(json!({ "error": { "code": "NOT_FOUND", "message": "gone" } }));
+        assert!(error.error.details.is_none());
+    }
+
+    #[test]
+    fn error_details_parse_from_error_bodies() {
+        let error: ApiErrorResponse = round_trip(json!({
+            "error": {
+                "code": "VALIDATION_FAILED",
+                "message": "Container request has 1 invalid field(s)",
+                "details": { "errors": [{ "field": "ports[0].host_port", "message": "Host port is used twice" }] },
+            }
+        }));
+        let details: ValidationErrorDetails = serde_json::from_value(error.error.details.unwrap()).unwrap();
+        assert_eq!(details.errors[0].field, "ports[0].host_port");
+
+        let details: CronErrorDetails = round_trip(json!({ "position": 4 }));
+        assert_eq!(details.position, 4);
+
+        let report: RestoreReport = round_trip(json!({
+            "dry_run": true,
+            "format_version": 1,
+            "created_at": "2024-05-02T10:00:00Z",
+            "changes": [{ "section": "registries", "item": "drift", "kind": "Added" }],
+            "confirmation_token": "abc",
+            "warnings": [],
+        }));
+        assert!(matches!(report.changes[0].kind, ChangeKind::Added));
+    }
+}
@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Result of an audited request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Success,
+    /// Refused for a missing or invalid token, or an insufficient role
+    Denied,
+    Failure,
+}
+
+impl AuditOutcome {
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            401 | 403 => AuditOutcome::Denied,
+            400..=599 => AuditOutcome::Failure,
+            _ => AuditOutcome::Success,
+        }
+    }
+}
+
+/// One mutating request made to the agent, e.g. `containers.delete` on a container ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AuditEvent {
+    pub timestamp: DateTime<Utc>,
+    /// Username or token name, or `anonymous`
+    pub user: String,
+    pub action: String,
+    pub resource_type: String,
+    pub resource_id: Option<String>,
+    pub outcome: AuditOutcome,
+    pub status: u16,
+    /// Error message for failed and denied requests
+    pub detail: Option<String>,
+}
+
+/// Query string of GET /api/v1/audit
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams), into_params(parameter_in = Query))]
+pub struct AuditQuery {
+    pub user: Option<String>,
+    /// Exact action, or a resource type prefix such as `containers.`
+    pub action: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+impl AuditQuery {
+    pub fn matches(&self, event: &AuditEvent) -> bool {
+        let user = self.user.as_deref().is_none_or(|user| event.user.eq_ignore_ascii_case(user));
+        let action = self.action.as_deref().is_none_or(|action| {
+            if action.ends_with('.') {
+                event.action.starts_with(action)
+            } else {
+                event.action == action
+            }
+        });
+        let since = self.since.is_none_or(|since| event.timestamp >= since);
+        user && action && since
+    }
+}
+
+/// Page of audit events, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AuditPage {
+    pub events: Vec<AuditEvent>,
+    /// Events matching the query across all pages
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// Where the agent writes its audit log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AuditConfig {
+    /// JSON Lines file; defaults to `audit.jsonl` in the data directory
+    #[serde(default)]
+    pub path: Option<String>,
+    /// The file is rotated once it grows past this
+    pub max_file_bytes: u64,
+    /// Rotated files kept besides the current one
+    pub max_files: usize,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            max_file_bytes: 10 * 1024 * 1024,
+            max_files: 5,
+        }
+    }
+}
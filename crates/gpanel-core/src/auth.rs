@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+
+/// Token accepted by the agent as `Authorization: Bearer <token>`. Only the token's
+/// SHA-256 is stored, as lowercase hex; `gpanel-agent --hash-token <token>` prints it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiTokenConfig {
+    pub name: String,
+    pub sha256: String,
+}
+
+/// Agent API authentication
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AuthConfig {
+    #[serde(default)]
+    pub api_tokens: Vec<ApiTokenConfig>,
+    /// Identity providers users can sign in with
+    #[serde(default)]
+    pub oidc: Vec<OidcConfig>,
+    /// Key that signs session tokens. When unset a random key is made at startup,
+    /// so every session ends when the agent restarts.
+    #[serde(default)]
+    pub session_secret: Option<String>,
+    /// Lifetime of each session token; clients refresh before it runs out
+    #[serde(default = "default_session_ttl_secs")]
+    pub session_ttl_secs: u64,
+    /// How long after signing in a session can keep being refreshed
+    #[serde(default = "default_session_max_age_secs")]
+    pub session_max_age_secs: u64,
+}
+
+fn default_session_ttl_secs() -> u64 {
+    8 * 60 * 60
+}
+
+fn default_session_max_age_secs() -> u64 {
+    7 * 24 * 60 * 60
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            api_tokens: Vec::new(),
+            oidc: Vec::new(),
+            session_secret: None,
+            session_ttl_secs: default_session_ttl_secs(),
+            session_max_age_secs: default_session_max_age_secs(),
+        }
+    }
+}
+
+/// Identity provider kind; same shape as the web client's `OidcProvider`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum OidcProvider {
+    Azure {
+        tenant_id: String,
+    },
+    Google,
+    GitHub,
+    Generic {
+        auth_url: String,
+        token_url: String,
+        userinfo_url: String,
+    },
+}
+
+impl OidcProvider {
+    pub fn token_url(&self) -> String {
+        match self {
+            OidcProvider::Azure { tenant_id } => {
+                format!("https://login.microsoftonline.com/{}/oauth2/v2.0/token", tenant_id)
+            }
+            OidcProvider::Google => "https://oauth2.googleapis.com/token".to_string(),
+            OidcProvider::GitHub => "https://github.com/login/oauth/access_token".to_string(),
+            OidcProvider::Generic { token_url, .. } => token_url.clone(),
+        }
+    }
+
+    pub fn userinfo_url(&self) -> String {
+        match self {
+            OidcProvider::Azure { .. } => "https://graph.microsoft.com/oidc/userinfo".to_string(),
+            OidcProvider::Google => "https://openidconnect.googleapis.com/v1/userinfo".to_string(),
+            OidcProvider::GitHub => "https://api.github.com/user".to_string(),
+            OidcProvider::Generic { userinfo_url, .. } => userinfo_url.clone(),
+        }
+    }
+}
+
+/// Identity provider the agent exchanges sign-in codes with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct OidcConfig {
+    /// Sent by the web client as `provider`, e.g. `azure`
+    pub name: String,
+    pub provider: OidcProvider,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    /// Redirect URIs registered with the provider; codes sent for any other are refused
+    pub redirect_uris: Vec<String>,
+    /// Emails or usernames allowed to sign in. Empty allows every account the provider
+    /// authenticates, which for Google and GitHub means anyone.
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Roles given to users signing in through this provider
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+/// Who a request was authenticated as
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WhoAmIResponse {
+    /// Name of the token or signed-in user, or `anonymous` when the agent runs without authentication
+    pub name: String,
+    pub auth_enabled: bool,
+}
+
+/// Body of POST /api/auth/oidc/callback
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct OidcCallbackRequest {
+    pub code: String,
+    pub state: String,
+    /// Provider `name`; may be left out when only one is configured
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Redirect URI used to get the code; defaults to the provider's first
+    #[serde(default)]
+    pub redirect_uri: Option<String>,
+}
+
+/// Signed-in user, in the shape the web client keeps in its `AuthContext`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionUser {
+    pub id: String,
+    pub username: String,
+    pub email: String,
+    pub roles: Vec<String>,
+}
+
+impl SessionUser {
+    pub fn has_role(&self, role: Role) -> bool {
+        role.granted_by(&self.roles)
+    }
+}
+
+/// What a user may do. Each role includes the ones below it: viewers may only read,
+/// operators also manage containers, images, networks, volumes and schedules, and
+/// admins also manage registries, notifications, webhooks, backups and users.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Viewer,
+    Operator,
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Operator => "operator",
+            Role::Admin => "admin",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Role> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "viewer" => Some(Role::Viewer),
+            "operator" => Some(Role::Operator),
+            "admin" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+
+    /// Whether a user holding `roles` has this role or a higher one. Unknown names are ignored.
+    pub fn granted_by(self, roles: &[String]) -> bool {
+        roles.iter().filter_map(|name| Role::parse(name)).any(|held| held >= self)
+    }
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Session minted after a successful sign-in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionResponse {
+    /// Session JWT, sent back as `Authorization: Bearer <token>`
+    pub token: String,
+    pub expires_in: u64,
+    pub user: SessionUser,
+}
+
+/// Body of POST /api/v1/auth/login
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+}
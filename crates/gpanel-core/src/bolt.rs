@@ -1,20 +1,113 @@
-use anyhow::Result;
-use reqwest::Client;
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use crate::api::BoltSystemInfo;
 use crate::container::*;
+use crate::error::{Error, Result};
+use crate::image::{ContainerCommitRequest, ImagePruneFilter, ImagePruneReport, ImageSummary, LocalImage};
+use crate::proxy::ProxyConfig;
+use crate::network::{gaming_driver_options, CreateNetworkRequest, Network, NetworkConnectRequest};
+use crate::system::{BoltDiskUsage, BuildCacheUsage};
+use crate::volume::{
+    CreateVolumeRequest, Volume, VolumeEntry, VolumeEntryType, VolumePruneFilter,
+    VolumePruneReport,
+};
 
 /// Bolt API client for container operations
 #[derive(Debug, Clone)]
 pub struct BoltClient {
     client: Client,
     base_url: String,
+    transport: BoltTransport,
+    config: BoltClientConfig,
+}
+
+/// How the client reaches the Bolt API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoltTransport {
+    Tcp,
+    Unix(std::path::PathBuf),
+}
+
+impl std::fmt::Display for BoltTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoltTransport::Tcp => write!(f, "TCP"),
+            BoltTransport::Unix(path) => write!(f, "Unix socket {}", path.display()),
+        }
+    }
+}
+
+/// Timeouts and retries for requests to Bolt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BoltClientConfig {
+    pub connect_timeout_secs: u64,
+    /// Limit for requests that should answer quickly. Streams, exec, pulls, waits and
+    /// archive transfers are only bounded by the connect timeout.
+    pub request_timeout_secs: u64,
+    /// Extra attempts for reads that failed to connect, timed out or got a 502/503/504
+    pub retries: u32,
+    /// Delay before the first retry; doubles with each further attempt, plus jitter
+    pub backoff_base_ms: u64,
+}
+
+impl Default for BoltClientConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout_secs: 5,
+            request_timeout_secs: 30,
+            retries: 3,
+            backoff_base_ms: 200,
+        }
+    }
+}
+
+impl BoltClientConfig {
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.request_timeout_secs)
+    }
+
+    /// Delay before retry number `attempt` (starting at 0), with up to 50% random jitter
+    /// so agents retrying together don't hit Bolt in lockstep
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        use std::hash::{BuildHasher, Hasher};
+
+        let base = self
+            .backoff_base_ms
+            .saturating_mul(1u64.checked_shl(attempt).unwrap_or(u64::MAX));
+        let random = std::collections::hash_map::RandomState::new().build_hasher().finish();
+        let jitter = random % (base / 2).max(1);
+        Duration::from_millis(base.saturating_add(jitter))
+    }
+}
+
+fn timeout_error(error: &reqwest::Error, timeout: Duration) -> Error {
+    let target = error.url().map(|url| url.path().to_string()).unwrap_or_default();
+    Error::Bolt(format!("timeout after {}s waiting for {}", timeout.as_secs(), target))
+}
+
+/// Bolt answering a request with `status`: 404s are objects that do not exist and 409s
+/// operations their state does not allow, anything else is Bolt failing
+fn status_error(status: StatusCode, message: String) -> Error {
+    match status {
+        StatusCode::NOT_FOUND => Error::NotFound(message),
+        StatusCode::CONFLICT => Error::Conflict(message),
+        _ => Error::Bolt(message),
+    }
 }
 
 /// Bolt container API response wrapper
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct BoltResponse<T> {
     pub success: bool,
     pub data: Option<T>,
@@ -24,6 +117,7 @@ pub struct BoltResponse<T> {
 
 /// Container operation request
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ContainerOperation {
     pub action: String,
     pub container_id: String,
@@ -32,6 +126,7 @@ pub struct ContainerOperation {
 
 /// Container logs request
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ContainerLogsRequest {
     pub container_id: String,
     pub follow: bool,
@@ -40,46 +135,147 @@ pub struct ContainerLogsRequest {
     pub since: Option<chrono::DateTime<chrono::Utc>>,
 }
 
-/// Container stats for real-time monitoring
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ContainerStats {
-    pub container_id: String,
-    pub timestamp: chrono::DateTime<chrono::Utc>,
-    pub cpu_percent: f64,
-    pub memory_usage: u64,
-    pub memory_limit: u64,
-    pub network_rx: u64,
-    pub network_tx: u64,
-    pub block_read: u64,
-    pub block_write: u64,
-    pub pid_count: u32,
+/// Byte stream of a file read from a volume
+pub type VolumeFileStream = Pin<Box<dyn Stream<Item = std::io::Result<Bytes>> + Send>>;
+
+/// Lines of a followed container log, without their trailing newlines
+pub type LogLineStream = Pin<Box<dyn Stream<Item = Result<String>> + Send>>;
+
+/// Input for an interactive terminal session
+#[derive(Debug, Clone)]
+pub enum TerminalInput {
+    /// Bytes typed into the terminal
+    Data(Bytes),
+    Resize { cols: u16, rows: u16 },
 }
 
-/// System information from Bolt
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BoltSystemInfo {
-    pub version: String,
-    pub api_version: String,
-    pub runtime: String,
-    pub kernel_version: String,
-    pub os: String,
-    pub architecture: String,
-    pub cpus: u32,
-    pub memory_total: u64,
-    pub storage_driver: String,
-    pub containers_running: u32,
-    pub containers_paused: u32,
-    pub containers_stopped: u32,
-    pub images_count: u32,
+/// Interactive exec with a TTY. `output` carries the raw terminal stream; dropping the
+/// session closes the exec's stdin, which ends it.
+pub struct TerminalSession {
+    pub input: tokio::sync::mpsc::Sender<TerminalInput>,
+    pub output: Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>,
+}
+
+/// Exec created by Bolt but not yet started
+#[derive(Debug, Clone, Deserialize)]
+struct ExecCreated {
+    id: String,
 }
 
 impl BoltClient {
-    /// Create a new Bolt API client
-    pub fn new(base_url: &str) -> Self {
-        let client = Client::new();
-        Self {
-            client,
-            base_url: base_url.to_string(),
+    /// Create a new Bolt API client for a `bolt://`, `http://` or `unix://` URL,
+    /// e.g. `unix:///run/bolt/bolt.sock`
+    pub fn new(base_url: &str) -> Result<Self> {
+        Self::with_config(base_url, BoltClientConfig::default())
+    }
+
+    /// Create a client with the given timeouts and retries, using the proxies from the
+    /// environment
+    pub fn with_config(base_url: &str, config: BoltClientConfig) -> Result<Self> {
+        Self::with_proxy(base_url, config, &ProxyConfig::default())
+    }
+
+    /// Create a client reaching Bolt through `proxy`; Unix sockets are never proxied
+    pub fn with_proxy(base_url: &str, config: BoltClientConfig, proxy: &ProxyConfig) -> Result<Self> {
+        let builder = proxy
+            .client_builder()
+            .connect_timeout(Duration::from_secs(config.connect_timeout_secs));
+
+        if let Some(path) = base_url.strip_prefix("unix://") {
+            return Self::unix(builder, path, config);
+        }
+
+        // Bolt serves its REST API over plain HTTP on the bolt:// address
+        let base_url = match base_url.strip_prefix("bolt://") {
+            Some(rest) => format!("http://{}", rest),
+            None => base_url.to_string(),
+        };
+        Ok(Self {
+            client: builder.build()?,
+            base_url,
+            transport: BoltTransport::Tcp,
+            config,
+        })
+    }
+
+    #[cfg(unix)]
+    fn unix(builder: reqwest::ClientBuilder, path: &str, config: BoltClientConfig) -> Result<Self> {
+        if !path.starts_with('/') {
+            return Err(Error::Config(format!(
+                "Unix socket URL needs an absolute path, e.g. unix:///run/bolt/bolt.sock, got unix://{}",
+                path
+            )));
+        }
+
+        Ok(Self {
+            client: builder.unix_socket(path).build()?,
+            // Every request goes over the socket, so the host is only used for the Host header
+            base_url: "http://localhost".to_string(),
+            transport: BoltTransport::Unix(path.into()),
+            config,
+        })
+    }
+
+    #[cfg(not(unix))]
+    fn unix(_builder: reqwest::ClientBuilder, path: &str, _config: BoltClientConfig) -> Result<Self> {
+        Err(Error::Config(format!(
+            "Unix socket Bolt URLs (unix://{}) are not supported on this platform, use http:// or bolt://",
+            path
+        )))
+    }
+
+    /// How requests reach Bolt
+    pub fn transport(&self) -> &BoltTransport {
+        &self.transport
+    }
+
+    /// Send a request that must answer within `timeout`. Never retried, so it is safe
+    /// for operations such as create or start that must not happen twice.
+    async fn send_within(&self, request: RequestBuilder, timeout: Duration) -> Result<Response> {
+        request.timeout(timeout).send().await.map_err(|e| {
+            if e.is_timeout() {
+                timeout_error(&e, timeout)
+            } else {
+                e.into()
+            }
+        })
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        self.send_within(request, self.config.request_timeout()).await
+    }
+
+    /// Send a read that is safe to repeat, retrying connection failures, timeouts and
+    /// gateway errors with backoff. `request` builds a fresh request for each attempt.
+    async fn send_idempotent(&self, request: impl Fn() -> RequestBuilder) -> Result<Response> {
+        let timeout = self.config.request_timeout();
+        let mut attempt = 0;
+        loop {
+            let outcome = request().timeout(timeout).send().await;
+            let retryable = match &outcome {
+                Ok(response) => matches!(
+                    response.status(),
+                    StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+                ),
+                Err(e) => e.is_connect() || e.is_timeout(),
+            };
+            if !retryable || attempt >= self.config.retries {
+                return outcome.map_err(|e| {
+                    if e.is_timeout() {
+                        timeout_error(&e, timeout)
+                    } else {
+                        e.into()
+                    }
+                });
+            }
+
+            let delay = self.config.backoff(attempt);
+            match &outcome {
+                Ok(response) => warn!("Bolt returned {}, retrying in {:?}", response.status(), delay),
+                Err(e) => warn!("Bolt request failed, retrying in {:?}: {}", delay, e),
+            }
+            tokio::time::sleep(delay).await;
+            attempt += 1;
         }
     }
 
@@ -87,7 +283,7 @@ impl BoltClient {
     pub async fn ping(&self) -> Result<bool> {
         let url = format!("{}/ping", self.base_url);
 
-        match self.client.get(&url).send().await {
+        match self.send(self.client.get(&url)).await {
             Ok(response) => {
                 let success = response.status().is_success();
                 if success {
@@ -108,17 +304,17 @@ impl BoltClient {
     pub async fn system_info(&self) -> Result<BoltSystemInfo> {
         let url = format!("{}/system/info", self.base_url);
 
-        let response = self.client.get(&url).send().await?;
+        let response = self.send_idempotent(|| self.client.get(&url)).await?;
 
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!("Bolt API error: {}", response.status()));
+            return Err(status_error(response.status(), format!("Bolt API error: {}", response.status())));
         }
 
         let bolt_response: BoltResponse<BoltSystemInfo> = response.json().await?;
 
         match bolt_response.data {
             Some(info) => Ok(info),
-            None => Err(anyhow::anyhow!("No system info in response: {:?}", bolt_response.error)),
+            None => Err(Error::Bolt(format!("No system info in response: {:?}", bolt_response.error))),
         }
     }
 
@@ -145,6 +341,12 @@ impl BoltClient {
             if let Some(gpu) = filter.has_gpu {
                 params.push(format!("gpu={}", gpu));
             }
+            if let Some(network) = filter.network {
+                params.push(format!("network={}", urlencoding::encode(&network)));
+            }
+            for (key, value) in filter.labels.unwrap_or_default() {
+                params.push(format!("label={}", urlencoding::encode(&format!("{}={}", key, value))));
+            }
 
             if !params.is_empty() {
                 url.push('?');
@@ -152,10 +354,10 @@ impl BoltClient {
             }
         }
 
-        let response = self.client.get(&url).send().await?;
+        let response = self.send_idempotent(|| self.client.get(&url)).await?;
 
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!("Failed to list containers: {}", response.status()));
+            return Err(status_error(response.status(), format!("Failed to list containers: {}", response.status())));
         }
 
         let bolt_response: BoltResponse<Vec<Container>> = response.json().await?;
@@ -165,7 +367,7 @@ impl BoltClient {
                 info!("Retrieved {} containers from Bolt", containers.len());
                 Ok(containers)
             }
-            None => Err(anyhow::anyhow!("No containers in response: {:?}", bolt_response.error)),
+            None => Err(Error::Bolt(format!("No containers in response: {:?}", bolt_response.error))),
         }
     }
 
@@ -173,17 +375,20 @@ impl BoltClient {
     pub async fn get_container(&self, id: &str) -> Result<Container> {
         let url = format!("{}/containers/{}", self.base_url, id);
 
-        let response = self.client.get(&url).send().await?;
+        let response = self.send_idempotent(|| self.client.get(&url)).await?;
 
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(Error::NotFound(format!("Container not found: {}", id)));
+        }
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!("Container not found: {}", id));
+            return Err(status_error(response.status(), format!("Failed to get container {}: {}", id, response.status())));
         }
 
         let bolt_response: BoltResponse<Container> = response.json().await?;
 
         match bolt_response.data {
             Some(container) => Ok(container),
-            None => Err(anyhow::anyhow!("No container data: {:?}", bolt_response.error)),
+            None => Err(Error::Bolt(format!("No container data: {:?}", bolt_response.error))),
         }
     }
 
@@ -212,6 +417,30 @@ impl BoltClient {
         self.container_operation(id, "restart", Some(options)).await
     }
 
+    /// Block until the container meets `condition`, returning its exit code.
+    /// Bolt holds the request open, so this can take as long as the container runs.
+    pub async fn wait_container(&self, id: &str, condition: WaitCondition) -> Result<i32> {
+        let url = format!("{}/containers/{}/wait", self.base_url, id);
+
+        let response = self
+            .client
+            .post(&url)
+            .query(&[("condition", condition.as_str())])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to wait for container {}: {}", id, response.status())));
+        }
+
+        let bolt_response: BoltResponse<ContainerWaitResponse> = response.json().await?;
+
+        match bolt_response.data {
+            Some(wait) => Ok(wait.status_code),
+            None => Err(Error::Bolt(format!("No wait result: {:?}", bolt_response.error))),
+        }
+    }
+
     /// Pause a container
     pub async fn pause_container(&self, id: &str) -> Result<()> {
         self.container_operation(id, "pause", None).await
@@ -241,18 +470,32 @@ impl BoltClient {
         self.container_operation(id, "remove", Some(options)).await
     }
 
+    /// Rename a container
+    pub async fn rename_container(&self, id: &str, new_name: &str) -> Result<()> {
+        let url = format!("{}/containers/{}/rename", self.base_url, id);
+
+        let response = self
+            .send(self.client.post(&url).json(&serde_json::json!({ "name": new_name })))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to rename container {}: {}", id, response.status())));
+        }
+
+        info!("Renamed container {} to {}", id, new_name);
+        Ok(())
+    }
+
     /// Create a new container
     pub async fn create_container(&self, request: CreateContainerRequest) -> Result<Container> {
         let url = format!("{}/containers", self.base_url);
 
-        let response = self.client
-            .post(&url)
-            .json(&request)
-            .send()
+        let response = self
+            .send(self.client.post(&url).json(&request))
             .await?;
 
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!("Failed to create container: {}", response.status()));
+            return Err(status_error(response.status(), format!("Failed to create container: {}", response.status())));
         }
 
         let bolt_response: BoltResponse<Container> = response.json().await?;
@@ -262,12 +505,37 @@ impl BoltClient {
                 info!("Created container: {} ({})", container.name, container.id);
                 Ok(container)
             }
-            None => Err(anyhow::anyhow!("No container data in create response: {:?}", bolt_response.error)),
+            None => Err(Error::Bolt(format!("No container data in create response: {:?}", bolt_response.error))),
         }
     }
 
     /// Get container logs
     pub async fn get_container_logs(&self, request: ContainerLogsRequest) -> Result<String> {
+        let url = self.logs_url(&request);
+        let response = self.send_idempotent(|| self.client.get(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to get logs: {}", response.status())));
+        }
+
+        let logs = response.text().await?;
+        Ok(logs)
+    }
+
+    /// Follow container logs; the stream ends when the container stops
+    pub async fn follow_container_logs(&self, request: ContainerLogsRequest) -> Result<LogLineStream> {
+        let request = ContainerLogsRequest { follow: true, ..request };
+        let response = self.client.get(self.logs_url(&request)).send().await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to follow logs: {}", response.status())));
+        }
+
+        let chunks = response.bytes_stream().map(|chunk| chunk.map_err(Error::from));
+        Ok(split_lines(Box::pin(chunks)))
+    }
+
+    fn logs_url(&self, request: &ContainerLogsRequest) -> String {
         let url = format!("{}/containers/{}/logs", self.base_url, request.container_id);
 
         let mut params = Vec::new();
@@ -281,175 +549,978 @@ impl BoltClient {
             params.push(format!("since={}", since.timestamp()));
         }
 
-        let url_with_params = format!("{}?{}", url, params.join("&"));
-
-        let response = self.client.get(&url_with_params).send().await?;
-
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!("Failed to get logs: {}", response.status()));
-        }
-
-        let logs = response.text().await?;
-        Ok(logs)
+        format!("{}?{}", url, params.join("&"))
     }
 
     /// Get container stats
     pub async fn get_container_stats(&self, id: &str) -> Result<ContainerStats> {
         let url = format!("{}/containers/{}/stats", self.base_url, id);
 
-        let response = self.client.get(&url).send().await?;
+        let response = self.send_idempotent(|| self.client.get(&url)).await?;
 
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!("Failed to get stats: {}", response.status()));
+            return Err(status_error(response.status(), format!("Failed to get stats: {}", response.status())));
         }
 
         let bolt_response: BoltResponse<ContainerStats> = response.json().await?;
 
         match bolt_response.data {
             Some(stats) => Ok(stats),
-            None => Err(anyhow::anyhow!("No stats data: {:?}", bolt_response.error)),
+            None => Err(Error::Bolt(format!("No stats data: {:?}", bolt_response.error))),
+        }
+    }
+
+    /// List the processes running in a container; `ps_args` are passed to `ps` (default `-ef`)
+    pub async fn top_container(&self, id: &str, ps_args: Option<&str>) -> Result<ContainerTop> {
+        let url = format!("{}/containers/{}/top", self.base_url, id);
+
+        let response = self
+            .send_idempotent(|| {
+                let request = self.client.get(&url);
+                match ps_args {
+                    Some(ps_args) => request.query(&[("ps_args", ps_args)]),
+                    None => request,
+                }
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to list processes in container {}: {}", id, response.status())));
+        }
+
+        let bolt_response: BoltResponse<ContainerTop> = response.json().await?;
+
+        match bolt_response.data {
+            Some(top) => Ok(top),
+            None => Err(Error::Bolt(format!("No process data: {:?}", bolt_response.error))),
         }
     }
 
-    /// Execute a command in a container
-    pub async fn exec_container(&self, id: &str, cmd: Vec<String>, interactive: bool) -> Result<String> {
+    /// Execute a command in a container and capture its output
+    pub async fn exec_container(&self, id: &str, request: &ExecRequest) -> Result<ExecResponse> {
         let url = format!("{}/containers/{}/exec", self.base_url, id);
 
-        let request = serde_json::json!({
-            "cmd": cmd,
-            "interactive": interactive,
-            "tty": interactive,
+        let body = serde_json::json!({
+            "cmd": request.cmd,
+            "interactive": request.interactive,
+            "tty": request.interactive,
+            "working_dir": request.working_dir,
+            "env": request.env,
             "attach_stdout": true,
             "attach_stderr": true
         });
 
         let response = self.client
             .post(&url)
-            .json(&request)
+            .json(&body)
             .send()
             .await?;
 
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!("Failed to exec: {}", response.status()));
+            return Err(status_error(response.status(), format!("Failed to exec: {}", response.status())));
         }
 
-        let output = response.text().await?;
-        Ok(output)
+        let bolt_response: BoltResponse<ExecResponse> = response.json().await?;
+
+        match bolt_response.data {
+            Some(output) => Ok(output),
+            None => Err(Error::Bolt(format!("No exec output: {:?}", bolt_response.error))),
+        }
     }
 
-    /// Internal helper for container operations
-    async fn container_operation(&self, id: &str, action: &str, options: Option<HashMap<String, serde_json::Value>>) -> Result<()> {
-        let url = format!("{}/containers/{}/action", self.base_url, id);
+    /// Start an interactive exec with a TTY. Typed input is streamed up as the request body
+    /// and terminal output comes back as the response body; resizes go out separately.
+    pub async fn exec_terminal(&self, id: &str, request: &ExecRequest) -> Result<TerminalSession> {
+        let url = format!("{}/containers/{}/exec", self.base_url, id);
 
-        let operation = ContainerOperation {
-            action: action.to_string(),
-            container_id: id.to_string(),
-            options,
+        let body = serde_json::json!({
+            "cmd": request.cmd,
+            "interactive": true,
+            "tty": true,
+            "working_dir": request.working_dir,
+            "env": request.env,
+            "attach_stdin": true,
+            "attach_stdout": true,
+            "attach_stderr": true,
+            "start": false
+        });
+
+        let response = self.client.post(&url).json(&body).send().await?;
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to create exec: {}", response.status())));
+        }
+
+        let bolt_response: BoltResponse<ExecCreated> = response.json().await?;
+        let exec_id = match bolt_response.data {
+            Some(exec) => exec.id,
+            None => return Err(Error::Bolt(format!("No exec created: {:?}", bolt_response.error))),
         };
 
+        let (input, mut commands) = tokio::sync::mpsc::channel::<TerminalInput>(64);
+        let (stdin, stdin_rx) = tokio::sync::mpsc::channel::<Bytes>(64);
+
+        let client = self.client.clone();
+        let resize_url = format!("{}/exec/{}/resize", self.base_url, exec_id);
+        tokio::spawn(async move {
+            while let Some(command) = commands.recv().await {
+                match command {
+                    TerminalInput::Data(data) => {
+                        if stdin.send(data).await.is_err() {
+                            break;
+                        }
+                    }
+                    TerminalInput::Resize { cols, rows } => {
+                        let resized = client
+                            .post(&resize_url)
+                            .json(&serde_json::json!({ "cols": cols, "rows": rows }))
+                            .send()
+                            .await;
+                        if let Err(e) = resized {
+                            warn!("Failed to resize exec terminal: {}", e);
+                        }
+                    }
+                }
+            }
+            // The session was dropped; `stdin` goes with it and Bolt sees end of input
+        });
+
+        let stdin = futures::stream::unfold(stdin_rx, |mut receiver| async move {
+            let data = receiver.recv().await?;
+            Some((Ok::<_, std::io::Error>(data), receiver))
+        });
+
+        let url = format!("{}/exec/{}/start", self.base_url, exec_id);
+        let response = self
+            .client
+            .post(&url)
+            .body(reqwest::Body::wrap_stream(stdin))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to start exec: {}", response.status())));
+        }
+
+        info!("Attached terminal to container {} (exec {})", id, exec_id);
+        let output = response.bytes_stream().map(|chunk| chunk.map_err(Error::from));
+        Ok(TerminalSession {
+            input,
+            output: Box::pin(output),
+        })
+    }
+
+    /// List images stored locally on the Bolt host
+    pub async fn list_images(&self) -> Result<Vec<LocalImage>> {
+        let url = format!("{}/images", self.base_url);
+
+        let response = self.send_idempotent(|| self.client.get(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to list images: {}", response.status())));
+        }
+
+        let bolt_response: BoltResponse<Vec<LocalImage>> = response.json().await?;
+
+        match bolt_response.data {
+            Some(images) => {
+                info!("Retrieved {} local images from Bolt", images.len());
+                Ok(images)
+            }
+            None => Err(Error::Bolt(format!("No images in response: {:?}", bolt_response.error))),
+        }
+    }
+
+    /// Pull an image onto the Bolt host
+    pub async fn pull_image(&self, reference: &str) -> Result<()> {
+        let url = format!("{}/images/pull", self.base_url);
+
         let response = self.client
             .post(&url)
-            .json(&operation)
+            .json(&serde_json::json!({ "reference": reference }))
             .send()
             .await?;
 
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!("Operation {} failed: {}", action, response.status()));
+            return Err(status_error(response.status(), format!("Failed to pull image {}: {}", reference, response.status())));
         }
 
-        let bolt_response: BoltResponse<()> = response.json().await?;
+        info!("Bolt pulled image {}", reference);
+        Ok(())
+    }
 
-        if !bolt_response.success {
-            return Err(anyhow::anyhow!("Bolt operation failed: {:?}", bolt_response.error));
+    /// Remove a local image
+    pub async fn remove_image(&self, id: &str, force: bool) -> Result<()> {
+        let url = format!("{}/images/{}?force={}", self.base_url, id, force);
+
+        let response = self.send(self.client.delete(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to remove image {}: {}", id, response.status())));
         }
 
-        info!("Container {} operation {} completed", id, action);
+        info!("Removed image {}", id);
         Ok(())
     }
-}
 
-/// Mock implementation for development/testing when Bolt is not available
-pub struct MockBoltClient;
+    /// Add a tag to a local image, moving it off any image that currently holds it
+    pub async fn tag_image(&self, id: &str, repository: &str, tag: &str) -> Result<()> {
+        let url = format!("{}/images/{}/tag", self.base_url, id);
 
-impl MockBoltClient {
-    pub fn new() -> Self {
-        Self
+        let response = self
+            .send(self.client.post(&url).json(&serde_json::json!({ "repository": repository, "tag": tag })))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to tag image {}: {}", id, response.status())));
+        }
+
+        info!("Tagged image {} as {}:{}", id, repository, tag);
+        Ok(())
     }
 
-    /// Generate mock containers for testing
-    pub async fn list_containers(&self, _filter: Option<ContainerFilter>) -> Result<Vec<Container>> {
-        let mock_containers = vec![
-            Container {
-                id: "mock_web_server_001".to_string(),
-                name: "nginx-web".to_string(),
-                image: "nginx:latest".to_string(),
-                status: ContainerStatus::Running,
-                ports: vec![
-                    PortMapping {
-                        container_port: 80,
-                        host_port: Some(8080),
-                        protocol: Protocol::Tcp,
-                        host_ip: Some("0.0.0.0".to_string()),
-                    }
-                ],
-                volumes: vec![],
-                networks: vec!["bridge".to_string()],
-                env: HashMap::new(),
-                labels: HashMap::new(),
-                created_at: chrono::Utc::now() - chrono::Duration::hours(2),
-                started_at: Some(chrono::Utc::now() - chrono::Duration::hours(1)),
-                finished_at: None,
-                gaming_config: None,
-                gpu_allocation: None,
-                performance_metrics: Some(PerformanceMetrics {
-                    cpu_usage: 15.2,
-                    memory_usage: MemoryUsage {
-                        used_mb: 128,
-                        limit_mb: 512,
-                        percentage: 25.0,
-                    },
-                    gpu_usage: None,
-                    network_io: NetworkIo {
-                        rx_bytes: 1024000,
-                        tx_bytes: 2048000,
-                        rx_packets: 1500,
-                        tx_packets: 1200,
-                    },
-                    disk_io: DiskIo {
-                        read_bytes: 512000,
-                        write_bytes: 256000,
-                        read_ops: 100,
-                        write_ops: 50,
-                    },
-                    gaming_metrics: None,
-                }),
-            },
-            Container {
-                id: "mock_gaming_container_002".to_string(),
-                name: "steam-gaming".to_string(),
-                image: "gaming/steam-proton:latest".to_string(),
-                status: ContainerStatus::Running,
-                ports: vec![],
-                volumes: vec![
-                    VolumeMount {
-                        source: "/home/user/games".to_string(),
-                        target: "/games".to_string(),
-                        read_only: false,
-                        volume_type: VolumeType::Bind,
-                    }
-                ],
-                networks: vec!["gaming".to_string()],
-                env: HashMap::from([
-                    ("STEAM_USER".to_string(), "player".to_string()),
-                    ("PROTON_VERSION".to_string(), "8.0".to_string()),
-                ]),
-                labels: HashMap::from([
-                    ("gaming".to_string(), "true".to_string()),
+    /// Remove unused local images matching the filter
+    pub async fn prune_images(&self, filter: &ImagePruneFilter) -> Result<ImagePruneReport> {
+        let url = format!("{}/images/prune", self.base_url);
+
+        let response = self.client
+            .post(&url)
+            .json(filter)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to prune images: {}", response.status())));
+        }
+
+        let bolt_response: BoltResponse<ImagePruneReport> = response.json().await?;
+
+        match bolt_response.data {
+            Some(report) => {
+                info!("Pruned {} images, reclaimed {} bytes", report.deleted.len(), report.reclaimed_bytes);
+                Ok(report)
+            }
+            None => Err(Error::Bolt(format!("No prune report in response: {:?}", bolt_response.error))),
+        }
+    }
+
+    /// List networks
+    pub async fn list_networks(&self) -> Result<Vec<Network>> {
+        let url = format!("{}/networks", self.base_url);
+
+        let response = self.send_idempotent(|| self.client.get(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to list networks: {}", response.status())));
+        }
+
+        let bolt_response: BoltResponse<Vec<Network>> = response.json().await?;
+
+        match bolt_response.data {
+            Some(networks) => {
+                debug!("Retrieved {} networks from Bolt", networks.len());
+                Ok(networks)
+            }
+            None => {
+                warn!("No network data in Bolt response: {:?}", bolt_response.error);
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// Create a network
+    pub async fn create_network(&self, request: CreateNetworkRequest) -> Result<Network> {
+        let url = format!("{}/networks", self.base_url);
+
+        let response = self
+            .send(self.client.post(&url).json(&request))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to create network {}: {}", request.name, response.status())));
+        }
+
+        let bolt_response: BoltResponse<Network> = response.json().await?;
+
+        match bolt_response.data {
+            Some(network) => {
+                info!("Created network {} ({})", network.name, network.id);
+                Ok(network)
+            }
+            None => Err(Error::Bolt(format!("No network data in response: {:?}", bolt_response.error))),
+        }
+    }
+
+    /// Remove a network
+    pub async fn remove_network(&self, id: &str) -> Result<()> {
+        let url = format!("{}/networks/{}", self.base_url, id);
+
+        let response = self.send(self.client.delete(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to remove network {}: {}", id, response.status())));
+        }
+
+        info!("Removed network {}", id);
+        Ok(())
+    }
+
+    /// Attach a container to a network
+    pub async fn connect_network(&self, id: &str, request: &NetworkConnectRequest) -> Result<()> {
+        self.network_operation(id, "connect", request).await
+    }
+
+    /// Detach a container from a network
+    pub async fn disconnect_network(&self, id: &str, request: &NetworkConnectRequest) -> Result<()> {
+        self.network_operation(id, "disconnect", request).await
+    }
+
+    /// List volumes
+    pub async fn list_volumes(&self) -> Result<Vec<Volume>> {
+        let url = format!("{}/volumes", self.base_url);
+
+        let response = self.send_idempotent(|| self.client.get(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to list volumes: {}", response.status())));
+        }
+
+        let bolt_response: BoltResponse<Vec<Volume>> = response.json().await?;
+
+        match bolt_response.data {
+            Some(volumes) => {
+                debug!("Retrieved {} volumes from Bolt", volumes.len());
+                Ok(volumes)
+            }
+            None => {
+                warn!("No volume data in Bolt response: {:?}", bolt_response.error);
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// Get a volume, including its size on disk when the driver reports it
+    pub async fn inspect_volume(&self, name: &str) -> Result<Option<Volume>> {
+        let url = format!("{}/volumes/{}", self.base_url, name);
+
+        let response = self.send_idempotent(|| self.client.get(&url)).await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to inspect volume {}: {}", name, response.status())));
+        }
+
+        let bolt_response: BoltResponse<Volume> = response.json().await?;
+        Ok(bolt_response.data)
+    }
+
+    /// Create a volume
+    pub async fn create_volume(&self, request: CreateVolumeRequest) -> Result<Volume> {
+        let url = format!("{}/volumes", self.base_url);
+
+        let response = self
+            .send(self.client.post(&url).json(&request))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to create volume {}: {}", request.name, response.status())));
+        }
+
+        let bolt_response: BoltResponse<Volume> = response.json().await?;
+
+        match bolt_response.data {
+            Some(volume) => {
+                info!("Created volume {}", volume.name);
+                Ok(volume)
+            }
+            None => Err(Error::Bolt(format!("No volume data in response: {:?}", bolt_response.error))),
+        }
+    }
+
+    /// Remove a volume
+    pub async fn remove_volume(&self, name: &str, force: bool) -> Result<()> {
+        let url = format!("{}/volumes/{}?force={}", self.base_url, name, force);
+
+        let response = self.send(self.client.delete(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to remove volume {}: {}", name, response.status())));
+        }
+
+        info!("Removed volume {}", name);
+        Ok(())
+    }
+
+    /// Remove unused volumes matching the filter
+    pub async fn prune_volumes(&self, filter: &VolumePruneFilter) -> Result<VolumePruneReport> {
+        let url = format!("{}/volumes/prune", self.base_url);
+
+        let response = self.client
+            .post(&url)
+            .json(filter)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to prune volumes: {}", response.status())));
+        }
+
+        let bolt_response: BoltResponse<VolumePruneReport> = response.json().await?;
+
+        match bolt_response.data {
+            Some(report) => {
+                info!("Pruned {} volumes, reclaimed {} bytes", report.deleted.len(), report.reclaimed_bytes);
+                Ok(report)
+            }
+            None => Err(Error::Bolt(format!("No prune report in response: {:?}", bolt_response.error))),
+        }
+    }
+
+    /// Stat a path inside a volume; `path` must already be normalized
+    pub async fn stat_volume_path(&self, name: &str, path: &str) -> Result<Option<VolumeEntry>> {
+        let url = format!("{}/volumes/{}/fs/stat", self.base_url, name);
+
+        let response = self
+            .send_idempotent(|| self.client.get(&url).query(&[("path", path)]))
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to stat {} in volume {}: {}", path, name, response.status())));
+        }
+
+        let bolt_response: BoltResponse<VolumeEntry> = response.json().await?;
+        Ok(bolt_response.data)
+    }
+
+    /// List a directory inside a volume; `path` must already be normalized
+    pub async fn list_volume_directory(&self, name: &str, path: &str) -> Result<Vec<VolumeEntry>> {
+        let url = format!("{}/volumes/{}/fs/list", self.base_url, name);
+
+        let response = self
+            .send_idempotent(|| self.client.get(&url).query(&[("path", path)]))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to list {} in volume {}: {}", path, name, response.status())));
+        }
+
+        let bolt_response: BoltResponse<Vec<VolumeEntry>> = response.json().await?;
+        Ok(bolt_response.data.unwrap_or_default())
+    }
+
+    /// Stream a file out of a volume; `path` must already be normalized
+    pub async fn read_volume_file(&self, name: &str, path: &str) -> Result<VolumeFileStream> {
+        let url = format!("{}/volumes/{}/fs/read", self.base_url, name);
+
+        let response = self.client.get(&url).query(&[("path", path)]).send().await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to read {} from volume {}: {}", path, name, response.status())));
+        }
+
+        let stream = response.bytes_stream().map(|chunk| chunk.map_err(std::io::Error::other));
+        Ok(Box::pin(stream))
+    }
+
+    /// Stream a container's whole filesystem as a tar
+    pub async fn export_container(&self, id: &str) -> Result<VolumeFileStream> {
+        let url = format!("{}/containers/{}/export", self.base_url, id);
+
+        let response = self.client.get(&url).send().await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to export container {}: {}", id, response.status())));
+        }
+
+        let stream = response.bytes_stream().map(|chunk| chunk.map_err(std::io::Error::other));
+        Ok(Box::pin(stream))
+    }
+
+    /// Save a container's filesystem as a new image
+    pub async fn commit_container(&self, id: &str, request: &ContainerCommitRequest) -> Result<ImageSummary> {
+        let url = format!("{}/containers/{}/commit", self.base_url, id);
+
+        let response = self.client.post(&url).json(request).send().await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to commit container {}: {}", id, response.status())));
+        }
+
+        let bolt_response: BoltResponse<ImageSummary> = response.json().await?;
+        let image = bolt_response
+            .data
+            .ok_or_else(|| Error::Bolt(format!("Bolt returned no image for commit of {}", id)))?;
+
+        info!("Committed container {} as {}", id, image.id);
+        Ok(image)
+    }
+
+    /// Stat a path inside a container's filesystem; `path` must already be normalized
+    pub async fn stat_container_path(&self, id: &str, path: &str) -> Result<Option<VolumeEntry>> {
+        let url = format!("{}/containers/{}/fs/stat", self.base_url, id);
+
+        let response = self
+            .send_idempotent(|| self.client.get(&url).query(&[("path", path)]))
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to stat {} in container {}: {}", path, id, response.status())));
+        }
+
+        let bolt_response: BoltResponse<VolumeEntry> = response.json().await?;
+        Ok(bolt_response.data)
+    }
+
+    /// Stream a single file out of a container
+    pub async fn read_container_file(&self, id: &str, path: &str) -> Result<VolumeFileStream> {
+        let url = format!("{}/containers/{}/fs/read", self.base_url, id);
+
+        let response = self.client.get(&url).query(&[("path", path)]).send().await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to read {} from container {}: {}", path, id, response.status())));
+        }
+
+        let stream = response.bytes_stream().map(|chunk| chunk.map_err(std::io::Error::other));
+        Ok(Box::pin(stream))
+    }
+
+    /// Stream a tar of a file or directory in a container; entries are named from the path's last component
+    pub async fn get_archive(&self, id: &str, path: &str) -> Result<VolumeFileStream> {
+        let url = format!("{}/containers/{}/archive", self.base_url, id);
+
+        let response = self.client.get(&url).query(&[("path", path)]).send().await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to archive {} in container {}: {}", path, id, response.status())));
+        }
+
+        let stream = response.bytes_stream().map(|chunk| chunk.map_err(std::io::Error::other));
+        Ok(Box::pin(stream))
+    }
+
+    /// Extract a tar stream into a directory of a container, streaming it up without buffering
+    pub async fn put_archive(&self, id: &str, path: &str, archive: VolumeFileStream) -> Result<()> {
+        let url = format!("{}/containers/{}/archive", self.base_url, id);
+
+        let response = self
+            .client
+            .put(&url)
+            .query(&[("path", path)])
+            .header(reqwest::header::CONTENT_TYPE, "application/x-tar")
+            .body(reqwest::Body::wrap_stream(archive))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to extract archive into {} in container {}: {}", path, id, response.status())));
+        }
+
+        info!("Extracted archive into {} in container {}", path, id);
+        Ok(())
+    }
+
+    /// Get Bolt's native disk usage report (container layers, build cache)
+    pub async fn disk_usage(&self) -> Result<BoltDiskUsage> {
+        let url = format!("{}/system/df", self.base_url);
+
+        let response = self.send_idempotent(|| self.client.get(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to get disk usage: {}", response.status())));
+        }
+
+        let bolt_response: BoltResponse<BoltDiskUsage> = response.json().await?;
+        Ok(bolt_response.data.unwrap_or_default())
+    }
+
+    /// Internal helper for network attach/detach
+    async fn network_operation(&self, id: &str, action: &str, request: &NetworkConnectRequest) -> Result<()> {
+        let url = format!("{}/networks/{}/{}", self.base_url, id, action);
+
+        let response = self
+            .send(self.client.post(&url).json(request))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Network {} failed: {}", action, response.status())));
+        }
+
+        info!("Network {} {} container {}", id, action, request.container_id);
+        Ok(())
+    }
+
+    /// Internal helper for container operations
+    async fn container_operation(&self, id: &str, action: &str, options: Option<HashMap<String, serde_json::Value>>) -> Result<()> {
+        let url = format!("{}/containers/{}/action", self.base_url, id);
+
+        let operation = ContainerOperation {
+            action: action.to_string(),
+            container_id: id.to_string(),
+            options,
+        };
+
+        // Stop and restart wait for the container's grace period before Bolt answers
+        let grace = operation
+            .options
+            .as_ref()
+            .and_then(|options| options.get("timeout"))
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(0);
+        let timeout = self.config.request_timeout() + Duration::from_secs(grace);
+        let response = self
+            .send_within(self.client.post(&url).json(&operation), timeout)
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Operation {} failed: {}", action, response.status())));
+        }
+
+        let bolt_response: BoltResponse<()> = response.json().await?;
+
+        if !bolt_response.success {
+            return Err(Error::Bolt(format!("Bolt operation failed: {:?}", bolt_response.error)));
+        }
+
+        info!("Container {} operation {} completed", id, action);
+        Ok(())
+    }
+}
+
+/// Wrap a file body of known size in a tar holding just that file, as a stream.
+/// The body must yield exactly `size` bytes, or the stream ends with an error.
+pub fn single_file_archive(name: &str, size: u64, body: VolumeFileStream) -> Result<VolumeFileStream> {
+    let mut header = tar::Header::new_gnu();
+    header.set_path(name)?;
+    header.set_size(size);
+    header.set_mode(0o644);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    header.set_entry_type(tar::EntryType::Regular);
+    header.set_cksum();
+
+    // Tar pads each file to 512-byte blocks and ends with two zero blocks
+    let padding = (512 - size % 512) % 512;
+    let trailer = Bytes::from(vec![0u8; padding as usize + 1024]);
+
+    let sent = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
+    let counter = sent.clone();
+    let body = body.map(move |chunk| {
+        let chunk = chunk?;
+        let total = counter.fetch_add(chunk.len() as u64, std::sync::atomic::Ordering::Relaxed) + chunk.len() as u64;
+        if total > size {
+            return Err(std::io::Error::other("file is longer than its declared size"));
+        }
+        Ok(chunk)
+    });
+    let trailer = futures::stream::once(async move {
+        // A short body would leave the next header misaligned, so fail instead of padding it
+        if sent.load(std::sync::atomic::Ordering::Relaxed) != size {
+            return Err(std::io::Error::other("file is shorter than its declared size"));
+        }
+        Ok(trailer)
+    });
+
+    let header = futures::stream::once(futures::future::ready(Ok(Bytes::copy_from_slice(header.as_bytes()))));
+    Ok(Box::pin(header.chain(body).chain(trailer)))
+}
+
+/// Split a byte stream into lines, holding back a partial line until its newline arrives
+fn split_lines(chunks: Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>) -> LogLineStream {
+    let lines = futures::stream::unfold(
+        (chunks, Vec::<u8>::new(), std::collections::VecDeque::<String>::new()),
+        |(mut chunks, mut partial, mut ready)| async move {
+            loop {
+                if let Some(line) = ready.pop_front() {
+                    return Some((Ok(line), (chunks, partial, ready)));
+                }
+
+                match chunks.next().await {
+                    Some(Ok(chunk)) => {
+                        partial.extend_from_slice(&chunk);
+                        while let Some(end) = partial.iter().position(|&b| b == b'\n') {
+                            let line: Vec<u8> = partial.drain(..=end).collect();
+                            let line = String::from_utf8_lossy(&line[..end]);
+                            ready.push_back(line.trim_end_matches('\r').to_string());
+                        }
+                    }
+                    Some(Err(e)) => return Some((Err(e), (chunks, partial, ready))),
+                    None if !partial.is_empty() => {
+                        let line = String::from_utf8_lossy(&partial).into_owned();
+                        partial.clear();
+                        return Some((Ok(line), (chunks, partial, ready)));
+                    }
+                    None => return None,
+                }
+            }
+        },
+    );
+    Box::pin(lines)
+}
+
+/// Mock implementation for development/testing when Bolt is not available
+pub struct MockBoltClient {
+    containers: RwLock<HashMap<String, Container>>,
+    images: RwLock<HashMap<String, LocalImage>>,
+    networks: RwLock<HashMap<String, Network>>,
+    volumes: RwLock<HashMap<String, Volume>>,
+    /// Files copied into containers, by container ID and absolute path
+    uploads: RwLock<HashMap<String, BTreeMap<String, Bytes>>>,
+}
+
+impl MockBoltClient {
+    pub fn new() -> Self {
+        let containers = Self::seed_containers()
+            .into_iter()
+            .map(|container| (container.id.clone(), container))
+            .collect();
+
+        let images = Self::seed_images()
+            .into_iter()
+            .map(|image| (image.id.clone(), image))
+            .collect();
+
+        let networks = Self::seed_networks()
+            .into_iter()
+            .map(|network| (network.id.clone(), network))
+            .collect();
+
+        let volumes = Self::seed_volumes()
+            .into_iter()
+            .map(|volume| (volume.name.clone(), volume))
+            .collect();
+
+        Self {
+            containers: RwLock::new(containers),
+            images: RwLock::new(images),
+            networks: RwLock::new(networks),
+            volumes: RwLock::new(volumes),
+            uploads: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Local images backing the mock containers, plus an unused and a dangling one
+    fn seed_images() -> Vec<LocalImage> {
+        let image = |id: &str, tags: &[&str], size_mb: u64, shared_mb: u64, days_old: i64| LocalImage {
+            id: format!("sha256:{}", id),
+            repo_tags: tags.iter().map(|t| t.to_string()).collect(),
+            size: size_mb * 1024 * 1024,
+            shared_size: shared_mb * 1024 * 1024,
+            created: chrono::Utc::now() - chrono::Duration::days(days_old),
+            dangling: tags.is_empty(),
+            labels: HashMap::new(),
+            containers: Vec::new(),
+        };
+
+        vec![
+            image("a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6e7f8a9b0c1d2e3f4a5b6c7d8e9f0a1b2", &["nginx:latest"], 187, 74, 14),
+            image("b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6e7f8a9b0c1d2e3f4a5b6c7d8e9f0a1b2c3", &["gaming/steam-proton:latest"], 4210, 74, 3),
+            image("c3d4e5f6a7b8c9d0e1f2a3b4c5d6e7f8a9b0c1d2e3f4a5b6c7d8e9f0a1b2c3d4", &["postgres:15"], 412, 74, 30),
+            image("d4e5f6a7b8c9d0e1f2a3b4c5d6e7f8a9b0c1d2e3f4a5b6c7d8e9f0a1b2c3d4e5", &["redis:7-alpine"], 41, 7, 45),
+            LocalImage {
+                labels: HashMap::from([("gpanel.build".to_string(), "game-server".to_string())]),
+                ..image("e5f6a7b8c9d0e1f2a3b4c5d6e7f8a9b0c1d2e3f4a5b6c7d8e9f0a1b2c3d4e5f6", &[], 3980, 3900, 6)
+            },
+        ]
+    }
+
+    /// Networks the mock containers are attached to
+    fn seed_networks() -> Vec<Network> {
+        let network = |name: &str, subnet: &str, gateway: &str, containers: &[&str]| Network {
+            id: Self::network_id(name),
+            name: name.to_string(),
+            driver: "bridge".to_string(),
+            subnet: Some(subnet.to_string()),
+            gateway: Some(gateway.to_string()),
+            internal: false,
+            driver_options: HashMap::new(),
+            labels: HashMap::new(),
+            containers: containers.iter().map(|c| c.to_string()).collect(),
+            created: chrono::Utc::now() - chrono::Duration::days(30),
+        };
+
+        vec![
+            network("bridge", "172.17.0.0/16", "172.17.0.1", &["mock_web_server_001"]),
+            Network {
+                driver_options: gaming_driver_options(),
+                ..network("gaming", "172.20.0.0/16", "172.20.0.1", &["mock_gaming_container_002"])
+            },
+            Network {
+                internal: true,
+                ..network("database", "172.21.0.0/16", "172.21.0.1", &["mock_database_003"])
+            },
+        ]
+    }
+
+    /// Volumes on the mock host; only `postgres_data` is mounted by a mock container
+    fn seed_volumes() -> Vec<Volume> {
+        let volume = |name: &str, size_mb: Option<u64>, days_old: i64| Volume {
+            name: name.to_string(),
+            driver: "local".to_string(),
+            mountpoint: format!("/var/lib/bolt/volumes/{}/_data", name),
+            labels: HashMap::new(),
+            size: size_mb.map(|mb| mb * 1024 * 1024),
+            created: chrono::Utc::now() - chrono::Duration::days(days_old),
+            containers: Vec::new(),
+        };
+
+        vec![
+            volume("postgres_data", Some(256), 30),
+            Volume {
+                labels: HashMap::from([("gpanel.game".to_string(), "minecraft".to_string())]),
+                ..volume("minecraft_world", Some(1240), 12)
+            },
+            volume("shader_cache", Some(880), 40),
+            volume("nfs_backups", None, 90),
+        ]
+    }
+
+    /// Files in the seeded volumes as (path, size, content); directories are implied
+    fn volume_files(volume: &str) -> Vec<(&'static str, u64, Option<&'static str>)> {
+        const MB: u64 = 1024 * 1024;
+
+        match volume {
+            "minecraft_world" => vec![
+                ("/server.properties", 0, Some("motd=GhostPanel Minecraft\nmax-players=20\nview-distance=12\n")),
+                ("/world/level.dat", 4 * 1024, None),
+                ("/world/region/r.0.0.mca", 9 * MB, None),
+                ("/world/region/r.0.-1.mca", 8 * MB, None),
+                ("/backups/world-full.tar.gz", 700 * MB, None),
+            ],
+            "postgres_data" => vec![
+                ("/PG_VERSION", 0, Some("15\n")),
+                ("/postgresql.conf", 29 * 1024, None),
+                ("/base/1/1259", 96 * 1024, None),
+            ],
+            "shader_cache" => vec![
+                ("/dxvk/steam-proton.dxvk-cache", 880 * MB, None),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    /// A few canned files for each container, overlaid with anything copied in
+    async fn container_files(&self, id: &str) -> Result<BTreeMap<String, Bytes>> {
+        let container = self.get_container(id).await?;
+
+        let mut files = BTreeMap::from([
+            ("/etc/hostname".to_string(), Bytes::from(format!("{}\n", container.id))),
+            (
+                "/etc/os-release".to_string(),
+                Bytes::from_static(b"NAME=\"Debian GNU/Linux\"\nVERSION_ID=\"12\"\nID=debian\n"),
+            ),
+        ]);
+        if container.image.starts_with("nginx") {
+            files.insert(
+                "/etc/nginx/conf.d/default.conf".to_string(),
+                Bytes::from_static(b"server {\n    listen 80;\n    root /usr/share/nginx/html;\n}\n"),
+            );
+            files.insert(
+                "/usr/share/nginx/html/index.html".to_string(),
+                Bytes::from_static(b"<h1>Welcome to nginx!</h1>\n"),
+            );
+        }
+
+        if let Some(uploads) = self.uploads.read().await.get(id) {
+            files.extend(uploads.iter().map(|(path, content)| (path.clone(), content.clone())));
+        }
+        Ok(files)
+    }
+
+    fn network_id(name: &str) -> String {
+        format!("{:x}", Sha256::digest(name.as_bytes()))[..12].to_string()
+    }
+
+    /// A running web server, a running gaming container and a stopped database
+    fn seed_containers() -> Vec<Container> {
+        vec![
+            Container {
+                id: "mock_web_server_001".to_string(),
+                name: "nginx-web".to_string(),
+                image: "nginx:latest".to_string(),
+                status: ContainerStatus::Running,
+                ports: vec![
+                    PortMapping {
+                        container_port: 80,
+                        host_port: Some(8080),
+                        protocol: Protocol::Tcp,
+                        host_ip: Some("0.0.0.0".to_string()),
+                    }
+                ],
+                volumes: vec![],
+                networks: vec!["bridge".to_string()],
+                env: HashMap::new(),
+                labels: HashMap::from([(PROJECT_LABEL.to_string(), "website".to_string())]),
+                created_at: chrono::Utc::now() - chrono::Duration::hours(2),
+                started_at: Some(chrono::Utc::now() - chrono::Duration::hours(1)),
+                finished_at: None,
+                health_status: Some(HealthStatus {
+                    state: HealthState::Healthy,
+                    failing_streak: 0,
+                }),
+                restart_policy: RestartPolicy::Always,
+                gaming_config: None,
+                gpu_allocation: None,
+                performance_metrics: Some(PerformanceMetrics {
+                    cpu_usage: 15.2,
+                    memory_usage: MemoryUsage {
+                        used_mb: 128,
+                        limit_mb: 512,
+                        percentage: 25.0,
+                    },
+                    gpu_usage: None,
+                    network_io: NetworkIo {
+                        rx_bytes: 1024000,
+                        tx_bytes: 2048000,
+                        rx_packets: 1500,
+                        tx_packets: 1200,
+                    },
+                    disk_io: DiskIo {
+                        read_bytes: 512000,
+                        write_bytes: 256000,
+                        read_ops: 100,
+                        write_ops: 50,
+                    },
+                    gaming_metrics: None,
+                }),
+            },
+            Container {
+                id: "mock_gaming_container_002".to_string(),
+                name: "steam-gaming".to_string(),
+                image: "gaming/steam-proton:latest".to_string(),
+                status: ContainerStatus::Running,
+                ports: vec![],
+                volumes: vec![
+                    VolumeMount {
+                        source: "/home/user/games".to_string(),
+                        target: "/games".to_string(),
+                        read_only: false,
+                        volume_type: VolumeType::Bind,
+                    }
+                ],
+                networks: vec!["gaming".to_string()],
+                env: HashMap::from([
+                    ("STEAM_USER".to_string(), "player".to_string()),
+                    ("PROTON_VERSION".to_string(), "8.0".to_string()),
+                ]),
+                labels: HashMap::from([
+                    ("gaming".to_string(), "true".to_string()),
                     ("gpu".to_string(), "nvidia".to_string()),
+                    (PROJECT_LABEL.to_string(), "steam".to_string()),
                 ]),
                 created_at: chrono::Utc::now() - chrono::Duration::minutes(30),
                 started_at: Some(chrono::Utc::now() - chrono::Duration::minutes(25)),
                 finished_at: None,
+                health_status: None,
+                restart_policy: RestartPolicy::UnlessStopped,
                 gaming_config: Some(GamingConfig {
                     proton_version: Some("8.0".to_string()),
                     wine_version: None,
@@ -459,6 +1530,8 @@ impl MockBoltClient {
                         system: AudioSystem::PipeWire,
                         latency: AudioLatency::Low,
                     }),
+                    profile_name: Some(OptimizationProfile::Gaming.profile_name().to_string()),
+                    profile: Some(OptimizationProfile::Gaming.builtin_profile()),
                 }),
                 gpu_allocation: Some(GpuAllocation {
                     device_id: "nvidia0".to_string(),
@@ -528,39 +1601,1001 @@ impl MockBoltClient {
                     ("POSTGRES_DB".to_string(), "app".to_string()),
                     ("POSTGRES_USER".to_string(), "postgres".to_string()),
                 ]),
-                labels: HashMap::new(),
+                labels: HashMap::from([(PROJECT_LABEL.to_string(), "website".to_string())]),
                 created_at: chrono::Utc::now() - chrono::Duration::days(1),
                 started_at: Some(chrono::Utc::now() - chrono::Duration::hours(12)),
                 finished_at: Some(chrono::Utc::now() - chrono::Duration::minutes(10)),
+                health_status: None,
+                restart_policy: RestartPolicy::No,
                 gaming_config: None,
                 gpu_allocation: None,
                 performance_metrics: None,
             },
-        ];
+        ]
+    }
+
+    /// Apply a change to a stored container, failing if it does not exist
+    async fn update_container<F>(&self, id: &str, update: F) -> Result<()>
+    where
+        F: FnOnce(&mut Container) -> Result<()>,
+    {
+        let mut containers = self.containers.write().await;
+        let container = containers
+            .get_mut(id)
+            .ok_or_else(|| Error::NotFound(format!("Container not found: {}", id)))?;
+        update(container)
+    }
+
+    /// Stored containers matching the filter, newest first
+    pub async fn list_containers(&self, filter: Option<ContainerFilter>) -> Result<Vec<Container>> {
+        let filter = filter.unwrap_or_default();
+        let mut list: Vec<Container> = self
+            .containers
+            .read()
+            .await
+            .values()
+            .filter(|container| filter.matches(container))
+            .cloned()
+            .collect();
+        list.sort_by_key(|container| std::cmp::Reverse(container.created_at));
+        Ok(list)
+    }
+
+    pub async fn ping(&self) -> Result<bool> {
+        Ok(true)
+    }
+
+    pub async fn get_container(&self, id: &str) -> Result<Container> {
+        self.containers
+            .read()
+            .await
+            .get(id)
+            .cloned()
+            .ok_or_else(|| Error::NotFound(format!("Container not found: {}", id)))
+    }
+
+    pub async fn create_container(&self, request: CreateContainerRequest) -> Result<Container> {
+        tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
+
+        let id = format!("mock_{}", &uuid::Uuid::new_v4().simple().to_string()[..12]);
+        let container = Container {
+            name: request.name.unwrap_or_else(|| id.clone()),
+            id,
+            image: request.image,
+            status: ContainerStatus::Created,
+            ports: request.ports,
+            volumes: request.volumes,
+            networks: request.networks,
+            env: request.env,
+            labels: request.labels,
+            created_at: chrono::Utc::now(),
+            started_at: None,
+            finished_at: None,
+            health_status: request.health.as_ref().map(|_| HealthStatus {
+                state: HealthState::Starting,
+                failing_streak: 0,
+            }),
+            restart_policy: request.restart_policy,
+            gaming_config: request.gaming_config,
+            gpu_allocation: request.gpu_allocation,
+            performance_metrics: None,
+        };
+
+        self.containers.write().await.insert(container.id.clone(), container.clone());
+        info!("Created mock container: {} ({})", container.name, container.id);
+        Ok(container)
+    }
+
+    /// Stats sampled from the metrics reported with the container listing
+    pub async fn get_container_stats(&self, id: &str) -> Result<ContainerStats> {
+        let container = self.get_container(id).await?;
+
+        match &container.performance_metrics {
+            Some(metrics) if matches!(container.status, ContainerStatus::Running | ContainerStatus::Paused) => {
+                let now = chrono::Utc::now();
+                let uptime = container
+                    .started_at
+                    .map(|started| (now - started).num_milliseconds().max(0) as f64 / 1000.0)
+                    .unwrap_or_default();
+                let mut stats = ContainerStats::from_metrics(&container.id, metrics, now);
+                Self::vary_stats(&mut stats, uptime);
+                Ok(stats)
+            }
+            _ => Err(Error::Container(format!("Container is not running: {}", id))),
+        }
+    }
+
+    /// Wobble the listed metrics over time so live charts move; I/O counters keep growing
+    fn vary_stats(stats: &mut ContainerStats, uptime_secs: f64) {
+        // Per-container phase, so the mock containers don't move in step
+        let phase = stats.container_id.bytes().map(f64::from).sum::<f64>();
+        let wave = |period: f64, amount: f64| 1.0 + amount * ((uptime_secs / period) + phase).sin();
+
+        stats.cpu_percent = (stats.cpu_percent * wave(7.0, 0.3)).clamp(0.0, 100.0);
+        stats.memory_usage = ((stats.memory_usage as f64 * wave(29.0, 0.05)) as u64).min(stats.memory_limit);
+
+        // Counters advance at roughly their listed total per hour of uptime
+        let grow = |total: u64| total + (total as f64 * uptime_secs / 3600.0) as u64;
+        stats.network_rx = grow(stats.network_rx);
+        stats.network_tx = grow(stats.network_tx);
+        stats.block_read = grow(stats.block_read);
+        stats.block_write = grow(stats.block_write);
+
+        if let Some(gpu) = &mut stats.gpu {
+            gpu.utilization = (gpu.utilization * wave(5.0, 0.15)).clamp(0.0, 100.0);
+        }
+        if let Some(gaming) = &mut stats.gaming {
+            gaming.fps = gaming.fps.map(|fps| fps * wave(3.0, 0.08) as f32);
+        }
+    }
+
+    /// A few plausible processes per image; `ps_args` containing `u` switches to the `ps aux` columns
+    pub async fn top_container(&self, id: &str, ps_args: Option<&str>) -> Result<ContainerTop> {
+        let container = self.get_container(id).await?;
+        if !matches!(container.status, ContainerStatus::Running | ContainerStatus::Paused) {
+            return Err(Error::Container(format!("Container is not running: {}", id)));
+        }
+
+        // (pid, user, %cpu, rss in KiB, command)
+        let processes: Vec<(u32, &str, f32, u64, &str)> = if container.image.starts_with("nginx") {
+            vec![
+                (1, "root", 0.0, 5_312, "nginx: master process nginx -g daemon off;"),
+                (29, "nginx", 0.3, 2_964, "nginx: worker process"),
+                (30, "nginx", 0.2, 2_948, "nginx: worker process"),
+            ]
+        } else if container.gaming_config.is_some() {
+            vec![
+                (1, "root", 0.0, 3_120, "/usr/bin/tini -- /entrypoint.sh"),
+                (14, "steam", 1.8, 412_880, "/home/steam/.steam/ubuntu12_32/steam -silent"),
+                (87, "steam", 42.5, 3_981_400, "/home/steam/proton/dist/bin/wine64 game.exe"),
+                (88, "steam", 3.1, 96_220, "/usr/bin/gamescope --backend headless"),
+            ]
+        } else {
+            vec![(1, "root", 0.1, 2_048, "/bin/sh")]
+        };
+
+        let aux = ps_args.map(|args| args.contains('u')).unwrap_or(false);
+        let titles: &[&str] = if aux {
+            &["USER", "PID", "%CPU", "%MEM", "VSZ", "RSS", "TTY", "STAT", "START", "TIME", "COMMAND"]
+        } else {
+            &["UID", "PID", "PPID", "C", "STIME", "TTY", "TIME", "CMD"]
+        };
+        let start = container.started_at.unwrap_or(container.created_at).format("%H:%M").to_string();
+
+        let processes = processes
+            .into_iter()
+            .map(|(pid, user, cpu, rss, command)| {
+                if aux {
+                    vec![
+                        user.to_string(),
+                        pid.to_string(),
+                        format!("{:.1}", cpu),
+                        format!("{:.1}", rss as f64 / (16.0 * 1024.0 * 1024.0) * 100.0),
+                        (rss * 3).to_string(),
+                        rss.to_string(),
+                        "?".to_string(),
+                        if pid == 1 { "Ss" } else { "S" }.to_string(),
+                        start.clone(),
+                        "0:00".to_string(),
+                        command.to_string(),
+                    ]
+                } else {
+                    vec![
+                        user.to_string(),
+                        pid.to_string(),
+                        if pid == 1 { "0" } else { "1" }.to_string(),
+                        format!("{:.0}", cpu),
+                        start.clone(),
+                        "?".to_string(),
+                        "00:00:00".to_string(),
+                        command.to_string(),
+                    ]
+                }
+            })
+            .collect();
+
+        Ok(ContainerTop {
+            titles: titles.iter().map(|title| title.to_string()).collect(),
+            processes,
+        })
+    }
+
+    pub async fn exec_container(&self, id: &str, request: &ExecRequest) -> Result<ExecResponse> {
+        let container = self.get_container(id).await?;
+        Ok(Self::canned_exec(&container, request))
+    }
+
+    /// Canned output for a few common commands; `sh -c` scripts are matched on their first word
+    fn canned_exec(container: &Container, request: &ExecRequest) -> ExecResponse {
+        let line = match request.cmd.as_slice() {
+            [shell, flag, script, ..] if shell.ends_with("sh") && flag == "-c" => script.clone(),
+            cmd => cmd.join(" "),
+        };
+        let program = line.split_whitespace().next().unwrap_or_default();
+
+        let output = |stdout: String| ExecResponse { stdout, stderr: String::new(), exit_code: 0 };
+        match program {
+            "ls" => output("bin\ndev\netc\nhome\nlib\nproc\nroot\ntmp\nusr\nvar\n".to_string()),
+            "env" => {
+                let mut env: Vec<String> = container
+                    .env
+                    .iter()
+                    .chain(&request.env)
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect();
+                env.push("PATH=/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin".to_string());
+                env.push(format!("HOSTNAME={}", container.id));
+                env.sort();
+                output(env.join("\n") + "\n")
+            }
+            "pwd" => output(format!("{}\n", request.working_dir.as_deref().unwrap_or("/"))),
+            "hostname" => output(format!("{}\n", container.id)),
+            "whoami" => output("root\n".to_string()),
+            "echo" => output(format!("{}\n", line.trim_start_matches("echo").trim())),
+            _ => ExecResponse {
+                stdout: String::new(),
+                stderr: format!("sh: {}: not found\n", program),
+                exit_code: 127,
+            },
+        }
+    }
+
+    /// Fake shell that echoes what is typed and answers commands from `canned_exec`; `exit` ends it
+    pub async fn exec_terminal(&self, id: &str, request: &ExecRequest) -> Result<TerminalSession> {
+        let container = self.get_container(id).await?;
+        if !matches!(container.status, ContainerStatus::Running) {
+            return Err(Error::Container(format!("Container is not running: {}", id)));
+        }
+
+        let (input, mut commands) = tokio::sync::mpsc::channel::<TerminalInput>(64);
+        let (output, received) = tokio::sync::mpsc::channel::<Bytes>(64);
+        let prompt = format!(
+            "root@{}:{}# ",
+            container.short_id(),
+            request.working_dir.as_deref().unwrap_or("/")
+        );
+        let request = request.clone();
+
+        tokio::spawn(async move {
+            if output.send(Bytes::from(prompt.clone())).await.is_err() {
+                return;
+            }
+
+            let mut line = String::new();
+            while let Some(command) = commands.recv().await {
+                let TerminalInput::Data(data) = command else {
+                    continue;
+                };
+
+                let mut reply = String::new();
+                let mut exited = false;
+                for c in String::from_utf8_lossy(&data).chars() {
+                    match c {
+                        '\r' | '\n' => {
+                            reply.push_str("\r\n");
+                            let typed = std::mem::take(&mut line);
+                            if typed.trim() == "exit" {
+                                exited = true;
+                                break;
+                            }
+                            if !typed.trim().is_empty() {
+                                let exec = ExecRequest {
+                                    cmd: vec!["sh".to_string(), "-c".to_string(), typed],
+                                    ..request.clone()
+                                };
+                                let result = Self::canned_exec(&container, &exec);
+                                reply.push_str(&(result.stdout + &result.stderr).replace('\n', "\r\n"));
+                            }
+                            reply.push_str(&prompt);
+                        }
+                        // Backspace: rub out the last character on screen
+                        '\x7f' | '\x08' if line.pop().is_some() => reply.push_str("\x08 \x08"),
+                        // Ctrl+C: abandon the line
+                        '\x03' => {
+                            line.clear();
+                            reply.push_str("^C\r\n");
+                            reply.push_str(&prompt);
+                        }
+                        c if !c.is_control() => {
+                            line.push(c);
+                            reply.push(c);
+                        }
+                        _ => {}
+                    }
+                }
+
+                if !reply.is_empty() && output.send(Bytes::from(reply)).await.is_err() {
+                    return;
+                }
+                if exited {
+                    return;
+                }
+            }
+        });
+
+        let output = futures::stream::unfold(received, |mut receiver| async move {
+            let data = receiver.recv().await?;
+            Some((Ok(data), receiver))
+        });
+        Ok(TerminalSession {
+            input,
+            output: Box::pin(output),
+        })
+    }
+
+    /// Mark a container as freshly started; health checks begin again from `Starting`
+    fn mark_started(container: &mut Container) {
+        container.status = ContainerStatus::Running;
+        container.started_at = Some(chrono::Utc::now());
+        container.finished_at = None;
+        if let Some(health) = &mut container.health_status {
+            health.state = HealthState::Starting;
+            health.failing_streak = 0;
+        }
+    }
+
+    fn mark_exited(container: &mut Container, code: i32) {
+        container.status = ContainerStatus::Exited { code };
+        container.finished_at = Some(chrono::Utc::now());
+    }
 
-        Ok(mock_containers)
+    /// Leave every running container exited, as a host reboot does when Bolt does not
+    /// bring them back. Bolt reports 255 for containers that died with the daemon.
+    pub async fn simulate_reboot(&self) {
+        for container in self.containers.write().await.values_mut() {
+            if matches!(
+                container.status,
+                ContainerStatus::Running | ContainerStatus::Paused | ContainerStatus::Restarting
+            ) {
+                Self::mark_exited(container, 255);
+            }
+        }
     }
 
-    pub async fn start_container(&self, _id: &str) -> Result<()> {
+    /// Starting a running container is a no-op, as with Bolt
+    pub async fn start_container(&self, id: &str) -> Result<()> {
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-        Ok(())
+        self.update_container(id, |container| {
+            match container.status {
+                ContainerStatus::Running => {}
+                ContainerStatus::Paused => {
+                    return Err(Error::Container(format!("Container is paused, unpause it instead: {}", id)));
+                }
+                _ => Self::mark_started(container),
+            }
+            Ok(())
+        })
+        .await
     }
 
-    pub async fn stop_container(&self, _id: &str, _timeout: Option<u32>) -> Result<()> {
+    /// Stopping a stopped container is a no-op, as with Bolt
+    pub async fn stop_container(&self, id: &str, _timeout: Option<u32>) -> Result<()> {
         tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
-        Ok(())
+        self.update_container(id, |container| {
+            if matches!(container.status, ContainerStatus::Running | ContainerStatus::Paused) {
+                Self::mark_exited(container, 0);
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// Polls the container map, holding its lock only for each check
+    pub async fn wait_container(&self, id: &str, condition: WaitCondition) -> Result<i32> {
+        const POLL_INTERVAL: tokio::time::Duration = tokio::time::Duration::from_millis(200);
+
+        let initial = self.get_container(id).await?;
+        let mut last_code = match initial.status {
+            ContainerStatus::Exited { code } => code,
+            _ => 0,
+        };
+
+        loop {
+            let current = self.containers.read().await.get(id).map(|c| (c.status.clone(), c.finished_at));
+            match (condition, current) {
+                (WaitCondition::Removed, None) => return Ok(last_code),
+                (_, None) => return Err(Error::Container(format!("Container was removed while waiting: {}", id))),
+                (_, Some((ContainerStatus::Exited { code }, finished_at))) => {
+                    last_code = code;
+                    let exited_since = finished_at != initial.finished_at;
+                    match condition {
+                        WaitCondition::NotRunning => return Ok(code),
+                        WaitCondition::NextExit if exited_since => return Ok(code),
+                        _ => {}
+                    }
+                }
+                (WaitCondition::NotRunning, Some((ContainerStatus::Created | ContainerStatus::Dead, _))) => {
+                    return Ok(last_code)
+                }
+                _ => {}
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
     }
 
-    pub async fn restart_container(&self, _id: &str, _timeout: Option<u32>) -> Result<()> {
+    pub async fn restart_container(&self, id: &str, _timeout: Option<u32>) -> Result<()> {
         tokio::time::sleep(tokio::time::Duration::from_millis(1500)).await;
-        Ok(())
+        self.update_container(id, |container| {
+            Self::mark_started(container);
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn pause_container(&self, id: &str) -> Result<()> {
+        self.update_container(id, |container| {
+            if !matches!(container.status, ContainerStatus::Running) {
+                return Err(Error::Container(format!("Container is not running: {}", id)));
+            }
+            container.status = ContainerStatus::Paused;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn unpause_container(&self, id: &str) -> Result<()> {
+        self.update_container(id, |container| {
+            if !matches!(container.status, ContainerStatus::Paused) {
+                return Err(Error::Container(format!("Container is not paused: {}", id)));
+            }
+            container.status = ContainerStatus::Running;
+            Ok(())
+        })
+        .await
+    }
+
+    /// SIGKILL and SIGTERM stop the mock container; other signals leave it running
+    pub async fn kill_container(&self, id: &str, signal: Option<&str>) -> Result<()> {
+        self.update_container(id, |container| {
+            if !matches!(container.status, ContainerStatus::Running) {
+                return Err(Error::Container(format!("Container is not running: {}", id)));
+            }
+
+            match signal.unwrap_or("SIGKILL") {
+                "SIGKILL" | "9" => Self::mark_exited(container, 137),
+                "SIGTERM" | "15" => Self::mark_exited(container, 143),
+                _ => {}
+            }
+            Ok(())
+        })
+        .await
     }
 
-    pub async fn remove_container(&self, _id: &str, _force: bool, _remove_volumes: bool) -> Result<()> {
+    /// Running containers are only removed with `force`
+    pub async fn remove_container(&self, id: &str, force: bool, _remove_volumes: bool) -> Result<()> {
         tokio::time::sleep(tokio::time::Duration::from_millis(800)).await;
+        let mut containers = self.containers.write().await;
+        let container = containers
+            .get(id)
+            .ok_or_else(|| Error::NotFound(format!("Container not found: {}", id)))?;
+
+        if !force && matches!(container.status, ContainerStatus::Running | ContainerStatus::Paused) {
+            return Err(Error::Container(format!("Container is running, stop it first or force removal: {}", id)));
+        }
+
+        containers.remove(id);
+        self.uploads.write().await.remove(id);
+        Ok(())
+    }
+
+    pub async fn rename_container(&self, id: &str, new_name: &str) -> Result<()> {
+        self.update_container(id, |container| {
+            container.name = new_name.to_string();
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn list_images(&self) -> Result<Vec<LocalImage>> {
+        let images = self.images.read().await;
+        let mut list: Vec<LocalImage> = images.values().cloned().collect();
+        list.sort_by_key(|image| std::cmp::Reverse(image.created));
+        Ok(list)
+    }
+
+    /// Record a pulled image so it shows up in the local image list
+    pub async fn pull_image(&self, reference: &str) -> Result<()> {
+        tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
+
+        let mut images = self.images.write().await;
+
+        if images.values().any(|image| image.matches_reference(reference)) {
+            return Ok(());
+        }
+
+        let id = format!("sha256:{:x}", Sha256::digest(reference.as_bytes()));
+        images.insert(id.clone(), LocalImage {
+            id,
+            repo_tags: vec![reference.to_string()],
+            size: 64 * 1024 * 1024,
+            shared_size: 0,
+            created: chrono::Utc::now(),
+            dangling: false,
+            labels: HashMap::new(),
+            containers: Vec::new(),
+        });
+
+        Ok(())
+    }
+
+    pub async fn remove_image(&self, id: &str, _force: bool) -> Result<()> {
+        let mut images = self.images.write().await;
+
+        let key = images
+            .values()
+            .find(|image| image.matches_reference(id))
+            .map(|image| image.id.clone());
+
+        match key {
+            Some(key) => {
+                images.remove(&key);
+                Ok(())
+            }
+            None => Err(Error::NotFound(format!("Image not found: {}", id))),
+        }
+    }
+
+    pub async fn tag_image(&self, id: &str, repository: &str, tag: &str) -> Result<()> {
+        let mut images = self.images.write().await;
+        let target = format!("{}:{}", repository, tag);
+
+        let key = images
+            .values()
+            .find(|image| image.matches_reference(id))
+            .map(|image| image.id.clone())
+            .ok_or_else(|| Error::NotFound(format!("Image not found: {}", id)))?;
+
+        for image in images.values_mut() {
+            image.repo_tags.retain(|existing| existing != &target);
+            image.dangling = image.repo_tags.is_empty();
+        }
+
+        if let Some(image) = images.get_mut(&key) {
+            image.repo_tags.push(target);
+            image.dangling = false;
+        }
+
+        Ok(())
+    }
+
+    pub async fn prune_images(&self, filter: &ImagePruneFilter) -> Result<ImagePruneReport> {
+        let containers = self.list_containers(None).await?;
+        let mut images = self.images.write().await;
+
+        let candidates: Vec<String> = images
+            .values()
+            .filter(|image| {
+                let in_use = containers.iter().any(|c| image.matches_reference(&c.image));
+                filter.matches(image, in_use)
+            })
+            .map(|image| image.id.clone())
+            .collect();
+
+        let mut report = ImagePruneReport::default();
+        for id in candidates {
+            if let Some(image) = images.remove(&id) {
+                report.reclaimed_bytes += image.unique_size();
+                report.deleted.push(id);
+            }
+        }
+
+        Ok(report)
+    }
+
+    pub async fn list_networks(&self) -> Result<Vec<Network>> {
+        let mut networks: Vec<Network> = self.networks.read().await.values().cloned().collect();
+        networks.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(networks)
+    }
+
+    pub async fn create_network(&self, request: CreateNetworkRequest) -> Result<Network> {
+        let mut networks = self.networks.write().await;
+
+        if networks.values().any(|n| n.name == request.name) {
+            return Err(Error::Conflict(format!("Network {} already exists", request.name)));
+        }
+
+        let mut driver_options = request.driver_options;
+        if request.gaming {
+            driver_options.extend(gaming_driver_options());
+        }
+
+        let network = Network {
+            id: Self::network_id(&request.name),
+            name: request.name,
+            driver: request.driver.unwrap_or_else(|| "bridge".to_string()),
+            subnet: request.subnet,
+            gateway: request.gateway,
+            internal: request.internal,
+            driver_options,
+            labels: request.labels,
+            containers: Vec::new(),
+            created: chrono::Utc::now(),
+        };
+
+        networks.insert(network.id.clone(), network.clone());
+        Ok(network)
+    }
+
+    pub async fn remove_network(&self, id: &str) -> Result<()> {
+        match self.networks.write().await.remove(id) {
+            Some(_) => Ok(()),
+            None => Err(Error::NotFound(format!("Network not found: {}", id))),
+        }
+    }
+
+    pub async fn connect_network(&self, id: &str, request: &NetworkConnectRequest) -> Result<()> {
+        let mut networks = self.networks.write().await;
+        let network = networks
+            .get_mut(id)
+            .ok_or_else(|| Error::NotFound(format!("Network not found: {}", id)))?;
+
+        if !network.containers.contains(&request.container_id) {
+            network.containers.push(request.container_id.clone());
+        }
+        Ok(())
+    }
+
+    pub async fn disconnect_network(&self, id: &str, request: &NetworkConnectRequest) -> Result<()> {
+        let mut networks = self.networks.write().await;
+        let network = networks
+            .get_mut(id)
+            .ok_or_else(|| Error::NotFound(format!("Network not found: {}", id)))?;
+
+        network.containers.retain(|c| c != &request.container_id);
+        Ok(())
+    }
+
+    pub async fn list_volumes(&self) -> Result<Vec<Volume>> {
+        let mut volumes: Vec<Volume> = self.volumes.read().await.values().cloned().collect();
+        volumes.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(volumes)
+    }
+
+    pub async fn inspect_volume(&self, name: &str) -> Result<Option<Volume>> {
+        Ok(self.volumes.read().await.get(name).cloned())
+    }
+
+    pub async fn create_volume(&self, request: CreateVolumeRequest) -> Result<Volume> {
+        let mut volumes = self.volumes.write().await;
+
+        if volumes.contains_key(&request.name) {
+            return Err(Error::Conflict(format!("Volume {} already exists", request.name)));
+        }
+
+        let volume = Volume {
+            mountpoint: format!("/var/lib/bolt/volumes/{}/_data", request.name),
+            name: request.name,
+            driver: request.driver.unwrap_or_else(|| "local".to_string()),
+            labels: request.labels,
+            size: Some(0),
+            created: chrono::Utc::now(),
+            containers: Vec::new(),
+        };
+
+        volumes.insert(volume.name.clone(), volume.clone());
+        Ok(volume)
+    }
+
+    pub async fn remove_volume(&self, name: &str, _force: bool) -> Result<()> {
+        match self.volumes.write().await.remove(name) {
+            Some(_) => Ok(()),
+            None => Err(Error::NotFound(format!("Volume not found: {}", name))),
+        }
+    }
+
+    pub async fn prune_volumes(&self, filter: &VolumePruneFilter) -> Result<VolumePruneReport> {
+        let containers = self.list_containers(None).await?;
+        let mut volumes = self.volumes.write().await;
+
+        let candidates: Vec<String> = volumes
+            .values()
+            .filter(|volume| {
+                let in_use = containers.iter().any(|c| volume.is_mounted_by(c));
+                filter.matches(volume, in_use)
+            })
+            .map(|volume| volume.name.clone())
+            .collect();
+
+        let mut report = VolumePruneReport::default();
+        for name in candidates {
+            if let Some(volume) = volumes.remove(&name) {
+                report.reclaimed_bytes += volume.size.unwrap_or(0);
+                report.deleted.push(name);
+            }
+        }
+
+        Ok(report)
+    }
+
+    pub async fn stat_volume_path(&self, name: &str, path: &str) -> Result<Option<VolumeEntry>> {
+        let Some(volume) = self.volumes.read().await.get(name).cloned() else {
+            return Ok(None);
+        };
+
+        let entry = |entry_type, size| VolumeEntry {
+            name: path.rsplit('/').next().unwrap_or_default().to_string(),
+            path: path.to_string(),
+            entry_type,
+            size,
+            modified: volume.created,
+            mode: if entry_type == VolumeEntryType::Directory { 0o755 } else { 0o644 },
+        };
+
+        if path == "/" {
+            return Ok(Some(entry(VolumeEntryType::Directory, 0)));
+        }
+
+        let files = Self::volume_files(name);
+        if let Some((_, size, content)) = files.iter().find(|(file, _, _)| *file == path) {
+            let size = content.map(|c| c.len() as u64).unwrap_or(*size);
+            return Ok(Some(entry(VolumeEntryType::File, size)));
+        }
+
+        let prefix = format!("{}/", path);
+        if files.iter().any(|(file, _, _)| file.starts_with(&prefix)) {
+            return Ok(Some(entry(VolumeEntryType::Directory, 0)));
+        }
+
+        Ok(None)
+    }
+
+    pub async fn list_volume_directory(&self, name: &str, path: &str) -> Result<Vec<VolumeEntry>> {
+        let prefix = if path == "/" { "/".to_string() } else { format!("{}/", path) };
+        let mut entries: Vec<VolumeEntry> = Vec::new();
+
+        for (file, _, _) in Self::volume_files(name) {
+            let Some(rest) = file.strip_prefix(&prefix) else {
+                continue;
+            };
+            let child = rest.split('/').next().unwrap_or(rest);
+            let child_path = format!("{}{}", prefix, child);
+
+            if entries.iter().any(|e| e.path == child_path) {
+                continue;
+            }
+            if let Some(entry) = self.stat_volume_path(name, &child_path).await? {
+                entries.push(entry);
+            }
+        }
+
+        entries.sort_by(|a, b| {
+            (a.entry_type != VolumeEntryType::Directory, &a.name)
+                .cmp(&(b.entry_type != VolumeEntryType::Directory, &b.name))
+        });
+        Ok(entries)
+    }
+
+    pub async fn read_volume_file(&self, name: &str, path: &str) -> Result<VolumeFileStream> {
+        const CHUNK_SIZE: u64 = 64 * 1024;
+
+        let (_, size, content) = Self::volume_files(name)
+            .into_iter()
+            .find(|(file, _, _)| *file == path)
+            .ok_or_else(|| Error::NotFound(format!("File not found: {}", path)))?;
+
+        if let Some(content) = content {
+            let chunk: std::io::Result<Bytes> = Ok(Bytes::from_static(content.as_bytes()));
+            return Ok(Box::pin(futures::stream::iter([chunk])));
+        }
+
+        let chunks = (0..size).step_by(CHUNK_SIZE as usize).map(move |offset| {
+            Ok(Bytes::from(vec![0u8; CHUNK_SIZE.min(size - offset) as usize]))
+        });
+        Ok(Box::pin(futures::stream::iter(chunks)))
+    }
+
+    pub async fn export_container(&self, id: &str) -> Result<VolumeFileStream> {
+        self.get_archive(id, "/").await
+    }
+
+    /// Snapshot the container as an image a little larger than the one it runs
+    pub async fn commit_container(&self, id: &str, request: &ContainerCommitRequest) -> Result<ImageSummary> {
+        let container = self.get_container(id).await?;
+        let pause = request.pause.unwrap_or(true) && matches!(container.status, ContainerStatus::Running);
+        if pause {
+            self.pause_container(id).await?;
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+        let created = chrono::Utc::now();
+        let digest = format!(
+            "sha256:{:x}",
+            Sha256::digest(format!("{}:{}:{}", id, request.repository, created.timestamp_nanos_opt().unwrap_or_default()))
+        );
+        let base_size = self
+            .images
+            .read()
+            .await
+            .values()
+            .find(|image| image.matches_reference(&container.image))
+            .map(|image| image.size)
+            .unwrap_or_default();
+
+        let labels = request
+            .comment
+            .iter()
+            .map(|comment| ("gpanel.commit.comment".to_string(), comment.clone()))
+            .collect();
+        let image = LocalImage {
+            id: digest.clone(),
+            repo_tags: Vec::new(),
+            size: base_size + 4 * 1024 * 1024,
+            shared_size: base_size,
+            created,
+            dangling: true,
+            labels,
+            containers: Vec::new(),
+        };
+        self.images.write().await.insert(image.id.clone(), image.clone());
+
+        let tag = request.tag.as_deref().unwrap_or("latest");
+        let tagged = self.tag_image(&digest, &request.repository, tag).await;
+        if pause {
+            self.unpause_container(id).await?;
+        }
+        tagged?;
+
+        info!("Committed mock container {} as {}:{}", id, request.repository, tag);
+        Ok(ImageSummary {
+            id: digest.clone(),
+            digest,
+            repo_tags: vec![format!("{}:{}", request.repository, tag)],
+            size: image.size,
+            created,
+        })
+    }
+
+    pub async fn stat_container_path(&self, id: &str, path: &str) -> Result<Option<VolumeEntry>> {
+        let container = self.get_container(id).await?;
+        let files = self.container_files(id).await?;
+
+        let entry = |entry_type, size| VolumeEntry {
+            name: path.rsplit('/').next().unwrap_or_default().to_string(),
+            path: path.to_string(),
+            entry_type,
+            size,
+            modified: container.created_at,
+            mode: if entry_type == VolumeEntryType::Directory { 0o755 } else { 0o644 },
+        };
+
+        if path == "/" {
+            return Ok(Some(entry(VolumeEntryType::Directory, 0)));
+        }
+        if let Some(content) = files.get(path) {
+            return Ok(Some(entry(VolumeEntryType::File, content.len() as u64)));
+        }
+
+        let prefix = format!("{}/", path);
+        if files.keys().any(|file| file.starts_with(&prefix)) {
+            return Ok(Some(entry(VolumeEntryType::Directory, 0)));
+        }
+
+        Ok(None)
+    }
+
+    pub async fn read_container_file(&self, id: &str, path: &str) -> Result<VolumeFileStream> {
+        let content = self
+            .container_files(id)
+            .await?
+            .remove(path)
+            .ok_or_else(|| Error::NotFound(format!("File not found: {}", path)))?;
+
+        let chunk: std::io::Result<Bytes> = Ok(content);
+        Ok(Box::pin(futures::stream::iter([chunk])))
+    }
+
+    /// Entries are named relative to the parent of `path`, as `docker cp` does
+    pub async fn get_archive(&self, id: &str, path: &str) -> Result<VolumeFileStream> {
+        let files = self.container_files(id).await?;
+        let parent = match path.rfind('/') {
+            Some(0) | None => "/",
+            Some(index) => &path[..index],
+        };
+        let prefix = if path == "/" { "/".to_string() } else { format!("{}/", path) };
+
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut found = false;
+        for (file, content) in files.iter().filter(|(file, _)| *file == path || file.starts_with(&prefix)) {
+            let name = file.strip_prefix(parent).unwrap_or(file).trim_start_matches('/');
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+            builder.append_data(&mut header, name, content.as_ref())?;
+            found = true;
+        }
+
+        if !found {
+            return Err(Error::NotFound(format!("Path not found: {}", path)));
+        }
+
+        let chunk: std::io::Result<Bytes> = Ok(Bytes::from(builder.into_inner()?));
+        Ok(Box::pin(futures::stream::iter([chunk])))
+    }
+
+    /// Regular files in the archive are kept in memory; other entry types are skipped
+    pub async fn put_archive(&self, id: &str, path: &str, mut archive: VolumeFileStream) -> Result<()> {
+        match self.stat_container_path(id, path).await? {
+            Some(entry) if entry.entry_type == VolumeEntryType::Directory => {}
+            _ => return Err(Error::NotFound(format!("Directory not found: {}", path))),
+        }
+
+        let mut data = Vec::new();
+        while let Some(chunk) = archive.next().await {
+            data.extend_from_slice(&chunk?);
+        }
+
+        let mut extracted = BTreeMap::new();
+        for entry in tar::Archive::new(data.as_slice()).entries()? {
+            let mut entry = entry?;
+            if entry.header().entry_type() != tar::EntryType::Regular {
+                continue;
+            }
+            let name = entry.path()?.to_string_lossy().trim_start_matches('/').to_string();
+            let target = crate::volume::normalize_volume_path(&format!("{}/{}", path, name))
+                .ok_or_else(|| Error::Invalid(format!("Archive entry escapes {}: {}", path, name)))?;
+
+            let mut content = Vec::new();
+            std::io::Read::read_to_end(&mut entry, &mut content)?;
+            extracted.insert(target, Bytes::from(content));
+        }
+
+        info!("Extracted {} file(s) into {} in mock container {}", extracted.len(), path, id);
+        self.uploads.write().await.entry(id.to_string()).or_default().extend(extracted);
         Ok(())
     }
 
+    /// A typical gaming host; the counts follow the mock's containers and images
+    pub async fn system_info(&self) -> Result<BoltSystemInfo> {
+        let containers = self.containers.read().await;
+        let count = |matches: fn(&ContainerStatus) -> bool| {
+            containers.values().filter(|c| matches(&c.status)).count() as u32
+        };
+
+        Ok(BoltSystemInfo {
+            version: "0.4.2".to_string(),
+            api_version: "1.2".to_string(),
+            runtime: "bolt (mock)".to_string(),
+            kernel_version: "6.8.0-45-generic".to_string(),
+            os: "Ubuntu 24.04.1 LTS".to_string(),
+            architecture: std::env::consts::ARCH.to_string(),
+            cpus: 16,
+            memory_total: 32 * 1024 * 1024 * 1024,
+            storage_driver: "overlay2".to_string(),
+            containers_running: count(|s| matches!(s, ContainerStatus::Running | ContainerStatus::Restarting)),
+            containers_paused: count(|s| matches!(s, ContainerStatus::Paused)),
+            containers_stopped: count(|s| s.is_stopped()),
+            images_count: self.images.read().await.len() as u32,
+        })
+    }
+
+    pub async fn disk_usage(&self) -> Result<BoltDiskUsage> {
+        const MB: u64 = 1024 * 1024;
+
+        let container_layers = self
+            .list_containers(None)
+            .await?
+            .into_iter()
+            .map(|c| {
+                let size = match c.id.as_str() {
+                    "mock_gaming_container_002" => 1536 * MB,
+                    "mock_database_003" => 48 * MB,
+                    _ => 12 * MB,
+                };
+                (c.id, size)
+            })
+            .collect();
+
+        Ok(BoltDiskUsage {
+            container_layers,
+            build_cache: Some(BuildCacheUsage {
+                total_bytes: 1200 * MB,
+                reclaimable_bytes: 800 * MB,
+            }),
+        })
+    }
+
     pub async fn get_container_logs(&self, _request: ContainerLogsRequest) -> Result<String> {
         let mock_logs = r#"2024-01-15 10:30:00 [INFO] Container started successfully
 2024-01-15 10:30:01 [INFO] Initializing application
@@ -572,10 +2607,291 @@ impl MockBoltClient {
 
         Ok(mock_logs.to_string())
     }
+
+    /// The tail of the canned log, then a fake request line every second
+    pub async fn follow_container_logs(&self, request: ContainerLogsRequest) -> Result<LogLineStream> {
+        self.get_container(&request.container_id).await?;
+
+        let logs = self.get_container_logs(request.clone()).await?;
+        let lines: Vec<String> = logs.lines().map(str::to_string).collect();
+        let skip = match request.tail {
+            Some(tail) => lines.len().saturating_sub(tail as usize),
+            None => 0,
+        };
+        let backlog = futures::stream::iter(lines.into_iter().skip(skip).map(Ok));
+
+        let live = futures::stream::unfold(1u64, |n| async move {
+            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+            let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S");
+            Some((Ok(format!("{} [INFO] Processed request #{}: GET /health", now, n)), n + 1))
+        });
+
+        Ok(Box::pin(backlog.chain(live)))
+    }
 }
 
 impl Default for MockBoltClient {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn mock_prune_dangling_only_removes_untagged_images() {
+        let mock = MockBoltClient::new();
+        let report = mock
+            .prune_images(&ImagePruneFilter {
+                dangling_only: true,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(report.deleted.len(), 1);
+        assert!(report.deleted[0].starts_with("sha256:e5f6"));
+        assert_eq!(report.reclaimed_bytes, 80 * 1024 * 1024);
+        assert!(mock.list_images().await.unwrap().iter().all(|image| !image.dangling));
+    }
+
+    #[tokio::test]
+    async fn mock_prune_keeps_images_used_by_containers() {
+        let mock = MockBoltClient::new();
+        let report = mock.prune_images(&ImagePruneFilter::default()).await.unwrap();
+
+        let mut deleted = report.deleted.clone();
+        deleted.sort();
+        assert_eq!(deleted.len(), 2);
+        assert!(deleted[0].starts_with("sha256:d4e5"), "unused redis image is pruned");
+        assert!(deleted[1].starts_with("sha256:e5f6"), "dangling image is pruned");
+
+        // Nothing is left to prune the second time
+        let again = mock.prune_images(&ImagePruneFilter::default()).await.unwrap();
+        assert!(again.deleted.is_empty());
+        assert_eq!(again.reclaimed_bytes, 0);
+    }
+
+    #[tokio::test]
+    async fn mock_prune_honors_label_and_age() {
+        let mock = MockBoltClient::new();
+        let by_label = mock
+            .prune_images(&ImagePruneFilter {
+                label: Some("gpanel.build=other".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(by_label.deleted.is_empty());
+
+        let older_than_a_week = mock
+            .prune_images(&ImagePruneFilter {
+                until: Some(chrono::Utc::now() - chrono::Duration::days(7)),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(older_than_a_week.deleted.len(), 1);
+        assert!(older_than_a_week.deleted[0].starts_with("sha256:d4e5"));
+    }
+
+    #[tokio::test]
+    async fn mock_container_lifecycle() {
+        let mock = MockBoltClient::new();
+        let container = mock
+            .create_container(CreateContainerRequest {
+                name: Some("lifecycle".to_string()),
+                image: "nginx:latest".to_string(),
+                ports: Vec::new(),
+                volumes: Vec::new(),
+                networks: Vec::new(),
+                env: HashMap::new(),
+                labels: HashMap::new(),
+                gaming_config: None,
+                gpu_allocation: None,
+                restart_policy: RestartPolicy::No,
+                health: None,
+            })
+            .await
+            .unwrap();
+        assert!(matches!(container.status, ContainerStatus::Created));
+        let listed = mock.list_containers(None).await.unwrap();
+        assert_eq!(listed[0].id, container.id, "newest container is listed first");
+
+        mock.start_container(&container.id).await.unwrap();
+        let running = mock.get_container(&container.id).await.unwrap();
+        assert!(matches!(running.status, ContainerStatus::Running));
+        assert!(running.started_at.is_some());
+        assert!(mock.remove_container(&container.id, false, false).await.is_err());
+
+        mock.stop_container(&container.id, None).await.unwrap();
+        let stopped = mock.get_container(&container.id).await.unwrap();
+        assert!(matches!(stopped.status, ContainerStatus::Exited { code: 0 }));
+        assert!(stopped.finished_at.is_some());
+
+        mock.remove_container(&container.id, false, false).await.unwrap();
+        assert!(mock.get_container(&container.id).await.is_err());
+        assert!(mock.start_container(&container.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn mock_pause_and_forced_removal() {
+        let mock = MockBoltClient::new();
+        let id = "mock_web_server_001";
+
+        mock.pause_container(id).await.unwrap();
+        assert!(matches!(mock.get_container(id).await.unwrap().status, ContainerStatus::Paused));
+        assert!(mock.start_container(id).await.is_err());
+        assert!(mock.pause_container(id).await.is_err());
+
+        mock.unpause_container(id).await.unwrap();
+        assert!(matches!(mock.get_container(id).await.unwrap().status, ContainerStatus::Running));
+
+        mock.remove_container(id, true, false).await.unwrap();
+        assert!(mock.list_containers(None).await.unwrap().iter().all(|c| c.id != id));
+    }
+
+    /// Answer one HTTP request on `stream` with `status` and a JSON `body`, then close
+    async fn answer<S>(mut stream: S, status: u16, body: &str)
+    where
+        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut request = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+            match stream.read(&mut buf).await {
+                Ok(0) | Err(_) => return,
+                Ok(n) => request.extend_from_slice(&buf[..n]),
+            }
+        }
+        let response = format!(
+            "HTTP/1.1 {} Stub\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+        let _ = stream.shutdown().await;
+    }
+
+    fn empty_list() -> String {
+        serde_json::json!({
+            "success": true,
+            "data": [],
+            "error": null,
+            "timestamp": chrono::Utc::now(),
+        })
+        .to_string()
+    }
+
+    /// A Bolt on a local port answering 503 to the first `failures` requests and an empty
+    /// list after that. Returns its URL and the number of requests so far.
+    async fn flaky_bolt(failures: usize) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = std::sync::Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let seen = counter.fetch_add(1, Ordering::SeqCst);
+                if seen < failures {
+                    answer(stream, 503, r#"{"error":"starting"}"#).await;
+                } else {
+                    answer(stream, 200, &empty_list()).await;
+                }
+            }
+        });
+        (url, requests)
+    }
+
+    fn quick_retries(retries: u32) -> BoltClientConfig {
+        BoltClientConfig {
+            retries,
+            backoff_base_ms: 1,
+            ..Default::default()
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn client_talks_to_bolt_over_a_unix_socket() {
+        let path = std::env::temp_dir().join(format!("gpanel-bolt-test-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                answer(stream, 200, &empty_list()).await;
+            }
+        });
+
+        let client = BoltClient::new(&format!("unix://{}", path.display())).unwrap();
+        assert_eq!(client.transport(), &BoltTransport::Unix(path.clone()));
+        assert!(client.ping().await.unwrap());
+        assert!(client.list_containers(None).await.unwrap().is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn unix_socket_url_needs_an_absolute_path() {
+        let Err(error) = BoltClient::new("unix://run/bolt.sock") else {
+            panic!("relative path is accepted");
+        };
+        assert!(error.to_string().contains("absolute path"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn reads_retry_until_bolt_recovers() {
+        use std::sync::atomic::Ordering;
+
+        let (url, requests) = flaky_bolt(2).await;
+        let client = BoltClient::with_config(&url, quick_retries(3)).unwrap();
+        assert!(client.list_containers(None).await.unwrap().is_empty());
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn reads_give_up_after_the_configured_retries() {
+        use std::sync::atomic::Ordering;
+
+        let (url, requests) = flaky_bolt(usize::MAX).await;
+        let client = BoltClient::with_config(&url, quick_retries(2)).unwrap();
+        assert!(client.list_containers(None).await.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 3, "one attempt plus two retries");
+    }
+
+    #[tokio::test]
+    async fn requests_go_through_the_proxy_unless_no_proxy_matches() {
+        use std::sync::atomic::Ordering;
+
+        let (proxy_url, proxied) = flaky_bolt(0).await;
+        let (bolt_url, direct) = flaky_bolt(0).await;
+        let mut proxy = ProxyConfig {
+            http_proxy: Some(proxy_url),
+            ..Default::default()
+        };
+        let client = BoltClient::with_proxy("bolt://bolt.example.com:8080", quick_retries(0), &proxy).unwrap();
+        assert!(client.list_containers(None).await.unwrap().is_empty());
+        assert_eq!((proxied.load(Ordering::SeqCst), direct.load(Ordering::SeqCst)), (1, 0));
+
+        proxy.no_proxy = vec!["127.0.0.1".to_string()];
+        let client = BoltClient::with_proxy(&bolt_url, quick_retries(0), &proxy).unwrap();
+        assert!(client.list_containers(None).await.unwrap().is_empty());
+        assert_eq!((proxied.load(Ordering::SeqCst), direct.load(Ordering::SeqCst)), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn writes_are_never_retried() {
+        use std::sync::atomic::Ordering;
+
+        let (url, requests) = flaky_bolt(1).await;
+        let client = BoltClient::with_config(&url, quick_retries(3)).unwrap();
+        assert!(client.start_container("web").await.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,8 +1,16 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use crate::gaming::GamingProfile;
+use crate::volume::is_valid_volume_name;
 
 /// Container information structure matching Bolt's container model
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Container {
     pub id: String,
     pub name: String,
@@ -16,6 +24,11 @@ pub struct Container {
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub started_at: Option<chrono::DateTime<chrono::Utc>>,
     pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Result of the container's health check, if it has one
+    #[serde(default)]
+    pub health_status: Option<HealthStatus>,
+    #[serde(default)]
+    pub restart_policy: RestartPolicy,
 
     // Gaming-specific fields
     pub gaming_config: Option<GamingConfig>,
@@ -23,7 +36,27 @@ pub struct Container {
     pub performance_metrics: Option<PerformanceMetrics>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Container {
+    /// Abbreviated ID for display
+    pub fn short_id(&self) -> &str {
+        short_id(&self.id)
+    }
+}
+
+/// Characters kept when abbreviating container IDs and digests, as `docker ps` does
+pub const SHORT_ID_LEN: usize = 12;
+
+/// First `SHORT_ID_LEN` characters of an ID or digest, or all of it when shorter.
+/// Bolt IDs are not always 12 ASCII bytes long, so never slice them by byte offset.
+pub fn short_id(id: &str) -> &str {
+    match id.char_indices().nth(SHORT_ID_LEN) {
+        Some((end, _)) => &id[..end],
+        None => id,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum ContainerStatus {
     Created,
     Running,
@@ -34,7 +67,77 @@ pub enum ContainerStatus {
     Unknown,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ContainerStatus {
+    /// Check whether the container is stopped and could be removed by a prune
+    pub fn is_stopped(&self) -> bool {
+        matches!(self, ContainerStatus::Created | ContainerStatus::Exited { .. } | ContainerStatus::Dead)
+    }
+}
+
+impl std::fmt::Display for ContainerStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContainerStatus::Created => write!(f, "Created"),
+            ContainerStatus::Running => write!(f, "Running"),
+            ContainerStatus::Paused => write!(f, "Paused"),
+            ContainerStatus::Restarting => write!(f, "Restarting"),
+            ContainerStatus::Exited { code } => write!(f, "Exited ({})", code),
+            ContainerStatus::Dead => write!(f, "Dead"),
+            ContainerStatus::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+/// Command run periodically inside a container to decide whether it is healthy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HealthCheck {
+    /// Command and arguments, e.g. `["sh", "-c", "curl -f http://localhost/"]`
+    pub command: Vec<String>,
+    /// Seconds between checks, 30 when left out
+    #[serde(default = "default_health_interval_secs")]
+    pub interval_secs: u64,
+    /// Seconds a check may run before it counts as failed, 5 when left out
+    #[serde(default = "default_health_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Consecutive failures before the container is reported unhealthy, 3 when left out
+    #[serde(default = "default_health_retries")]
+    pub retries: u32,
+    /// Time after start during which failures are not counted
+    #[serde(default)]
+    pub start_period_secs: u64,
+}
+
+fn default_health_interval_secs() -> u64 {
+    30
+}
+
+fn default_health_timeout_secs() -> u64 {
+    5
+}
+
+fn default_health_retries() -> u32 {
+    3
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum HealthState {
+    Starting,
+    Healthy,
+    Unhealthy,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HealthStatus {
+    pub state: HealthState,
+    /// Consecutive failed checks; reset by a passing one
+    pub failing_streak: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct PortMapping {
     pub container_port: u16,
     pub host_port: Option<u16>,
@@ -42,14 +145,137 @@ pub struct PortMapping {
     pub host_ip: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum Protocol {
     Tcp,
     Udp,
     Quic, // QUIC protocol support
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Lower case, as in `8080:80/udp`
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Protocol::Tcp => write!(f, "tcp"),
+            Protocol::Udp => write!(f, "udp"),
+            Protocol::Quic => write!(f, "quic"),
+        }
+    }
+}
+
+impl FromStr for Protocol {
+    type Err = Error;
+
+    /// `tcp`, `udp` or `quic`, in any case
+    fn from_str(protocol: &str) -> Result<Self> {
+        match protocol.to_ascii_lowercase().as_str() {
+            "tcp" => Ok(Protocol::Tcp),
+            "udp" => Ok(Protocol::Udp),
+            "quic" => Ok(Protocol::Quic),
+            _ => Err(Error::Invalid(format!("unknown protocol {:?}, expected tcp, udp or quic", protocol))),
+        }
+    }
+}
+
+impl FromStr for PortMapping {
+    type Err = Error;
+
+    /// Docker's `-p` syntax, `[[host_ip:][host_port]:]container_port[/protocol]`: `80`,
+    /// `8080:80`, `127.0.0.1:8080:80/udp`, `127.0.0.1::80` for any free host port on that
+    /// address, or `[::1]:8080:80` for IPv6. Port ranges are not supported.
+    fn from_str(spec: &str) -> Result<Self> {
+        let invalid = |reason: String| Error::Invalid(format!("invalid port mapping {:?}: {}", spec, reason));
+        let (ports, protocol) = match spec.split_once('/') {
+            Some((ports, protocol)) => (ports, protocol.parse().map_err(|e: Error| invalid(e.to_string()))?),
+            None => (spec, Protocol::Tcp),
+        };
+
+        // An IPv6 address has colons of its own, so it must be bracketed
+        let (host_ip, ports) = match ports.strip_prefix('[') {
+            Some(bracketed) => {
+                let (ip, rest) = bracketed
+                    .split_once(']')
+                    .ok_or_else(|| invalid("missing ']' after the IPv6 address".to_string()))?;
+                let rest = rest
+                    .strip_prefix(':')
+                    .ok_or_else(|| invalid("expected ':' after the host address".to_string()))?;
+                (Some(ip), rest)
+            }
+            None => (None, ports),
+        };
+        let parts: Vec<&str> = ports.split(':').collect();
+        let (host_ip, host_port, container_port) = match (host_ip, parts.as_slice()) {
+            (None, [container]) => (None, None, *container),
+            (None, [host, container]) => (None, Some(*host), *container),
+            (None, [ip, "", container]) => (Some(*ip), None, *container),
+            (None, [ip, host, container]) => (Some(*ip), Some(*host), *container),
+            (Some(ip), ["", container]) => (Some(ip), None, *container),
+            (Some(ip), [host, container]) => (Some(ip), Some(*host), *container),
+            (None, _) => {
+                return Err(invalid(
+                    "too many ':'; put an IPv6 address in brackets, e.g. [::1]:8080:80".to_string(),
+                ));
+            }
+            (Some(_), _) => return Err(invalid("expected host_port:container_port after the address".to_string())),
+        };
+
+        let host_ip = host_ip
+            .map(|ip| {
+                ip.parse::<IpAddr>()
+                    .map(|ip| ip.to_string())
+                    .map_err(|_| invalid(format!("{:?} is not an IP address", ip)))
+            })
+            .transpose()?;
+        Ok(PortMapping {
+            container_port: parse_port(container_port, "container port").map_err(invalid)?,
+            host_port: host_port.map(|port| parse_port(port, "host port")).transpose().map_err(invalid)?,
+            protocol,
+            host_ip,
+        })
+    }
+}
+
+/// A port number from 1 to 65535
+fn parse_port(port: &str, name: &str) -> std::result::Result<u16, String> {
+    if port.is_empty() {
+        return Err(format!("the {} is missing", name));
+    }
+    if port.contains('-') {
+        return Err(format!("the {} {:?} is a range, which is not supported", name, port));
+    }
+    if !port.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("the {} {:?} is not a number", name, port));
+    }
+    match port.parse::<u16>() {
+        Ok(port) if port > 0 => Ok(port),
+        _ => Err(format!("the {} {} is not between 1 and 65535", name, port)),
+    }
+}
+
+/// The form [`PortMapping::from_str`] reads, leaving out `/tcp`
+impl fmt::Display for PortMapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.host_ip, self.host_port) {
+            (Some(ip), _) if ip.contains(':') => write!(f, "[{}]:", ip)?,
+            (Some(ip), _) => write!(f, "{}:", ip)?,
+            _ => {}
+        }
+        match (&self.host_ip, self.host_port) {
+            (_, Some(port)) => write!(f, "{}:", port)?,
+            (Some(_), None) => write!(f, ":")?,
+            (None, None) => {}
+        }
+        write!(f, "{}", self.container_port)?;
+        if self.protocol != Protocol::Tcp {
+            write!(f, "/{}", self.protocol)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct VolumeMount {
     pub source: String,
     pub target: String,
@@ -57,25 +283,116 @@ pub struct VolumeMount {
     pub volume_type: VolumeType,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum VolumeType {
     Bind,
     Volume,
     Tmpfs,
 }
 
+impl FromStr for VolumeMount {
+    type Err = Error;
+
+    /// Docker's `-v` syntax, `source:target[:ro|rw]`. A source starting with `/` is a host
+    /// path to bind, any other a named volume: `/srv/data:/data:ro` or `pgdata:/var/lib/postgresql/data`.
+    fn from_str(spec: &str) -> Result<Self> {
+        let invalid = |reason: &str| Error::Invalid(format!("invalid volume mount {:?}: {}", spec, reason));
+        let parts: Vec<&str> = spec.split(':').collect();
+        if parts.iter().any(|part| part.is_empty()) {
+            return Err(invalid("empty field; check for doubled or trailing ':'"));
+        }
+        let (source, target, read_only) = match parts.as_slice() {
+            [source, target] => (*source, *target, false),
+            [source, target, "ro"] => (*source, *target, true),
+            [source, target, "rw"] => (*source, *target, false),
+            [_, _, option] => return Err(invalid(&format!("unknown option {:?}, expected ro or rw", option))),
+            [_] => return Err(invalid("expected source:target")),
+            _ => return Err(invalid("too many ':'")),
+        };
+
+        if !target.starts_with('/') {
+            return Err(invalid("the container path must be absolute"));
+        }
+        let volume_type = if source.starts_with('/') {
+            VolumeType::Bind
+        } else if is_valid_volume_name(source) {
+            VolumeType::Volume
+        } else {
+            return Err(invalid(
+                "host paths must be absolute, and volume names use letters, digits, '_', '.' and '-'",
+            ));
+        };
+        Ok(VolumeMount {
+            source: source.to_string(),
+            target: target.to_string(),
+            read_only,
+            volume_type,
+        })
+    }
+}
+
+/// The form [`VolumeMount::from_str`] reads. Tmpfs mounts have no source and show as
+/// their target alone, which does not parse back.
+impl fmt::Display for VolumeMount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.volume_type != VolumeType::Tmpfs {
+            write!(f, "{}:", self.source)?;
+        }
+        write!(f, "{}", self.target)?;
+        if self.read_only {
+            write!(f, ":ro")?;
+        }
+        Ok(())
+    }
+}
+
+/// An entry of the `ports` or `volumes` array of a create request: an object, or the
+/// compact string form
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CompactOr<T> {
+    Compact(String),
+    Full(T),
+}
+
+/// Read a list whose entries may be given as strings such as `"8080:80"`
+fn compact_or_full<'de, D, T>(deserializer: D) -> std::result::Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + FromStr<Err = Error>,
+{
+    Vec::<CompactOr<T>>::deserialize(deserializer)?
+        .into_iter()
+        .map(|entry| match entry {
+            CompactOr::Compact(spec) => spec.parse().map_err(serde::de::Error::custom),
+            CompactOr::Full(value) => Ok(value),
+        })
+        .collect()
+}
+
 /// Gaming-specific configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct GamingConfig {
     pub proton_version: Option<String>,
     pub wine_version: Option<String>,
     pub steam_app_id: Option<u32>,
+    #[serde(default)]
     pub optimization_profile: OptimizationProfile,
     pub audio_config: Option<AudioConfig>,
+    /// Gaming profile to apply, by name; the agent embeds it as `profile` on creation
+    #[serde(default)]
+    pub profile_name: Option<String>,
+    /// The profile as it was when the container was created
+    #[serde(default)]
+    pub profile: Option<GamingProfile>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum OptimizationProfile {
+    #[default]
     Gaming,
     Streaming,
     Competitive,
@@ -84,12 +401,14 @@ pub enum OptimizationProfile {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct AudioConfig {
     pub system: AudioSystem,
     pub latency: AudioLatency,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum AudioSystem {
     PulseAudio,
     PipeWire,
@@ -97,6 +416,7 @@ pub enum AudioSystem {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum AudioLatency {
     UltraLow,
     Low,
@@ -106,6 +426,7 @@ pub enum AudioLatency {
 
 /// GPU allocation for gaming containers
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct GpuAllocation {
     pub device_id: String,
     pub gpu_type: GpuType,
@@ -114,7 +435,8 @@ pub struct GpuAllocation {
     pub isolation_level: IsolationLevel,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum GpuType {
     Nvidia,
     Amd,
@@ -122,6 +444,7 @@ pub enum GpuType {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum IsolationLevel {
     Shared,
     Exclusive,
@@ -130,6 +453,7 @@ pub enum IsolationLevel {
 
 /// Real-time performance metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct PerformanceMetrics {
     pub cpu_usage: f64,
     pub memory_usage: MemoryUsage,
@@ -140,6 +464,7 @@ pub struct PerformanceMetrics {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct MemoryUsage {
     pub used_mb: u64,
     pub limit_mb: u64,
@@ -147,6 +472,7 @@ pub struct MemoryUsage {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct GpuUsage {
     pub utilization: f64,
     pub memory_used_mb: u64,
@@ -156,6 +482,7 @@ pub struct GpuUsage {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct NetworkIo {
     pub rx_bytes: u64,
     pub tx_bytes: u64,
@@ -164,6 +491,7 @@ pub struct NetworkIo {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct DiskIo {
     pub read_bytes: u64,
     pub write_bytes: u64,
@@ -173,6 +501,7 @@ pub struct DiskIo {
 
 /// Gaming-specific performance metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct GamingMetrics {
     pub fps: Option<f32>,
     pub frame_time_ms: Option<f32>,
@@ -181,12 +510,62 @@ pub struct GamingMetrics {
     pub gpu_temperature: Option<f32>,
 }
 
+/// Container stats for real-time monitoring
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ContainerStats {
+    pub container_id: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub cpu_percent: f64,
+    pub memory_usage: u64,
+    pub memory_limit: u64,
+    pub network_rx: u64,
+    pub network_tx: u64,
+    pub block_read: u64,
+    pub block_write: u64,
+    pub pid_count: u32,
+    #[serde(default)]
+    pub gpu: Option<GpuUsage>,
+    #[serde(default)]
+    pub gaming: Option<GamingMetrics>,
+}
+
+impl ContainerStats {
+    /// Stats sample from the metrics reported with a container listing
+    pub fn from_metrics(
+        container_id: &str,
+        metrics: &PerformanceMetrics,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        const MIB: u64 = 1024 * 1024;
+        Self {
+            container_id: container_id.to_string(),
+            timestamp,
+            cpu_percent: metrics.cpu_usage,
+            memory_usage: metrics.memory_usage.used_mb * MIB,
+            memory_limit: metrics.memory_usage.limit_mb * MIB,
+            network_rx: metrics.network_io.rx_bytes,
+            network_tx: metrics.network_io.tx_bytes,
+            block_read: metrics.disk_io.read_bytes,
+            block_write: metrics.disk_io.write_bytes,
+            pid_count: 0,
+            gpu: metrics.gpu_usage.clone(),
+            gaming: metrics.gaming_metrics.clone(),
+        }
+    }
+}
+
 /// Container creation request
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CreateContainerRequest {
     pub name: Option<String>,
     pub image: String,
+    /// Objects, or strings such as `"127.0.0.1:8080:80/udp"`
+    #[serde(deserialize_with = "compact_or_full")]
     pub ports: Vec<PortMapping>,
+    /// Objects, or strings such as `"/srv/data:/data:ro"`
+    #[serde(deserialize_with = "compact_or_full")]
     pub volumes: Vec<VolumeMount>,
     pub networks: Vec<String>,
     pub env: HashMap<String, String>,
@@ -194,18 +573,208 @@ pub struct CreateContainerRequest {
     pub gaming_config: Option<GamingConfig>,
     pub gpu_allocation: Option<GpuAllocation>,
     pub restart_policy: RestartPolicy,
+    #[serde(default)]
+    pub health: Option<HealthCheck>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Externally tagged with kebab-case names: `"always"`, `{"on-failure": {"max_retries": 3}}`.
+/// The web UI uses this type directly, so the wire format has a single definition.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "kebab-case")]
 pub enum RestartPolicy {
+    #[default]
     No,
     Always,
     OnFailure { max_retries: Option<u32> },
     UnlessStopped,
 }
 
-/// Container list filters
+impl RestartPolicy {
+    /// Whether an exited container with this policy should be running after a host reboot
+    pub fn starts_on_boot(&self) -> bool {
+        matches!(self, RestartPolicy::Always | RestartPolicy::UnlessStopped)
+    }
+}
+
+/// Command to run inside a container
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ExecRequest {
+    pub cmd: Vec<String>,
+    #[serde(default)]
+    pub interactive: bool,
+    pub working_dir: Option<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+/// Captured output of a finished exec
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ExecResponse {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+}
+
+/// What a wait on a container blocks until
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum WaitCondition {
+    /// Returns at once if the container is already stopped
+    #[default]
+    NotRunning,
+    /// Ignores the current state and waits for the container's next exit
+    NextExit,
+    Removed,
+}
+
+impl WaitCondition {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WaitCondition::NotRunning => "not-running",
+            WaitCondition::NextExit => "next-exit",
+            WaitCondition::Removed => "removed",
+        }
+    }
+}
+
+/// Result of waiting on a container
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ContainerWaitResponse {
+    /// Exit code the container stopped with
+    pub status_code: i32,
+}
+
+/// Processes running in a container, as `ps` reports them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ContainerTop {
+    /// Column headers, e.g. `PID`, `USER`, `COMMAND`
+    pub titles: Vec<String>,
+    /// One row per process, in the same order as `titles`
+    pub processes: Vec<Vec<String>>,
+}
+
+/// Operation applied to every container in a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerBatchAction {
+    Start,
+    Stop,
+    Restart,
+    Remove,
+}
+
+/// Request for POST /api/v1/containers/batch
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ContainerBatchRequest {
+    pub action: ContainerBatchAction,
+    pub ids: Vec<String>,
+    /// Seconds to wait for stop and restart
+    pub timeout: Option<u32>,
+    /// Remove running containers
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Outcome for one container in a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ContainerBatchResult {
+    pub id: String,
+    pub success: bool,
+    pub message: String,
+}
+
+/// Which stopped containers a prune removes
+#[derive(Debug, Clone, Default)]
+pub struct ContainerPruneFilter {
+    /// Only remove containers created before this time
+    pub until: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only remove containers carrying this label (`key` or `key=value`)
+    pub label: Option<String>,
+}
+
+impl ContainerPruneFilter {
+    /// Check whether a container is a prune candidate; only exited and dead containers are
+    pub fn matches(&self, container: &Container) -> bool {
+        if !matches!(container.status, ContainerStatus::Exited { .. } | ContainerStatus::Dead) {
+            return false;
+        }
+        if let Some(until) = self.until
+            && container.created_at >= until
+        {
+            return false;
+        }
+        if let Some(label) = &self.label {
+            let matched = match label.split_once('=') {
+                Some((key, value)) => container.labels.get(key).map(|v| v == value).unwrap_or(false),
+                None => container.labels.contains_key(label),
+            };
+            if !matched {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Result of a container prune
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ContainerPruneReport {
+    pub removed: Vec<String>,
+    pub reclaimed_count: usize,
+}
+
+/// Size limits for copying files into and out of containers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FileTransferConfig {
+    pub max_upload_bytes: u64,
+    /// Also caps directory archives, which are cut off once they grow past it
+    pub max_download_bytes: u64,
+}
+
+impl Default for FileTransferConfig {
+    fn default() -> Self {
+        Self {
+            max_upload_bytes: 256 * 1024 * 1024,
+            max_download_bytes: 256 * 1024 * 1024,
+        }
+    }
+}
+
+/// Response of GET /api/v1/containers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ContainerListResponse {
+    pub containers: Vec<Container>,
+    /// Containers matching the filters, before pagination
+    pub total: usize,
+    pub limit: Option<usize>,
+    pub offset: usize,
+}
+
+/// Body of the stop, restart and delete container endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ContainerOperationRequest {
+    pub action: String,
+    pub timeout: Option<u32>,
+    pub force: Option<bool>,
+    pub remove_volumes: Option<bool>,
+}
+
+/// Container list filters
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ContainerFilter {
     pub status: Option<ContainerStatus>,
     pub name_contains: Option<String>,
@@ -213,10 +782,463 @@ pub struct ContainerFilter {
     pub has_gaming_config: Option<bool>,
     pub has_gpu: Option<bool>,
     pub network: Option<String>,
+    /// Every label must be present with exactly this value
+    #[serde(default)]
+    pub labels: Option<HashMap<String, String>>,
 }
 
-impl Default for RestartPolicy {
-    fn default() -> Self {
-        RestartPolicy::No
+impl ContainerFilter {
+    /// Whether a container passes every filter that is set. Statuses compare by kind,
+    /// so an `Exited` filter matches any exit code.
+    pub fn matches(&self, container: &Container) -> bool {
+        let contains = |value: &str, needle: &Option<String>| {
+            needle
+                .as_ref()
+                .is_none_or(|needle| value.to_lowercase().contains(&needle.to_lowercase()))
+        };
+
+        self.status
+            .as_ref()
+            .is_none_or(|status| std::mem::discriminant(status) == std::mem::discriminant(&container.status))
+            && contains(&container.name, &self.name_contains)
+            && contains(&container.image, &self.image_contains)
+            && self.has_gaming_config.is_none_or(|gaming| gaming == container.gaming_config.is_some())
+            && self.has_gpu.is_none_or(|gpu| gpu == container.gpu_allocation.is_some())
+            && self.network.as_ref().is_none_or(|network| container.networks.contains(network))
+            && self.labels.as_ref().is_none_or(|labels| {
+                labels.iter().all(|(key, value)| container.labels.get(key) == Some(value))
+            })
+    }
+}
+
+/// Label that groups containers into a project (a stack of related containers)
+pub const PROJECT_LABEL: &str = "gpanel.project";
+
+/// Overall state of a project's containers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectStatus {
+    Running,
+    /// Some containers are running and some are not
+    Partial,
+    Stopped,
+}
+
+/// Containers sharing a `gpanel.project` label value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProjectSummary {
+    pub name: String,
+    pub container_count: usize,
+    pub running: usize,
+    pub status: ProjectStatus,
+    pub containers: Vec<String>,
+}
+
+/// Group containers by their project label; unlabelled containers are left out
+pub fn group_projects(containers: &[Container]) -> Vec<ProjectSummary> {
+    let mut projects: std::collections::BTreeMap<&str, Vec<&Container>> = std::collections::BTreeMap::new();
+    for container in containers {
+        if let Some(project) = container.labels.get(PROJECT_LABEL) {
+            projects.entry(project).or_default().push(container);
+        }
+    }
+
+    projects
+        .into_iter()
+        .map(|(name, members)| {
+            let running = members
+                .iter()
+                .filter(|c| matches!(c.status, ContainerStatus::Running))
+                .count();
+            let status = match running {
+                0 => ProjectStatus::Stopped,
+                n if n == members.len() => ProjectStatus::Running,
+                _ => ProjectStatus::Partial,
+            };
+            ProjectSummary {
+                name: name.to_string(),
+                container_count: members.len(),
+                running,
+                status,
+                containers: members.iter().map(|c| c.id.clone()).collect(),
+            }
+        })
+        .collect()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn container(id: &str, status: ContainerStatus, age_hours: i64, labels: &[(&str, &str)]) -> Container {
+        Container {
+            id: id.to_string(),
+            name: format!("{}-name", id),
+            image: "nginx:latest".to_string(),
+            status,
+            ports: Vec::new(),
+            volumes: Vec::new(),
+            networks: Vec::new(),
+            env: HashMap::new(),
+            labels: labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            created_at: Utc::now() - Duration::hours(age_hours),
+            started_at: None,
+            finished_at: None,
+            health_status: None,
+            restart_policy: RestartPolicy::No,
+            gaming_config: None,
+            gpu_allocation: None,
+            performance_metrics: None,
+        }
+    }
+
+    fn stats() -> ContainerStats {
+        ContainerStats {
+            container_id: "abc123".to_string(),
+            timestamp: "2024-03-01T10:00:00Z".parse().unwrap(),
+            cpu_percent: 12.5,
+            memory_usage: 256 * 1024 * 1024,
+            memory_limit: 1024 * 1024 * 1024,
+            network_rx: 1_000,
+            network_tx: 2_000,
+            block_read: 3_000,
+            block_write: 4_000,
+            pid_count: 7,
+            gpu: Some(GpuUsage {
+                utilization: 80.0,
+                memory_used_mb: 4096,
+                memory_total_mb: 8192,
+                temperature: Some(65.0),
+                power_usage: None,
+            }),
+            gaming: Some(GamingMetrics {
+                fps: Some(144.0),
+                frame_time_ms: Some(6.9),
+                input_latency_ms: None,
+                network_latency_ms: None,
+                gpu_temperature: Some(65.0),
+            }),
+        }
+    }
+
+    /// The web UI decodes the agent's JSON with these same types, built for wasm32
+    #[test]
+    fn container_stats_round_trip_through_json() {
+        let json = serde_json::to_value(stats()).unwrap();
+        assert_eq!(json["timestamp"], "2024-03-01T10:00:00Z");
+        assert_eq!(json["gpu"]["memory_used_mb"], 4096);
+
+        let decoded: ContainerStats = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(decoded).unwrap(), json);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn container_stats_without_gpu_or_gaming_metrics_decode() {
+        let mut json = serde_json::to_value(stats()).unwrap();
+        let object = json.as_object_mut().unwrap();
+        object.remove("gpu");
+        object.remove("gaming");
+
+        let decoded: ContainerStats = serde_json::from_value(json).unwrap();
+        assert!(decoded.gpu.is_none());
+        assert!(decoded.gaming.is_none());
+        assert_eq!(decoded.pid_count, 7);
+    }
+
+    #[test]
+    fn health_checks_round_trip_through_json() {
+        let check = HealthCheck {
+            command: vec!["sh".to_string(), "-c".to_string(), "curl -f http://localhost/".to_string()],
+            interval_secs: 10,
+            timeout_secs: 2,
+            retries: 5,
+            start_period_secs: 60,
+        };
+        let json = serde_json::to_value(&check).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "command": ["sh", "-c", "curl -f http://localhost/"],
+                "interval_secs": 10,
+                "timeout_secs": 2,
+                "retries": 5,
+                "start_period_secs": 60,
+            })
+        );
+        assert_eq!(serde_json::from_value::<HealthCheck>(json).unwrap(), check);
+    }
+
+    #[test]
+    fn health_checks_default_everything_but_the_command() {
+        let check: HealthCheck = serde_json::from_value(serde_json::json!({ "command": ["true"] })).unwrap();
+        assert_eq!(check.command, ["true"]);
+        assert_eq!(
+            (check.interval_secs, check.timeout_secs, check.retries, check.start_period_secs),
+            (30, 5, 3, 0)
+        );
+
+        assert!(serde_json::from_value::<HealthCheck>(serde_json::json!({ "retries": 3 })).is_err());
+    }
+
+    #[test]
+    fn health_statuses_round_trip_through_json() {
+        for (state, name) in [
+            (HealthState::Starting, "Starting"),
+            (HealthState::Healthy, "Healthy"),
+            (HealthState::Unhealthy, "Unhealthy"),
+        ] {
+            let status = HealthStatus { state, failing_streak: 2 };
+            let json = serde_json::to_value(&status).unwrap();
+            assert_eq!(json, serde_json::json!({ "state": name, "failing_streak": 2 }));
+            assert_eq!(serde_json::from_value::<HealthStatus>(json).unwrap(), status);
+        }
+
+        // Containers without a health check leave the status out as null
+        let json = serde_json::to_value(container("a", ContainerStatus::Running, 1, &[])).unwrap();
+        assert!(json["health_status"].is_null());
+        let healthy = Container {
+            health_status: Some(HealthStatus {
+                state: HealthState::Healthy,
+                failing_streak: 0,
+            }),
+            ..container("a", ContainerStatus::Running, 1, &[])
+        };
+        let decoded: Container = serde_json::from_value(serde_json::to_value(&healthy).unwrap()).unwrap();
+        assert_eq!(decoded.health_status, healthy.health_status);
+    }
+
+    #[test]
+    fn restart_policies_use_kebab_case_names() {
+        let policies = [
+            (RestartPolicy::No, serde_json::json!("no")),
+            (RestartPolicy::Always, serde_json::json!("always")),
+            (
+                RestartPolicy::OnFailure { max_retries: Some(3) },
+                serde_json::json!({ "on-failure": { "max_retries": 3 } }),
+            ),
+            (
+                RestartPolicy::OnFailure { max_retries: None },
+                serde_json::json!({ "on-failure": { "max_retries": null } }),
+            ),
+            (RestartPolicy::UnlessStopped, serde_json::json!("unless-stopped")),
+        ];
+        for (policy, json) in policies {
+            assert_eq!(serde_json::to_value(&policy).unwrap(), json);
+            assert_eq!(serde_json::from_value::<RestartPolicy>(json).unwrap(), policy);
+        }
+
+        assert!(serde_json::from_value::<RestartPolicy>(serde_json::json!("unless_stopped")).is_err());
+        assert_eq!(RestartPolicy::default(), RestartPolicy::No);
+    }
+
+    #[test]
+    fn prune_only_matches_stopped_containers() {
+        let filter = ContainerPruneFilter::default();
+        assert!(filter.matches(&container("a", ContainerStatus::Exited { code: 1 }, 1, &[])));
+        assert!(filter.matches(&container("a", ContainerStatus::Dead, 1, &[])));
+        assert!(!filter.matches(&container("a", ContainerStatus::Running, 1, &[])));
+        assert!(!filter.matches(&container("a", ContainerStatus::Created, 1, &[])));
+    }
+
+    #[test]
+    fn prune_honors_age_and_labels() {
+        let exited = ContainerStatus::Exited { code: 0 };
+        let until = ContainerPruneFilter {
+            until: Some(Utc::now() - Duration::hours(24)),
+            ..Default::default()
+        };
+        assert!(until.matches(&container("a", exited.clone(), 48, &[])));
+        assert!(!until.matches(&container("a", exited.clone(), 1, &[])));
+
+        let by_key = ContainerPruneFilter {
+            label: Some("temp".to_string()),
+            ..Default::default()
+        };
+        let by_value = ContainerPruneFilter {
+            label: Some("env=ci".to_string()),
+            ..Default::default()
+        };
+        let labeled = container("a", exited.clone(), 1, &[("temp", ""), ("env", "ci")]);
+        assert!(by_key.matches(&labeled));
+        assert!(by_value.matches(&labeled));
+        assert!(!by_value.matches(&container("a", exited, 1, &[("env", "prod")])));
+    }
+
+    #[test]
+    fn short_ids_keep_shorter_ids_whole() {
+        assert_eq!(short_id("abc"), "abc");
+        assert_eq!(short_id(""), "");
+    }
+
+    #[test]
+    fn short_ids_cut_at_twelve_characters() {
+        assert_eq!(short_id("0123456789ab"), "0123456789ab");
+        assert_eq!(short_id("0123456789abcdef0123"), "0123456789ab");
+        assert_eq!(container("0123456789abcdef", ContainerStatus::Running, 1, &[]).short_id(), "0123456789ab");
+    }
+
+    #[test]
+    fn short_ids_never_split_multibyte_characters() {
+        assert_eq!(short_id("äöüäöüäöüäöüäöü"), "äöüäöüäöüäöü");
+        assert_eq!(short_id("容器容器容器"), "容器容器容器");
+        assert_eq!(short_id("🐳🐳🐳🐳🐳🐳🐳🐳🐳🐳🐳🐳🐳").chars().count(), SHORT_ID_LEN);
+    }
+
+    fn port(spec: &str) -> PortMapping {
+        spec.parse().unwrap_or_else(|e| panic!("{}: {}", spec, e))
+    }
+
+    fn mapping(host_ip: Option<&str>, host_port: Option<u16>, container_port: u16, protocol: Protocol) -> PortMapping {
+        PortMapping {
+            container_port,
+            host_port,
+            protocol,
+            host_ip: host_ip.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn port_mappings_parse_docker_syntax() {
+        assert_eq!(port("80"), mapping(None, None, 80, Protocol::Tcp));
+        assert_eq!(port("8080:80"), mapping(None, Some(8080), 80, Protocol::Tcp));
+        assert_eq!(port("127.0.0.1:8080:80/udp"), mapping(Some("127.0.0.1"), Some(8080), 80, Protocol::Udp));
+        assert_eq!(port("0.0.0.0::443/QUIC"), mapping(Some("0.0.0.0"), None, 443, Protocol::Quic));
+        assert_eq!(port("[::1]:8080:80/tcp"), mapping(Some("::1"), Some(8080), 80, Protocol::Tcp));
+        assert_eq!(port("[fd00::0:1]::53/udp"), mapping(Some("fd00::1"), None, 53, Protocol::Udp));
+        assert_eq!(port("65535:1"), mapping(None, Some(65535), 1, Protocol::Tcp));
+    }
+
+    #[test]
+    fn port_mappings_display_as_they_parse() {
+        for spec in ["80", "8080:80", "127.0.0.1:8080:80/udp", "0.0.0.0::443/quic", "[::1]:8080:80", "[fd00::1]::53/udp"] {
+            assert_eq!(port(spec).to_string(), spec);
+        }
+        assert_eq!(port("8080:80/tcp").to_string(), "8080:80");
+    }
+
+    #[test]
+    fn invalid_port_mappings_are_rejected() {
+        for spec in [
+            "",
+            "0",
+            "65536",
+            "8080:0",
+            "-1",
+            "http",
+            "8080:80:",
+            ":80",
+            "::80",
+            "8080::80",
+            "80/",
+            "80/sctp",
+            "80/udp/tcp",
+            "8080:80//udp",
+            "127.0.0.1:8080:80:90",
+            "::1:8080:80",
+            "localhost:8080:80",
+            "300.0.0.1:8080:80",
+            "[::1]8080:80",
+            "[::1:8080:80",
+            "[::1]:80",
+            "[::1]:1:2:3",
+            "[nope]:8080:80",
+            " 8080:80",
+            "+80",
+        ] {
+            assert!(spec.parse::<PortMapping>().is_err(), "{:?} parsed", spec);
+        }
+
+        let error = "8000-8010:80".parse::<PortMapping>().unwrap_err().to_string();
+        assert!(error.contains("range"), "{}", error);
+        let error = "8080:80-81/udp".parse::<PortMapping>().unwrap_err().to_string();
+        assert!(error.contains("range"), "{}", error);
+        let error = "::1:8080:80".parse::<PortMapping>().unwrap_err().to_string();
+        assert!(error.contains("brackets"), "{}", error);
+    }
+
+    fn mount(spec: &str) -> VolumeMount {
+        spec.parse().unwrap_or_else(|e| panic!("{}: {}", spec, e))
+    }
+
+    #[test]
+    fn volume_mounts_parse_binds_and_named_volumes() {
+        let bind = mount("/data:/var/lib/app:ro");
+        assert_eq!(
+            (bind.source.as_str(), bind.target.as_str(), bind.read_only, bind.volume_type),
+            ("/data", "/var/lib/app", true, VolumeType::Bind)
+        );
+        let named = mount("pg-data_1.0:/var/lib/postgresql/data");
+        assert_eq!(
+            (named.source.as_str(), named.target.as_str(), named.read_only, named.volume_type),
+            ("pg-data_1.0", "/var/lib/postgresql/data", false, VolumeType::Volume)
+        );
+        assert!(!mount("/srv:/srv:rw").read_only);
+
+        for spec in ["/data:/var/lib/app:ro", "pgdata:/data", "/srv:/srv"] {
+            assert_eq!(mount(spec).to_string(), spec);
+        }
+        assert_eq!(mount("/srv:/srv:rw").to_string(), "/srv:/srv");
+        let tmpfs = VolumeMount {
+            source: String::new(),
+            target: "/tmp".to_string(),
+            read_only: false,
+            volume_type: VolumeType::Tmpfs,
+        };
+        assert_eq!(tmpfs.to_string(), "/tmp");
+    }
+
+    #[test]
+    fn invalid_volume_mounts_are_rejected() {
+        for spec in [
+            "",
+            "/data",
+            "/data:",
+            ":/data",
+            "/data::/app",
+            "/data:/app:",
+            "/data:/app::ro",
+            "/data:/app:rx",
+            "/data:/app:ro:z",
+            "/data:app",
+            "./data:/app",
+            "my volume:/app",
+            "-data:/app",
+        ] {
+            assert!(spec.parse::<VolumeMount>().is_err(), "{:?} parsed", spec);
+        }
+        let error = "/data::/app".parse::<VolumeMount>().unwrap_err().to_string();
+        assert!(error.contains("doubled"), "{}", error);
+    }
+
+    #[test]
+    fn create_requests_take_compact_ports_and_volumes() {
+        let request = |ports: serde_json::Value, volumes: serde_json::Value| {
+            serde_json::from_value::<CreateContainerRequest>(serde_json::json!({
+                "name": null,
+                "image": "nginx:latest",
+                "ports": ports,
+                "volumes": volumes,
+                "networks": [],
+                "env": {},
+                "labels": {},
+                "gaming_config": null,
+                "gpu_allocation": null,
+                "restart_policy": "no",
+            }))
+        };
+
+        let parsed = request(
+            serde_json::json!(["8080:80", {"container_port": 443, "host_port": 8443, "protocol": "Quic", "host_ip": null}]),
+            serde_json::json!(["/data:/var/lib/app:ro"]),
+        )
+        .unwrap();
+        assert_eq!(parsed.ports, [port("8080:80"), port("8443:443/quic")]);
+        assert_eq!(parsed.volumes[0].volume_type, VolumeType::Bind);
+
+        let error = request(serde_json::json!(["8000-8010:80"]), serde_json::json!([])).unwrap_err();
+        assert!(error.to_string().contains("range"), "{}", error);
+        assert!(request(serde_json::json!([]), serde_json::json!(["/data"])).is_err());
+    }
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// Header choosing the environment a container request is for; `?env=` works too
+pub const ENVIRONMENT_HEADER: &str = "x-gpanel-env";
+
+/// Name of the single environment used when none are configured
+pub const DEFAULT_ENVIRONMENT: &str = "default";
+
+/// A Bolt host managed by the agent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EnvironmentConfig {
+    pub name: String,
+    /// Same forms as `bolt_api_url`
+    pub bolt_api_url: String,
+    /// Used by requests that name no environment, and by the watchdog, autostart,
+    /// schedules and events. The first environment if none is marked.
+    #[serde(default)]
+    pub default: bool,
+}
+
+/// An environment and whether its Bolt host answered, from GET /api/v1/environments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EnvironmentStatus {
+    pub name: String,
+    pub bolt_api_url: String,
+    pub default: bool,
+    pub reachable: bool,
+}
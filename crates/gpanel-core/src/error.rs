@@ -6,19 +6,39 @@ pub enum Error {
     /// Configuration errors
     Config(String),
 
-    /// Network/HTTP errors
+    /// Network/HTTP errors: the other end could not be reached, or the connection failed
     Network(String),
 
+    /// A request got no answer in time
+    Timeout(String),
+
+    /// An HTTP request was answered with an error status
+    Http { status: u16, message: String },
+
     /// Bolt integration errors
     Bolt(String),
 
+    /// A registry answered with something other than what the registry API promises
+    Registry(String),
+
+    /// The container, image or other object asked for does not exist
+    NotFound(String),
+
+    /// The object already exists, or its state does not allow the operation
+    Conflict(String),
+
+    /// A request or setting is not valid, e.g. an archive with paths outside its target
+    /// or a port used twice in the config
+    Invalid(String),
+
     /// QUIC/HTTP3 errors
     Quic(String),
 
     /// Serialization errors
     Serialization(serde_json::Error),
 
-    /// I/O errors
+    /// I/O errors; only the clients touch files and sockets
+    #[cfg(feature = "client")]
     Io(std::io::Error),
 
     /// Authentication errors
@@ -29,6 +49,9 @@ pub enum Error {
 
     /// GPU/Gaming errors
     Gaming(String),
+
+    /// `message`, caused by `source`; added with [`Context`]
+    Context { message: String, source: Box<Error> },
 }
 
 impl fmt::Display for Error {
@@ -36,18 +59,102 @@ impl fmt::Display for Error {
         match self {
             Error::Config(msg) => write!(f, "Configuration error: {}", msg),
             Error::Network(msg) => write!(f, "Network error: {}", msg),
+            Error::Timeout(msg) => write!(f, "Timeout: {}", msg),
+            Error::Http { status, message } => write!(f, "HTTP {}: {}", status, message),
             Error::Bolt(msg) => write!(f, "Bolt error: {}", msg),
+            Error::Registry(msg) => write!(f, "Registry error: {}", msg),
+            Error::NotFound(msg) | Error::Conflict(msg) | Error::Invalid(msg) => f.write_str(msg),
             Error::Quic(msg) => write!(f, "QUIC error: {}", msg),
             Error::Serialization(err) => write!(f, "Serialization error: {}", err),
+            #[cfg(feature = "client")]
             Error::Io(err) => write!(f, "I/O error: {}", err),
             Error::Auth(msg) => write!(f, "Authentication error: {}", msg),
             Error::Container(msg) => write!(f, "Container error: {}", msg),
             Error::Gaming(msg) => write!(f, "Gaming error: {}", msg),
+            // Like anyhow: the cause only shows with `{:#}`
+            Error::Context { message, source } if f.alternate() => write!(f, "{}: {:#}", message, source),
+            Error::Context { message, .. } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Serialization(err) => Some(err),
+            #[cfg(feature = "client")]
+            Error::Io(err) => Some(err),
+            Error::Context { source, .. } => Some(source.as_ref()),
+            _ => None,
         }
     }
 }
 
-impl std::error::Error for Error {}
+impl Error {
+    /// Whether this is a request to Bolt that did not answer in time
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            Error::Bolt(msg) => msg.starts_with("timeout"),
+            Error::Context { source, .. } => source.is_timeout(),
+            _ => false,
+        }
+    }
+
+    /// The error without the context added to it
+    pub fn root_cause(&self) -> &Error {
+        match self {
+            Error::Context { source, .. } => source.root_cause(),
+            error => error,
+        }
+    }
+
+    /// HTTP status the agent answers with when a request fails with this error: 404 for
+    /// objects that do not exist, 502 and 504 for services that failed or did not answer
+    pub fn status_code(&self) -> u16 {
+        match self.root_cause() {
+            Error::Bolt(_) if self.is_timeout() => 504,
+            Error::Timeout(_) => 504,
+            Error::NotFound(_) => 404,
+            Error::Conflict(_) => 409,
+            Error::Invalid(_) => 400,
+            // Answers the agent passes on; other statuses are the service failing it
+            Error::Http { status, .. } if matches!(status, 404 | 409 | 429) => *status,
+            Error::Http { .. } | Error::Network(_) | Error::Bolt(_) | Error::Registry(_) => 502,
+            Error::Auth(_) => 401,
+            Error::Container(_) => 409,
+            Error::Gaming(_) => 400,
+            Error::Config(_) | Error::Quic(_) | Error::Serialization(_) => 500,
+            #[cfg(feature = "client")]
+            Error::Io(_) => 500,
+            Error::Context { .. } => unreachable!("the root cause has no context"),
+        }
+    }
+
+    /// Machine-readable code for API error bodies, e.g. `BOLT_TIMEOUT`
+    pub fn code(&self) -> &'static str {
+        match self.root_cause() {
+            Error::Bolt(_) if self.is_timeout() => "BOLT_TIMEOUT",
+            Error::Bolt(_) => "BOLT_ERROR",
+            Error::Timeout(_) => "UPSTREAM_TIMEOUT",
+            Error::Http { status: 404, .. } | Error::NotFound(_) => "NOT_FOUND",
+            Error::Http { status: 409, .. } | Error::Conflict(_) => "CONFLICT",
+            Error::Invalid(_) => "INVALID_REQUEST",
+            Error::Http { status: 429, .. } => "RATE_LIMITED",
+            Error::Http { .. } => "UPSTREAM_ERROR",
+            Error::Network(_) => "NETWORK_ERROR",
+            Error::Registry(_) => "REGISTRY_ERROR",
+            Error::Auth(_) => "UNAUTHORIZED",
+            Error::Container(_) => "CONTAINER_ERROR",
+            Error::Gaming(_) => "GAMING_ERROR",
+            Error::Config(_) => "CONFIG_ERROR",
+            Error::Quic(_) => "QUIC_ERROR",
+            Error::Serialization(_) => "SERIALIZATION_ERROR",
+            #[cfg(feature = "client")]
+            Error::Io(_) => "IO_ERROR",
+            Error::Context { .. } => unreachable!("the root cause has no context"),
+        }
+    }
+}
 
 impl From<serde_json::Error> for Error {
     fn from(err: serde_json::Error) -> Self {
@@ -55,13 +162,196 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+#[cfg(feature = "client")]
 impl From<std::io::Error> for Error {
     fn from(err: std::io::Error) -> Self {
         Error::Io(err)
     }
 }
 
+/// Requests that timed out, failed to connect or were answered with an error status, told
+/// apart so the agent can answer with a matching status
+#[cfg(feature = "client")]
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        // reqwest's own message leaves out why, e.g. that the connection was refused
+        let mut message = err.to_string();
+        let mut cause = std::error::Error::source(&err);
+        while let Some(error) = cause {
+            message.push_str(&format!(": {}", error));
+            cause = error.source();
+        }
+
+        if err.is_timeout() {
+            Error::Timeout(message)
+        } else if let Some(status) = err.status() {
+            Error::Http {
+                status: status.as_u16(),
+                message,
+            }
+        } else if err.is_builder() {
+            Error::Config(message)
+        } else {
+            Error::Network(message)
+        }
+    }
+}
+
+/// Describe what was being done when an error happened, as `anyhow::Context` does
+pub trait Context<T> {
+    fn context(self, message: impl Into<String>) -> Result<T>;
+
+    fn with_context<M: Into<String>>(self, message: impl FnOnce() -> M) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> Context<T> for std::result::Result<T, E> {
+    fn context(self, message: impl Into<String>) -> Result<T> {
+        self.with_context(|| message)
+    }
+
+    fn with_context<M: Into<String>>(self, message: impl FnOnce() -> M) -> Result<T> {
+        self.map_err(|error| Error::Context {
+            message: message().into(),
+            source: Box::new(error.into()),
+        })
+    }
+}
+
 // QUIC error conversions will be added when GQUIC library is ready
 
 /// GhostPanel result type
-pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_variant_has_a_status_and_code() {
+        let json = || serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        let http = |status| Error::Http {
+            status,
+            message: "answered".to_string(),
+        };
+        let cases = [
+            (Error::Config("bad".to_string()), 500, "CONFIG_ERROR"),
+            (Error::Network("refused".to_string()), 502, "NETWORK_ERROR"),
+            (Error::Timeout("slow".to_string()), 504, "UPSTREAM_TIMEOUT"),
+            (http(404), 404, "NOT_FOUND"),
+            (http(409), 409, "CONFLICT"),
+            (http(429), 429, "RATE_LIMITED"),
+            (http(500), 502, "UPSTREAM_ERROR"),
+            (http(401), 502, "UPSTREAM_ERROR"),
+            (Error::Bolt("failed".to_string()), 502, "BOLT_ERROR"),
+            (Error::Bolt("timeout after 30s waiting for /containers".to_string()), 504, "BOLT_TIMEOUT"),
+            (Error::Registry("no digest".to_string()), 502, "REGISTRY_ERROR"),
+            (Error::NotFound("gone".to_string()), 404, "NOT_FOUND"),
+            (Error::Conflict("exists".to_string()), 409, "CONFLICT"),
+            (Error::Invalid("bad path".to_string()), 400, "INVALID_REQUEST"),
+            (Error::Quic("closed".to_string()), 500, "QUIC_ERROR"),
+            (Error::from(json()), 500, "SERIALIZATION_ERROR"),
+            (Error::Auth("no token".to_string()), 401, "UNAUTHORIZED"),
+            (Error::Container("not running".to_string()), 409, "CONTAINER_ERROR"),
+            (Error::Gaming("no GPU".to_string()), 400, "GAMING_ERROR"),
+        ];
+        for (error, status, code) in cases {
+            assert_eq!((error.status_code(), error.code()), (status, code), "{:?}", error);
+            // Context keeps the status and code of what it wraps
+            let wrapped = Err::<(), _>(error).context("while testing").unwrap_err();
+            assert_eq!((wrapped.status_code(), wrapped.code()), (status, code), "{:?}", wrapped);
+        }
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn io_errors_are_internal() {
+        let error = Error::from(std::io::Error::other("disk on fire"));
+        assert_eq!((error.status_code(), error.code()), (500, "IO_ERROR"));
+        assert_eq!(std::error::Error::source(&error).unwrap().to_string(), "disk on fire");
+    }
+
+    #[test]
+    fn sources_and_context_chain() {
+        let parse_error = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        let message = parse_error.to_string();
+        let error = Error::from(parse_error);
+        assert_eq!(std::error::Error::source(&error).unwrap().to_string(), message);
+        assert!(std::error::Error::source(&Error::NotFound("gone".to_string())).is_none());
+
+        let error = Err::<(), _>(Error::Bolt("timeout after 5s waiting for /containers".to_string()))
+            .context("Failed to start web")
+            .with_context(|| format!("Failed to restart {}", "project"))
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Failed to restart project");
+        assert_eq!(
+            format!("{:#}", error),
+            "Failed to restart project: Failed to start web: Bolt error: timeout after 5s waiting for /containers"
+        );
+        assert!(error.is_timeout());
+        assert!(matches!(error.root_cause(), Error::Bolt(_)));
+        let source = std::error::Error::source(&error).unwrap();
+        assert_eq!(source.to_string(), "Failed to start web");
+    }
+
+    #[cfg(feature = "client")]
+    mod reqwest_errors {
+        use super::*;
+        use std::time::Duration;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        /// A server answering every connection with `response`, or never when it is `None`
+        async fn server(response: Option<&'static str>) -> String {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}/", listener.local_addr().unwrap());
+            tokio::spawn(async move {
+                while let Ok((mut socket, _)) = listener.accept().await {
+                    tokio::spawn(async move {
+                        let mut request = [0; 1024];
+                        let _ = socket.read(&mut request).await;
+                        match response {
+                            Some(response) => {
+                                let _ = socket.write_all(response.as_bytes()).await;
+                            }
+                            None => tokio::time::sleep(Duration::from_secs(60)).await,
+                        }
+                    });
+                }
+            });
+            url
+        }
+
+        async fn get(url: &str) -> Error {
+            let client = reqwest::Client::builder().timeout(Duration::from_millis(200)).build().unwrap();
+            match client.get(url).send().await {
+                Ok(response) => response.error_for_status().unwrap_err().into(),
+                Err(e) => e.into(),
+            }
+        }
+
+        #[tokio::test]
+        async fn timeouts_connection_failures_and_statuses_are_told_apart() {
+            let error = get(&server(None).await).await;
+            assert!(matches!(error, Error::Timeout(_)), "{:?}", error);
+            assert_eq!(error.status_code(), 504);
+
+            let unused = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+            let error = get(&format!("http://{}/", unused)).await;
+            assert!(matches!(error, Error::Network(_)), "{:?}", error);
+            assert_eq!(error.status_code(), 502);
+
+            let response = "HTTP/1.1 429 Too Many Requests\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+            let error = get(&server(Some(response)).await).await;
+            assert!(matches!(error, Error::Http { status: 429, .. }), "{:?}", error);
+            assert_eq!(error.code(), "RATE_LIMITED");
+
+            let response = "HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+            let error = get(&server(Some(response)).await).await;
+            assert!(matches!(error, Error::Http { status: 503, .. }), "{:?}", error);
+            assert_eq!(error.status_code(), 502);
+
+            let error = get("not a url").await;
+            assert!(matches!(error, Error::Config(_)), "{:?}", error);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Container lifecycle event published inside the agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ContainerEvent {
+    pub id: String,
+    pub name: String,
+    pub action: ContainerEventAction,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Image, exit code and container labels
+    pub attributes: HashMap<String, String>,
+    /// Environment the container runs in
+    #[serde(default)]
+    pub environment: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum ContainerEventAction {
+    Created,
+    Started,
+    Stopped,
+    /// Exited with a nonzero code
+    Died,
+    Removed,
+    Paused,
+    /// The watchdog stopped restarting a container that keeps crashing
+    CrashLoop,
+}
+
+impl ContainerEvent {
+    pub fn new(id: &str, name: &str, action: ContainerEventAction) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            action,
+            timestamp: chrono::Utc::now(),
+            attributes: HashMap::new(),
+            environment: String::new(),
+        }
+    }
+
+    /// Build an event carrying the container's image and labels as attributes
+    pub fn for_container(container: &crate::container::Container, action: ContainerEventAction) -> Self {
+        let mut event = Self::new(&container.id, &container.name, action);
+        event.attributes = container.labels.clone();
+        event.attributes.insert("image".to_string(), container.image.clone());
+        if let crate::container::ContainerStatus::Exited { code } = container.status {
+            event.attributes.insert("exit_code".to_string(), code.to_string());
+        }
+        event
+    }
+}
+
+/// Agent-wide change published next to container events, sent as `system` server-sent events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SystemEvent {
+    /// The configured registries changed after the config file was reloaded
+    RegistriesChanged {
+        added: Vec<String>,
+        removed: Vec<String>,
+        updated: Vec<String>,
+    },
+    /// A registry notified the agent that an image was pushed to it
+    ImagePushed {
+        registry: String,
+        repository: String,
+        tag: Option<String>,
+        digest: Option<String>,
+    },
+    /// A registry notified the agent that an image was deleted from it
+    ImageDeleted {
+        registry: String,
+        repository: String,
+        tag: Option<String>,
+        digest: Option<String>,
+    },
+}
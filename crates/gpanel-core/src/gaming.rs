@@ -0,0 +1,282 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::container::{ContainerStats, OptimizationProfile};
+use crate::validation::FieldError;
+
+/// CPU frequency governors a profile may ask for
+pub const CPU_GOVERNORS: &[&str] = &["performance", "powersave", "schedutil", "ondemand", "conservative"];
+
+/// Host tuning for a gaming container, referenced from `GamingConfig.profile_name`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GamingProfile {
+    pub name: String,
+    pub description: Option<String>,
+    /// One of `CPU_GOVERNORS`; unset leaves the host's governor alone
+    pub cpu_governor: Option<String>,
+    /// Scheduling priority of the container's processes, -20 (highest) to 19
+    pub nice_level: Option<i8>,
+    pub gpu_power_limit_watts: Option<u32>,
+    /// Arguments for gamescope, e.g. `["-W", "2560", "-H", "1440", "-r", "144"]`
+    #[serde(default)]
+    pub gamescope_args: Vec<String>,
+    /// Seeded from `OptimizationProfile`; cannot be changed or deleted
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+/// Body of POST /api/v1/gaming/profiles and PUT /api/v1/gaming/profiles/{name}.
+/// A PUT replaces the whole profile, and a different `name` renames it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GamingProfileRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub cpu_governor: Option<String>,
+    pub nice_level: Option<i8>,
+    pub gpu_power_limit_watts: Option<u32>,
+    #[serde(default)]
+    pub gamescope_args: Vec<String>,
+}
+
+impl GamingProfileRequest {
+    /// Problems with the request, each naming its field
+    pub fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if self.name.is_empty()
+            || !self.name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            errors.push(FieldError::new("name", "Name must be letters, digits, '-', '_' or '.'"));
+        }
+        if let Some(governor) = &self.cpu_governor
+            && !CPU_GOVERNORS.contains(&governor.as_str())
+        {
+            errors.push(FieldError::new(
+                "cpu_governor",
+                format!("Unknown CPU governor '{}', expected one of {}", governor, CPU_GOVERNORS.join(", ")),
+            ));
+        }
+        if self.nice_level.is_some_and(|nice| !(-20..=19).contains(&nice)) {
+            errors.push(FieldError::new("nice_level", "Nice level must be between -20 and 19"));
+        }
+        if self.gpu_power_limit_watts == Some(0) {
+            errors.push(FieldError::new("gpu_power_limit_watts", "GPU power limit must be positive"));
+        }
+        errors
+    }
+
+    pub fn into_profile(self) -> GamingProfile {
+        GamingProfile {
+            name: self.name,
+            description: self.description,
+            cpu_governor: self.cpu_governor,
+            nice_level: self.nice_level,
+            gpu_power_limit_watts: self.gpu_power_limit_watts,
+            gamescope_args: self.gamescope_args,
+            builtin: false,
+        }
+    }
+}
+
+impl OptimizationProfile {
+    pub const ALL: [OptimizationProfile; 5] = [
+        OptimizationProfile::Gaming,
+        OptimizationProfile::Streaming,
+        OptimizationProfile::Competitive,
+        OptimizationProfile::Balanced,
+        OptimizationProfile::PowerSaving,
+    ];
+
+    /// Name of the built-in profile for this variant
+    pub fn profile_name(&self) -> &'static str {
+        match self {
+            OptimizationProfile::Gaming => "gaming",
+            OptimizationProfile::Streaming => "streaming",
+            OptimizationProfile::Competitive => "competitive",
+            OptimizationProfile::Balanced => "balanced",
+            OptimizationProfile::PowerSaving => "power-saving",
+        }
+    }
+
+    /// The read-only profile seeded for this variant
+    pub fn builtin_profile(&self) -> GamingProfile {
+        let (description, governor, nice_level, gamescope_args): (&str, &str, i8, &[&str]) = match self {
+            OptimizationProfile::Gaming => ("High performance for single-player games", "performance", -5, &[]),
+            OptimizationProfile::Streaming => ("Headroom for encoding while playing", "schedutil", 0, &[]),
+            OptimizationProfile::Competitive => {
+                ("Lowest latency, tearing allowed", "performance", -10, &["--immediate-flips"])
+            }
+            OptimizationProfile::Balanced => ("The host's defaults", "schedutil", 0, &[]),
+            OptimizationProfile::PowerSaving => ("Lower clocks and priority for idle or light games", "powersave", 5, &[]),
+        };
+        GamingProfile {
+            name: self.profile_name().to_string(),
+            description: Some(description.to_string()),
+            cpu_governor: Some(governor.to_string()),
+            nice_level: Some(nice_level),
+            gpu_power_limit_watts: None,
+            gamescope_args: gamescope_args.iter().map(|arg| arg.to_string()).collect(),
+            builtin: true,
+        }
+    }
+}
+
+/// Where Proton builds are installed and where GE-Proton releases are listed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct ProtonConfig {
+    /// One subdirectory per installed build, as in Steam's `compatibilitytools.d`
+    pub install_dir: String,
+    /// GitHub releases API of GE-Proton; point it elsewhere to use a mirror
+    pub releases_url: String,
+    /// How long the release list is reused before GitHub is asked again
+    pub releases_cache_secs: u64,
+}
+
+impl Default for ProtonConfig {
+    fn default() -> Self {
+        Self {
+            install_dir: "/var/lib/ghostpanel/compatibilitytools.d".to_string(),
+            releases_url: "https://api.github.com/repos/GloriousEggroll/proton-ge-custom/releases".to_string(),
+            releases_cache_secs: 60 * 60,
+        }
+    }
+}
+
+/// Proton build found in the install directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct InstalledProton {
+    /// Directory name, e.g. `GE-Proton9-20`; what `GamingConfig.proton_version` takes
+    pub name: String,
+    pub path: String,
+}
+
+/// GE-Proton release published on GitHub
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProtonRelease {
+    pub tag: String,
+    pub published_at: Option<DateTime<Utc>>,
+    /// Size of the release archive
+    pub size_bytes: u64,
+    pub installed: bool,
+}
+
+/// Response of GET /api/v1/gaming/proton/versions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProtonVersions {
+    /// Installed builds by name, newest GE-Proton first
+    pub installed: Vec<InstalledProton>,
+    /// Releases, newest first. May be from an earlier fetch when GitHub is unavailable.
+    pub available: Vec<ProtonRelease>,
+    /// Why the release list could not be refreshed
+    pub available_error: Option<String>,
+    /// Set while GitHub's rate limit is exhausted; no requests are made before then
+    pub rate_limited_until: Option<DateTime<Utc>>,
+}
+
+/// Body of POST /api/v1/gaming/proton/install
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProtonInstallRequest {
+    /// Release tag, e.g. `GE-Proton9-20`
+    pub tag: String,
+}
+
+/// Where game metadata comes from and how long it is kept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct SteamConfig {
+    /// Steam store `appdetails` endpoint
+    pub store_api_url: String,
+    /// Steam Web API endpoint listing every app ID and name
+    pub app_list_url: String,
+    /// How long looked-up apps and the app list are used before Steam is asked again
+    pub cache_ttl_secs: u64,
+}
+
+impl Default for SteamConfig {
+    fn default() -> Self {
+        Self {
+            store_api_url: "https://store.steampowered.com/api/appdetails".to_string(),
+            app_list_url: "https://api.steampowered.com/ISteamApps/GetAppList/v2/".to_string(),
+            cache_ttl_secs: 24 * 60 * 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SteamPlatforms {
+    pub windows: bool,
+    pub mac: bool,
+    pub linux: bool,
+}
+
+/// Store page details of a Steam app, from GET /api/v1/gaming/steam/apps/{appid}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SteamApp {
+    pub app_id: u32,
+    pub name: String,
+    pub header_image: Option<String>,
+    pub platforms: SteamPlatforms,
+    pub fetched_at: DateTime<Utc>,
+    /// Served from an expired cache entry because Steam could not be reached
+    #[serde(default)]
+    pub stale: bool,
+}
+
+/// Search result from GET /api/v1/gaming/steam/apps
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SteamAppSummary {
+    pub app_id: u32,
+    pub name: String,
+}
+
+/// Limits above which the gaming dashboard warns about a container's GPU
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct GamingAlertThresholds {
+    pub gpu_temperature_celsius: f32,
+    pub gpu_power_watts: f32,
+}
+
+impl Default for GamingAlertThresholds {
+    fn default() -> Self {
+        Self {
+            gpu_temperature_celsius: 85.0,
+            gpu_power_watts: 300.0,
+        }
+    }
+}
+
+impl GamingAlertThresholds {
+    /// Limits a stats sample is over; readings the sample lacks are not checked
+    pub fn warnings(&self, stats: &ContainerStats) -> Vec<String> {
+        let gpu = stats.gpu.as_ref();
+        let temperature = gpu
+            .and_then(|gpu| gpu.temperature)
+            .or_else(|| stats.gaming.as_ref().and_then(|gaming| gaming.gpu_temperature));
+        let power = gpu.and_then(|gpu| gpu.power_usage);
+
+        let mut warnings = Vec::new();
+        if let Some(temperature) = temperature.filter(|t| *t > self.gpu_temperature_celsius) {
+            warnings.push(format!(
+                "GPU at {:.0} °C, above {:.0} °C",
+                temperature, self.gpu_temperature_celsius
+            ));
+        }
+        if let Some(power) = power.filter(|watts| *watts > self.gpu_power_watts) {
+            warnings.push(format!("GPU drawing {:.0} W, above {:.0} W", power, self.gpu_power_watts));
+        }
+        warnings
+    }
+}
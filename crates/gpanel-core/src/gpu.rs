@@ -0,0 +1,211 @@
+use serde::{Deserialize, Serialize};
+
+use crate::container::{Container, ContainerStatus, GpuAllocation, GpuType, IsolationLevel};
+
+/// GPU found on the agent host, from GET /api/v1/system/gpus
+///
+/// NVIDIA devices are `nvidia0`, `nvidia1`, … by NVML index, matching `/dev/nvidiaN`; AMD
+/// and Intel devices are named after their DRM card, matching `/dev/dri/cardN`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GpuDevice {
+    /// What a container's `gpu_allocation.device_id` names
+    pub id: String,
+    pub vendor: GpuType,
+    pub name: String,
+    /// Dedicated memory; 0 when the driver does not report it, as for integrated GPUs
+    pub memory_total_mb: u64,
+    pub driver_version: Option<String>,
+    /// Running containers allocated this GPU
+    pub in_use_by: Vec<String>,
+}
+
+impl GpuAllocation {
+    pub fn is_exclusive(&self) -> bool {
+        matches!(self.isolation_level, IsolationLevel::Exclusive)
+    }
+
+    /// Why this allocation cannot use the same GPU as `held`, or `None` if they can share it.
+    /// Exclusive allocations share with nothing; shared and partitioned ones share with
+    /// anything but an exclusive one, except that two claims on one partition conflict.
+    pub fn conflict_with(&self, held: &GpuAllocation) -> Option<String> {
+        if self.device_id != held.device_id {
+            return None;
+        }
+        match (&self.isolation_level, &held.isolation_level) {
+            (_, IsolationLevel::Exclusive) => Some("it holds the GPU exclusively".to_string()),
+            (IsolationLevel::Exclusive, _) => Some("an exclusive allocation needs the GPU to itself".to_string()),
+            (
+                IsolationLevel::Partitioned { partition_id },
+                IsolationLevel::Partitioned { partition_id: held_partition },
+            ) if partition_id == held_partition => Some(format!("both allocations use partition '{}'", partition_id)),
+            _ => None,
+        }
+    }
+}
+
+/// Running container holding a GPU claim a new allocation cannot coexist with
+#[derive(Debug, Clone)]
+pub struct GpuConflict<'a> {
+    pub holder: &'a Container,
+    pub reason: String,
+}
+
+impl GpuConflict<'_> {
+    pub fn message(&self, allocation: &GpuAllocation) -> String {
+        format!(
+            "GPU '{}' is in use by running container '{}': {}",
+            allocation.device_id, self.holder.name, self.reason
+        )
+    }
+}
+
+/// First running container whose GPU claim conflicts with `allocation`. Stopped containers
+/// hold no GPU.
+pub fn find_gpu_conflict<'a>(allocation: &GpuAllocation, existing: &'a [Container]) -> Option<GpuConflict<'a>> {
+    existing.iter().filter(|container| !container.status.is_stopped()).find_map(|container| {
+        let held = container.gpu_allocation.as_ref()?;
+        let reason = allocation.conflict_with(held)?;
+        Some(GpuConflict { holder: container, reason })
+    })
+}
+
+/// A container's claim on a GPU, from GET /api/v1/system/gpus/{id}/allocations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GpuClaim {
+    pub container_id: String,
+    pub container_name: String,
+    pub status: ContainerStatus,
+    pub isolation_level: IsolationLevel,
+    pub memory_mb: Option<u64>,
+    pub compute_units: Option<u32>,
+    /// Only containers that are not stopped hold their claim and can block others
+    pub active: bool,
+}
+
+impl GpuClaim {
+    /// Claims on `device_id` by `containers`, active ones first, then by container name
+    pub fn for_device(device_id: &str, containers: &[Container]) -> Vec<GpuClaim> {
+        let mut claims: Vec<GpuClaim> = containers
+            .iter()
+            .filter_map(|container| {
+                let allocation = container.gpu_allocation.as_ref().filter(|a| a.device_id == device_id)?;
+                Some(GpuClaim {
+                    container_id: container.id.clone(),
+                    container_name: container.name.clone(),
+                    status: container.status.clone(),
+                    isolation_level: allocation.isolation_level.clone(),
+                    memory_mb: allocation.memory_mb,
+                    compute_units: allocation.compute_units,
+                    active: !container.status.is_stopped(),
+                })
+            })
+            .collect();
+        claims.sort_by(|a, b| b.active.cmp(&a.active).then_with(|| a.container_name.cmp(&b.container_name)));
+        claims
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::RestartPolicy;
+    use std::collections::HashMap;
+
+    fn allocation(device_id: &str, isolation_level: IsolationLevel) -> GpuAllocation {
+        GpuAllocation {
+            device_id: device_id.to_string(),
+            gpu_type: GpuType::Nvidia,
+            memory_mb: None,
+            compute_units: None,
+            isolation_level,
+        }
+    }
+
+    fn partition(id: &str) -> IsolationLevel {
+        IsolationLevel::Partitioned { partition_id: id.to_string() }
+    }
+
+    fn container(name: &str, status: ContainerStatus, allocation: GpuAllocation) -> Container {
+        Container {
+            id: format!("{}-id", name),
+            name: name.to_string(),
+            image: "game:latest".to_string(),
+            status,
+            ports: Vec::new(),
+            volumes: Vec::new(),
+            networks: Vec::new(),
+            env: HashMap::new(),
+            labels: HashMap::new(),
+            created_at: chrono::Utc::now(),
+            started_at: None,
+            finished_at: None,
+            health_status: None,
+            restart_policy: RestartPolicy::No,
+            gaming_config: None,
+            gpu_allocation: Some(allocation),
+            performance_metrics: None,
+        }
+    }
+
+    #[test]
+    fn conflict_matrix() {
+        // (new, held, conflicts)
+        let cases = [
+            (IsolationLevel::Exclusive, IsolationLevel::Exclusive, true),
+            (IsolationLevel::Exclusive, IsolationLevel::Shared, true),
+            (IsolationLevel::Exclusive, partition("a"), true),
+            (IsolationLevel::Shared, IsolationLevel::Exclusive, true),
+            (IsolationLevel::Shared, IsolationLevel::Shared, false),
+            (IsolationLevel::Shared, partition("a"), false),
+            (partition("a"), IsolationLevel::Exclusive, true),
+            (partition("a"), IsolationLevel::Shared, false),
+            (partition("a"), partition("a"), true),
+            (partition("a"), partition("b"), false),
+        ];
+        for (new, held, conflicts) in cases {
+            let label = format!("{:?} against {:?}", new, held);
+            let result = allocation("nvidia0", new).conflict_with(&allocation("nvidia0", held));
+            assert_eq!(result.is_some(), conflicts, "{}", label);
+        }
+    }
+
+    #[test]
+    fn other_devices_never_conflict() {
+        let new = allocation("nvidia0", IsolationLevel::Exclusive);
+        assert!(new.conflict_with(&allocation("nvidia1", IsolationLevel::Exclusive)).is_none());
+    }
+
+    #[test]
+    fn stopped_containers_hold_no_claim() {
+        let new = allocation("nvidia0", IsolationLevel::Exclusive);
+        let exited = container("old", ContainerStatus::Exited { code: 0 }, allocation("nvidia0", IsolationLevel::Exclusive));
+        let created = container("new", ContainerStatus::Created, allocation("nvidia0", IsolationLevel::Shared));
+        assert!(find_gpu_conflict(&new, &[exited.clone(), created.clone()]).is_none());
+
+        let running = container("game", ContainerStatus::Running, allocation("nvidia0", IsolationLevel::Shared));
+        let paused = container("paused", ContainerStatus::Paused, allocation("nvidia0", partition("a")));
+        let existing = [exited, created, running];
+        let conflict = find_gpu_conflict(&new, &existing).unwrap();
+        assert_eq!(conflict.holder.name, "game");
+        assert!(conflict.message(&new).contains("'game'"));
+
+        // A paused container keeps its claim
+        let conflict = find_gpu_conflict(&allocation("nvidia0", partition("a")), std::slice::from_ref(&paused)).unwrap();
+        assert!(conflict.reason.contains("partition 'a'"));
+    }
+
+    #[test]
+    fn claims_list_active_holders_first() {
+        let containers = [
+            container("b-stopped", ContainerStatus::Exited { code: 1 }, allocation("nvidia0", IsolationLevel::Exclusive)),
+            container("c-running", ContainerStatus::Running, allocation("nvidia0", partition("x"))),
+            container("a-running", ContainerStatus::Running, allocation("nvidia0", IsolationLevel::Shared)),
+            container("elsewhere", ContainerStatus::Running, allocation("nvidia1", IsolationLevel::Shared)),
+        ];
+        let claims = GpuClaim::for_device("nvidia0", &containers);
+        let order: Vec<(&str, bool)> = claims.iter().map(|c| (c.container_name.as_str(), c.active)).collect();
+        assert_eq!(order, [("a-running", true), ("c-running", true), ("b-stopped", false)]);
+    }
+}
@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::image_ref::ImageRef;
+
+/// Image stored locally on the Bolt host (as opposed to an image in a registry)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LocalImage {
+    pub id: String,
+    pub repo_tags: Vec<String>,
+    pub size: u64,
+    /// Bytes shared with other local images through common layers
+    pub shared_size: u64,
+    pub created: chrono::DateTime<chrono::Utc>,
+    /// Untagged image left behind after its tag moved to a newer build
+    pub dangling: bool,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    /// Containers using this image (filled in by the agent, not by Bolt)
+    #[serde(default)]
+    pub containers: Vec<String>,
+}
+
+impl LocalImage {
+    /// Check whether a container image reference points at this image
+    pub fn matches_reference(&self, reference: &str) -> bool {
+        if self.id == reference || self.id.strip_prefix("sha256:") == Some(reference) {
+            return true;
+        }
+
+        let normalized = normalize_image_reference(reference);
+        self.repo_tags.iter().any(|tag| normalize_image_reference(tag) == normalized)
+    }
+
+    /// Bytes that would be freed by removing this image
+    pub fn unique_size(&self) -> u64 {
+        self.size.saturating_sub(self.shared_size)
+    }
+}
+
+/// Filters for pruning local images
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImagePruneFilter {
+    /// Only remove dangling images; otherwise every image without containers is removed
+    pub dangling_only: bool,
+    /// Only remove images created before this time
+    pub until: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only remove images carrying this label (`key` or `key=value`)
+    pub label: Option<String>,
+}
+
+impl ImagePruneFilter {
+    /// Check whether an image is a prune candidate; images used by containers never are
+    pub fn matches(&self, image: &LocalImage, in_use: bool) -> bool {
+        if in_use {
+            return false;
+        }
+        if self.dangling_only && !image.dangling {
+            return false;
+        }
+        if let Some(until) = self.until
+            && image.created >= until
+        {
+            return false;
+        }
+        if let Some(label) = &self.label {
+            let matched = match label.split_once('=') {
+                Some((key, value)) => image.labels.get(key).map(|v| v == value).unwrap_or(false),
+                None => image.labels.contains_key(label),
+            };
+            if !matched {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Result of an image prune
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImagePruneReport {
+    pub deleted: Vec<String>,
+    pub reclaimed_bytes: u64,
+}
+
+/// Request to save a container's filesystem as a new image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ContainerCommitRequest {
+    pub repository: String,
+    /// Defaults to `latest`
+    pub tag: Option<String>,
+    pub comment: Option<String>,
+    /// Pause the container while committing so the snapshot is consistent; defaults to true
+    pub pause: Option<bool>,
+}
+
+/// Image created by a commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageSummary {
+    pub id: String,
+    /// Content digest of the image config, `sha256:...`
+    pub digest: String,
+    pub repo_tags: Vec<String>,
+    pub size: u64,
+    pub created: chrono::DateTime<chrono::Utc>,
+}
+
+/// Check that a repository name is valid for tagging (e.g. `library/nginx`)
+pub fn is_valid_repository(repository: &str) -> bool {
+    !repository.is_empty()
+        && repository.len() <= 255
+        && repository.split('/').all(|component| {
+            !component.is_empty()
+                && component
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '.' | '_' | '-'))
+                && component.starts_with(|c: char| c.is_ascii_alphanumeric())
+                && component.ends_with(|c: char| c.is_ascii_alphanumeric())
+        })
+}
+
+/// Check that a tag is valid (up to 128 characters, not starting with `.` or `-`)
+pub fn is_valid_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag.len() <= 128
+        && !tag.starts_with(['.', '-'])
+        && tag.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+/// A reference in full, with Docker Hub shorthand expanded and the implicit `latest` tag
+/// added, so `nginx` and `docker.io/library/nginx:latest` compare equal. References that
+/// do not parse are compared as they are.
+fn normalize_image_reference(reference: &str) -> String {
+    match ImageRef::parse(reference) {
+        Ok(image) => match image.digest() {
+            Some(digest) => format!("{}/{}:{}@{}", image.registry(), image.repository(), image.tag(), digest),
+            None => format!("{}/{}:{}", image.registry(), image.repository(), image.tag()),
+        },
+        Err(_) => reference.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn image(dangling: bool, age_hours: i64, labels: &[(&str, &str)]) -> LocalImage {
+        LocalImage {
+            id: "sha256:abc".to_string(),
+            repo_tags: if dangling { Vec::new() } else { vec!["game:latest".to_string()] },
+            size: 100,
+            shared_size: 40,
+            created: Utc::now() - Duration::hours(age_hours),
+            dangling,
+            labels: labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            containers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn prune_never_matches_images_in_use() {
+        let filter = ImagePruneFilter::default();
+        assert!(filter.matches(&image(true, 1, &[]), false));
+        assert!(!filter.matches(&image(true, 1, &[]), true));
+    }
+
+    #[test]
+    fn prune_dangling_only_keeps_tagged_images() {
+        let filter = ImagePruneFilter {
+            dangling_only: true,
+            ..Default::default()
+        };
+        assert!(filter.matches(&image(true, 1, &[]), false));
+        assert!(!filter.matches(&image(false, 1, &[]), false));
+    }
+
+    #[test]
+    fn prune_until_keeps_newer_images() {
+        let filter = ImagePruneFilter {
+            until: Some(Utc::now() - Duration::hours(24)),
+            ..Default::default()
+        };
+        assert!(filter.matches(&image(true, 48, &[]), false));
+        assert!(!filter.matches(&image(true, 1, &[]), false));
+    }
+
+    #[test]
+    fn prune_label_matches_key_or_key_and_value() {
+        let tagged = image(true, 1, &[("stage", "build")]);
+        let by_key = ImagePruneFilter {
+            label: Some("stage".to_string()),
+            ..Default::default()
+        };
+        let by_value = ImagePruneFilter {
+            label: Some("stage=build".to_string()),
+            ..Default::default()
+        };
+        let other_value = ImagePruneFilter {
+            label: Some("stage=release".to_string()),
+            ..Default::default()
+        };
+        assert!(by_key.matches(&tagged, false));
+        assert!(by_value.matches(&tagged, false));
+        assert!(!other_value.matches(&tagged, false));
+        assert!(!by_key.matches(&image(true, 1, &[]), false));
+    }
+
+    #[test]
+    fn references_match_however_they_are_written() {
+        let mut nginx = image(false, 1, &[]);
+        nginx.repo_tags = vec!["nginx:latest".to_string(), "localhost:5000/app:1.0".to_string()];
+        for reference in ["nginx", "nginx:latest", "library/nginx", "docker.io/library/nginx:latest", "localhost:5000/app:1.0"] {
+            assert!(nginx.matches_reference(reference), "{}", reference);
+        }
+        for reference in ["nginx:1.25", "app:1.0", "localhost:5001/app:1.0", "ghcr.io/nginx"] {
+            assert!(!nginx.matches_reference(reference), "{}", reference);
+        }
+        assert!(nginx.matches_reference("sha256:abc") && nginx.matches_reference("abc"));
+    }
+
+    #[test]
+    fn unique_size_excludes_shared_layers() {
+        assert_eq!(image(false, 1, &[]).unique_size(), 60);
+    }
+}
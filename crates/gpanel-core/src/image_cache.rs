@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::metrics::ImageCacheStats;
+use crate::api::ImageInfo;
+
+/// How many image details registries are spared fetching again, and for how long
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct ImageCacheConfig {
+    /// Most images kept, dropping the least recently used first; 0 turns the cache off
+    pub capacity: usize,
+    /// How long an image is kept after it was fetched, even while its digest is unchanged
+    pub ttl_secs: u64,
+    /// Keep the cache in `data_dir` so it survives restarts
+    pub persist: bool,
+}
+
+impl Default for ImageCacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 500,
+            ttl_secs: 24 * 60 * 60,
+            persist: false,
+        }
+    }
+}
+
+/// What an image was looked up by. `reference` is a tag or a digest.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ImageCacheKey {
+    pub registry: String,
+    pub repository: String,
+    pub reference: String,
+    pub platform: Option<String>,
+}
+
+/// A cached image, as it is kept on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedImage {
+    pub key: ImageCacheKey,
+    pub info: ImageInfo,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Least recently used cache of image details. Whether an entry is still current is for
+/// the caller to check, by comparing its `manifest_digest` with the registry's.
+#[derive(Debug)]
+pub struct ImageCache {
+    config: ImageCacheConfig,
+    /// Each image with the use count it was last used at
+    entries: HashMap<ImageCacheKey, (CachedImage, u64)>,
+    uses: u64,
+    hits: u64,
+    misses: u64,
+    /// Set when entries change, so persisting can skip an unchanged cache
+    changed: bool,
+}
+
+impl ImageCache {
+    pub fn new(config: ImageCacheConfig) -> Self {
+        Self {
+            config,
+            entries: HashMap::new(),
+            uses: 0,
+            hits: 0,
+            misses: 0,
+            changed: false,
+        }
+    }
+
+    fn is_fresh(&self, fetched_at: DateTime<Utc>) -> bool {
+        let ttl = chrono::Duration::seconds(self.config.ttl_secs.try_into().unwrap_or(i64::MAX));
+        Utc::now() < fetched_at + ttl
+    }
+
+    /// The image cached under `key`, unless it has outlived the TTL
+    pub fn get(&mut self, key: &ImageCacheKey) -> Option<ImageInfo> {
+        let fetched_at = self.entries.get(key)?.0.fetched_at;
+        if !self.is_fresh(fetched_at) {
+            self.entries.remove(key);
+            self.changed = true;
+            return None;
+        }
+        self.uses += 1;
+        let (cached, last_use) = self.entries.get_mut(key)?;
+        *last_use = self.uses;
+        Some(cached.info.clone())
+    }
+
+    pub fn insert(&mut self, key: ImageCacheKey, info: ImageInfo) {
+        self.insert_cached(CachedImage { key, info, fetched_at: Utc::now() });
+    }
+
+    fn insert_cached(&mut self, cached: CachedImage) {
+        if self.config.capacity == 0 {
+            return;
+        }
+        if !self.entries.contains_key(&cached.key) && self.entries.len() >= self.config.capacity {
+            let oldest = self.entries.iter().min_by_key(|(_, (_, last_use))| *last_use).map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.uses += 1;
+        self.entries.insert(cached.key.clone(), (cached, self.uses));
+        self.changed = true;
+    }
+
+    /// Forget every image of `repository` on `registry`, such as after it was deleted
+    pub fn invalidate(&mut self, registry: &str, repository: &str) {
+        let before = self.entries.len();
+        self.entries.retain(|key, _| key.registry != registry || key.repository != repository);
+        self.changed |= self.entries.len() != before;
+    }
+
+    pub fn record_hit(&mut self) {
+        self.hits += 1;
+    }
+
+    pub fn record_miss(&mut self) {
+        self.misses += 1;
+    }
+
+    pub fn stats(&self) -> ImageCacheStats {
+        ImageCacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.entries.len(),
+            capacity: self.config.capacity,
+        }
+    }
+
+    /// Every entry, least recently used first, when any changed since the last call
+    pub fn take_changes(&mut self) -> Option<Vec<CachedImage>> {
+        if !std::mem::take(&mut self.changed) {
+            return None;
+        }
+        let mut entries: Vec<_> = self.entries.values().collect();
+        entries.sort_by_key(|(_, last_use)| *last_use);
+        Some(entries.into_iter().map(|(cached, _)| cached.clone()).collect())
+    }
+
+    /// Add entries kept from an earlier run, dropping any that have expired since
+    pub fn restore(&mut self, cached: Vec<CachedImage>) {
+        for cached in cached {
+            if self.is_fresh(cached.fetched_at) {
+                self.insert_cached(cached);
+            }
+        }
+        self.changed = false;
+    }
+}
@@ -0,0 +1,340 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::image::{is_valid_repository, is_valid_tag};
+
+/// Registry of references that name none, such as `nginx:1.25`
+pub const DOCKER_HUB_REGISTRY: &str = "docker.io";
+
+/// Hosts Docker Hub is reached or referred to by
+pub const DOCKER_HUB_HOSTS: &[&str] = &["registry-1.docker.io", "registry.hub.docker.com", "index.docker.io", "docker.io"];
+
+/// An image reference, `[registry[:port]/]repository[:tag][@digest]`, as in
+/// `registry.example.com:5000/team/app:1.2@sha256:...`. References without a registry are
+/// on Docker Hub, whose official images live under `library/`, so `nginx` is
+/// `docker.io/library/nginx`. Displays in that full form, which parses back to the same
+/// reference.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ImageRef {
+    registry: String,
+    repository: String,
+    tag: Option<String>,
+    digest: Option<String>,
+}
+
+/// Why a string is not an image reference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidImageRef {
+    pub reference: String,
+    pub reason: String,
+}
+
+impl fmt::Display for InvalidImageRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' is not a valid image reference: {}", self.reference, self.reason)
+    }
+}
+
+impl std::error::Error for InvalidImageRef {}
+
+impl ImageRef {
+    pub fn parse(reference: &str) -> Result<Self, InvalidImageRef> {
+        let invalid = |reason: &str| InvalidImageRef {
+            reference: reference.to_string(),
+            reason: reason.to_string(),
+        };
+        if reference.is_empty() {
+            return Err(invalid("it is empty"));
+        }
+
+        let (name, digest) = match reference.split_once('@') {
+            Some((name, digest)) => {
+                if !is_valid_digest(digest) {
+                    return Err(invalid("the digest must be sha256 with 64 or sha512 with 128 lowercase hex digits"));
+                }
+                (name, Some(digest.to_string()))
+            }
+            None => (reference, None),
+        };
+
+        // A colon after the last slash separates the tag; earlier ones belong to a registry port
+        let (path, tag) = match name.rfind(':') {
+            Some(index) if !name[index..].contains('/') => (&name[..index], Some(&name[index + 1..])),
+            _ => (name, None),
+        };
+        if let Some(tag) = tag
+            && !is_valid_tag(tag)
+        {
+            return Err(invalid("tags are up to 128 letters, digits, '_', '.' and '-', not starting with '.' or '-'"));
+        }
+
+        // The first component is a registry host if it looks like one: `localhost`, or with
+        // a dot, a port or IPv6 brackets
+        let (registry, repository) = match path.split_once('/') {
+            Some((host, rest)) if host == "localhost" || host.contains(['.', ':', '[']) => {
+                if !is_valid_host(host) {
+                    return Err(invalid("the registry must be a host name or IP address with an optional port"));
+                }
+                let host = if DOCKER_HUB_HOSTS.contains(&host) { DOCKER_HUB_REGISTRY } else { host };
+                (host.to_string(), rest)
+            }
+            _ => (DOCKER_HUB_REGISTRY.to_string(), path),
+        };
+        let repository = if registry == DOCKER_HUB_REGISTRY && !repository.contains('/') {
+            format!("library/{}", repository)
+        } else {
+            repository.to_string()
+        };
+        if !is_valid_repository(&repository) {
+            return Err(invalid(
+                "repository names are lowercase letters, digits and '.', '_' or '-' between them, in '/'-separated parts",
+            ));
+        }
+
+        Ok(Self {
+            registry,
+            repository,
+            tag: tag.map(str::to_string),
+            digest,
+        })
+    }
+
+    /// Registry host with its port, if any; `docker.io` for Docker Hub
+    pub fn registry(&self) -> &str {
+        &self.registry
+    }
+
+    /// Repository within the registry, such as `library/nginx`
+    pub fn repository(&self) -> &str {
+        &self.repository
+    }
+
+    /// The tag, `latest` when the reference names none
+    pub fn tag(&self) -> &str {
+        self.tag.as_deref().unwrap_or("latest")
+    }
+
+    pub fn digest(&self) -> Option<&str> {
+        self.digest.as_deref()
+    }
+
+    /// What to ask the registry for: the digest when there is one, as it pins the image,
+    /// and the tag otherwise
+    pub fn reference(&self) -> &str {
+        self.digest.as_deref().unwrap_or_else(|| self.tag())
+    }
+
+    pub fn is_docker_hub(&self) -> bool {
+        self.registry == DOCKER_HUB_REGISTRY
+    }
+}
+
+impl fmt::Display for ImageRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.registry, self.repository)?;
+        if let Some(tag) = &self.tag {
+            write!(f, ":{}", tag)?;
+        }
+        if let Some(digest) = &self.digest {
+            write!(f, "@{}", digest)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ImageRef {
+    type Err = InvalidImageRef;
+
+    fn from_str(reference: &str) -> Result<Self, Self::Err> {
+        Self::parse(reference)
+    }
+}
+
+impl TryFrom<String> for ImageRef {
+    type Error = InvalidImageRef;
+
+    fn try_from(reference: String) -> Result<Self, Self::Error> {
+        Self::parse(&reference)
+    }
+}
+
+impl From<ImageRef> for String {
+    fn from(reference: ImageRef) -> Self {
+        reference.to_string()
+    }
+}
+
+fn is_valid_digest(digest: &str) -> bool {
+    let Some((algorithm, hex)) = digest.split_once(':') else {
+        return false;
+    };
+    let length = match algorithm {
+        "sha256" => 64,
+        "sha512" => 128,
+        _ => return false,
+    };
+    hex.len() == length && hex.chars().all(|c| c.is_ascii_digit() || matches!(c, 'a'..='f'))
+}
+
+/// `host`, `host:port`, `[ipv6]` or `[ipv6]:port`
+fn is_valid_host(host: &str) -> bool {
+    let (hostname, port) = if let Some(bracketed) = host.strip_prefix('[') {
+        let Some((address, rest)) = bracketed.split_once(']') else {
+            return false;
+        };
+        let port = match rest {
+            "" => None,
+            rest => match rest.strip_prefix(':') {
+                Some(port) => Some(port),
+                None => return false,
+            },
+        };
+        if address.parse::<std::net::Ipv6Addr>().is_err() {
+            return false;
+        }
+        (None, port)
+    } else {
+        match host.split_once(':') {
+            Some((hostname, port)) => (Some(hostname), Some(port)),
+            None => (Some(host), None),
+        }
+    };
+    let valid_hostname = hostname.is_none_or(|hostname| {
+        !hostname.is_empty()
+            && hostname.split('.').all(|label| {
+                !label.is_empty()
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            })
+    });
+    valid_hostname && port.is_none_or(|port| port.parse::<u16>().is_ok_and(|port| port > 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIGEST: &str = "sha256:0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    fn parts(reference: &str) -> (String, String, String, Option<String>) {
+        let parsed = ImageRef::parse(reference).unwrap_or_else(|e| panic!("{}", e));
+        (
+            parsed.registry().to_string(),
+            parsed.repository().to_string(),
+            parsed.tag().to_string(),
+            parsed.digest().map(str::to_string),
+        )
+    }
+
+    fn expect(registry: &str, repository: &str, tag: &str, digest: Option<&str>) -> (String, String, String, Option<String>) {
+        (registry.to_string(), repository.to_string(), tag.to_string(), digest.map(str::to_string))
+    }
+
+    #[test]
+    fn docker_hub_shorthand_is_normalized() {
+        assert_eq!(parts("nginx"), expect("docker.io", "library/nginx", "latest", None));
+        assert_eq!(parts("nginx:1.25-alpine"), expect("docker.io", "library/nginx", "1.25-alpine", None));
+        assert_eq!(parts("bitnami/redis:7.2"), expect("docker.io", "bitnami/redis", "7.2", None));
+        assert_eq!(parts("docker.io/nginx"), expect("docker.io", "library/nginx", "latest", None));
+        assert_eq!(parts("index.docker.io/library/nginx:1"), expect("docker.io", "library/nginx", "1", None));
+        assert_eq!(parts("registry-1.docker.io/grafana/grafana"), expect("docker.io", "grafana/grafana", "latest", None));
+        assert!(ImageRef::parse("nginx").unwrap().is_docker_hub());
+    }
+
+    #[test]
+    fn registry_hosts_keep_their_ports() {
+        assert_eq!(
+            parts("registry.example.com:5000/team/app:1.2"),
+            expect("registry.example.com:5000", "team/app", "1.2", None)
+        );
+        assert_eq!(parts("localhost:5000/app"), expect("localhost:5000", "app", "latest", None));
+        assert_eq!(parts("localhost/app:dev"), expect("localhost", "app", "dev", None));
+        assert_eq!(parts("127.0.0.1:5000/app:v1"), expect("127.0.0.1:5000", "app", "v1", None));
+        assert_eq!(parts("[::1]:5000/app:v1"), expect("[::1]:5000", "app", "v1", None));
+        assert_eq!(parts("[fe80::1]/app"), expect("[fe80::1]", "app", "latest", None));
+        assert_eq!(parts("ghcr.io/org/team/tool:2024.01"), expect("ghcr.io", "org/team/tool", "2024.01", None));
+        // Registries other than Docker Hub have no `library/`
+        assert_eq!(parts("ghcr.io/tool"), expect("ghcr.io", "tool", "latest", None));
+        assert!(!ImageRef::parse("ghcr.io/tool").unwrap().is_docker_hub());
+        // Without a slash, a colon is a tag, as Docker reads it
+        assert_eq!(parts("localhost:5000"), expect("docker.io", "library/localhost", "5000", None));
+    }
+
+    #[test]
+    fn digests_are_read_with_or_without_tags() {
+        let sha512 = format!("sha512:{}", "ab".repeat(64));
+        assert_eq!(parts(&format!("nginx@{}", DIGEST)), expect("docker.io", "library/nginx", "latest", Some(DIGEST)));
+        assert_eq!(
+            parts(&format!("registry.example.com:5000/team/app:1.2@{}", DIGEST)),
+            expect("registry.example.com:5000", "team/app", "1.2", Some(DIGEST))
+        );
+        assert_eq!(parts(&format!("localhost:5000/app@{}", sha512)), expect("localhost:5000", "app", "latest", Some(&sha512)));
+
+        let pinned = ImageRef::parse(&format!("app:1.2@{}", DIGEST)).unwrap();
+        assert_eq!(pinned.reference(), DIGEST);
+        assert_eq!(ImageRef::parse("app:1.2").unwrap().reference(), "1.2");
+        assert_eq!(ImageRef::parse("app").unwrap().reference(), "latest");
+    }
+
+    #[test]
+    fn invalid_references_say_why() {
+        for (reference, reason) in [
+            ("", "empty"),
+            ("Nginx", "repository"),
+            ("nginx:", "tags"),
+            ("nginx:-dev", "tags"),
+            ("nginx:a/b", "registry"),
+            ("team//app", "repository"),
+            ("/app", "repository"),
+            ("app/", "repository"),
+            ("nginx@sha256:abc", "digest"),
+            ("nginx@md5:0123456789abcdef0123456789abcdef", "digest"),
+            (&format!("nginx@{}", DIGEST.to_uppercase()), "digest"),
+            (&format!("nginx@{}@{}", DIGEST, DIGEST), "digest"),
+            ("registry.example.com:0/app", "registry"),
+            ("registry.example.com:99999/app", "registry"),
+            ("registry.example.com:port/app", "registry"),
+            ("-bad.example.com/app", "registry"),
+            ("bad..example.com/app", "registry"),
+            ("[::1/app", "registry"),
+            ("[not-ipv6]:5000/app", "registry"),
+            ("[::1]x/app", "registry"),
+            ("nginx latest", "repository"),
+        ] {
+            let error = ImageRef::parse(reference).expect_err(reference);
+            assert!(error.reason.contains(reason), "{}: {}", reference, error);
+            assert_eq!(error.reference, reference);
+        }
+        assert_eq!(
+            ImageRef::parse("Nginx").unwrap_err().to_string(),
+            "'Nginx' is not a valid image reference: repository names are lowercase letters, digits and '.', '_' or '-' between them, in '/'-separated parts"
+        );
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for (reference, displayed) in [
+            ("nginx", "docker.io/library/nginx".to_string()),
+            ("nginx:1.25", "docker.io/library/nginx:1.25".to_string()),
+            ("registry.example.com:5000/team/app:1.2", "registry.example.com:5000/team/app:1.2".to_string()),
+            (&format!("[::1]:5000/app:v1@{}", DIGEST), format!("[::1]:5000/app:v1@{}", DIGEST)),
+            (&format!("ghcr.io/org/app@{}", DIGEST), format!("ghcr.io/org/app@{}", DIGEST)),
+        ] {
+            let parsed = ImageRef::parse(reference).unwrap();
+            assert_eq!(parsed.to_string(), displayed);
+            assert_eq!(ImageRef::parse(&displayed).unwrap(), parsed);
+            assert_eq!(displayed.parse::<ImageRef>().unwrap(), parsed);
+        }
+    }
+
+    #[test]
+    fn serializes_as_its_string() {
+        let parsed: ImageRef = serde_json::from_value(serde_json::json!("team/app:2")).unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), serde_json::json!("docker.io/team/app:2"));
+        let error = serde_json::from_value::<ImageRef>(serde_json::json!("Team/App")).unwrap_err();
+        assert!(error.to_string().contains("not a valid image reference"));
+    }
+}
@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Long-running operation the agent runs in the background
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    ImagePull,
+    ImageCopy,
+    ProtonInstall,
+    RegistryStats,
+    /// Recreating containers after a registry reported a push of their image
+    ContainerUpdate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_finished(self) -> bool {
+        self != JobStatus::Running
+    }
+}
+
+/// Background operation and how far it has got, from GET /api/v1/jobs/{id}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Job {
+    pub id: String,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    /// Percent complete, 0 to 100
+    pub percent: u8,
+    /// Bytes and layers transferred so far, for jobs that download an image
+    pub progress: Option<PullProgress>,
+    /// Current step while running; the result or error once finished
+    pub message: String,
+    /// What the job works on, e.g. `library/nginx:latest`
+    pub target: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// How far an image pull has got. Layers that were already stored count as downloaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PullProgress {
+    pub current_bytes: u64,
+    /// Sum of the layer sizes in the manifest
+    pub total_bytes: u64,
+    /// Layer being downloaded, counting from 1
+    pub current_layer: usize,
+    pub total_layers: usize,
+}
+
+/// Response of an endpoint that started a job, sent with 202 Accepted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct JobAccepted {
+    pub job_id: String,
+}
@@ -1,30 +1,189 @@
 pub mod api;
+pub mod audit;
+pub mod auth;
+#[cfg(feature = "client")]
 pub mod bolt;
+#[cfg(feature = "client")]
 pub mod config;
 pub mod container;
+pub mod environment;
 pub mod error;
+pub mod event;
+pub mod gaming;
+pub mod gpu;
+pub mod image;
+pub mod image_ref;
+#[cfg(feature = "client")]
+pub mod image_cache;
+pub mod job;
+pub mod logs;
+pub mod metrics;
+pub mod network;
+pub mod notification;
+#[cfg(feature = "client")]
+pub mod oci_layout;
+#[cfg(feature = "client")]
+pub mod proxy;
 pub mod quic;
+#[cfg(feature = "client")]
 pub mod registry;
+#[cfg(feature = "client")]
+pub mod runtime;
+pub mod schedule;
+pub mod system;
+pub mod tags;
+pub mod validation;
+pub mod volume;
+pub mod watchdog;
+pub mod webhook;
 
 pub use error::{Error, Result};
+pub use api::*;
+pub use audit::*;
+pub use auth::*;
 pub use container::*;
+pub use environment::*;
+pub use event::*;
+pub use gaming::*;
+pub use gpu::*;
+pub use image::*;
+pub use image_ref::*;
+#[cfg(feature = "client")]
+pub use image_cache::*;
+pub use job::*;
+pub use logs::*;
+pub use metrics::*;
+pub use network::*;
+pub use notification::*;
+#[cfg(feature = "client")]
+pub use proxy::*;
+#[cfg(feature = "client")]
 pub use registry::*;
+#[cfg(feature = "client")]
+pub use runtime::ContainerRuntime;
+pub use schedule::*;
+pub use system::*;
+pub use tags::*;
+pub use validation::*;
+pub use volume::*;
+pub use watchdog::*;
+pub use webhook::*;
+#[cfg(feature = "client")]
 pub use bolt::*;
 
 /// Core types and utilities shared across GhostPanel components
+#[cfg(feature = "client")]
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
 pub struct GhostPanelConfig {
     pub web_port: u16,
     pub agent_port: u16,
     pub cli_port: u16,
+    /// `bolt://host:port` or `http://host:port` over TCP, or `unix:///run/bolt/bolt.sock`
     pub bolt_api_url: String,
+    /// Bolt hosts to manage; when empty, the one at `bolt_api_url` is the only environment
+    #[serde(default)]
+    pub environments: Vec<EnvironmentConfig>,
     pub enable_quic: bool,
     pub enable_http3: bool,
     pub tls_cert_path: Option<String>,
     pub tls_key_path: Option<String>,
+    /// With TLS enabled, also listen for plain HTTP here and redirect it to `agent_port`
+    #[serde(default)]
+    pub tls_redirect_port: Option<u16>,
     pub registries: Vec<RegistryConfig>,
+    /// Secret the passwords of registries added or edited through the API are encrypted
+    /// with in `data_dir`. When unset, a random one is generated and kept next to them.
+    #[serde(default)]
+    pub registry_secret: Option<String>,
+    /// Directory for agent state (webhooks, schedules, watchdog, log cache)
+    pub data_dir: String,
+    /// Bolt's own data directory on this host, whose filesystem the host metrics report
+    #[serde(default = "default_bolt_data_dir")]
+    pub bolt_data_dir: String,
+    pub webhooks: Vec<WebhookConfig>,
+    pub watchdog: WatchdogConfig,
+    pub log_cache: LogCacheConfig,
+    /// Missing from backups made before container file copy existed
+    #[serde(default)]
+    pub file_transfer: FileTransferConfig,
+    /// Timeouts and retries for calls to Bolt
+    #[serde(default)]
+    pub bolt_client: BoltClientConfig,
+    /// Start exited containers with an `always` or `unless-stopped` policy when the agent
+    /// starts, for hosts where Bolt does not bring them back after a reboot
+    #[serde(default = "default_autostart_on_boot")]
+    pub autostart_on_boot: bool,
+    /// Tokens required on `/api/v1` requests; more can be given in `GPANEL_API_TOKENS`
+    #[serde(default)]
+    pub auth: AuthConfig,
+    /// Log of every mutating request
+    #[serde(default)]
+    pub audit: AuditConfig,
+    /// Origins allowed to call the agent from a browser, exact (`https://panel.example.com`)
+    /// or with a wildcard subdomain (`https://*.example.com`). Empty allows only the web UI
+    /// on `web_port`; `*` allows any origin but is only honoured with auth disabled.
+    #[serde(default)]
+    pub cors_allowed_origins: Vec<String>,
+    #[serde(default = "default_cors_allowed_methods")]
+    pub cors_allowed_methods: Vec<String>,
+    #[serde(default = "default_cors_allowed_headers")]
+    pub cors_allowed_headers: Vec<String>,
+    /// How long browsers may cache a preflight response
+    #[serde(default = "default_cors_max_age_secs")]
+    pub cors_max_age_secs: u64,
+    /// How long finished background jobs, such as image pulls, can still be looked up
+    #[serde(default = "default_job_ttl_secs")]
+    pub job_ttl_secs: u64,
+    /// Proton builds for gaming containers
+    #[serde(default)]
+    pub proton: ProtonConfig,
+    /// Steam store lookups for gaming containers
+    #[serde(default)]
+    pub steam: SteamConfig,
+    /// Registry image details kept between lookups
+    #[serde(default)]
+    pub image_cache: ImageCacheConfig,
+    /// Push notifications from registries
+    #[serde(default)]
+    pub registry_webhook: RegistryWebhookConfig,
+    /// Proxies for requests to registries and Bolt
+    #[serde(default)]
+    pub proxy: ProxyConfig,
 }
 
+#[cfg(feature = "client")]
+fn default_autostart_on_boot() -> bool {
+    true
+}
+
+#[cfg(feature = "client")]
+fn default_bolt_data_dir() -> String {
+    "/var/lib/bolt".to_string()
+}
+
+#[cfg(feature = "client")]
+fn default_cors_allowed_methods() -> Vec<String> {
+    ["GET", "POST", "PUT", "PATCH", "DELETE"].iter().map(|method| method.to_string()).collect()
+}
+
+#[cfg(feature = "client")]
+fn default_cors_allowed_headers() -> Vec<String> {
+    ["authorization", "content-type", "x-backup-passphrase", ENVIRONMENT_HEADER].iter().map(|header| header.to_string()).collect()
+}
+
+#[cfg(feature = "client")]
+fn default_cors_max_age_secs() -> u64 {
+    600
+}
+
+#[cfg(feature = "client")]
+fn default_job_ttl_secs() -> u64 {
+    60 * 60
+}
+
+#[cfg(feature = "client")]
 impl Default for GhostPanelConfig {
     fn default() -> Self {
         Self {
@@ -32,10 +191,12 @@ impl Default for GhostPanelConfig {
             agent_port: 8000,
             cli_port: 9000,
             bolt_api_url: "bolt://localhost:8080".to_string(),
+            environments: Vec::new(),
             enable_quic: true,
             enable_http3: true,
             tls_cert_path: None,
             tls_key_path: None,
+            tls_redirect_port: None,
             registries: vec![
                 // Default local Drift registry
                 RegistryConfig {
@@ -44,6 +205,10 @@ impl Default for GhostPanelConfig {
                     username: None,
                     password: None,
                     insecure: true,
+                    page_size: None,
+                    max_list_entries: None,
+                    search_url: None,
+                    ca_cert_path: None,
                 },
                 // Docker Hub for public images
                 RegistryConfig {
@@ -52,8 +217,33 @@ impl Default for GhostPanelConfig {
                     username: None,
                     password: None,
                     insecure: false,
+                    page_size: None,
+                    max_list_entries: None,
+                    search_url: None,
+                    ca_cert_path: None,
                 },
             ],
+            registry_secret: None,
+            data_dir: "data".to_string(),
+            bolt_data_dir: default_bolt_data_dir(),
+            webhooks: Vec::new(),
+            watchdog: WatchdogConfig::default(),
+            log_cache: LogCacheConfig::default(),
+            file_transfer: FileTransferConfig::default(),
+            bolt_client: BoltClientConfig::default(),
+            autostart_on_boot: true,
+            auth: AuthConfig::default(),
+            audit: AuditConfig::default(),
+            cors_allowed_origins: Vec::new(),
+            cors_allowed_methods: default_cors_allowed_methods(),
+            cors_allowed_headers: default_cors_allowed_headers(),
+            cors_max_age_secs: default_cors_max_age_secs(),
+            job_ttl_secs: default_job_ttl_secs(),
+            proton: ProtonConfig::default(),
+            steam: SteamConfig::default(),
+            image_cache: ImageCacheConfig::default(),
+            registry_webhook: RegistryWebhookConfig::default(),
+            proxy: ProxyConfig::default(),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// Limits for the agent's on-disk container log cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LogCacheConfig {
+    pub enabled: bool,
+    /// Combined size of all cached logs; least recently used logs are evicted beyond this
+    pub max_total_bytes: u64,
+    /// Cached logs not read for this long are dropped
+    pub retention_secs: u64,
+    /// Cached logs younger than this are served without asking Bolt for new lines
+    pub fresh_secs: u64,
+}
+
+impl Default for LogCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_total_bytes: 256 * 1024 * 1024,
+            retention_secs: 24 * 60 * 60,
+            fresh_secs: 2,
+        }
+    }
+}
+
+/// Log cache usage reported on the metrics endpoint
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LogCacheStats {
+    pub entries: usize,
+    pub total_bytes: u64,
+    pub max_total_bytes: u64,
+    /// Requests served from cache without contacting Bolt
+    pub hits: u64,
+    /// Requests served from cache after fetching only new lines
+    pub delta_fetches: u64,
+    /// Requests that fetched the log from Bolt
+    pub misses: u64,
+    pub evictions: u64,
+}
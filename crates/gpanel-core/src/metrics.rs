@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+
+use crate::logs::LogCacheStats;
+
+/// Host-level aggregate of the latest recorded container stats
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MetricsSummary {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub containers: ContainerCounts,
+    /// Sum of container CPU percentages (100 per fully used core)
+    pub cpu_percent: f64,
+    pub memory_used_bytes: u64,
+    pub memory_limit_bytes: u64,
+    /// Throughput over each container's last sampling interval
+    pub network_rx_bytes_per_sec: f64,
+    pub network_tx_bytes_per_sec: f64,
+    pub disk_read_bytes_per_sec: f64,
+    pub disk_write_bytes_per_sec: f64,
+    pub top_cpu: Vec<ContainerMetric>,
+    pub top_memory: Vec<ContainerMetric>,
+    /// Present when at least one container reports GPU usage
+    pub gpu: Option<GpuAggregate>,
+    #[serde(default)]
+    pub log_cache: LogCacheStats,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ContainerCounts {
+    pub total: usize,
+    pub running: usize,
+    pub paused: usize,
+    pub stopped: usize,
+    pub other: usize,
+}
+
+/// Entry in a top-N list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ContainerMetric {
+    pub id: String,
+    pub name: String,
+    pub cpu_percent: f64,
+    pub memory_used_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GpuAggregate {
+    pub containers: usize,
+    pub average_utilization: f64,
+    pub memory_used_mb: u64,
+    pub memory_total_mb: u64,
+}
+
+/// The machine the agent runs on, from GET /api/v1/system/metrics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HostMetrics {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub hostname: Option<String>,
+    pub os: Option<String>,
+    pub kernel_version: Option<String>,
+    pub uptime_secs: u64,
+    pub cpu: HostCpu,
+    pub load_average: LoadAverage,
+    pub memory: HostMemory,
+    /// Filesystem holding Bolt's data directory, unset if the directory is not on a known mount
+    pub data_filesystem: Option<FilesystemUsage>,
+    /// Empty unless the agent was built with the `nvml` feature and found NVIDIA GPUs
+    pub gpus: Vec<HostGpu>,
+    /// Lookups of registry image details answered from the agent's cache
+    #[serde(default)]
+    pub image_cache: ImageCacheStats,
+}
+
+/// Counters of the registry image details cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageCacheStats {
+    /// Lookups answered from the cache after the registry confirmed the digest
+    pub hits: u64,
+    /// Lookups that fetched the image from the registry
+    pub misses: u64,
+    pub entries: usize,
+    pub capacity: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HostCpu {
+    /// Logical cores
+    pub cores: usize,
+    /// Average across all cores, 0 to 100
+    pub usage_percent: f64,
+    pub per_core_percent: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HostMemory {
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    /// What can be allocated without swapping, including reclaimable caches
+    pub available_bytes: u64,
+    pub swap_total_bytes: u64,
+    pub swap_used_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FilesystemUsage {
+    /// Directory that was looked up
+    pub path: String,
+    pub mount_point: String,
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub available_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HostGpu {
+    pub index: u32,
+    pub name: String,
+    pub utilization_percent: f64,
+    pub memory_used_bytes: u64,
+    pub memory_total_bytes: u64,
+    pub temperature_celsius: Option<u32>,
+    pub power_watts: Option<f64>,
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+/// Container network managed by Bolt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Network {
+    pub id: String,
+    pub name: String,
+    pub driver: String,
+    pub subnet: Option<String>,
+    pub gateway: Option<String>,
+    pub internal: bool,
+    pub driver_options: HashMap<String, String>,
+    pub labels: HashMap<String, String>,
+    /// IDs of the containers attached to this network
+    pub containers: Vec<String>,
+    pub created: chrono::DateTime<chrono::Utc>,
+}
+
+impl Network {
+    /// Check whether the network was created with the gaming driver options
+    pub fn is_gaming(&self) -> bool {
+        self.driver_options
+            .get("bolt.network.low_latency")
+            .is_some_and(|value| value == "true")
+    }
+}
+
+/// Network creation request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateNetworkRequest {
+    pub name: String,
+    pub driver: Option<String>,
+    pub subnet: Option<String>,
+    pub gateway: Option<String>,
+    #[serde(default)]
+    pub internal: bool,
+    #[serde(default)]
+    pub driver_options: HashMap<String, String>,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    /// Apply the low-latency driver options used for gaming networks
+    #[serde(default)]
+    pub gaming: bool,
+}
+
+/// Request to attach a container to a network, or detach it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct NetworkConnectRequest {
+    pub container_id: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+/// Driver options tuned for low-latency game traffic
+pub fn gaming_driver_options() -> HashMap<String, String> {
+    HashMap::from([
+        ("bolt.network.quic".to_string(), "true".to_string()),
+        ("bolt.network.low_latency".to_string(), "true".to_string()),
+        ("bolt.network.congestion_control".to_string(), "bbr".to_string()),
+        ("bolt.network.mtu".to_string(), "1500".to_string()),
+    ])
+}
+
+/// Parse a CIDR block such as `172.20.0.0/16` into its address and prefix length
+pub fn parse_cidr(cidr: &str) -> Option<(IpAddr, u8)> {
+    let (address, prefix) = cidr.split_once('/')?;
+    let address: IpAddr = address.parse().ok()?;
+    let prefix: u8 = prefix.parse().ok()?;
+
+    let max_prefix = if address.is_ipv4() { 32 } else { 128 };
+    (prefix <= max_prefix).then_some((address, prefix))
+}
+
+/// Check whether an address falls inside a CIDR block
+pub fn cidr_contains(cidr: &str, address: &IpAddr) -> bool {
+    let Some((network, prefix)) = parse_cidr(cidr) else {
+        return false;
+    };
+
+    match (network, address) {
+        (IpAddr::V4(network), IpAddr::V4(address)) => {
+            let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            u32::from(network) & mask == u32::from(*address) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(address)) => {
+            let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+            u128::from(network) & mask == u128::from(*address) & mask
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,266 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::event::{ContainerEvent, ContainerEventAction};
+
+/// Destination for panel alerts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct NotificationChannel {
+    pub id: String,
+    pub name: String,
+    pub kind: NotificationChannelKind,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum NotificationChannelKind {
+    Email {
+        smtp_host: String,
+        smtp_port: u16,
+        username: Option<String>,
+        password: Option<String>,
+        from: String,
+        to: Vec<String>,
+        /// Upgrade the connection with STARTTLS; only disable for local relays
+        starttls: bool,
+    },
+    /// Generic JSON POST
+    Webhook { url: String },
+    Discord { url: String },
+}
+
+/// Condition that raises an alert
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum AlertCondition {
+    /// The agent cannot reach the Bolt runtime
+    BoltUnreachable,
+    /// A watched container crash-looped; `None` matches any container
+    ContainerCrashLoop { container: Option<String> },
+    /// A filesystem is fuller than this percentage
+    DiskUsageAbove { percent: f64 },
+    /// A TLS certificate expires within this many days
+    CertificateExpiring { days: i64 },
+}
+
+impl std::fmt::Display for AlertCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlertCondition::BoltUnreachable => write!(f, "Bolt unreachable"),
+            AlertCondition::ContainerCrashLoop { container: Some(container) } => write!(f, "{} crash looping", container),
+            AlertCondition::ContainerCrashLoop { container: None } => write!(f, "Any container crash looping"),
+            AlertCondition::DiskUsageAbove { percent } => write!(f, "Disk usage above {}%", percent),
+            AlertCondition::CertificateExpiring { days } => write!(f, "Certificate expires within {} days", days),
+        }
+    }
+}
+
+/// Alert rule sending to one or more channels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct NotificationRule {
+    pub id: String,
+    pub name: String,
+    pub condition: AlertCondition,
+    /// Channel IDs to notify
+    pub channels: Vec<String>,
+    /// Minimum time between notifications from this rule
+    pub cooldown_secs: u64,
+    pub enabled: bool,
+}
+
+/// Observation the rules are evaluated against
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum AlertSignal {
+    Event(ContainerEvent),
+    BoltStatus { reachable: bool, error: Option<String> },
+    DiskUsage { mount_point: String, used_percent: f64 },
+    CertificateExpiry { path: String, expires_at: DateTime<Utc> },
+}
+
+/// Human-readable alert produced by a rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Alert {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub title: String,
+    pub message: String,
+    pub raised_at: DateTime<Utc>,
+}
+
+impl NotificationRule {
+    /// Check a signal against this rule's condition
+    pub fn evaluate(&self, signal: &AlertSignal, now: DateTime<Utc>) -> Option<Alert> {
+        if !self.enabled {
+            return None;
+        }
+
+        let (title, message) = match (&self.condition, signal) {
+            (AlertCondition::BoltUnreachable, AlertSignal::BoltStatus { reachable: false, error }) => (
+                "Bolt runtime unreachable".to_string(),
+                match error {
+                    Some(error) => format!("The agent lost contact with Bolt: {}", error),
+                    None => "The agent lost contact with Bolt.".to_string(),
+                },
+            ),
+            (AlertCondition::ContainerCrashLoop { container }, AlertSignal::Event(event))
+                if event.action == ContainerEventAction::CrashLoop
+                    && container.as_ref().map(|c| c == &event.id || c == &event.name).unwrap_or(true) =>
+            {
+                let restarts = event.attributes.get("restarts").map(String::as_str).unwrap_or("several");
+                (
+                    format!("Container {} is crash looping", event.name),
+                    format!(
+                        "Container {} crashed again after {} automatic restarts. The watchdog has stopped restarting it.",
+                        event.name, restarts
+                    ),
+                )
+            }
+            (AlertCondition::DiskUsageAbove { percent }, AlertSignal::DiskUsage { mount_point, used_percent })
+                if used_percent > percent =>
+            {
+                (
+                    format!("Disk usage high on {}", mount_point),
+                    format!("{} is {:.1}% full (threshold {:.0}%).", mount_point, used_percent, percent),
+                )
+            }
+            (AlertCondition::CertificateExpiring { days }, AlertSignal::CertificateExpiry { path, expires_at })
+                if *expires_at - now <= Duration::days(*days) =>
+            {
+                let remaining = (*expires_at - now).num_days();
+                (
+                    "TLS certificate expiring".to_string(),
+                    if remaining < 0 {
+                        format!("The certificate at {} expired on {}.", path, expires_at.format("%Y-%m-%d"))
+                    } else {
+                        format!(
+                            "The certificate at {} expires in {} days ({}).",
+                            path,
+                            remaining,
+                            expires_at.format("%Y-%m-%d")
+                        )
+                    },
+                )
+            }
+            _ => return None,
+        };
+
+        Some(Alert {
+            rule_id: self.id.clone(),
+            rule_name: self.name.clone(),
+            title,
+            message,
+            raised_at: now,
+        })
+    }
+
+    /// Whether the cooldown since the last notification has passed
+    pub fn cooldown_elapsed(&self, last_sent: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+        last_sent
+            .map(|sent| now - sent >= Duration::seconds(self.cooldown_secs as i64))
+            .unwrap_or(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::ContainerEventAction;
+
+    fn rule(condition: AlertCondition) -> NotificationRule {
+        NotificationRule {
+            id: "rule".to_string(),
+            name: "Rule".to_string(),
+            condition,
+            channels: Vec::new(),
+            cooldown_secs: 600,
+            enabled: true,
+        }
+    }
+
+    fn disk(used_percent: f64) -> AlertSignal {
+        AlertSignal::DiskUsage { mount_point: "/var".to_string(), used_percent }
+    }
+
+    #[test]
+    fn bolt_alerts_only_when_unreachable() {
+        let rule = rule(AlertCondition::BoltUnreachable);
+        let now = Utc::now();
+
+        let alert = rule
+            .evaluate(&AlertSignal::BoltStatus { reachable: false, error: Some("connection refused".to_string()) }, now)
+            .unwrap();
+        assert_eq!(alert.rule_id, "rule");
+        assert_eq!(alert.raised_at, now);
+        assert!(alert.message.contains("connection refused"));
+
+        assert!(rule.evaluate(&AlertSignal::BoltStatus { reachable: true, error: None }, now).is_none());
+        assert!(rule.evaluate(&disk(99.0), now).is_none());
+    }
+
+    #[test]
+    fn crash_loops_match_the_watched_container() {
+        let now = Utc::now();
+        let mut event = ContainerEvent::new("abc123", "web", ContainerEventAction::CrashLoop);
+        event.attributes.insert("restarts".to_string(), "5".to_string());
+        let crash = AlertSignal::Event(event);
+
+        let any = rule(AlertCondition::ContainerCrashLoop { container: None });
+        assert!(any.evaluate(&crash, now).unwrap().message.contains("after 5 automatic restarts"));
+        for watched in ["abc123", "web"] {
+            let rule = rule(AlertCondition::ContainerCrashLoop { container: Some(watched.to_string()) });
+            assert!(rule.evaluate(&crash, now).is_some(), "{}", watched);
+        }
+        let other = rule(AlertCondition::ContainerCrashLoop { container: Some("db".to_string()) });
+        assert!(other.evaluate(&crash, now).is_none());
+
+        let died = AlertSignal::Event(ContainerEvent::new("abc123", "web", ContainerEventAction::Died));
+        assert!(any.evaluate(&died, now).is_none());
+    }
+
+    #[test]
+    fn disk_usage_must_exceed_the_threshold() {
+        let rule = rule(AlertCondition::DiskUsageAbove { percent: 90.0 });
+        let now = Utc::now();
+
+        let alert = rule.evaluate(&disk(92.5), now).unwrap();
+        assert_eq!(alert.title, "Disk usage high on /var");
+        assert!(alert.message.contains("92.5% full (threshold 90%)"));
+        assert!(rule.evaluate(&disk(90.0), now).is_none());
+        assert!(rule.evaluate(&disk(45.0), now).is_none());
+    }
+
+    #[test]
+    fn certificates_alert_within_the_window() {
+        let rule = rule(AlertCondition::CertificateExpiring { days: 14 });
+        let now = Utc::now();
+        let expiring = |expires_at| AlertSignal::CertificateExpiry { path: "/etc/tls/cert.pem".to_string(), expires_at };
+
+        let soon = rule.evaluate(&expiring(now + Duration::days(3)), now).unwrap();
+        assert!(soon.message.contains("expires in 3 days"));
+        let expired = rule.evaluate(&expiring(now - Duration::days(2)), now).unwrap();
+        assert!(expired.message.contains("expired on"));
+        assert!(rule.evaluate(&expiring(now + Duration::days(30)), now).is_none());
+    }
+
+    #[test]
+    fn disabled_rules_never_alert() {
+        let mut rule = rule(AlertCondition::DiskUsageAbove { percent: 90.0 });
+        rule.enabled = false;
+        assert!(rule.evaluate(&disk(99.0), Utc::now()).is_none());
+    }
+
+    #[test]
+    fn cooldown_counts_from_the_last_notification() {
+        let rule = rule(AlertCondition::BoltUnreachable);
+        let now = Utc::now();
+
+        assert!(rule.cooldown_elapsed(None, now));
+        assert!(!rule.cooldown_elapsed(Some(now - Duration::seconds(599)), now));
+        assert!(rule.cooldown_elapsed(Some(now - Duration::seconds(600)), now));
+    }
+}
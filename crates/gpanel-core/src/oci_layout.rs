@@ -0,0 +1,299 @@
+use crate::error::{Context, Error, Result};
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+
+use crate::registry::split_digest;
+
+/// File at the root of an OCI image layout naming its version
+pub const LAYOUT_FILE: &str = "oci-layout";
+pub const LAYOUT_VERSION: &str = r#"{"imageLayoutVersion":"1.0.0"}"#;
+
+/// The layout's index, listing the images it holds
+pub const INDEX_FILE: &str = "index.json";
+
+/// Annotation giving the tag of an image listed in `index.json`
+pub const REF_NAME_ANNOTATION: &str = "org.opencontainers.image.ref.name";
+
+const BLOCK_SIZE: u64 = 512;
+
+/// Where a blob is kept in the layout, `blobs/{algorithm}/{hex}`
+pub fn blob_file(digest: &str) -> Result<String> {
+    let (algorithm, hex) = split_digest(digest)?;
+    Ok(format!("blobs/{}/{}", algorithm, hex))
+}
+
+/// The digest of the blob kept at `path`, if it is under `blobs/`
+pub fn blob_digest(path: &str) -> Option<String> {
+    let (algorithm, hex) = path.strip_prefix("blobs/")?.split_once('/')?;
+    let digest = format!("{}:{}", algorithm, hex);
+    split_digest(&digest).is_ok().then_some(digest)
+}
+
+/// Header of a regular file of `size` bytes in a tar archive
+pub fn tar_header(path: &str, size: u64) -> Result<Bytes> {
+    let mut header = tar::Header::new_ustar();
+    header
+        .set_path(path)
+        .with_context(|| format!("{} cannot be named in a tar archive", path))?;
+    header.set_size(size);
+    header.set_mode(0o644);
+    header.set_mtime(0);
+    header.set_entry_type(tar::EntryType::Regular);
+    header.set_cksum();
+    Ok(Bytes::copy_from_slice(header.as_bytes()))
+}
+
+/// Zeros filling out the last block of a file of `size` bytes
+pub fn tar_padding(size: u64) -> Bytes {
+    Bytes::from(vec![0; padding(size) as usize])
+}
+
+/// The two empty blocks that end a tar archive
+pub fn tar_end() -> Bytes {
+    Bytes::from(vec![0; 2 * BLOCK_SIZE as usize])
+}
+
+/// A whole file of a tar archive, for files small enough to hold in memory
+pub fn tar_file(path: &str, contents: impl Into<Bytes>) -> Result<Bytes> {
+    let contents = contents.into();
+    let size = contents.len() as u64;
+    Ok([tar_header(path, size)?, contents, tar_padding(size)].concat().into())
+}
+
+fn padding(size: u64) -> u64 {
+    (BLOCK_SIZE - size % BLOCK_SIZE) % BLOCK_SIZE
+}
+
+/// A file of a tar archive, whose contents follow from the reader
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TarEntry {
+    /// Without a leading `./`
+    pub path: String,
+    pub size: u64,
+}
+
+/// Reads the files of a tar archive from a stream of chunks as they arrive, so large
+/// files can be passed on without holding them whole. Directories and links are skipped;
+/// GNU and PAX long names are read.
+pub struct TarReader<S> {
+    chunks: S,
+    buffer: Bytes,
+    /// Bytes of the current file not read yet, and the padding after them
+    remaining: u64,
+    padding: u64,
+}
+
+impl<S, E> TarReader<S>
+where
+    S: Stream<Item = std::result::Result<Bytes, E>> + Unpin,
+    E: Into<Error>,
+{
+    pub fn new(chunks: S) -> Self {
+        Self {
+            chunks,
+            buffer: Bytes::new(),
+            remaining: 0,
+            padding: 0,
+        }
+    }
+
+    /// The next file, skipping whatever is left of the current one; `None` at the end
+    pub async fn next_entry(&mut self) -> Result<Option<TarEntry>> {
+        self.skip(self.remaining + self.padding).await?;
+        (self.remaining, self.padding) = (0, 0);
+
+        let mut long_path = None;
+        loop {
+            if !self.fill().await? {
+                return Ok(None);
+            }
+            let block = self.read_exact(BLOCK_SIZE).await?;
+            if block.iter().all(|byte| *byte == 0) {
+                return Ok(None);
+            }
+            let header = tar::Header::from_byte_slice(&block);
+            let size = header.entry_size().context("Invalid tar header")?;
+            match header.entry_type() {
+                tar::EntryType::Regular | tar::EntryType::Continuous => {
+                    let path = match long_path.take() {
+                        Some(path) => path,
+                        None => header.path().context("Invalid tar header")?.to_string_lossy().into_owned(),
+                    };
+                    (self.remaining, self.padding) = (size, padding(size));
+                    let path = path.trim_start_matches("./").to_string();
+                    return Ok(Some(TarEntry { path, size }));
+                }
+                tar::EntryType::GNULongName => {
+                    let name = self.read_exact(size).await?;
+                    self.skip(padding(size)).await?;
+                    long_path = Some(String::from_utf8_lossy(&name).trim_end_matches('\0').to_string());
+                }
+                tar::EntryType::XHeader => {
+                    let records = self.read_exact(size).await?;
+                    self.skip(padding(size)).await?;
+                    long_path = pax_path(&records).or(long_path);
+                }
+                _ => self.skip(size + padding(size)).await?,
+            }
+        }
+    }
+
+    /// The next chunk of the current file; `None` once it has all been read
+    pub async fn read_chunk(&mut self) -> Result<Option<Bytes>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        let chunk = self.take(self.remaining).await?;
+        self.remaining -= chunk.len() as u64;
+        Ok(Some(chunk))
+    }
+
+    /// The rest of the current file
+    pub async fn read_to_end(&mut self) -> Result<Vec<u8>> {
+        let contents = self.read_exact(self.remaining).await?;
+        self.remaining = 0;
+        Ok(contents)
+    }
+
+    /// Whether there is anything left to read
+    async fn fill(&mut self) -> Result<bool> {
+        while self.buffer.is_empty() {
+            match self.chunks.next().await {
+                Some(chunk) => self.buffer = chunk.map_err(Into::into)?,
+                None => return Ok(false),
+            }
+        }
+        Ok(true)
+    }
+
+    /// Between 1 and `max` bytes
+    async fn take(&mut self, max: u64) -> Result<Bytes> {
+        if !self.fill().await? {
+            return Err(Error::Invalid("The archive ends in the middle of a file".to_string()));
+        }
+        let length = self.buffer.len().min(usize::try_from(max).unwrap_or(usize::MAX));
+        Ok(self.buffer.split_to(length))
+    }
+
+    async fn read_exact(&mut self, length: u64) -> Result<Vec<u8>> {
+        let mut contents = Vec::with_capacity(usize::try_from(length).unwrap_or(0).min(1 << 20));
+        while (contents.len() as u64) < length {
+            let chunk = self.take(length - contents.len() as u64).await?;
+            contents.extend_from_slice(&chunk);
+        }
+        Ok(contents)
+    }
+
+    async fn skip(&mut self, mut length: u64) -> Result<()> {
+        while length > 0 {
+            length -= self.take(length).await?.len() as u64;
+        }
+        Ok(())
+    }
+}
+
+/// The `path` of PAX extended header records, each `{length} {key}={value}\n`
+fn pax_path(records: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(records)
+        .lines()
+        .find_map(|record| record.split_once(' ')?.1.strip_prefix("path=").map(str::to_string))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunks(archive: &[u8], size: usize) -> impl Stream<Item = Result<Bytes>> + Unpin {
+        let chunks: Vec<Result<Bytes>> = archive.chunks(size).map(|chunk| Ok(Bytes::copy_from_slice(chunk))).collect();
+        futures::stream::iter(chunks)
+    }
+
+    async fn read_all(archive: &[u8], chunk_size: usize) -> Vec<(String, Vec<u8>)> {
+        let mut reader = TarReader::new(chunks(archive, chunk_size));
+        let mut files = Vec::new();
+        while let Some(entry) = reader.next_entry().await.unwrap() {
+            let mut contents = Vec::new();
+            while let Some(chunk) = reader.read_chunk().await.unwrap() {
+                contents.extend_from_slice(&chunk);
+            }
+            assert_eq!(contents.len() as u64, entry.size);
+            files.push((entry.path, contents));
+        }
+        files
+    }
+
+    #[tokio::test]
+    async fn archives_written_here_are_read_back_in_any_chunk_size() {
+        let large = vec![7u8; 1300];
+        let archive = [
+            tar_file(LAYOUT_FILE, LAYOUT_VERSION).unwrap(),
+            tar_file("blobs/sha256/ab", large.clone()).unwrap(),
+            tar_file("empty", Bytes::new()).unwrap(),
+            tar_end(),
+        ]
+        .concat();
+        assert_eq!(archive.len() % 512, 0);
+
+        // Other tools read them too
+        let mut tar = tar::Archive::new(archive.as_slice());
+        let paths: Vec<String> = tar
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+            .collect();
+        assert_eq!(paths, [LAYOUT_FILE, "blobs/sha256/ab", "empty"]);
+
+        for chunk_size in [1, 100, 512, 700, archive.len()] {
+            let files = read_all(&archive, chunk_size).await;
+            assert_eq!(
+                files,
+                [
+                    (LAYOUT_FILE.to_string(), LAYOUT_VERSION.as_bytes().to_vec()),
+                    ("blobs/sha256/ab".to_string(), large.clone()),
+                    ("empty".to_string(), Vec::new()),
+                ],
+                "chunks of {}",
+                chunk_size
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn long_names_directories_and_unread_files_are_handled() {
+        let long_name = format!("./blobs/sha256/{}", "a".repeat(120));
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut directory = tar::Header::new_gnu();
+        directory.set_entry_type(tar::EntryType::Directory);
+        directory.set_size(0);
+        builder.append_data(&mut directory, "blobs/", std::io::empty()).unwrap();
+        let mut file = tar::Header::new_gnu();
+        file.set_size(3);
+        builder.append_data(&mut file, &long_name, b"abc".as_slice()).unwrap();
+        builder.append_data(&mut file, "index.json", b"{}\n".as_slice()).unwrap();
+        let archive = builder.into_inner().unwrap();
+
+        let mut reader = TarReader::new(chunks(&archive, 64));
+        let entry = reader.next_entry().await.unwrap().unwrap();
+        assert_eq!(entry.path, long_name.trim_start_matches("./"));
+        // Left unread, so skipped
+        let entry = reader.next_entry().await.unwrap().unwrap();
+        assert_eq!(entry.path, INDEX_FILE);
+        assert_eq!(reader.read_to_end().await.unwrap(), b"{}\n");
+        assert_eq!(reader.next_entry().await.unwrap(), None);
+
+        // Cut in the header of `index.json`
+        let truncated = &archive[..archive.len() - 1024 - 600];
+        let mut reader = TarReader::new(chunks(truncated, 64));
+        reader.next_entry().await.unwrap();
+        assert!(reader.next_entry().await.is_err());
+    }
+
+    #[test]
+    fn blob_paths_name_their_digest() {
+        assert_eq!(blob_file("sha256:abc123").unwrap(), "blobs/sha256/abc123");
+        assert_eq!(blob_digest("blobs/sha256/abc123"), Some("sha256:abc123".to_string()));
+        assert_eq!(blob_digest("blobs/sha256/not-hex"), None);
+        assert_eq!(blob_digest("index.json"), None);
+        assert!(blob_file("latest").is_err());
+    }
+}
@@ -0,0 +1,267 @@
+// Outbound HTTP settings shared by the registry and Bolt clients
+use crate::error::{Context, Error, Result};
+use reqwest::{Client, ClientBuilder, Proxy, Url};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use tracing::warn;
+
+/// Sent on every request to registries and Bolt, as some registries throttle reqwest's default
+pub const USER_AGENT: &str = concat!("ghostpanel/", env!("CARGO_PKG_VERSION"));
+
+/// Proxies for outbound requests. Settings left unset are taken from the usual
+/// `http_proxy`, `https_proxy` and `no_proxy` environment variables, lower or upper case.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct ProxyConfig {
+    /// Proxy for `http://` URLs, e.g. `http://proxy.corp:3128`
+    pub http_proxy: Option<String>,
+    /// Proxy for `https://` URLs; `http_proxy` is not used for them
+    pub https_proxy: Option<String>,
+    /// Hosts reached directly: `*` for all, a domain that also matches its subdomains
+    /// (`corp.example`, `.corp.example` or `*.corp.example`), an IP address or a CIDR
+    /// range such as `10.0.0.0/8`
+    pub no_proxy: Vec<String>,
+}
+
+impl ProxyConfig {
+    /// These settings, with the unset ones taken from `vars`
+    pub fn with_env(&self, vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        let vars: Vec<(String, String)> = vars.into_iter().collect();
+        // Lower case first, as curl reads it
+        let var = |name: &str| {
+            [name.to_string(), name.to_uppercase()].iter().find_map(|wanted| {
+                vars.iter()
+                    .find(|(key, value)| key == wanted && !value.trim().is_empty())
+                    .map(|(_, value)| value.trim().to_string())
+            })
+        };
+
+        let mut config = self.clone();
+        config.http_proxy = config.http_proxy.or_else(|| var("http_proxy"));
+        config.https_proxy = config.https_proxy.or_else(|| var("https_proxy"));
+        if config.no_proxy.is_empty() {
+            config.no_proxy = var("no_proxy")
+                .map(|list| list.split(',').map(str::trim).filter(|host| !host.is_empty()).map(str::to_string).collect())
+                .unwrap_or_default();
+        }
+        config
+    }
+
+    /// Check the proxy URLs and `no_proxy` entries parse
+    pub fn validate(&self) -> Result<()> {
+        for (key, proxy) in [("http_proxy", &self.http_proxy), ("https_proxy", &self.https_proxy)] {
+            if let Some(proxy) = proxy {
+                proxy_url(proxy).with_context(|| format!("proxy.{}", key))?;
+            }
+        }
+        for (index, entry) in self.no_proxy.iter().enumerate() {
+            NoProxyRule::parse(entry).with_context(|| format!("proxy.no_proxy[{}]", index))?;
+        }
+        Ok(())
+    }
+
+    /// A client builder sending [`USER_AGENT`] through these proxies, falling back to the
+    /// process environment. Settings that do not parse are left out with a warning.
+    pub fn client_builder(&self) -> ClientBuilder {
+        let builder = Client::builder().user_agent(USER_AGENT).no_proxy();
+        let config = self.with_env(std::env::vars());
+        let proxy = |key: &str, proxy: &Option<String>| {
+            proxy.as_ref().and_then(|proxy| {
+                proxy_url(proxy)
+                    .map_err(|e| warn!("Ignoring {}: {:#}", key, e))
+                    .ok()
+            })
+        };
+        let http = proxy("http_proxy", &config.http_proxy);
+        let https = proxy("https_proxy", &config.https_proxy);
+        if http.is_none() && https.is_none() {
+            return builder;
+        }
+
+        let no_proxy: Vec<NoProxyRule> = config
+            .no_proxy
+            .iter()
+            .filter_map(|entry| {
+                NoProxyRule::parse(entry)
+                    .map_err(|e| warn!("Ignoring no_proxy entry {:?}: {}", entry, e))
+                    .ok()
+            })
+            .collect();
+        builder.proxy(Proxy::custom(move |url| {
+            if no_proxy.iter().any(|rule| rule.matches(url)) {
+                return None;
+            }
+            match url.scheme() {
+                "http" => http.clone(),
+                "https" => https.clone(),
+                _ => None,
+            }
+        }))
+    }
+}
+
+/// One `no_proxy` entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NoProxyRule {
+    All,
+    /// Lower case, without a leading dot; also matches its subdomains
+    Domain(String),
+    Network(IpAddr, u8),
+}
+
+impl NoProxyRule {
+    fn parse(entry: &str) -> Result<Self> {
+        let entry = entry.trim();
+        if entry == "*" {
+            return Ok(Self::All);
+        }
+        if let Some((address, prefix)) = entry.split_once('/') {
+            let invalid = |e: &dyn std::fmt::Display| Error::Invalid(format!("invalid CIDR range {:?}: {}", entry, e));
+            let address: IpAddr = address.parse().map_err(|e| invalid(&e))?;
+            let prefix: u8 = prefix.parse().map_err(|e| invalid(&e))?;
+            if prefix > bits(address) {
+                return Err(invalid(&"the prefix is longer than the address"));
+            }
+            return Ok(Self::Network(address, prefix));
+        }
+        if let Ok(address) = entry.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+            return Ok(Self::Network(address, bits(address)));
+        }
+
+        // A port, as in `registry.local:5000`, is not told apart
+        let domain = entry.rsplit_once(':').map_or(entry, |(host, _)| host);
+        let domain = domain.trim_start_matches("*.").trim_start_matches('.').trim_end_matches('.');
+        if domain.is_empty() {
+            return Err(Error::Invalid(format!("{:?} names no host", entry)));
+        }
+        Ok(Self::Domain(domain.to_ascii_lowercase()))
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let address = host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>().ok();
+        match (self, address) {
+            (Self::All, _) => true,
+            (Self::Domain(domain), None) => {
+                let host = host.trim_end_matches('.').to_ascii_lowercase();
+                host == *domain || host.strip_suffix(domain.as_str()).is_some_and(|sub| sub.ends_with('.'))
+            }
+            (Self::Network(network, prefix), Some(address)) => in_network(*network, *prefix, address),
+            _ => false,
+        }
+    }
+}
+
+fn bits(address: IpAddr) -> u8 {
+    match address {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+fn in_network(network: IpAddr, prefix: u8, address: IpAddr) -> bool {
+    let (network, address, bits) = match (network, address) {
+        (IpAddr::V4(network), IpAddr::V4(address)) => (u32::from(network) as u128, u32::from(address) as u128, 32),
+        (IpAddr::V6(network), IpAddr::V6(address)) => (u128::from(network), u128::from(address), 128),
+        _ => return false,
+    };
+    let host_bits = u32::from(bits - prefix);
+    host_bits >= 128 || (network ^ address) >> host_bits == 0
+}
+
+/// A proxy URL, which must name the proxy's host
+fn proxy_url(proxy: &str) -> Result<Url> {
+    let url = Url::parse(proxy).map_err(|e| Error::Invalid(format!("invalid URL {:?}: {}", proxy, e)))?;
+    if url.host_str().is_none() {
+        return Err(Error::Invalid(format!("{:?} names no host, e.g. http://proxy:3128", proxy)));
+    }
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(entry: &str, url: &str) -> bool {
+        NoProxyRule::parse(entry).unwrap().matches(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn no_proxy_matches_domain_suffixes() {
+        for entry in ["corp.example", ".corp.example", "*.corp.example", "CORP.example:5000"] {
+            assert!(matches(entry, "https://corp.example/v2/"), "{}", entry);
+            assert!(matches(entry, "https://drift.corp.example:5000/v2/"), "{}", entry);
+            assert!(matches(entry, "https://a.b.corp.example./v2/"), "{}", entry);
+            assert!(!matches(entry, "https://notcorp.example/v2/"), "{}", entry);
+            assert!(!matches(entry, "https://corp.example.com/v2/"), "{}", entry);
+        }
+        assert!(matches("*", "http://anything:8080"));
+        assert!(!matches("localhost", "http://127.0.0.1:5000"));
+    }
+
+    #[test]
+    fn no_proxy_matches_addresses_and_cidr_ranges() {
+        assert!(matches("10.0.0.0/8", "http://10.20.30.40:5000/v2/"));
+        assert!(!matches("10.0.0.0/8", "http://11.0.0.1/v2/"));
+        assert!(matches("192.168.1.7", "http://192.168.1.7:5000/"));
+        assert!(!matches("192.168.1.7", "http://192.168.1.8:5000/"));
+        assert!(matches("0.0.0.0/0", "http://8.8.8.8/"));
+        assert!(matches("fd00::/8", "http://[fd12:3456::1]:5000/"));
+        assert!(!matches("fd00::/8", "http://[fe80::1]/"));
+        assert!(matches("[::1]", "http://[::1]:5000/"));
+        // Families do not mix, and names are not resolved
+        assert!(!matches("10.0.0.0/8", "http://[::ffff:10.0.0.1]/"));
+        assert!(!matches("127.0.0.0/8", "http://localhost/"));
+
+        assert!(NoProxyRule::parse("10.0.0.0/33").is_err());
+        assert!(NoProxyRule::parse("10.0.0/8").is_err());
+        assert!(NoProxyRule::parse(".").is_err());
+    }
+
+    #[test]
+    fn unset_settings_come_from_the_environment() {
+        let vars = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+        };
+        let env = vars(&[
+            ("HTTP_PROXY", "http://upper:3128"),
+            ("http_proxy", "http://lower:3128"),
+            ("HTTPS_PROXY", "http://secure:3128"),
+            ("NO_PROXY", "localhost, .corp.example,,10.0.0.0/8"),
+        ]);
+
+        let config = ProxyConfig::default().with_env(env.clone());
+        assert_eq!(config.http_proxy.as_deref(), Some("http://lower:3128"));
+        assert_eq!(config.https_proxy.as_deref(), Some("http://secure:3128"));
+        assert_eq!(config.no_proxy, ["localhost", ".corp.example", "10.0.0.0/8"]);
+
+        let configured = ProxyConfig {
+            http_proxy: None,
+            https_proxy: Some("http://configured:3128".to_string()),
+            no_proxy: vec!["drift.local".to_string()],
+        };
+        let config = configured.with_env(env);
+        assert_eq!(config.http_proxy.as_deref(), Some("http://lower:3128"));
+        assert_eq!(config.https_proxy.as_deref(), Some("http://configured:3128"));
+        assert_eq!(config.no_proxy, ["drift.local"]);
+
+        assert_eq!(ProxyConfig::default().with_env(vars(&[("http_proxy", " ")])), ProxyConfig::default());
+    }
+
+    #[test]
+    fn invalid_settings_are_named() {
+        let config = ProxyConfig {
+            http_proxy: Some("proxy:3128".to_string()),
+            ..Default::default()
+        };
+        assert!(config.validate().unwrap_err().to_string().contains("proxy.http_proxy"));
+        let config = ProxyConfig {
+            no_proxy: vec!["localhost".to_string(), "10.0.0.0/40".to_string()],
+            ..Default::default()
+        };
+        assert!(format!("{:#}", config.validate().unwrap_err()).contains("no_proxy[1]"));
+    }
+}
@@ -1,17 +1,56 @@
-use anyhow::{Context, Result};
+use crate::error::{Context, Error, Result};
+use futures::{StreamExt, TryStreamExt};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use tracing::{debug, info};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, info, warn};
+
+use crate::api::{
+    DriftGcStatus, DriftInfo, DriftQuota, ImageCopyResult, ImageImportResult, ImageInfo, ImageReference, ImageSearchEntry,
+    ImageSearchGroup, LayerInfo, RegistryHealth, RegistryStats, RegistryStatus, RepositoryList,
+};
+use crate::image::{is_valid_repository, is_valid_tag};
+use crate::image_ref::{ImageRef, DOCKER_HUB_HOSTS, DOCKER_HUB_REGISTRY};
+use crate::image_cache::{CachedImage, ImageCache, ImageCacheConfig, ImageCacheKey};
+use crate::metrics::ImageCacheStats;
+use crate::tags::{sort_tags, TagSort};
+use crate::job::PullProgress;
+use crate::proxy::ProxyConfig;
+use crate::oci_layout::{
+    blob_digest, blob_file, tar_end, tar_file, tar_header, tar_padding, TarReader, INDEX_FILE, LAYOUT_FILE, LAYOUT_VERSION,
+    REF_NAME_ANNOTATION,
+};
 
 /// Registry configuration for connecting to Docker/Drift registries
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct RegistryConfig {
     pub name: String,
     pub url: String,
     pub username: Option<String>,
     pub password: Option<String>,
+    /// Accept self-signed or otherwise invalid certificates, and plain `http://` URLs
     pub insecure: bool,
+    /// PEM file of CA certificates to trust besides the system ones, for registries with
+    /// a certificate from an internal CA
+    #[serde(default)]
+    pub ca_cert_path: Option<String>,
+    /// Entries asked for per catalog or tag list page, 100 if unset
+    #[serde(default)]
+    pub page_size: Option<u32>,
+    /// Most entries a full catalog or tag listing collects, 10000 if unset, so a registry
+    /// that keeps sending pages cannot keep the agent listing forever
+    #[serde(default)]
+    pub max_list_entries: Option<usize>,
+    /// Repository search API to use instead of walking the catalog, which Docker Hub does
+    /// not offer. Docker Hub registries use `DOCKER_HUB_SEARCH_URL` when unset.
+    #[serde(default)]
+    pub search_url: Option<String>,
 }
 
 /// Registry client for interacting with Docker Registry v2 API and Drift extensions
@@ -19,13 +58,39 @@ pub struct RegistryConfig {
 pub struct RegistryClient {
     client: Client,
     config: RegistryConfig,
-    auth_token: Option<String>,
+    /// Shared by clones, so a token fetched through one is reused by the others
+    auth: Arc<Mutex<RegistryAuth>>,
+}
+
+/// How the registry asked to be authenticated, from its `WWW-Authenticate` header
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum AuthChallenge {
+    Basic,
+    Bearer { realm: String, service: Option<String> },
+}
+
+#[derive(Debug, Clone)]
+struct CachedToken {
+    token: String,
+    expires_at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct RegistryAuth {
+    /// Unknown until the registry first answers 401
+    challenge: Option<AuthChallenge>,
+    /// Bearer tokens by the scope they were issued for
+    tokens: HashMap<String, CachedToken>,
 }
 
 /// Container image manifest as returned by registry API
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
 pub struct ImageManifest {
     pub schema_version: i32,
+    /// Optional in OCI manifests
+    #[serde(default)]
     pub media_type: String,
     pub config: Descriptor,
     pub layers: Vec<Descriptor>,
@@ -33,6 +98,8 @@ pub struct ImageManifest {
 
 /// Image descriptor containing metadata about layers and configs
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
 pub struct Descriptor {
     pub media_type: String,
     pub size: u64,
@@ -41,362 +108,3856 @@ pub struct Descriptor {
     pub urls: Option<Vec<String>>,
 }
 
-/// Repository list response from catalog API
+/// Docker manifest list or OCI image index: one manifest per platform of a multi-arch image
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RepositoryList {
-    pub repositories: Vec<String>,
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageIndex {
+    pub manifests: Vec<PlatformManifest>,
 }
 
-/// Tag list response for a specific repository
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TagList {
-    pub name: String,
-    pub tags: Vec<String>,
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformManifest {
+    pub media_type: String,
+    pub size: u64,
+    pub digest: String,
+    pub platform: Option<Platform>,
 }
 
-/// Image information with metadata for UI display
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ImageInfo {
-    pub repository: String,
-    pub tag: String,
-    pub digest: String,
-    pub size: u64,
-    pub created: chrono::DateTime<chrono::Utc>,
-    pub author: Option<String>,
-    pub layers: Vec<LayerInfo>,
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Platform {
+    pub os: String,
+    pub architecture: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
 }
 
-/// Layer information for image inspection
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LayerInfo {
-    pub digest: String,
-    pub size: u64,
-    pub media_type: String,
-    pub created_by: Option<String>,
+impl Platform {
+    /// Whether this is the platform named by `name`, given as `os/architecture[/variant]`.
+    /// A name without a variant matches any variant.
+    pub fn matches(&self, name: &str) -> bool {
+        let mut parts = name.split('/');
+        parts.next() == Some(self.os.as_str())
+            && parts.next() == Some(self.architecture.as_str())
+            && parts.next().is_none_or(|variant| self.variant.as_deref() == Some(variant))
+    }
 }
 
-impl RegistryClient {
-    /// Create a new registry client
-    pub fn new(config: RegistryConfig) -> Self {
-        let client = Client::new();
-        Self {
-            client,
-            config,
-            auth_token: None,
+impl std::fmt::Display for Platform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.os, self.architecture)?;
+        if let Some(variant) = &self.variant {
+            write!(f, "/{}", variant)?;
         }
+        Ok(())
     }
+}
+
+/// Repository found through a registry's search API, such as Docker Hub's
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RepositorySearchResult {
+    /// Name to pull by; official Docker Hub images are under `library/`
+    pub repository: String,
+    pub description: Option<String>,
+    pub stars: u64,
+    pub official: bool,
+}
+
+/// Repository whose name matched a catalog search
+#[derive(Debug, Clone)]
+pub struct CatalogMatch {
+    pub repository: String,
+    /// `latest`, then in reverse name order, at most the `max_tags` searched with
+    pub tags: Vec<String>,
+    pub total_tags: usize,
+}
+
+/// Outcome of searching every registry
+#[derive(Debug, Default)]
+pub struct RegistrySearch {
+    /// Matches from registries searched through their catalog, by registry
+    pub catalog: Vec<(String, CatalogMatch)>,
+    /// Matches from registries with a search API, by registry
+    pub repositories: Vec<(String, RepositorySearchResult)>,
+    /// Registries whose search failed or timed out, so results may be missing
+    pub warnings: Vec<String>,
+}
 
-    /// Authenticate with the registry if credentials are provided
-    pub async fn authenticate(&mut self) -> Result<()> {
-        if let (Some(username), Some(password)) = (&self.config.username, &self.config.password) {
-            // For Docker Registry v2, we need to get a token from the auth endpoint
-            let auth_url = format!("{}/v2/", self.config.url);
+/// Score for how well a repository name matches a query: exact, prefix, then substring
+fn match_score(repository: &str, query: &str) -> u32 {
+    let repository = repository.to_lowercase();
+    let query = query.trim().to_lowercase();
+    let name = repository.rsplit('/').next().unwrap_or(&repository);
+
+    if repository == query || name == query {
+        300
+    } else if repository.starts_with(&query) || name.starts_with(&query) {
+        200
+    } else if repository.contains(&query) {
+        100
+    } else {
+        0
+    }
+}
 
-            debug!("Authenticating with registry: {}", self.config.url);
+/// Index of the group for `name`, added if there is none yet
+fn search_group(groups: &mut Vec<ImageSearchGroup>, name: &str, query: &str) -> usize {
+    match groups.iter().position(|group| group.repository == name) {
+        Some(index) => index,
+        None => {
+            groups.push(ImageSearchGroup {
+                official: !name.contains('/'),
+                score: match_score(name, query),
+                repository: name.to_string(),
+                description: None,
+                stars: None,
+                latest: None,
+                tags: Vec::new(),
+                total_tags: 0,
+                more_tags: 0,
+                images: Vec::new(),
+            });
+            groups.len() - 1
+        }
+    }
+}
 
-            // First, try to access the registry to get the auth challenge
-            let response = self.client.get(&auth_url).send().await?;
+/// Group search matches by repository and rank the groups. Repositories from search APIs
+/// add their description and stars to a group of the same name. Official images and
+/// repositories in `local_registries` are boosted; each group keeps `max_tags` tags,
+/// taking those listed first by each registry in turn.
+pub fn rank_search_results(
+    query: &str,
+    catalog: &[(String, CatalogMatch)],
+    repositories: &[(String, RepositorySearchResult)],
+    local_registries: &[String],
+    max_tags: usize,
+) -> Vec<ImageSearchGroup> {
+    let mut groups: Vec<ImageSearchGroup> = Vec::new();
+    // Tags of each group, one list per registry
+    let mut group_tags: Vec<Vec<Vec<ImageReference>>> = Vec::new();
+
+    for (registry, found) in repositories {
+        let name = found.repository.strip_prefix("library/").unwrap_or(&found.repository);
+        let index = search_group(&mut groups, name, query);
+        let group = &mut groups[index];
+        group.official = found.official;
+        group.description = found.description.clone();
+        group.stars = Some(found.stars);
+        group.latest = Some(ImageReference {
+            registry: registry.clone(),
+            repository: found.repository.clone(),
+            tag: "latest".to_string(),
+        });
+    }
 
-            if response.status() == 401 {
-                // Parse WWW-Authenticate header to get auth service info
-                if let Some(auth_header) = response.headers().get("www-authenticate") {
-                    let auth_str = auth_header.to_str().context("Invalid auth header")?;
+    for (registry, found) in catalog {
+        let name = found.repository.strip_prefix("library/").unwrap_or(&found.repository);
+        let index = search_group(&mut groups, name, query);
+        groups[index].total_tags += found.total_tags;
+        group_tags.resize_with(groups.len(), Vec::new);
+        group_tags[index].push(
+            found
+                .tags
+                .iter()
+                .map(|tag| ImageReference {
+                    registry: registry.clone(),
+                    repository: found.repository.clone(),
+                    tag: tag.clone(),
+                })
+                .collect(),
+        );
+    }
+    group_tags.resize_with(groups.len(), Vec::new);
 
-                    // Parse Bearer realm, service, scope from header
-                    if let Some(token) = self.get_auth_token(auth_str, username, password).await? {
-                        self.auth_token = Some(token);
-                        info!("Successfully authenticated with registry: {}", self.config.name);
-                    }
+    for (group, mut per_registry) in groups.iter_mut().zip(group_tags) {
+        if group.official {
+            group.score += 50;
+        }
+        if per_registry.iter().flatten().any(|reference| local_registries.contains(&reference.registry)) {
+            group.score += 25;
+        }
+        // Interleave registries so each has its first tags shown
+        let mut lists: Vec<_> = per_registry.iter_mut().map(|tags| tags.drain(..)).collect();
+        while group.tags.len() < max_tags {
+            let before = group.tags.len();
+            for list in &mut lists {
+                if group.tags.len() < max_tags
+                    && let Some(reference) = list.next()
+                {
+                    group.tags.push(reference);
                 }
             }
+            if group.tags.len() == before {
+                break;
+            }
         }
-        Ok(())
+        group.more_tags = group.total_tags.saturating_sub(group.tags.len());
     }
 
-    /// Get authentication token from auth service
-    async fn get_auth_token(&self, auth_header: &str, username: &str, password: &str) -> Result<Option<String>> {
-        // Parse auth header: Bearer realm="...", service="...", scope="..."
-        let mut realm = None;
-        let mut service = None;
+    groups.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.repository.cmp(&b.repository)));
+    groups
+}
 
-        let header_without_bearer = auth_header.replace("Bearer ", "");
-        for part in header_without_bearer.split(',') {
-            let part = part.trim();
-            if let Some(value) = part.strip_prefix("realm=") {
-                realm = Some(value.trim_matches('"'));
-            } else if let Some(value) = part.strip_prefix("service=") {
-                service = Some(value.trim_matches('"'));
-            }
+/// Fill each group's `images` from the details of its listed tags, merging the tags that
+/// name the same manifest, in any registry, into one entry. Images are newest first.
+pub fn merge_image_details(groups: &mut [ImageSearchGroup], details: Vec<(ImageReference, ImageInfo)>) {
+    for (reference, info) in details {
+        let Some(group) = groups.iter_mut().find(|group| group.tags.contains(&reference)) else {
+            continue;
+        };
+        let digest = info.manifest_digest.unwrap_or(info.digest);
+        match group.images.iter_mut().find(|image| image.digest == digest) {
+            Some(image) => image.references.push(reference),
+            None => group.images.push(ImageSearchEntry {
+                digest,
+                size: info.size,
+                created: info.created,
+                references: vec![reference],
+            }),
         }
+    }
+    for group in groups {
+        group.images.sort_by_key(|image| std::cmp::Reverse(image.created));
+    }
+}
 
-        if let (Some(realm), Some(service)) = (realm, service) {
-            let auth_url = format!("{}?service={}&scope=registry:catalog:*", realm, service);
+/// `latest` and then a repository's highest versions, `max_tags` in all, as `sort_tags`
+/// orders them
+fn newest_tags(tags: &[String], max_tags: usize) -> Vec<String> {
+    let mut tags = tags.to_vec();
+    sort_tags(&mut tags, TagSort::Semver);
+    tags.truncate(max_tags);
+    tags
+}
 
-            let response = self.client
-                .get(&auth_url)
-                .basic_auth(username, Some(password))
-                .send()
-                .await?;
+/// A manifest or index exactly as the registry sent it, so its digest is preserved when it
+/// is stored or pushed again
+#[derive(Debug, Clone)]
+struct RawManifest {
+    content_type: String,
+    digest: String,
+    body: Vec<u8>,
+}
 
-            if response.status().is_success() {
-                #[derive(Deserialize)]
-                struct TokenResponse {
-                    token: String,
-                }
+impl RawManifest {
+    /// Wrap a manifest read from somewhere other than a registry response, taking its type
+    /// from its `mediaType`
+    fn from_body(body: Vec<u8>) -> Result<Self> {
+        let parsed: serde_json::Value = serde_json::from_slice(&body).context("Invalid image manifest")?;
+        let content_type = match parsed.get("mediaType").and_then(|media_type| media_type.as_str()) {
+            Some(media_type) => media_type.to_string(),
+            None if parsed.get("manifests").is_some() => "application/vnd.oci.image.index.v1+json".to_string(),
+            None => "application/vnd.oci.image.manifest.v1+json".to_string(),
+        };
+        Ok(Self {
+            content_type,
+            digest: format!("sha256:{:x}", Sha256::digest(&body)),
+            body,
+        })
+    }
 
-                let token_resp: TokenResponse = response.json().await?;
-                return Ok(Some(token_resp.token));
-            }
-        }
+    fn json(&self) -> Result<serde_json::Value> {
+        serde_json::from_slice(&self.body).with_context(|| format!("Invalid manifest {}", self.digest))
+    }
 
-        Ok(None)
+    /// Digests of the platform manifests an index lists; empty for a single manifest
+    fn children(&self) -> Result<Vec<String>> {
+        Ok(self
+            .json()?
+            .get("manifests")
+            .and_then(|manifests| manifests.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| Some(entry.get("digest")?.as_str()?.to_string()))
+            .collect())
     }
 
-    /// List all repositories in the registry
-    pub async fn list_repositories(&self) -> Result<Vec<String>> {
-        let url = format!("{}/v2/_catalog", self.config.url);
+    /// Digests and sizes of the config and layers of a single manifest
+    fn blobs(&self) -> Result<Vec<(String, u64)>> {
+        self.descriptors(|parsed| parsed.get("config").into_iter().chain(layers(parsed)).collect())
+    }
+
+    /// Digests and sizes of the layers of a single manifest
+    fn layers(&self) -> Result<Vec<(String, u64)>> {
+        self.descriptors(|parsed| layers(parsed).collect())
+    }
+
+    fn descriptors(&self, pick: impl Fn(&serde_json::Value) -> Vec<&serde_json::Value>) -> Result<Vec<(String, u64)>> {
+        let parsed = self.json()?;
+        Ok(pick(&parsed)
+            .into_iter()
+            .filter_map(|blob| {
+                let digest = blob.get("digest")?.as_str()?.to_string();
+                Some((digest, blob.get("size")?.as_u64()?))
+            })
+            .collect())
+    }
+}
+
+fn layers(manifest: &serde_json::Value) -> impl Iterator<Item = &serde_json::Value> {
+    manifest.get("layers").and_then(|l| l.as_array()).into_iter().flatten()
+}
 
-        let mut request = self.client.get(&url);
-        if let Some(token) = &self.auth_token {
-            request = request.bearer_auth(token);
+/// Every blob the manifests name, once each, in order; an index names none
+fn image_blobs<'a>(manifests: impl IntoIterator<Item = &'a RawManifest>) -> Result<Vec<(String, u64)>> {
+    let mut blobs: Vec<(String, u64)> = Vec::new();
+    for manifest in manifests {
+        for blob in manifest.blobs()? {
+            if !blobs.iter().any(|(digest, _)| *digest == blob.0) {
+                blobs.push(blob);
+            }
         }
+    }
+    Ok(blobs)
+}
+
+/// A manifest picked from a multi-arch index, or a single-platform manifest
+struct ResolvedManifest {
+    manifest: ImageManifest,
+    /// The picked manifest as sent
+    raw: RawManifest,
+    /// Digest of what the reference names, the index for a multi-arch image
+    digest: String,
+    /// Platform of the index entry picked
+    platform: Option<Platform>,
+    /// Every platform the index lists
+    platforms: Vec<Platform>,
+}
 
-        let response = request.send().await?;
+/// Why a registry did not delete an image
+#[derive(Debug)]
+pub enum DeleteImageError {
+    /// The repository or tag does not exist
+    NotFound(String),
+    /// The registry answered 405: deleting is turned off in its configuration
+    Disabled(String),
+    /// The registry deletes images through another API only, as Docker Hub does
+    Unsupported(String),
+    /// The registry failed the request or could not be reached
+    Failed(Error),
+}
 
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!("Failed to list repositories: {}", response.status()));
+impl std::fmt::Display for DeleteImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeleteImageError::NotFound(message)
+            | DeleteImageError::Disabled(message)
+            | DeleteImageError::Unsupported(message) => f.write_str(message),
+            DeleteImageError::Failed(error) => std::fmt::Display::fmt(error, f),
+        }
+    }
+}
+
+impl std::error::Error for DeleteImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DeleteImageError::Failed(error) => Some(error),
+            _ => None,
         }
+    }
+}
 
-        let repo_list: RepositoryList = response.json().await?;
-        Ok(repo_list.repositories)
+impl From<Error> for DeleteImageError {
+    fn from(error: Error) -> Self {
+        DeleteImageError::Failed(error)
     }
+}
 
-    /// List tags for a specific repository
-    pub async fn list_tags(&self, repository: &str) -> Result<Vec<String>> {
-        let url = format!("{}/v2/{}/tags/list", self.config.url, repository);
+/// Why an image could not be tagged
+#[derive(Debug)]
+pub enum TagImageError {
+    /// The source tag or digest does not exist
+    NotFound(String),
+    /// The new tag is not a valid tag name
+    InvalidTag(String),
+    /// The registry failed the request or could not be reached
+    Failed(Error),
+}
 
-        let mut request = self.client.get(&url);
-        if let Some(token) = &self.auth_token {
-            request = request.bearer_auth(token);
+impl std::fmt::Display for TagImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TagImageError::NotFound(message) | TagImageError::InvalidTag(message) => f.write_str(message),
+            TagImageError::Failed(error) => std::fmt::Display::fmt(error, f),
         }
+    }
+}
 
-        let response = request.send().await?;
-
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!("Failed to list tags for {}: {}", repository, response.status()));
+impl std::error::Error for TagImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TagImageError::Failed(error) => Some(error),
+            _ => None,
         }
+    }
+}
 
-        let tag_list: TagList = response.json().await?;
-        Ok(tag_list.tags)
+impl From<Error> for TagImageError {
+    fn from(error: Error) -> Self {
+        TagImageError::Failed(error)
     }
+}
 
-    /// Get manifest for a specific image
-    pub async fn get_manifest(&self, repository: &str, tag: &str) -> Result<ImageManifest> {
-        let url = format!("{}/v2/{}/manifests/{}", self.config.url, repository, tag);
+/// Why an image archive could not be imported
+#[derive(Debug)]
+pub enum ImportImageError {
+    /// The archive is not a readable OCI image layout, or lacks part of the image
+    InvalidArchive(String),
+    /// The registry failed the request or could not be reached
+    Failed(Error),
+}
 
-        let mut request = self.client.get(&url)
-            .header("Accept", "application/vnd.docker.distribution.manifest.v2+json");
+impl std::fmt::Display for ImportImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportImageError::InvalidArchive(message) => f.write_str(message),
+            ImportImageError::Failed(error) => std::fmt::Display::fmt(error, f),
+        }
+    }
+}
 
-        if let Some(token) = &self.auth_token {
-            request = request.bearer_auth(token);
+impl std::error::Error for ImportImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ImportImageError::Failed(error) => Some(error),
+            _ => None,
         }
+    }
+}
 
-        let response = request.send().await?;
+impl From<Error> for ImportImageError {
+    fn from(error: Error) -> Self {
+        ImportImageError::Failed(error)
+    }
+}
 
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!("Failed to get manifest for {}:{}: {}", repository, tag, response.status()));
+/// Where `push_from` reads an image's manifests and blobs
+enum ImageSource<'a> {
+    Registry {
+        client: &'a RegistryClient,
+        repository: &'a str,
+    },
+    /// A blob directory filled by `pull_image`
+    Local(&'a Path),
+}
+
+impl ImageSource<'_> {
+    async fn manifest(&self, reference: &str) -> Result<RawManifest> {
+        match self {
+            ImageSource::Registry { client, repository } => client.fetch_manifest_raw(repository, reference).await,
+            ImageSource::Local(blob_dir) => {
+                let path = blob_path(blob_dir, reference)?;
+                let body = tokio::fs::read(&path)
+                    .await
+                    .with_context(|| format!("Manifest {} is not stored locally", reference))?;
+                RawManifest::from_body(body)
+            }
         }
+    }
 
-        let manifest: ImageManifest = response.json().await?;
-        Ok(manifest)
+    async fn blob_body(&self, digest: &str) -> Result<reqwest::Body> {
+        match self {
+            ImageSource::Registry { client, repository } => client.blob_body(repository, digest).await,
+            ImageSource::Local(blob_dir) => {
+                let path = blob_path(blob_dir, digest)?;
+                let file = tokio::fs::File::open(&path)
+                    .await
+                    .with_context(|| format!("Blob {} is not stored locally", digest))?;
+                Ok(reqwest::Body::from(file))
+            }
+        }
     }
+}
 
-    /// Get detailed image information including layers and metadata
-    pub async fn get_image_info(&self, repository: &str, tag: &str) -> Result<ImageInfo> {
-        let manifest = self.get_manifest(repository, tag).await?;
+/// Outcome of a cross-repository mount attempt
+#[derive(Debug, Clone)]
+pub enum BlobMount {
+    Mounted,
+    /// The registry did not mount the blob; upload it to this session URL instead
+    Upload(String),
+}
 
-        // Calculate total size from layers
-        let total_size: u64 = manifest.layers.iter().map(|l| l.size).sum();
+/// Docker Hub's repository search, used for Docker Hub registries since they have no catalog
+pub const DOCKER_HUB_SEARCH_URL: &str = "https://hub.docker.com/v2/search/repositories/";
 
-        // Get image config to extract creation date and other metadata
-        let config_url = format!("{}/v2/{}/blobs/{}", self.config.url, repository, manifest.config.digest);
+/// Most repositories taken from a search API per search
+pub const MAX_SEARCH_RESULTS: usize = 100;
 
-        let mut request = self.client.get(&config_url);
-        if let Some(token) = &self.auth_token {
-            request = request.bearer_auth(token);
-        }
+/// Times a rate-limited search is retried, and the longest `Retry-After` waited for
+const SEARCH_RETRIES: u32 = 3;
+const MAX_RETRY_AFTER_SECS: u64 = 30;
 
-        let config_response = request.send().await?;
-        let config_data: serde_json::Value = config_response.json().await?;
+/// Registries searched at once
+pub const SEARCH_CONCURRENCY: usize = 4;
 
-        // Extract created timestamp and author from config
-        let created = config_data
-            .get("created")
-            .and_then(|v| v.as_str())
-            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
-            .map(|dt| dt.with_timezone(&chrono::Utc))
-            .unwrap_or_else(chrono::Utc::now);
+/// Longest one registry may take to answer a search before it is left out of the results
+pub const SEARCH_TIMEOUT: Duration = Duration::from_secs(10);
 
-        let author = config_data
-            .get("author")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
+/// Longest a health check waits for the registry, its token service included
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
 
-        // Convert layers to LayerInfo
-        let layers: Vec<LayerInfo> = manifest.layers.into_iter().map(|layer| {
-            LayerInfo {
-                digest: layer.digest,
-                size: layer.size,
-                media_type: layer.media_type,
-                created_by: None, // Would need to parse history from config for this
-            }
-        }).collect();
+/// Repositories whose tags one registry's catalog search lists at once
+const TAG_LIST_CONCURRENCY: usize = 8;
 
-        Ok(ImageInfo {
-            repository: repository.to_string(),
-            tag: tag.to_string(),
-            digest: manifest.config.digest,
-            size: total_size,
-            created,
-            author,
-            layers,
-        })
-    }
+/// Longest the tags and manifests of one repository may take to read for registry stats
+/// before it is skipped
+pub const STATS_REPOSITORY_TIMEOUT: Duration = Duration::from_secs(60);
 
-    /// Pull an image (download layers) - simplified for now
-    pub async fn pull_image(&self, repository: &str, tag: &str) -> Result<()> {
-        info!("Pulling image {}:{}", repository, tag);
+/// Blobs of an imported archive up to this size are held until the whole archive has been
+/// read, as its manifests and configs are among them; larger ones are layers, uploaded as
+/// they are read
+const IMPORT_BUFFER_LIMIT: u64 = 4 * 1024 * 1024;
 
-        let manifest = self.get_manifest(repository, tag).await?;
+/// Catalog and tag list page size when the registry config sets none
+pub const DEFAULT_PAGE_SIZE: u32 = 100;
 
-        // In a real implementation, we would download and store the layers
-        // For now, we'll just verify they exist
-        for layer in &manifest.layers {
-            let blob_url = format!("{}/v2/{}/blobs/{}", self.config.url, repository, layer.digest);
+/// Cap on a full catalog or tag listing when the registry config sets none
+pub const DEFAULT_MAX_LIST_ENTRIES: usize = 10_000;
 
-            let mut request = self.client.head(&blob_url);
-            if let Some(token) = &self.auth_token {
-                request = request.bearer_auth(token);
-            }
+/// Token lifetime assumed when the token service gives none, as the distribution spec says
+const DEFAULT_TOKEN_LIFETIME_SECS: u64 = 60;
 
-            let response = request.send().await?;
-            if !response.status().is_success() {
-                return Err(anyhow::anyhow!("Layer {} not found", layer.digest));
+/// Tokens are dropped this long before they expire, so none runs out in flight
+const TOKEN_EXPIRY_MARGIN_SECS: u64 = 5;
+
+const CATALOG_SCOPE: &str = "registry:catalog:*";
+
+/// Scope Drift's own endpoints are authorized for
+const DRIFT_SCOPE: &str = "registry:drift:*";
+
+fn pull_scope(repository: &str) -> String {
+    format!("repository:{}:pull", repository)
+}
+
+fn push_scope(repository: &str) -> String {
+    format!("repository:{}:pull,push", repository)
+}
+
+fn delete_scope(repository: &str) -> String {
+    format!("repository:{}:delete", repository)
+}
+
+/// Platform picked from a multi-arch image when none is asked for
+pub const DEFAULT_PLATFORM: &str = "linux/amd64";
+
+/// Manifest media types accepted when inspecting or pulling images, including multi-arch indexes
+const MANIFEST_OR_INDEX_ACCEPT: &str = "application/vnd.docker.distribution.manifest.v2+json, application/vnd.docker.distribution.manifest.list.v2+json, application/vnd.oci.image.manifest.v1+json, application/vnd.oci.image.index.v1+json";
+
+impl RegistryClient {
+    /// Create a new registry client, using the proxies from the environment
+    pub fn new(config: RegistryConfig) -> Self {
+        Self::with_proxy(config, &ProxyConfig::default())
+    }
+
+    /// Create a client reaching the registry through `proxy`. A CA file that cannot be read
+    /// is left out with a warning, so the registry's health check reports the certificate it
+    /// then rejects.
+    pub fn with_proxy(config: RegistryConfig, proxy: &ProxyConfig) -> Self {
+        let mut builder = proxy
+            .client_builder()
+            .danger_accept_invalid_certs(config.insecure)
+            .https_only(!config.insecure);
+        if let Some(path) = &config.ca_cert_path {
+            match read_certificates(path) {
+                Ok(certificates) => {
+                    for certificate in certificates {
+                        builder = builder.add_root_certificate(certificate);
+                    }
+                }
+                Err(e) => warn!("Ignoring the CA file of registry {}: {:#}", config.name, e),
             }
         }
+        let client = builder.build().unwrap_or_else(|e| {
+            warn!("Failed to set up the HTTP client of registry {}: {}", config.name, e);
+            Client::new()
+        });
+        Self {
+            client,
+            config,
+            auth: Arc::default(),
+        }
+    }
+
+    /// The registry's configuration, including credentials
+    pub fn config(&self) -> &RegistryConfig {
+        &self.config
+    }
 
-        info!("Successfully verified image {}:{}", repository, tag);
+    /// Find out how the registry authenticates, and check the credentials when it uses tokens
+    pub async fn authenticate(&self) -> Result<()> {
+        let url = format!("{}/v2/", self.config.url);
+        debug!("Authenticating with registry: {}", self.config.url);
+
+        let response = self.client.get(&url).send().await?;
+        if response.status() != reqwest::StatusCode::UNAUTHORIZED {
+            return Ok(());
+        }
+        let Some(challenge) = auth_challenge(&response) else {
+            return Ok(());
+        };
+        self.auth_state().challenge = Some(challenge.clone());
+
+        if let AuthChallenge::Bearer { realm, service } = &challenge
+            && self.config.username.is_some()
+        {
+            self.token_for(realm, service.as_deref(), CATALOG_SCOPE).await?;
+            info!("Successfully authenticated with registry: {}", self.config.name);
+        }
         Ok(())
     }
 
-    /// Push an image (upload layers and manifest) - placeholder
-    pub async fn push_image(&self, _repository: &str, _tag: &str) -> Result<()> {
-        // This would require implementing the full Docker Registry v2 push protocol
-        // Including blob uploads, manifest uploads, etc.
-        Err(anyhow::anyhow!("Push functionality not yet implemented"))
+    /// Check that the registry answers on `/v2/` and accepts the configured credentials.
+    /// Registries that hand out anonymous tokens are fine without credentials.
+    pub async fn health(&self) -> RegistryHealth {
+        let (status, message) = match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, self.check_health()).await {
+            Ok(Ok(())) => (RegistryStatus::Ok, None),
+            Ok(Err((status, message))) => (status, Some(message)),
+            Err(_) => (
+                RegistryStatus::Unreachable,
+                Some(format!("No answer within {} s", HEALTH_CHECK_TIMEOUT.as_secs())),
+            ),
+        };
+        RegistryHealth {
+            status,
+            message,
+            checked_at: chrono::Utc::now(),
+        }
     }
 
-    /// Delete an image from the registry
-    pub async fn delete_image(&self, repository: &str, tag: &str) -> Result<()> {
-        // First get the manifest to get the digest for deletion
-        let url = format!("{}/v2/{}/manifests/{}", self.config.url, repository, tag);
+    async fn check_health(&self) -> std::result::Result<(), (RegistryStatus, String)> {
+        if !self.config.insecure && self.config.url.starts_with("http://") {
+            return Err((
+                RegistryStatus::TlsError,
+                "Plain HTTP is only used for registries marked insecure".to_string(),
+            ));
+        }
+        let url = format!("{}/v2/", self.config.url);
+        let response = self.client.get(&url).send().await.map_err(connection_failure)?;
+        if response.status().is_success() {
+            return Ok(());
+        }
+        if response.status() != reqwest::StatusCode::UNAUTHORIZED {
+            return Err((RegistryStatus::Unreachable, format!("Registry answered {}", response.status())));
+        }
+
+        match auth_challenge(&response) {
+            Some(AuthChallenge::Bearer { realm, service }) => {
+                self.auth_state().challenge = Some(AuthChallenge::Bearer {
+                    realm: realm.clone(),
+                    service: service.clone(),
+                });
+                if self.config.username.is_none() {
+                    return Ok(());
+                }
+                self.token_for(&realm, service.as_deref(), CATALOG_SCOPE)
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| (RegistryStatus::AuthRequired, e.to_string()))
+            }
+            Some(AuthChallenge::Basic) if self.config.username.is_some() => {
+                self.auth_state().challenge = Some(AuthChallenge::Basic);
+                let response = self
+                    .basic_auth(self.client.get(&url))
+                    .send()
+                    .await
+                    .map_err(connection_failure)?;
+                if response.status().is_success() {
+                    Ok(())
+                } else {
+                    Err((
+                        RegistryStatus::AuthRequired,
+                        format!("Registry refused the credentials: {}", response.status()),
+                    ))
+                }
+            }
+            _ => Err((RegistryStatus::AuthRequired, "Registry requires credentials".to_string())),
+        }
+    }
 
-        let mut request = self.client.get(&url)
-            .header("Accept", "application/vnd.docker.distribution.manifest.v2+json");
+    fn auth_state(&self) -> std::sync::MutexGuard<'_, RegistryAuth> {
+        self.auth.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
 
-        if let Some(token) = &self.auth_token {
-            request = request.bearer_auth(token);
+    /// A token for `scope`, from the cache while it is valid, otherwise from the token
+    /// service. Several scopes are separated by spaces.
+    async fn token_for(&self, realm: &str, service: Option<&str>, scope: &str) -> Result<String> {
+        if let Some(cached) = self.auth_state().tokens.get(scope)
+            && cached.expires_at > Instant::now()
+        {
+            return Ok(cached.token.clone());
         }
 
-        let response = request.send().await?;
+        let mut url = reqwest::Url::parse(realm).map_err(|e| Error::Config(format!("Invalid token realm {}: {}", realm, e)))?;
+        {
+            let mut query = url.query_pairs_mut();
+            if let Some(service) = service {
+                query.append_pair("service", service);
+            }
+            for scope in scope.split(' ') {
+                query.append_pair("scope", scope);
+            }
+        }
 
-        if let Some(digest) = response.headers().get("docker-content-digest") {
-            let digest_str = digest.to_str().context("Invalid digest header")?;
+        debug!("Requesting token for {} from {}", scope, realm);
+        // Without credentials the token is anonymous, which is enough for public images
+        let response = self.basic_auth(self.client.get(url)).send().await?;
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!(
+                "Registry {} refused a token for {}: {}",
+                self.config.name,
+                scope,
+                response.status()
+            )));
+        }
 
-            // Delete by digest
-            let delete_url = format!("{}/v2/{}/manifests/{}", self.config.url, repository, digest_str);
+        #[derive(Deserialize)]
+        struct TokenResponse {
+            token: Option<String>,
+            /// OAuth2 name of the token, sent by some token services instead of `token`
+            access_token: Option<String>,
+            expires_in: Option<u64>,
+        }
 
-            let mut delete_request = self.client.delete(&delete_url);
-            if let Some(token) = &self.auth_token {
-                delete_request = delete_request.bearer_auth(token);
-            }
+        let token_resp: TokenResponse = response.json().await.context("Invalid token response")?;
+        let token = token_resp
+            .token
+            .or(token_resp.access_token)
+            .ok_or_else(|| Error::Registry(format!("Token service of {} returned no token", self.config.name)))?;
+        let lifetime = token_resp
+            .expires_in
+            .unwrap_or(DEFAULT_TOKEN_LIFETIME_SECS)
+            .saturating_sub(TOKEN_EXPIRY_MARGIN_SECS);
+
+        self.auth_state().tokens.insert(
+            scope.to_string(),
+            CachedToken {
+                token: token.clone(),
+                expires_at: Instant::now() + Duration::from_secs(lifetime),
+            },
+        );
+        Ok(token)
+    }
 
-            let delete_response = delete_request.send().await?;
+    fn basic_auth(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match (&self.config.username, &self.config.password) {
+            (Some(username), password) => request.basic_auth(username, password.as_deref()),
+            _ => request,
+        }
+    }
 
-            if delete_response.status().is_success() {
-                info!("Successfully deleted image {}:{}", repository, tag);
-                Ok(())
-            } else {
-                Err(anyhow::anyhow!("Failed to delete image: {}", delete_response.status()))
+    /// Add what the registry asked for to `request`: a bearer token for `scope`, or basic auth
+    async fn with_credentials(&self, request: reqwest::RequestBuilder, scope: &str) -> Result<reqwest::RequestBuilder> {
+        let challenge = self.auth_state().challenge.clone();
+        Ok(match challenge {
+            Some(AuthChallenge::Bearer { realm, service }) => {
+                request.bearer_auth(self.token_for(&realm, service.as_deref(), scope).await?)
             }
-        } else {
-            Err(anyhow::anyhow!("Could not get digest for image deletion"))
+            Some(AuthChallenge::Basic) => self.basic_auth(request),
+            None => request,
+        })
+    }
+
+    /// Send `request` with credentials for `scope`. When the registry answers 401, the
+    /// token for `scope` is dropped, a new one fetched per the response's challenge and
+    /// the request sent once more. Requests with a streamed body cannot be repeated and
+    /// are only sent once.
+    async fn send(&self, scope: &str, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let retry = request.try_clone();
+        let response = self.with_credentials(request, scope).await?.send().await?;
+        if response.status() != reqwest::StatusCode::UNAUTHORIZED {
+            return Ok(response);
         }
+        let (Some(retry), Some(challenge)) = (retry, auth_challenge(&response)) else {
+            return Ok(response);
+        };
+
+        debug!("Registry {} answered 401, re-authenticating for {}", self.config.name, scope);
+        {
+            let mut auth = self.auth_state();
+            auth.tokens.remove(scope);
+            auth.challenge = Some(challenge);
+        }
+        Ok(self.with_credentials(retry, scope).await?.send().await?)
     }
-}
 
-/// Registry manager for handling multiple registries
-#[derive(Debug)]
-pub struct RegistryManager {
-    registries: HashMap<String, RegistryClient>,
-}
+    /// List all repositories in the registry, following pages up to `max_list_entries`
+    pub async fn list_repositories(&self) -> Result<Vec<String>> {
+        self.list_all("/v2/_catalog", CATALOG_SCOPE, "repositories", "repositories").await
+    }
 
-impl RegistryManager {
-    pub fn new() -> Self {
-        Self {
-            registries: HashMap::new(),
-        }
+    /// One page of at most `n` repositories, starting after `last`
+    pub async fn list_repositories_page(&self, n: Option<u32>, last: Option<&str>) -> Result<RepositoryList> {
+        let n = n.unwrap_or_else(|| self.page_size());
+        let (repositories, next) = self
+            .list_page("/v2/_catalog", CATALOG_SCOPE, "repositories", "repositories", n, last)
+            .await?;
+        Ok(RepositoryList { repositories, next })
     }
 
-    /// Add a new registry configuration
-    pub async fn add_registry(&mut self, config: RegistryConfig) -> Result<()> {
-        let mut client = RegistryClient::new(config.clone());
-        client.authenticate().await?;
-        self.registries.insert(config.name.clone(), client);
-        Ok(())
+    /// List tags for a specific repository, following pages up to `max_list_entries`
+    pub async fn list_tags(&self, repository: &str) -> Result<Vec<String>> {
+        let path = format!("/v2/{}/tags/list", repository);
+        self.list_all(&path, &pull_scope(repository), "tags", &format!("tags for {}", repository))
+            .await
     }
 
-    /// Get a registry client by name
-    pub fn get_registry(&self, name: &str) -> Option<&RegistryClient> {
-        self.registries.get(name)
+    /// Search API this registry is searched through instead of its catalog, if any
+    pub fn search_url(&self) -> Option<&str> {
+        self.config
+            .search_url
+            .as_deref()
+            .or_else(|| self.is_docker_hub().then_some(DOCKER_HUB_SEARCH_URL))
     }
 
-    /// List all configured registries
-    pub fn list_registries(&self) -> Vec<&str> {
-        self.registries.keys().map(|s| s.as_str()).collect()
+    fn is_docker_hub(&self) -> bool {
+        reqwest::Url::parse(&self.config.url)
+            .ok()
+            .and_then(|url| url.host_str().map(|host| DOCKER_HUB_HOSTS.contains(&host)))
+            .unwrap_or(false)
     }
 
-    /// Remove a registry
-    pub fn remove_registry(&mut self, name: &str) -> bool {
-        self.registries.remove(name).is_some()
+    /// `repository` at `reference`, a tag or digest, on this registry. Docker Hub's
+    /// official images are named without `library/` in pulls, so `nginx` becomes
+    /// `library/nginx` there.
+    pub fn image_ref(&self, repository: &str, reference: &str) -> Result<ImageRef> {
+        let host = if self.is_docker_hub() {
+            DOCKER_HUB_REGISTRY.to_string()
+        } else {
+            let url = reqwest::Url::parse(&self.config.url).map_err(|e| Error::Config(format!("Invalid registry URL {}: {}", self.config.url, e)))?;
+            match (url.host_str(), url.port()) {
+                (Some(host), Some(port)) => format!("{}:{}", host, port),
+                (Some(host), None) => host.to_string(),
+                (None, _) => return Err(Error::Config(format!("Registry URL {} has no host", self.config.url))),
+            }
+        };
+        let separator = if reference.contains(':') { '@' } else { ':' };
+        ImageRef::parse(&format!("{}/{}{}{}", host, repository, separator, reference)).map_err(|e| Error::Invalid(e.to_string()))
     }
 
-    /// Search for images across all registries
-    pub async fn search_images(&self, query: &str) -> Result<Vec<(String, ImageInfo)>> {
-        let mut results = Vec::new();
+    /// Repositories whose name contains `query`, found by walking the catalog, with their
+    /// `latest` and last tags by name, `max_tags` in all. Repositories whose tags cannot be
+    /// listed are left out.
+    pub async fn search_catalog(&self, query: &str, max_tags: usize) -> Result<Vec<CatalogMatch>> {
+        // Collected first, as a closure in the stream would keep it from being `Send`
+        let lookups: Vec<_> = self
+            .list_repositories()
+            .await?
+            .into_iter()
+            .filter(|repo| repo.contains(query))
+            .map(|repository| async move {
+                let tags = self.list_tags(&repository).await.ok()?;
+                Some(CatalogMatch {
+                    tags: newest_tags(&tags, max_tags),
+                    total_tags: tags.len(),
+                    repository,
+                })
+            })
+            .collect();
+        let matches = futures::stream::iter(lookups)
+            .buffered(TAG_LIST_CONCURRENCY)
+            .filter_map(std::future::ready)
+            .collect()
+            .await;
+        Ok(matches)
+    }
 
-        for (registry_name, client) in &self.registries {
-            if let Ok(repositories) = client.list_repositories().await {
-                for repo in repositories {
-                    if repo.contains(query) {
-                        if let Ok(tags) = client.list_tags(&repo).await {
-                            for tag in tags {
-                                if let Ok(image_info) = client.get_image_info(&repo, &tag).await {
-                                    results.push((registry_name.clone(), image_info));
-                                }
-                            }
-                        }
+    /// Count the repositories, tags and distinct layers of the registry by walking its
+    /// catalog, calling `progress` with how many repositories are done of how many.
+    /// Repositories are read `SEARCH_CONCURRENCY` at a time and their manifests
+    /// `TAG_LIST_CONCURRENCY` at a time; one that fails or takes longer than
+    /// `STATS_REPOSITORY_TIMEOUT` is skipped.
+    pub async fn stats(&self, mut progress: impl FnMut(usize, usize)) -> Result<RegistryStats> {
+        let repositories = self.list_repositories().await?;
+        let total = repositories.len();
+        progress(0, total);
+
+        let lookups: Vec<_> = repositories
+            .iter()
+            .map(|repository| async move {
+                match tokio::time::timeout(STATS_REPOSITORY_TIMEOUT, self.repository_layers(repository)).await {
+                    Ok(Ok(counted)) => Some(counted),
+                    Ok(Err(e)) => {
+                        warn!("Left {} of registry {} out of its stats: {}", repository, self.config.name, e);
+                        None
+                    }
+                    Err(_) => {
+                        warn!("Left {} of registry {} out of its stats, as reading it timed out", repository, self.config.name);
+                        None
                     }
                 }
+            })
+            .collect();
+        let mut results = futures::stream::iter(lookups).buffer_unordered(SEARCH_CONCURRENCY);
+
+        let mut stats = RegistryStats {
+            repositories: total,
+            ..RegistryStats::default()
+        };
+        let mut layers = HashMap::new();
+        let mut done = 0;
+        while let Some(counted) = results.next().await {
+            match counted {
+                Some((tags, repository_layers)) => {
+                    stats.tags += tags;
+                    layers.extend(repository_layers);
+                }
+                None => stats.skipped_repositories += 1,
             }
+            done += 1;
+            progress(done, total);
         }
 
-        Ok(results)
+        stats.unique_layers = layers.len();
+        stats.total_bytes = layers.values().sum();
+        stats.computed_at = chrono::Utc::now();
+        Ok(stats)
     }
-}
 
-impl Default for RegistryManager {
-    fn default() -> Self {
-        Self::new()
-    }
-}
\ No newline at end of file
+    /// How many tags `repository` has, and the digests and sizes of their layers. Platform
+    /// manifests shared by several indexes are read once.
+    async fn repository_layers(&self, repository: &str) -> Result<(usize, HashMap<String, u64>)> {
+        let tags = self.list_tags(repository).await?;
+        let mut seen = HashSet::new();
+        let mut layers = HashMap::new();
+        let mut references = tags.clone();
+
+        // The tags' manifests, then the platform manifests of any indexes among them
+        while !references.is_empty() {
+            let mut children = Vec::new();
+            for manifest in self.fetch_manifests(repository, &references).await? {
+                if !seen.insert(manifest.digest.clone()) {
+                    continue;
+                }
+                match manifest.children()? {
+                    platforms if platforms.is_empty() => layers.extend(manifest.layers()?),
+                    platforms => children.extend(platforms),
+                }
+            }
+            children.retain(|digest| !seen.contains(digest));
+            children.sort();
+            children.dedup();
+            references = children;
+        }
+        Ok((tags.len(), layers))
+    }
+
+    async fn fetch_manifests(&self, repository: &str, references: &[String]) -> Result<Vec<RawManifest>> {
+        let fetches: Vec<_> = references
+            .iter()
+            .map(|reference| self.fetch_manifest_raw(repository, reference))
+            .collect();
+        futures::stream::iter(fetches)
+            .buffer_unordered(TAG_LIST_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    /// Up to `limit` repositories matching `query` from the registry's search API,
+    /// following its pages
+    pub async fn search_repositories(&self, query: &str, limit: usize) -> Result<Vec<RepositorySearchResult>> {
+        let search_url = self
+            .search_url()
+            .ok_or_else(|| Error::Config(format!("Registry {} has no search API", self.config.name)))?;
+        let mut url = reqwest::Url::parse(search_url).map_err(|e| Error::Config(format!("Invalid search URL {}: {}", search_url, e)))?;
+        url.query_pairs_mut()
+            .append_pair("query", query)
+            .append_pair("page_size", &limit.min(100).to_string());
+
+        #[derive(Deserialize)]
+        struct SearchPage {
+            next: Option<String>,
+            #[serde(default)]
+            results: Vec<HubRepository>,
+        }
+
+        #[derive(Deserialize)]
+        struct HubRepository {
+            repo_name: String,
+            short_description: Option<String>,
+            #[serde(default)]
+            star_count: u64,
+            #[serde(default)]
+            is_official: bool,
+        }
+
+        let mut results = Vec::new();
+        let mut next = Some(url.to_string());
+        while let Some(page_url) = next.take()
+            && results.len() < limit
+        {
+            let page: SearchPage = self
+                .search_page(&page_url)
+                .await?
+                .json()
+                .await
+                .context("Invalid search response")?;
+            if page.results.is_empty() {
+                break;
+            }
+            next = page.next;
+            results.extend(page.results.into_iter().map(|found| RepositorySearchResult {
+                repository: if found.is_official && !found.repo_name.contains('/') {
+                    format!("library/{}", found.repo_name)
+                } else {
+                    found.repo_name
+                },
+                description: found.short_description.filter(|description| !description.is_empty()),
+                stars: found.star_count,
+                official: found.is_official,
+            }));
+        }
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Fetch a page of search results, waiting out the API's rate limit when it asks
+    /// for no more than `MAX_RETRY_AFTER_SECS`
+    async fn search_page(&self, url: &str) -> Result<reqwest::Response> {
+        let mut attempt = 0;
+        loop {
+            let response = self.client.get(url).send().await?;
+            if response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+                if !response.status().is_success() {
+                    return Err(status_error(response.status(), format!("Search of {} failed: {}", self.config.name, response.status())));
+                }
+                return Ok(response);
+            }
+
+            let wait = retry_after(&response).unwrap_or(1);
+            attempt += 1;
+            if attempt > SEARCH_RETRIES || wait > MAX_RETRY_AFTER_SECS {
+                return Err(Error::Http {
+                    status: 429,
+                    message: format!(
+                    "Search of {} is rate limited, retry after {} s",
+                    self.config.name,
+                    wait
+                ),
+                });
+            }
+            warn!("Search of {} is rate limited, retrying in {} s", self.config.name, wait);
+            tokio::time::sleep(Duration::from_secs(wait)).await;
+        }
+    }
+
+    fn page_size(&self) -> u32 {
+        self.config.page_size.unwrap_or(DEFAULT_PAGE_SIZE).max(1)
+    }
+
+    /// Collect every entry of a paginated listing. Stops at `max_list_entries`, or when a
+    /// page brings nothing new, for registries that ignore `last`.
+    async fn list_all(&self, path: &str, scope: &str, key: &str, what: &str) -> Result<Vec<String>> {
+        let max_entries = self.config.max_list_entries.unwrap_or(DEFAULT_MAX_LIST_ENTRIES);
+        let mut entries = Vec::new();
+        let mut seen = HashSet::new();
+        let mut last: Option<String> = None;
+
+        loop {
+            let (page, next) = self
+                .list_page(path, scope, key, what, self.page_size(), last.as_deref())
+                .await?;
+            let before = entries.len();
+            entries.extend(page.into_iter().filter(|entry| seen.insert(entry.clone())));
+
+            if entries.len() >= max_entries {
+                warn!(
+                    "Stopped listing {} in registry {} at {} entries",
+                    what, self.config.name, max_entries
+                );
+                entries.truncate(max_entries);
+                break;
+            }
+            match next {
+                Some(next) if entries.len() > before => last = Some(next),
+                _ => break,
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Fetch one page of `path`, returning the strings under `key` and where the next page
+    /// starts. That is the `last` of the `Link` header's next URL, or, from registries that
+    /// send no `Link`, the last entry of a full page.
+    async fn list_page(
+        &self,
+        path: &str,
+        scope: &str,
+        key: &str,
+        what: &str,
+        n: u32,
+        last: Option<&str>,
+    ) -> Result<(Vec<String>, Option<String>)> {
+        let mut url = reqwest::Url::parse(&format!("{}{}", self.config.url, path))
+            .map_err(|e| Error::Config(format!("Invalid registry URL {}: {}", self.config.url, e)))?;
+        url.query_pairs_mut().append_pair("n", &n.to_string());
+        if let Some(last) = last {
+            url.query_pairs_mut().append_pair("last", last);
+        }
+
+        let response = self.send(scope, self.client.get(url.clone())).await?;
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to list {}: {}", what, response.status())));
+        }
+        let link_next = response
+            .headers()
+            .get_all(reqwest::header::LINK)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .find_map(|value| next_link(&url, value));
+
+        // Registries answer `"tags": null` for a repository without tags
+        let body: serde_json::Value = response.json().await?;
+        let entries: Vec<String> = body
+            .get(key)
+            .and_then(|entries| entries.as_array())
+            .map(|entries| entries.iter().filter_map(|entry| entry.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+
+        let next = match link_next {
+            Some(next) => Some(next),
+            None if entries.len() >= n as usize => entries.last().cloned(),
+            None => None,
+        };
+        Ok((entries, next))
+    }
+
+    /// Get manifest for a specific image, for `DEFAULT_PLATFORM` if it is multi-arch
+    pub async fn get_manifest(&self, repository: &str, tag: &str) -> Result<ImageManifest> {
+        Ok(self.resolve_manifest(repository, tag, None).await?.manifest)
+    }
+
+    /// The manifest of `reference` for `platform`. A single-platform manifest is returned
+    /// as is, without platforms.
+    async fn resolve_manifest(
+        &self,
+        repository: &str,
+        reference: &str,
+        platform: Option<&str>,
+    ) -> Result<ResolvedManifest> {
+        let raw = self.fetch_manifest_raw(repository, reference).await?;
+        let body = raw.json()?;
+        if body.get("manifests").is_none() {
+            let manifest = serde_json::from_value(body)
+                .with_context(|| format!("Invalid manifest for {}:{}", repository, reference))?;
+            return Ok(ResolvedManifest {
+                manifest,
+                digest: raw.digest.clone(),
+                raw,
+                platform: None,
+                platforms: Vec::new(),
+            });
+        }
+
+        let index: ImageIndex = serde_json::from_value(body)
+            .with_context(|| format!("Invalid manifest list for {}:{}", repository, reference))?;
+        // Build attestations are listed as `unknown/unknown`
+        let entries: Vec<(&PlatformManifest, &Platform)> = index
+            .manifests
+            .iter()
+            .filter_map(|entry| entry.platform.as_ref().map(|platform| (entry, platform)))
+            .filter(|(_, platform)| platform.os != "unknown")
+            .collect();
+
+        let wanted = platform.unwrap_or(DEFAULT_PLATFORM);
+        let (chosen, chosen_platform) = match entries.iter().find(|(_, candidate)| candidate.matches(wanted)) {
+            Some(entry) => *entry,
+            // Without a requested platform, any is better than none
+            None if platform.is_none() && !entries.is_empty() => entries[0],
+            None => {
+                let available: Vec<String> = entries.iter().map(|(_, platform)| platform.to_string()).collect();
+                return Err(Error::NotFound(format!(
+                    "{}:{} has no {} image, only {}",
+                    repository,
+                    reference,
+                    wanted,
+                    available.join(", ")
+                )));
+            }
+        };
+
+        debug!("Resolved {}:{} for {} to {}", repository, reference, wanted, chosen.digest);
+        let chosen_raw = self.fetch_manifest_raw(repository, &chosen.digest).await?;
+        let manifest = serde_json::from_value(chosen_raw.json()?)
+            .with_context(|| format!("Invalid manifest {} of {}", chosen.digest, repository))?;
+        Ok(ResolvedManifest {
+            manifest,
+            raw: chosen_raw,
+            digest: raw.digest,
+            platform: Some(chosen_platform.clone()),
+            platforms: entries.into_iter().map(|(_, platform)| platform.clone()).collect(),
+        })
+    }
+
+    /// The manifest or index `reference` names, with its digest from `Docker-Content-Digest`,
+    /// or computed from the body when the registry leaves that header out
+    async fn fetch_manifest_raw(&self, repository: &str, reference: &str) -> Result<RawManifest> {
+        let url = format!("{}/v2/{}/manifests/{}", self.config.url, repository, reference);
+        let request = self.client.get(&url).header("Accept", MANIFEST_OR_INDEX_ACCEPT);
+
+        let response = self.send(&pull_scope(repository), request).await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to get manifest for {}:{}: {}", repository, reference, response.status())));
+        }
+
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let (digest, content_type) = (header("docker-content-digest"), header("content-type"));
+        let manifest = RawManifest::from_body(response.bytes().await?.to_vec())?;
+        Ok(RawManifest {
+            digest: digest.unwrap_or(manifest.digest),
+            content_type: content_type.unwrap_or(manifest.content_type),
+            body: manifest.body,
+        })
+    }
+
+    /// Digest of the manifest or index `reference` names, from a HEAD request, which Docker
+    /// Hub does not count against its pull limit. `None` if there is no such manifest.
+    pub async fn manifest_digest(&self, repository: &str, reference: &str) -> Result<Option<String>> {
+        let url = format!("{}/v2/{}/manifests/{}", self.config.url, repository, reference);
+        let request = self.client.head(&url).header("Accept", MANIFEST_OR_INDEX_ACCEPT);
+        let response = self.send(&pull_scope(repository), request).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to look up {}:{}: {}", repository, reference, response.status())));
+        }
+        response
+            .headers()
+            .get("docker-content-digest")
+            .and_then(|value| value.to_str().ok())
+            .map(|digest| Some(digest.to_string()))
+            .ok_or_else(|| Error::Registry(format!("Registry did not give the digest of {}:{}", repository, reference)))
+    }
+
+    /// Get detailed image information including layers and metadata
+    pub async fn get_image_info(&self, repository: &str, tag: &str) -> Result<ImageInfo> {
+        self.get_image_info_for_platform(repository, tag, None).await
+    }
+
+    /// `get_image_info` for one platform of a multi-arch image, `DEFAULT_PLATFORM` if none
+    /// is given
+    pub async fn get_image_info_for_platform(
+        &self,
+        repository: &str,
+        tag: &str,
+        platform: Option<&str>,
+    ) -> Result<ImageInfo> {
+        let image = self.image_ref(repository, tag)?;
+        let remote = image.repository();
+        let ResolvedManifest { manifest, digest: manifest_digest, platform: index_platform, platforms, .. } =
+            self.resolve_manifest(remote, tag, platform).await?;
+
+        // Calculate total size from layers
+        let total_size: u64 = manifest.layers.iter().map(|l| l.size).sum();
+
+        // Get image config to extract creation date and other metadata
+        let config_url = format!("{}/v2/{}/blobs/{}", self.config.url, remote, manifest.config.digest);
+
+        let config_response = self.send(&pull_scope(remote), self.client.get(&config_url)).await?;
+        let config_data: serde_json::Value = config_response.json().await?;
+
+        // Extract created timestamp and author from config
+        let created = config_data
+            .get("created")
+            .and_then(|v| v.as_str())
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+            .unwrap_or_else(chrono::Utc::now);
+
+        let author = config_data
+            .get("author")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        // A single-platform image names its platform only in the config
+        let image_platform = match index_platform {
+            Some(platform) => Some(platform),
+            None => {
+                let config_platform: Option<Platform> = serde_json::from_value(config_data.clone()).ok();
+                if let (Some(wanted), Some(actual)) = (platform, &config_platform)
+                    && !actual.matches(wanted)
+                {
+                    return Err(Error::NotFound(format!("{}:{} has no {} image, only {}", repository, tag, wanted, actual)));
+                }
+                config_platform
+            }
+        };
+        let platforms: Vec<String> = if platforms.is_empty() {
+            image_platform.iter().map(Platform::to_string).collect()
+        } else {
+            platforms.iter().map(Platform::to_string).collect()
+        };
+
+        // Convert layers to LayerInfo
+        let layers: Vec<LayerInfo> = manifest.layers.into_iter().map(|layer| {
+            LayerInfo {
+                digest: layer.digest,
+                size: layer.size,
+                media_type: layer.media_type,
+                created_by: None, // Would need to parse history from config for this
+            }
+        }).collect();
+
+        Ok(ImageInfo {
+            repository: repository.to_string(),
+            tag: tag.to_string(),
+            digest: manifest.config.digest,
+            manifest_digest: Some(manifest_digest),
+            size: total_size,
+            created,
+            author,
+            layers,
+            platform: image_platform.map(|platform| platform.to_string()),
+            platforms,
+        })
+    }
+
+    /// Download an image into `blob_dir`: its layers, config and manifest, each stored as
+    /// `{algorithm}/{hex}`, and the manifest `repository:tag` names under `refs/`, so it
+    /// can be pushed again with `push_image`
+    pub async fn pull_image(&self, repository: &str, tag: &str, blob_dir: &Path) -> Result<()> {
+        self.pull_image_with_progress(repository, tag, blob_dir, |_| {}).await
+    }
+
+    /// `pull_image`, calling `progress` as each chunk of a layer arrives. Layers already
+    /// in `blob_dir` at the size the manifest gives are not downloaded again.
+    pub async fn pull_image_with_progress(
+        &self,
+        repository: &str,
+        tag: &str,
+        blob_dir: &Path,
+        mut progress: impl FnMut(&PullProgress),
+    ) -> Result<()> {
+        info!("Pulling image {}:{}", repository, tag);
+
+        // The local ref keeps the name asked for, as the runtime is given that name too
+        let image = self.image_ref(repository, tag)?;
+        let remote = image.repository();
+        let ResolvedManifest { manifest, raw, .. } = self.resolve_manifest(remote, tag, None).await?;
+        let mut state = PullProgress {
+            total_bytes: manifest.layers.iter().map(|layer| layer.size).sum(),
+            total_layers: manifest.layers.len(),
+            ..Default::default()
+        };
+        progress(&state);
+
+        for (index, layer) in manifest.layers.iter().enumerate() {
+            state.current_layer = index + 1;
+            let path = blob_path(blob_dir, &layer.digest)?;
+            let layer_start = state.current_bytes;
+
+            if tokio::fs::metadata(&path).await.is_ok_and(|metadata| metadata.len() == layer.size) {
+                debug!("Layer {} already stored", layer.digest);
+            } else {
+                self.download_blob(remote, &layer.digest, &path, |received| {
+                    state.current_bytes = layer_start + received;
+                    progress(&state);
+                })
+                .await?;
+            }
+            state.current_bytes = layer_start + layer.size;
+            progress(&state);
+        }
+
+        let config = &manifest.config;
+        let config_path = blob_path(blob_dir, &config.digest)?;
+        if !tokio::fs::metadata(&config_path).await.is_ok_and(|metadata| metadata.len() == config.size) {
+            self.download_blob(remote, &config.digest, &config_path, |_| {}).await?;
+        }
+        write_blob(&blob_path(blob_dir, &raw.digest)?, &raw.body).await?;
+        tag_local_manifest(blob_dir, repository, tag, &raw.digest).await?;
+
+        info!("Successfully pulled image {}:{} ({} bytes)", repository, tag, state.total_bytes);
+        Ok(())
+    }
+
+    /// Stream a blob to `path`, checking it against its digest. `received` is called with
+    /// the number of bytes written so far.
+    async fn download_blob(
+        &self,
+        repository: &str,
+        digest: &str,
+        path: &Path,
+        mut received: impl FnMut(u64),
+    ) -> Result<()> {
+        let url = format!("{}/v2/{}/blobs/{}", self.config.url, repository, digest);
+        let response = self.send(&pull_scope(repository), self.client.get(&url)).await?;
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to fetch layer {}: {}", digest, response.status())));
+        }
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        // Written under another name first, so an interrupted download is never taken for a stored layer
+        let partial = path.with_extension("partial");
+        let mut file = tokio::fs::File::create(&partial)
+            .await
+            .with_context(|| format!("Failed to create {}", partial.display()))?;
+
+        let mut hasher = Sha256::new();
+        let mut written = 0;
+        let mut chunks = response.bytes_stream();
+        while let Some(chunk) = chunks.next().await {
+            let chunk = chunk.with_context(|| format!("Failed to download layer {}", digest))?;
+            hasher.update(&chunk);
+            file.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+            received(written);
+        }
+        file.flush().await?;
+        drop(file);
+
+        if let Some(expected) = digest.strip_prefix("sha256:") {
+            let actual = format!("{:x}", hasher.finalize());
+            if actual != expected {
+                let _ = tokio::fs::remove_file(&partial).await;
+                return Err(Error::Registry(format!("Layer {} failed verification, got sha256:{}", digest, actual)));
+            }
+        }
+        tokio::fs::rename(&partial, path)
+            .await
+            .with_context(|| format!("Failed to store layer {}", digest))?;
+        Ok(())
+    }
+
+    /// Push the image `repository:tag` names in `blob_dir`, as stored by `pull_image` or
+    /// `tag_local_manifest`, to the same name on this registry. Blobs the registry already
+    /// has are not uploaded again.
+    pub async fn push_image(&self, repository: &str, tag: &str, blob_dir: &Path) -> Result<ImageCopyResult> {
+        let digest = local_manifest_digest(blob_dir, repository, tag).await?.ok_or_else(|| {
+            Error::NotFound(format!("{}:{} was not pulled through GhostPanel, so there is nothing to push", repository, tag))
+        })?;
+        let result = self
+            .push_from(&ImageSource::Local(blob_dir), &digest, repository, tag, &mut |_| {})
+            .await?;
+        info!(
+            "Pushed {}:{} to {} ({} uploaded, {} existing)",
+            repository, tag, self.config.name, result.blobs_uploaded, result.blobs_existing
+        );
+        Ok(result)
+    }
+
+    /// Whether both clients talk to the same registry host, so blobs can be mounted across repositories
+    pub fn same_host(&self, other: &RegistryClient) -> bool {
+        let host = |url: &str| {
+            reqwest::Url::parse(url)
+                .ok()
+                .and_then(|url| url.host_str().map(|host| (host.to_string(), url.port_or_known_default())))
+        };
+        match (host(&self.config.url), host(&other.config.url)) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Resolve a possibly relative upload location against the registry URL
+    fn upload_url(&self, location: &str) -> String {
+        if location.starts_with("http://") || location.starts_with("https://") {
+            location.to_string()
+        } else {
+            format!("{}{}", self.config.url.trim_end_matches('/'), location)
+        }
+    }
+
+    /// Check whether a repository already has a blob
+    pub async fn blob_exists(&self, repository: &str, digest: &str) -> Result<bool> {
+        let url = format!("{}/v2/{}/blobs/{}", self.config.url, repository, digest);
+        let response = self.send(&pull_scope(repository), self.client.head(&url)).await?;
+        Ok(response.status().is_success())
+    }
+
+    /// Open a blob upload session, returning its location
+    async fn start_upload(&self, repository: &str) -> Result<String> {
+        let url = format!("{}/v2/{}/blobs/uploads/", self.config.url, repository);
+        let response = self.send(&push_scope(repository), self.client.post(&url)).await?;
+
+        if response.status() != reqwest::StatusCode::ACCEPTED {
+            return Err(status_error(response.status(), format!("Failed to start blob upload to {}: {}", repository, response.status())));
+        }
+        upload_location(&response)
+    }
+
+    /// Try to mount a blob from another repository on this registry
+    pub async fn mount_blob(&self, repository: &str, digest: &str, from: &str) -> Result<BlobMount> {
+        let url = format!(
+            "{}/v2/{}/blobs/uploads/?mount={}&from={}",
+            self.config.url,
+            repository,
+            urlencoding::encode(digest),
+            urlencoding::encode(from)
+        );
+        let scope = format!("{} {}", push_scope(repository), pull_scope(from));
+        let response = self.send(&scope, self.client.post(&url)).await?;
+
+        match response.status() {
+            reqwest::StatusCode::CREATED => Ok(BlobMount::Mounted),
+            // The registry opened a regular upload session instead
+            reqwest::StatusCode::ACCEPTED => Ok(BlobMount::Upload(upload_location(&response)?)),
+            status => {
+                debug!("Mounting {} from {} into {} failed: {}", digest, from, repository, status);
+                Ok(BlobMount::Upload(self.start_upload(repository).await?))
+            }
+        }
+    }
+
+    /// Finish an upload session by sending the whole blob
+    async fn upload_blob(
+        &self,
+        repository: &str,
+        location: &str,
+        digest: &str,
+        size: u64,
+        body: reqwest::Body,
+    ) -> Result<()> {
+        let url = self.upload_url(location);
+        let separator = if url.contains('?') { '&' } else { '?' };
+        let url = format!("{}{}digest={}", url, separator, urlencoding::encode(digest));
+
+        let request = self
+            .client
+            .put(&url)
+            .header("Content-Type", "application/octet-stream")
+            .header("Content-Length", size)
+            .body(body);
+        let response = self.send(&push_scope(repository), request).await?;
+
+        if response.status() != reqwest::StatusCode::CREATED {
+            return Err(status_error(response.status(), format!("Failed to upload blob {}: {}", digest, response.status())));
+        }
+        Ok(())
+    }
+
+    /// Stream a blob's contents
+    async fn blob_body(&self, repository: &str, digest: &str) -> Result<reqwest::Body> {
+        Ok(reqwest::Body::wrap_stream(self.blob_stream(repository, digest).await?))
+    }
+
+    async fn blob_stream(
+        &self,
+        repository: &str,
+        digest: &str,
+    ) -> Result<impl futures::Stream<Item = reqwest::Result<bytes::Bytes>> + use<>> {
+        let url = format!("{}/v2/{}/blobs/{}", self.config.url, repository, digest);
+        let response = self.send(&pull_scope(repository), self.client.get(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to fetch blob {}: {}", digest, response.status())));
+        }
+        Ok(response.bytes_stream())
+    }
+
+    async fn put_manifest(&self, repository: &str, reference: &str, manifest: &RawManifest) -> Result<()> {
+        let url = format!("{}/v2/{}/manifests/{}", self.config.url, repository, reference);
+        let request = self
+            .client
+            .put(&url)
+            .header("Content-Type", &manifest.content_type)
+            .body(manifest.body.clone());
+        let response = self.send(&push_scope(repository), request).await?;
+
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to push manifest for {}:{}: {}", repository, reference, response.status())));
+        }
+        Ok(())
+    }
+
+    /// Tag the image `source`, a tag or digest, as `target` in the same repository by
+    /// putting its manifest back under the new name, byte for byte and with its content
+    /// type, so no blobs move. A multi-arch index is tagged whole, as its platform
+    /// manifests are already in the repository. Returns the digest both tags now name.
+    pub async fn tag_image(&self, repository: &str, source: &str, target: &str) -> std::result::Result<String, TagImageError> {
+        if !is_valid_tag(target) {
+            return Err(TagImageError::InvalidTag(format!(
+                "'{}' is not a valid tag: use up to 128 letters, digits, '_', '.' and '-', not starting with '.' or '-'",
+                target
+            )));
+        }
+        if self.manifest_digest(repository, source).await?.is_none() {
+            return Err(TagImageError::NotFound(format!("{}:{} does not exist", repository, source)));
+        }
+
+        let manifest = self.fetch_manifest_raw(repository, source).await?;
+        self.put_manifest(repository, target, &manifest).await?;
+        info!("Tagged {}:{} as {} ({})", repository, source, target, manifest.digest);
+        Ok(manifest.digest)
+    }
+
+    /// `repository:reference` as a tar of an OCI image layout: `oci-layout`, an `index.json`
+    /// naming the image by its tag, then its manifests and blobs under `blobs/`. With a
+    /// `platform`, a multi-arch image is narrowed to that platform; without, every platform
+    /// is included. The manifests are fetched before this returns, so a missing image fails
+    /// here; blobs stream from the registry as the archive is read, checked against their
+    /// digests.
+    pub async fn export_image(
+        &self,
+        repository: &str,
+        reference: &str,
+        platform: Option<&str>,
+    ) -> Result<futures::stream::BoxStream<'static, Result<bytes::Bytes>>> {
+        let top = match platform {
+            Some(_) => self.resolve_manifest(repository, reference, platform).await?.raw,
+            None => self.fetch_manifest_raw(repository, reference).await?,
+        };
+        let mut manifests = vec![top];
+        for child in manifests[0].children()? {
+            manifests.push(self.fetch_manifest_raw(repository, &child).await?);
+        }
+        let blobs = image_blobs(&manifests)?;
+
+        let top = &manifests[0];
+        let mut entry = serde_json::json!({
+            "mediaType": top.content_type,
+            "digest": top.digest,
+            "size": top.body.len(),
+        });
+        if !reference.contains(':') {
+            entry["annotations"] = serde_json::json!({ REF_NAME_ANNOTATION: reference });
+        }
+        let index = serde_json::json!({
+            "schemaVersion": 2,
+            "mediaType": "application/vnd.oci.image.index.v1+json",
+            "manifests": [entry],
+        });
+
+        let mut files = vec![
+            tar_file(LAYOUT_FILE, LAYOUT_VERSION)?,
+            tar_file(INDEX_FILE, index.to_string())?,
+        ];
+        for manifest in &manifests {
+            files.push(tar_file(&blob_file(&manifest.digest)?, manifest.body.clone())?);
+        }
+
+        let client = self.clone();
+        let repository = repository.to_string();
+        let blobs = futures::stream::iter(blobs)
+            .then(move |(digest, size)| {
+                let (client, repository) = (client.clone(), repository.clone());
+                async move { client.blob_file_stream(&repository, &digest, size).await }
+            })
+            .try_flatten();
+        Ok(futures::stream::iter(files.into_iter().map(Ok))
+            .chain(blobs)
+            .chain(futures::stream::once(async { Ok(tar_end()) }))
+            .boxed())
+    }
+
+    /// A blob as a file of an OCI layout tar: its header, its contents as they arrive from
+    /// the registry, failing if they do not match `digest` and `size`, and padding
+    async fn blob_file_stream(
+        &self,
+        repository: &str,
+        digest: &str,
+        size: u64,
+    ) -> Result<futures::stream::BoxStream<'static, Result<bytes::Bytes>>> {
+        let header = tar_header(&blob_file(digest)?, size)?;
+        let chunks = Box::pin(self.blob_stream(repository, digest).await?);
+        let digest = digest.to_string();
+        let contents = futures::stream::try_unfold(
+            (chunks, Sha256::new(), 0u64),
+            move |(mut chunks, mut hasher, received)| {
+                let digest = digest.clone();
+                async move {
+                    let Some(chunk) = chunks.next().await else {
+                        if received != size {
+                            return Err(Error::Registry(format!("Blob {} has {} bytes, not {}", digest, received, size)));
+                        }
+                        check_digest(&digest, hasher)?;
+                        return Ok(None);
+                    };
+                    let chunk = chunk?;
+                    hasher.update(&chunk);
+                    Ok(Some((chunk.clone(), (chunks, hasher, received + chunk.len() as u64))))
+                }
+            },
+        );
+        Ok(futures::stream::once(async { Ok(header) })
+            .chain(contents)
+            .chain(futures::stream::once(async move { Ok(tar_padding(size)) }))
+            .boxed())
+    }
+
+    /// Push the image in an OCI layout tar, as `export_image` writes, to `repository:tag`.
+    /// Without a `tag`, the image's tag in `index.json` is used; an archive of several
+    /// images needs one to pick the image by. Blobs are uploaded as they are read, except
+    /// small ones, which wait until the manifests are known. Fails with an
+    /// [`ImportImageError`] when the archive is not a complete image.
+    pub async fn import_image<S, E>(
+        &self,
+        repository: &str,
+        tag: Option<&str>,
+        archive: S,
+    ) -> std::result::Result<ImageImportResult, ImportImageError>
+    where
+        S: futures::Stream<Item = std::result::Result<bytes::Bytes, E>> + Unpin,
+        E: Into<Error>,
+    {
+        let invalid = |e: Error| ImportImageError::InvalidArchive(format!("{:#}", e));
+        let mut reader = TarReader::new(archive);
+        let mut index = None;
+        let mut held: HashMap<String, Vec<u8>> = HashMap::new();
+        // Blobs uploaded as they were read, or that the repository already had
+        let mut pushed = HashSet::new();
+        let mut result = ImageImportResult {
+            repository: repository.to_string(),
+            tag: String::new(),
+            digest: String::new(),
+            blobs_uploaded: 0,
+            blobs_existing: 0,
+        };
+
+        while let Some(entry) = reader.next_entry().await.map_err(invalid)? {
+            if entry.path == INDEX_FILE && entry.size <= IMPORT_BUFFER_LIMIT {
+                index = Some(reader.read_to_end().await.map_err(invalid)?);
+                continue;
+            }
+            let Some(digest) = blob_digest(&entry.path) else {
+                continue;
+            };
+            if entry.size <= IMPORT_BUFFER_LIMIT {
+                let contents = reader.read_to_end().await.map_err(invalid)?;
+                let mut hasher = Sha256::new();
+                hasher.update(&contents);
+                check_digest(&digest, hasher).map_err(invalid)?;
+                held.insert(digest, contents);
+            } else if self.blob_exists(repository, &digest).await? {
+                result.blobs_existing += 1;
+                pushed.insert(digest);
+            } else {
+                self.upload_from_archive(repository, &digest, entry.size, &mut reader).await?;
+                result.blobs_uploaded += 1;
+                pushed.insert(digest);
+            }
+        }
+
+        let index = index.ok_or_else(|| ImportImageError::InvalidArchive(format!("The archive has no {}, so it is not an OCI image layout", INDEX_FILE)))?;
+        let index: serde_json::Value = serde_json::from_slice(&index).map_err(|e| ImportImageError::InvalidArchive(format!("Invalid {}: {}", INDEX_FILE, e)))?;
+        let images = index.get("manifests").and_then(|manifests| manifests.as_array()).cloned().unwrap_or_default();
+        let tag_of = |image: &serde_json::Value| {
+            image.pointer(&format!("/annotations/{}", REF_NAME_ANNOTATION.replace('/', "~1"))).and_then(|name| name.as_str()).map(str::to_string)
+        };
+        let image = match (images.as_slice(), tag) {
+            ([image], _) => image,
+            (images, Some(tag)) => images
+                .iter()
+                .find(|image| tag_of(image).as_deref() == Some(tag))
+                .ok_or_else(|| ImportImageError::InvalidArchive(format!("{} has no image tagged {}", INDEX_FILE, tag)))?,
+            (images, None) => {
+                return Err(ImportImageError::InvalidArchive(format!("{} lists {} images; give the tag of one", INDEX_FILE, images.len())));
+            }
+        };
+        result.tag = tag
+            .map(str::to_string)
+            .or_else(|| tag_of(image))
+            .ok_or_else(|| ImportImageError::InvalidArchive(format!("The image in {} has no tag, so one must be given", INDEX_FILE)))?;
+        if !is_valid_tag(&result.tag) {
+            return Err(ImportImageError::InvalidArchive(format!("'{}' is not a valid tag", result.tag)));
+        }
+
+        let manifest = |digest: &str| {
+            let body = held.get(digest).ok_or_else(|| ImportImageError::InvalidArchive(format!("Manifest {} is missing from the archive", digest)))?;
+            RawManifest::from_body(body.clone()).map_err(invalid)
+        };
+        let top_digest = image
+            .get("digest")
+            .and_then(|digest| digest.as_str())
+            .ok_or_else(|| ImportImageError::InvalidArchive(format!("The image in {} has no digest", INDEX_FILE)))?;
+        let mut top = manifest(top_digest)?;
+        // Docker manifest lists may leave their type to the index
+        if let Some(media_type) = image.get("mediaType").and_then(|media_type| media_type.as_str()) {
+            top.content_type = media_type.to_string();
+        }
+        let mut platform_manifests = Vec::new();
+        for child in top.children().map_err(invalid)? {
+            platform_manifests.push(manifest(&child)?);
+        }
+
+        for (digest, size) in image_blobs(platform_manifests.iter().chain([&top])).map_err(invalid)? {
+            if pushed.contains(&digest) {
+                continue;
+            }
+            let contents = held
+                .get(&digest)
+                .ok_or_else(|| ImportImageError::InvalidArchive(format!("Blob {} is missing from the archive", digest)))?;
+            if self.blob_exists(repository, &digest).await? {
+                result.blobs_existing += 1;
+            } else {
+                let location = self.start_upload(repository).await?;
+                self.upload_blob(repository, &location, &digest, size, contents.clone().into()).await?;
+                result.blobs_uploaded += 1;
+            }
+            pushed.insert(digest);
+        }
+
+        for manifest in &platform_manifests {
+            self.put_manifest(repository, &manifest.digest, manifest).await?;
+        }
+        self.put_manifest(repository, &result.tag, &top).await?;
+        result.digest = top.digest;
+        info!(
+            "Imported {}:{} into {} ({} uploaded, {} existing)",
+            repository, result.tag, self.config.name, result.blobs_uploaded, result.blobs_existing
+        );
+        Ok(result)
+    }
+
+    /// Upload the current file of `reader`, `size` bytes of the blob `digest`, passing each
+    /// chunk on as it is read
+    async fn upload_from_archive<S, E>(
+        &self,
+        repository: &str,
+        digest: &str,
+        size: u64,
+        reader: &mut TarReader<S>,
+    ) -> std::result::Result<(), ImportImageError>
+    where
+        S: futures::Stream<Item = std::result::Result<bytes::Bytes, E>> + Unpin,
+        E: Into<Error>,
+    {
+        use futures::{SinkExt, TryFutureExt};
+
+        let location = self.start_upload(repository).await?;
+        let (mut sender, receiver) = futures::channel::mpsc::channel::<std::io::Result<bytes::Bytes>>(4);
+        let upload = self
+            .upload_blob(repository, &location, digest, size, reqwest::Body::wrap_stream(receiver))
+            .map_err(ImportImageError::from);
+        let read = async move {
+            let mut hasher = Sha256::new();
+            while let Some(chunk) = reader.read_chunk().await.map_err(|e| ImportImageError::InvalidArchive(format!("{:#}", e)))? {
+                hasher.update(&chunk);
+                // The upload has failed when nothing is receiving, and says why
+                if sender.send(Ok(chunk)).await.is_err() {
+                    return Ok(());
+                }
+            }
+            check_digest(digest, hasher).map_err(|e| ImportImageError::InvalidArchive(e.to_string()))?;
+            Ok::<_, ImportImageError>(())
+        };
+        tokio::try_join!(upload, read)?;
+        Ok(())
+    }
+
+    /// Copy an image into this registry, mounting blobs instead of uploading them when
+    /// `source` is on the same registry host. Multi-arch images are copied whole.
+    pub async fn copy_image_from(
+        &self,
+        source: &RegistryClient,
+        source_repository: &str,
+        source_tag: &str,
+        repository: &str,
+        tag: &str,
+    ) -> Result<ImageCopyResult> {
+        self.copy_image_with_progress(source, source_repository, source_tag, repository, tag, |_| {})
+            .await
+    }
+
+    /// `copy_image_from`, calling `progress` as each blob reaches this registry. Blobs
+    /// stream from one registry to the other without being held in memory.
+    pub async fn copy_image_with_progress(
+        &self,
+        source: &RegistryClient,
+        source_repository: &str,
+        source_tag: &str,
+        repository: &str,
+        tag: &str,
+        mut progress: impl FnMut(&PullProgress) + Send,
+    ) -> Result<ImageCopyResult> {
+        let image = ImageSource::Registry {
+            client: source,
+            repository: source_repository,
+        };
+        let result = self.push_from(&image, source_tag, repository, tag, &mut progress).await?;
+        info!(
+            "Copied {}:{} to {}:{} ({} mounted, {} uploaded, {} existing)",
+            source_repository, source_tag, repository, tag, result.blobs_mounted, result.blobs_uploaded, result.blobs_existing
+        );
+        Ok(result)
+    }
+
+    /// Push the image `reference` names in `source` to `repository:tag`: every blob it is
+    /// missing, then each platform manifest of an index by digest, then the manifest or
+    /// index itself under `tag`
+    async fn push_from(
+        &self,
+        source: &ImageSource<'_>,
+        reference: &str,
+        repository: &str,
+        tag: &str,
+        progress: &mut (dyn FnMut(&PullProgress) + Send),
+    ) -> Result<ImageCopyResult> {
+        let top = source.manifest(reference).await?;
+        let mut platform_manifests = Vec::new();
+        for child in top.children()? {
+            platform_manifests.push(source.manifest(&child).await?);
+        }
+        let blobs = image_blobs(platform_manifests.iter().chain([&top]))?;
+
+        let mount_from = match source {
+            ImageSource::Registry { client, repository } if self.same_host(client) => Some(*repository),
+            _ => None,
+        };
+        let mut state = PullProgress {
+            total_bytes: blobs.iter().map(|(_, size)| size).sum(),
+            total_layers: blobs.len(),
+            ..Default::default()
+        };
+        progress(&state);
+        let mut result = ImageCopyResult::default();
+
+        for (index, (digest, size)) in blobs.iter().enumerate() {
+            state.current_layer = index + 1;
+            if self.blob_exists(repository, digest).await? {
+                result.blobs_existing += 1;
+            } else {
+                let location = match mount_from {
+                    Some(from) => match self.mount_blob(repository, digest, from).await? {
+                        BlobMount::Mounted => {
+                            debug!("Mounted {} from {} into {}", digest, from, repository);
+                            None
+                        }
+                        BlobMount::Upload(location) => Some(location),
+                    },
+                    None => Some(self.start_upload(repository).await?),
+                };
+                match location {
+                    Some(location) => {
+                        let body = source.blob_body(digest).await?;
+                        self.upload_blob(repository, &location, digest, *size, body).await?;
+                        result.blobs_uploaded += 1;
+                    }
+                    None => result.blobs_mounted += 1,
+                }
+            }
+            state.current_bytes += size;
+            progress(&state);
+        }
+
+        for manifest in &platform_manifests {
+            self.put_manifest(repository, &manifest.digest, manifest).await?;
+        }
+        self.put_manifest(repository, tag, &top).await?;
+        Ok(result)
+    }
+
+    /// Delete the manifest `tag` points to, which also removes any other tag pointing to
+    /// it, and return its digest. Fails with a [`DeleteImageError`] when the tag does not
+    /// exist or the registry does not delete images.
+    pub async fn delete_image(&self, repository: &str, tag: &str) -> std::result::Result<String, DeleteImageError> {
+        if self.is_docker_hub() {
+            return Err(DeleteImageError::Unsupported(format!(
+                "{} only deletes images through the Docker Hub website or API",
+                self.config.name
+            )));
+        }
+
+        // The digest of whatever the tag names, a multi-arch index included
+        let digest = self
+            .manifest_digest(repository, tag)
+            .await?
+            .ok_or_else(|| DeleteImageError::NotFound(format!("{}:{} does not exist", repository, tag)))?;
+
+        // Registries only delete manifests by digest
+        let delete_url = format!("{}/v2/{}/manifests/{}", self.config.url, repository, digest);
+        let response = self.send(&delete_scope(repository), self.client.delete(&delete_url)).await?;
+        match response.status() {
+            status if status.is_success() => {
+                info!("Deleted {}:{} ({})", repository, tag, digest);
+                Ok(digest)
+            }
+            reqwest::StatusCode::METHOD_NOT_ALLOWED => Err(DeleteImageError::Disabled(format!(
+                "{} does not allow deleting images; enable deletes in its storage settings",
+                self.config.name
+            ))),
+            reqwest::StatusCode::NOT_FOUND => {
+                Err(DeleteImageError::NotFound(format!("{}:{} was already deleted", repository, tag)))
+            }
+            status => Err(status_error(status, format!("Failed to delete {}:{}: {}", repository, tag, status)).into()),
+        }
+    }
+
+    /// Drift's endpoints, if `GET /drift/v1/info` shows this registry is Drift; `None` for
+    /// other registries, which answer it with an error or something other than Drift's info
+    pub async fn drift(&self) -> Result<Option<DriftClient>> {
+        let url = format!("{}/drift/v1/info", self.config.url);
+        let response = self.send(DRIFT_SCOPE, self.client.get(&url)).await?;
+        if !response.status().is_success() {
+            debug!("{} is not a Drift registry: {} answered {}", self.config.name, url, response.status());
+            return Ok(None);
+        }
+        Ok(response.json::<DriftInfo>().await.ok().map(|info| DriftClient {
+            registry: self.clone(),
+            info,
+        }))
+    }
+}
+
+/// The endpoints a Drift registry has beyond the registry API, for garbage collection and
+/// storage quotas. Got from [`RegistryClient::drift`], which checks the registry is Drift.
+#[derive(Clone)]
+pub struct DriftClient {
+    registry: RegistryClient,
+    info: DriftInfo,
+}
+
+impl DriftClient {
+    pub fn info(&self) -> &DriftInfo {
+        &self.info
+    }
+
+    /// Start deleting the blobs no manifest refers to. Drift runs one collection at a
+    /// time, so while one runs this returns its status rather than starting another.
+    pub async fn trigger_gc(&self) -> Result<DriftGcStatus> {
+        let url = format!("{}/drift/v1/gc", self.registry.config.url);
+        let response = self.registry.send(DRIFT_SCOPE, self.registry.client.post(&url)).await?;
+        match response.status() {
+            status if status.is_success() => {
+                info!("Started garbage collection on {}", self.registry.config.name);
+                Ok(response.json().await?)
+            }
+            reqwest::StatusCode::CONFLICT => self.gc_status().await,
+            status => Err(status_error(status, format!("Failed to start garbage collection: {}", status))),
+        }
+    }
+
+    /// The running collection, or the last one
+    pub async fn gc_status(&self) -> Result<DriftGcStatus> {
+        self.get("gc", "garbage collection status").await
+    }
+
+    pub async fn get_quota(&self) -> Result<DriftQuota> {
+        self.get("quota", "storage quota").await
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, endpoint: &str, what: &str) -> Result<T> {
+        let url = format!("{}/drift/v1/{}", self.registry.config.url, endpoint);
+        let response = self.registry.send(DRIFT_SCOPE, self.registry.client.get(&url)).await?;
+        if !response.status().is_success() {
+            return Err(status_error(response.status(), format!("Failed to get the {}: {}", what, response.status())));
+        }
+        response.json().await.with_context(|| format!("Invalid {} from {}", what, self.registry.config.name))
+    }
+}
+
+/// Certificates in the PEM file at `path`
+fn read_certificates(path: &str) -> Result<Vec<reqwest::Certificate>> {
+    let pem = std::fs::read(path).with_context(|| format!("Failed to read {}", path))?;
+    let certificates = reqwest::Certificate::from_pem_bundle(&pem).with_context(|| format!("Invalid PEM in {}", path))?;
+    if certificates.is_empty() {
+        return Err(Error::Config(format!("No certificates in {}", path)));
+    }
+    Ok(certificates)
+}
+
+/// Status and message of a request that got no answer, telling certificate and handshake
+/// failures apart from the rest by the error's causes
+fn connection_failure(error: reqwest::Error) -> (RegistryStatus, String) {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(&error);
+    while let Some(cause) = source {
+        // Some causes already include their own source in their message
+        let cause_message = cause.to_string();
+        if !message.contains(&cause_message) {
+            message = format!("{}: {}", message, cause_message);
+        }
+        source = cause.source();
+    }
+    let lowercase = message.to_lowercase();
+    let tls = ["certificate", "tls", "ssl", "handshake"]
+        .iter()
+        .any(|word| lowercase.contains(word));
+    if tls {
+        (RegistryStatus::TlsError, message)
+    } else {
+        (RegistryStatus::Unreachable, message)
+    }
+}
+
+/// A registry answering with an error `status`, kept so the agent can pass on 404s and 429s
+fn status_error(status: reqwest::StatusCode, message: String) -> Error {
+    Error::Http {
+        status: status.as_u16(),
+        message,
+    }
+}
+
+/// Seconds to wait from a `Retry-After` header, given either as seconds or as an HTTP date
+fn retry_after(response: &reqwest::Response) -> Option<u64> {
+    let value = response.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse() {
+        return Some(seconds);
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some((at.with_timezone(&chrono::Utc) - chrono::Utc::now()).num_seconds().max(0) as u64)
+}
+
+/// Parse a challenge such as
+/// `Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="..."`
+fn auth_challenge(response: &reqwest::Response) -> Option<AuthChallenge> {
+    let header = response.headers().get(reqwest::header::WWW_AUTHENTICATE)?.to_str().ok()?.trim();
+    let (scheme, params) = header.split_once(' ').unwrap_or((header, ""));
+    if scheme.eq_ignore_ascii_case("basic") {
+        return Some(AuthChallenge::Basic);
+    }
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+
+    let mut realm = None;
+    let mut service = None;
+    // `scope` may hold commas inside its quotes; it is not needed, so splitting it apart is harmless
+    for part in params.split(',') {
+        match part.trim().split_once('=') {
+            Some(("realm", value)) => realm = Some(value.trim_matches('"').to_string()),
+            Some(("service", value)) => service = Some(value.trim_matches('"').to_string()),
+            _ => {}
+        }
+    }
+    Some(AuthChallenge::Bearer { realm: realm?, service })
+}
+
+/// The `last` parameter of the `rel="next"` URL in a `Link` header such as
+/// `</v2/_catalog?last=b&n=100>; rel="next"`. The URL may be relative to `base`.
+fn next_link(base: &reqwest::Url, header: &str) -> Option<String> {
+    header.split(',').find_map(|link| {
+        let (target, params) = link.split_once(';')?;
+        let is_next = params
+            .split(';')
+            .any(|param| matches!(param.trim().split_once('='), Some(("rel", rel)) if rel.trim_matches('"') == "next"));
+        if !is_next {
+            return None;
+        }
+        let target = target.trim().strip_prefix('<')?.strip_suffix('>')?;
+        let url = base.join(target).ok()?;
+        url.query_pairs().find(|(name, _)| name == "last").map(|(_, last)| last.into_owned())
+    })
+}
+
+/// Where a blob is kept under `blob_dir`. The digest comes from a remote manifest, so
+/// anything but `algorithm:hex` is refused rather than used as a path.
+fn blob_path(blob_dir: &Path, digest: &str) -> Result<PathBuf> {
+    let (algorithm, hex) = split_digest(digest)?;
+    Ok(blob_dir.join(algorithm).join(hex))
+}
+
+/// The algorithm and hex of a digest, refusing anything that could leave a directory
+pub(crate) fn split_digest(digest: &str) -> Result<(&str, &str)> {
+    match digest.split_once(':') {
+        Some((algorithm, hex))
+            if !algorithm.is_empty()
+                && !hex.is_empty()
+                && algorithm.chars().all(|c| c.is_ascii_alphanumeric())
+                && hex.chars().all(|c| c.is_ascii_hexdigit()) =>
+        {
+            Ok((algorithm, hex))
+        }
+        _ => Err(Error::Invalid(format!("Invalid layer digest {}", digest))),
+    }
+}
+
+/// Fail unless what `hasher` was fed matches `digest`. Only sha256 digests are checked;
+/// registries check the others.
+fn check_digest(digest: &str, hasher: Sha256) -> Result<()> {
+    let actual = format!("sha256:{:x}", hasher.finalize());
+    if digest.starts_with("sha256:") && actual != digest {
+        return Err(Error::Registry(format!("Blob {} does not match its digest, it hashes to {}", digest, actual)));
+    }
+    Ok(())
+}
+
+/// Where the manifest digest `repository:tag` names is kept in `blob_dir`
+fn local_ref_path(blob_dir: &Path, repository: &str, tag: &str) -> Result<PathBuf> {
+    if !is_valid_repository(repository) || !is_valid_tag(tag) {
+        return Err(Error::Invalid(format!("Invalid image reference '{}:{}'", repository, tag)));
+    }
+    Ok(blob_dir.join("refs").join(repository).join(tag))
+}
+
+/// The digest of the manifest `repository:tag` names in `blob_dir`, if it was pulled or
+/// tagged there
+pub async fn local_manifest_digest(blob_dir: &Path, repository: &str, tag: &str) -> Result<Option<String>> {
+    match tokio::fs::read_to_string(local_ref_path(blob_dir, repository, tag)?).await {
+        Ok(digest) => Ok(Some(digest.trim().to_string())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to read the local tag {}:{}", repository, tag)),
+    }
+}
+
+/// Point `repository:tag` in `blob_dir` at a stored manifest, as tagging a local image does
+pub async fn tag_local_manifest(blob_dir: &Path, repository: &str, tag: &str, digest: &str) -> Result<()> {
+    write_blob(&local_ref_path(blob_dir, repository, tag)?, digest.as_bytes()).await
+}
+
+/// Write `contents` to `path`, creating its directory
+async fn write_blob(path: &Path, contents: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    tokio::fs::write(path, contents)
+        .await
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn upload_location(response: &reqwest::Response) -> Result<String> {
+    response
+        .headers()
+        .get("location")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+        .ok_or_else(|| Error::Registry("Registry did not return an upload location".to_string()))
+}
+
+/// Registry manager for handling multiple registries
+#[derive(Debug)]
+pub struct RegistryManager {
+    registries: HashMap<String, RegistryClient>,
+    image_cache: Mutex<ImageCache>,
+    /// Proxies for the clients of registries added from now on
+    proxy: ProxyConfig,
+}
+
+impl RegistryManager {
+    pub fn new() -> Self {
+        Self::with_image_cache(ImageCacheConfig::default())
+    }
+
+    pub fn with_image_cache(config: ImageCacheConfig) -> Self {
+        Self {
+            registries: HashMap::new(),
+            image_cache: Mutex::new(ImageCache::new(config)),
+            proxy: ProxyConfig::default(),
+        }
+    }
+
+    /// Reach the registries added from now on through `proxy`
+    pub fn set_proxy(&mut self, proxy: ProxyConfig) {
+        self.proxy = proxy;
+    }
+
+    /// A client for `config` with the manager's proxies, to authenticate before adding it
+    /// through [`Self::insert_client`]
+    pub fn client_for(&self, config: RegistryConfig) -> RegistryClient {
+        RegistryClient::with_proxy(config, &self.proxy)
+    }
+
+    /// Add a new registry configuration
+    pub async fn add_registry(&mut self, config: RegistryConfig) -> Result<()> {
+        let client = self.client_for(config);
+        client.authenticate().await?;
+        self.insert_client(client);
+        Ok(())
+    }
+
+    /// Replace registry `name` with `config`, which may rename it. The new client is
+    /// authenticated first, so a registry whose new settings fail keeps its old ones.
+    pub async fn update_registry(&mut self, name: &str, config: RegistryConfig) -> Result<()> {
+        let client = self.client_for(config);
+        client.authenticate().await?;
+        self.registries.remove(name);
+        self.insert_client(client);
+        Ok(())
+    }
+
+    /// Add a registry through a client made beforehand, so it can be authenticated without
+    /// holding the lock around the manager
+    pub fn insert_client(&mut self, client: RegistryClient) {
+        self.registries.insert(client.config.name.clone(), client);
+    }
+
+    /// Add a registry without contacting it, e.g. one that could not be reached at startup.
+    /// It learns how to authenticate from its first 401.
+    pub fn insert_registry(&mut self, config: RegistryConfig) {
+        let client = self.client_for(config);
+        self.registries.insert(client.config.name.clone(), client);
+    }
+
+    /// Copy `repository:tag` from one registry to the same name on another
+    pub async fn copy_image(
+        &self,
+        source_registry: &str,
+        destination_registry: &str,
+        repository: &str,
+        tag: &str,
+    ) -> Result<ImageCopyResult> {
+        let client = |name: &str| {
+            self.get_registry(name)
+                .ok_or_else(|| Error::NotFound(format!("Registry '{}' not found", name)))
+        };
+        let (source, destination) = (client(source_registry)?, client(destination_registry)?);
+        destination.copy_image_from(source, repository, tag, repository, tag).await
+    }
+
+    /// Get a registry client by name
+    pub fn get_registry(&self, name: &str) -> Option<&RegistryClient> {
+        self.registries.get(name)
+    }
+
+    /// Configurations of all registries, including credentials
+    pub fn registry_configs(&self) -> Vec<RegistryConfig> {
+        self.registries.values().map(|client| client.config.clone()).collect()
+    }
+
+    /// Registries served from this host, such as the local Drift registry
+    pub fn local_registries(&self) -> Vec<String> {
+        self.registries
+            .iter()
+            .filter(|(_, client)| {
+                reqwest::Url::parse(&client.config.url)
+                    .ok()
+                    .and_then(|url| url.host_str().map(|host| matches!(host, "localhost" | "127.0.0.1" | "[::1]")))
+                    .unwrap_or(false)
+            })
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// List all configured registries
+    pub fn list_registries(&self) -> Vec<&str> {
+        self.registries.keys().map(|s| s.as_str()).collect()
+    }
+
+    /// Remove a registry
+    pub fn remove_registry(&mut self, name: &str) -> bool {
+        self.registries.remove(name).is_some()
+    }
+
+    /// Search every registry, or only `registry`, at most `SEARCH_CONCURRENCY` at a time.
+    /// Registries with a search API are searched through it and the others through their
+    /// catalog. A registry that fails or takes longer than `SEARCH_TIMEOUT` is named in the
+    /// warnings and the others' results are still returned.
+    pub async fn search(&self, query: &str, registry: Option<&str>, max_tags: usize) -> RegistrySearch {
+        enum Found {
+            Catalog(Vec<CatalogMatch>),
+            Repositories(Vec<RepositorySearchResult>),
+        }
+
+        let searches: Vec<_> = self
+            .registries
+            .iter()
+            .filter(|(name, _)| registry.is_none_or(|registry| registry == name.as_str()))
+            .map(|(name, client)| async move {
+                let search = async {
+                    if client.search_url().is_some() {
+                        client.search_repositories(query, MAX_SEARCH_RESULTS).await.map(Found::Repositories)
+                    } else {
+                        client.search_catalog(query, max_tags).await.map(Found::Catalog)
+                    }
+                };
+                (name, tokio::time::timeout(SEARCH_TIMEOUT, search).await)
+            })
+            .collect();
+        let mut outcomes: Vec<_> = futures::stream::iter(searches)
+            .buffer_unordered(SEARCH_CONCURRENCY)
+            .collect()
+            .await;
+        outcomes.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut results = RegistrySearch::default();
+        for (name, outcome) in outcomes {
+            match outcome {
+                Ok(Ok(Found::Catalog(matches))) => {
+                    results.catalog.extend(matches.into_iter().map(|found| (name.clone(), found)));
+                }
+                Ok(Ok(Found::Repositories(found))) => {
+                    results.repositories.extend(found.into_iter().map(|found| (name.clone(), found)));
+                }
+                Ok(Err(e)) => {
+                    warn!("Failed to search registry {}: {}", name, e);
+                    results.warnings.push(format!("Search of {} failed: {}", name, e));
+                }
+                Err(_) => {
+                    warn!("Search of registry {} timed out", name);
+                    results
+                        .warnings
+                        .push(format!("{} did not answer within {} s", name, SEARCH_TIMEOUT.as_secs()));
+                }
+            }
+        }
+        results
+    }
+
+    /// Details of the images `references` name, fetched `SEARCH_CONCURRENCY` at a time,
+    /// each with its reference. Images that cannot be inspected are left out.
+    pub async fn image_details(&self, references: &[ImageReference]) -> Vec<(ImageReference, ImageInfo)> {
+        let lookups: Vec<_> = references
+            .iter()
+            .map(|reference| async move {
+                let info = self.image_info(&reference.registry, &reference.repository, &reference.tag, None);
+                let info = tokio::time::timeout(SEARCH_TIMEOUT, info).await.ok()?.ok()?;
+                Some((reference.clone(), info))
+            })
+            .collect();
+        futures::stream::iter(lookups)
+            .buffered(SEARCH_CONCURRENCY)
+            .filter_map(std::future::ready)
+            .collect()
+            .await
+    }
+
+    /// `RegistryClient::get_image_info_for_platform` through the image cache. A cached
+    /// image is only served once a HEAD request shows its tag still names the same
+    /// manifest; images looked up by digest cannot change and skip that request.
+    pub async fn image_info(
+        &self,
+        registry: &str,
+        repository: &str,
+        reference: &str,
+        platform: Option<&str>,
+    ) -> Result<ImageInfo> {
+        let client = self
+            .get_registry(registry)
+            .ok_or_else(|| Error::NotFound(format!("Registry '{}' not found", registry)))?;
+        let key = ImageCacheKey {
+            registry: registry.to_string(),
+            repository: repository.to_string(),
+            reference: reference.to_string(),
+            platform: platform.map(str::to_string),
+        };
+
+        let cached = self.image_cache.lock().unwrap().get(&key);
+        if let Some(cached) = cached {
+            let current = if reference.contains(':') {
+                Ok(Some(reference.to_string()))
+            } else {
+                client.manifest_digest(repository, reference).await
+            };
+            match current {
+                Ok(Some(digest)) if cached.manifest_digest.as_deref() == Some(digest.as_str()) => {
+                    self.image_cache.lock().unwrap().record_hit();
+                    return Ok(cached);
+                }
+                Ok(_) => debug!("{}/{}:{} changed since it was cached", registry, repository, reference),
+                Err(e) => debug!("Failed to check the cached {}/{}:{}: {}", registry, repository, reference, e),
+            }
+        }
+
+        self.image_cache.lock().unwrap().record_miss();
+        let info = client.get_image_info_for_platform(repository, reference, platform).await?;
+        self.image_cache.lock().unwrap().insert(key, info.clone());
+        Ok(info)
+    }
+
+    /// Forget the cached images of `repository` on `registry`
+    pub fn invalidate_image_cache(&self, registry: &str, repository: &str) {
+        self.image_cache.lock().unwrap().invalidate(registry, repository);
+    }
+
+    pub fn image_cache_stats(&self) -> ImageCacheStats {
+        self.image_cache.lock().unwrap().stats()
+    }
+
+    /// Every cached image, when the cache changed since the last call, for persisting it
+    pub fn image_cache_changes(&self) -> Option<Vec<CachedImage>> {
+        self.image_cache.lock().unwrap().take_changes()
+    }
+
+    /// Add cached images persisted by an earlier run
+    pub fn restore_image_cache(&self, cached: Vec<CachedImage>) {
+        self.image_cache.lock().unwrap().restore(cached);
+    }
+}
+
+impl Default for RegistryManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::DriftGcState;
+
+    fn catalog(registry: &str, repository: &str, tags: &[&str]) -> (String, CatalogMatch) {
+        (
+            registry.to_string(),
+            CatalogMatch {
+                repository: repository.to_string(),
+                tags: tags.iter().map(|tag| tag.to_string()).collect(),
+                total_tags: tags.len(),
+            },
+        )
+    }
+
+    fn hub(repository: &str, stars: u64, official: bool) -> (String, RepositorySearchResult) {
+        (
+            "docker.io".to_string(),
+            RepositorySearchResult {
+                repository: repository.to_string(),
+                description: Some(format!("{} image", repository)),
+                stars,
+                official,
+            },
+        )
+    }
+
+    type Corpus = (Vec<(String, CatalogMatch)>, Vec<(String, RepositorySearchResult)>);
+
+    /// Registries and repositories every ranking test searches
+    fn corpus() -> Corpus {
+        let catalog = vec![
+            catalog("drift", "library/nginx", &["latest", "1.25", "1.24"]),
+            catalog("drift", "team/nginx-exporter", &["v1"]),
+            catalog("ghcr", "library/nginx", &["1.25-alpine", "1.23"]),
+            catalog("ghcr", "acme/my-nginx", &["main"]),
+            catalog("ghcr", "team/web", &["latest"]),
+        ];
+        let repositories = vec![hub("library/nginx", 19000, true), hub("bitnami/nginx", 150, false)];
+        (catalog, repositories)
+    }
+
+    fn rank(query: &str, local: &[&str], max_tags: usize) -> Vec<ImageSearchGroup> {
+        let (catalog, repositories) = corpus();
+        let local: Vec<String> = local.iter().map(|name| name.to_string()).collect();
+        rank_search_results(query, &catalog, &repositories, &local, max_tags)
+    }
+
+    #[test]
+    fn match_scores_prefer_exact_then_prefix_then_substring() {
+        assert_eq!(match_score("library/nginx", "nginx"), 300);
+        assert_eq!(match_score("team/nginx-exporter", "NGINX"), 200);
+        assert_eq!(match_score("acme/my-nginx", "nginx"), 100);
+        assert_eq!(match_score("team/web", "nginx"), 0);
+    }
+
+    #[test]
+    fn groups_are_ranked_by_match_and_boosts() {
+        let groups = rank("nginx", &[], 10);
+        let order: Vec<(&str, u32)> = groups.iter().map(|g| (g.repository.as_str(), g.score)).collect();
+        assert_eq!(
+            order,
+            vec![
+                ("nginx", 350),
+                ("bitnami/nginx", 300),
+                ("team/nginx-exporter", 200),
+                ("acme/my-nginx", 100),
+                ("team/web", 0),
+            ]
+        );
+
+        let nginx = &groups[0];
+        assert!(nginx.official);
+        assert_eq!(nginx.stars, Some(19000));
+        assert_eq!(nginx.latest.as_ref().map(|r| r.repository.as_str()), Some("library/nginx"));
+    }
+
+    #[test]
+    fn local_registries_are_boosted() {
+        let groups = rank("nginx", &["ghcr"], 10);
+        let my_nginx = groups.iter().find(|g| g.repository == "acme/my-nginx").unwrap();
+        assert_eq!(my_nginx.score, 125);
+        let exporter = groups.iter().find(|g| g.repository == "team/nginx-exporter").unwrap();
+        assert_eq!(exporter.score, 200);
+    }
+
+    #[test]
+    fn tags_interleave_registries_up_to_the_cap() {
+        let groups = rank("nginx", &[], 3);
+        let nginx = &groups[0];
+        let tags: Vec<(&str, &str)> = nginx.tags.iter().map(|r| (r.registry.as_str(), r.tag.as_str())).collect();
+        assert_eq!(tags, vec![("drift", "latest"), ("ghcr", "1.25-alpine"), ("drift", "1.25")]);
+        assert_eq!(nginx.total_tags, 5);
+        assert_eq!(nginx.more_tags, 2);
+    }
+
+    #[test]
+    fn inspected_tags_are_merged_by_digest() {
+        let mut groups = rank("nginx", &[], 10);
+        let reference = |registry: &str, repository: &str, tag: &str| ImageReference {
+            registry: registry.to_string(),
+            repository: repository.to_string(),
+            tag: tag.to_string(),
+        };
+        let info = |reference: &ImageReference, digest: &str, manifest_digest: Option<&str>, days_old: i64| ImageInfo {
+            repository: reference.repository.clone(),
+            tag: reference.tag.clone(),
+            digest: digest.to_string(),
+            manifest_digest: manifest_digest.map(str::to_string),
+            size: 1000,
+            created: chrono::Utc::now() - chrono::Duration::days(days_old),
+            author: None,
+            layers: Vec::new(),
+            platform: None,
+            platforms: Vec::new(),
+        };
+        let latest = reference("drift", "library/nginx", "latest");
+        let pinned = reference("drift", "library/nginx", "1.25");
+        let mirrored = reference("ghcr", "library/nginx", "1.25-alpine");
+        let old = reference("drift", "library/nginx", "1.24");
+        let unlisted = reference("ghcr", "library/nginx", "1.22");
+        let details = vec![
+            (latest.clone(), info(&latest, "sha256:config", Some("sha256:current"), 2)),
+            (old.clone(), info(&old, "sha256:old", None, 30)),
+            (mirrored.clone(), info(&mirrored, "sha256:alpine", Some("sha256:alpine-index"), 1)),
+            // The same manifest under another tag, whatever config digest was reported
+            (pinned.clone(), info(&pinned, "sha256:other-config", Some("sha256:current"), 2)),
+            (unlisted.clone(), info(&unlisted, "sha256:unlisted", None, 90)),
+        ];
+
+        merge_image_details(&mut groups, details);
+        let nginx = &groups[0];
+        let images: Vec<(&str, Vec<&str>)> = nginx
+            .images
+            .iter()
+            .map(|image| (image.digest.as_str(), image.references.iter().map(|r| r.tag.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            images,
+            vec![
+                ("sha256:alpine-index", vec!["1.25-alpine"]),
+                ("sha256:current", vec!["latest", "1.25"]),
+                ("sha256:old", vec!["1.24"]),
+            ]
+        );
+        // Merging leaves the listed tags and counts alone
+        assert_eq!((nginx.tags.len(), nginx.total_tags, nginx.more_tags), (5, 5, 0));
+        assert!(groups[1..].iter().all(|group| group.images.is_empty()));
+    }
+
+    #[test]
+    fn newest_tags_put_latest_first() {
+        let tags: Vec<String> = ["1.0", "1.1", "latest", "1.2"].iter().map(|tag| tag.to_string()).collect();
+        assert_eq!(newest_tags(&tags, 3), vec!["latest", "1.2", "1.1"]);
+        assert_eq!(newest_tags(&tags, 0), Vec::<String>::new());
+
+        // Version order, not name order
+        let tags: Vec<String> = ["1.9", "1.10"].iter().map(|tag| tag.to_string()).collect();
+        assert_eq!(newest_tags(&tags, 2), vec!["1.10", "1.9"]);
+    }
+
+    /// A request seen by `stub_registry`
+    #[derive(Debug, Clone)]
+    struct StubRequest {
+        method: String,
+        url: reqwest::Url,
+        authorization: Option<String>,
+        accept: Option<String>,
+        content_type: Option<String>,
+        user_agent: Option<String>,
+        body: Vec<u8>,
+    }
+
+    impl StubRequest {
+        fn query(&self, name: &str) -> Option<String> {
+            self.url.query_pairs().find(|(key, _)| key == name).map(|(_, value)| value.into_owned())
+        }
+    }
+
+    /// Status, extra headers and JSON body
+    type StubResponse = (u16, Vec<(&'static str, String)>, String);
+
+    /// A registry on a local port answering each request with `handler`, given the stub's
+    /// own URL too. Returns that URL and every request so far.
+    async fn stub_registry<F>(handler: F) -> (String, Arc<Mutex<Vec<StubRequest>>>)
+    where
+        F: Fn(&str, &StubRequest) -> StubResponse + Send + Sync + 'static,
+    {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        let base = url.clone();
+        let handler = Arc::new(handler);
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut head = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => head.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let end = head.windows(4).position(|w| w == b"\r\n\r\n").map_or(head.len(), |end| end + 4);
+                let mut body = head.split_off(end);
+                let head = String::from_utf8_lossy(&head).into_owned();
+                let mut lines = head.lines();
+                let Some((method, target)) = lines.next().and_then(|line| {
+                    let mut parts = line.split(' ');
+                    Some((parts.next()?.to_string(), parts.next()?))
+                }) else {
+                    continue;
+                };
+                let headers: Vec<(&str, &str)> = lines.filter_map(|line| line.split_once(':')).collect();
+                let header = |wanted: &str| {
+                    headers
+                        .iter()
+                        .find(|(name, _)| name.eq_ignore_ascii_case(wanted))
+                        .map(|(_, value)| value.trim().to_string())
+                };
+                let length: usize = header("content-length").and_then(|length| length.parse().ok()).unwrap_or(0);
+                while body.len() < length {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => body.extend_from_slice(&buf[..n]),
+                    }
+                }
+                // The client gave up partway through the body
+                if body.len() < length {
+                    continue;
+                }
+                // Absolute when the stub is used as a proxy
+                let url = reqwest::Url::parse(target).or_else(|_| reqwest::Url::parse(&format!("{}{}", base, target)));
+                let request = StubRequest {
+                    method,
+                    url: url.unwrap(),
+                    authorization: header("authorization"),
+                    accept: header("accept"),
+                    content_type: header("content-type"),
+                    user_agent: header("user-agent"),
+                    body,
+                };
+                seen.lock().unwrap().push(request.clone());
+
+                let (status, headers, body) = handler(&base, &request);
+                let mut response = format!("HTTP/1.1 {} Stub\r\n", status);
+                if !headers.iter().any(|(name, _)| name.eq_ignore_ascii_case("content-type")) {
+                    response.push_str("Content-Type: application/json\r\n");
+                }
+                for (name, value) in headers {
+                    response.push_str(&format!("{}: {}\r\n", name, value));
+                }
+                response.push_str(&format!("Content-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body));
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            }
+        });
+        (url, requests)
+    }
+
+    fn ok(body: serde_json::Value) -> StubResponse {
+        (200, Vec::new(), body.to_string())
+    }
+
+    fn client(url: &str, configure: impl FnOnce(&mut RegistryConfig)) -> RegistryClient {
+        let mut config = RegistryConfig {
+            name: "stub".to_string(),
+            url: url.to_string(),
+            username: None,
+            password: None,
+            // The stubs serve plain HTTP
+            insecure: true,
+            page_size: None,
+            max_list_entries: None,
+            search_url: None,
+            ca_cert_path: None,
+        };
+        configure(&mut config);
+        RegistryClient::new(config)
+    }
+
+    const REPOSITORIES: [&str; 5] = ["a/one", "a/two", "b/three", "b/four", "c/five"];
+
+    /// The page of `REPOSITORIES` after `last`, with at most `n` entries
+    fn catalog_page(request: &StubRequest) -> Vec<&'static str> {
+        let n: usize = request.query("n").unwrap().parse().unwrap();
+        let start = match request.query("last") {
+            Some(last) => REPOSITORIES.iter().position(|repo| *repo == last).unwrap() + 1,
+            None => 0,
+        };
+        REPOSITORIES.iter().skip(start).take(n).copied().collect()
+    }
+
+    #[tokio::test]
+    async fn catalog_pages_are_followed_through_link_headers() {
+        let (url, requests) = stub_registry(|_, request| {
+            let page = catalog_page(request);
+            let mut headers = Vec::new();
+            if let Some(last) = page.last().filter(|last| **last != "c/five") {
+                headers.push(("Link", format!("</v2/_catalog?last={}&n=2>; rel=\"next\"", last)));
+            }
+            ok(serde_json::json!({ "repositories": page }))
+        })
+        .await;
+
+        let repositories = client(&url, |config| config.page_size = Some(2))
+            .list_repositories()
+            .await
+            .unwrap();
+        assert_eq!(repositories, REPOSITORIES);
+
+        let lasts: Vec<Option<String>> = requests.lock().unwrap().iter().map(|r| r.query("last")).collect();
+        assert_eq!(lasts, [None, Some("a/two".to_string()), Some("b/four".to_string())]);
+        assert!(requests.lock().unwrap().iter().all(|r| r.query("n").as_deref() == Some("2")));
+    }
+
+    #[tokio::test]
+    async fn full_pages_without_link_continue_from_their_last_entry() {
+        let (url, requests) = stub_registry(|_, request| ok(serde_json::json!({ "repositories": catalog_page(request) }))).await;
+
+        let repositories = client(&url, |config| config.page_size = Some(2))
+            .list_repositories()
+            .await
+            .unwrap();
+        assert_eq!(repositories, REPOSITORIES);
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn listings_stop_at_the_cap_and_at_repeated_pages() {
+        let (url, _) = stub_registry(|_, request| ok(serde_json::json!({ "repositories": catalog_page(request) }))).await;
+        let capped = client(&url, |config| {
+            config.page_size = Some(2);
+            config.max_list_entries = Some(3);
+        });
+        assert_eq!(capped.list_repositories().await.unwrap(), ["a/one", "a/two", "b/three"]);
+
+        // A registry ignoring `last` sends the first page forever
+        let (url, requests) = stub_registry(|_, _| {
+            let headers = vec![("Link", "</v2/_catalog?last=a%2Ftwo&n=2>; rel=\"next\"".to_string())];
+            (200, headers, serde_json::json!({ "repositories": ["a/one", "a/two"] }).to_string())
+        })
+        .await;
+        let repositories = client(&url, |config| config.page_size = Some(2))
+            .list_repositories()
+            .await
+            .unwrap();
+        assert_eq!(repositories, ["a/one", "a/two"]);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn single_pages_report_where_the_next_starts() {
+        let (url, _) = stub_registry(|_, request| ok(serde_json::json!({ "repositories": catalog_page(request) }))).await;
+        let client = client(&url, |_| {});
+
+        let page = client.list_repositories_page(Some(2), Some("a/two")).await.unwrap();
+        assert_eq!(page.repositories, ["b/three", "b/four"]);
+        assert_eq!(page.next.as_deref(), Some("b/four"));
+
+        let page = client.list_repositories_page(Some(2), Some("b/four")).await.unwrap();
+        assert_eq!(page.repositories, ["c/five"]);
+        assert_eq!(page.next, None);
+    }
+
+    #[tokio::test]
+    async fn tag_lists_are_paged_and_null_means_none() {
+        let (url, requests) = stub_registry(|_, request| match request.url.path() {
+            "/v2/a/one/tags/list" => {
+                let tags = match request.query("last").as_deref() {
+                    None => serde_json::json!(["1.0", "1.1"]),
+                    Some("1.1") => serde_json::json!(["2.0"]),
+                    Some(other) => panic!("unexpected last {}", other),
+                };
+                ok(serde_json::json!({ "name": "a/one", "tags": tags }))
+            }
+            _ => ok(serde_json::json!({ "name": "a/two", "tags": null })),
+        })
+        .await;
+        let client = client(&url, |config| config.page_size = Some(2));
+
+        assert_eq!(client.list_tags("a/one").await.unwrap(), ["1.0", "1.1", "2.0"]);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        assert!(client.list_tags("a/two").await.unwrap().is_empty());
+    }
+
+    /// A registry with a token service at `/token`. Tokens are `<scope>#<n>`, numbered from
+    /// 1 as issued, and live `expires_in` seconds; the registry only takes tokens for the
+    /// scope of the request numbered at least `valid_from`, so raising it revokes the older
+    /// ones.
+    async fn token_registry(
+        expires_in: u64,
+        valid_from: Arc<std::sync::atomic::AtomicUsize>,
+    ) -> (String, Arc<Mutex<Vec<StubRequest>>>) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let issued = AtomicUsize::new(0);
+        stub_registry(move |base, request| {
+            let path = request.url.path();
+            if path == "/token" {
+                let number = issued.fetch_add(1, Ordering::SeqCst) + 1;
+                let scope = request.query("scope").unwrap();
+                return ok(serde_json::json!({ "token": format!("{}#{}", scope, number), "expires_in": expires_in }));
+            }
+
+            let repository = path.strip_prefix("/v2/").and_then(|rest| rest.strip_suffix("/tags/list"));
+            let scope = match repository {
+                Some(repository) => pull_scope(repository),
+                None => CATALOG_SCOPE.to_string(),
+            };
+            let accepted = request
+                .authorization
+                .as_deref()
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .and_then(|token| token.split_once('#'))
+                .is_some_and(|(token_scope, number)| {
+                    token_scope == scope && number.parse::<usize>().unwrap() >= valid_from.load(Ordering::SeqCst)
+                });
+            if !accepted {
+                let challenge = format!("Bearer realm=\"{}/token\",service=\"stub\",scope=\"{}\"", base, scope);
+                return (401, vec![("WWW-Authenticate", challenge)], r#"{"errors":[]}"#.to_string());
+            }
+            match repository {
+                Some(repository) => ok(serde_json::json!({ "name": repository, "tags": ["latest"] })),
+                None => ok(serde_json::json!({ "repositories": ["team/app"] })),
+            }
+        })
+        .await
+    }
+
+    fn token_requests(requests: &Mutex<Vec<StubRequest>>) -> Vec<StubRequest> {
+        requests.lock().unwrap().iter().filter(|r| r.url.path() == "/token").cloned().collect()
+    }
+
+    fn with_credentials(config: &mut RegistryConfig) {
+        config.username = Some("robot".to_string());
+        config.password = Some("secret".to_string());
+    }
+
+    #[tokio::test]
+    async fn tokens_are_requested_per_scope_and_reused() {
+        let (url, requests) = token_registry(300, Arc::default()).await;
+        let client = client(&url, with_credentials);
+
+        assert_eq!(client.list_tags("team/app").await.unwrap(), ["latest"]);
+        assert_eq!(client.list_tags("team/app").await.unwrap(), ["latest"]);
+        assert_eq!(client.list_repositories().await.unwrap(), ["team/app"]);
+
+        let tokens = token_requests(&requests);
+        let scopes: Vec<String> = tokens.iter().map(|r| r.query("scope").unwrap()).collect();
+        assert_eq!(scopes, ["repository:team/app:pull", "registry:catalog:*"]);
+        assert!(tokens.iter().all(|r| r.query("service").as_deref() == Some("stub")));
+        assert!(tokens.iter().all(|r| r.authorization.as_deref().is_some_and(|a| a.starts_with("Basic "))));
+    }
+
+    #[tokio::test]
+    async fn expired_tokens_are_renewed() {
+        // Within the expiry margin, so the token is stale as soon as it arrives
+        let (url, requests) = token_registry(TOKEN_EXPIRY_MARGIN_SECS, Arc::default()).await;
+        let client = client(&url, with_credentials);
+
+        client.list_tags("team/app").await.unwrap();
+        client.list_tags("team/app").await.unwrap();
+        assert_eq!(token_requests(&requests).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn anonymous_tokens_serve_public_repositories() {
+        let (url, requests) = token_registry(300, Arc::default()).await;
+        let client = client(&url, |_| {});
+
+        assert_eq!(client.list_tags("library/nginx").await.unwrap(), ["latest"]);
+        let tokens = token_requests(&requests);
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].authorization, None);
+    }
+
+    #[tokio::test]
+    async fn rejected_tokens_are_renewed_and_retried_once() {
+        use std::sync::atomic::Ordering;
+
+        let valid_from = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let (url, requests) = token_registry(300, valid_from.clone()).await;
+        let client = client(&url, with_credentials);
+        client.list_tags("team/app").await.unwrap();
+
+        // The cached token is revoked: one 401, a new token, then success
+        valid_from.store(2, Ordering::SeqCst);
+        requests.lock().unwrap().clear();
+        assert_eq!(client.list_tags("team/app").await.unwrap(), ["latest"]);
+        let paths: Vec<String> = requests.lock().unwrap().iter().map(|r| r.url.path().to_string()).collect();
+        assert_eq!(paths, ["/v2/team/app/tags/list", "/token", "/v2/team/app/tags/list"]);
+
+        // A registry refusing every token gets one retry, not a loop
+        valid_from.store(usize::MAX, Ordering::SeqCst);
+        requests.lock().unwrap().clear();
+        let error = client.list_tags("team/app").await.unwrap_err();
+        assert!(error.to_string().contains("401"));
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    fn image_manifest(config: &str, layers: &[u64]) -> serde_json::Value {
+        serde_json::json!({
+            "schemaVersion": 2,
+            "mediaType": "application/vnd.docker.distribution.manifest.v2+json",
+            "config": { "mediaType": "application/vnd.docker.container.image.v1+json", "size": 100, "digest": config },
+            "layers": layers.iter().enumerate().map(|(index, size)| serde_json::json!({
+                "mediaType": "application/vnd.docker.image.rootfs.diff.tar.gzip",
+                "size": size,
+                "digest": format!("sha256:{}-layer{}", config.trim_start_matches("sha256:"), index),
+            })).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Manifest list or OCI index for amd64 and arm64/v8, plus a build attestation
+    fn image_index(media_type: &str, entry_type: &str) -> serde_json::Value {
+        let entry = |digest: &str, platform: serde_json::Value| {
+            serde_json::json!({ "mediaType": entry_type, "size": 500, "digest": digest, "platform": platform })
+        };
+        serde_json::json!({
+            "schemaVersion": 2,
+            "mediaType": media_type,
+            "manifests": [
+                entry("sha256:amd64", serde_json::json!({ "os": "linux", "architecture": "amd64" })),
+                entry("sha256:arm64", serde_json::json!({ "os": "linux", "architecture": "arm64", "variant": "v8" })),
+                entry("sha256:attestation", serde_json::json!({ "os": "unknown", "architecture": "unknown" })),
+            ],
+        })
+    }
+
+    /// Repository `app` with a single-platform `single`, a Docker manifest list `list` and
+    /// an OCI index `oci`
+    async fn manifest_registry() -> (String, Arc<Mutex<Vec<StubRequest>>>) {
+        stub_registry(|_, request| {
+            let body = match request.url.path().strip_prefix("/v2/app/") {
+                Some("manifests/single") => image_manifest("sha256:single-config", &[10, 20]),
+                Some("manifests/list") => image_index(
+                    "application/vnd.docker.distribution.manifest.list.v2+json",
+                    "application/vnd.docker.distribution.manifest.v2+json",
+                ),
+                Some("manifests/oci") => image_index(
+                    "application/vnd.oci.image.index.v1+json",
+                    "application/vnd.oci.image.manifest.v1+json",
+                ),
+                Some("manifests/sha256:amd64") => image_manifest("sha256:amd64-config", &[100]),
+                Some("manifests/sha256:arm64") => image_manifest("sha256:arm64-config", &[200, 50]),
+                Some(blob) if blob.starts_with("blobs/") => {
+                    let architecture = if blob.contains("arm64") { "arm64" } else { "amd64" };
+                    serde_json::json!({
+                        "created": "2024-05-01T12:00:00Z",
+                        "author": "builder",
+                        "os": "linux",
+                        "architecture": architecture,
+                    })
+                }
+                _ => return (404, Vec::new(), r#"{"errors":[]}"#.to_string()),
+            };
+            ok(body)
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn single_manifests_are_read_as_they_are() {
+        let (url, requests) = manifest_registry().await;
+        let info = client(&url, |_| {}).get_image_info("app", "single").await.unwrap();
+
+        assert_eq!(info.digest, "sha256:single-config");
+        assert_eq!(info.size, 30);
+        assert_eq!(info.layers.len(), 2);
+        assert_eq!(info.author.as_deref(), Some("builder"));
+        assert_eq!(info.platform.as_deref(), Some("linux/amd64"));
+        assert_eq!(info.platforms, ["linux/amd64"]);
+
+        let accept = requests.lock().unwrap()[0].accept.clone().unwrap();
+        for media_type in [
+            "application/vnd.docker.distribution.manifest.v2+json",
+            "application/vnd.docker.distribution.manifest.list.v2+json",
+            "application/vnd.oci.image.manifest.v1+json",
+            "application/vnd.oci.image.index.v1+json",
+        ] {
+            assert!(accept.contains(media_type), "{} missing from {}", media_type, accept);
+        }
+
+        let error = client(&url, |_| {})
+            .get_image_info_for_platform("app", "single", Some("linux/arm64"))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("only linux/amd64"));
+    }
+
+    #[tokio::test]
+    async fn manifest_lists_resolve_to_the_default_platform() {
+        let (url, requests) = manifest_registry().await;
+        let info = client(&url, |_| {}).get_image_info("app", "list").await.unwrap();
+
+        assert_eq!(info.digest, "sha256:amd64-config");
+        assert_eq!(info.size, 100);
+        assert_eq!(info.platform.as_deref(), Some("linux/amd64"));
+        assert_eq!(info.platforms, ["linux/amd64", "linux/arm64/v8"]);
+        let paths: Vec<String> = requests.lock().unwrap().iter().map(|r| r.url.path().to_string()).collect();
+        assert_eq!(
+            paths,
+            ["/v2/app/manifests/list", "/v2/app/manifests/sha256:amd64", "/v2/app/blobs/sha256:amd64-config"]
+        );
+    }
+
+    #[tokio::test]
+    async fn oci_indexes_resolve_to_the_requested_platform() {
+        let (url, _) = manifest_registry().await;
+        let client = client(&url, |_| {});
+
+        let info = client.get_image_info_for_platform("app", "oci", Some("linux/arm64")).await.unwrap();
+        assert_eq!(info.digest, "sha256:arm64-config");
+        assert_eq!(info.size, 250);
+        assert_eq!(info.platform.as_deref(), Some("linux/arm64/v8"));
+        assert_eq!(info.platforms, ["linux/amd64", "linux/arm64/v8"]);
+
+        let manifest = client.get_manifest("app", "oci").await.unwrap();
+        assert_eq!(manifest.config.digest, "sha256:amd64-config");
+
+        let error = client
+            .get_image_info_for_platform("app", "oci", Some("windows/amd64"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "app:oci has no windows/amd64 image, only linux/amd64, linux/arm64/v8");
+    }
+
+    #[tokio::test]
+    async fn search_finishes_despite_a_hanging_registry() {
+        let (good, _) = stub_registry(|_, request| match request.url.path() {
+            "/v2/_catalog" => ok(serde_json::json!({ "repositories": ["team/nginx", "team/web"] })),
+            _ => ok(serde_json::json!({ "name": "team/nginx", "tags": ["1.24", "1.25", "latest"] })),
+        })
+        .await;
+        // Accepts connections and never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let hanging = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                held.push(stream);
+            }
+        });
+
+        let mut manager = RegistryManager::new();
+        manager.insert_client(client(&good, |config| config.name = "good".to_string()));
+        manager.insert_client(client(&hanging, |config| config.name = "hanging".to_string()));
+
+        let started = Instant::now();
+        let results = manager.search("nginx", None, 2).await;
+        assert!(started.elapsed() < SEARCH_TIMEOUT + Duration::from_secs(5));
+
+        assert_eq!(results.catalog.len(), 1);
+        let (registry, found) = &results.catalog[0];
+        assert_eq!(registry, "good");
+        assert_eq!(found.repository, "team/nginx");
+        assert_eq!(found.tags, ["latest", "1.25"]);
+        assert_eq!(found.total_tags, 3);
+        assert_eq!(results.warnings, ["hanging did not answer within 10 s"]);
+    }
+
+    #[tokio::test]
+    async fn updating_a_registry_replaces_it_only_once_authenticated() {
+        let (url, _) = stub_registry(|_, _| ok(serde_json::json!({}))).await;
+        let mut manager = RegistryManager::new();
+        manager.insert_client(client(&url, |config| config.name = "old".to_string()));
+
+        let unreachable = client("http://127.0.0.1:1", |config| config.name = "new".to_string());
+        assert!(manager.update_registry("old", unreachable.config().clone()).await.is_err());
+        assert_eq!(manager.list_registries(), ["old"]);
+
+        let renamed = client(&url, |config| {
+            config.name = "new".to_string();
+            config.page_size = Some(7);
+        });
+        manager.update_registry("old", renamed.config().clone()).await.unwrap();
+        assert_eq!(manager.list_registries(), ["new"]);
+        assert_eq!(manager.get_registry("new").unwrap().config().page_size, Some(7));
+    }
+
+    /// Repository `app` whose tag `v1` names `sha256:v1`, deleting with `delete_status`
+    async fn deleting_registry(delete_status: u16) -> (String, Arc<Mutex<Vec<StubRequest>>>) {
+        stub_registry(move |_, request| match (request.method.as_str(), request.url.path()) {
+            ("HEAD", "/v2/app/manifests/v1") => (200, vec![("Docker-Content-Digest", "sha256:v1".to_string())], String::new()),
+            ("DELETE", "/v2/app/manifests/sha256:v1") => (delete_status, Vec::new(), String::new()),
+            _ => (404, Vec::new(), r#"{"errors":[]}"#.to_string()),
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn images_are_deleted_by_the_digest_their_tag_names() {
+        let (url, requests) = deleting_registry(202).await;
+        let digest = client(&url, |_| {}).delete_image("app", "v1").await.unwrap();
+        assert_eq!(digest, "sha256:v1");
+
+        let requests = requests.lock().unwrap();
+        let seen: Vec<(&str, &str)> = requests.iter().map(|r| (r.method.as_str(), r.url.path())).collect();
+        assert_eq!(seen, [("HEAD", "/v2/app/manifests/v1"), ("DELETE", "/v2/app/manifests/sha256:v1")]);
+        assert!(requests[0].accept.as_deref().unwrap().contains("application/vnd.oci.image.index.v1+json"));
+    }
+
+    #[tokio::test]
+    async fn failed_deletes_say_why() {
+        let (url, _) = deleting_registry(405).await;
+        let error = client(&url, |_| {}).delete_image("app", "v1").await.unwrap_err();
+        assert!(matches!(error, DeleteImageError::Disabled(_)));
+
+        let error = client(&url, |_| {}).delete_image("app", "v2").await.unwrap_err();
+        assert!(matches!(error, DeleteImageError::NotFound(message) if message == "app:v2 does not exist"));
+
+        let (url, _) = deleting_registry(404).await;
+        let error = client(&url, |_| {}).delete_image("app", "v1").await.unwrap_err();
+        assert!(matches!(error, DeleteImageError::NotFound(_)));
+
+        // Docker Hub is refused before any request is made
+        let hub = client("https://registry-1.docker.io", |config| config.name = "hub".to_string());
+        let error = hub.delete_image("library/nginx", "latest").await.unwrap_err();
+        assert!(matches!(error, DeleteImageError::Unsupported(_)));
+    }
+
+    /// Blobs and manifests of an in-memory registry, keyed `repository@digest` and
+    /// `repository:reference`
+    #[derive(Default)]
+    struct Stored {
+        blobs: HashMap<String, String>,
+        manifests: HashMap<String, (String, String)>,
+    }
+
+    fn sha256(content: &str) -> String {
+        format!("sha256:{:x}", Sha256::digest(content.as_bytes()))
+    }
+
+    impl Stored {
+        fn add_blob(&mut self, repository: &str, content: &str) -> serde_json::Value {
+            let digest = sha256(content);
+            self.blobs.insert(format!("{}@{}", repository, digest), content.to_string());
+            serde_json::json!({ "mediaType": "application/octet-stream", "size": content.len(), "digest": digest })
+        }
+
+        /// Store `manifest` under its digest and `reference`, returning the digest
+        fn add_manifest(&mut self, repository: &str, reference: &str, manifest: serde_json::Value) -> String {
+            let body = manifest.to_string();
+            let digest = sha256(&body);
+            let media_type = manifest["mediaType"].as_str().unwrap().to_string();
+            for key in [reference, &digest] {
+                self.manifests.insert(format!("{}:{}", repository, key), (media_type.clone(), body.clone()));
+            }
+            digest
+        }
+
+        /// An image of a config and `layers` in `repository`, returning its manifest digest
+        fn add_image(&mut self, repository: &str, reference: &str, architecture: &str, layers: &[&str]) -> String {
+            let config = format!(r#"{{"architecture":"{}","os":"linux"}}"#, architecture);
+            let manifest = serde_json::json!({
+                "schemaVersion": 2,
+                "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                "config": self.add_blob(repository, &config),
+                "layers": layers.iter().map(|layer| self.add_blob(repository, layer)).collect::<Vec<_>>(),
+            });
+            self.add_manifest(repository, reference, manifest)
+        }
+    }
+
+    /// A registry keeping what is pushed to it, enough of the distribution API for listings,
+    /// pulls, pushes and copies
+    async fn memory_registry() -> (String, Arc<Mutex<Stored>>, Arc<Mutex<Vec<StubRequest>>>) {
+        let stored = Arc::new(Mutex::new(Stored::default()));
+        let store = stored.clone();
+        let (url, requests) = stub_registry(move |_, request| store.lock().unwrap().serve(request)).await;
+        (url, stored, requests)
+    }
+
+    impl Stored {
+        /// Answer `request` from what is stored, keeping whatever is pushed
+        fn serve(&mut self, request: &StubRequest) -> StubResponse {
+            let stored = self;
+            let path = request.url.path().strip_prefix("/v2/").unwrap_or_default();
+            let not_found = (404, Vec::new(), r#"{"errors":[]}"#.to_string());
+            if let Some((repository, upload)) = path.split_once("/blobs/uploads/") {
+                return match request.method.as_str() {
+                    "POST" => match (request.query("mount"), request.query("from")) {
+                        (Some(digest), Some(from)) if stored.blobs.contains_key(&format!("{}@{}", from, digest)) => {
+                            let content = stored.blobs[&format!("{}@{}", from, digest)].clone();
+                            stored.blobs.insert(format!("{}@{}", repository, digest), content);
+                            (201, Vec::new(), String::new())
+                        }
+                        _ => (202, vec![("Location", format!("/v2/{}/blobs/uploads/session", repository))], String::new()),
+                    },
+                    _ => {
+                        assert_eq!(upload, "session");
+                        let content = String::from_utf8(request.body.clone()).unwrap();
+                        let digest = request.query("digest").unwrap();
+                        assert_eq!(sha256(&content), digest, "uploaded blobs match their digest");
+                        stored.blobs.insert(format!("{}@{}", repository, digest), content);
+                        (201, Vec::new(), String::new())
+                    }
+                };
+            }
+            if let Some((repository, digest)) = path.split_once("/blobs/") {
+                return match stored.blobs.get(&format!("{}@{}", repository, digest)) {
+                    Some(content) => (200, Vec::new(), content.clone()),
+                    None => not_found,
+                };
+            }
+            if path == "_catalog" || path.ends_with("/tags/list") {
+                let mut tagged: Vec<(&str, &str)> = stored
+                    .manifests
+                    .keys()
+                    .filter_map(|key| key.split_once(':'))
+                    .filter(|(_, reference)| !reference.starts_with("sha256:"))
+                    .collect();
+                tagged.sort();
+                let listing = match path.strip_suffix("/tags/list") {
+                    Some(repository) => serde_json::json!({
+                        "name": repository,
+                        "tags": tagged.iter().filter(|(name, _)| *name == repository).map(|(_, tag)| tag).collect::<Vec<_>>(),
+                    }),
+                    None => {
+                        let mut repositories: Vec<&str> = tagged.iter().map(|(name, _)| *name).collect();
+                        repositories.dedup();
+                        serde_json::json!({ "repositories": repositories })
+                    }
+                };
+                return (200, Vec::new(), listing.to_string());
+            }
+            let Some((repository, reference)) = path.split_once("/manifests/") else {
+                return not_found;
+            };
+            if request.method == "PUT" {
+                let body = String::from_utf8(request.body.clone()).unwrap();
+                let media_type = serde_json::from_str::<serde_json::Value>(&body).unwrap()["mediaType"].clone();
+                let digest = sha256(&body);
+                for key in [reference, &digest] {
+                    stored.manifests.insert(
+                        format!("{}:{}", repository, key),
+                        (media_type.as_str().unwrap().to_string(), body.clone()),
+                    );
+                }
+                return (201, vec![("Docker-Content-Digest", digest)], String::new());
+            }
+            match stored.manifests.get(&format!("{}:{}", repository, reference)) {
+                Some((media_type, body)) => {
+                    (200, vec![("Content-Type", media_type.clone()), ("Docker-Content-Digest", sha256(body))], body.clone())
+                }
+                None => not_found,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn stats_count_each_shared_layer_once() {
+        let (url, stored, _) = memory_registry().await;
+        {
+            let mut stored = stored.lock().unwrap();
+            stored.add_image("app", "v1", "amd64", &["shared", "a"]);
+            // The same image under a second tag
+            stored.add_image("app", "v2", "amd64", &["shared", "a"]);
+            let entry = |digest: String, architecture: &str| {
+                serde_json::json!({
+                    "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                    "size": 500,
+                    "digest": digest,
+                    "platform": { "os": "linux", "architecture": architecture },
+                })
+            };
+            let amd64 = stored.add_image("multi", "amd64", "amd64", &["shared", "amd64 layer"]);
+            let arm64 = stored.add_image("multi", "arm64", "arm64", &["shared", "arm64 layer"]);
+            let index = serde_json::json!({
+                "schemaVersion": 2,
+                "mediaType": "application/vnd.oci.image.index.v1+json",
+                "manifests": [entry(amd64, "amd64"), entry(arm64, "arm64")],
+            });
+            stored.add_manifest("multi", "latest", index);
+            stored
+                .manifests
+                .insert("broken:v1".to_string(), ("application/vnd.oci.image.manifest.v1+json".to_string(), "{".to_string()));
+        }
+
+        let mut reported = Vec::new();
+        let stats = client(&url, |_| {}).stats(|done, total| reported.push((done, total))).await.unwrap();
+        assert_eq!(
+            (stats.repositories, stats.tags, stats.unique_layers, stats.total_bytes, stats.skipped_repositories),
+            // `broken` is skipped; `shared`, `a` and both platform layers remain
+            (3, 5, 4, "shared".len() as u64 + 1 + 2 * "amd64 layer".len() as u64, 1)
+        );
+        assert_eq!(reported, [(0, 3), (1, 3), (2, 3), (3, 3)]);
+    }
+
+    #[tokio::test]
+    async fn exported_images_import_with_the_same_digests() {
+        let (source_url, source, _) = memory_registry().await;
+        // Larger than imports hold in memory, so it is uploaded as it is read
+        let large = "x".repeat(IMPORT_BUFFER_LIMIT as usize + 1);
+        let (index_digest, arm64) = {
+            let mut source = source.lock().unwrap();
+            let entry = |digest: &str, architecture: &str| {
+                serde_json::json!({
+                    "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                    "size": 500,
+                    "digest": digest,
+                    "platform": { "os": "linux", "architecture": architecture },
+                })
+            };
+            let amd64 = source.add_image("app", "amd64", "amd64", &["shared", &large]);
+            let arm64 = source.add_image("app", "arm64", "arm64", &["shared", "arm64 layer"]);
+            let index = serde_json::json!({
+                "schemaVersion": 2,
+                "mediaType": "application/vnd.oci.image.index.v1+json",
+                "manifests": [entry(&amd64, "amd64"), entry(&arm64, "arm64")],
+            });
+            (source.add_manifest("app", "v1", index), arm64)
+        };
+        let source_client = client(&source_url, |_| {});
+        let export = |platform: Option<&'static str>| {
+            let source_client = source_client.clone();
+            async move {
+                let chunks: Vec<bytes::Bytes> =
+                    source_client.export_image("app", "v1", platform).await.unwrap().try_collect().await.unwrap();
+                chunks.concat()
+            }
+        };
+        let import = |url: String, repository: &'static str, tag: Option<&'static str>, archive: Vec<u8>| async move {
+            let chunks: Vec<std::io::Result<bytes::Bytes>> =
+                archive.chunks(1000).map(|chunk| Ok(bytes::Bytes::copy_from_slice(chunk))).collect();
+            client(&url, |_| {}).import_image(repository, tag, futures::stream::iter(chunks)).await
+        };
+
+        let archive = export(None).await;
+        let paths: Vec<String> = tar::Archive::new(archive.as_slice())
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+            .collect();
+        // The layout and index, the manifests, then two configs and three layers
+        assert_eq!(paths[..3], [LAYOUT_FILE.to_string(), INDEX_FILE.to_string(), blob_file(&index_digest).unwrap()]);
+        assert_eq!(paths.len(), 10);
+
+        let (url, destination, _) = memory_registry().await;
+        let imported = import(url.clone(), "team/app", None, archive.clone()).await.unwrap();
+        assert_eq!((imported.tag.as_str(), imported.digest.as_str()), ("v1", index_digest.as_str()));
+        assert_eq!((imported.blobs_uploaded, imported.blobs_existing), (5, 0));
+        {
+            let destination = destination.lock().unwrap();
+            assert_eq!(destination.manifests["team/app:v1"], source.lock().unwrap().manifests["app:v1"]);
+            assert!(destination.blobs.contains_key(&format!("team/app@{}", sha256(&large))));
+        }
+        let pulled = client(&url, |_| {}).get_image_info_for_platform("team/app", "v1", Some("linux/arm64")).await.unwrap();
+        assert_eq!(pulled.manifest_digest.as_deref(), Some(index_digest.as_str()));
+
+        // Importing again finds every blob in place
+        let again = import(url.clone(), "team/app", Some("v2"), archive.clone()).await.unwrap();
+        assert_eq!((again.tag.as_str(), again.blobs_uploaded, again.blobs_existing), ("v2", 0, 5));
+
+        // One platform of it
+        let imported = import(url.clone(), "solo", Some("arm"), export(Some("linux/arm64")).await).await.unwrap();
+        assert_eq!(imported.digest, arm64);
+        assert_eq!(imported.blobs_uploaded, 3);
+
+        let error = import(url.clone(), "broken", None, archive[..archive.len() / 2].to_vec()).await.unwrap_err();
+        assert!(matches!(error, ImportImageError::InvalidArchive(_)));
+        let unindexed = [tar_file(LAYOUT_FILE, LAYOUT_VERSION).unwrap(), tar_end()].concat();
+        let error = import(url.clone(), "broken", None, unindexed).await.unwrap_err();
+        assert!(matches!(error, ImportImageError::InvalidArchive(message) if message.contains("no index.json")));
+        // A blob whose contents were changed
+        let offset = archive.windows(11).position(|window| window == b"arm64 layer").unwrap();
+        let mut tampered = archive.clone();
+        tampered[offset] = b'A';
+        let error = import(url, "broken", None, tampered).await.unwrap_err();
+        assert!(matches!(error, ImportImageError::InvalidArchive(message) if message.contains("does not match its digest")));
+    }
+
+    #[tokio::test]
+    async fn pulled_images_are_pushed_with_only_the_missing_blobs() {
+        let (source_url, source, _) = memory_registry().await;
+        let digest = source.lock().unwrap().add_image("app", "v1", "amd64", &["layer one", "layer two"]);
+        let (url, destination, requests) = memory_registry().await;
+        // The destination already has the first layer
+        destination.lock().unwrap().add_blob("team/app", "layer one");
+
+        let blob_dir = std::env::temp_dir().join(format!("gpanel-push-{}", uuid::Uuid::new_v4()));
+        client(&source_url, |_| {}).pull_image("app", "v1", &blob_dir).await.unwrap();
+        assert_eq!(local_manifest_digest(&blob_dir, "app", "v1").await.unwrap(), Some(digest.clone()));
+        tag_local_manifest(&blob_dir, "team/app", "v1", &digest).await.unwrap();
+
+        let destination_client = client(&url, |_| {});
+        let result = destination_client.push_image("team/app", "v1", &blob_dir).await.unwrap();
+        assert_eq!((result.blobs_uploaded, result.blobs_existing, result.blobs_mounted), (2, 1, 0));
+        assert_eq!(destination_client.get_image_info("team/app", "v1").await.unwrap().manifest_digest, Some(digest));
+
+        let seen: Vec<String> = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .take(4)
+            .map(|request| format!("{} {}", request.method, request.url.path()))
+            .collect();
+        assert!(seen[0].starts_with("HEAD /v2/team/app/blobs/sha256:"));
+        assert_eq!(seen[1], "POST /v2/team/app/blobs/uploads/");
+        assert_eq!(seen[2], "PUT /v2/team/app/blobs/uploads/session");
+
+        let error = destination_client.push_image("team/app", "v2", &blob_dir).await.unwrap_err();
+        assert!(error.to_string().contains("was not pulled"));
+        std::fs::remove_dir_all(&blob_dir).unwrap();
+    }
+
+    /// A registry storing images like `memory_registry` that answers cross-repository mounts
+    /// with `mount_status`, opening the upload session `/v2/<repository>/blobs/uploads/session?from=mount`
+    /// on a 202
+    async fn mounting_registry(mount_status: u16) -> (RegistryClient, Arc<Mutex<Vec<StubRequest>>>) {
+        let stored = Arc::new(Mutex::new(Stored::default()));
+        stored.lock().unwrap().add_image("app", "v1", "amd64", &["layer one", "layer two"]);
+        let (url, requests) = stub_registry(move |_, request| {
+            let mut stored = stored.lock().unwrap();
+            if request.method == "POST" && request.query("mount").is_some() {
+                return match mount_status {
+                    201 => stored.serve(request),
+                    202 => {
+                        let repository = request.url.path().trim_start_matches("/v2/").split("/blobs/").next().unwrap();
+                        let location = format!("/v2/{}/blobs/uploads/session?from=mount", repository);
+                        (202, vec![("Location", location)], String::new())
+                    }
+                    status => (status, Vec::new(), r#"{"errors":[]}"#.to_string()),
+                };
+            }
+            stored.serve(request)
+        })
+        .await;
+        (client(&url, |_| {}), requests)
+    }
+
+    /// `method path?query` of each blob request
+    fn blob_requests(requests: &Mutex<Vec<StubRequest>>) -> Vec<String> {
+        requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request.url.path().contains("/blobs/"))
+            .map(|request| match request.url.query() {
+                Some(query) => format!("{} {}?{}", request.method, request.url.path(), query),
+                None => format!("{} {}", request.method, request.url.path()),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn blobs_are_mounted_from_the_same_registry() {
+        let (client, requests) = mounting_registry(201).await;
+        assert!(client.same_host(&client));
+
+        let result = client.copy_image_from(&client, "app", "v1", "team/app", "v1").await.unwrap();
+        // A config and two layers, none of them uploaded
+        assert_eq!((result.blobs_mounted, result.blobs_uploaded, result.blobs_existing), (3, 0, 0));
+        let blobs = blob_requests(&requests);
+        assert!(blobs.iter().all(|request| !request.starts_with("PUT ")));
+        let mounts: Vec<&String> = blobs.iter().filter(|request| request.starts_with("POST ")).collect();
+        assert_eq!(mounts.len(), 3);
+        assert!(mounts[0].starts_with("POST /v2/team/app/blobs/uploads/?mount=sha256%3A"));
+        assert!(mounts[0].ends_with("&from=app"));
+        assert!(client.get_image_info("team/app", "v1").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn declined_mounts_upload_to_the_session_they_open() {
+        let (client, requests) = mounting_registry(202).await;
+
+        let result = client.copy_image_from(&client, "app", "v1", "team/app", "v1").await.unwrap();
+        assert_eq!((result.blobs_mounted, result.blobs_uploaded), (0, 3));
+        let blobs = blob_requests(&requests);
+        let posts: Vec<&String> = blobs.iter().filter(|request| request.starts_with("POST ")).collect();
+        assert_eq!(posts.len(), 3, "no second session is opened");
+        assert!(posts.iter().all(|request| request.contains("mount=")));
+        let uploads: Vec<&String> = blobs.iter().filter(|request| request.starts_with("PUT ")).collect();
+        assert_eq!(uploads.len(), 3);
+        assert!(uploads.iter().all(|request| request.starts_with("PUT /v2/team/app/blobs/uploads/session?from=mount&digest=")));
+    }
+
+    #[tokio::test]
+    async fn failed_mounts_fall_back_to_a_full_upload() {
+        let (client, requests) = mounting_registry(404).await;
+
+        let result = client.copy_image_from(&client, "app", "v1", "team/app", "v1").await.unwrap();
+        assert_eq!((result.blobs_mounted, result.blobs_uploaded), (0, 3));
+        let blobs = blob_requests(&requests);
+        let first_upload = blobs.iter().position(|request| request.starts_with("PUT ")).unwrap();
+        // The mount, a plain session, then the blob read from the source
+        assert!(blobs[first_upload - 3].contains("mount="));
+        assert_eq!(blobs[first_upload - 2], "POST /v2/team/app/blobs/uploads/");
+        assert!(blobs[first_upload - 1].starts_with("GET /v2/app/blobs/sha256:"));
+        assert!(blobs[first_upload].starts_with("PUT /v2/team/app/blobs/uploads/session?digest="));
+        assert!(client.get_image_info("team/app", "v1").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn images_are_copied_between_registries_with_every_platform() {
+        let (source_url, source, _) = memory_registry().await;
+        let index_digest = {
+            let mut source = source.lock().unwrap();
+            let entry = |digest: String, architecture: &str| {
+                serde_json::json!({
+                    "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                    "size": 500,
+                    "digest": digest,
+                    "platform": { "os": "linux", "architecture": architecture },
+                })
+            };
+            let amd64 = source.add_image("app", "amd64", "amd64", &["shared", "amd64 layer"]);
+            let arm64 = source.add_image("app", "arm64", "arm64", &["shared", "arm64 layer"]);
+            let index = serde_json::json!({
+                "schemaVersion": 2,
+                "mediaType": "application/vnd.oci.image.index.v1+json",
+                "manifests": [entry(amd64, "amd64"), entry(arm64, "arm64")],
+            });
+            source.add_manifest("app", "v1", index)
+        };
+        let (destination_url, destination, _) = memory_registry().await;
+
+        let mut manager = RegistryManager::new();
+        manager.insert_client(client(&source_url, |config| config.name = "source".to_string()));
+        manager.insert_client(client(&destination_url, |config| config.name = "destination".to_string()));
+        let result = manager.copy_image("source", "destination", "app", "v1").await.unwrap();
+        // Two configs and three distinct layers
+        assert_eq!((result.blobs_uploaded, result.blobs_existing), (5, 0));
+
+        {
+            let destination = destination.lock().unwrap();
+            assert_eq!(sha256(&destination.manifests["app:v1"].1), index_digest, "the index is copied byte for byte");
+            assert_eq!(destination.manifests["app:v1"].0, "application/vnd.oci.image.index.v1+json");
+            assert_eq!(destination.manifests.len(), 4, "both platform manifests are stored by digest");
+        }
+
+        let again = manager.copy_image("source", "destination", "app", "v1").await.unwrap();
+        assert_eq!((again.blobs_uploaded, again.blobs_existing), (0, 5));
+        assert!(manager.copy_image("source", "missing", "app", "v1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn images_are_retagged_with_their_manifest_unchanged() {
+        let (url, stored, requests) = memory_registry().await;
+        let (single, index) = {
+            let mut stored = stored.lock().unwrap();
+            let single = stored.add_image("app", "staging", "amd64", &["layer"]);
+            let arm64 = stored.add_image("app", "arm64", "arm64", &["arm layer"]);
+            let index = serde_json::json!({
+                "schemaVersion": 2,
+                "mediaType": "application/vnd.oci.image.index.v1+json",
+                "manifests": [{
+                    "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                    "size": 400,
+                    "digest": arm64,
+                    "platform": { "os": "linux", "architecture": "arm64" },
+                }],
+            });
+            (single, stored.add_manifest("app", "multi", index))
+        };
+        let registry = client(&url, |_| {});
+
+        assert_eq!(registry.tag_image("app", "staging", "prod").await.unwrap(), single);
+        assert_eq!(registry.tag_image("app", "multi", "multi-prod").await.unwrap(), index);
+        // By digest too
+        assert_eq!(registry.tag_image("app", &single, "pinned").await.unwrap(), single);
+
+        {
+            let stored = stored.lock().unwrap();
+            for (source, target) in [("staging", "prod"), ("multi", "multi-prod"), ("staging", "pinned")] {
+                assert_eq!(stored.manifests[&format!("app:{}", target)], stored.manifests[&format!("app:{}", source)]);
+            }
+        }
+        let puts: Vec<StubRequest> =
+            requests.lock().unwrap().iter().filter(|request| request.method == "PUT").cloned().collect();
+        assert_eq!(puts.len(), 3, "only manifests are put, no blobs");
+        assert_eq!(puts[1].url.path(), "/v2/app/manifests/multi-prod");
+        assert_eq!(puts[1].content_type.as_deref(), Some("application/vnd.oci.image.index.v1+json"));
+        assert_eq!(sha256(&String::from_utf8(puts[1].body.clone()).unwrap()), index);
+
+        let error = registry.tag_image("app", "missing", "prod").await.unwrap_err();
+        assert!(matches!(error, TagImageError::NotFound(message) if message == "app:missing does not exist"));
+        let error = registry.tag_image("app", "staging", "-bad").await.unwrap_err();
+        assert!(matches!(error, TagImageError::InvalidTag(_)));
+    }
+
+    #[test]
+    fn image_refs_name_the_registry_host() {
+        let hub = client("https://registry-1.docker.io", |_| {});
+        assert_eq!(hub.image_ref("nginx", "1.25").unwrap().to_string(), "docker.io/library/nginx:1.25");
+        assert_eq!(hub.image_ref("bitnami/redis", "7").unwrap().repository(), "bitnami/redis");
+
+        let local = client("http://localhost:5000", |_| {});
+        let digest = format!("sha256:{}", "a".repeat(64));
+        let pinned = local.image_ref("app", &digest).unwrap();
+        assert_eq!(pinned.to_string(), format!("localhost:5000/app@{}", digest));
+        assert_eq!(pinned.reference(), digest);
+        assert!(local.image_ref("App", "1").is_err());
+    }
+
+    #[tokio::test]
+    async fn cached_images_are_served_while_their_digest_is_unchanged() {
+        let (url, stored, requests) = memory_registry().await;
+        stored.lock().unwrap().add_image("app", "v1", "amd64", &["layer"]);
+        let mut manager = RegistryManager::new();
+        manager.insert_client(client(&url, |_| {}));
+        let config_fetches = || requests.lock().unwrap().iter().filter(|request| request.url.path().contains("/blobs/")).count();
+
+        let first = manager.image_info("stub", "app", "v1", None).await.unwrap();
+        assert_eq!(config_fetches(), 1);
+        let second = manager.image_info("stub", "app", "v1", None).await.unwrap();
+        assert_eq!(config_fetches(), 1, "the second lookup is answered from the cache");
+        assert_eq!(second.manifest_digest, first.manifest_digest);
+        let last = requests.lock().unwrap().last().cloned().unwrap();
+        assert_eq!((last.method.as_str(), last.url.path()), ("HEAD", "/v2/app/manifests/v1"));
+
+        // A new image under the same tag is fetched again
+        let digest = stored.lock().unwrap().add_image("app", "v1", "arm64", &["other layer"]);
+        let third = manager.image_info("stub", "app", "v1", None).await.unwrap();
+        assert_eq!(config_fetches(), 2);
+        assert_eq!(third.manifest_digest, Some(digest.clone()));
+
+        // Lookups by digest need no request at all
+        let before = requests.lock().unwrap().len();
+        manager.image_info("stub", "app", &digest, None).await.unwrap();
+        manager.image_info("stub", "app", &digest, None).await.unwrap();
+        assert_eq!(requests.lock().unwrap().len(), before + 2, "only the first lookup reaches the registry");
+
+        let stats = manager.image_cache_stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (2, 3, 2));
+    }
+
+    #[test]
+    fn image_cache_drops_the_least_recently_used_and_expired() {
+        let key = |reference: &str| ImageCacheKey {
+            registry: "stub".to_string(),
+            repository: "app".to_string(),
+            reference: reference.to_string(),
+            platform: None,
+        };
+        let info = |tag: &str| ImageInfo {
+            repository: "app".to_string(),
+            tag: tag.to_string(),
+            digest: "sha256:config".to_string(),
+            manifest_digest: Some(format!("sha256:{}", tag)),
+            size: 0,
+            created: chrono::Utc::now(),
+            author: None,
+            layers: Vec::new(),
+            platform: None,
+            platforms: Vec::new(),
+        };
+
+        let mut cache = ImageCache::new(ImageCacheConfig { capacity: 2, ..Default::default() });
+        cache.insert(key("a"), info("a"));
+        cache.insert(key("b"), info("b"));
+        assert!(cache.get(&key("a")).is_some());
+        cache.insert(key("c"), info("c"));
+        assert!(cache.get(&key("b")).is_none(), "b was used longest ago");
+        assert!(cache.get(&key("a")).is_some() && cache.get(&key("c")).is_some());
+
+        // Persisted entries come back in the order they were used
+        let saved = cache.take_changes().unwrap();
+        assert_eq!(saved.iter().map(|cached| cached.key.reference.as_str()).collect::<Vec<_>>(), ["a", "c"]);
+        assert!(cache.take_changes().is_none());
+        let mut restored = ImageCache::new(ImageCacheConfig::default());
+        restored.restore(saved.clone());
+        assert_eq!(restored.stats().entries, 2);
+
+        let mut expired = ImageCache::new(ImageCacheConfig { ttl_secs: 0, ..Default::default() });
+        expired.restore(saved);
+        assert_eq!(expired.stats().entries, 0);
+        expired.insert(key("a"), info("a"));
+        assert!(expired.get(&key("a")).is_none());
+
+        let mut disabled = ImageCache::new(ImageCacheConfig { capacity: 0, ..Default::default() });
+        disabled.insert(key("a"), info("a"));
+        assert_eq!(disabled.stats().entries, 0);
+    }
+
+    #[tokio::test]
+    async fn drift_registries_are_detected_and_collect_garbage() {
+        let (plain, _) = stub_registry(|_, _| (404, Vec::new(), r#"{"errors":[{"code":"NOT_FOUND"}]}"#.to_string())).await;
+        assert!(client(&plain, |_| {}).drift().await.unwrap().is_none());
+        // Something else answering the path is not Drift either
+        let (other, _) = stub_registry(|_, _| ok(serde_json::json!({ "status": "ok" }))).await;
+        assert!(client(&other, |_| {}).drift().await.unwrap().is_none());
+
+        let collections = Arc::new(Mutex::new(0u32));
+        let started = collections.clone();
+        let (url, requests) = stub_registry(move |_, request| {
+            let status = |state: &str| {
+                serde_json::json!({
+                    "state": state,
+                    "started_at": "2024-05-02T10:00:00Z",
+                    "finished_at": (state == "succeeded").then_some("2024-05-02T10:00:05Z"),
+                    "blobs_removed": if state == "succeeded" { 12 } else { 0 },
+                    "bytes_freed": if state == "succeeded" { 4096 } else { 0 },
+                })
+            };
+            let mut collections = started.lock().unwrap();
+            match (request.method.as_str(), request.url.path()) {
+                ("GET", "/drift/v1/info") => ok(serde_json::json!({ "version": "0.4.2", "features": ["gc", "quota"] })),
+                ("POST", "/drift/v1/gc") if *collections == 1 => (409, Vec::new(), "{}".to_string()),
+                ("POST", "/drift/v1/gc") => {
+                    *collections += 1;
+                    (202, Vec::new(), status("running").to_string())
+                }
+                ("GET", "/drift/v1/gc") if *collections == 1 => ok(status("running")),
+                ("GET", "/drift/v1/gc") => ok(status("succeeded")),
+                ("GET", "/drift/v1/quota") => ok(serde_json::json!({ "used_bytes": 1_000_000, "limit_bytes": 5_000_000 })),
+                _ => (404, Vec::new(), "{}".to_string()),
+            }
+        })
+        .await;
+
+        let drift = client(&url, |_| {}).drift().await.unwrap().expect("detected as Drift");
+        assert_eq!(drift.info().version, "0.4.2");
+        assert_eq!(drift.info().features, ["gc", "quota"]);
+
+        let running = drift.trigger_gc().await.unwrap();
+        assert_eq!(running.state, DriftGcState::Running);
+        // A second request while it runs reports the same collection
+        assert_eq!(drift.trigger_gc().await.unwrap(), running);
+        assert_eq!(*collections.lock().unwrap(), 1);
+        assert_eq!(drift.gc_status().await.unwrap().state, DriftGcState::Running);
+
+        *collections.lock().unwrap() = 2;
+        let finished = drift.gc_status().await.unwrap();
+        assert_eq!((finished.state, finished.blobs_removed, finished.bytes_freed), (DriftGcState::Succeeded, 12, 4096));
+        assert!(finished.finished_at.is_some());
+        assert_eq!(
+            drift.get_quota().await.unwrap(),
+            DriftQuota {
+                used_bytes: 1_000_000,
+                limit_bytes: Some(5_000_000)
+            }
+        );
+
+        let calls: Vec<String> = requests.lock().unwrap().iter().map(|request| format!("{} {}", request.method, request.url.path())).collect();
+        assert_eq!(
+            calls,
+            [
+                "GET /drift/v1/info",
+                "POST /drift/v1/gc",
+                "POST /drift/v1/gc",
+                "GET /drift/v1/gc",
+                "GET /drift/v1/gc",
+                "GET /drift/v1/gc",
+                "GET /drift/v1/quota"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn requests_go_through_the_proxy_unless_no_proxy_matches() {
+        let (proxy_url, proxied) = stub_registry(|_, _| ok(serde_json::json!({}))).await;
+        let (direct_url, direct) = stub_registry(|_, _| ok(serde_json::json!({}))).await;
+        let proxy = ProxyConfig {
+            http_proxy: Some(proxy_url),
+            https_proxy: None,
+            no_proxy: vec![".corp.example".to_string(), "127.0.0.0/8".to_string()],
+        };
+        let through = |url: &str| {
+            let config = client(url, |_| {}).config().clone();
+            RegistryClient::with_proxy(config, &proxy)
+        };
+
+        // Never resolved: the proxy answers for it
+        through("http://registry.example.com:5000").authenticate().await.unwrap();
+        through(&direct_url).authenticate().await.unwrap();
+
+        let proxied = proxied.lock().unwrap().clone();
+        assert_eq!(proxied.len(), 1);
+        assert_eq!(proxied[0].url.as_str(), "http://registry.example.com:5000/v2/");
+        let direct = direct.lock().unwrap().clone();
+        assert_eq!(direct.len(), 1);
+        for request in [&proxied[0], &direct[0]] {
+            assert_eq!(request.user_agent.as_deref(), Some(crate::proxy::USER_AGENT));
+        }
+        assert!(crate::proxy::USER_AGENT.starts_with("ghostpanel/"));
+    }
+}
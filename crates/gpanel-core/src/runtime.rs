@@ -0,0 +1,269 @@
+use async_trait::async_trait;
+
+use crate::api::BoltSystemInfo;
+use crate::error::Result;
+use crate::bolt::{
+    BoltClient, ContainerLogsRequest, LogLineStream, MockBoltClient, TerminalSession, VolumeFileStream,
+};
+use crate::container::{
+    Container, ContainerFilter, ContainerStats, ContainerTop, CreateContainerRequest, ExecRequest, ExecResponse,
+    WaitCondition,
+};
+use crate::image::{ContainerCommitRequest, ImagePruneFilter, ImagePruneReport, ImageSummary, LocalImage};
+use crate::network::{CreateNetworkRequest, Network, NetworkConnectRequest};
+use crate::system::BoltDiskUsage;
+use crate::volume::{
+    CreateVolumeRequest, Volume, VolumeEntry, VolumePruneFilter, VolumePruneReport,
+};
+
+/// Container runtime the agent drives: the Bolt API, or the mock when Bolt is not available
+#[async_trait]
+pub trait ContainerRuntime: Send + Sync {
+    /// Check whether the runtime is reachable
+    async fn ping(&self) -> Result<bool>;
+
+    // Containers
+    async fn list_containers(&self, filter: Option<ContainerFilter>) -> Result<Vec<Container>>;
+    async fn get_container(&self, id: &str) -> Result<Container>;
+    async fn create_container(&self, request: CreateContainerRequest) -> Result<Container>;
+    async fn start_container(&self, id: &str) -> Result<()>;
+    async fn stop_container(&self, id: &str, timeout: Option<u32>) -> Result<()>;
+    async fn restart_container(&self, id: &str, timeout: Option<u32>) -> Result<()>;
+    async fn wait_container(&self, id: &str, condition: WaitCondition) -> Result<i32>;
+    async fn pause_container(&self, id: &str) -> Result<()>;
+    async fn unpause_container(&self, id: &str) -> Result<()>;
+    async fn kill_container(&self, id: &str, signal: Option<&str>) -> Result<()>;
+    async fn remove_container(&self, id: &str, force: bool, remove_volumes: bool) -> Result<()>;
+    async fn rename_container(&self, id: &str, new_name: &str) -> Result<()>;
+    async fn get_container_logs(&self, request: ContainerLogsRequest) -> Result<String>;
+    async fn follow_container_logs(&self, request: ContainerLogsRequest) -> Result<LogLineStream>;
+    async fn get_container_stats(&self, id: &str) -> Result<ContainerStats>;
+    async fn top_container(&self, id: &str, ps_args: Option<&str>) -> Result<ContainerTop>;
+    async fn exec_container(&self, id: &str, request: &ExecRequest) -> Result<ExecResponse>;
+    async fn exec_terminal(&self, id: &str, request: &ExecRequest) -> Result<TerminalSession>;
+    async fn export_container(&self, id: &str) -> Result<VolumeFileStream>;
+    async fn commit_container(&self, id: &str, request: &ContainerCommitRequest) -> Result<ImageSummary>;
+    async fn stat_container_path(&self, id: &str, path: &str) -> Result<Option<VolumeEntry>>;
+    async fn read_container_file(&self, id: &str, path: &str) -> Result<VolumeFileStream>;
+    async fn get_archive(&self, id: &str, path: &str) -> Result<VolumeFileStream>;
+    async fn put_archive(&self, id: &str, path: &str, archive: VolumeFileStream) -> Result<()>;
+
+    // Images
+    async fn list_images(&self) -> Result<Vec<LocalImage>>;
+    async fn pull_image(&self, reference: &str) -> Result<()>;
+    async fn remove_image(&self, id: &str, force: bool) -> Result<()>;
+    async fn tag_image(&self, id: &str, repository: &str, tag: &str) -> Result<()>;
+    async fn prune_images(&self, filter: &ImagePruneFilter) -> Result<ImagePruneReport>;
+
+    // Networks
+    async fn list_networks(&self) -> Result<Vec<Network>>;
+    async fn create_network(&self, request: CreateNetworkRequest) -> Result<Network>;
+    async fn remove_network(&self, id: &str) -> Result<()>;
+    async fn connect_network(&self, id: &str, request: &NetworkConnectRequest) -> Result<()>;
+    async fn disconnect_network(&self, id: &str, request: &NetworkConnectRequest) -> Result<()>;
+
+    // Volumes
+    async fn list_volumes(&self) -> Result<Vec<Volume>>;
+    async fn inspect_volume(&self, name: &str) -> Result<Option<Volume>>;
+    async fn create_volume(&self, request: CreateVolumeRequest) -> Result<Volume>;
+    async fn remove_volume(&self, name: &str, force: bool) -> Result<()>;
+    async fn prune_volumes(&self, filter: &VolumePruneFilter) -> Result<VolumePruneReport>;
+    async fn stat_volume_path(&self, name: &str, path: &str) -> Result<Option<VolumeEntry>>;
+    async fn list_volume_directory(&self, name: &str, path: &str) -> Result<Vec<VolumeEntry>>;
+    async fn read_volume_file(&self, name: &str, path: &str) -> Result<VolumeFileStream>;
+
+    // System
+    async fn system_info(&self) -> Result<BoltSystemInfo>;
+    async fn disk_usage(&self) -> Result<BoltDiskUsage>;
+}
+
+/// Implement `ContainerRuntime` by forwarding to the client's inherent methods of the same name
+macro_rules! impl_container_runtime {
+    ($client:ty) => {
+        #[async_trait]
+        impl ContainerRuntime for $client {
+            async fn ping(&self) -> Result<bool> {
+                <$client>::ping(self).await
+            }
+
+            async fn list_containers(&self, filter: Option<ContainerFilter>) -> Result<Vec<Container>> {
+                <$client>::list_containers(self, filter).await
+            }
+
+            async fn get_container(&self, id: &str) -> Result<Container> {
+                <$client>::get_container(self, id).await
+            }
+
+            async fn create_container(&self, request: CreateContainerRequest) -> Result<Container> {
+                <$client>::create_container(self, request).await
+            }
+
+            async fn start_container(&self, id: &str) -> Result<()> {
+                <$client>::start_container(self, id).await
+            }
+
+            async fn stop_container(&self, id: &str, timeout: Option<u32>) -> Result<()> {
+                <$client>::stop_container(self, id, timeout).await
+            }
+
+            async fn restart_container(&self, id: &str, timeout: Option<u32>) -> Result<()> {
+                <$client>::restart_container(self, id, timeout).await
+            }
+
+            async fn wait_container(&self, id: &str, condition: WaitCondition) -> Result<i32> {
+                <$client>::wait_container(self, id, condition).await
+            }
+
+            async fn pause_container(&self, id: &str) -> Result<()> {
+                <$client>::pause_container(self, id).await
+            }
+
+            async fn unpause_container(&self, id: &str) -> Result<()> {
+                <$client>::unpause_container(self, id).await
+            }
+
+            async fn kill_container(&self, id: &str, signal: Option<&str>) -> Result<()> {
+                <$client>::kill_container(self, id, signal).await
+            }
+
+            async fn remove_container(&self, id: &str, force: bool, remove_volumes: bool) -> Result<()> {
+                <$client>::remove_container(self, id, force, remove_volumes).await
+            }
+
+            async fn rename_container(&self, id: &str, new_name: &str) -> Result<()> {
+                <$client>::rename_container(self, id, new_name).await
+            }
+
+            async fn get_container_logs(&self, request: ContainerLogsRequest) -> Result<String> {
+                <$client>::get_container_logs(self, request).await
+            }
+
+            async fn follow_container_logs(&self, request: ContainerLogsRequest) -> Result<LogLineStream> {
+                <$client>::follow_container_logs(self, request).await
+            }
+
+            async fn get_container_stats(&self, id: &str) -> Result<ContainerStats> {
+                <$client>::get_container_stats(self, id).await
+            }
+
+            async fn top_container(&self, id: &str, ps_args: Option<&str>) -> Result<ContainerTop> {
+                <$client>::top_container(self, id, ps_args).await
+            }
+
+            async fn exec_container(&self, id: &str, request: &ExecRequest) -> Result<ExecResponse> {
+                <$client>::exec_container(self, id, request).await
+            }
+
+            async fn exec_terminal(&self, id: &str, request: &ExecRequest) -> Result<TerminalSession> {
+                <$client>::exec_terminal(self, id, request).await
+            }
+
+            async fn export_container(&self, id: &str) -> Result<VolumeFileStream> {
+                <$client>::export_container(self, id).await
+            }
+
+            async fn commit_container(&self, id: &str, request: &ContainerCommitRequest) -> Result<ImageSummary> {
+                <$client>::commit_container(self, id, request).await
+            }
+
+            async fn stat_container_path(&self, id: &str, path: &str) -> Result<Option<VolumeEntry>> {
+                <$client>::stat_container_path(self, id, path).await
+            }
+
+            async fn read_container_file(&self, id: &str, path: &str) -> Result<VolumeFileStream> {
+                <$client>::read_container_file(self, id, path).await
+            }
+
+            async fn get_archive(&self, id: &str, path: &str) -> Result<VolumeFileStream> {
+                <$client>::get_archive(self, id, path).await
+            }
+
+            async fn put_archive(&self, id: &str, path: &str, archive: VolumeFileStream) -> Result<()> {
+                <$client>::put_archive(self, id, path, archive).await
+            }
+
+            async fn list_images(&self) -> Result<Vec<LocalImage>> {
+                <$client>::list_images(self).await
+            }
+
+            async fn pull_image(&self, reference: &str) -> Result<()> {
+                <$client>::pull_image(self, reference).await
+            }
+
+            async fn remove_image(&self, id: &str, force: bool) -> Result<()> {
+                <$client>::remove_image(self, id, force).await
+            }
+
+            async fn tag_image(&self, id: &str, repository: &str, tag: &str) -> Result<()> {
+                <$client>::tag_image(self, id, repository, tag).await
+            }
+
+            async fn prune_images(&self, filter: &ImagePruneFilter) -> Result<ImagePruneReport> {
+                <$client>::prune_images(self, filter).await
+            }
+
+            async fn list_networks(&self) -> Result<Vec<Network>> {
+                <$client>::list_networks(self).await
+            }
+
+            async fn create_network(&self, request: CreateNetworkRequest) -> Result<Network> {
+                <$client>::create_network(self, request).await
+            }
+
+            async fn remove_network(&self, id: &str) -> Result<()> {
+                <$client>::remove_network(self, id).await
+            }
+
+            async fn connect_network(&self, id: &str, request: &NetworkConnectRequest) -> Result<()> {
+                <$client>::connect_network(self, id, request).await
+            }
+
+            async fn disconnect_network(&self, id: &str, request: &NetworkConnectRequest) -> Result<()> {
+                <$client>::disconnect_network(self, id, request).await
+            }
+
+            async fn list_volumes(&self) -> Result<Vec<Volume>> {
+                <$client>::list_volumes(self).await
+            }
+
+            async fn inspect_volume(&self, name: &str) -> Result<Option<Volume>> {
+                <$client>::inspect_volume(self, name).await
+            }
+
+            async fn create_volume(&self, request: CreateVolumeRequest) -> Result<Volume> {
+                <$client>::create_volume(self, request).await
+            }
+
+            async fn remove_volume(&self, name: &str, force: bool) -> Result<()> {
+                <$client>::remove_volume(self, name, force).await
+            }
+
+            async fn prune_volumes(&self, filter: &VolumePruneFilter) -> Result<VolumePruneReport> {
+                <$client>::prune_volumes(self, filter).await
+            }
+
+            async fn stat_volume_path(&self, name: &str, path: &str) -> Result<Option<VolumeEntry>> {
+                <$client>::stat_volume_path(self, name, path).await
+            }
+
+            async fn list_volume_directory(&self, name: &str, path: &str) -> Result<Vec<VolumeEntry>> {
+                <$client>::list_volume_directory(self, name, path).await
+            }
+
+            async fn read_volume_file(&self, name: &str, path: &str) -> Result<VolumeFileStream> {
+                <$client>::read_volume_file(self, name, path).await
+            }
+
+            async fn system_info(&self) -> Result<BoltSystemInfo> {
+                <$client>::system_info(self).await
+            }
+
+            async fn disk_usage(&self) -> Result<BoltDiskUsage> {
+                <$client>::disk_usage(self).await
+            }
+        }
+    };
+}
+
+impl_container_runtime!(BoltClient);
+impl_container_runtime!(MockBoltClient);
@@ -0,0 +1,322 @@
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Container action run on a cron schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Schedule {
+    pub id: String,
+    pub name: Option<String>,
+    /// Target a single container by ID or name
+    pub container_id: Option<String>,
+    /// Or every container carrying this label (`key` or `key=value`)
+    pub label_selector: Option<String>,
+    pub action: ScheduleAction,
+    /// Five-field cron expression, evaluated in UTC
+    pub cron: String,
+    pub enabled: bool,
+    pub last_run: Option<DateTime<Utc>>,
+    pub last_result: Option<ScheduleRunResult>,
+    pub next_run: Option<DateTime<Utc>>,
+    /// Environment the targets run in; the default one when unset
+    #[serde(default)]
+    pub environment: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum ScheduleAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+/// Outcome of the last run of a schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ScheduleRunResult {
+    pub success: bool,
+    pub message: String,
+    /// Containers the action was applied to
+    pub containers: Vec<String>,
+}
+
+/// Schedule creation and update request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ScheduleRequest {
+    pub name: Option<String>,
+    pub container_id: Option<String>,
+    pub label_selector: Option<String>,
+    pub action: Option<ScheduleAction>,
+    pub cron: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+/// Cron expression parse error
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CronError {
+    /// Character offset of the offending token in the expression
+    pub position: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for CronError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for CronError {}
+
+/// Parsed five-field cron expression (minute, hour, day of month, month, day of week)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// Whether the day fields were `*`, which changes how they combine
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+struct CronField {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+}
+
+const FIELDS: [CronField; 5] = [
+    CronField { name: "minute", min: 0, max: 59, names: &[] },
+    CronField { name: "hour", min: 0, max: 23, names: &[] },
+    CronField { name: "day of month", min: 1, max: 31, names: &[] },
+    CronField {
+        name: "month",
+        min: 1,
+        max: 12,
+        names: &["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"],
+    },
+    // 7 is accepted as Sunday and folded onto 0
+    CronField { name: "day of week", min: 0, max: 7, names: &["sun", "mon", "tue", "wed", "thu", "fri", "sat"] },
+];
+
+impl CronField {
+    fn value(&self, token: &str, position: usize) -> Result<u32, CronError> {
+        if let Some(index) = self.names.iter().position(|name| name.eq_ignore_ascii_case(token)) {
+            return Ok(index as u32 + self.min);
+        }
+
+        let value: u32 = token.parse().map_err(|_| CronError {
+            position,
+            message: format!("Invalid {} value '{}'", self.name, token),
+        })?;
+        if value < self.min || value > self.max {
+            return Err(CronError {
+                position,
+                message: format!("Value {} is outside {}-{} for the {} field", value, self.min, self.max, self.name),
+            });
+        }
+        Ok(value)
+    }
+
+    /// Parse one field into a bitset of allowed values
+    fn parse(&self, field: &str, offset: usize) -> Result<u64, CronError> {
+        let mut bits = 0u64;
+        let mut position = offset;
+
+        for part in field.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => {
+                    let step_position = position + range.len() + 1;
+                    let step: u32 = step.parse().ok().filter(|s| *s > 0).ok_or_else(|| CronError {
+                        position: step_position,
+                        message: format!("Invalid step '{}' in {} field", step, self.name),
+                    })?;
+                    (range, step)
+                }
+                None => (part, 1),
+            };
+
+            let (start, end) = if range == "*" {
+                (self.min, self.max)
+            } else if let Some((start, end)) = range.split_once('-') {
+                let start_value = self.value(start, position)?;
+                let end_value = self.value(end, position + start.len() + 1)?;
+                if start_value > end_value {
+                    return Err(CronError {
+                        position,
+                        message: format!("Range {} is reversed in {} field", range, self.name),
+                    });
+                }
+                (start_value, end_value)
+            } else {
+                let value = self.value(range, position)?;
+                // A step on a single value runs to the end of the field, like `5/15`
+                (value, if part.contains('/') { self.max } else { value })
+            };
+
+            for value in (start..=end).step_by(step as usize) {
+                bits |= 1 << value;
+            }
+            position += part.len() + 1;
+        }
+
+        Ok(bits)
+    }
+}
+
+impl CronSchedule {
+    /// Parse a five-field expression or one of the `@hourly`-style shorthands
+    pub fn parse(expression: &str) -> Result<Self, CronError> {
+        let expression = match expression.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            _ => expression,
+        };
+
+        let mut fields = Vec::new();
+        let mut start = None;
+        for (index, c) in expression.char_indices().chain(std::iter::once((expression.len(), ' '))) {
+            match (c.is_whitespace(), start) {
+                (false, None) => start = Some(index),
+                (true, Some(begin)) => {
+                    fields.push((begin, &expression[begin..index]));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+
+        if fields.len() != FIELDS.len() {
+            let position = fields.get(FIELDS.len()).map(|(offset, _)| *offset).unwrap_or(expression.len());
+            return Err(CronError {
+                position,
+                message: format!("Expected 5 fields (minute hour day month weekday), found {}", fields.len()),
+            });
+        }
+
+        let mut bits = [0u64; 5];
+        for (i, (offset, field)) in fields.iter().enumerate() {
+            bits[i] = FIELDS[i].parse(field, *offset)?;
+        }
+
+        // Fold Sunday-as-7 onto 0
+        if bits[4] & (1 << 7) != 0 {
+            bits[4] = (bits[4] & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            minutes: bits[0],
+            hours: bits[1],
+            days_of_month: bits[2],
+            months: bits[3],
+            days_of_week: bits[4],
+            any_day_of_month: fields[2].1 == "*",
+            any_day_of_week: fields[4].1 == "*",
+        })
+    }
+
+    fn day_matches(&self, time: &DateTime<Utc>) -> bool {
+        let dom = self.days_of_month & (1 << time.day()) != 0;
+        let dow = self.days_of_week & (1 << time.weekday().num_days_from_sunday()) != 0;
+
+        // Standard cron: when both day fields are restricted, either may match
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (false, false) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+
+    /// Next matching minute strictly after `after`, or `None` if nothing matches within five years
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let limit = after + Duration::days(5 * 366);
+        let mut time = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+
+        while time <= limit {
+            if self.months & (1 << time.month()) == 0 {
+                let (year, month) = if time.month() == 12 { (time.year() + 1, 1) } else { (time.year(), time.month() + 1) };
+                time = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+                continue;
+            }
+            if !self.day_matches(&time) {
+                time = Utc
+                    .with_ymd_and_hms(time.year(), time.month(), time.day(), 0, 0, 0)
+                    .single()?
+                    + Duration::days(1);
+                continue;
+            }
+            if self.hours & (1 << time.hour()) == 0 {
+                time = time.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if self.minutes & (1 << time.minute()) == 0 {
+                time += Duration::minutes(1);
+                continue;
+            }
+            return Some(time);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text).unwrap().with_timezone(&Utc)
+    }
+
+    fn next(expression: &str, after: &str) -> DateTime<Utc> {
+        CronSchedule::parse(expression).unwrap().next_after(at(after)).unwrap()
+    }
+
+    #[test]
+    fn next_run_is_strictly_after_the_given_time() {
+        assert_eq!(next("*/15 * * * *", "2024-03-01T10:00:00Z"), at("2024-03-01T10:15:00Z"));
+        assert_eq!(next("*/15 * * * *", "2024-03-01T10:14:59Z"), at("2024-03-01T10:15:00Z"));
+        assert_eq!(next("30 2 * * *", "2024-03-01T02:30:00Z"), at("2024-03-02T02:30:00Z"));
+        assert_eq!(next("@hourly", "2024-12-31T23:59:00Z"), at("2025-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn names_ranges_and_sunday_as_seven() {
+        // 2024-03-01 is a Friday
+        assert_eq!(next("0 9 * * mon-fri", "2024-03-01T09:00:00Z"), at("2024-03-04T09:00:00Z"));
+        assert_eq!(next("0 0 * * 7", "2024-03-01T00:00:00Z"), at("2024-03-03T00:00:00Z"));
+        assert_eq!(next("0 0 1 jun *", "2024-03-01T00:00:00Z"), at("2024-06-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either_day() {
+        // The 15th or any Monday, whichever comes first
+        assert_eq!(next("0 0 15 * mon", "2024-03-01T00:00:00Z"), at("2024-03-04T00:00:00Z"));
+        assert_eq!(next("0 0 15 * mon", "2024-03-11T00:00:00Z"), at("2024-03-15T00:00:00Z"));
+    }
+
+    #[test]
+    fn impossible_dates_never_run() {
+        assert_eq!(CronSchedule::parse("0 0 30 feb *").unwrap().next_after(at("2024-01-01T00:00:00Z")), None);
+    }
+
+    #[test]
+    fn errors_point_at_the_bad_field() {
+        let error = CronSchedule::parse("0 25 * * *").unwrap_err();
+        assert_eq!(error.position, 2);
+
+        let error = CronSchedule::parse("*/0 * * * *").unwrap_err();
+        assert_eq!(error.position, 2);
+
+        let error = CronSchedule::parse("0 0 * *").unwrap_err();
+        assert_eq!(error.position, 7);
+        assert!(error.message.contains("found 4"));
+    }
+}
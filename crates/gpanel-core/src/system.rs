@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::container::RestartPolicy;
+
+/// Disk usage reported natively by Bolt, for things the list endpoints don't expose
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BoltDiskUsage {
+    /// Writable layer size per container ID
+    pub container_layers: HashMap<String, u64>,
+    pub build_cache: Option<BuildCacheUsage>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BuildCacheUsage {
+    pub total_bytes: u64,
+    pub reclaimable_bytes: u64,
+}
+
+/// Aggregate disk usage across images, containers and volumes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DiskUsageSummary {
+    pub images: ImageDiskUsage,
+    pub containers: ContainerDiskUsage,
+    pub volumes: VolumeDiskUsage,
+    pub build_cache: Option<BuildCacheUsage>,
+    pub total_bytes: u64,
+    pub reclaimable_bytes: u64,
+    /// Per-image and per-volume rows, only with `?verbose=true`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<DiskUsageDetails>,
+}
+
+/// Reclaimable space worth suggesting a prune for, regardless of how full the disk is
+pub const PRUNE_SUGGESTION_BYTES: u64 = 1024 * 1024 * 1024;
+
+impl DiskUsageSummary {
+    /// Whether enough space could be freed to suggest pruning: 1 GiB, or a quarter of
+    /// everything in use
+    pub fn suggests_prune(&self) -> bool {
+        self.reclaimable_bytes >= PRUNE_SUGGESTION_BYTES
+            || (self.reclaimable_bytes > 0 && self.reclaimable_bytes >= self.total_bytes / 4)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageDiskUsage {
+    pub count: usize,
+    pub total_bytes: u64,
+    /// What a default (dangling only) image prune would free
+    pub dangling_bytes: u64,
+    /// What a full image prune would free
+    pub reclaimable_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ContainerDiskUsage {
+    pub count: usize,
+    pub writable_bytes: u64,
+    /// Writable layers of stopped containers
+    pub reclaimable_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VolumeDiskUsage {
+    pub count: usize,
+    pub total_bytes: u64,
+    /// What a volume prune would free
+    pub unused_bytes: u64,
+}
+
+/// Rows behind the disk usage totals
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DiskUsageDetails {
+    /// Largest unique size first
+    pub images: Vec<ImageDiskUsageRow>,
+    /// Largest first; volumes whose driver reports no size come last
+    pub volumes: Vec<VolumeDiskUsageRow>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImageDiskUsageRow {
+    pub id: String,
+    pub repo_tags: Vec<String>,
+    pub size: u64,
+    pub shared_size: u64,
+    /// What removing this image alone would free
+    pub unique_size: u64,
+    pub dangling: bool,
+    /// Containers using the image; an image with none is reclaimable
+    pub containers: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VolumeDiskUsageRow {
+    pub name: String,
+    pub driver: String,
+    pub size: Option<u64>,
+    /// Containers mounting the volume; a volume with none is reclaimable
+    pub containers: usize,
+}
+
+/// Container the agent tried to start when reconciling restart policies at boot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AutostartEntry {
+    pub container_id: String,
+    pub name: String,
+    pub restart_policy: RestartPolicy,
+    /// Why the start failed; unset for containers that started
+    pub error: Option<String>,
+}
+
+/// What the agent started at boot, for GET /api/v1/system/autostart-report
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AutostartReport {
+    /// Whether `autostart_on_boot` was set when the agent started
+    pub enabled: bool,
+    /// Unset until the pass has finished
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Set when the runtime could not be reached or listed, so nothing was attempted
+    pub error: Option<String>,
+    pub started: Vec<AutostartEntry>,
+    pub failed: Vec<AutostartEntry>,
+}
+
+/// What changed when the agent re-read its config file, from POST /api/v1/system/reload-config
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ConfigReloadResult {
+    pub path: String,
+    pub registries_added: Vec<String>,
+    pub registries_removed: Vec<String>,
+    /// Registries whose URL, credentials or TLS setting changed
+    pub registries_updated: Vec<String>,
+    /// Registries that could not be added, with the reason
+    pub registry_errors: Vec<String>,
+    /// Settings that changed in the file but only take effect when the agent restarts
+    pub restart_required: Vec<String>,
+}
@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Order of a repository's tag list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum TagSort {
+    /// Highest version first, after `latest`; tags that are not versions follow by name
+    Semver,
+    /// By name
+    Alpha,
+    /// As the registry lists them
+    #[default]
+    #[serde(rename = "none")]
+    Unsorted,
+}
+
+/// A tag read as a version: `1`, `v1.2`, `1.25.3`, `2.0.0-rc.1`, `1.2.3+build.5`. Up to
+/// four numbers are read, as some images add a build number (`1.2.3.4`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagVersion {
+    numbers: Vec<u64>,
+    /// What follows `-`, as in `1.25-alpine` or `2.0.0-beta.2`; `None` for a release
+    prerelease: Option<String>,
+}
+
+impl TagVersion {
+    pub fn parse(tag: &str) -> Option<Self> {
+        let version = tag.strip_prefix(['v', 'V']).unwrap_or(tag);
+        // Build metadata does not take part in ordering
+        let version = version.split_once('+').map_or(version, |(version, _)| version);
+        let (core, prerelease) = match version.split_once('-') {
+            Some((_, "")) => return None,
+            Some((core, prerelease)) => (core, Some(prerelease.to_string())),
+            None => (version, None),
+        };
+        let numbers = core
+            .split('.')
+            .map(|number| {
+                (!number.is_empty() && number.bytes().all(|byte| byte.is_ascii_digit()))
+                    .then(|| number.parse().ok())
+                    .flatten()
+            })
+            .collect::<Option<Vec<u64>>>()?;
+        (numbers.len() <= 4).then_some(Self { numbers, prerelease })
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        self.prerelease.is_some()
+    }
+}
+
+impl Ord for TagVersion {
+    /// Missing numbers count as 0, and a release is above its prereleases, whose parts
+    /// compare as in semver: numbers numerically and below words, words by name
+    fn cmp(&self, other: &Self) -> Ordering {
+        let length = self.numbers.len().max(other.numbers.len());
+        let number = |version: &Self, index: usize| version.numbers.get(index).copied().unwrap_or(0);
+        (0..length)
+            .map(|index| number(self, index).cmp(&number(other, index)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| match (&self.prerelease, &other.prerelease) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(ours), Some(theirs)) => compare_prereleases(ours, theirs),
+            })
+    }
+}
+
+impl PartialOrd for TagVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn compare_prereleases(ours: &str, theirs: &str) -> Ordering {
+    let mut ours = ours.split('.');
+    let mut theirs = theirs.split('.');
+    loop {
+        let ordering = match (ours.next(), theirs.next()) {
+            (None, None) => return Ordering::Equal,
+            // `rc` is below `rc.1`
+            (None, Some(_)) => Ordering::Less,
+            (Some(_), None) => Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+}
+
+/// Order `tags` as `sort` says
+pub fn sort_tags(tags: &mut [String], sort: TagSort) {
+    match sort {
+        TagSort::Semver => tags.sort_by_cached_key(|tag| {
+            let version = TagVersion::parse(tag).map(std::cmp::Reverse);
+            // `latest` first, versions highest first, then the rest; ties by name
+            (tag != "latest", version.is_none(), version, tag.clone())
+        }),
+        TagSort::Alpha => tags.sort(),
+        TagSort::Unsorted => {}
+    }
+}
+
+/// The highest version among `tags` that is not a prerelease, such as `1.25.3` from
+/// `1.25.3-alpine`, `1.25.3`, `1.26.0-rc.1` and `latest`
+pub fn latest_stable_tag(tags: &[String]) -> Option<&String> {
+    tags.iter()
+        .filter_map(|tag| TagVersion::parse(tag).filter(|version| !version.is_prerelease()).map(|version| (version, tag)))
+        // Of equal versions such as `1.2` and `v1.2.0`, the first by name
+        .max_by(|(a, a_tag), (b, b_tag)| a.cmp(b).then_with(|| b_tag.cmp(a_tag)))
+        .map(|(_, tag)| tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(list: &[&str]) -> Vec<String> {
+        list.iter().map(|tag| tag.to_string()).collect()
+    }
+
+    fn sorted(list: &[&str], sort: TagSort) -> Vec<String> {
+        let mut tags = tags(list);
+        sort_tags(&mut tags, sort);
+        tags
+    }
+
+    #[test]
+    fn versions_parse_with_prefixes_prereleases_and_builds() {
+        let version = |numbers: &[u64], prerelease: Option<&str>| {
+            Some(TagVersion {
+                numbers: numbers.to_vec(),
+                prerelease: prerelease.map(str::to_string),
+            })
+        };
+        assert_eq!(TagVersion::parse("1"), version(&[1], None));
+        assert_eq!(TagVersion::parse("v1.2"), version(&[1, 2], None));
+        assert_eq!(TagVersion::parse("V10.20.30"), version(&[10, 20, 30], None));
+        assert_eq!(TagVersion::parse("1.2.3.4"), version(&[1, 2, 3, 4], None));
+        assert_eq!(TagVersion::parse("2.0.0-rc.1"), version(&[2, 0, 0], Some("rc.1")));
+        assert_eq!(TagVersion::parse("1.25-alpine3.18"), version(&[1, 25], Some("alpine3.18")));
+        assert_eq!(TagVersion::parse("1.2.3+build.5"), version(&[1, 2, 3], None));
+        assert_eq!(TagVersion::parse("1.0.0-beta+exp.sha.5114f85"), version(&[1, 0, 0], Some("beta")));
+        assert_eq!(TagVersion::parse("3.12-slim-bookworm"), version(&[3, 12], Some("slim-bookworm")));
+
+        for tag in [
+            "latest", "", "v", "1.", ".1", "1..2", "1.2.3.4.5", "1.2-", "stable", "alpine", "sha-1a2b3c",
+            "1.2.x", "1.2a", "-1", "vv1", "99999999999999999999999",
+        ] {
+            assert_eq!(TagVersion::parse(tag), None, "{}", tag);
+        }
+    }
+
+    #[test]
+    fn prereleases_order_as_in_semver() {
+        let order = ["1.0.0-alpha", "1.0.0-alpha.1", "1.0.0-alpha.beta", "1.0.0-beta", "1.0.0-beta.2", "1.0.0-beta.11", "1.0.0-rc.1", "1.0.0"];
+        for pair in order.windows(2) {
+            let (lower, higher) = (TagVersion::parse(pair[0]).unwrap(), TagVersion::parse(pair[1]).unwrap());
+            assert!(lower < higher, "{} < {}", pair[0], pair[1]);
+        }
+        assert_eq!(TagVersion::parse("1.2").unwrap().cmp(&TagVersion::parse("1.2.0").unwrap()), Ordering::Equal);
+        assert!(TagVersion::parse("1.10").unwrap() > TagVersion::parse("1.9.9").unwrap());
+    }
+
+    #[test]
+    fn semver_sorts_highest_first_after_latest() {
+        assert_eq!(
+            sorted(&["0.1", "10.2", "2.0", "latest", "2.0.1", "1.9", "1.10"], TagSort::Semver),
+            ["latest", "10.2", "2.0.1", "2.0", "1.10", "1.9", "0.1"]
+        );
+    }
+
+    #[test]
+    fn semver_sorts_real_world_tag_sets() {
+        // nginx: versions with variants, distro names and moving tags
+        assert_eq!(
+            sorted(
+                &["stable", "1.25.3-alpine", "mainline", "1.24", "1.25.3", "alpine", "1.25", "latest", "1.25-alpine", "1.24.0"],
+                TagSort::Semver
+            ),
+            ["latest", "1.25.3", "1.25.3-alpine", "1.25", "1.25-alpine", "1.24", "1.24.0", "alpine", "mainline", "stable"]
+        );
+
+        // Prefixed releases with release candidates and a commit tag
+        assert_eq!(
+            sorted(&["v1.0.0", "v1.1.0-rc.2", "sha-9f8e7d", "v1.1.0-rc.10", "v0.9.0", "v1.1.0", "main"], TagSort::Semver),
+            ["v1.1.0", "v1.1.0-rc.10", "v1.1.0-rc.2", "v1.0.0", "v0.9.0", "main", "sha-9f8e7d"]
+        );
+
+        // Python-style variants and date tags
+        assert_eq!(
+            sorted(&["3.9-slim", "3.12-slim-bookworm", "3.12", "3.11.7", "3", "20240101"], TagSort::Semver),
+            ["20240101", "3.12", "3.12-slim-bookworm", "3.11.7", "3.9-slim", "3"]
+        );
+
+        // Mixed prefixes of the same version keep a stable order
+        assert_eq!(sorted(&["v2", "2.0.0", "2.0"], TagSort::Semver), ["2.0", "2.0.0", "v2"]);
+        assert_eq!(sorted(&[], TagSort::Semver), Vec::<String>::new());
+    }
+
+    #[test]
+    fn alpha_and_unsorted_orders() {
+        let list = ["1.10", "latest", "1.9", "alpine"];
+        assert_eq!(sorted(&list, TagSort::Alpha), ["1.10", "1.9", "alpine", "latest"]);
+        assert_eq!(sorted(&list, TagSort::Unsorted), list);
+    }
+
+    #[test]
+    fn sort_is_read_from_its_query_value() {
+        for (value, sort) in [("semver", TagSort::Semver), ("alpha", TagSort::Alpha), ("none", TagSort::Unsorted)] {
+            assert_eq!(serde_json::from_value::<TagSort>(serde_json::json!(value)).unwrap(), sort);
+            assert_eq!(serde_json::to_value(sort).unwrap(), value);
+        }
+        assert!(serde_json::from_value::<TagSort>(serde_json::json!("unsorted")).is_err());
+    }
+
+    #[test]
+    fn latest_stable_skips_prereleases_and_names() {
+        let stable = |list: &[&str]| latest_stable_tag(&tags(list)).cloned();
+        assert_eq!(stable(&["1.25.3-alpine", "1.25.3", "1.26.0-rc.1", "latest"]), Some("1.25.3".to_string()));
+        assert_eq!(stable(&["v1.0.0", "v1.1.0-beta", "v0.9.0"]), Some("v1.0.0".to_string()));
+        assert_eq!(stable(&["0.1", "10.2", "2.0"]), Some("10.2".to_string()));
+        assert_eq!(stable(&["v1.2.0", "1.2"]), Some("1.2".to_string()));
+        assert_eq!(stable(&["latest", "stable", "2.0.0-rc.1"]), None);
+        assert_eq!(stable(&[]), None);
+    }
+}
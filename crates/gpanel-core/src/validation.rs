@@ -0,0 +1,419 @@
+use serde::{Deserialize, Serialize};
+
+use crate::container::{Container, ContainerStatus, CreateContainerRequest, IsolationLevel, VolumeType};
+use crate::image_ref::ImageRef;
+
+/// Problem with one field of a request. `field` is a path into the request body,
+/// e.g. `ports[0].host_port` or `env.PATH`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Container names follow Bolt's `[a-zA-Z0-9][a-zA-Z0-9_.-]*`, up to 128 characters
+pub fn is_valid_container_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    name.len() <= 128
+        && chars.next().is_some_and(|c| c.is_ascii_alphanumeric())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// Check a create request before it reaches Bolt, returning every problem found
+pub fn validate_create_request(request: &CreateContainerRequest) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    if request.name.as_deref().is_some_and(|name| !is_valid_container_name(name)) {
+        errors.push(FieldError::new(
+            "name",
+            "Use letters, digits, '_', '.' and '-', starting with a letter or digit",
+        ));
+    }
+
+    if request.image.trim().is_empty() {
+        errors.push(FieldError::new("image", "Image is required"));
+    } else if let Err(e) = ImageRef::parse(&request.image) {
+        errors.push(FieldError::new("image", e.to_string()));
+    }
+
+    for (index, port) in request.ports.iter().enumerate() {
+        if port.container_port == 0 {
+            errors.push(FieldError::new(
+                format!("ports[{}].container_port", index),
+                "Container port must be between 1 and 65535",
+            ));
+        }
+        match port.host_port {
+            Some(0) => errors.push(FieldError::new(
+                format!("ports[{}].host_port", index),
+                "Host port must be between 1 and 65535, or empty to pick one automatically",
+            )),
+            Some(host_port) => {
+                // Compared pairwise, as a wildcard address clashes with every other one
+                let mapped_before = request.ports[..index].iter().any(|other| {
+                    other.host_port == Some(host_port)
+                        && other.protocol == port.protocol
+                        && host_ips_overlap(other.host_ip.as_deref(), port.host_ip.as_deref())
+                });
+                if mapped_before {
+                    errors.push(FieldError::new(
+                        format!("ports[{}].host_port", index),
+                        format!("Host port {} is already mapped by another port", host_port),
+                    ));
+                }
+            }
+            None => {}
+        }
+    }
+
+    for (index, volume) in request.volumes.iter().enumerate() {
+        if !volume.target.starts_with('/') {
+            errors.push(FieldError::new(
+                format!("volumes[{}].target", index),
+                "Container path must be absolute",
+            ));
+        }
+        if volume.source.trim().is_empty() && !matches!(volume.volume_type, VolumeType::Tmpfs) {
+            errors.push(FieldError::new(
+                format!("volumes[{}].source", index),
+                "Host path or volume name is required",
+            ));
+        }
+    }
+
+    // Sorted so the errors come back in a stable order
+    let mut keys: Vec<&String> = request.env.keys().collect();
+    keys.sort();
+    for key in keys {
+        if key.is_empty() || key.contains('=') {
+            errors.push(FieldError::new(
+                format!("env.{}", key),
+                "Variable names must not be empty or contain '='",
+            ));
+        }
+    }
+
+    if request.gpu_allocation.as_ref().is_some_and(|gpu| gpu.memory_mb == Some(0)) {
+        errors.push(FieldError::new("gpu_allocation.memory_mb", "GPU memory must be positive"));
+    }
+    if let Some(gpu) = &request.gpu_allocation
+        && let IsolationLevel::Partitioned { partition_id } = &gpu.isolation_level
+        && partition_id.trim().is_empty()
+    {
+        errors.push(FieldError::new("gpu_allocation.isolation_level", "Partition ID must not be empty"));
+    }
+
+    errors
+}
+
+/// Host port in a create request that a running container already binds
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PortConflict {
+    /// Request field holding the port, e.g. `ports[0].host_port`
+    pub field: String,
+    pub host_port: u16,
+    pub protocol: String,
+    pub container_id: String,
+    pub container_name: String,
+}
+
+impl PortConflict {
+    pub fn message(&self) -> String {
+        format!(
+            "Host port {}/{} is already bound by container '{}'",
+            self.host_port, self.protocol, self.container_name
+        )
+    }
+}
+
+/// Result of checking a create request without creating anything
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ContainerValidationResponse {
+    pub valid: bool,
+    pub errors: Vec<FieldError>,
+    pub conflicts: Vec<PortConflict>,
+}
+
+/// An unset or unspecified address binds every interface, so it overlaps any other
+fn host_ips_overlap(a: Option<&str>, b: Option<&str>) -> bool {
+    let wildcard = |ip: Option<&str>| matches!(ip.map(str::trim), None | Some("" | "0.0.0.0" | "::"));
+    wildcard(a) || wildcard(b) || a == b
+}
+
+/// Find requested host ports already bound by running containers. Ports left for Bolt to
+/// pick are never in conflict, and stopped containers hold no ports.
+pub fn find_port_conflicts(request: &CreateContainerRequest, existing: &[Container]) -> Vec<PortConflict> {
+    let bound: Vec<(&Container, &crate::container::PortMapping)> = existing
+        .iter()
+        .filter(|c| matches!(c.status, ContainerStatus::Running | ContainerStatus::Paused | ContainerStatus::Restarting))
+        .flat_map(|c| c.ports.iter().map(move |port| (c, port)))
+        .collect();
+
+    let mut conflicts = Vec::new();
+    for (index, port) in request.ports.iter().enumerate() {
+        let Some(host_port) = port.host_port else {
+            continue;
+        };
+        let clash = bound.iter().find(|(_, other)| {
+            other.host_port == Some(host_port)
+                && other.protocol == port.protocol
+                && host_ips_overlap(other.host_ip.as_deref(), port.host_ip.as_deref())
+        });
+        if let Some((container, _)) = clash {
+            conflicts.push(PortConflict {
+                field: format!("ports[{}].host_port", index),
+                host_port,
+                protocol: port.protocol.to_string(),
+                container_id: container.id.clone(),
+                container_name: container.name.clone(),
+            });
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::{PortMapping, Protocol, RestartPolicy};
+    use std::collections::HashMap;
+
+    fn port(host_port: Option<u16>, protocol: Protocol, host_ip: Option<&str>) -> PortMapping {
+        PortMapping {
+            container_port: 80,
+            host_port,
+            protocol,
+            host_ip: host_ip.map(str::to_string),
+        }
+    }
+
+    fn container(id: &str, status: ContainerStatus, ports: Vec<PortMapping>) -> Container {
+        Container {
+            id: id.to_string(),
+            name: format!("{}-name", id),
+            image: "nginx:latest".to_string(),
+            status,
+            ports,
+            volumes: Vec::new(),
+            networks: Vec::new(),
+            env: HashMap::new(),
+            labels: HashMap::new(),
+            created_at: chrono::Utc::now(),
+            started_at: None,
+            finished_at: None,
+            health_status: None,
+            restart_policy: RestartPolicy::No,
+            gaming_config: None,
+            gpu_allocation: None,
+            performance_metrics: None,
+        }
+    }
+
+    fn request(ports: Vec<PortMapping>) -> CreateContainerRequest {
+        CreateContainerRequest {
+            name: Some("new".to_string()),
+            image: "nginx:latest".to_string(),
+            ports,
+            volumes: Vec::new(),
+            networks: Vec::new(),
+            env: HashMap::new(),
+            labels: HashMap::new(),
+            gaming_config: None,
+            gpu_allocation: None,
+            restart_policy: RestartPolicy::No,
+            health: None,
+        }
+    }
+
+    fn running(ports: Vec<PortMapping>) -> Vec<Container> {
+        vec![container("web", ContainerStatus::Running, ports)]
+    }
+
+    #[test]
+    fn same_port_and_protocol_conflicts() {
+        let existing = running(vec![port(Some(8080), Protocol::Tcp, None)]);
+        let conflicts = find_port_conflicts(&request(vec![port(Some(8080), Protocol::Tcp, None)]), &existing);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].field, "ports[0].host_port");
+        assert_eq!(conflicts[0].protocol, "tcp");
+        assert_eq!(conflicts[0].container_id, "web");
+    }
+
+    #[test]
+    fn different_host_ips_do_not_conflict_unless_one_is_a_wildcard() {
+        let existing = running(vec![port(Some(8080), Protocol::Tcp, Some("127.0.0.1"))]);
+        let other_ip = request(vec![port(Some(8080), Protocol::Tcp, Some("192.168.1.10"))]);
+        assert!(find_port_conflicts(&other_ip, &existing).is_empty());
+
+        for wildcard in [None, Some("0.0.0.0"), Some("::"), Some("")] {
+            let every_interface = request(vec![port(Some(8080), Protocol::Tcp, wildcard)]);
+            assert_eq!(find_port_conflicts(&every_interface, &existing).len(), 1, "{:?}", wildcard);
+        }
+    }
+
+    #[test]
+    fn different_protocols_do_not_conflict() {
+        let existing = running(vec![port(Some(27015), Protocol::Udp, None)]);
+        let tcp = request(vec![port(Some(27015), Protocol::Tcp, None)]);
+        assert!(find_port_conflicts(&tcp, &existing).is_empty());
+        let udp = request(vec![port(Some(27015), Protocol::Udp, None)]);
+        assert_eq!(find_port_conflicts(&udp, &existing).len(), 1);
+    }
+
+    #[test]
+    fn auto_assigned_ports_and_stopped_containers_never_conflict() {
+        let existing = vec![
+            container("web", ContainerStatus::Running, vec![port(None, Protocol::Tcp, None)]),
+            container("old", ContainerStatus::Exited { code: 0 }, vec![port(Some(9000), Protocol::Tcp, None)]),
+        ];
+        let auto = request(vec![port(None, Protocol::Tcp, None)]);
+        assert!(find_port_conflicts(&auto, &existing).is_empty());
+        let stopped_port = request(vec![port(Some(9000), Protocol::Tcp, None)]);
+        assert!(find_port_conflicts(&stopped_port, &existing).is_empty());
+    }
+
+    fn with(configure: impl FnOnce(&mut CreateContainerRequest)) -> Vec<String> {
+        let mut request = request(Vec::new());
+        configure(&mut request);
+        validate_create_request(&request).into_iter().map(|error| error.field).collect()
+    }
+
+    #[test]
+    fn a_plain_request_is_valid() {
+        assert!(with(|_| {}).is_empty());
+        assert!(with(|r| r.name = None).is_empty());
+    }
+
+    #[test]
+    fn names_follow_bolts_rules_up_to_128_characters() {
+        for name in ["web", "web-1.prod_a", "0cache", &"a".repeat(128)] {
+            assert!(with(|r| r.name = Some(name.to_string())).is_empty(), "{}", name);
+        }
+        for name in ["", "-web", ".web", "_web", "web app", "web/1", "wéb", &"a".repeat(129)] {
+            assert_eq!(with(|r| r.name = Some(name.to_string())), ["name"], "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn images_must_be_given_and_parse() {
+        assert_eq!(with(|r| r.image = String::new()), ["image"]);
+        assert_eq!(with(|r| r.image = "   ".to_string()), ["image"]);
+        assert_eq!(with(|r| r.image = "Nginx:latest".to_string()), ["image"]);
+        assert_eq!(with(|r| r.image = "nginx:".to_string()), ["image"]);
+        assert!(with(|r| r.image = "registry.local:5000/team/app:1.2".to_string()).is_empty());
+    }
+
+    #[test]
+    fn port_zero_is_refused() {
+        let mut zero_container = port(Some(8080), Protocol::Tcp, None);
+        zero_container.container_port = 0;
+        assert_eq!(with(|r| r.ports = vec![zero_container]), ["ports[0].container_port"]);
+        assert_eq!(
+            with(|r| r.ports = vec![port(None, Protocol::Tcp, None), port(Some(0), Protocol::Tcp, None)]),
+            ["ports[1].host_port"]
+        );
+    }
+
+    #[test]
+    fn host_ports_mapped_twice_are_refused() {
+        let duplicate = |a: PortMapping, b: PortMapping| with(|r| r.ports = vec![a, b]);
+        assert_eq!(
+            duplicate(port(Some(8080), Protocol::Tcp, None), port(Some(8080), Protocol::Tcp, None)),
+            ["ports[1].host_port"]
+        );
+        // A wildcard address overlaps every specific one
+        assert_eq!(
+            duplicate(port(Some(8080), Protocol::Tcp, None), port(Some(8080), Protocol::Tcp, Some("0.0.0.0"))),
+            ["ports[1].host_port"]
+        );
+        assert_eq!(
+            duplicate(port(Some(8080), Protocol::Tcp, Some("127.0.0.1")), port(Some(8080), Protocol::Tcp, Some("::"))),
+            ["ports[1].host_port"]
+        );
+        assert_eq!(
+            duplicate(port(Some(8080), Protocol::Tcp, Some("127.0.0.1")), port(Some(8080), Protocol::Tcp, None)),
+            ["ports[1].host_port"]
+        );
+
+        let other_ips = duplicate(
+            port(Some(8080), Protocol::Tcp, Some("127.0.0.1")),
+            port(Some(8080), Protocol::Tcp, Some("10.0.0.1")),
+        );
+        assert!(other_ips.is_empty());
+        assert!(duplicate(port(Some(8080), Protocol::Tcp, None), port(Some(8080), Protocol::Udp, None)).is_empty());
+        assert!(duplicate(port(Some(8080), Protocol::Tcp, None), port(Some(8081), Protocol::Tcp, None)).is_empty());
+        assert!(duplicate(port(None, Protocol::Tcp, None), port(None, Protocol::Tcp, None)).is_empty());
+    }
+
+    fn mount(source: &str, target: &str, volume_type: VolumeType) -> crate::container::VolumeMount {
+        crate::container::VolumeMount {
+            source: source.to_string(),
+            target: target.to_string(),
+            read_only: false,
+            volume_type,
+        }
+    }
+
+    #[test]
+    fn volumes_need_an_absolute_target_and_a_source() {
+        assert_eq!(with(|r| r.volumes = vec![mount("/srv", "data", VolumeType::Bind)]), ["volumes[0].target"]);
+        assert_eq!(with(|r| r.volumes = vec![mount(" ", "/data", VolumeType::Volume)]), ["volumes[0].source"]);
+        assert_eq!(with(|r| r.volumes = vec![mount("", "/data", VolumeType::Bind)]), ["volumes[0].source"]);
+        assert!(with(|r| r.volumes = vec![mount("", "/tmp", VolumeType::Tmpfs)]).is_empty());
+        assert!(with(|r| r.volumes = vec![mount("pgdata", "/var/lib/postgresql", VolumeType::Volume)]).is_empty());
+    }
+
+    #[test]
+    fn env_names_must_be_nonempty_without_equals() {
+        let env = |keys: &[&str]| {
+            with(|r| r.env = keys.iter().map(|key| (key.to_string(), "1".to_string())).collect())
+        };
+        assert_eq!(env(&["PATH", "", "A=B"]), ["env.", "env.A=B"]);
+        assert!(env(&["PATH", "lower_case", "_UNDERSCORE"]).is_empty());
+    }
+
+    #[test]
+    fn gpu_allocations_need_memory_and_a_partition() {
+        let gpu = |memory_mb: Option<u64>, isolation_level: IsolationLevel| {
+            with(|r| {
+                r.gpu_allocation = Some(crate::container::GpuAllocation {
+                    device_id: "nvidia0".to_string(),
+                    gpu_type: crate::container::GpuType::Nvidia,
+                    memory_mb,
+                    compute_units: None,
+                    isolation_level,
+                })
+            })
+        };
+        assert_eq!(gpu(Some(0), IsolationLevel::Shared), ["gpu_allocation.memory_mb"]);
+        assert_eq!(
+            gpu(None, IsolationLevel::Partitioned { partition_id: " ".to_string() }),
+            ["gpu_allocation.isolation_level"]
+        );
+        assert!(gpu(Some(2048), IsolationLevel::Partitioned { partition_id: "mig-1g.5gb".to_string() }).is_empty());
+        assert!(gpu(None, IsolationLevel::Exclusive).is_empty());
+    }
+
+    #[test]
+    fn every_problem_is_reported() {
+        let fields = with(|r| {
+            r.name = Some("-bad".to_string());
+            r.image = String::new();
+            r.ports = vec![port(Some(0), Protocol::Tcp, None)];
+            r.env = HashMap::from([("".to_string(), "x".to_string())]);
+        });
+        assert_eq!(fields, ["name", "image", "ports[0].host_port", "env."]);
+    }
+}
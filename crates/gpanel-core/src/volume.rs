@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::container::{Container, VolumeType};
+
+/// Named volume managed by Bolt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Volume {
+    pub name: String,
+    pub driver: String,
+    pub mountpoint: String,
+    pub labels: HashMap<String, String>,
+    /// Size on disk in bytes, when the driver can report it
+    pub size: Option<u64>,
+    pub created: chrono::DateTime<chrono::Utc>,
+    /// Containers mounting this volume (filled in by the agent, not by Bolt)
+    #[serde(default)]
+    pub containers: Vec<String>,
+}
+
+impl Volume {
+    /// Check whether a container mounts this volume
+    pub fn is_mounted_by(&self, container: &Container) -> bool {
+        container
+            .volumes
+            .iter()
+            .any(|mount| matches!(mount.volume_type, VolumeType::Volume) && mount.source == self.name)
+    }
+}
+
+/// Volume creation request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateVolumeRequest {
+    pub name: String,
+    pub driver: Option<String>,
+    #[serde(default)]
+    pub driver_options: HashMap<String, String>,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+}
+
+/// Filters for pruning unused volumes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VolumePruneFilter {
+    /// Only remove volumes carrying this label (`key` or `key=value`)
+    pub label: Option<String>,
+}
+
+impl VolumePruneFilter {
+    /// Check whether a volume is a prune candidate; mounted volumes never are
+    pub fn matches(&self, volume: &Volume, in_use: bool) -> bool {
+        if in_use {
+            return false;
+        }
+        match &self.label {
+            Some(label) => match label.split_once('=') {
+                Some((key, value)) => volume.labels.get(key).map(|v| v == value).unwrap_or(false),
+                None => volume.labels.contains_key(label),
+            },
+            None => true,
+        }
+    }
+}
+
+/// Result of a volume prune
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VolumePruneReport {
+    pub deleted: Vec<String>,
+    pub reclaimed_bytes: u64,
+}
+
+/// Entry in a volume directory listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VolumeEntry {
+    pub name: String,
+    /// Path relative to the volume root, always starting with `/`
+    pub path: String,
+    pub entry_type: VolumeEntryType,
+    pub size: u64,
+    pub modified: chrono::DateTime<chrono::Utc>,
+    /// Unix permission bits
+    pub mode: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum VolumeEntryType {
+    File,
+    Directory,
+    Symlink,
+}
+
+/// Volume names follow Bolt's `[a-zA-Z0-9][a-zA-Z0-9_.-]*`
+pub fn is_valid_volume_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// Resolve a user-supplied path against the volume root
+///
+/// Returns `None` if the path would escape the root through `..`.
+pub fn normalize_volume_path(path: &str) -> Option<String> {
+    if path.contains('\0') {
+        return None;
+    }
+
+    let mut components: Vec<&str> = Vec::new();
+    for component in path.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop()?;
+            }
+            component => components.push(component),
+        }
+    }
+
+    Some(format!("/{}", components.join("/")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn volume_paths_are_rooted_and_normalized() {
+        assert_eq!(normalize_volume_path("").as_deref(), Some("/"));
+        assert_eq!(normalize_volume_path("/").as_deref(), Some("/"));
+        assert_eq!(normalize_volume_path("world/./region//").as_deref(), Some("/world/region"));
+        assert_eq!(normalize_volume_path("/world/region/../level.dat").as_deref(), Some("/world/level.dat"));
+        assert_eq!(normalize_volume_path("/.../x").as_deref(), Some("/.../x"));
+        // Absolute paths name a path inside the volume, never on the host
+        assert_eq!(normalize_volume_path("/etc/passwd").as_deref(), Some("/etc/passwd"));
+        assert_eq!(normalize_volume_path("//etc//passwd").as_deref(), Some("/etc/passwd"));
+    }
+
+    #[test]
+    fn volume_paths_escaping_the_root_are_rejected() {
+        for path in [
+            "..",
+            "/..",
+            "../etc/passwd",
+            "/world/../../etc/passwd",
+            "world/region/../../..",
+            "./../x",
+            "/server.properties\0",
+            "\0",
+            "/world/\0/../x",
+        ] {
+            assert_eq!(normalize_volume_path(path), None, "{:?}", path);
+        }
+    }
+}
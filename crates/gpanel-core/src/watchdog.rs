@@ -0,0 +1,169 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Containers carrying this label set to `true` are supervised by the agent watchdog
+pub const WATCHDOG_LABEL: &str = "gpanel.watchdog";
+
+/// Watchdog restart policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WatchdogConfig {
+    pub enabled: bool,
+    /// Delay before the first restart; doubles with each further attempt
+    pub initial_backoff_secs: u64,
+    pub max_backoff_secs: u64,
+    /// Restarts allowed within the window before the container is treated as crash looping
+    pub max_retries: u32,
+    pub window_secs: u64,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            initial_backoff_secs: 5,
+            max_backoff_secs: 300,
+            max_retries: 5,
+            window_secs: 600,
+        }
+    }
+}
+
+impl WatchdogConfig {
+    /// Delay before restart number `attempt` (starting at 0)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let secs = self
+            .initial_backoff_secs
+            .saturating_mul(1u64.checked_shl(attempt).unwrap_or(u64::MAX))
+            .min(self.max_backoff_secs);
+        Duration::seconds(secs as i64)
+    }
+}
+
+/// Supervision state of one container
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WatchdogState {
+    pub container_id: String,
+    /// Restarts made in the current window
+    pub attempts: u32,
+    pub window_started: Option<DateTime<Utc>>,
+    pub next_retry: Option<DateTime<Utc>>,
+    pub last_exit_code: Option<i32>,
+    pub last_restart: Option<DateTime<Utc>>,
+    /// Retries have stopped until the window passes
+    pub crash_looping: bool,
+}
+
+impl WatchdogState {
+    pub fn new(container_id: &str) -> Self {
+        Self {
+            container_id: container_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Record a crash at `now` and schedule the next restart, or flag a crash loop
+    ///
+    /// Returns `true` if this crash started a crash loop.
+    pub fn record_crash(&mut self, config: &WatchdogConfig, exit_code: Option<i32>, now: DateTime<Utc>) -> bool {
+        let window_expired = self
+            .window_started
+            .map(|started| now - started > Duration::seconds(config.window_secs as i64))
+            .unwrap_or(true);
+        if window_expired {
+            self.attempts = 0;
+            self.window_started = Some(now);
+            self.crash_looping = false;
+        }
+
+        self.last_exit_code = exit_code;
+
+        if self.attempts >= config.max_retries {
+            let started = !self.crash_looping;
+            self.crash_looping = true;
+            self.next_retry = None;
+            return started;
+        }
+
+        self.next_retry = Some(now + config.backoff(self.attempts));
+        false
+    }
+
+    /// Whether a restart is due at `now`
+    pub fn restart_due(&self, now: DateTime<Utc>) -> bool {
+        !self.crash_looping && self.next_retry.map(|next| next <= now).unwrap_or(false)
+    }
+
+    /// Record a restart made at `now`
+    pub fn record_restart(&mut self, now: DateTime<Utc>) {
+        self.attempts += 1;
+        self.last_restart = Some(now);
+        self.next_retry = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> WatchdogConfig {
+        WatchdogConfig {
+            enabled: true,
+            initial_backoff_secs: 5,
+            max_backoff_secs: 60,
+            max_retries: 3,
+            window_secs: 600,
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let config = config();
+        let delays: Vec<i64> = (0..6).map(|attempt| config.backoff(attempt).num_seconds()).collect();
+        assert_eq!(delays, vec![5, 10, 20, 40, 60, 60]);
+        assert_eq!(config.backoff(200).num_seconds(), 60);
+    }
+
+    #[test]
+    fn crashes_schedule_restarts_until_the_retries_run_out() {
+        let config = config();
+        let start = Utc::now();
+        let mut state = WatchdogState::new("web");
+
+        for attempt in 0..config.max_retries {
+            let now = start + Duration::seconds(attempt as i64 * 30);
+            assert!(!state.record_crash(&config, Some(1), now));
+            assert_eq!(state.next_retry, Some(now + config.backoff(attempt)));
+            assert!(!state.restart_due(now));
+            assert!(state.restart_due(now + config.backoff(attempt)));
+            state.record_restart(now + config.backoff(attempt));
+        }
+
+        let now = start + Duration::seconds(120);
+        assert!(state.record_crash(&config, Some(137), now));
+        assert!(state.crash_looping);
+        assert_eq!(state.next_retry, None);
+        assert_eq!(state.last_exit_code, Some(137));
+        // Only the first crash of a loop reports it
+        assert!(!state.record_crash(&config, Some(137), now + Duration::seconds(1)));
+    }
+
+    #[test]
+    fn a_new_window_clears_a_crash_loop() {
+        let config = config();
+        let start = Utc::now();
+        let mut state = WatchdogState::new("web");
+        for _ in 0..config.max_retries {
+            state.record_crash(&config, Some(1), start);
+            state.record_restart(start);
+        }
+        assert!(state.record_crash(&config, Some(1), start));
+
+        let later = start + Duration::seconds(config.window_secs as i64 + 1);
+        assert!(!state.record_crash(&config, Some(1), later));
+        assert!(!state.crash_looping);
+        assert_eq!(state.attempts, 0);
+        assert_eq!(state.next_retry, Some(later + config.backoff(0)));
+    }
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::event::{ContainerEvent, ContainerEventAction};
+
+/// Outbound webhook fired on container events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookConfig {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    /// Key for the HMAC-SHA256 signature header
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// Event actions to deliver; empty means all
+    #[serde(default)]
+    pub events: Vec<ContainerEventAction>,
+    /// Container IDs or names to deliver for; empty means all
+    #[serde(default)]
+    pub containers: Vec<String>,
+    /// Labels the container must carry
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+/// Label opting a container into being recreated when its image is pushed again
+pub const AUTO_UPDATE_LABEL: &str = "gpanel.autoupdate";
+
+/// Push notifications registries send to `POST /api/v1/webhooks/registry`, as the Docker
+/// registry's `notifications` endpoints and Harbor's webhook policies do
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct RegistryWebhookConfig {
+    /// Expected in the notification's `Authorization` header, bare or as
+    /// `Bearer <secret>`; notifications are refused while it is unset
+    pub secret: Option<String>,
+    /// Pull a pushed tag and recreate the containers running it that are labelled
+    /// `gpanel.autoupdate=true`
+    pub auto_update: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Payload shape sent to the webhook target
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum WebhookFormat {
+    /// The raw event as JSON
+    #[default]
+    Json,
+    Discord,
+    Slack,
+}
+
+impl WebhookConfig {
+    /// Check whether an event passes this webhook's filters
+    pub fn matches(&self, event: &ContainerEvent) -> bool {
+        self.enabled
+            && (self.events.is_empty() || self.events.contains(&event.action))
+            && (self.containers.is_empty()
+                || self.containers.iter().any(|c| c == &event.id || c == &event.name))
+            && self
+                .labels
+                .iter()
+                .all(|(key, value)| event.attributes.get(key) == Some(value))
+    }
+}
@@ -0,0 +1,447 @@
+use bytes::Bytes;
+use futures_util::TryStreamExt;
+use gpanel_core::{Error, Result};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+
+use crate::proxy::{ProxyBody, ProxyRequest, ProxyResponse};
+
+/// Time allowed to open a TCP connection to an upstream
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Headers scoped to a single connection, which a proxy must not pass on.
+/// `host` is not hop-by-hop but has to name the upstream, so the client sets it.
+const HOP_BY_HOP: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+    "host",
+];
+
+/// Service the proxy forwards requests to
+#[derive(Debug, Clone)]
+pub struct Upstream {
+    pub name: &'static str,
+    pub base_url: String,
+    /// Time allowed for the upstream to start responding; the body may stream for longer
+    pub timeout: Duration,
+}
+
+/// HTTP/1.1 forwarding that streams bodies in both directions
+pub struct Forwarder {
+    client: reqwest::Client,
+    /// Body bytes forwarded in either direction, counted per chunk without locking
+    bytes_transferred: Arc<AtomicU64>,
+}
+
+impl Forwarder {
+    pub fn new() -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            // Redirects are passed back to the client rather than followed
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| Error::Network(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            bytes_transferred: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    pub fn bytes_transferred(&self) -> u64 {
+        self.bytes_transferred.load(Ordering::Relaxed)
+    }
+
+    /// Forward a request, keeping its method, path, query, headers and body
+    pub async fn forward(&self, upstream: &Upstream, req: ProxyRequest) -> Result<ProxyResponse> {
+        let url = format!("{}{}", upstream.base_url.trim_end_matches('/'), req.path);
+        debug!("Forwarding {} {} to {}", req.method, req.path, upstream.name);
+
+        let method = reqwest::Method::from_bytes(req.method.as_bytes())
+            .map_err(|_| Error::Network(format!("Invalid HTTP method '{}'", req.method)))?;
+
+        let mut builder = self.client.request(method, &url);
+        let listed = connection_listed(&req.headers);
+        for (name, value) in &req.headers {
+            if !is_hop_by_hop(name, &listed) {
+                builder = builder.header(name.as_str(), value.as_str());
+            }
+        }
+
+        let counter = self.bytes_transferred.clone();
+        builder = builder.body(match req.body {
+            ProxyBody::Full(bytes) => {
+                counter.fetch_add(bytes.len() as u64, Ordering::Relaxed);
+                reqwest::Body::from(bytes)
+            }
+            ProxyBody::Stream(stream) => reqwest::Body::wrap_stream(stream.inspect_ok(move |chunk| {
+                counter.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+            })),
+        });
+
+        // The timeout covers the response head only, so long-lived streams stay open
+        let response = tokio::time::timeout(upstream.timeout, builder.send())
+            .await
+            .map_err(|_| {
+                Error::Network(format!(
+                    "{} did not respond within {}s",
+                    upstream.name,
+                    upstream.timeout.as_secs()
+                ))
+            })?
+            .map_err(|e| Error::Network(format!("Failed to reach {}: {}", upstream.name, e)))?;
+
+        let status = response.status().as_u16();
+        let response_headers: Vec<(String, String)> = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.as_str().to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let listed = connection_listed(&response_headers);
+        let mut headers: Vec<(String, String)> = response_headers
+            .into_iter()
+            .filter(|(name, _)| !is_hop_by_hop(name, &listed))
+            .collect();
+
+        // Ask any proxy in front of us not to hold back server-sent events
+        let event_stream = headers
+            .iter()
+            .any(|(name, value)| name == "content-type" && value.starts_with("text/event-stream"));
+        if event_stream {
+            headers.push(("x-accel-buffering".to_string(), "no".to_string()));
+        }
+
+        let counter = self.bytes_transferred.clone();
+        let body = response
+            .bytes_stream()
+            .map_err(std::io::Error::other)
+            .inspect_ok(move |chunk: &Bytes| {
+                counter.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+            });
+
+        Ok(ProxyResponse {
+            status,
+            headers,
+            body: ProxyBody::Stream(Box::pin(body)),
+        })
+    }
+}
+
+/// Header names listed in a `Connection` header, which are hop-by-hop as well
+fn connection_listed(headers: &[(String, String)]) -> Vec<String> {
+    headers
+        .iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("connection"))
+        .flat_map(|(_, value)| value.split(','))
+        .map(|name| name.trim().to_ascii_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+fn is_hop_by_hop(name: &str, listed: &[String]) -> bool {
+    let name = name.to_ascii_lowercase();
+    HOP_BY_HOP.contains(&name.as_str()) || listed.contains(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxy::Protocol;
+    use axum::{
+        body::Body,
+        extract::Request,
+        http::{HeaderMap, StatusCode},
+        response::{IntoResponse, Response},
+        routing::{any, get},
+        Router,
+    };
+    use futures_util::{stream, StreamExt};
+    use std::convert::Infallible;
+    use tokio::sync::mpsc;
+
+    async fn serve(router: Router) -> Upstream {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        Upstream {
+            name: "stub",
+            base_url,
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    fn request(method: &str, path: &str, headers: &[(&str, &str)], body: ProxyBody) -> ProxyRequest {
+        ProxyRequest {
+            method: method.to_string(),
+            path: path.to_string(),
+            headers: headers.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect(),
+            body,
+            protocol: Protocol::Http,
+        }
+    }
+
+    async fn read_body(body: ProxyBody) -> Vec<u8> {
+        match body {
+            ProxyBody::Full(bytes) => bytes.to_vec(),
+            ProxyBody::Stream(stream) => stream
+                .try_fold(Vec::new(), |mut all, chunk| async move {
+                    all.extend_from_slice(&chunk);
+                    Ok(all)
+                })
+                .await
+                .unwrap(),
+        }
+    }
+
+    fn header<'a>(response: &'a ProxyResponse, name: &str) -> Option<&'a str> {
+        response.headers.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Answers with the request's method, URI, headers and body as JSON
+    async fn echo(request: Request) -> Response {
+        let (parts, body) = request.into_parts();
+        let body = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        let headers: serde_json::Map<String, serde_json::Value> = parts
+            .headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_str().unwrap().into()))
+            .collect();
+        let echoed = serde_json::json!({
+            "method": parts.method.as_str(),
+            "uri": parts.uri.to_string(),
+            "headers": headers,
+            "body": String::from_utf8_lossy(&body),
+        });
+        (StatusCode::CREATED, axum::Json(echoed)).into_response()
+    }
+
+    #[tokio::test]
+    async fn method_path_query_and_body_reach_the_upstream() {
+        let upstream = serve(Router::new().route("/*path", any(echo))).await;
+        let forwarder = Forwarder::new().unwrap();
+
+        let response = forwarder
+            .forward(
+                &upstream,
+                request(
+                    "PATCH",
+                    "/api/containers/web?force=true&tail=10",
+                    &[("content-type", "application/json"), ("x-request-id", "abc")],
+                    ProxyBody::Full(Bytes::from_static(b"{\"name\":\"web\"}")),
+                ),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status, 201);
+        let echoed: serde_json::Value = serde_json::from_slice(&read_body(response.body).await).unwrap();
+        assert_eq!(echoed["method"], "PATCH");
+        assert_eq!(echoed["uri"], "/api/containers/web?force=true&tail=10");
+        assert_eq!(echoed["headers"]["x-request-id"], "abc");
+        assert_eq!(echoed["headers"]["content-type"], "application/json");
+        assert_eq!(echoed["body"], "{\"name\":\"web\"}");
+    }
+
+    #[tokio::test]
+    async fn hop_by_hop_headers_are_stripped_both_ways() {
+        let upstream = serve(Router::new().route(
+            "/",
+            any(|headers: HeaderMap| async move {
+                let mut response = echo_headers(&headers).into_response();
+                let out = response.headers_mut();
+                out.insert("connection", "x-upstream-secret".parse().unwrap());
+                out.insert("x-upstream-secret", "1".parse().unwrap());
+                out.insert("proxy-authenticate", "Basic".parse().unwrap());
+                out.insert("x-upstream-kept", "1".parse().unwrap());
+                response
+            }),
+        ))
+        .await;
+        let forwarder = Forwarder::new().unwrap();
+
+        let response = forwarder
+            .forward(
+                &upstream,
+                request(
+                    "GET",
+                    "/",
+                    &[
+                        ("Connection", "X-Secret, keep-alive"),
+                        ("X-Secret", "1"),
+                        ("Keep-Alive", "timeout=5"),
+                        ("Proxy-Authorization", "Basic Zm9vOmJhcg=="),
+                        ("TE", "trailers"),
+                        ("Upgrade", "websocket"),
+                        ("Host", "client.example"),
+                        ("X-Kept", "1"),
+                    ],
+                    ProxyBody::Full(Bytes::new()),
+                ),
+            )
+            .await
+            .unwrap();
+        assert_eq!(header(&response, "x-upstream-kept"), Some("1"));
+        for name in ["connection", "x-upstream-secret", "proxy-authenticate"] {
+            assert_eq!(header(&response, name), None, "{}", name);
+        }
+
+        let seen: serde_json::Value = serde_json::from_slice(&read_body(response.body).await).unwrap();
+        assert_eq!(seen["x-kept"], "1");
+        for name in ["x-secret", "keep-alive", "proxy-authorization", "te", "upgrade"] {
+            assert!(seen.get(name).is_none(), "{} was forwarded", name);
+        }
+        assert_ne!(seen["host"], "client.example");
+    }
+
+    fn echo_headers(headers: &HeaderMap) -> axum::Json<serde_json::Value> {
+        axum::Json(
+            headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_str().unwrap().into()))
+                .collect::<serde_json::Map<_, _>>()
+                .into(),
+        )
+    }
+
+    #[tokio::test]
+    async fn request_bodies_stream_and_are_counted() {
+        let upstream = serve(Router::new().route(
+            "/upload",
+            any(|body: axum::body::Bytes| async move { format!("{} bytes", body.len()) }),
+        ))
+        .await;
+        let forwarder = Forwarder::new().unwrap();
+
+        let chunks = stream::iter(["abc", "defg", "hi"].map(|chunk| Ok(Bytes::from_static(chunk.as_bytes()))));
+        let response = forwarder
+            .forward(&upstream, request("PUT", "/upload", &[], ProxyBody::Stream(Box::pin(chunks))))
+            .await
+            .unwrap();
+        let body = read_body(response.body).await;
+        assert_eq!(body, b"9 bytes");
+        // 9 bytes up, 7 bytes down
+        assert_eq!(forwarder.bytes_transferred(), 16);
+
+        let response = forwarder
+            .forward(&upstream, request("POST", "/upload", &[], ProxyBody::Full(Bytes::from_static(b"12345"))))
+            .await
+            .unwrap();
+        read_body(response.body).await;
+        assert_eq!(forwarder.bytes_transferred(), 16 + 5 + 7);
+    }
+
+    #[tokio::test]
+    async fn event_streams_pass_through_unbuffered() {
+        let (send, receive) = mpsc::channel::<&'static str>(4);
+        let receive = std::sync::Arc::new(tokio::sync::Mutex::new(Some(receive)));
+        let upstream = serve(Router::new().route(
+            "/events",
+            get(move || async move {
+                let receive = receive.lock().await.take().unwrap();
+                let events = stream::unfold(receive, |mut receive| async move {
+                    let event = receive.recv().await?;
+                    Some((Ok::<_, Infallible>(Bytes::from(format!("data: {}\n\n", event))), receive))
+                });
+                ([("content-type", "text/event-stream")], Body::from_stream(events))
+            }),
+        ))
+        .await;
+        let forwarder = Forwarder::new().unwrap();
+
+        let response = forwarder
+            .forward(&upstream, request("GET", "/events", &[], ProxyBody::Full(Bytes::new())))
+            .await
+            .unwrap();
+        assert_eq!(header(&response, "x-accel-buffering"), Some("no"));
+        let ProxyBody::Stream(mut body) = response.body else {
+            panic!("responses are streamed");
+        };
+
+        // Each event arrives while the upstream still holds the stream open
+        for event in ["one", "two"] {
+            send.send(event).await.unwrap();
+            let chunk = tokio::time::timeout(Duration::from_secs(5), body.next())
+                .await
+                .expect("event forwarded before the stream ended")
+                .unwrap()
+                .unwrap();
+            assert_eq!(chunk, format!("data: {}\n\n", event));
+        }
+        drop(send);
+        assert!(body.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn slow_upstreams_time_out_but_slow_bodies_do_not() {
+        let upstream = serve(
+            Router::new()
+                .route(
+                    "/slow-head",
+                    get(|| async {
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        "late"
+                    }),
+                )
+                .route(
+                    "/slow-body",
+                    get(|| async {
+                        let chunks = stream::iter(["a", "b"]).then(|chunk| async move {
+                            tokio::time::sleep(Duration::from_millis(300)).await;
+                            Ok::<_, Infallible>(chunk)
+                        });
+                        Body::from_stream(chunks)
+                    }),
+                ),
+        )
+        .await;
+        let upstream = Upstream {
+            timeout: Duration::from_millis(200),
+            ..upstream
+        };
+        let forwarder = Forwarder::new().unwrap();
+
+        let error = forwarder
+            .forward(&upstream, request("GET", "/slow-head", &[], ProxyBody::Full(Bytes::new())))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("did not respond"), "{}", error);
+
+        let response = forwarder
+            .forward(&upstream, request("GET", "/slow-body", &[], ProxyBody::Full(Bytes::new())))
+            .await
+            .unwrap();
+        assert_eq!(read_body(response.body).await, b"ab");
+    }
+
+    #[tokio::test]
+    async fn upstream_statuses_and_redirects_are_passed_back() {
+        let upstream = serve(
+            Router::new()
+                .route("/missing", get(|| async { (StatusCode::NOT_FOUND, "no such container") }))
+                .route("/moved", get(|| async { axum::response::Redirect::temporary("/elsewhere") })),
+        )
+        .await;
+        let forwarder = Forwarder::new().unwrap();
+
+        let response = forwarder
+            .forward(&upstream, request("GET", "/missing", &[], ProxyBody::Full(Bytes::new())))
+            .await
+            .unwrap();
+        assert_eq!(response.status, 404);
+        assert_eq!(read_body(response.body).await, b"no such container");
+
+        let response = forwarder
+            .forward(&upstream, request("GET", "/moved", &[], ProxyBody::Full(Bytes::new())))
+            .await
+            .unwrap();
+        assert_eq!(response.status, 307);
+        assert_eq!(header(&response, "location"), Some("/elsewhere"));
+    }
+}
@@ -2,9 +2,14 @@ use clap::Parser;
 use gpanel_core::{GhostPanelConfig, Result};
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::signal;
 use tracing::{info, error};
 
+// Nothing routes requests to these until the QUIC and HTTP servers are implemented
+#[allow(dead_code)]
+mod forward;
+#[allow(dead_code)]
 mod proxy;
 mod quic_server;
 mod http_fallback;
@@ -46,6 +51,14 @@ struct Args {
     /// Connection idle timeout in seconds
     #[arg(long, default_value = "300")]
     idle_timeout: u64,
+
+    /// Seconds to wait for Bolt to start responding to a forwarded request
+    #[arg(long, default_value = "30")]
+    bolt_timeout: u64,
+
+    /// Seconds to wait for the agent to start responding to a forwarded request
+    #[arg(long, default_value = "30")]
+    agent_timeout: u64,
 }
 
 #[tokio::main]
@@ -72,10 +85,21 @@ async fn main() -> Result<()> {
         tls_cert_path: args.cert_path.clone(),
         tls_key_path: args.key_path.clone(),
         registries: Vec::new(), // No registries needed for proxy
+        ..GhostPanelConfig::default()
     };
 
     // Create the proxy instance
-    let proxy = Arc::new(GhostProxy::new(config, args.dev_mode, args.max_connections, args.idle_timeout).await?);
+    let proxy = Arc::new(
+        GhostProxy::new(
+            config,
+            args.dev_mode,
+            args.max_connections,
+            args.idle_timeout,
+            Duration::from_secs(args.bolt_timeout),
+            Duration::from_secs(args.agent_timeout),
+        )
+        .await?,
+    );
 
     // Start QUIC/HTTP3 server
     let quic_proxy = proxy.clone();
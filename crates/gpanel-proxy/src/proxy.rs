@@ -1,9 +1,14 @@
-use gpanel_core::{GhostPanelConfig, Result};
+use bytes::Bytes;
+use futures_util::Stream;
+use gpanel_core::{Error, GhostPanelConfig, Result};
 use std::net::SocketAddr;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{info, debug};
 
+use crate::forward::{Forwarder, Upstream};
 use crate::quic_server::QuicProxyServer;
 use crate::http_fallback::HttpFallbackServer;
 
@@ -13,6 +18,9 @@ pub struct GhostProxy {
     quic_server: QuicProxyServer,
     http_server: HttpFallbackServer,
     stats: Arc<RwLock<ProxyStats>>,
+    forwarder: Forwarder,
+    bolt: Upstream,
+    agent: Upstream,
 }
 
 #[derive(Default, Debug, serde::Serialize)]
@@ -31,6 +39,8 @@ impl GhostProxy {
         dev_mode: bool,
         max_connections: usize,
         idle_timeout: u64,
+        bolt_timeout: Duration,
+        agent_timeout: Duration,
     ) -> Result<Self> {
         info!("🔧 Initializing GhostPanel QUIC Proxy");
 
@@ -51,11 +61,25 @@ impl GhostProxy {
             stats.clone(),
         )?;
 
+        let bolt = Upstream {
+            name: "Bolt",
+            base_url: bolt_http_url(&config.bolt_api_url),
+            timeout: bolt_timeout,
+        };
+        let agent = Upstream {
+            name: "agent",
+            base_url: format!("http://localhost:{}", config.agent_port),
+            timeout: agent_timeout,
+        };
+
         Ok(Self {
             config,
             quic_server,
             http_server,
             stats,
+            forwarder: Forwarder::new()?,
+            bolt,
+            agent,
         })
     }
 
@@ -79,7 +103,7 @@ impl GhostProxy {
             total_requests: stats.total_requests,
             quic_requests: stats.quic_requests,
             http_requests: stats.http_requests,
-            bytes_transferred: stats.bytes_transferred,
+            bytes_transferred: stats.bytes_transferred + self.forwarder.bytes_transferred(),
             uptime_seconds: stats.uptime_seconds,
         }
     }
@@ -129,42 +153,27 @@ impl GhostProxy {
 
     async fn handle_container_request(&self, req: ProxyRequest) -> Result<ProxyResponse> {
         debug!("📦 Handling container request: {}", req.path);
-
-        // Forward to Bolt API
-        let bolt_url = format!("{}{}", self.config.bolt_api_url, req.path);
-
-        // Use QUIC client if available, fallback to HTTP
-        match self.forward_to_bolt_quic(&bolt_url, &req).await {
-            Ok(response) => Ok(response),
-            Err(e) => {
-                debug!("QUIC forward failed, trying HTTP: {}", e);
-                self.forward_to_bolt_http(&bolt_url, &req).await
-            }
-        }
+        self.forward_to_bolt(req).await
     }
 
     async fn handle_image_request(&self, req: ProxyRequest) -> Result<ProxyResponse> {
         debug!("🖼️ Handling image request: {}", req.path);
-        let bolt_url = format!("{}{}", self.config.bolt_api_url, req.path);
-        self.forward_to_bolt_quic(&bolt_url, &req).await
+        self.forward_to_bolt(req).await
     }
 
     async fn handle_network_request(&self, req: ProxyRequest) -> Result<ProxyResponse> {
         debug!("🌐 Handling network request: {}", req.path);
-        let bolt_url = format!("{}{}", self.config.bolt_api_url, req.path);
-        self.forward_to_bolt_quic(&bolt_url, &req).await
+        self.forward_to_bolt(req).await
     }
 
     async fn handle_volume_request(&self, req: ProxyRequest) -> Result<ProxyResponse> {
         debug!("💾 Handling volume request: {}", req.path);
-        let bolt_url = format!("{}{}", self.config.bolt_api_url, req.path);
-        self.forward_to_bolt_quic(&bolt_url, &req).await
+        self.forward_to_bolt(req).await
     }
 
     async fn handle_gaming_request(&self, req: ProxyRequest) -> Result<ProxyResponse> {
         debug!("🎮 Handling gaming request: {}", req.path);
-        let bolt_url = format!("{}{}", self.config.bolt_api_url, req.path);
-        self.forward_to_bolt_quic(&bolt_url, &req).await
+        self.forward_to_bolt(req).await
     }
 
     async fn handle_system_request(&self, req: ProxyRequest) -> Result<ProxyResponse> {
@@ -172,12 +181,10 @@ impl GhostProxy {
 
         // Some system requests go to agent service instead of Bolt
         if req.path.starts_with("/api/system/stats") {
-            let agent_url = format!("http://localhost:{}{}", self.config.agent_port, req.path);
-            return self.forward_to_agent(&agent_url, &req).await;
+            return self.forward_to_agent(req).await;
         }
 
-        let bolt_url = format!("{}{}", self.config.bolt_api_url, req.path);
-        self.forward_to_bolt_quic(&bolt_url, &req).await
+        self.forward_to_bolt(req).await
     }
 
     async fn handle_stats_request(&self, _req: ProxyRequest) -> Result<ProxyResponse> {
@@ -189,7 +196,7 @@ impl GhostProxy {
         Ok(ProxyResponse {
             status: 200,
             headers: vec![("content-type".to_string(), "application/json".to_string())],
-            body: response_body,
+            body: ProxyBody::Full(response_body.into()),
         })
     }
 
@@ -204,7 +211,7 @@ impl GhostProxy {
             return Ok(ProxyResponse {
                 status: 200,
                 headers: vec![("content-type".to_string(), "text/html".to_string())],
-                body: html.as_bytes().to_vec(),
+                body: ProxyBody::Full(Bytes::from_static(html.as_bytes())),
             });
         }
 
@@ -212,51 +219,74 @@ impl GhostProxy {
         Ok(ProxyResponse {
             status: 404,
             headers: vec![("content-type".to_string(), "text/plain".to_string())],
-            body: b"Not Found".to_vec(),
+            body: ProxyBody::Full(Bytes::from_static(b"Not Found")),
         })
     }
 
-    async fn forward_to_bolt_quic(&self, url: &str, _req: &ProxyRequest) -> Result<ProxyResponse> {
-        debug!("⚡ Forwarding to Bolt via QUIC: {}", url);
+    /// Forward to Bolt over QUIC, falling back to HTTP/1.1 when QUIC is unavailable
+    async fn forward_to_bolt(&self, req: ProxyRequest) -> Result<ProxyResponse> {
+        match self.forward_to_bolt_quic(&req).await {
+            Ok(response) => Ok(response),
+            Err(e) => {
+                debug!("QUIC forward failed, trying HTTP: {}", e);
+                self.forward_to_bolt_http(req).await
+            }
+        }
+    }
+
+    /// Stub: always fails with [`Error::Quic`] until QUIC forwarding to Bolt is implemented,
+    /// so every request takes the HTTP/1.1 path
+    async fn forward_to_bolt_quic(&self, req: &ProxyRequest) -> Result<ProxyResponse> {
+        debug!("⚡ Forwarding to Bolt via QUIC: {}", req.path);
 
         // TODO: Implement actual QUIC forwarding to Bolt
-        // For now, return a mock response
-        Ok(ProxyResponse {
-            status: 200,
-            headers: vec![("content-type".to_string(), "application/json".to_string())],
-            body: br#"{"status": "forwarded_via_quic", "original_url": ""}"#.to_vec(),
-        })
+        Err(Error::Quic("QUIC forwarding to Bolt is not implemented yet".to_string()))
     }
 
-    async fn forward_to_bolt_http(&self, url: &str, _req: &ProxyRequest) -> Result<ProxyResponse> {
-        debug!("🔄 Forwarding to Bolt via HTTP: {}", url);
+    async fn forward_to_bolt_http(&self, req: ProxyRequest) -> Result<ProxyResponse> {
+        debug!("🔄 Forwarding to Bolt via HTTP: {}", req.path);
+        self.forwarder.forward(&self.bolt, req).await
+    }
 
-        // TODO: Implement HTTP forwarding to Bolt as fallback
-        Ok(ProxyResponse {
-            status: 200,
-            headers: vec![("content-type".to_string(), "application/json".to_string())],
-            body: br#"{"status": "forwarded_via_http", "original_url": ""}"#.to_vec(),
-        })
+    async fn forward_to_agent(&self, req: ProxyRequest) -> Result<ProxyResponse> {
+        debug!("🔧 Forwarding to Agent: {}", req.path);
+        self.forwarder.forward(&self.agent, req).await
     }
+}
 
-    async fn forward_to_agent(&self, url: &str, _req: &ProxyRequest) -> Result<ProxyResponse> {
-        debug!("🔧 Forwarding to Agent: {}", url);
+/// HTTP base URL for the Bolt API, which is configured with a `bolt://` scheme
+fn bolt_http_url(bolt_api_url: &str) -> String {
+    match bolt_api_url.strip_prefix("bolt://") {
+        Some(rest) => format!("http://{}", rest),
+        None => bolt_api_url.to_string(),
+    }
+}
 
-        // TODO: Implement forwarding to agent service
-        Ok(ProxyResponse {
-            status: 200,
-            headers: vec![("content-type".to_string(), "application/json".to_string())],
-            body: br#"{"status": "forwarded_to_agent", "original_url": ""}"#.to_vec(),
-        })
+/// Byte stream for a request or response body
+pub type BodyStream = Pin<Box<dyn Stream<Item = std::io::Result<Bytes>> + Send>>;
+
+/// Message body, either already in memory or streamed as it arrives
+pub enum ProxyBody {
+    Full(Bytes),
+    Stream(BodyStream),
+}
+
+impl std::fmt::Debug for ProxyBody {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProxyBody::Full(bytes) => write!(f, "Full({} bytes)", bytes.len()),
+            ProxyBody::Stream(_) => write!(f, "Stream"),
+        }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct ProxyRequest {
     pub method: String,
+    /// Path including any query string
     pub path: String,
     pub headers: Vec<(String, String)>,
-    pub body: Vec<u8>,
+    pub body: ProxyBody,
     pub protocol: Protocol,
 }
 
@@ -266,9 +296,9 @@ pub enum Protocol {
     Http,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct ProxyResponse {
     pub status: u16,
     pub headers: Vec<(String, String)>,
-    pub body: Vec<u8>,
+    pub body: ProxyBody,
 }
\ No newline at end of file
@@ -5,14 +5,15 @@ use leptos_router::*;
 use crate::auth::{AuthProvider, AuthContext};
 use crate::pages::{
     dashboard::Dashboard,
-    containers::ContainerList,
+    containers::{ContainerDetails, ContainerList},
     images::ImageList,
     networks::NetworkList,
-    volumes::VolumeList,
+    volumes::{VolumeDetails, VolumeList},
     gaming::GamingDashboard,
     login::LoginPage,
     settings::SettingsPage,
     registries::RegistryManagement,
+    audit::AuditLog,
 };
 use crate::components::layout::Layout;
 
@@ -163,7 +164,7 @@ pub fn AuthGuard() -> impl IntoView {
 
                     // Container Management
                     <Route path="/containers" view=ContainerList/>
-                    <Route path="/containers/:id" view=|| view! { <div>"Container Details"</div> }/>
+                    <Route path="/containers/:id" view=ContainerDetails/>
 
                     // Image Management
                     <Route path="/images" view=ImageList/>
@@ -179,7 +180,7 @@ pub fn AuthGuard() -> impl IntoView {
 
                     // Volume Management
                     <Route path="/volumes" view=VolumeList/>
-                    <Route path="/volumes/:id" view=|| view! { <div>"Volume Details"</div> }/>
+                    <Route path="/volumes/:name" view=VolumeDetails/>
 
                     // Gaming Features
                     <Route path="/gaming" view=GamingDashboard/>
@@ -191,6 +192,7 @@ pub fn AuthGuard() -> impl IntoView {
                     <Route path="/settings" view=SettingsPage/>
                     <Route path="/users" view=|| view! { <div>"User Management"</div> }/>
                     <Route path="/logs" view=|| view! { <div>"System Logs"</div> }/>
+                    <Route path="/audit" view=AuditLog/>
 
                     // Catch-all 404
                     <Route path="/*any" view=|| view! {
@@ -1,10 +1,19 @@
 pub mod oidc;
 
+use gloo_storage::{LocalStorage, Storage};
+use gpanel_core::Role;
 use leptos::*;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 pub use oidc::*;
 
+/// How long before a session token expires to refresh it
+const REFRESH_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+/// localStorage key holding the signed-in session, so a reload keeps it
+const SESSION_STORAGE_KEY: &str = "gpanel.session";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     pub id: String,
@@ -13,10 +22,20 @@ pub struct User {
     pub roles: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+/// Session as kept in localStorage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredSession {
+    user: User,
+    token: String,
+    expires_at: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy)]
 pub struct AuthContext {
     pub user: RwSignal<Option<User>>,
     pub token: RwSignal<Option<String>>,
+    /// When the session token expires, in milliseconds since the epoch; `None` for API tokens
+    pub expires_at: RwSignal<Option<f64>>,
 }
 
 impl AuthContext {
@@ -24,27 +43,115 @@ impl AuthContext {
         Self {
             user: create_rw_signal(None),
             token: create_rw_signal(None),
+            expires_at: create_rw_signal(None),
         }
     }
 
+    /// Context holding the session saved by an earlier visit, unless it has expired
+    pub fn restore() -> Self {
+        let context = Self::new();
+        match LocalStorage::get::<StoredSession>(SESSION_STORAGE_KEY) {
+            Ok(session) if session.expires_at.is_none_or(|at| at > js_sys::Date::now()) => {
+                context.user.set(Some(session.user));
+                context.token.set(Some(session.token));
+                context.expires_at.set(session.expires_at);
+            }
+            Ok(_) => LocalStorage::delete(SESSION_STORAGE_KEY),
+            Err(_) => {}
+        }
+        context
+    }
+
     pub fn is_authenticated(&self) -> bool {
         self.user.get().is_some() && self.token.get().is_some()
     }
 
-    pub fn login(&self, user: User, token: String) {
+    /// Whether the signed-in user has `role` or a higher one. Reactive, so views can
+    /// hide or disable actions with it; the agent enforces the same rule.
+    pub fn has_role(&self, role: Role) -> bool {
+        self.user.with(|user| user.as_ref().is_some_and(|user| role.granted_by(&user.roles)))
+    }
+
+    /// Store a signed-in user; `expires_in` is the session token's lifetime in seconds
+    pub fn login(&self, user: User, token: String, expires_in: Option<u64>) {
+        let expires_at = expires_in.map(|secs| js_sys::Date::now() + secs as f64 * 1000.0);
+        let stored = StoredSession {
+            user: user.clone(),
+            token: token.clone(),
+            expires_at,
+        };
+        let _ = LocalStorage::set(SESSION_STORAGE_KEY, &stored);
+
         self.user.set(Some(user));
         self.token.set(Some(token));
+        self.expires_at.set(expires_at);
     }
 
+    /// Clear the session here and end it on the agent
     pub fn logout(&self) {
+        if let Some(token) = self.token.get_untracked() {
+            spawn_local(async move { crate::services::logout(&token).await });
+        }
+        self.clear();
+    }
+
+    /// Forget the session without telling the agent, e.g. once it has refused the token
+    pub fn clear(&self) {
+        LocalStorage::delete(SESSION_STORAGE_KEY);
         self.user.set(None);
         self.token.set(None);
+        self.expires_at.set(None);
+    }
+
+    /// Swap the session token for a fresh one, signing out if the agent refuses
+    async fn refresh(self) {
+        match crate::services::refresh().await {
+            Ok(session) => self.login(
+                User {
+                    id: session.user.id,
+                    username: session.user.username,
+                    email: session.user.email,
+                    roles: session.user.roles,
+                },
+                session.token,
+                Some(session.expires_in),
+            ),
+            Err(message) => {
+                web_sys::console::warn_1(&format!("Session refresh failed, signing out: {}", message).into());
+                self.clear();
+            }
+        }
+    }
+}
+
+/// Time until the session should be refreshed: `REFRESH_MARGIN` before expiry, or
+/// halfway there for sessions shorter than twice the margin
+fn refresh_delay(expires_at: f64) -> Duration {
+    let remaining = Duration::from_millis((expires_at - js_sys::Date::now()).max(0.0) as u64);
+    if remaining > REFRESH_MARGIN * 2 {
+        remaining - REFRESH_MARGIN
+    } else {
+        remaining / 2
     }
 }
 
 #[component]
 pub fn AuthProvider(children: Children) -> impl IntoView {
-    let auth_context = AuthContext::new();
+    let auth_context = AuthContext::restore();
+    crate::services::api_client::use_auth(auth_context);
     provide_context(auth_context);
+
+    // Refresh each session token ahead of its expiry
+    create_effect(move |previous: Option<Option<TimeoutHandle>>| {
+        if let Some(Some(handle)) = previous {
+            handle.clear();
+        }
+        let expires_at = auth_context.expires_at.get()?;
+        set_timeout_with_handle(
+            move || spawn_local(auth_context.refresh()),
+            refresh_delay(expires_at),
+        )
+        .ok()
+    });
     children()
 }
\ No newline at end of file
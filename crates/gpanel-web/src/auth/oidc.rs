@@ -1,9 +1,14 @@
+use gpanel_core::SessionResponse;
 use leptos::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::User;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OidcConfig {
+    /// Name of the provider in the agent's `auth.oidc` config
+    pub name: String,
     pub provider: OidcProvider,
     pub client_id: String,
     pub client_secret: Option<String>, // Not used in frontend
@@ -156,14 +161,17 @@ impl OidcService {
         if let Ok(storage) = window().session_storage() {
             if let Some(storage) = storage {
                 let _ = storage.set_item("oidc_state", &state);
-                let _ = storage.set_item("oidc_provider", &serde_json::to_string(&provider.provider).unwrap_or_default());
+                let _ = storage.set_item("oidc_provider", &provider.name);
             }
         }
 
         provider.provider.get_auth_url(&provider.client_id, &redirect_uri, &state)
     }
 
-    pub async fn handle_callback(&self, code: &str, state: &str) -> Result<UserInfo, String> {
+    /// Exchange the code through the agent; returns the signed-in user and their session token
+    pub async fn handle_callback(&self, code: &str, state: &str) -> Result<(User, String), String> {
+        let mut provider = None;
+
         // Validate state
         if let Ok(storage) = window().session_storage() {
             if let Some(storage) = storage {
@@ -175,14 +183,21 @@ impl OidcService {
                 } else {
                     return Err("No state found in session".to_string());
                 }
+                provider = storage.get_item("oidc_provider").ok().flatten();
+                let _ = storage.remove_item("oidc_provider");
             }
         }
 
+        // Must match the redirect_uri the authorization request was sent with
+        let redirect_uri = format!("{}/auth/callback", window().location().origin().unwrap());
+
         // Exchange code for tokens via backend
-        let response = gloo_net::http::Request::post("/api/auth/oidc/callback")
+        let response = gloo_net::http::Request::post(&crate::services::api_client::url("/api/auth/oidc/callback"))
             .json(&serde_json::json!({
                 "code": code,
-                "state": state
+                "state": state,
+                "provider": provider,
+                "redirect_uri": redirect_uri,
             }))
             .map_err(|e| format!("Request error: {}", e))?
             .send()
@@ -190,15 +205,21 @@ impl OidcService {
             .map_err(|e| format!("Network error: {}", e))?;
 
         if !response.ok() {
-            return Err(format!("Authentication failed: {}", response.status()));
+            return Err(format!("Authentication failed: {}", crate::services::error_message(&response).await));
         }
 
-        let user_info: UserInfo = response
+        let session: SessionResponse = response
             .json()
             .await
             .map_err(|e| format!("Failed to parse response: {}", e))?;
 
-        Ok(user_info)
+        let user = User {
+            id: session.user.id,
+            username: session.user.username,
+            email: session.user.email,
+            roles: session.user.roles,
+        };
+        Ok((user, session.token))
     }
 }
 
@@ -1,5 +1,9 @@
 use leptos::*;
 use leptos_router::*;
+use gpanel_core::{EnvironmentStatus, Role};
+
+use crate::auth::AuthContext;
+use crate::services::api_client;
 
 #[component]
 pub fn Layout(children: Children) -> impl IntoView {
@@ -18,6 +22,8 @@ pub fn Layout(children: Children) -> impl IntoView {
 
 #[component]
 pub fn Sidebar() -> impl IntoView {
+    let auth_context = use_context::<AuthContext>().expect("AuthContext must be provided");
+
     view! {
         <div class="sidebar">
             <div class="sidebar-header">
@@ -33,6 +39,9 @@ pub fn Sidebar() -> impl IntoView {
                 <A href="/volumes" class="nav-item">"💾 Volumes"</A>
                 <A href="/gaming" class="nav-item">"🎮 Gaming"</A>
                 <A href="/settings" class="nav-item">"⚙️ Settings"</A>
+                {move || auth_context.has_role(Role::Admin).then(|| view! {
+                    <A href="/audit" class="nav-item">"📜 Audit Log"</A>
+                })}
             </nav>
         </div>
     }
@@ -40,10 +49,70 @@ pub fn Sidebar() -> impl IntoView {
 
 #[component]
 pub fn Header() -> impl IntoView {
+    let (environments, set_environments) = create_signal(Vec::<EnvironmentStatus>::new());
+
+    // A stored choice the agent no longer has is dropped, going back to its default
+    create_effect(move |_| {
+        spawn_local(async move {
+            let Ok(response) = api_client::get("/api/v1/environments").send().await else {
+                return;
+            };
+            let Ok(list) = response.json::<Vec<EnvironmentStatus>>().await else {
+                return;
+            };
+            if let Some(selected) = api_client::environment() {
+                if !list.iter().any(|environment| environment.name == selected) {
+                    api_client::set_environment(None);
+                    let _ = window().location().reload();
+                }
+            }
+            set_environments.set(list);
+        });
+    });
+
+    // Every page loads its data on mount, so switching reloads to fetch it from the new host
+    let switch_environment = move |name: String| {
+        let default = environments
+            .get_untracked()
+            .into_iter()
+            .find(|environment| environment.default)
+            .map(|environment| environment.name);
+        api_client::set_environment((Some(&name) != default.as_ref()).then_some(name.as_str()));
+        let _ = window().location().reload();
+    };
+
     view! {
         <div class="header">
             <h1>"Container Management"</h1>
             <div class="header-actions">
+                {move || {
+                    let list = environments.get();
+                    (list.len() > 1).then(|| {
+                        let selected = api_client::environment()
+                            .or_else(|| list.iter().find(|environment| environment.default).map(|environment| environment.name.clone()))
+                            .unwrap_or_default();
+                        view! {
+                            <select
+                                title="Bolt host"
+                                style="margin-right: 10px;"
+                                on:change=move |ev| switch_environment(event_target_value(&ev))
+                            >
+                                {list.into_iter().map(|environment| {
+                                    let label = format!(
+                                        "{} {}",
+                                        if environment.reachable { "🟢" } else { "🔴" },
+                                        environment.name
+                                    );
+                                    view! {
+                                        <option value=environment.name.clone() selected=environment.name == selected>
+                                            {label}
+                                        </option>
+                                    }
+                                }).collect_view()}
+                            </select>
+                        }
+                    })
+                }}
                 <button class="btn-primary">"New Container"</button>
             </div>
         </div>
@@ -0,0 +1,181 @@
+use futures::future::{AbortHandle, Abortable};
+use futures::{SinkExt, StreamExt};
+use gloo_net::websocket::{futures::WebSocket, Message};
+use leptos::*;
+
+use crate::services::api_client;
+
+/// Characters of output kept on screen before the oldest are dropped
+const SCROLLBACK_CHARS: usize = 200_000;
+
+/// Approximate cell size of the terminal font, used to size the remote TTY
+const CELL_WIDTH_PX: f64 = 8.4;
+const CELL_HEIGHT_PX: f64 = 17.0;
+
+/// Minimal terminal for a container shell: renders output as text and sends key presses.
+/// The connection opens on mount and closes when the component is removed.
+#[component]
+pub fn Terminal(#[prop(into)] container_id: String) -> impl IntoView {
+    let (screen, set_screen) = create_signal(String::new());
+    let (status, set_status) = create_signal("Connecting...".to_string());
+    let (connected, set_connected) = create_signal(false);
+    let input = store_value(None::<futures::channel::mpsc::UnboundedSender<Message>>);
+    let screen_ref = create_node_ref::<html::Pre>();
+
+    let path = format!("/api/v1/containers/{}/terminal", container_id);
+    match WebSocket::open(&api_client::websocket_url(&path)) {
+        Ok(socket) => {
+            let (mut writer, mut reader) = socket.split();
+            let (sender, mut outgoing) = futures::channel::mpsc::unbounded::<Message>();
+            input.set_value(Some(sender));
+
+            let (abort, registration) = AbortHandle::new_pair();
+            let session = async move {
+                // Size the remote TTY to the element before any output arrives
+                if let Some(pre) = screen_ref.get_untracked() {
+                    let cols = (pre.client_width() as f64 / CELL_WIDTH_PX).max(20.0) as u16;
+                    let rows = (pre.client_height() as f64 / CELL_HEIGHT_PX).max(5.0) as u16;
+                    let resize = serde_json::json!({ "type": "resize", "cols": cols, "rows": rows });
+                    let _ = writer.send(Message::Text(resize.to_string())).await;
+                }
+
+                let forward = async move {
+                    while let Some(message) = outgoing.next().await {
+                        if writer.send(message).await.is_err() {
+                            break;
+                        }
+                    }
+                };
+                let render = async move {
+                    set_connected.set(true);
+                    set_status.set("Connected".to_string());
+                    while let Some(message) = reader.next().await {
+                        let data = match message {
+                            Ok(Message::Bytes(bytes)) => String::from_utf8_lossy(&bytes).into_owned(),
+                            Ok(Message::Text(text)) => text,
+                            Err(e) => {
+                                set_status.set(format!("Disconnected: {}", e));
+                                break;
+                            }
+                        };
+                        set_screen.update(|screen| apply_output(screen, &data));
+                        if let Some(pre) = screen_ref.get_untracked() {
+                            pre.set_scroll_top(pre.scroll_height());
+                        }
+                    }
+                    set_connected.set(false);
+                    set_status.update(|status| {
+                        if *status == "Connected" {
+                            *status = "Session ended".to_string();
+                        }
+                    });
+                };
+                futures::future::select(Box::pin(forward), Box::pin(render)).await;
+            };
+            spawn_local(async move {
+                let _ = Abortable::new(session, registration).await;
+            });
+            // Aborting drops the socket, which closes it and ends the shell on the agent
+            on_cleanup(move || abort.abort());
+        }
+        Err(e) => set_status.set(format!("❌ Failed to connect: {}", e)),
+    }
+
+    let on_key = move |ev: ev::KeyboardEvent| {
+        if ev.meta_key() {
+            return;
+        }
+        let Some(bytes) = key_bytes(&ev.key(), ev.ctrl_key()) else {
+            return;
+        };
+        ev.prevent_default();
+        input.with_value(|sender| {
+            if let Some(sender) = sender {
+                let _ = sender.unbounded_send(Message::Bytes(bytes));
+            }
+        });
+    };
+
+    view! {
+        <div>
+            <div style="color: #bbb; font-size: 12px; margin-bottom: 6px;">
+                {move || status.get()} " · click the terminal to type"
+            </div>
+            <pre
+                node_ref=screen_ref
+                tabindex="0"
+                on:keydown=on_key
+                style="background: #0d1117; color: #e6edf3; height: 420px; overflow-y: auto; margin: 0; padding: 10px; font-family: monospace; font-size: 14px; line-height: 17px; white-space: pre-wrap; word-break: break-all; outline: none;"
+            >
+                {move || screen.get()}
+                {move || connected.get().then(|| view! { <span style="background: #e6edf3;">" "</span> })}
+            </pre>
+        </div>
+    }
+}
+
+/// Bytes a terminal sends for a key press, or None for keys it ignores
+fn key_bytes(key: &str, ctrl: bool) -> Option<Vec<u8>> {
+    let sequence: &str = match key {
+        "Enter" => "\r",
+        "Backspace" => "\x7f",
+        "Tab" => "\t",
+        "Escape" => "\x1b",
+        "ArrowUp" => "\x1b[A",
+        "ArrowDown" => "\x1b[B",
+        "ArrowRight" => "\x1b[C",
+        "ArrowLeft" => "\x1b[D",
+        "Home" => "\x1b[H",
+        "End" => "\x1b[F",
+        "Delete" => "\x1b[3~",
+        _ => {
+            let mut chars = key.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                // Named keys such as Shift or F1
+                return None;
+            };
+            if ctrl && c.is_ascii_alphabetic() {
+                return Some(vec![c.to_ascii_lowercase() as u8 & 0x1f]);
+            }
+            return Some(c.to_string().into_bytes());
+        }
+    };
+    Some(sequence.as_bytes().to_vec())
+}
+
+/// Apply terminal output to the screen text. Handles newlines and backspace; other
+/// ANSI escape sequences are skipped rather than interpreted.
+fn apply_output(screen: &mut String, data: &str) {
+    let mut chars = data.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' | '\x07' => {}
+            '\x08' => {
+                if !screen.ends_with('\n') {
+                    screen.pop();
+                }
+            }
+            '\x1b' => {
+                // CSI sequences run to a final byte in '@'..='~'; others are two characters
+                if chars.next_if_eq(&'[').is_some() {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                } else {
+                    chars.next();
+                }
+            }
+            c => screen.push(c),
+        }
+    }
+
+    if screen.len() > SCROLLBACK_CHARS {
+        let mut cut = screen.len() - SCROLLBACK_CHARS;
+        while !screen.is_char_boundary(cut) {
+            cut += 1;
+        }
+        screen.drain(..cut);
+    }
+}
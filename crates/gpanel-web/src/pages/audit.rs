@@ -0,0 +1,237 @@
+use leptos::*;
+use gpanel_core::{AuditOutcome, AuditPage};
+
+use crate::services::{api_client, error_message};
+
+/// Events shown per page
+const PAGE_SIZE: usize = 50;
+
+/// Query string for the current filters; `since` is a `YYYY-MM-DD` date from the date picker
+fn audit_query(user: &str, action: &str, since: &str, offset: usize) -> String {
+    let mut query = format!("limit={}&offset={}", PAGE_SIZE, offset);
+    if !user.trim().is_empty() {
+        query.push_str(&format!("&user={}", urlencoding::encode(user.trim())));
+    }
+    if !action.trim().is_empty() {
+        query.push_str(&format!("&action={}", urlencoding::encode(action.trim())));
+    }
+    if !since.is_empty() {
+        query.push_str(&format!("&since={}", urlencoding::encode(&format!("{}T00:00:00Z", since))));
+    }
+    query
+}
+
+fn outcome_color(outcome: AuditOutcome) -> &'static str {
+    match outcome {
+        AuditOutcome::Success => "#27ae60",
+        AuditOutcome::Denied => "#f39c12",
+        AuditOutcome::Failure => "#e74c3c",
+    }
+}
+
+#[component]
+pub fn AuditLog() -> impl IntoView {
+    let (page, set_page) = create_signal(None::<AuditPage>);
+    let (offset, set_offset) = create_signal(0usize);
+    let (user_filter, set_user_filter) = create_signal(String::new());
+    let (action_filter, set_action_filter) = create_signal(String::new());
+    let (since_filter, set_since_filter) = create_signal(String::new());
+    let (loading, set_loading) = create_signal(false);
+    let (error_message_signal, set_error_message) = create_signal(None::<String>);
+
+    // Reload whenever the page changes; filters apply on Search
+    create_effect(move |_| {
+        let offset = offset.get();
+        let query = audit_query(
+            &user_filter.get_untracked(),
+            &action_filter.get_untracked(),
+            &since_filter.get_untracked(),
+            offset,
+        );
+        spawn_local(async move {
+            set_loading.set(true);
+            match api_client::get(&format!("/api/v1/audit?{}", query)).send().await {
+                Ok(response) if response.ok() => match response.json::<AuditPage>().await {
+                    Ok(audit_page) => {
+                        set_error_message.set(None);
+                        set_page.set(Some(audit_page));
+                    }
+                    Err(e) => set_error_message.set(Some(format!("❌ Unexpected response: {}", e))),
+                },
+                Ok(response) => {
+                    set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
+                }
+                Err(e) => {
+                    set_error_message.set(Some(format!("❌ Failed to load audit log: {}", e)));
+                }
+            }
+            set_loading.set(false);
+        });
+    });
+
+    // Back to the first page, which reloads with the new filters
+    let search = move || {
+        set_offset.set(0);
+    };
+
+    let clear_filters = move |_| {
+        set_user_filter.set(String::new());
+        set_action_filter.set(String::new());
+        set_since_filter.set(String::new());
+        set_offset.set(0);
+    };
+
+    let has_previous = move || offset.get() > 0;
+    let has_next = move || {
+        page.get()
+            .map(|page| page.offset + page.events.len() < page.total)
+            .unwrap_or(false)
+    };
+
+    let input_style = "width: 100%; padding: 10px; border: 1px solid #555; border-radius: 4px; background-color: #2c3e50; color: white;";
+
+    view! {
+        <div class="audit-log">
+            <div class="header-section">
+                <h2>"Audit Log"</h2>
+                <p>"Every change made through the agent API, including refused and failed requests"</p>
+            </div>
+
+            {move || error_message_signal.get().map(|message| view! {
+                <div style="background-color: #e74c3c; color: white; padding: 10px; border-radius: 4px; margin-bottom: 20px;">
+                    {message}
+                    <button style="float: right; background: none; border: none; color: white; cursor: pointer;"
+                            on:click=move |_| set_error_message.set(None)>
+                        "×"
+                    </button>
+                </div>
+            })}
+
+            <div class="container-card" style="margin-bottom: 20px;">
+                <div style="display: grid; grid-template-columns: 1fr 1fr 1fr auto auto; gap: 15px; align-items: end;">
+                    <div>
+                        <label style="display: block; margin-bottom: 5px;">"User"</label>
+                        <input
+                            type="text"
+                            placeholder="admin"
+                            style=input_style
+                            prop:value=move || user_filter.get()
+                            on:input=move |ev| set_user_filter.set(event_target_value(&ev))
+                            on:keydown=move |ev| {
+                                if ev.key() == "Enter" {
+                                    search();
+                                }
+                            }
+                        />
+                    </div>
+                    <div>
+                        <label style="display: block; margin-bottom: 5px;">"Action"</label>
+                        <input
+                            type="text"
+                            placeholder="containers.delete or containers."
+                            style=input_style
+                            prop:value=move || action_filter.get()
+                            on:input=move |ev| set_action_filter.set(event_target_value(&ev))
+                            on:keydown=move |ev| {
+                                if ev.key() == "Enter" {
+                                    search();
+                                }
+                            }
+                        />
+                    </div>
+                    <div>
+                        <label style="display: block; margin-bottom: 5px;">"Since"</label>
+                        <input
+                            type="date"
+                            style=input_style
+                            prop:value=move || since_filter.get()
+                            on:input=move |ev| set_since_filter.set(event_target_value(&ev))
+                        />
+                    </div>
+                    <button class="btn-primary" style="padding: 10px 20px;" on:click=move |_| search() disabled=loading>
+                        "Search"
+                    </button>
+                    <button class="btn-primary" style="padding: 10px 20px;" on:click=clear_filters disabled=loading>
+                        "Clear"
+                    </button>
+                </div>
+            </div>
+
+            <div class="container-card">
+                <div style="display: flex; justify-content: space-between; align-items: center;">
+                    <span style="color: #bbb;">
+                        {move || match page.get() {
+                            Some(page) if page.total > 0 => format!(
+                                "Showing {}-{} of {} events",
+                                page.offset + 1,
+                                page.offset + page.events.len(),
+                                page.total
+                            ),
+                            Some(_) => "No matching events".to_string(),
+                            None => "Loading...".to_string(),
+                        }}
+                    </span>
+                    <div style="display: flex; gap: 10px;">
+                        <button
+                            class="btn-primary"
+                            on:click=move |_| set_offset.update(|offset| *offset = offset.saturating_sub(PAGE_SIZE))
+                            disabled=move || loading.get() || !has_previous()
+                        >
+                            "← Newer"
+                        </button>
+                        <button
+                            class="btn-primary"
+                            on:click=move |_| set_offset.update(|offset| *offset += PAGE_SIZE)
+                            disabled=move || loading.get() || !has_next()
+                        >
+                            "Older →"
+                        </button>
+                    </div>
+                </div>
+
+                <table style="width: 100%; margin-top: 15px; border-collapse: collapse; font-size: 14px;">
+                    <thead>
+                        <tr style="text-align: left; color: #bbb; border-bottom: 1px solid #4a5568;">
+                            <th style="padding: 8px;">"Time"</th>
+                            <th style="padding: 8px;">"User"</th>
+                            <th style="padding: 8px;">"Action"</th>
+                            <th style="padding: 8px;">"Resource"</th>
+                            <th style="padding: 8px;">"Outcome"</th>
+                            <th style="padding: 8px;">"Detail"</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || {
+                            page.get()
+                                .map(|page| page.events)
+                                .unwrap_or_default()
+                                .into_iter()
+                                .map(|event| view! {
+                                    <tr style="border-bottom: 1px solid #34495e;">
+                                        <td style="padding: 8px; white-space: nowrap;">
+                                            {event.timestamp.format("%Y-%m-%d %H:%M:%S").to_string()}
+                                        </td>
+                                        <td style="padding: 8px;">{event.user.clone()}</td>
+                                        <td style="padding: 8px;"><code>{event.action.clone()}</code></td>
+                                        <td style="padding: 8px;">
+                                            {event.resource_id.clone().map(|id| view! { <code>{id}</code> })}
+                                        </td>
+                                        <td style="padding: 8px;">
+                                            <span style=format!(
+                                                "background-color: {}; padding: 2px 6px; border-radius: 3px; font-size: 12px;",
+                                                outcome_color(event.outcome)
+                                            )>
+                                                {format!("{:?} ({})", event.outcome, event.status)}
+                                            </span>
+                                        </td>
+                                        <td style="padding: 8px; color: #bbb;">{event.detail.clone().unwrap_or_default()}</td>
+                                    </tr>
+                                })
+                                .collect_view()
+                        }}
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
@@ -1,188 +1,18 @@
 use leptos::*;
-use serde::{Deserialize, Serialize};
-use gloo_net::http::Request;
-use crate::pages::registries::{RegistryConfig, ImageInfo};
-
-/// Container status enum for UI
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub enum ContainerStatus {
-    Created,
-    Running,
-    Paused,
-    Restarting,
-    Exited { code: i32 },
-    Dead,
-    Unknown,
-}
-
-impl std::fmt::Display for ContainerStatus {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ContainerStatus::Created => write!(f, "Created"),
-            ContainerStatus::Running => write!(f, "Running"),
-            ContainerStatus::Paused => write!(f, "Paused"),
-            ContainerStatus::Restarting => write!(f, "Restarting"),
-            ContainerStatus::Exited { code } => write!(f, "Exited ({})", code),
-            ContainerStatus::Dead => write!(f, "Dead"),
-            ContainerStatus::Unknown => write!(f, "Unknown"),
-        }
-    }
-}
-
-/// Port mapping for containers
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PortMapping {
-    pub container_port: u16,
-    pub host_port: Option<u16>,
-    pub protocol: String,
-    pub host_ip: Option<String>,
-}
-
-/// Volume mount for containers
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct VolumeMount {
-    pub source: String,
-    pub target: String,
-    pub read_only: bool,
-    pub volume_type: String,
-}
-
-/// Gaming configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GamingConfig {
-    pub proton_version: Option<String>,
-    pub wine_version: Option<String>,
-    pub steam_app_id: Option<u32>,
-    pub optimization_profile: String,
-}
-
-/// GPU allocation
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GpuAllocation {
-    pub device_id: String,
-    pub gpu_type: String,
-    pub memory_mb: Option<u64>,
-    pub compute_units: Option<u32>,
-    pub isolation_level: String,
-}
-
-/// Performance metrics
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PerformanceMetrics {
-    pub cpu_usage: f64,
-    pub memory_usage: MemoryUsage,
-    pub gpu_usage: Option<GpuUsage>,
-    pub network_io: NetworkIo,
-    pub disk_io: DiskIo,
-    pub gaming_metrics: Option<GamingMetrics>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MemoryUsage {
-    pub used_mb: u64,
-    pub limit_mb: u64,
-    pub percentage: f64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GpuUsage {
-    pub utilization: f64,
-    pub memory_used_mb: u64,
-    pub memory_total_mb: u64,
-    pub temperature: Option<f32>,
-    pub power_usage: Option<f32>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct NetworkIo {
-    pub rx_bytes: u64,
-    pub tx_bytes: u64,
-    pub rx_packets: u64,
-    pub tx_packets: u64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DiskIo {
-    pub read_bytes: u64,
-    pub write_bytes: u64,
-    pub read_ops: u64,
-    pub write_ops: u64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GamingMetrics {
-    pub fps: Option<f32>,
-    pub frame_time_ms: Option<f32>,
-    pub input_latency_ms: Option<f32>,
-    pub network_latency_ms: Option<f32>,
-    pub gpu_temperature: Option<f32>,
-}
-
-/// Container model for UI
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Container {
-    pub id: String,
-    pub name: String,
-    pub image: String,
-    pub status: ContainerStatus,
-    pub ports: Vec<PortMapping>,
-    pub volumes: Vec<VolumeMount>,
-    pub networks: Vec<String>,
-    pub env: std::collections::HashMap<String, String>,
-    pub labels: std::collections::HashMap<String, String>,
-    pub created_at: chrono::DateTime<chrono::Utc>,
-    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
-    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
-    pub gaming_config: Option<GamingConfig>,
-    pub gpu_allocation: Option<GpuAllocation>,
-    pub performance_metrics: Option<PerformanceMetrics>,
-}
-
-/// Container list response
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ContainerListResponse {
-    pub containers: Vec<Container>,
-}
-
-/// Container operation request
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ContainerOperationRequest {
-    pub action: String,
-    pub timeout: Option<u32>,
-    pub force: Option<bool>,
-    pub remove_volumes: Option<bool>,
-}
-
-/// Container creation request (matches gpanel-core structure)
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ContainerCreateRequest {
-    pub name: Option<String>,
-    pub image: String,
-    pub ports: Vec<PortMapping>,
-    pub volumes: Vec<VolumeMount>,
-    pub networks: Vec<String>,
-    pub env: std::collections::HashMap<String, String>,
-    pub labels: std::collections::HashMap<String, String>,
-    pub gaming_config: Option<GamingConfig>,
-    pub gpu_allocation: Option<GpuAllocation>,
-    pub restart_policy: RestartPolicy,
-}
-
-/// Restart policy enum
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum RestartPolicy {
-    No,
-    Always,
-    UnlessStopped,
-    OnFailure,
-}
-
-/// Operation result response
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OperationResult {
-    pub success: bool,
-    pub message: String,
-}
+use leptos_router::*;
+use futures::future::{AbortHandle, Abortable};
+use futures::StreamExt;
+use gloo_net::eventsource::futures::EventSource;
+use crate::auth::AuthContext;
+use crate::components::terminal::Terminal;
+use crate::services::{api_client, api_error, error_message};
+use crate::utils::stats::{ChartPoint, StatsHistory};
+use gpanel_core::{
+    Container, ContainerBatchAction, ContainerBatchRequest, ContainerBatchResult, ContainerCommitRequest, ContainerEvent, ContainerEventAction, ContainerListResponse, ContainerOperationRequest, ContainerPruneReport, ContainerStats, ContainerStatus, ContainerTop, ContainerValidationResponse, CreateContainerRequest, CronErrorDetails, FieldError, GamingConfig, GamingProfile, GpuAllocation, GpuDevice, HealthCheck, HealthState, ImageInfo, ImageRef, ImageSummary, InstalledProton, KillContainerRequest, IsolationLevel, OperationResult, PortMapping, ProjectListResponse, ProjectSummary, Protocol, ProtonVersions, RegistryConfigResponse, RegistryListResponse, RenameContainerRequest, RestartPolicy, Role, Schedule, ScheduleAction, ScheduleListResponse, ScheduleRequest, SteamApp, ValidationErrorDetails, VolumeMount, VolumeType, PROJECT_LABEL, short_id,
+};
+
+/// Samples kept in the live stats chart
+const STATS_HISTORY_POINTS: usize = 60;
 
 /// Format file size in human readable format
 fn format_size(size: u64) -> String {
@@ -221,11 +51,70 @@ fn format_uptime(started_at: Option<chrono::DateTime<chrono::Utc>>) -> String {
 
 #[component]
 pub fn ContainerList() -> impl IntoView {
+    let auth_context = use_context::<AuthContext>().expect("AuthContext must be provided");
+    let can_operate = move || auth_context.has_role(Role::Operator);
     let (containers, set_containers) = create_signal(Vec::<Container>::new());
     let (loading, set_loading) = create_signal(true);
-    let (error_message, set_error_message) = create_signal(None::<String>);
+    let (error_message_signal, set_error_message) = create_signal(None::<String>);
     let (selected_container, set_selected_container) = create_signal(None::<Container>);
     let (show_logs, set_show_logs) = create_signal(false);
+    let (log_tail, set_log_tail) = create_signal("100".to_string());
+    let (follow_logs, set_follow_logs) = create_signal(false);
+    let log_follow = store_value(None::<EventStream>);
+    let logs_ref = create_node_ref::<html::Div>();
+
+    // Replacing or clearing `log_follow` closes the previous EventSource
+    let start_follow = move |container_id: String| {
+        let tail = log_tail.get_untracked();
+        let query = if tail == "all" { "all=true".to_string() } else { format!("tail={}", tail) };
+        let url = format!("/api/v1/containers/{}/logs/stream?{}", container_id, query);
+
+        let mut source = match EventSource::new(&api_client::authorized_url(&url)) {
+            Ok(source) => source,
+            Err(e) => {
+                set_container_logs.set(format!("Error following logs: {:?}", e));
+                set_follow_logs.set(false);
+                return;
+            }
+        };
+        let (Ok(lines), Ok(ends)) = (source.subscribe("message"), source.subscribe("end")) else {
+            set_container_logs.set("Error following logs".to_string());
+            set_follow_logs.set(false);
+            return;
+        };
+
+        let (abort, registration) = AbortHandle::new_pair();
+        log_follow.set_value(Some(EventStream { _source: source, abort }));
+        set_container_logs.set(String::new());
+
+        let follow = async move {
+            let mut events = futures::stream::select(lines, ends);
+            while let Some(event) = events.next().await {
+                let Ok((kind, message)) = event else {
+                    set_container_logs.update(|logs| logs.push_str("--- Connection lost ---\n"));
+                    break;
+                };
+                let data = message.data().as_string().unwrap_or_default();
+                if kind == "end" {
+                    set_container_logs.update(|logs| logs.push_str(&format!("--- {} ---\n", data)));
+                    break;
+                }
+
+                set_container_logs.update(|logs| {
+                    logs.push_str(&data);
+                    logs.push('\n');
+                });
+                if let Some(div) = logs_ref.get_untracked() {
+                    div.set_scroll_top(div.scroll_height());
+                }
+            }
+            set_follow_logs.set(false);
+            log_follow.set_value(None);
+        };
+        spawn_local(async move {
+            let _ = Abortable::new(follow, registration).await;
+        });
+    };
     let (container_logs, set_container_logs) = create_signal(String::new());
     let (show_create_wizard, set_show_create_wizard) = create_signal(false);
 
@@ -236,20 +125,44 @@ pub fn ContainerList() -> impl IntoView {
         });
     });
 
-    // Auto-refresh every 5 seconds
+    // Apply container events as they arrive rather than polling the whole list
     create_effect(move |_| {
-        let interval = set_interval(
-            move || {
-                spawn_local(async move {
-                    load_containers(set_containers, set_loading, set_error_message).await;
-                });
-            },
-            std::time::Duration::from_secs(5),
-        );
+        let mut source = match EventSource::new(&api_client::authorized_url("/api/v1/events")) {
+            Ok(source) => source,
+            Err(e) => {
+                set_error_message.set(Some(format!("❌ Failed to subscribe to container events: {:?}", e)));
+                return;
+            }
+        };
+        let (Ok(changes), Ok(resyncs)) = (source.subscribe("container"), source.subscribe("resync")) else {
+            return;
+        };
 
-        on_cleanup(move || {
-            clear_interval(interval);
+        let (abort, registration) = AbortHandle::new_pair();
+        let stream = EventStream { _source: source, abort };
+
+        let apply = async move {
+            let mut events = futures::stream::select(changes, resyncs);
+            while let Some(event) = events.next().await {
+                let change = match &event {
+                    Ok((kind, message)) if kind == "container" => message
+                        .data()
+                        .as_string()
+                        .and_then(|data| serde_json::from_str::<ContainerEvent>(&data).ok()),
+                    _ => None,
+                };
+                match change {
+                    Some(change) => apply_container_event(change, set_containers).await,
+                    // Missed events, or a dropped connection the browser is reopening
+                    None => load_containers(set_containers, set_loading, set_error_message).await,
+                }
+            }
+        };
+        spawn_local(async move {
+            let _ = Abortable::new(apply, registration).await;
         });
+
+        on_cleanup(move || drop(stream));
     });
 
     let container_operation = move |container_id: String, action: String| {
@@ -264,9 +177,11 @@ pub fn ContainerList() -> impl IntoView {
             };
 
             let url = match action.as_str() {
-                "start" => format!("http://localhost:8000/api/v1/containers/{}/start", container_id),
-                "stop" => format!("http://localhost:8000/api/v1/containers/{}/stop", container_id),
-                "restart" => format!("http://localhost:8000/api/v1/containers/{}/restart", container_id),
+                "start" => format!("/api/v1/containers/{}/start", container_id),
+                "stop" => format!("/api/v1/containers/{}/stop", container_id),
+                "restart" => format!("/api/v1/containers/{}/restart", container_id),
+                "pause" => format!("/api/v1/containers/{}/pause", container_id),
+                "unpause" => format!("/api/v1/containers/{}/unpause", container_id),
                 _ => {
                     set_error_message.set(Some(format!("Unknown action: {}", action)));
                     set_loading.set(false);
@@ -274,23 +189,25 @@ pub fn ContainerList() -> impl IntoView {
                 }
             };
 
-            match Request::post(&url)
+            match api_client::post(&url)
                 .json(&request)
                 .unwrap()
                 .send()
                 .await
             {
-                Ok(response) => {
-                    if let Ok(result) = response.json::<OperationResult>().await {
-                        if result.success {
-                            set_error_message.set(Some(format!("✅ {}", result.message)));
-                            // Refresh container list
-                            load_containers(set_containers, set_loading, set_error_message).await;
-                        } else {
-                            set_error_message.set(Some(format!("❌ {}", result.message)));
-                        }
-                    }
+                Ok(response) if !response.ok() => {
+                    set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
                 }
+                Ok(response) => match response.json::<OperationResult>().await {
+                    Ok(result) => {
+                        set_error_message.set(Some(format!("✅ {}", result.message)));
+                        // Refresh container list
+                        load_containers(set_containers, set_loading, set_error_message).await;
+                    }
+                    Err(_) => {
+                        set_error_message.set(Some(format!("❌ Request failed with status {}", response.status())));
+                    }
+                },
                 Err(e) => {
                     set_error_message.set(Some(format!("❌ Operation failed: {}", e)));
                 }
@@ -299,65 +216,629 @@ pub fn ContainerList() -> impl IntoView {
         });
     };
 
-    let show_container_logs = move |container: Container| {
+    let send_signal = move |container_id: String, signal: String| {
         spawn_local(async move {
-            set_selected_container.set(Some(container.clone()));
-            set_show_logs.set(true);
+            set_loading.set(true);
+
+            let url = format!("/api/v1/containers/{}/kill", container_id);
+            match api_client::post(&url)
+                .json(&KillContainerRequest { signal: signal.clone() })
+                .unwrap()
+                .send()
+                .await
+            {
+                Ok(response) if !response.ok() => {
+                    set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
+                }
+                Ok(response) => match response.json::<OperationResult>().await {
+                    Ok(result) => {
+                        set_error_message.set(Some(format!("✅ {}", result.message)));
+                        load_containers(set_containers, set_loading, set_error_message).await;
+                    }
+                    Err(_) => {
+                        set_error_message.set(Some(format!("❌ Request failed with status {}", response.status())));
+                    }
+                },
+                Err(e) => {
+                    set_error_message.set(Some(format!("❌ Failed to send signal: {}", e)));
+                }
+            }
+            set_loading.set(false);
+        });
+    };
 
-            let url = format!("http://localhost:8000/api/v1/containers/{}/logs", container.id);
+    let (selected_ids, set_selected_ids) = create_signal(std::collections::HashSet::<String>::new());
 
-            match Request::get(&url).send().await {
-                Ok(response) => {
-                    if let Ok(logs) = response.text().await {
-                        set_container_logs.set(logs);
-                    } else {
-                        set_container_logs.set("Failed to load logs".to_string());
+    let run_batch = move |action: ContainerBatchAction| {
+        let request = ContainerBatchRequest {
+            action,
+            ids: selected_ids.get_untracked().into_iter().collect(),
+            timeout: Some(30),
+            force: false,
+        };
+
+        spawn_local(async move {
+            set_loading.set(true);
+
+            match api_client::post("/api/v1/containers/batch")
+                .json(&request)
+                .unwrap()
+                .send()
+                .await
+            {
+                Ok(response) if response.ok() => match response.json::<Vec<ContainerBatchResult>>().await {
+                    Ok(results) => {
+                        let failures: Vec<String> = results
+                            .iter()
+                            .filter(|result| !result.success)
+                            .map(|result| format!("{}: {}", result.id, result.message))
+                            .collect();
+                        let succeeded = results.len() - failures.len();
+                        set_error_message.set(Some(if failures.is_empty() {
+                            format!("✅ {:?} succeeded on {} containers", action, succeeded)
+                        } else {
+                            format!("❌ {} succeeded, {} failed\n{}", succeeded, failures.len(), failures.join("\n"))
+                        }));
+                        set_selected_ids.set(std::collections::HashSet::new());
+                        load_containers(set_containers, set_loading, set_error_message).await;
                     }
+                    Err(e) => {
+                        set_error_message.set(Some(format!("❌ Failed to parse batch results: {}", e)));
+                    }
+                },
+                Ok(response) => set_error_message.set(Some(format!("❌ {}", error_message(&response).await))),
+                Err(e) => {
+                    set_error_message.set(Some(format!("❌ Batch operation failed: {}", e)));
                 }
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let (show_prune_confirm, set_show_prune_confirm) = create_signal(false);
+
+    let prune_stopped = move || {
+        set_show_prune_confirm.set(false);
+        spawn_local(async move {
+            set_loading.set(true);
+
+            match api_client::post("/api/v1/containers/prune").send().await {
+                Ok(response) if response.ok() => match response.json::<ContainerPruneReport>().await {
+                    Ok(report) => {
+                        set_error_message.set(Some(format!("✅ Pruned {} stopped containers", report.reclaimed_count)));
+                        load_containers(set_containers, set_loading, set_error_message).await;
+                    }
+                    Err(e) => {
+                        set_error_message.set(Some(format!("❌ Failed to parse prune report: {}", e)));
+                    }
+                },
+                Ok(response) => set_error_message.set(Some(format!("❌ {}", error_message(&response).await))),
                 Err(e) => {
-                    set_container_logs.set(format!("Error loading logs: {}", e));
+                    set_error_message.set(Some(format!("❌ Prune failed: {}", e)));
                 }
             }
+            set_loading.set(false);
         });
     };
 
-    view! {
-        <div class="container-list">
-            <div class="header-section">
-                <h2>"Containers"</h2>
-                <p>"Manage your Bolt containers with advanced monitoring and gaming features"</p>
-                <div style="display: flex; gap: 10px;">
+    let rename_container = move |container_id: String, name: String| {
+        spawn_local(async move {
+            set_loading.set(true);
+
+            let url = format!("/api/v1/containers/{}/rename", container_id);
+            match api_client::post(&url)
+                .json(&RenameContainerRequest { name: name.clone() })
+                .unwrap()
+                .send()
+                .await
+            {
+                Ok(response) if !response.ok() => {
+                    set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
+                }
+                Ok(response) => match response.json::<OperationResult>().await {
+                    Ok(result) => {
+                        set_error_message.set(Some(format!("✅ {}", result.message)));
+                        load_containers(set_containers, set_loading, set_error_message).await;
+                    }
+                    Err(_) => {
+                        set_error_message.set(Some(format!("❌ Request failed with status {}", response.status())));
+                    }
+                },
+                Err(e) => {
+                    set_error_message.set(Some(format!("❌ Failed to rename container: {}", e)));
+                }
+            }
+            set_loading.set(false);
+        });
+    };
+
+    // Commit to image dialog
+    let (commit_target, set_commit_target) = create_signal(None::<Container>);
+    let (commit_repository, set_commit_repository) = create_signal(String::new());
+    let (commit_tag, set_commit_tag) = create_signal("latest".to_string());
+    let (commit_comment, set_commit_comment) = create_signal(String::new());
+    let (commit_pause, set_commit_pause) = create_signal(true);
+
+    let open_commit = move |container: Container| {
+        // Suggest the container's image repository without its tag
+        let repository = container.image.rsplit_once(':').map(|(repo, _)| repo).unwrap_or(&container.image);
+        set_commit_repository.set(repository.to_string());
+        set_commit_tag.set("latest".to_string());
+        set_commit_comment.set(String::new());
+        set_commit_pause.set(true);
+        set_commit_target.set(Some(container));
+    };
+
+    let commit_container = move || {
+        let Some(container) = commit_target.get_untracked() else {
+            return;
+        };
+        set_commit_target.set(None);
+        let request = ContainerCommitRequest {
+            repository: commit_repository.get_untracked().trim().to_string(),
+            tag: Some(commit_tag.get_untracked().trim().to_string()).filter(|tag| !tag.is_empty()),
+            comment: Some(commit_comment.get_untracked()).filter(|comment| !comment.trim().is_empty()),
+            pause: Some(commit_pause.get_untracked()),
+        };
+
+        spawn_local(async move {
+            set_loading.set(true);
+
+            let url = format!("/api/v1/containers/{}/commit", container.id);
+            match api_client::post(&url).json(&request).unwrap().send().await {
+                Ok(response) if response.ok() => match response.json::<ImageSummary>().await {
+                    Ok(image) => set_error_message.set(Some(format!(
+                        "✅ Committed {} as {} ({})",
+                        container.name,
+                        image.repo_tags.join(", "),
+                        image.id.trim_start_matches("sha256:").chars().take(12).collect::<String>()
+                    ))),
+                    Err(e) => set_error_message.set(Some(format!("❌ Failed to parse commit result: {}", e))),
+                },
+                Ok(response) => set_error_message.set(Some(format!("❌ {}", error_message(&response).await))),
+                Err(e) => set_error_message.set(Some(format!("❌ Commit failed: {}", e))),
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let show_container_logs = move |container: Container| {
+        spawn_local(async move {
+            set_selected_container.set(Some(container.clone()));
+            set_show_logs.set(true);
+            set_follow_logs.set(false);
+            log_follow.set_value(None);
+            load_logs(container.id, log_tail.get_untracked(), set_container_logs).await;
+        });
+    };
+
+    // One card per container, shared by the flat and grouped views
+    let container_card = move |container: Container| {
+        let container_for_start = container.clone();
+        let container_for_stop = container.clone();
+        let container_for_restart = container.clone();
+        let container_for_pause = container.clone();
+        let container_for_resume = container.clone();
+        let container_for_signal = container.clone();
+        let (signal, set_signal) = create_signal("SIGHUP".to_string());
+        let (renaming, set_renaming) = create_signal(false);
+        let (new_name, set_new_name) = create_signal(container.name.clone());
+        let container_id = container.id.clone();
+        let container_name = container.name.clone();
+        let select_id = container.id.clone();
+        let checked_id = container.id.clone();
+        let container_for_logs = container.clone();
+        let container_for_commit = container.clone();
+
+        view! {
+            <div class="container-card" style="background-color: #2c3e50; border-radius: 8px; padding: 20px; border: 1px solid #4a5568;">
+                // Container header
+                <div style="display: flex; justify-content: space-between; align-items: center; margin-bottom: 15px;">
+                    <div>
+                        <h3 style="margin: 0; color: #3498db; display: flex; align-items: center; gap: 10px;">
+                            <input
+                                type="checkbox"
+                                title="Select for bulk actions"
+                                prop:checked=move || selected_ids.with(|ids| ids.contains(&checked_id))
+                                on:change=move |ev| {
+                                    let checked = event_target_checked(&ev);
+                                    set_selected_ids.update(|ids| {
+                                        if checked {
+                                            ids.insert(select_id.clone());
+                                        } else {
+                                            ids.remove(&select_id);
+                                        }
+                                    });
+                                }
+                            />
+                            {move || if renaming.get() {
+                                let container_id = container_id.clone();
+                                view! {
+                                    <input
+                                        type="text"
+                                        style="padding: 4px; font-size: 14px;"
+                                        prop:value=new_name
+                                        on:input=move |ev| set_new_name.set(event_target_value(&ev))
+                                    />
+                                    <button
+                                        class="btn-success"
+                                        style="padding: 4px 10px; font-size: 12px;"
+                                        on:click=move |_| {
+                                            set_renaming.set(false);
+                                            rename_container(container_id.clone(), new_name.get_untracked());
+                                        }
+                                        disabled=move || loading.get()
+                                    >
+                                        "Save"
+                                    </button>
+                                    <button
+                                        class="btn-primary"
+                                        style="padding: 4px 10px; font-size: 12px; background-color: #6c757d;"
+                                        on:click=move |_| set_renaming.set(false)
+                                    >
+                                        "Cancel"
+                                    </button>
+                                }.into_view()
+                            } else {
+                                view! {
+                                    <A href=format!("/containers/{}", container_id)>{container_name.clone()}</A>
+                                    <button
+                                        title="Rename"
+                                        style="background: none; border: none; color: #888; cursor: pointer; font-size: 14px;"
+                                        on:click=move |_| set_renaming.set(true)
+                                    >
+                                        "✏️"
+                                    </button>
+                                }.into_view()
+                            }}
+                            <span class=format!(
+                                "status-badge status-{}",
+                                match container.status {
+                                    ContainerStatus::Running => "running",
+                                    ContainerStatus::Exited { .. } => "stopped",
+                                    ContainerStatus::Paused => "paused",
+                                    _ => "unknown"
+                                }
+                            )>
+                                {container.status.to_string()}
+                            </span>
+                            {container.health_status.as_ref().map(|health| {
+                                let (label, color) = match health.state {
+                                    HealthState::Starting => ("starting".to_string(), "#f39c12"),
+                                    HealthState::Healthy => ("healthy".to_string(), "#2ecc71"),
+                                    HealthState::Unhealthy => (format!("unhealthy ({} failed)", health.failing_streak), "#e74c3c"),
+                                };
+                                view! {
+                                    <span
+                                        class="status-badge"
+                                        style=format!("background-color: {}; color: white;", color)
+                                    >
+                                        {label}
+                                    </span>
+                                }
+                            })}
+                            {container.gaming_config.as_ref().map(|_| view! {
+                                <span class="gaming-badge">"GAMING"</span>
+                            })}
+                            {container.gpu_allocation.as_ref().map(|_| view! {
+                                <span class="gpu-indicator">"GPU"</span>
+                            })}
+                        </h3>
+                        <p style="margin: 5px 0; color: #bbb; font-size: 14px;">{&container.image}</p>
+                    </div>
+                    <div style="font-size: 12px; color: #888; text-align: right;">
+                        <div>
+                            <strong>"ID: "</strong>
+                            <code style="background-color: #1a1a1a; padding: 2px 4px; border-radius: 2px;">
+                                {short_id(&container.id).to_string()}
+                            </code>
+                        </div>
+                        <div style="margin-top: 4px;">
+                            <strong>"Uptime: "</strong>
+                            {format_uptime(container.started_at)}
+                        </div>
+                    </div>
+                </div>
+
+                // Container details
+                <div style="display: grid; grid-template-columns: 1fr 1fr; gap: 15px; margin-bottom: 15px;">
+                    <div>
+                        <h4 style="margin: 0 0 8px 0; color: #fff; font-size: 14px;">"Configuration"</h4>
+
+                        // Ports
+                        {if !container.ports.is_empty() {
+                            view! {
+                                <div style="margin-bottom: 8px;">
+                                    <strong>"Ports: "</strong>
+                                    <span style="color: #3498db;">
+                                        {container.ports.iter().map(|port| {
+                                            format!("{}:{}",
+                                                port.host_port.map(|p| p.to_string()).unwrap_or_else(|| "auto".to_string()),
+                                                port.container_port)
+                                        }).collect::<Vec<_>>().join(", ")}
+                                    </span>
+                                </div>
+                            }.into_view()
+                        } else {
+                            view! { <div></div> }.into_view()
+                        }}
+
+                        // Networks
+                        {if !container.networks.is_empty() {
+                            view! {
+                                <div style="margin-bottom: 8px;">
+                                    <strong>"Networks: "</strong>
+                                    <span style="color: #27ae60;">
+                                        {container.networks.join(", ")}
+                                    </span>
+                                </div>
+                            }.into_view()
+                        } else {
+                            view! { <div></div> }.into_view()
+                        }}
+
+                        // Gaming config
+                        {if let Some(gaming) = &container.gaming_config {
+                            view! {
+                                <div style="margin-bottom: 8px;">
+                                    <strong>"Proton: "</strong>
+                                    <span style="color: #9b59b6;">
+                                        {gaming.proton_version.as_ref().unwrap_or(&"None".to_string())}
+                                    </span>
+                                    {gaming.steam_app_id.map(|id| view! {
+                                        <div>
+                                            <strong>"Steam App: "</strong>
+                                            <span style="color: #9b59b6;">{id.to_string()}</span>
+                                        </div>
+                                    })}
+                                </div>
+                            }.into_view()
+                        } else {
+                            view! { <div></div> }.into_view()
+                        }}
+                    </div>
+
+                    <div>
+                        <h4 style="margin: 0 0 8px 0; color: #fff; font-size: 14px;">"Performance"</h4>
+
+                        {if let Some(metrics) = &container.performance_metrics {
+                            view! {
+                                <div>
+                                    <div style="margin-bottom: 6px;">
+                                        <strong>"CPU: "</strong>
+                                        <span style="color: #f39c12;">{format!("{:.1}%", metrics.cpu_usage)}</span>
+                                    </div>
+                                    <div style="margin-bottom: 6px;">
+                                        <strong>"Memory: "</strong>
+                                        <span style="color: #e74c3c;">
+                                            {format_size(metrics.memory_usage.used_mb * 1024 * 1024)}
+                                            " / "
+                                            {format_size(metrics.memory_usage.limit_mb * 1024 * 1024)}
+                                            " ("
+                                            {format!("{:.1}%", metrics.memory_usage.percentage)}
+                                            ")"
+                                        </span>
+                                    </div>
+                                    {if let Some(gpu) = &metrics.gpu_usage {
+                                        view! {
+                                            <div style="margin-bottom: 6px;">
+                                                <strong>"GPU: "</strong>
+                                                <span style="color: #f39c12;">{format!("{:.1}%", gpu.utilization)}</span>
+                                                <div style="font-size: 12px; color: #888;">
+                                                    {format_size(gpu.memory_used_mb * 1024 * 1024)}
+                                                    " / "
+                                                    {format_size(gpu.memory_total_mb * 1024 * 1024)}
+                                                </div>
+                                            </div>
+                                        }.into_view()
+                                    } else {
+                                        view! { <div></div> }.into_view()
+                                    }}
+                                    {if let Some(gaming_metrics) = &metrics.gaming_metrics {
+                                        view! {
+                                            <div style="margin-bottom: 6px;">
+                                                <strong>"FPS: "</strong>
+                                                <span style="color: #2ecc71;">
+                                                    {gaming_metrics.fps.map(|f| format!("{:.0}", f)).unwrap_or_else(|| "N/A".to_string())}
+                                                </span>
+                                            </div>
+                                        }.into_view()
+                                    } else {
+                                        view! { <div></div> }.into_view()
+                                    }}
+                                </div>
+                            }.into_view()
+                        } else {
+                            view! {
+                                <div style="color: #888; font-style: italic;">"No metrics available"</div>
+                            }.into_view()
+                        }}
+                    </div>
+                </div>
+
+                // Container actions
+                <div style="display: flex; gap: 8px; align-items: center;">
+                    {match container.status {
+                        ContainerStatus::Running => view! {
+                            <button
+                                class="btn-danger"
+                                style="padding: 6px 12px; font-size: 12px;"
+                                on:click=move |_| container_operation(container_for_stop.id.clone(), "stop".to_string())
+                                disabled=move || loading.get()
+                            >
+                                "Stop"
+                            </button>
+                            <button
+                                class="btn-primary"
+                                style="padding: 6px 12px; font-size: 12px;"
+                                on:click=move |_| container_operation(container_for_restart.id.clone(), "restart".to_string())
+                                disabled=move || loading.get()
+                            >
+                                "Restart"
+                            </button>
+                            <button
+                                class="btn-primary"
+                                style="padding: 6px 12px; font-size: 12px; background-color: #f39c12;"
+                                on:click=move |_| container_operation(container_for_pause.id.clone(), "pause".to_string())
+                                disabled=move || loading.get()
+                            >
+                                "Pause"
+                            </button>
+                            <select
+                                style="padding: 5px; font-size: 12px;"
+                                on:change=move |ev| set_signal.set(event_target_value(&ev))
+                            >
+                                <option value="SIGHUP" selected=true>"SIGHUP"</option>
+                                <option value="SIGTERM">"SIGTERM"</option>
+                                <option value="SIGKILL">"SIGKILL"</option>
+                                <option value="SIGINT">"SIGINT"</option>
+                                <option value="SIGUSR1">"SIGUSR1"</option>
+                                <option value="SIGUSR2">"SIGUSR2"</option>
+                            </select>
+                            <button
+                                class="btn-danger"
+                                style="padding: 6px 12px; font-size: 12px;"
+                                on:click=move |_| send_signal(container_for_signal.id.clone(), signal.get())
+                                disabled=move || loading.get()
+                            >
+                                "Send Signal"
+                            </button>
+                        }.into_view(),
+                        ContainerStatus::Paused => view! {
+                            <button
+                                class="btn-success"
+                                style="padding: 6px 12px; font-size: 12px;"
+                                on:click=move |_| container_operation(container_for_resume.id.clone(), "unpause".to_string())
+                                disabled=move || loading.get()
+                            >
+                                "Resume"
+                            </button>
+                        }.into_view(),
+                        _ => view! {
+                            <button
+                                class="btn-success"
+                                style="padding: 6px 12px; font-size: 12px;"
+                                on:click=move |_| container_operation(container_for_start.id.clone(), "start".to_string())
+                                disabled=move || loading.get()
+                            >
+                                "Start"
+                            </button>
+                        }.into_view()
+                    }}
+
                     <button
                         class="btn-primary"
-                        on:click=move |_| {
-                            set_show_create_wizard.set(true);
-                        }
+                        style="padding: 6px 12px; font-size: 12px; background-color: #6c757d;"
+                        on:click=move |_| show_container_logs(container_for_logs.clone())
                     >
-                        "Create Container"
+                        "Logs"
                     </button>
+
                     <button
                         class="btn-primary"
-                        style="background-color: #6c757d;"
+                        style="padding: 6px 12px; font-size: 12px; background-color: #6f42c1;"
+                        on:click=move |_| open_commit(container_for_commit.clone())
+                        disabled=move || loading.get()
+                    >
+                        "Commit to image"
+                    </button>
+
+                    <button
+                        class="btn-primary"
+                        style="padding: 6px 12px; font-size: 12px; background-color: #17a2b8;"
                         on:click=move |_| {
-                            spawn_local(async move {
-                                load_containers(set_containers, set_loading, set_error_message).await;
-                            });
+                            // TODO: Navigate to container details
+                            web_sys::console::log_1(&format!("View details for {}", container.id).into());
                         }
                     >
-                        "Refresh"
+                        "Details"
                     </button>
                 </div>
             </div>
+        }
+    };
 
-            // Error/Success message display
+    let (projects, set_projects) = create_signal(Vec::<ProjectSummary>::new());
+    let (project_filter, set_project_filter) = create_signal(String::new());
+    let (group_by_project, set_group_by_project) = create_signal(false);
+
+    // Project counts change whenever the container list does
+    create_effect(move |_| {
+        containers.track();
+        spawn_local(load_projects(set_projects));
+    });
+
+    let visible_containers = move || {
+        let filter = project_filter.get();
+        containers
+            .get()
+            .into_iter()
+            .filter(|c| filter.is_empty() || project_of(c) == Some(filter.as_str()))
+            .collect::<Vec<_>>()
+    };
+
+    view! {
+        <div class="container-list">
+            <div class="header-section">
+                <h2>"Containers"</h2>
+                <p>"Manage your Bolt containers with advanced monitoring and gaming features"</p>
+                <div style="display: flex; gap: 10px;">
+                    <button
+                        class="btn-primary"
+                        on:click=move |_| {
+                            set_show_create_wizard.set(true);
+                        }
+                    >
+                        "Create Container"
+                    </button>
+                    <button
+                        class="btn-primary"
+                        style="background-color: #6c757d;"
+                        on:click=move |_| {
+                            spawn_local(async move {
+                                load_containers(set_containers, set_loading, set_error_message).await;
+                            });
+                        }
+                    >
+                        "Refresh"
+                    </button>
+                    <select
+                        on:change=move |ev| set_project_filter.set(event_target_value(&ev))
+                        style="padding: 8px; border: 1px solid #4a5568; border-radius: 4px; background-color: #1a1a1a; color: white;"
+                    >
+                        <option value="" selected=move || project_filter.get().is_empty()>"All projects"</option>
+                        {move || projects.get().into_iter().map(|project| {
+                            let selected_name = project.name.clone();
+                            view! {
+                                <option
+                                    value=project.name.clone()
+                                    selected=move || project_filter.get() == selected_name
+                                >
+                                    {format!("{} ({}/{} running)", project.name, project.running, project.container_count)}
+                                </option>
+                            }
+                        }).collect_view()}
+                    </select>
+                    <label style="display: flex; align-items: center; gap: 5px; color: #bbb;">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || group_by_project.get()
+                            on:change=move |ev| set_group_by_project.set(event_target_checked(&ev))
+                        />
+                        "Group by project"
+                    </label>
+                </div>
+            </div>
+
+            // Error/Success message display
             {move || {
-                if let Some(message) = error_message.get() {
+                if let Some(message) = error_message_signal.get() {
                     let is_success = message.starts_with("✅");
                     view! {
                         <div
                             class="message-banner"
                             style=format!(
-                                "background-color: {}; color: white; padding: 10px; border-radius: 4px; margin-bottom: 20px;",
+                                "background-color: {}; color: white; padding: 10px; border-radius: 4px; margin-bottom: 20px; white-space: pre-line;",
                                 if is_success { "#27ae60" } else { "#e74c3c" }
                             )
                         >
@@ -387,231 +868,80 @@ pub fn ContainerList() -> impl IntoView {
                 }
             }}
 
-            // Container grid
-            <div class="container-grid" style="display: grid; gap: 20px;">
-                <For
-                    each=move || containers.get()
-                    key=|container| container.id.clone()
-                    children=move |container| {
-                        let container_for_start = container.clone();
-                        let container_for_stop = container.clone();
-                        let container_for_restart = container.clone();
-                        let container_for_logs = container.clone();
-
-                        view! {
-                            <div class="container-card" style="background-color: #2c3e50; border-radius: 8px; padding: 20px; border: 1px solid #4a5568;">
-                                // Container header
-                                <div style="display: flex; justify-content: space-between; align-items: center; margin-bottom: 15px;">
-                                    <div>
-                                        <h3 style="margin: 0; color: #3498db; display: flex; align-items: center; gap: 10px;">
-                                            {&container.name}
-                                            <span class=format!(
-                                                "status-badge status-{}",
-                                                match container.status {
-                                                    ContainerStatus::Running => "running",
-                                                    ContainerStatus::Exited { .. } => "stopped",
-                                                    ContainerStatus::Paused => "paused",
-                                                    _ => "unknown"
-                                                }
-                                            )>
-                                                {container.status.to_string()}
-                                            </span>
-                                            {container.gaming_config.as_ref().map(|_| view! {
-                                                <span class="gaming-badge">"GAMING"</span>
-                                            })}
-                                            {container.gpu_allocation.as_ref().map(|_| view! {
-                                                <span class="gpu-indicator">"GPU"</span>
-                                            })}
-                                        </h3>
-                                        <p style="margin: 5px 0; color: #bbb; font-size: 14px;">{&container.image}</p>
-                                    </div>
-                                    <div style="font-size: 12px; color: #888; text-align: right;">
-                                        <div>
-                                            <strong>"ID: "</strong>
-                                            <code style="background-color: #1a1a1a; padding: 2px 4px; border-radius: 2px;">
-                                                {&container.id[..12]}
-                                            </code>
-                                        </div>
-                                        <div style="margin-top: 4px;">
-                                            <strong>"Uptime: "</strong>
-                                            {format_uptime(container.started_at)}
-                                        </div>
-                                    </div>
-                                </div>
-
-                                // Container details
-                                <div style="display: grid; grid-template-columns: 1fr 1fr; gap: 15px; margin-bottom: 15px;">
-                                    <div>
-                                        <h4 style="margin: 0 0 8px 0; color: #fff; font-size: 14px;">"Configuration"</h4>
-
-                                        // Ports
-                                        {if !container.ports.is_empty() {
-                                            view! {
-                                                <div style="margin-bottom: 8px;">
-                                                    <strong>"Ports: "</strong>
-                                                    <span style="color: #3498db;">
-                                                        {container.ports.iter().map(|port| {
-                                                            format!("{}:{}",
-                                                                port.host_port.map(|p| p.to_string()).unwrap_or_else(|| "auto".to_string()),
-                                                                port.container_port)
-                                                        }).collect::<Vec<_>>().join(", ")}
-                                                    </span>
-                                                </div>
-                                            }.into_view()
-                                        } else {
-                                            view! { <div></div> }.into_view()
-                                        }}
-
-                                        // Networks
-                                        {if !container.networks.is_empty() {
-                                            view! {
-                                                <div style="margin-bottom: 8px;">
-                                                    <strong>"Networks: "</strong>
-                                                    <span style="color: #27ae60;">
-                                                        {container.networks.join(", ")}
-                                                    </span>
-                                                </div>
-                                            }.into_view()
-                                        } else {
-                                            view! { <div></div> }.into_view()
-                                        }}
-
-                                        // Gaming config
-                                        {if let Some(gaming) = &container.gaming_config {
-                                            view! {
-                                                <div style="margin-bottom: 8px;">
-                                                    <strong>"Proton: "</strong>
-                                                    <span style="color: #9b59b6;">
-                                                        {gaming.proton_version.as_ref().unwrap_or(&"None".to_string())}
-                                                    </span>
-                                                    {gaming.steam_app_id.map(|id| view! {
-                                                        <div>
-                                                            <strong>"Steam App: "</strong>
-                                                            <span style="color: #9b59b6;">{id.to_string()}</span>
-                                                        </div>
-                                                    })}
-                                                </div>
-                                            }.into_view()
-                                        } else {
-                                            view! { <div></div> }.into_view()
-                                        }}
-                                    </div>
-
-                                    <div>
-                                        <h4 style="margin: 0 0 8px 0; color: #fff; font-size: 14px;">"Performance"</h4>
-
-                                        {if let Some(metrics) = &container.performance_metrics {
-                                            view! {
-                                                <div>
-                                                    <div style="margin-bottom: 6px;">
-                                                        <strong>"CPU: "</strong>
-                                                        <span style="color: #f39c12;">{format!("{:.1}%", metrics.cpu_usage)}</span>
-                                                    </div>
-                                                    <div style="margin-bottom: 6px;">
-                                                        <strong>"Memory: "</strong>
-                                                        <span style="color: #e74c3c;">
-                                                            {format_size(metrics.memory_usage.used_mb * 1024 * 1024)}
-                                                            " / "
-                                                            {format_size(metrics.memory_usage.limit_mb * 1024 * 1024)}
-                                                            " ("
-                                                            {format!("{:.1}%", metrics.memory_usage.percentage)}
-                                                            ")"
-                                                        </span>
-                                                    </div>
-                                                    {if let Some(gpu) = &metrics.gpu_usage {
-                                                        view! {
-                                                            <div style="margin-bottom: 6px;">
-                                                                <strong>"GPU: "</strong>
-                                                                <span style="color: #f39c12;">{format!("{:.1}%", gpu.utilization)}</span>
-                                                                <div style="font-size: 12px; color: #888;">
-                                                                    {format_size(gpu.memory_used_mb * 1024 * 1024)}
-                                                                    " / "
-                                                                    {format_size(gpu.memory_total_mb * 1024 * 1024)}
-                                                                </div>
-                                                            </div>
-                                                        }.into_view()
-                                                    } else {
-                                                        view! { <div></div> }.into_view()
-                                                    }}
-                                                    {if let Some(gaming_metrics) = &metrics.gaming_metrics {
-                                                        view! {
-                                                            <div style="margin-bottom: 6px;">
-                                                                <strong>"FPS: "</strong>
-                                                                <span style="color: #2ecc71;">
-                                                                    {gaming_metrics.fps.map(|f| format!("{:.0}", f)).unwrap_or_else(|| "N/A".to_string())}
-                                                                </span>
-                                                            </div>
-                                                        }.into_view()
-                                                    } else {
-                                                        view! { <div></div> }.into_view()
-                                                    }}
-                                                </div>
-                                            }.into_view()
-                                        } else {
-                                            view! {
-                                                <div style="color: #888; font-style: italic;">"No metrics available"</div>
-                                            }.into_view()
-                                        }}
-                                    </div>
-                                </div>
-
-                                // Container actions
-                                <div style="display: flex; gap: 8px; align-items: center;">
-                                    {match container.status {
-                                        ContainerStatus::Running => view! {
-                                            <button
-                                                class="btn-danger"
-                                                style="padding: 6px 12px; font-size: 12px;"
-                                                on:click=move |_| container_operation(container_for_stop.id.clone(), "stop".to_string())
-                                                disabled=move || loading.get()
-                                            >
-                                                "Stop"
-                                            </button>
-                                            <button
-                                                class="btn-primary"
-                                                style="padding: 6px 12px; font-size: 12px;"
-                                                on:click=move |_| container_operation(container_for_restart.id.clone(), "restart".to_string())
-                                                disabled=move || loading.get()
-                                            >
-                                                "Restart"
-                                            </button>
-                                        }.into_view(),
-                                        _ => view! {
-                                            <button
-                                                class="btn-success"
-                                                style="padding: 6px 12px; font-size: 12px;"
-                                                on:click=move |_| container_operation(container_for_start.id.clone(), "start".to_string())
-                                                disabled=move || loading.get()
-                                            >
-                                                "Start"
-                                            </button>
-                                        }.into_view()
-                                    }}
-
-                                    <button
-                                        class="btn-primary"
-                                        style="padding: 6px 12px; font-size: 12px; background-color: #6c757d;"
-                                        on:click=move |_| show_container_logs(container_for_logs.clone())
-                                    >
-                                        "Logs"
-                                    </button>
+            // Bulk action toolbar
+            {move || {
+                let count = selected_ids.with(|ids| ids.len());
+                if count > 0 {
+                    view! {
+                        <div style="display: flex; gap: 8px; align-items: center; background-color: #34495e; padding: 10px; border-radius: 4px; margin-bottom: 15px;">
+                            <strong>{format!("{} selected", count)}</strong>
+                            <button class="btn-success" style="padding: 6px 12px; font-size: 12px;"
+                                on:click=move |_| run_batch(ContainerBatchAction::Start) disabled=move || loading.get()>
+                                "Start"
+                            </button>
+                            <button class="btn-danger" style="padding: 6px 12px; font-size: 12px;"
+                                on:click=move |_| run_batch(ContainerBatchAction::Stop) disabled=move || loading.get()>
+                                "Stop"
+                            </button>
+                            <button class="btn-primary" style="padding: 6px 12px; font-size: 12px;"
+                                on:click=move |_| run_batch(ContainerBatchAction::Restart) disabled=move || loading.get()>
+                                "Restart"
+                            </button>
+                            <button class="btn-danger" style="padding: 6px 12px; font-size: 12px; background-color: #c0392b;"
+                                on:click=move |_| run_batch(ContainerBatchAction::Remove) disabled=move || loading.get() || !can_operate()>
+                                "Remove"
+                            </button>
+                            <button style="margin-left: auto; background: none; border: none; color: #bbb; cursor: pointer;"
+                                on:click=move |_| set_selected_ids.set(std::collections::HashSet::new())>
+                                "Clear selection"
+                            </button>
+                        </div>
+                    }.into_view()
+                } else {
+                    view! { <div></div> }.into_view()
+                }
+            }}
 
-                                    <button
-                                        class="btn-primary"
-                                        style="padding: 6px 12px; font-size: 12px; background-color: #17a2b8;"
-                                        on:click=move |_| {
-                                            // TODO: Navigate to container details
-                                            web_sys::console::log_1(&format!("View details for {}", container.id).into());
-                                        }
-                                    >
-                                        "Details"
-                                    </button>
+            // Container grid, optionally split into a section per project
+            {move || if group_by_project.get() {
+                view! {
+                    <For
+                        each=move || {
+                            // Unlabelled containers ("") sort last
+                            let mut names: Vec<String> = visible_containers()
+                                .iter()
+                                .map(|c| project_of(c).unwrap_or_default().to_string())
+                                .collect();
+                            names.sort_by_key(|name| (name.is_empty(), name.clone()));
+                            names.dedup();
+                            names
+                        }
+                        key=|name| name.clone()
+                        children=move |name: String| {
+                            let title = if name.is_empty() { "No project".to_string() } else { name.clone() };
+                            view! {
+                                <h3 style="color: #bbb; margin: 20px 0 10px;">{title}</h3>
+                                <div class="container-grid" style="display: grid; gap: 20px;">
+                                    <For
+                                        each=move || visible_containers()
+                                            .into_iter()
+                                            .filter(|c| project_of(c).unwrap_or_default() == name)
+                                            .collect::<Vec<_>>()
+                                        key=card_key
+                                        children=container_card
+                                    />
                                 </div>
-                            </div>
+                            }
                         }
-                    }
-                />
-            </div>
+                    />
+                }.into_view()
+            } else {
+                view! {
+                    <div class="container-grid" style="display: grid; gap: 20px;">
+                        <For each=visible_containers key=card_key children=container_card/>
+                    </div>
+                }.into_view()
+            }}
 
             // Container logs modal
             {move || {
@@ -622,15 +952,62 @@ pub fn ContainerList() -> impl IntoView {
                                 <div class="container-card" style="width: 80%; max-width: 800px; height: 60%; max-height: 600px; display: flex; flex-direction: column;">
                                     <div style="display: flex; justify-content: space-between; align-items: center; margin-bottom: 15px;">
                                         <h3 style="margin: 0;">"Logs: " {&container.name}</h3>
+                                        <select
+                                            style="margin-left: auto; margin-right: 10px; padding: 5px;"
+                                            on:change={
+                                                let container_id = container.id.clone();
+                                                move |ev| {
+                                                    let tail = event_target_value(&ev);
+                                                    set_log_tail.set(tail.clone());
+                                                    if follow_logs.get_untracked() {
+                                                        start_follow(container_id.clone());
+                                                        return;
+                                                    }
+                                                    let container_id = container_id.clone();
+                                                    spawn_local(async move {
+                                                        load_logs(container_id, tail, set_container_logs).await;
+                                                    });
+                                                }
+                                            }
+                                        >
+                                            {["100", "500", "1000", "all"].into_iter().map(|tail| view! {
+                                                <option value=tail selected=move || log_tail.get() == tail>
+                                                    {if tail == "all" { "All lines".to_string() } else { format!("Last {} lines", tail) }}
+                                                </option>
+                                            }).collect_view()}
+                                        </select>
+                                        <label style="margin-right: 10px; font-size: 14px;">
+                                            <input
+                                                type="checkbox"
+                                                prop:checked=follow_logs
+                                                on:change={
+                                                    let container_id = container.id.clone();
+                                                    move |ev| {
+                                                        let follow = event_target_checked(&ev);
+                                                        set_follow_logs.set(follow);
+                                                        if follow {
+                                                            start_follow(container_id.clone());
+                                                        } else {
+                                                            log_follow.set_value(None);
+                                                        }
+                                                    }
+                                                }
+                                            />
+                                            " Follow"
+                                        </label>
                                         <button
                                             style="background: none; border: none; color: white; font-size: 24px; cursor: pointer;"
-                                            on:click=move |_| set_show_logs.set(false)
+                                            on:click=move |_| {
+                                                log_follow.set_value(None);
+                                                set_follow_logs.set(false);
+                                                set_show_logs.set(false);
+                                            }
                                         >
                                             "×"
                                         </button>
                                     </div>
-                                    <div style="flex: 1; background-color: #1a1a1a; border-radius: 4px; padding: 15px; overflow-y: auto; font-family: 'Courier New', monospace; font-size: 12px; white-space: pre-wrap;">
-                                        {container_logs.get()}
+                                    <div node_ref=logs_ref style="flex: 1; background-color: #1a1a1a; border-radius: 4px; padding: 15px; overflow-y: auto; font-family: 'Courier New', monospace; font-size: 12px; white-space: pre-wrap;">
+                                        {move || container_logs.get()}
                                     </div>
                                 </div>
                             </div>
@@ -657,60 +1034,729 @@ pub fn ContainerList() -> impl IntoView {
                                 });
                             }
                         />
-                    }.into_view()
-                } else {
-                    view! { <div></div> }.into_view()
-                }
-            }}
+                    }.into_view()
+                } else {
+                    view! { <div></div> }.into_view()
+                }
+            }}
+
+            // Summary stats
+            <div class="container-card" style="margin-top: 30px;">
+                <div style="display: flex; justify-content: space-between; align-items: center;">
+                    <h3 style="margin: 0;">"Summary"</h3>
+                    <button
+                        class="btn-danger"
+                        on:click=move |_| set_show_prune_confirm.set(true)
+                        disabled=move || loading.get() || !can_operate()
+                    >
+                        "Prune stopped"
+                    </button>
+                </div>
+                <div style="display: grid; grid-template-columns: repeat(auto-fit, minmax(150px, 1fr)); gap: 20px; margin-top: 15px;">
+                    {move || {
+                        let containers_list = containers.get();
+                        let total = containers_list.len();
+                        let running = containers_list.iter().filter(|c| matches!(c.status, ContainerStatus::Running)).count();
+                        let stopped = containers_list.iter().filter(|c| matches!(c.status, ContainerStatus::Exited { .. })).count();
+                        let gaming = containers_list.iter().filter(|c| c.gaming_config.is_some()).count();
+                        let gpu_enabled = containers_list.iter().filter(|c| c.gpu_allocation.is_some()).count();
+
+                        view! {
+                            <div class="stat-item" style="text-align: center;">
+                                <div style="font-size: 24px; font-weight: bold; color: #3498db;">{total}</div>
+                                <div style="color: #bbb; font-size: 14px;">"Total Containers"</div>
+                            </div>
+                            <div class="stat-item" style="text-align: center;">
+                                <div style="font-size: 24px; font-weight: bold; color: #2ecc71;">{running}</div>
+                                <div style="color: #bbb; font-size: 14px;">"Running"</div>
+                            </div>
+                            <div class="stat-item" style="text-align: center;">
+                                <div style="font-size: 24px; font-weight: bold; color: #e74c3c;">{stopped}</div>
+                                <div style="color: #bbb; font-size: 14px;">"Stopped"</div>
+                            </div>
+                            <div class="stat-item" style="text-align: center;">
+                                <div style="font-size: 24px; font-weight: bold; color: #9b59b6;">{gaming}</div>
+                                <div style="color: #bbb; font-size: 14px;">"Gaming"</div>
+                            </div>
+                            <div class="stat-item" style="text-align: center;">
+                                <div style="font-size: 24px; font-weight: bold; color: #f39c12;">{gpu_enabled}</div>
+                                <div style="color: #bbb; font-size: 14px;">"GPU Enabled"</div>
+                            </div>
+                        }
+                    }}
+                </div>
+            </div>
+
+            // Commit to image modal
+            {move || commit_target.get().map(|container| view! {
+                <div style="position: fixed; top: 0; left: 0; width: 100%; height: 100%; background-color: rgba(0,0,0,0.5); z-index: 2000; display: flex; align-items: center; justify-content: center;">
+                    <div class="container-card" style="width: 90%; max-width: 480px;">
+                        <h3 style="margin-top: 0;">"Commit " {&container.name} " to image"</h3>
+                        <div style="display: flex; flex-direction: column; gap: 10px;">
+                            <input
+                                type="text"
+                                placeholder="Repository (e.g. myimg)"
+                                prop:value=move || commit_repository.get()
+                                on:input=move |ev| set_commit_repository.set(event_target_value(&ev))
+                                style="padding: 8px; border: 1px solid #4a5568; border-radius: 4px; background-color: #1a1a1a; color: white;"
+                            />
+                            <input
+                                type="text"
+                                placeholder="Tag"
+                                prop:value=move || commit_tag.get()
+                                on:input=move |ev| set_commit_tag.set(event_target_value(&ev))
+                                style="padding: 8px; border: 1px solid #4a5568; border-radius: 4px; background-color: #1a1a1a; color: white;"
+                            />
+                            <input
+                                type="text"
+                                placeholder="Comment (optional)"
+                                prop:value=move || commit_comment.get()
+                                on:input=move |ev| set_commit_comment.set(event_target_value(&ev))
+                                style="padding: 8px; border: 1px solid #4a5568; border-radius: 4px; background-color: #1a1a1a; color: white;"
+                            />
+                            <label style="color: #bbb; font-size: 14px;">
+                                <input
+                                    type="checkbox"
+                                    prop:checked=move || commit_pause.get()
+                                    on:change=move |ev| set_commit_pause.set(event_target_checked(&ev))
+                                />
+                                " Pause the container while committing"
+                            </label>
+                        </div>
+                        <div style="display: flex; justify-content: flex-end; gap: 10px; margin-top: 15px;">
+                            <button
+                                class="btn-primary"
+                                style="background: #4a5568;"
+                                on:click=move |_| set_commit_target.set(None)
+                            >
+                                "Cancel"
+                            </button>
+                            <button
+                                class="btn-success"
+                                on:click=move |_| commit_container()
+                                disabled=move || commit_repository.with(|repository| repository.trim().is_empty())
+                            >
+                                "Commit"
+                            </button>
+                        </div>
+                    </div>
+                </div>
+            })}
+
+            // Prune confirmation modal
+            {move || {
+                if show_prune_confirm.get() {
+                    view! {
+                        <div style="position: fixed; top: 0; left: 0; width: 100%; height: 100%; background-color: rgba(0,0,0,0.5); z-index: 2000; display: flex; align-items: center; justify-content: center;">
+                            <div class="container-card" style="width: 90%; max-width: 480px;">
+                                <h3 style="margin-top: 0;">"Prune stopped containers"</h3>
+                                <p style="color: #bbb;">
+                                    "This permanently removes every exited and dead container. Their volumes are kept."
+                                </p>
+                                <div style="display: flex; justify-content: flex-end; gap: 10px;">
+                                    <button
+                                        class="btn-primary"
+                                        style="background: #4a5568;"
+                                        on:click=move |_| set_show_prune_confirm.set(false)
+                                    >
+                                        "Cancel"
+                                    </button>
+                                    <button class="btn-danger" on:click=move |_| prune_stopped()>
+                                        "Prune"
+                                    </button>
+                                </div>
+                            </div>
+                        </div>
+                    }.into_view()
+                } else {
+                    view! { <div></div> }.into_view()
+                }
+            }}
+        </div>
+    }
+}
+
+#[component]
+pub fn ContainerDetails() -> impl IntoView {
+    let params = use_params_map();
+    let container_id = move || params.with(|p| p.get("id").cloned().unwrap_or_default());
+
+    let (container, set_container) = create_signal(None::<Container>);
+    let (active_tab, set_active_tab) = create_signal("overview");
+    let (schedules, set_schedules) = create_signal(Vec::<Schedule>::new());
+    let (error_message_signal, set_error_message) = create_signal(None::<String>);
+
+    // New schedule form
+    let (schedule_name, set_schedule_name) = create_signal(String::new());
+    let (schedule_action, set_schedule_action) = create_signal(ScheduleAction::Restart);
+    let (schedule_cron, set_schedule_cron) = create_signal("0 4 * * *".to_string());
+    let (cron_error_position, set_cron_error_position) = create_signal(None::<usize>);
+    let stats_history = create_rw_signal(StatsHistory::new(STATS_HISTORY_POINTS));
+
+    // File copy
+    let (download_path, set_download_path) = create_signal("/etc/hostname".to_string());
+    let (upload_dir, set_upload_dir) = create_signal("/tmp".to_string());
+    let (upload_file, set_upload_file) = create_signal(None::<web_sys::File>);
+    let (uploading, set_uploading) = create_signal(false);
+
+    // Processes
+    let (processes, set_processes) = create_signal(None::<Result<ContainerTop, String>>);
+    let (ps_aux, set_ps_aux) = create_signal(false);
+
+    // Load container details and its schedules when the route changes
+    create_effect(move |_| {
+        let id = container_id();
+        spawn_local(async move {
+            let url = format!("/api/v1/containers/{}", urlencoding::encode(&id));
+            match api_client::get(&url).send().await {
+                Ok(response) if response.ok() => {
+                    if let Ok(details) = response.json::<Container>().await {
+                        set_container.set(Some(details));
+                    }
+                }
+                Ok(_) => set_error_message.set(Some(format!("❌ Container {} not found", id))),
+                Err(e) => set_error_message.set(Some(format!("❌ Failed to load container: {}", e))),
+            }
+            load_schedules(id, set_schedules, set_error_message).await;
+        });
+    });
+
+    // Poll live stats every 5 seconds, starting a fresh chart when the route changes
+    create_effect(move |_| {
+        container_id();
+        stats_history.set(StatsHistory::new(STATS_HISTORY_POINTS));
+    });
+    spawn_local(load_stats(container_id(), stats_history));
+    if let Ok(handle) = set_interval_with_handle(
+        move || spawn_local(load_stats(container_id(), stats_history)),
+        std::time::Duration::from_secs(5),
+    ) {
+        on_cleanup(move || handle.clear());
+    }
+
+    // Reload the process list whenever its tab is opened or the column set changes
+    let refresh_processes = move || spawn_local(load_processes(container_id(), ps_aux.get_untracked(), set_processes));
+    create_effect(move |_| {
+        if active_tab.get() == "processes" {
+            ps_aux.track();
+            refresh_processes();
+        }
+    });
+
+    let create_schedule = move |_| {
+        let id = container_id();
+        let request = ScheduleRequest {
+            name: Some(schedule_name.get()).filter(|name| !name.trim().is_empty()),
+            container_id: Some(id.clone()),
+            action: Some(schedule_action.get()),
+            cron: Some(schedule_cron.get()),
+            enabled: Some(true),
+            ..Default::default()
+        };
+
+        spawn_local(async move {
+            set_cron_error_position.set(None);
+            let response = match api_client::post("/api/v1/schedules").json(&request) {
+                Ok(request) => request.send().await,
+                Err(e) => {
+                    set_error_message.set(Some(format!("❌ Failed to create schedule: {}", e)));
+                    return;
+                }
+            };
+
+            match response {
+                Ok(response) if response.ok() => {
+                    set_error_message.set(Some("✅ Schedule created".to_string()));
+                    set_schedule_name.set(String::new());
+                    load_schedules(id, set_schedules, set_error_message).await;
+                }
+                Ok(response) => {
+                    let error = api_error(&response).await;
+                    let details = error.details.and_then(|details| serde_json::from_value::<CronErrorDetails>(details).ok());
+                    set_cron_error_position.set(details.map(|details| details.position));
+                    set_error_message.set(Some(format!("❌ {}", error.message)));
+                }
+                Err(e) => set_error_message.set(Some(format!("❌ Failed to create schedule: {}", e))),
+            }
+        });
+    };
+
+    let toggle_schedule = move |schedule: Schedule| {
+        let id = container_id();
+        let request = ScheduleRequest {
+            enabled: Some(!schedule.enabled),
+            ..Default::default()
+        };
+
+        spawn_local(async move {
+            let url = format!("/api/v1/schedules/{}", schedule.id);
+            match api_client::put(&url).json(&request) {
+                Ok(request) => match request.send().await {
+                    Ok(response) if response.ok() => {
+                        load_schedules(id, set_schedules, set_error_message).await;
+                    }
+                    Ok(_) => set_error_message.set(Some("❌ Failed to update schedule".to_string())),
+                    Err(e) => set_error_message.set(Some(format!("❌ Failed to update schedule: {}", e))),
+                },
+                Err(e) => set_error_message.set(Some(format!("❌ Failed to update schedule: {}", e))),
+            }
+        });
+    };
+
+    let delete_schedule = move |schedule_id: String| {
+        let id = container_id();
+        spawn_local(async move {
+            let url = format!("/api/v1/schedules/{}", schedule_id);
+            match api_client::delete(&url).send().await {
+                Ok(response) if response.ok() => {
+                    set_error_message.set(Some("✅ Schedule deleted".to_string()));
+                    load_schedules(id, set_schedules, set_error_message).await;
+                }
+                Ok(_) => set_error_message.set(Some("❌ Failed to delete schedule".to_string())),
+                Err(e) => set_error_message.set(Some(format!("❌ Failed to delete schedule: {}", e))),
+            }
+        });
+    };
+
+    let select_upload = move |ev: ev::Event| {
+        let input: web_sys::HtmlInputElement = event_target(&ev);
+        set_upload_file.set(input.files().and_then(|files| files.get(0)));
+    };
+
+    let upload = move |_| {
+        let Some(file) = upload_file.get_untracked() else {
+            return;
+        };
+        let id = container_id();
+        let path = format!("{}/{}", upload_dir.get_untracked().trim_end_matches('/'), file.name());
+
+        spawn_local(async move {
+            set_uploading.set(true);
+            let url = format!(
+                "/api/v1/containers/{}/files?path={}",
+                urlencoding::encode(&id),
+                urlencoding::encode(&path)
+            );
+
+            // The browser streams the file and sets Content-Length from its size
+            let request = api_client::put(&url)
+                .header("Content-Type", "application/octet-stream")
+                .body(file)
+                .unwrap();
+
+            match request.send().await {
+                Ok(response) if response.ok() => {
+                    set_error_message.set(Some(format!("✅ Uploaded {}", path)));
+                }
+                Ok(response) => {
+                    set_error_message.set(Some(format!("❌ Upload failed: {}", error_message(&response).await)));
+                }
+                Err(e) => set_error_message.set(Some(format!("❌ Upload failed: {}", e))),
+            }
+            set_uploading.set(false);
+        });
+    };
+
+    view! {
+        <div class="container-details">
+            <div class="header-section">
+                <h2>"Container: " {move || container.get().map(|c| c.name).unwrap_or_else(container_id)}</h2>
+                <A href="/containers">"← Back to containers"</A>
+            </div>
+
+            {move || error_message_signal.get().map(|message| view! {
+                <div
+                    class="message-banner"
+                    style=format!(
+                        "background-color: {}; color: white; padding: 10px; border-radius: 4px; margin-bottom: 20px;",
+                        if message.starts_with("✅") { "#27ae60" } else { "#e74c3c" }
+                    )
+                >
+                    {message}
+                    <button style="float: right; background: none; border: none; color: white; cursor: pointer;"
+                            on:click=move |_| set_error_message.set(None)>
+                        "×"
+                    </button>
+                </div>
+            })}
+
+            // Tabs
+            <div style="display: flex; gap: 10px; margin-bottom: 20px;">
+                <button
+                    class="btn-primary"
+                    style=move || if active_tab.get() == "overview" { "" } else { "background: #4a5568;" }
+                    on:click=move |_| set_active_tab.set("overview")
+                >
+                    "Overview"
+                </button>
+                <button
+                    class="btn-primary"
+                    style=move || if active_tab.get() == "schedules" { "" } else { "background: #4a5568;" }
+                    on:click=move |_| set_active_tab.set("schedules")
+                >
+                    "Schedules"
+                </button>
+                <button
+                    class="btn-primary"
+                    style=move || if active_tab.get() == "console" { "" } else { "background: #4a5568;" }
+                    on:click=move |_| set_active_tab.set("console")
+                >
+                    "Console"
+                </button>
+                <button
+                    class="btn-primary"
+                    style=move || if active_tab.get() == "files" { "" } else { "background: #4a5568;" }
+                    on:click=move |_| set_active_tab.set("files")
+                >
+                    "Files"
+                </button>
+                <button
+                    class="btn-primary"
+                    style=move || if active_tab.get() == "processes" { "" } else { "background: #4a5568;" }
+                    on:click=move |_| set_active_tab.set("processes")
+                >
+                    "Processes"
+                </button>
+            </div>
+
+            // Overview
+            <div class="container-card" style=move || if active_tab.get() == "overview" { "" } else { "display: none;" }>
+                {move || container.get().map(|container| view! {
+                    <div style="display: grid; grid-template-columns: repeat(auto-fit, minmax(200px, 1fr)); gap: 10px; font-size: 14px; color: #bbb;">
+                        <div><strong>"Image: "</strong> {&container.image}</div>
+                        <div><strong>"Status: "</strong> {container.status.to_string()}</div>
+                        <div><strong>"Uptime: "</strong> {format_uptime(container.started_at)}</div>
+                        <div><strong>"Created: "</strong> {container.created_at.format("%Y-%m-%d %H:%M").to_string()}</div>
+                        <div><strong>"Networks: "</strong> {container.networks.join(", ")}</div>
+                        <div><strong>"ID: "</strong> <code>{&container.id}</code></div>
+                    </div>
+                })}
+
+                {move || stats_history.with(|history| history.latest().cloned()).map(|latest| view! {
+                    <h3 style="margin-top: 20px;">"Live Stats"</h3>
+                    <div style="display: grid; grid-template-columns: repeat(auto-fit, minmax(200px, 1fr)); gap: 10px; font-size: 14px; color: #bbb;">
+                        <div><strong>"CPU: "</strong> <span style="color: #f39c12;">{format!("{:.1}%", latest.cpu_percent)}</span></div>
+                        <div><strong>"Memory: "</strong> <span style="color: #3498db;">{format!("{:.1}%", latest.memory_percent)}</span></div>
+                        <div><strong>"Network: "</strong> {format!("↓ {}/s ↑ {}/s", format_size(latest.network_rx_per_sec as u64), format_size(latest.network_tx_per_sec as u64))}</div>
+                        {latest.fps.map(|fps| view! { <div><strong>"FPS: "</strong> {format!("{:.0}", fps)}</div> })}
+                    </div>
+                    <svg viewBox="0 0 300 60" preserveAspectRatio="none" style="width: 100%; height: 80px; margin-top: 10px; background-color: #1a1a1a; border-radius: 4px;">
+                        <polyline
+                            fill="none"
+                            stroke="#f39c12"
+                            stroke-width="1.5"
+                            points=move || stats_history.with(|history| history.polyline(|p: &ChartPoint| p.cpu_percent, 100.0, 300.0, 60.0))
+                        />
+                        <polyline
+                            fill="none"
+                            stroke="#3498db"
+                            stroke-width="1.5"
+                            points=move || stats_history.with(|history| history.polyline(|p: &ChartPoint| p.memory_percent, 100.0, 300.0, 60.0))
+                        />
+                    </svg>
+                    <div style="font-size: 12px; color: #888;">
+                        <span style="color: #f39c12;">"■ CPU"</span> " " <span style="color: #3498db;">"■ Memory"</span>
+                    </div>
+                })}
+            </div>
 
-            // Summary stats
-            <div class="container-card" style="margin-top: 30px;">
-                <h3>"Summary"</h3>
-                <div style="display: grid; grid-template-columns: repeat(auto-fit, minmax(150px, 1fr)); gap: 20px; margin-top: 15px;">
-                    {move || {
-                        let containers_list = containers.get();
-                        let total = containers_list.len();
-                        let running = containers_list.iter().filter(|c| matches!(c.status, ContainerStatus::Running)).count();
-                        let stopped = containers_list.iter().filter(|c| matches!(c.status, ContainerStatus::Exited { .. })).count();
-                        let gaming = containers_list.iter().filter(|c| c.gaming_config.is_some()).count();
-                        let gpu_enabled = containers_list.iter().filter(|c| c.gpu_allocation.is_some()).count();
+            // Console: mounted only while its tab is open, so leaving the tab closes the shell
+            {move || (active_tab.get() == "console").then(|| view! {
+                <div class="container-card">
+                    {match container.get() {
+                        Some(c) if c.status == ContainerStatus::Running => view! {
+                            <Terminal container_id=c.id.clone()/>
+                        }.into_view(),
+                        Some(_) => view! {
+                            <p style="color: #bbb;">"Start the container to open a console."</p>
+                        }.into_view(),
+                        None => view! { <p style="color: #bbb;">"Loading..."</p> }.into_view(),
+                    }}
+                </div>
+            })}
+
+            // Files
+            <div class="container-card" style=move || if active_tab.get() == "files" { "" } else { "display: none;" }>
+                <h3>"Download"</h3>
+                <p style="color: #bbb; font-size: 14px;">"Directories are downloaded as a tar archive."</p>
+                <div style="display: flex; gap: 10px; align-items: center; margin-bottom: 20px;">
+                    <input
+                        type="text"
+                        placeholder="/path/in/container"
+                        prop:value=move || download_path.get()
+                        on:input=move |ev| set_download_path.set(event_target_value(&ev))
+                        style="flex: 1; padding: 8px; border: 1px solid #4a5568; border-radius: 4px; background-color: #1a1a1a; color: white;"
+                    />
+                    <a
+                        class="btn-primary"
+                        download=""
+                        href=move || api_client::authorized_url(&format!(
+                            "/api/v1/containers/{}/files?path={}",
+                            urlencoding::encode(&container_id()),
+                            urlencoding::encode(&download_path.get())
+                        ))
+                    >
+                        "Download"
+                    </a>
+                </div>
 
-                        view! {
-                            <div class="stat-item" style="text-align: center;">
-                                <div style="font-size: 24px; font-weight: bold; color: #3498db;">{total}</div>
-                                <div style="color: #bbb; font-size: 14px;">"Total Containers"</div>
-                            </div>
-                            <div class="stat-item" style="text-align: center;">
-                                <div style="font-size: 24px; font-weight: bold; color: #2ecc71;">{running}</div>
-                                <div style="color: #bbb; font-size: 14px;">"Running"</div>
-                            </div>
-                            <div class="stat-item" style="text-align: center;">
-                                <div style="font-size: 24px; font-weight: bold; color: #e74c3c;">{stopped}</div>
-                                <div style="color: #bbb; font-size: 14px;">"Stopped"</div>
-                            </div>
-                            <div class="stat-item" style="text-align: center;">
-                                <div style="font-size: 24px; font-weight: bold; color: #9b59b6;">{gaming}</div>
-                                <div style="color: #bbb; font-size: 14px;">"Gaming"</div>
-                            </div>
-                            <div class="stat-item" style="text-align: center;">
-                                <div style="font-size: 24px; font-weight: bold; color: #f39c12;">{gpu_enabled}</div>
-                                <div style="color: #bbb; font-size: 14px;">"GPU Enabled"</div>
-                            </div>
+                <h3>"Upload"</h3>
+                <div style="display: flex; gap: 10px; align-items: center;">
+                    <input type="file" on:change=select_upload/>
+                    <input
+                        type="text"
+                        placeholder="Destination directory"
+                        prop:value=move || upload_dir.get()
+                        on:input=move |ev| set_upload_dir.set(event_target_value(&ev))
+                        style="flex: 1; padding: 8px; border: 1px solid #4a5568; border-radius: 4px; background-color: #1a1a1a; color: white;"
+                    />
+                    <button
+                        class="btn-primary"
+                        on:click=upload
+                        disabled=move || uploading.get() || upload_file.with(Option::is_none)
+                    >
+                        {move || if uploading.get() { "Uploading..." } else { "Upload" }}
+                    </button>
+                </div>
+            </div>
+
+            // Processes
+            <div class="container-card" style=move || if active_tab.get() == "processes" { "" } else { "display: none;" }>
+                <div style="display: flex; gap: 10px; align-items: center; margin-bottom: 15px;">
+                    <h3 style="margin: 0;">"Processes"</h3>
+                    <label style="margin-left: auto; color: #bbb; font-size: 14px;">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || ps_aux.get()
+                            on:change=move |ev| set_ps_aux.set(event_target_checked(&ev))
+                        />
+                        " Resource columns (ps aux)"
+                    </label>
+                    <button class="btn-primary" on:click=move |_| refresh_processes()>"Refresh"</button>
+                </div>
+                {move || match processes.get() {
+                    None => view! { <p style="color: #bbb;">"Loading..."</p> }.into_view(),
+                    Some(Err(message)) => view! { <p style="color: #bbb;">{message}</p> }.into_view(),
+                    Some(Ok(top)) => view! {
+                        <table style="width: 100%; border-collapse: collapse; font-size: 13px;">
+                            <thead>
+                                <tr>
+                                    {top.titles.iter().map(|title| view! {
+                                        <th style="text-align: left; padding: 6px; border-bottom: 1px solid #4a5568;">{title.clone()}</th>
+                                    }).collect_view()}
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {top.processes.iter().map(|row| view! {
+                                    <tr>
+                                        {row.iter().map(|cell| view! {
+                                            <td style="padding: 6px; border-bottom: 1px solid #2d3748; font-family: monospace;">{cell.clone()}</td>
+                                        }).collect_view()}
+                                    </tr>
+                                }).collect_view()}
+                            </tbody>
+                        </table>
+                    }.into_view(),
+                }}
+            </div>
+
+            // Schedules
+            <div class="container-card" style=move || if active_tab.get() == "schedules" { "" } else { "display: none;" }>
+                <h3>"Scheduled Actions"</h3>
+                <p style="color: #bbb; font-size: 14px;">"Cron expressions are evaluated in UTC."</p>
+
+                <div style="display: flex; gap: 10px; align-items: flex-start; margin-bottom: 20px;">
+                    <input
+                        type="text"
+                        placeholder="Name (optional)"
+                        prop:value=move || schedule_name.get()
+                        on:input=move |ev| set_schedule_name.set(event_target_value(&ev))
+                        style="padding: 8px; border: 1px solid #4a5568; border-radius: 4px; background-color: #1a1a1a; color: white;"
+                    />
+                    <select
+                        on:change=move |ev| {
+                            set_schedule_action.set(match event_target_value(&ev).as_str() {
+                                "Stop" => ScheduleAction::Stop,
+                                "Start" => ScheduleAction::Start,
+                                _ => ScheduleAction::Restart,
+                            })
                         }
-                    }}
+                        style="padding: 8px; border: 1px solid #4a5568; border-radius: 4px; background-color: #1a1a1a; color: white;"
+                    >
+                        <option value="Restart" selected=true>"Restart"</option>
+                        <option value="Stop">"Stop"</option>
+                        <option value="Start">"Start"</option>
+                    </select>
+                    <div>
+                        <input
+                            type="text"
+                            placeholder="0 4 * * *"
+                            prop:value=move || schedule_cron.get()
+                            on:input=move |ev| {
+                                set_cron_error_position.set(None);
+                                set_schedule_cron.set(event_target_value(&ev));
+                            }
+                            style="padding: 8px; border: 1px solid #4a5568; border-radius: 4px; background-color: #1a1a1a; color: white; font-family: monospace;"
+                        />
+                        // Point at the offending character of a rejected expression
+                        {move || cron_error_position.get().map(|position| view! {
+                            <pre style="margin: 2px 0 0 9px; color: #e74c3c; font-size: 13px;">
+                                {format!("{}^", " ".repeat(position))}
+                            </pre>
+                        })}
+                    </div>
+                    <button class="btn-primary" on:click=create_schedule>
+                        "Add Schedule"
+                    </button>
                 </div>
+
+                {move || if schedules.get().is_empty() {
+                    view! { <p style="color: #bbb;">"No schedules for this container."</p> }.into_view()
+                } else {
+                    view! {
+                        <table style="width: 100%; border-collapse: collapse; font-size: 14px;">
+                            <thead>
+                                <tr style="text-align: left; color: #bbb; border-bottom: 1px solid #4a5568;">
+                                    <th style="padding: 8px;">"Name"</th>
+                                    <th style="padding: 8px;">"Action"</th>
+                                    <th style="padding: 8px;">"Cron"</th>
+                                    <th style="padding: 8px;">"Next run"</th>
+                                    <th style="padding: 8px;">"Last run"</th>
+                                    <th style="padding: 8px;"></th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                <For
+                                    each=move || schedules.get()
+                                    key=|schedule| (schedule.id.clone(), schedule.enabled, schedule.last_run)
+                                    children=move |schedule| {
+                                        let schedule_for_toggle = schedule.clone();
+                                        let id_for_delete = schedule.id.clone();
+                                        let last_run = match (&schedule.last_run, &schedule.last_result) {
+                                            (Some(time), Some(result)) => format!(
+                                                "{} {} {}",
+                                                time.format("%Y-%m-%d %H:%M"),
+                                                if result.success { "✅" } else { "❌" },
+                                                result.message
+                                            ),
+                                            _ => "never".to_string(),
+                                        };
+
+                                        view! {
+                                            <tr style="border-bottom: 1px solid #34495e;">
+                                                <td style="padding: 8px;">{schedule.name.clone().unwrap_or_else(|| "-".to_string())}</td>
+                                                <td style="padding: 8px;">{format!("{:?}", schedule.action)}</td>
+                                                <td style="padding: 8px;"><code>{&schedule.cron}</code></td>
+                                                <td style="padding: 8px; color: #bbb;">
+                                                    {schedule
+                                                        .next_run
+                                                        .map(|next| next.format("%Y-%m-%d %H:%M UTC").to_string())
+                                                        .unwrap_or_else(|| "disabled".to_string())}
+                                                </td>
+                                                <td style="padding: 8px; color: #bbb;">{last_run}</td>
+                                                <td style="padding: 8px; text-align: right; white-space: nowrap;">
+                                                    <button
+                                                        class="btn-primary"
+                                                        style="padding: 4px 10px; margin-right: 5px;"
+                                                        on:click=move |_| toggle_schedule(schedule_for_toggle.clone())
+                                                    >
+                                                        {if schedule.enabled { "Disable" } else { "Enable" }}
+                                                    </button>
+                                                    <button
+                                                        class="btn-danger"
+                                                        style="padding: 4px 10px;"
+                                                        on:click=move |_| delete_schedule(id_for_delete.clone())
+                                                    >
+                                                        "Delete"
+                                                    </button>
+                                                </td>
+                                            </tr>
+                                        }
+                                    }
+                                />
+                            </tbody>
+                        </table>
+                    }.into_view()
+                }}
             </div>
         </div>
     }
 }
 
+/// An open server-sent event stream; dropping it closes the EventSource and stops the reader task
+struct EventStream {
+    _source: EventSource,
+    abort: AbortHandle,
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        self.abort.abort();
+    }
+}
+
+/// Update the list from one container event: drop the container when it was removed, otherwise refetch it
+async fn apply_container_event(event: ContainerEvent, set_containers: WriteSignal<Vec<Container>>) {
+    if event.action == ContainerEventAction::Removed {
+        set_containers.update(|containers| containers.retain(|c| c.id != event.id));
+        return;
+    }
+
+    let url = format!("/api/v1/containers/{}", event.id);
+    let Ok(response) = api_client::get(&url).send().await else {
+        return;
+    };
+    if !response.ok() {
+        return;
+    }
+    if let Ok(container) = response.json::<Container>().await {
+        set_containers.update(|containers| match containers.iter_mut().find(|c| c.id == container.id) {
+            Some(existing) => *existing = container,
+            None => containers.insert(0, container),
+        });
+    }
+}
+
+/// Fetch a container's logs; `tail` is a line count or "all"
+async fn load_logs(container_id: String, tail: String, set_container_logs: WriteSignal<String>) {
+    let query = if tail == "all" { "all=true".to_string() } else { format!("tail={}", tail) };
+    let url = format!("/api/v1/containers/{}/logs?{}", container_id, query);
+
+    match api_client::get(&url).send().await {
+        Ok(response) if response.ok() => match response.text().await {
+            Ok(logs) => set_container_logs.set(logs),
+            Err(_) => set_container_logs.set("Failed to load logs".to_string()),
+        },
+        Ok(response) => set_container_logs.set(format!("Error loading logs: {}", error_message(&response).await)),
+        Err(e) => set_container_logs.set(format!("Error loading logs: {}", e)),
+    }
+}
+
 /// Load containers from API
+/// The project a container belongs to, from its `gpanel.project` label
+fn project_of(container: &Container) -> Option<&str> {
+    container.labels.get(PROJECT_LABEL).map(String::as_str)
+}
+
+/// Cards are keyed on name and status too, so a card redraws after rename, pause, stop, etc.
+fn card_key(container: &Container) -> (String, String, String) {
+    (container.id.clone(), container.name.clone(), container.status.to_string())
+}
+
+async fn load_projects(set_projects: WriteSignal<Vec<ProjectSummary>>) {
+    if let Ok(response) = api_client::get("/api/v1/projects").send().await {
+        if let Ok(list) = response.json::<ProjectListResponse>().await {
+            set_projects.set(list.projects);
+        }
+    }
+}
+
 async fn load_containers(
     set_containers: WriteSignal<Vec<Container>>,
     set_loading: WriteSignal<bool>,
     set_error_message: WriteSignal<Option<String>>,
 ) {
-    match Request::get("http://localhost:8000/api/v1/containers")
+    match api_client::get("/api/v1/containers")
         .send()
         .await
     {
@@ -729,18 +1775,6 @@ async fn load_containers(
     set_loading.set(false);
 }
 
-/// Mock function for setInterval (would be provided by web framework)
-fn set_interval<F>(f: F, duration: std::time::Duration) -> i32
-where F: Fn() + 'static
-{
-    // This is a placeholder - in real implementation would use web_sys::setInterval
-    0
-}
-
-/// Mock function for clearInterval
-fn clear_interval(_id: i32) {
-    // This is a placeholder - in real implementation would use web_sys::clearInterval
-}
 
 #[component]
 pub fn ContainerCreateWizard<F1, F2>(
@@ -757,18 +1791,59 @@ where
     let (selected_image, set_selected_image) = create_signal(None::<ImageInfo>);
     let (search_query, set_search_query) = create_signal(String::new());
     let (search_results, set_search_results) = create_signal(Vec::<ImageInfo>::new());
-    let (registries, set_registries) = create_signal(Vec::<RegistryConfig>::new());
+    let (registries, set_registries) = create_signal(Vec::<RegistryConfigResponse>::new());
     let (loading, set_loading) = create_signal(false);
-    let (error_message, set_error_message) = create_signal(None::<String>);
+    let (error_message_signal, set_error_message) = create_signal(None::<String>);
 
     // Container configuration
     let (ports, set_ports) = create_signal(Vec::<PortMapping>::new());
+    // Docker-style port strings pasted under "Advanced", and why they did not parse
+    let (port_spec, set_port_spec) = create_signal(String::new());
+    let (port_spec_error, set_port_spec_error) = create_signal(None::<String>);
     let (volumes, set_volumes) = create_signal(Vec::<VolumeMount>::new());
     let (env_vars, set_env_vars) = create_signal(std::collections::HashMap::<String, String>::new());
     let (networks, set_networks) = create_signal(vec!["bridge".to_string()]);
-    let (enable_gaming, set_enable_gaming) = create_signal(false);
+    let (gaming_profiles, set_gaming_profiles) = create_signal(Vec::<GamingProfile>::new());
+    let (gaming_profile, set_gaming_profile) = create_signal(None::<String>);
+    let (proton_builds, set_proton_builds) = create_signal(Vec::<InstalledProton>::new());
+    let (proton_version, set_proton_version) = create_signal(None::<String>);
+    let (steam_app_id, set_steam_app_id) = create_signal(None::<u32>);
+    // The game the App ID names, or why it could not be looked up
+    let (steam_app, set_steam_app) = create_signal(None::<Result<SteamApp, String>>);
     let (enable_gpu, set_enable_gpu) = create_signal(false);
+    let (gpus, set_gpus) = create_signal(None::<Vec<GpuDevice>>);
+    let (gpu_device, set_gpu_device) = create_signal(None::<String>);
+    let (gpu_memory_mb, set_gpu_memory_mb) = create_signal(None::<u64>);
+    // "shared", "exclusive" or "partitioned"
+    let (gpu_isolation, set_gpu_isolation) = create_signal("shared".to_string());
+    let (gpu_partition, set_gpu_partition) = create_signal(String::new());
     let (restart_policy, set_restart_policy) = create_signal(RestartPolicy::No);
+    let (health_command, set_health_command) = create_signal(String::new());
+    let (health_interval, set_health_interval) = create_signal(30u64);
+    let (health_timeout, set_health_timeout) = create_signal(5u64);
+    let (health_retries, set_health_retries) = create_signal(3u32);
+    let (health_start_period, set_health_start_period) = create_signal(0u64);
+
+    // Per-field problems reported by the agent, shown next to the inputs they refer to
+    let (field_errors, set_field_errors) = create_signal(Vec::<FieldError>::new());
+    let errors_for = move |field: String| {
+        field_errors.with(|errors| {
+            errors
+                .iter()
+                .filter(|error| error.field == field || error.field.starts_with(&format!("{}.", field)))
+                .map(|error| error.message.clone())
+                .collect::<Vec<_>>()
+                .join("; ")
+        })
+    };
+    let field_error_view = move |field: String| {
+        move || {
+            let message = errors_for(field.clone());
+            (!message.is_empty()).then(|| view! {
+                <div style="color: #e74c3c; font-size: 12px; margin-top: 4px;">{message}</div>
+            })
+        }
+    };
 
     // Load registries on mount
     create_effect(move |_| {
@@ -776,6 +1851,43 @@ where
             spawn_local(async move {
                 load_registries_for_wizard(set_registries).await;
             });
+            spawn_local(async move {
+                if let Ok(response) = api_client::get("/api/v1/gaming/profiles").send().await
+                    && let Ok(profiles) = response.json::<Vec<GamingProfile>>().await
+                {
+                    set_gaming_profiles.set(profiles);
+                }
+            });
+            spawn_local(async move {
+                if let Ok(response) = api_client::get("/api/v1/gaming/proton/versions").send().await
+                    && let Ok(versions) = response.json::<ProtonVersions>().await
+                {
+                    // Newest build first, so it is the default
+                    if proton_version.get_untracked().is_none() {
+                        set_proton_version.set(versions.installed.first().map(|proton| proton.name.clone()));
+                    }
+                    set_proton_builds.set(versions.installed);
+                }
+            });
+        }
+    });
+
+    // Discover the host's GPUs the first time GPU access is turned on
+    create_effect(move |_| {
+        if enable_gpu.get() && gpus.get_untracked().is_none() {
+            spawn_local(async move {
+                match api_client::get("/api/v1/system/gpus").send().await {
+                    Ok(response) if response.ok() => {
+                        let list = response.json::<Vec<GpuDevice>>().await.unwrap_or_default();
+                        if gpu_device.get_untracked().is_none() {
+                            set_gpu_device.set(list.first().map(|gpu| gpu.id.clone()));
+                        }
+                        set_gpus.set(Some(list));
+                    }
+                    Ok(response) => set_error_message.set(Some(error_message(&response).await)),
+                    Err(e) => set_error_message.set(Some(format!("Failed to load GPUs: {}", e))),
+                }
+            });
         }
     });
 
@@ -789,7 +1901,7 @@ where
             set_loading.set(true);
             set_error_message.set(None);
 
-            match Request::get(&format!("http://localhost:8000/api/v1/images/search?q={}", query))
+            match api_client::get(&format!("/api/v1/images/search?q={}", urlencoding::encode(&query)))
                 .send()
                 .await
             {
@@ -813,19 +1925,37 @@ where
         current_ports.push(PortMapping {
             container_port: 80,
             host_port: None,
-            protocol: "tcp".to_string(),
+            protocol: Protocol::Tcp,
             host_ip: None,
         });
         set_ports.set(current_ports);
     };
 
+    // Add every mapping in the pasted string, or none if one of them does not parse
+    let add_port_spec = move || {
+        let parsed: Result<Vec<PortMapping>, _> = port_spec
+            .get()
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|spec| !spec.is_empty())
+            .map(str::parse::<PortMapping>)
+            .collect();
+        match parsed {
+            Ok(parsed) => {
+                set_ports.update(|current_ports| current_ports.extend(parsed));
+                set_port_spec.set(String::new());
+                set_port_spec_error.set(None);
+            }
+            Err(e) => set_port_spec_error.set(Some(e.to_string())),
+        }
+    };
+
     let add_volume = move || {
         let mut current_volumes = volumes.get();
         current_volumes.push(VolumeMount {
             source: "/host/path".to_string(),
             target: "/container/path".to_string(),
             read_only: false,
-            volume_type: "bind".to_string(),
+            volume_type: VolumeType::Bind,
         });
         set_volumes.set(current_volumes);
     };
@@ -836,45 +1966,72 @@ where
         set_env_vars.set(current_env);
     };
 
-    let create_container = move || {
+    let build_request = move || {
         let name = container_name.get();
         let image = match selected_image.get() {
             Some(img) => format!("{}:{}", img.name, img.tag),
             None => {
                 set_error_message.set(Some("Please select an image".to_string()));
-                return;
+                return None;
             }
         };
+        if let Err(e) = ImageRef::parse(&image) {
+            set_error_message.set(Some(e.to_string()));
+            return None;
+        }
 
         if name.is_empty() {
             set_error_message.set(Some("Please enter a container name".to_string()));
-            return;
+            return None;
         }
 
-        let gaming_config = if enable_gaming.get() {
-            Some(GamingConfig {
-                proton_version: Some("8.0-3".to_string()),
-                wine_version: None,
-                steam_app_id: None,
-                optimization_profile: "gaming".to_string(),
-            })
-        } else {
-            None
-        };
+        // The agent fills in the profile's settings and base optimization profile
+        let gaming_config = gaming_profile.get().map(|name| GamingConfig {
+            proton_version: proton_version.get(),
+            wine_version: None,
+            steam_app_id: steam_app_id.get(),
+            optimization_profile: Default::default(),
+            audio_config: None,
+            profile_name: Some(name),
+            profile: None,
+        });
 
         let gpu_allocation = if enable_gpu.get() {
+            let device = gpu_device.get().and_then(|id| {
+                gpus.get().unwrap_or_default().into_iter().find(|gpu| gpu.id == id)
+            });
+            let Some(device) = device else {
+                set_error_message.set(Some("Please select a GPU".to_string()));
+                return None;
+            };
+            let memory_mb = gpu_memory_mb.get();
+            // Drivers that don't report memory leave nothing to check the slice against
+            if let Some(memory_mb) = memory_mb
+                && device.memory_total_mb > 0
+                && memory_mb > device.memory_total_mb
+            {
+                set_error_message.set(Some(format!(
+                    "{} has {} MB of memory, less than the {} MB requested",
+                    device.name, device.memory_total_mb, memory_mb
+                )));
+                return None;
+            }
             Some(GpuAllocation {
-                device_id: "gpu0".to_string(),
-                gpu_type: "nvidia".to_string(),
-                memory_mb: Some(2048),
-                compute_units: Some(1),
-                isolation_level: "process".to_string(),
+                device_id: device.id,
+                gpu_type: device.vendor,
+                memory_mb,
+                compute_units: None,
+                isolation_level: match gpu_isolation.get().as_str() {
+                    "exclusive" => IsolationLevel::Exclusive,
+                    "partitioned" => IsolationLevel::Partitioned { partition_id: gpu_partition.get().trim().to_string() },
+                    _ => IsolationLevel::Shared,
+                },
             })
         } else {
             None
         };
 
-        let request = ContainerCreateRequest {
+        Some(CreateContainerRequest {
             name: Some(name),
             image,
             ports: ports.get(),
@@ -885,13 +2042,78 @@ where
             gaming_config,
             gpu_allocation,
             restart_policy: restart_policy.get(),
+            // The command runs through the shell, like a Dockerfile HEALTHCHECK CMD-SHELL
+            health: Some(health_command.get())
+                .filter(|command| !command.trim().is_empty())
+                .map(|command| HealthCheck {
+                    command: vec!["sh".to_string(), "-c".to_string(), command],
+                    interval_secs: health_interval.get(),
+                    timeout_secs: health_timeout.get(),
+                    retries: health_retries.get(),
+                    start_period_secs: health_start_period.get(),
+                }),
+        })
+    };
+
+    // Check the port mappings against running containers before leaving the ports step
+    let check_ports = move || {
+        let Some(request) = build_request() else {
+            return;
+        };
+
+        spawn_local(async move {
+            set_loading.set(true);
+            set_error_message.set(None);
+            set_field_errors.set(Vec::new());
+
+            match api_client::post("/api/v1/containers/validate")
+                .json(&request)
+                .unwrap()
+                .send()
+                .await
+            {
+                Ok(response) if !response.ok() => {
+                    set_error_message.set(Some(format!("Failed to check port mappings: {}", error_message(&response).await)));
+                }
+                Ok(response) => match response.json::<ContainerValidationResponse>().await {
+                    Ok(report) => {
+                        let step_three: Vec<FieldError> = report
+                            .errors
+                            .into_iter()
+                            .filter(|e| e.field.starts_with("ports") || e.field.starts_with("volumes") || e.field.starts_with("env"))
+                            .chain(report.conflicts.iter().map(|c| FieldError {
+                                field: c.field.clone(),
+                                message: c.message(),
+                            }))
+                            .collect();
+                        if step_three.is_empty() {
+                            set_current_step.set(4);
+                        } else {
+                            set_error_message.set(Some("Fix the highlighted ports, volumes or variables".to_string()));
+                            set_field_errors.set(step_three);
+                        }
+                    }
+                    Err(_) => set_error_message.set(Some("Failed to check port mappings".to_string())),
+                },
+                Err(e) => {
+                    set_error_message.set(Some(format!("Port check failed: {}", e)));
+                }
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let create_container = move || {
+        let Some(request) = build_request() else {
+            return;
         };
 
         spawn_local(async move {
             set_loading.set(true);
             set_error_message.set(None);
+            set_field_errors.set(Vec::new());
 
-            match Request::post("http://localhost:8000/api/v1/containers")
+            match api_client::post("/api/v1/containers")
                 .json(&request)
                 .unwrap()
                 .send()
@@ -901,7 +2123,46 @@ where
                     if response.status() == 201 {
                         on_created();
                     } else {
-                        set_error_message.set(Some("Failed to create container".to_string()));
+                        let error = api_error(&response).await;
+                        match error.code.as_str() {
+                            "VALIDATION_FAILED" => {
+                                let errors = error
+                                    .details
+                                    .and_then(|details| serde_json::from_value::<ValidationErrorDetails>(details).ok())
+                                    .map(|details| details.errors)
+                                    .unwrap_or_default();
+                                // Go back to the step holding the first bad field
+                                let step = match errors.first().map(|e| e.field.as_str()) {
+                                    Some("image") => 1,
+                                    Some("name") => 2,
+                                    Some(field) if field.starts_with("ports") || field.starts_with("volumes") || field.starts_with("env") => 3,
+                                    _ => 4,
+                                };
+                                // Fields without an input of their own are listed in the banner
+                                let unplaced: Vec<String> = errors
+                                    .iter()
+                                    .filter(|e| e.field == "image" || e.field.starts_with("gpu_allocation") || e.field.starts_with("gaming_config"))
+                                    .map(|e| e.message.clone())
+                                    .collect();
+                                set_error_message.set(Some(if unplaced.is_empty() {
+                                    error.message
+                                } else {
+                                    format!("{}: {}", error.message, unplaced.join("; "))
+                                }));
+                                set_current_step.set(step);
+                                set_field_errors.set(errors);
+                            }
+                            "PORT_CONFLICT" => {
+                                // A port was taken between the ports step and now
+                                set_error_message.set(Some(error.message));
+                                set_current_step.set(3);
+                            }
+                            "GPU_IN_USE" => {
+                                set_error_message.set(Some(error.message));
+                                set_current_step.set(2);
+                            }
+                            _ => set_error_message.set(Some(format!("Failed to create container: {}", error.message))),
+                        }
                     }
                 }
                 Err(e) => {
@@ -964,7 +2225,7 @@ where
 
                 // Error message
                 {move || {
-                    if let Some(message) = error_message.get() {
+                    if let Some(message) = error_message_signal.get() {
                         view! {
                             <div style="background-color: #e74c3c; color: white; padding: 10px; border-radius: 4px; margin-bottom: 20px;">
                                 {message}
@@ -1090,6 +2351,7 @@ where
                                                 prop:value=move || container_name.get()
                                                 on:input=move |ev| set_container_name.set(event_target_value(&ev))
                                             />
+                                            {field_error_view("name".to_string())}
                                         </div>
 
                                         <div>
@@ -1100,7 +2362,7 @@ where
                                                     let policy = match event_target_value(&ev).as_str() {
                                                         "always" => RestartPolicy::Always,
                                                         "unless-stopped" => RestartPolicy::UnlessStopped,
-                                                        "on-failure" => RestartPolicy::OnFailure,
+                                                        "on-failure" => RestartPolicy::OnFailure { max_retries: None },
                                                         _ => RestartPolicy::No,
                                                     };
                                                     set_restart_policy.set(policy);
@@ -1111,6 +2373,63 @@ where
                                                 <option value="unless-stopped">"Unless Stopped"</option>
                                                 <option value="on-failure">"On Failure"</option>
                                             </select>
+                                            {move || match restart_policy.get() {
+                                                RestartPolicy::OnFailure { max_retries } => view! {
+                                                    <input
+                                                        type="number"
+                                                        min="1"
+                                                        placeholder="Max retries (unlimited)"
+                                                        style="width: 100%; margin-top: 8px; padding: 8px; border: 1px solid #4a5568; border-radius: 4px; background-color: #2c3e50; color: white;"
+                                                        prop:value=max_retries.map(|n| n.to_string()).unwrap_or_default()
+                                                        on:change=move |ev| {
+                                                            let max_retries = event_target_value(&ev).parse().ok().filter(|&n: &u32| n > 0);
+                                                            set_restart_policy.set(RestartPolicy::OnFailure { max_retries });
+                                                        }
+                                                    />
+                                                }.into_view(),
+                                                _ => view! { <span></span> }.into_view(),
+                                            }}
+                                        </div>
+                                    </div>
+
+                                    <div style="margin-top: 20px;">
+                                        <label style="display: block; margin-bottom: 5px; font-weight: bold;">"Health Check Command (optional):"</label>
+                                        <input
+                                            type="text"
+                                            placeholder="curl -f http://localhost/ || exit 1"
+                                            style="width: 100%; padding: 8px; border: 1px solid #4a5568; border-radius: 4px; background-color: #2c3e50; color: white;"
+                                            prop:value=move || health_command.get()
+                                            on:input=move |ev| set_health_command.set(event_target_value(&ev))
+                                        />
+                                        <div style="display: grid; grid-template-columns: repeat(4, 1fr); gap: 10px; margin-top: 10px; font-size: 12px;">
+                                            <label>
+                                                "Interval (s)"
+                                                <input type="number" min="1" style="width: 100%; padding: 6px;"
+                                                    prop:value=move || health_interval.get().to_string()
+                                                    on:input=move |ev| set_health_interval.set(event_target_value(&ev).parse().unwrap_or(30))
+                                                />
+                                            </label>
+                                            <label>
+                                                "Timeout (s)"
+                                                <input type="number" min="1" style="width: 100%; padding: 6px;"
+                                                    prop:value=move || health_timeout.get().to_string()
+                                                    on:input=move |ev| set_health_timeout.set(event_target_value(&ev).parse().unwrap_or(5))
+                                                />
+                                            </label>
+                                            <label>
+                                                "Retries"
+                                                <input type="number" min="1" style="width: 100%; padding: 6px;"
+                                                    prop:value=move || health_retries.get().to_string()
+                                                    on:input=move |ev| set_health_retries.set(event_target_value(&ev).parse().unwrap_or(3))
+                                                />
+                                            </label>
+                                            <label>
+                                                "Start period (s)"
+                                                <input type="number" min="0" style="width: 100%; padding: 6px;"
+                                                    prop:value=move || health_start_period.get().to_string()
+                                                    on:input=move |ev| set_health_start_period.set(event_target_value(&ev).parse().unwrap_or(0))
+                                                />
+                                            </label>
                                         </div>
                                     </div>
 
@@ -1140,15 +2459,80 @@ where
 
                                     <div style="margin-top: 20px;">
                                         <h4>"Special Features:"</h4>
-                                        <div style="display: flex; gap: 20px;">
-                                            <label style="display: flex; align-items: center; gap: 5px; cursor: pointer;">
-                                                <input
-                                                    type="checkbox"
-                                                    prop:checked=move || enable_gaming.get()
-                                                    on:change=move |ev| set_enable_gaming.set(event_target_checked(&ev))
-                                                />
-                                                <span style="color: #9b59b6; font-weight: bold;">"Gaming Mode"</span>
+                                        <div style="display: flex; gap: 20px; align-items: center;">
+                                            <label style="display: flex; align-items: center; gap: 5px;">
+                                                <span style="color: #9b59b6; font-weight: bold;">"Gaming Profile:"</span>
+                                                <select
+                                                    style="padding: 6px; border-radius: 4px; border: 1px solid #444; background-color: #1a1a1a; color: #fff;"
+                                                    on:change=move |ev| {
+                                                        let name = event_target_value(&ev);
+                                                        set_gaming_profile.set((!name.is_empty()).then_some(name));
+                                                    }
+                                                >
+                                                    <option value="" selected=move || gaming_profile.get().is_none()>"None"</option>
+                                                    {move || gaming_profiles.get().into_iter().map(|profile| {
+                                                        let selected = gaming_profile.get().as_ref() == Some(&profile.name);
+                                                        view! {
+                                                            <option value=profile.name.clone() selected=selected>
+                                                                {if profile.builtin { format!("{} (built in)", profile.name) } else { profile.name.clone() }}
+                                                            </option>
+                                                        }
+                                                    }).collect_view()}
+                                                </select>
                                             </label>
+                                            {move || gaming_profile.get().is_some().then(|| view! {
+                                                <label style="display: flex; align-items: center; gap: 5px;">
+                                                    <span style="color: #9b59b6; font-weight: bold;">"Proton:"</span>
+                                                    <select
+                                                        style="padding: 6px; border-radius: 4px; border: 1px solid #444; background-color: #1a1a1a; color: #fff;"
+                                                        on:change=move |ev| {
+                                                            let name = event_target_value(&ev);
+                                                            set_proton_version.set((!name.is_empty()).then_some(name));
+                                                        }
+                                                    >
+                                                        <option value="" selected=move || proton_version.get().is_none()>"None"</option>
+                                                        {move || proton_builds.get().into_iter().map(|proton| {
+                                                            let selected = proton_version.get().as_ref() == Some(&proton.name);
+                                                            view! { <option value=proton.name.clone() selected=selected>{proton.name.clone()}</option> }
+                                                        }).collect_view()}
+                                                    </select>
+                                                    {move || proton_builds.get().is_empty().then(|| view! {
+                                                        <span style="color: #bbb; font-size: 12px;">"None installed, see the Gaming page"</span>
+                                                    })}
+                                                </label>
+                                            })}
+                                            {move || gaming_profile.get().is_some().then(|| view! {
+                                                <label style="display: flex; align-items: center; gap: 5px;">
+                                                    <span style="color: #9b59b6; font-weight: bold;">"Steam App ID:"</span>
+                                                    <input
+                                                        type="number"
+                                                        min="1"
+                                                        placeholder="440"
+                                                        style="width: 100px; padding: 6px; border-radius: 4px; border: 1px solid #444; background-color: #1a1a1a; color: #fff;"
+                                                        prop:value=move || steam_app_id.get().map(|id| id.to_string()).unwrap_or_default()
+                                                        on:change=move |ev| {
+                                                            let app_id = event_target_value(&ev).trim().parse::<u32>().ok();
+                                                            set_steam_app_id.set(app_id);
+                                                            set_steam_app.set(None);
+                                                            if let Some(app_id) = app_id {
+                                                                spawn_local(async move {
+                                                                    let app = match api_client::get(&format!("/api/v1/gaming/steam/apps/{}", app_id)).send().await {
+                                                                        Ok(response) if response.ok() => {
+                                                                            response.json::<SteamApp>().await.map_err(|e| e.to_string())
+                                                                        }
+                                                                        Ok(response) => Err(error_message(&response).await),
+                                                                        Err(e) => Err(e.to_string()),
+                                                                    };
+                                                                    // Ignore answers for an ID that has since been changed
+                                                                    if steam_app_id.get_untracked() == Some(app_id) {
+                                                                        set_steam_app.set(Some(app));
+                                                                    }
+                                                                });
+                                                            }
+                                                        }
+                                                    />
+                                                </label>
+                                            })}
                                             <label style="display: flex; align-items: center; gap: 5px; cursor: pointer;">
                                                 <input
                                                     type="checkbox"
@@ -1158,6 +2542,99 @@ where
                                                 <span style="color: #f39c12; font-weight: bold;">"GPU Access"</span>
                                             </label>
                                         </div>
+
+                                        {move || gaming_profile.get().and(steam_app.get()).map(|app| match app {
+                                            Ok(app) => view! {
+                                                <div style="display: flex; align-items: center; gap: 10px; margin-top: 10px;">
+                                                    {app.header_image.clone().map(|src| view! {
+                                                        <img src=src alt=app.name.clone() style="height: 45px; border-radius: 4px;"/>
+                                                    })}
+                                                    <span style="color: #2ecc71;">{app.name.clone()}</span>
+                                                    {app.stale.then(|| view! {
+                                                        <span style="color: #bbb; font-size: 12px;">"(cached, Steam unavailable)"</span>
+                                                    })}
+                                                </div>
+                                            }.into_view(),
+                                            Err(message) => view! {
+                                                <div style="color: #e74c3c; font-size: 12px; margin-top: 10px;">{message}</div>
+                                            }.into_view(),
+                                        })}
+
+                                        {move || enable_gpu.get().then(|| match gpus.get() {
+                                            None => view! { <p style="color: #bbb;">"Looking for GPUs..."</p> }.into_view(),
+                                            Some(list) if list.is_empty() => view! {
+                                                <p style="color: #e74c3c;">"No GPUs were found on the agent host"</p>
+                                            }.into_view(),
+                                            Some(list) => {
+                                                let selected = list.iter().find(|gpu| Some(&gpu.id) == gpu_device.get().as_ref()).cloned();
+                                                view! {
+                                                    <div style="display: grid; grid-template-columns: 2fr 1fr; gap: 10px; margin-top: 15px;">
+                                                        <div>
+                                                            <label>"Device:"</label>
+                                                            <select
+                                                                style="width: 100%; padding: 8px; border-radius: 4px; border: 1px solid #444; background-color: #1a1a1a; color: #fff;"
+                                                                on:change=move |ev| set_gpu_device.set(Some(event_target_value(&ev)))
+                                                            >
+                                                                {list.into_iter().map(|gpu| {
+                                                                    let memory = if gpu.memory_total_mb > 0 {
+                                                                        format!("{} MB", gpu.memory_total_mb)
+                                                                    } else {
+                                                                        "shared memory".to_string()
+                                                                    };
+                                                                    let users = match gpu.in_use_by.len() {
+                                                                        0 => String::new(),
+                                                                        n => format!(", used by {} running", n),
+                                                                    };
+                                                                    view! {
+                                                                        <option value=gpu.id.clone() selected=Some(&gpu.id) == gpu_device.get_untracked().as_ref()>
+                                                                            {format!("{} · {} ({}{})", gpu.id, gpu.name, memory, users)}
+                                                                        </option>
+                                                                    }
+                                                                }).collect_view()}
+                                                            </select>
+                                                        </div>
+                                                        <div>
+                                                            <label>"Memory (MB):"</label>
+                                                            <input
+                                                                type="number"
+                                                                min="1"
+                                                                max=selected.as_ref().filter(|gpu| gpu.memory_total_mb > 0).map(|gpu| gpu.memory_total_mb.to_string())
+                                                                placeholder="No limit"
+                                                                style="width: 100%; padding: 8px; border-radius: 4px; border: 1px solid #444; background-color: #1a1a1a; color: #fff;"
+                                                                prop:value=move || gpu_memory_mb.get().map(|mb| mb.to_string()).unwrap_or_default()
+                                                                on:input=move |ev| set_gpu_memory_mb.set(event_target_value(&ev).parse().ok().filter(|mb| *mb > 0))
+                                                            />
+                                                        </div>
+                                                    </div>
+                                                    <div style="display: grid; grid-template-columns: 2fr 1fr; gap: 10px; margin-top: 10px;">
+                                                        <div>
+                                                            <label>"Isolation:"</label>
+                                                            <select
+                                                                style="width: 100%; padding: 8px; border-radius: 4px; border: 1px solid #444; background-color: #1a1a1a; color: #fff;"
+                                                                prop:value=move || gpu_isolation.get()
+                                                                on:change=move |ev| set_gpu_isolation.set(event_target_value(&ev))
+                                                            >
+                                                                <option value="shared">"Shared: any container without an exclusive claim may use this GPU"</option>
+                                                                <option value="exclusive">"Exclusive: no other running container may use this GPU"</option>
+                                                                <option value="partitioned">"Partitioned: only one running container per partition"</option>
+                                                            </select>
+                                                        </div>
+                                                        <Show when=move || gpu_isolation.get() == "partitioned">
+                                                            <div>
+                                                                <label>"Partition ID:"</label>
+                                                                <input
+                                                                    type="text"
+                                                                    placeholder="e.g. MIG instance or vGPU profile"
+                                                                    style="width: 100%; padding: 8px; border-radius: 4px; border: 1px solid #444; background-color: #1a1a1a; color: #fff;"
+                                                                    prop:value=move || gpu_partition.get()
+                                                                    on:input=move |ev| set_gpu_partition.set(event_target_value(&ev))
+                                                                />
+                                                            </div>
+                                                        </Show>
+                                                    </div>
+                                                }.into_view()
+                                            }
+                                        })}
                                     </div>
                                 </div>
                             }.into_view(),
@@ -1220,12 +2697,17 @@ where
                                                                 style="width: 100%; padding: 6px; border: 1px solid #4a5568; border-radius: 4px; background-color: #2c3e50; color: white;"
                                                                 on:change=move |ev| {
                                                                     let mut current_ports = ports.get();
-                                                                    current_ports[index].protocol = event_target_value(&ev);
+                                                                    current_ports[index].protocol = match event_target_value(&ev).as_str() {
+                                                                        "udp" => Protocol::Udp,
+                                                                        "quic" => Protocol::Quic,
+                                                                        _ => Protocol::Tcp,
+                                                                    };
                                                                     set_ports.set(current_ports);
                                                                 }
                                                             >
-                                                                <option value="tcp" selected=port.protocol == "tcp">"TCP"</option>
-                                                                <option value="udp" selected=port.protocol == "udp">"UDP"</option>
+                                                                <option value="tcp" selected=port.protocol == Protocol::Tcp>"TCP"</option>
+                                                                <option value="udp" selected=port.protocol == Protocol::Udp>"UDP"</option>
+                                                                <option value="quic" selected=port.protocol == Protocol::Quic>"QUIC"</option>
                                                             </select>
                                                         </div>
                                                         <button
@@ -1239,9 +2721,33 @@ where
                                                             "×"
                                                         </button>
                                                     </div>
+                                                    {field_error_view(format!("ports[{}]", index))}
                                                 }
                                             }
                                         />
+                                        <details style="margin-top: 10px;">
+                                            <summary style="cursor: pointer; font-size: 12px;">"Advanced: paste port string"</summary>
+                                            <div style="display: flex; gap: 10px; margin-top: 10px;">
+                                                <input
+                                                    type="text"
+                                                    placeholder="8080:80, 127.0.0.1:5353:53/udp"
+                                                    style="flex: 1; padding: 6px; border: 1px solid #4a5568; border-radius: 4px; background-color: #2c3e50; color: white;"
+                                                    prop:value=move || port_spec.get()
+                                                    on:input=move |ev| set_port_spec.set(event_target_value(&ev))
+                                                    on:keydown=move |ev| {
+                                                        if ev.key() == "Enter" {
+                                                            add_port_spec();
+                                                        }
+                                                    }
+                                                />
+                                                <button class="btn-primary" style="padding: 5px 10px; font-size: 12px;" on:click=move |_| add_port_spec()>
+                                                    "Add"
+                                                </button>
+                                            </div>
+                                            {move || port_spec_error.get().map(|error| view! {
+                                                <p style="color: #e74c3c; font-size: 12px; margin: 5px 0 0;">{error}</p>
+                                            })}
+                                        </details>
                                     </div>
 
                                     // Volume mounts
@@ -1307,6 +2813,7 @@ where
                                                             "×"
                                                         </button>
                                                     </div>
+                                                    {field_error_view(format!("volumes[{}]", index))}
                                                 }
                                             }
                                         />
@@ -1326,6 +2833,7 @@ where
                                             children=move |(key, value)| {
                                                 let key_for_update = key.clone();
                                                 let key_for_delete = key.clone();
+                                                let key_for_error = key.clone();
                                                 view! {
                                                     <div style="display: grid; grid-template-columns: 1fr 1fr auto; gap: 10px; margin-bottom: 10px; align-items: end;">
                                                         <div>
@@ -1369,6 +2877,7 @@ where
                                                             "×"
                                                         </button>
                                                     </div>
+                                                    {field_error_view(format!("env.{}", key_for_error))}
                                                 }
                                             }
                                         />
@@ -1409,10 +2918,11 @@ where
                                                     <strong>"Restart Policy: "</strong>
                                                     <span style="color: #f39c12;">
                                                         {move || match restart_policy.get() {
-                                                            RestartPolicy::No => "No",
-                                                            RestartPolicy::Always => "Always",
-                                                            RestartPolicy::UnlessStopped => "Unless Stopped",
-                                                            RestartPolicy::OnFailure => "On Failure",
+                                                            RestartPolicy::No => "No".to_string(),
+                                                            RestartPolicy::Always => "Always".to_string(),
+                                                            RestartPolicy::UnlessStopped => "Unless Stopped".to_string(),
+                                                            RestartPolicy::OnFailure { max_retries: None } => "On Failure".to_string(),
+                                                            RestartPolicy::OnFailure { max_retries: Some(n) } => format!("On Failure (max {} retries)", n),
                                                         }}
                                                     </span>
                                                 </div>
@@ -1421,14 +2931,19 @@ where
                                                     <div style="margin-top: 5px;">
                                                         {move || {
                                                             let mut features = Vec::new();
-                                                            if enable_gaming.get() {
-                                                                features.push("Gaming Mode");
+                                                            if let Some(profile) = gaming_profile.get() {
+                                                                let mut details = vec![profile];
+                                                                details.extend(proton_version.get());
+                                                                if let Some(Ok(app)) = steam_app.get() {
+                                                                    details.push(app.name);
+                                                                }
+                                                                features.push(format!("Gaming ({})", details.join(", ")));
                                                             }
                                                             if enable_gpu.get() {
-                                                                features.push("GPU Access");
+                                                                features.push(format!("GPU Access ({})", gpu_device.get().unwrap_or_default()));
                                                             }
                                                             if features.is_empty() {
-                                                                features.push("Standard");
+                                                                features.push("Standard".to_string());
                                                             }
                                                             features.join(", ")
                                                         }}
@@ -1515,13 +3030,18 @@ where
                                     <button
                                         class="btn-primary"
                                         on:click=move |_| {
-                                            set_current_step.set(current_step.get() + 1);
+                                            if current_step.get() == 3 {
+                                                check_ports();
+                                            } else {
+                                                set_current_step.set(current_step.get() + 1);
+                                            }
                                         }
                                         disabled=move || {
                                             let step = current_step.get();
                                             match step {
                                                 1 => selected_image.get().is_none(),
                                                 2 => container_name.get().is_empty(),
+                                                3 => loading.get(),
                                                 _ => false
                                             }
                                         }
@@ -1550,19 +3070,75 @@ where
 
 /// Load registries for the wizard
 async fn load_registries_for_wizard(
-    set_registries: WriteSignal<Vec<RegistryConfig>>,
+    set_registries: WriteSignal<Vec<RegistryConfigResponse>>,
 ) {
-    match Request::get("http://localhost:8000/api/v1/registries")
+    match api_client::get("/api/v1/registries")
         .send()
         .await
     {
         Ok(response) => {
-            if let Ok(registry_list) = response.json::<Vec<RegistryConfig>>().await {
-                set_registries.set(registry_list);
+            if let Ok(registry_list) = response.json::<RegistryListResponse>().await {
+                set_registries.set(registry_list.registries);
             }
         }
         Err(_) => {
             // Silently handle error, user can still manually enter image names
         }
     }
-}
\ No newline at end of file
+}
+
+/// Load schedules targeting a container
+async fn load_schedules(
+    container_id: String,
+    set_schedules: WriteSignal<Vec<Schedule>>,
+    set_error_message: WriteSignal<Option<String>>,
+) {
+    let url = format!(
+        "/api/v1/schedules?container_id={}",
+        urlencoding::encode(&container_id)
+    );
+    match api_client::get(&url).send().await {
+        Ok(response) => {
+            if let Ok(schedule_list) = response.json::<ScheduleListResponse>().await {
+                set_schedules.set(schedule_list.schedules);
+            } else {
+                set_error_message.set(Some("Failed to parse schedules".to_string()));
+            }
+        }
+        Err(e) => {
+            set_error_message.set(Some(format!("Failed to load schedules: {}", e)));
+        }
+    }
+}
+
+/// Fetch the container's process list; stopped containers report why there is none
+async fn load_processes(id: String, aux: bool, set_processes: WriteSignal<Option<Result<ContainerTop, String>>>) {
+    let mut url = format!("/api/v1/containers/{}/top", urlencoding::encode(&id));
+    if aux {
+        url.push_str("?ps_args=aux");
+    }
+
+    let result = match api_client::get(&url).send().await {
+        Ok(response) if response.ok() => response
+            .json::<ContainerTop>()
+            .await
+            .map_err(|e| format!("❌ Failed to parse process list: {}", e)),
+        Ok(response) => Err(error_message(&response).await),
+        Err(e) => Err(format!("❌ Failed to load processes: {}", e)),
+    };
+    set_processes.set(Some(result));
+}
+
+/// Fetch one stats sample into the chart buffer; stopped containers have no stats
+async fn load_stats(id: String, stats_history: RwSignal<StatsHistory>) {
+    let url = format!("/api/v1/containers/{}/stats", urlencoding::encode(&id));
+    if let Ok(response) = api_client::get(&url).send().await {
+        if response.ok() {
+            if let Ok(sample) = response.json::<ContainerStats>().await {
+                if sample.container_id == id {
+                    stats_history.update(|history| history.push(sample));
+                }
+            }
+        }
+    }
+}
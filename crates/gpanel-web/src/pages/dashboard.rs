@@ -1,31 +1,419 @@
 use leptos::*;
+use leptos_router::*;
+use crate::services::api_client;
+use gpanel_core::{
+    BoltSystemInfo, Container, ContainerListResponse, DiskUsageSummary, HostMetrics, MetricsSummary, Network,
+    NetworkListResponse,
+};
+
+/// Format file size in human readable format
+fn format_size(size: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+    let mut size = size as f64;
+    let mut unit_index = 0;
+
+    while size >= 1024.0 && unit_index < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit_index += 1;
+    }
+
+    format!("{:.1} {}", size, UNITS[unit_index])
+}
+
+/// Format an uptime in seconds as days, hours and minutes
+fn format_uptime(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60);
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else {
+        format!("{}h {}m", hours, minutes)
+    }
+}
+
+/// Percentage of `used` in `total`, 0 when the total is unknown
+fn percent_of(used: u64, total: u64) -> f64 {
+    if total > 0 { used as f64 * 100.0 / total as f64 } else { 0.0 }
+}
+
+/// Horizontal gauge that turns amber from 70% and red from 90%
+#[component]
+fn Gauge(#[prop(into)] label: String, percent: f64, #[prop(into)] detail: String) -> impl IntoView {
+    let color = if percent >= 90.0 {
+        "#e74c3c"
+    } else if percent >= 70.0 {
+        "#f39c12"
+    } else {
+        "#27ae60"
+    };
+
+    view! {
+        <div>
+            <div style="display: flex; justify-content: space-between; font-size: 14px;">
+                <strong>{label}</strong>
+                <span>{format!("{:.0}%", percent)}</span>
+            </div>
+            <div style="height: 10px; border-radius: 4px; overflow: hidden; background-color: #1a1a1a; margin: 6px 0;">
+                <div style=format!("width: {:.2}%; height: 100%; background-color: {};", percent.clamp(0.0, 100.0), color)></div>
+            </div>
+            <div class="stat-label">{detail}</div>
+        </div>
+    }
+}
 
 #[component]
 pub fn Dashboard() -> impl IntoView {
+    let (disk_usage, set_disk_usage) = create_signal(None::<DiskUsageSummary>);
+    let (metrics, set_metrics) = create_signal(None::<MetricsSummary>);
+    let (host, set_host) = create_signal(None::<HostMetrics>);
+    let (system_info, set_system_info) = create_signal(None::<BoltSystemInfo>);
+    let (containers, set_containers) = create_signal(None::<Vec<Container>>);
+    let (networks, set_networks) = create_signal(None::<Vec<Network>>);
+
+    // Load metrics now and every 10 seconds
+    spawn_local(load_metrics(set_metrics));
+    if let Ok(handle) = set_interval_with_handle(
+        move || spawn_local(load_metrics(set_metrics)),
+        std::time::Duration::from_secs(10),
+    ) {
+        on_cleanup(move || handle.clear());
+    }
+
+    // Host gauges move faster, so refresh them every 3 seconds
+    spawn_local(load_host_metrics(set_host));
+    if let Ok(handle) = set_interval_with_handle(
+        move || spawn_local(load_host_metrics(set_host)),
+        std::time::Duration::from_secs(3),
+    ) {
+        on_cleanup(move || handle.clear());
+    }
+
+    // Load disk usage and the inventory counts on mount
+    create_effect(move |_| {
+        spawn_local(load_inventory(set_system_info, set_containers, set_networks));
+        spawn_local(async move {
+            if let Ok(response) = api_client::get("/api/v1/system/df")
+                .send()
+                .await
+            {
+                if let Ok(summary) = response.json::<DiskUsageSummary>().await {
+                    set_disk_usage.set(Some(summary));
+                }
+            }
+        });
+    });
+
     view! {
         <div class="dashboard">
             <div class="stats-grid">
                 <div class="container-card">
                     <h3>"Containers"</h3>
-                    <div class="stat-value">"12"</div>
-                    <div class="stat-label">"4 running, 8 stopped"</div>
+                    <div class="stat-value">{move || metrics.get().map(|m| m.containers.total.to_string()).unwrap_or_else(|| "-".to_string())}</div>
+                    <div class="stat-label">
+                        {move || metrics.get().map(|m| format!(
+                            "{} running, {} stopped",
+                            m.containers.running,
+                            m.containers.stopped
+                        ))}
+                    </div>
                 </div>
                 <div class="container-card">
                     <h3>"Images"</h3>
-                    <div class="stat-value">"8"</div>
-                    <div class="stat-label">"2.4 GB total"</div>
+                    <div class="stat-value">{move || system_info.get().map(|info| info.images_count.to_string()).unwrap_or_else(|| "-".to_string())}</div>
+                    <div class="stat-label">
+                        {move || disk_usage.get().map(|usage| format!("{} total", format_size(usage.images.total_bytes)))}
+                    </div>
                 </div>
                 <div class="container-card">
                     <h3>"Networks"</h3>
-                    <div class="stat-value">"3"</div>
-                    <div class="stat-label">"1 gaming network"</div>
+                    <div class="stat-value">{move || networks.get().map(|n| n.len().to_string()).unwrap_or_else(|| "-".to_string())}</div>
+                    <div class="stat-label">
+                        {move || networks.get().map(|n| {
+                            let gaming = n.iter().filter(|network| network.is_gaming()).count();
+                            format!("{} gaming network{}", gaming, if gaming == 1 { "" } else { "s" })
+                        })}
+                    </div>
                 </div>
                 <div class="container-card">
                     <h3>"Gaming Containers"</h3>
-                    <div class="stat-value">"2"</div>
-                    <div class="stat-label">"1 with GPU access"</div>
+                    <div class="stat-value">
+                        {move || containers.get().map(|c| c.iter().filter(|c| c.gaming_config.is_some()).count().to_string()).unwrap_or_else(|| "-".to_string())}
+                    </div>
+                    <div class="stat-label">
+                        {move || containers.get().map(|c| {
+                            format!("{} with GPU access", c.iter().filter(|c| c.gpu_allocation.is_some()).count())
+                        })}
+                    </div>
+                </div>
+                <div class="container-card">
+                    <h3>"Runtime"</h3>
+                    <div class="stat-value">{move || system_info.get().map(|info| format!("Bolt {}", info.version)).unwrap_or_else(|| "-".to_string())}</div>
+                    <div class="stat-label">
+                        {move || system_info.get().map(|info| format!(
+                            "{} ({}), {} CPUs, {} memory",
+                            info.os,
+                            info.architecture,
+                            info.cpus,
+                            format_size(info.memory_total)
+                        ))}
+                    </div>
                 </div>
             </div>
+
+            // Host
+            {move || host.get().map(|host| {
+                let memory_detail = format!(
+                    "{} of {}, {} available",
+                    format_size(host.memory.used_bytes),
+                    format_size(host.memory.total_bytes),
+                    format_size(host.memory.available_bytes)
+                );
+
+                view! {
+                    <div class="container-card" style="margin-top: 20px;">
+                        <div style="display: flex; justify-content: space-between; align-items: center;">
+                            <h3>{host.hostname.clone().map(|name| format!("Host {}", name)).unwrap_or_else(|| "Host".to_string())}</h3>
+                            <span style="color: #bbb;">
+                                {format!(
+                                    "{} · kernel {} · up {}",
+                                    host.os.clone().unwrap_or_else(|| "Unknown OS".to_string()),
+                                    host.kernel_version.clone().unwrap_or_else(|| "-".to_string()),
+                                    format_uptime(host.uptime_secs)
+                                )}
+                            </span>
+                        </div>
+                        <div style="display: grid; grid-template-columns: repeat(auto-fit, minmax(200px, 1fr)); gap: 15px; color: #bbb; margin-top: 10px;">
+                            <Gauge
+                                label="CPU"
+                                percent=host.cpu.usage_percent
+                                detail=format!(
+                                    "{} cores · load {:.2} {:.2} {:.2}",
+                                    host.cpu.cores,
+                                    host.load_average.one,
+                                    host.load_average.five,
+                                    host.load_average.fifteen
+                                )
+                            />
+                            <Gauge
+                                label="Memory"
+                                percent=percent_of(host.memory.used_bytes, host.memory.total_bytes)
+                                detail=memory_detail
+                            />
+                            {host.data_filesystem.map(|fs| view! {
+                                <Gauge
+                                    label=format!("Disk {}", fs.mount_point)
+                                    percent=percent_of(fs.used_bytes, fs.total_bytes)
+                                    detail=format!(
+                                        "{} free of {} ({})",
+                                        format_size(fs.available_bytes),
+                                        format_size(fs.total_bytes),
+                                        fs.path
+                                    )
+                                />
+                            })}
+                            {host.gpus.into_iter().map(|gpu| view! {
+                                <Gauge
+                                    label=format!("GPU {} {}", gpu.index, gpu.name)
+                                    percent=gpu.utilization_percent
+                                    detail=format!(
+                                        "{} / {}{}",
+                                        format_size(gpu.memory_used_bytes),
+                                        format_size(gpu.memory_total_bytes),
+                                        gpu.temperature_celsius.map(|c| format!(" · {}°C", c)).unwrap_or_default()
+                                    )
+                                />
+                            }).collect_view()}
+                        </div>
+                    </div>
+                }
+            })}
+
+            // Resource usage
+            {move || metrics.get().map(|summary| {
+                let memory_percent = if summary.memory_limit_bytes > 0 {
+                    summary.memory_used_bytes as f64 * 100.0 / summary.memory_limit_bytes as f64
+                } else {
+                    0.0
+                };
+
+                view! {
+                    <div class="container-card" style="margin-top: 20px;">
+                        <h3>"Resource Usage"</h3>
+                        <div style="display: grid; grid-template-columns: repeat(auto-fit, minmax(200px, 1fr)); gap: 15px; font-size: 14px; color: #bbb;">
+                            <div>
+                                <div class="stat-value">{format!("{:.1}%", summary.cpu_percent)}</div>
+                                <div class="stat-label">"CPU (all containers)"</div>
+                            </div>
+                            <div>
+                                <div class="stat-value">{format_size(summary.memory_used_bytes)}</div>
+                                <div class="stat-label">
+                                    {format!("of {} limit ({:.0}%)", format_size(summary.memory_limit_bytes), memory_percent)}
+                                </div>
+                            </div>
+                            <div>
+                                <div>{format!("Network ↓ {}/s", format_size(summary.network_rx_bytes_per_sec as u64))}</div>
+                                <div>{format!("Network ↑ {}/s", format_size(summary.network_tx_bytes_per_sec as u64))}</div>
+                                <div>{format!("Disk read {}/s", format_size(summary.disk_read_bytes_per_sec as u64))}</div>
+                                <div>{format!("Disk write {}/s", format_size(summary.disk_write_bytes_per_sec as u64))}</div>
+                            </div>
+                            {summary.gpu.map(|gpu| view! {
+                                <div>
+                                    <div class="stat-value">{format!("{:.0}%", gpu.average_utilization)}</div>
+                                    <div class="stat-label">
+                                        {format!(
+                                            "GPU across {} containers, {} / {} MB",
+                                            gpu.containers,
+                                            gpu.memory_used_mb,
+                                            gpu.memory_total_mb
+                                        )}
+                                    </div>
+                                </div>
+                            })}
+                        </div>
+
+                        <div style="display: grid; grid-template-columns: 1fr 1fr; gap: 20px; margin-top: 15px; font-size: 14px;">
+                            <div>
+                                <h4>"Top CPU"</h4>
+                                {summary.top_cpu.into_iter().map(|container| view! {
+                                    <div style="display: flex; justify-content: space-between; color: #bbb;">
+                                        <A href=format!("/containers/{}", container.id)>{container.name}</A>
+                                        <span>{format!("{:.1}%", container.cpu_percent)}</span>
+                                    </div>
+                                }).collect_view()}
+                            </div>
+                            <div>
+                                <h4>"Top Memory"</h4>
+                                {summary.top_memory.into_iter().map(|container| view! {
+                                    <div style="display: flex; justify-content: space-between; color: #bbb;">
+                                        <A href=format!("/containers/{}", container.id)>{container.name}</A>
+                                        <span>{format_size(container.memory_used_bytes)}</span>
+                                    </div>
+                                }).collect_view()}
+                            </div>
+                        </div>
+                    </div>
+                }
+            })}
+
+            // Disk usage
+            {move || disk_usage.get().map(|usage| {
+                let build_cache_bytes = usage.build_cache.as_ref().map(|c| c.total_bytes).unwrap_or(0);
+                let segments = [
+                    ("Images", usage.images.total_bytes, "#3498db"),
+                    ("Containers", usage.containers.writable_bytes, "#9b59b6"),
+                    ("Volumes", usage.volumes.total_bytes, "#27ae60"),
+                    ("Build cache", build_cache_bytes, "#f39c12"),
+                ];
+                let total = usage.total_bytes.max(1);
+
+                view! {
+                    <div class="container-card" style="margin-top: 20px;">
+                        <div style="display: flex; justify-content: space-between; align-items: center;">
+                            <h3>"Disk Usage"</h3>
+                            <span style="color: #bbb;">
+                                {format!(
+                                    "{} used, {} reclaimable",
+                                    format_size(usage.total_bytes),
+                                    format_size(usage.reclaimable_bytes)
+                                )}
+                            </span>
+                        </div>
+
+                        {usage.suggests_prune().then(|| view! {
+                            <div style="padding: 8px 12px; border-left: 3px solid #f39c12; background-color: #2a2418; color: #f39c12; font-size: 14px;">
+                                {format!(
+                                    "💡 {} could be freed by pruning unused images, stopped containers and unused volumes",
+                                    format_size(usage.reclaimable_bytes)
+                                )}
+                            </div>
+                        })}
+
+                        <div style="display: flex; height: 16px; border-radius: 4px; overflow: hidden; background-color: #1a1a1a; margin: 15px 0;">
+                            {segments.iter().map(|(label, bytes, color)| view! {
+                                <div
+                                    title=format!("{}: {}", label, format_size(*bytes))
+                                    style=format!("width: {:.2}%; background-color: {};", *bytes as f64 * 100.0 / total as f64, color)
+                                ></div>
+                            }).collect_view()}
+                        </div>
+
+                        <div style="display: grid; grid-template-columns: repeat(auto-fit, minmax(200px, 1fr)); gap: 15px; font-size: 14px; color: #bbb;">
+                            <div>
+                                <div><strong style="color: #3498db;">"Images: "</strong> {format_size(usage.images.total_bytes)}</div>
+                                <div>{format!("{} images, {} dangling", usage.images.count, format_size(usage.images.dangling_bytes))}</div>
+                                <div>{format!("{} reclaimable", format_size(usage.images.reclaimable_bytes))}</div>
+                                <A href="/images?tab=local" class="btn-primary">"Prune images"</A>
+                            </div>
+                            <div>
+                                <div><strong style="color: #9b59b6;">"Containers: "</strong> {format_size(usage.containers.writable_bytes)}</div>
+                                <div>{format!("{} containers", usage.containers.count)}</div>
+                                <div>{format!("{} in stopped containers", format_size(usage.containers.reclaimable_bytes))}</div>
+                                <A href="/containers" class="btn-primary">"Manage containers"</A>
+                            </div>
+                            <div>
+                                <div><strong style="color: #27ae60;">"Volumes: "</strong> {format_size(usage.volumes.total_bytes)}</div>
+                                <div>{format!("{} volumes", usage.volumes.count)}</div>
+                                <div>{format!("{} unused", format_size(usage.volumes.unused_bytes))}</div>
+                                <A href="/volumes" class="btn-primary">"Prune volumes"</A>
+                            </div>
+                            {usage.build_cache.map(|cache| view! {
+                                <div>
+                                    <div><strong style="color: #f39c12;">"Build cache: "</strong> {format_size(cache.total_bytes)}</div>
+                                    <div>{format!("{} reclaimable", format_size(cache.reclaimable_bytes))}</div>
+                                </div>
+                            })}
+                        </div>
+                    </div>
+                }
+            })}
         </div>
     }
-}
\ No newline at end of file
+}
+
+/// Load the aggregate metrics summary
+async fn load_metrics(set_metrics: WriteSignal<Option<MetricsSummary>>) {
+    if let Ok(response) = api_client::get("/api/v1/metrics/summary")
+        .send()
+        .await
+    {
+        if let Ok(summary) = response.json::<MetricsSummary>().await {
+            set_metrics.set(Some(summary));
+        }
+    }
+}
+
+/// Load the agent host's CPU, memory, disk and GPU figures
+async fn load_host_metrics(set_host: WriteSignal<Option<HostMetrics>>) {
+    if let Ok(response) = api_client::get("/api/v1/system/metrics")
+        .send()
+        .await
+    {
+        if let Ok(host) = response.json::<HostMetrics>().await {
+            set_host.set(Some(host));
+        }
+    }
+}
+
+/// Load the runtime info, containers and networks behind the summary cards
+async fn load_inventory(
+    set_system_info: WriteSignal<Option<BoltSystemInfo>>,
+    set_containers: WriteSignal<Option<Vec<Container>>>,
+    set_networks: WriteSignal<Option<Vec<Network>>>,
+) {
+    if let Ok(response) = api_client::get("/api/v1/system/info").send().await {
+        if let Ok(info) = response.json::<BoltSystemInfo>().await {
+            set_system_info.set(Some(info));
+        }
+    }
+
+    if let Ok(response) = api_client::get("/api/v1/containers").send().await {
+        if let Ok(list) = response.json::<ContainerListResponse>().await {
+            set_containers.set(Some(list.containers));
+        }
+    }
+
+    if let Ok(response) = api_client::get("/api/v1/networks").send().await {
+        if let Ok(list) = response.json::<NetworkListResponse>().await {
+            set_networks.set(Some(list.networks));
+        }
+    }
+}
@@ -1,13 +1,819 @@
 use leptos::*;
+use gpanel_core::{
+    Container, ContainerStatus, GamingAlertThresholds, GamingProfile, GamingProfileRequest, GpuDevice, Job, JobAccepted,
+    JobStatus, ProtonInstallRequest, ProtonVersions, Role, SteamApp, CPU_GOVERNORS, short_id,
+};
+use std::collections::HashMap;
+
+use crate::auth::AuthContext;
+use crate::pages::images::load_job;
+use crate::services::gaming::{self as gaming_service, StatsStream};
+use crate::services::{api_client, error_message};
+use crate::utils::stats::{ChartPoint, StatsHistory};
+
+const MIB: u64 = 1024 * 1024;
+
+/// Samples kept in each gaming container's sparklines
+const SPARKLINE_POINTS: usize = 60;
+
+/// Seconds between stats samples, and between reloads of the container and GPU lists
+const STATS_INTERVAL_SECS: u64 = 2;
+const REFRESH_INTERVAL_SECS: u64 = 10;
+
+/// Log lines shown by a container's Logs action
+const LOG_TAIL: u32 = 200;
+
+async fn load_profiles(set_profiles: WriteSignal<Vec<GamingProfile>>, set_error_message: WriteSignal<Option<String>>) {
+    match api_client::get("/api/v1/gaming/profiles").send().await {
+        Ok(response) if response.ok() => {
+            if let Ok(profiles) = response.json::<Vec<GamingProfile>>().await {
+                set_profiles.set(profiles);
+            }
+        }
+        Ok(response) => set_error_message.set(Some(error_message(&response).await)),
+        Err(e) => set_error_message.set(Some(format!("Failed to load gaming profiles: {}", e))),
+    }
+}
+
+async fn load_proton(set_proton: WriteSignal<Option<ProtonVersions>>, set_error_message: WriteSignal<Option<String>>) {
+    match api_client::get("/api/v1/gaming/proton/versions").send().await {
+        Ok(response) if response.ok() => {
+            if let Ok(versions) = response.json::<ProtonVersions>().await {
+                set_proton.set(Some(versions));
+            }
+        }
+        Ok(response) => set_error_message.set(Some(error_message(&response).await)),
+        Err(e) => set_error_message.set(Some(format!("Failed to load Proton versions: {}", e))),
+    }
+}
+
+/// Load the gaming containers and GPUs, following the stats of running containers and
+/// looking up the Steam apps they name
+async fn load_games(
+    games: RwSignal<Option<Result<Vec<Container>, String>>>,
+    gpus: RwSignal<Option<Result<Vec<GpuDevice>, String>>>,
+    histories: RwSignal<HashMap<String, StatsHistory>>,
+    streams: StoredValue<HashMap<String, StatsStream>>,
+    steam_apps: RwSignal<HashMap<u32, SteamApp>>,
+) {
+    let (list, inventory) = futures::join!(gaming_service::gaming_containers(), gaming_service::gpu_inventory());
+    gpus.set(Some(inventory));
+    let containers = match list {
+        Ok(containers) => containers,
+        Err(e) => {
+            games.set(Some(Err(e)));
+            return;
+        }
+    };
+
+    // Streams end when their container stops, so reopen any that are closed
+    let running: Vec<String> = containers
+        .iter()
+        .filter(|container| matches!(container.status, ContainerStatus::Running))
+        .map(|container| container.id.clone())
+        .collect();
+    streams.update_value(|streams| {
+        streams.retain(|id, stream| stream.is_open() && running.contains(id));
+        for id in &running {
+            if streams.contains_key(id) {
+                continue;
+            }
+            let sample_id = id.clone();
+            let stream = gaming_service::stream_stats(id, STATS_INTERVAL_SECS, move |sample| {
+                histories.update(|histories| {
+                    histories
+                        .entry(sample_id.clone())
+                        .or_insert_with(|| StatsHistory::new(SPARKLINE_POINTS))
+                        .push(sample)
+                });
+            });
+            if let Ok(stream) = stream {
+                streams.insert(id.clone(), stream);
+            }
+        }
+    });
+    histories.update(|histories| histories.retain(|id, _| running.contains(id)));
+
+    let mut app_ids: Vec<u32> = containers
+        .iter()
+        .filter_map(|container| container.gaming_config.as_ref()?.steam_app_id)
+        .collect();
+    app_ids.sort_unstable();
+    app_ids.dedup();
+    games.set(Some(Ok(containers)));
+
+    for app_id in app_ids {
+        if steam_apps.with_untracked(|apps| apps.contains_key(&app_id)) {
+            continue;
+        }
+        if let Some(app) = gaming_service::steam_app(app_id).await {
+            steam_apps.update(|apps| {
+                apps.insert(app_id, app);
+            });
+        }
+    }
+}
+
+fn status_label(status: &ContainerStatus) -> String {
+    match status {
+        ContainerStatus::Exited { code } => format!("Exited ({})", code),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Latest value of a gaming metric with its sparkline, or a dash when the container does
+/// not report it
+fn metric_view(
+    label: &'static str,
+    unit: &'static str,
+    color: &'static str,
+    history: Option<&StatsHistory>,
+    value: fn(&ChartPoint) -> Option<f32>,
+) -> View {
+    let latest = history.and_then(|history| history.latest()).and_then(value);
+    let line = history.and_then(|history| history.sparkline(value, 120.0, 30.0));
+    view! {
+        <div>
+            <div style="font-size: 0.85em; color: #aaa;">{label}</div>
+            <div style=format!("font-weight: bold; color: {};", color)>
+                {latest.map(|v| format!("{:.1} {}", v, unit)).unwrap_or_else(|| "—".to_string())}
+            </div>
+            {line.map(|points| view! {
+                <svg viewBox="0 0 120 30" preserveAspectRatio="none" style="width: 120px; height: 30px; background-color: #1a1a1a; border-radius: 3px;">
+                    <polyline fill="none" stroke=color stroke-width="1.5" points=points/>
+                </svg>
+            })}
+        </div>
+    }
+    .into_view()
+}
+
+/// Optional number field; empty means unset
+fn parse_optional<T: std::str::FromStr>(value: &str, field: &str) -> Result<Option<T>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value.parse().map(Some).map_err(|_| format!("{} must be a whole number", field))
+}
 
 #[component]
 pub fn GamingDashboard() -> impl IntoView {
+    let auth_context = use_context::<AuthContext>().expect("AuthContext must be provided");
+    let can_operate = move || auth_context.has_role(Role::Operator);
+    let (profiles, set_profiles) = create_signal(Vec::<GamingProfile>::new());
+    let (loading, set_loading) = create_signal(false);
+    let (error_message_signal, set_error_message) = create_signal(None::<String>);
+
+    // `None` hides the editor, `Some(None)` creates a profile, `Some(Some(name))` edits one
+    let (editing, set_editing) = create_signal(None::<Option<String>>);
+    let (profile_name, set_profile_name) = create_signal(String::new());
+    let (description, set_description) = create_signal(String::new());
+    let (cpu_governor, set_cpu_governor) = create_signal(String::new());
+    let (nice_level, set_nice_level) = create_signal(String::new());
+    let (gpu_power_limit, set_gpu_power_limit) = create_signal(String::new());
+    let (gamescope_args, set_gamescope_args) = create_signal(String::new());
+
+    let (proton, set_proton) = create_signal(None::<ProtonVersions>);
+    let (install_jobs, set_install_jobs) = create_signal(Vec::<Job>::new());
+
+    let games = create_rw_signal(None::<Result<Vec<Container>, String>>);
+    let gpus = create_rw_signal(None::<Result<Vec<GpuDevice>, String>>);
+    let histories = create_rw_signal(HashMap::<String, StatsHistory>::new());
+    let streams = store_value(HashMap::<String, StatsStream>::new());
+    let steam_apps = create_rw_signal(HashMap::<u32, SteamApp>::new());
+    let (thresholds, set_thresholds) = create_signal(gaming_service::alert_thresholds());
+    let (restarting, set_restarting) = create_signal(None::<String>);
+    // Name and text of the logs being shown
+    let (logs, set_logs) = create_signal(None::<(String, String)>);
+
+    create_effect(move |_| {
+        spawn_local(load_profiles(set_profiles, set_error_message));
+        spawn_local(load_proton(set_proton, set_error_message));
+        spawn_local(load_games(games, gpus, histories, streams, steam_apps));
+    });
+
+    // Pick up started, stopped and new gaming containers, and the GPUs they hold
+    if let Ok(handle) = set_interval_with_handle(
+        move || spawn_local(load_games(games, gpus, histories, streams, steam_apps)),
+        std::time::Duration::from_secs(REFRESH_INTERVAL_SECS),
+    ) {
+        on_cleanup(move || handle.clear());
+    }
+    on_cleanup(move || streams.update_value(|streams| streams.clear()));
+
+    let restart_game = move |id: String| {
+        spawn_local(async move {
+            set_restarting.set(Some(id.clone()));
+            match gaming_service::restart_container(&id).await {
+                Ok(()) => {
+                    // The old stream ended with the restart; the reload opens a new one
+                    streams.update_value(|streams| {
+                        streams.remove(&id);
+                    });
+                    load_games(games, gpus, histories, streams, steam_apps).await;
+                }
+                Err(e) => set_error_message.set(Some(e)),
+            }
+            set_restarting.set(None);
+        });
+    };
+
+    let show_logs = move |id: String, name: String| {
+        spawn_local(async move {
+            match gaming_service::container_logs(&id, LOG_TAIL).await {
+                Ok(text) => set_logs.set(Some((name, text))),
+                Err(e) => set_error_message.set(Some(e)),
+            }
+        });
+    };
+
+    let update_thresholds = move |update: fn(&mut GamingAlertThresholds, f32), value: String| {
+        if let Ok(value) = value.trim().parse::<f32>()
+            && value > 0.0
+        {
+            set_thresholds.update(|thresholds| update(thresholds, value));
+            gaming_service::set_alert_thresholds(&thresholds.get_untracked());
+        }
+    };
+
+    // Running containers over a threshold, by name
+    let warnings = move || {
+        let thresholds = thresholds.get();
+        let names: HashMap<String, String> = games.with(|games| match games {
+            Some(Ok(games)) => games.iter().map(|game| (game.id.clone(), game.name.clone())).collect(),
+            _ => HashMap::new(),
+        });
+        histories.with(|histories| {
+            let mut warnings: Vec<String> = histories
+                .iter()
+                .filter_map(|(id, history)| Some((names.get(id)?, history.last_sample()?)))
+                .flat_map(|(name, sample)| {
+                    thresholds.warnings(sample).into_iter().map(move |warning| format!("{}: {}", name, warning))
+                })
+                .collect();
+            warnings.sort();
+            warnings
+        })
+    };
+
+    let install_proton = move |tag: String| {
+        spawn_local(async move {
+            let request = ProtonInstallRequest { tag };
+            match api_client::post("/api/v1/gaming/proton/install").json(&request).unwrap().send().await {
+                Ok(response) if response.ok() => {
+                    if let Ok(accepted) = response.json::<JobAccepted>().await
+                        && let Some(job) = load_job(&accepted.job_id).await
+                    {
+                        set_install_jobs.update(|jobs| jobs.insert(0, job));
+                    }
+                }
+                Ok(response) => set_error_message.set(Some(error_message(&response).await)),
+                Err(e) => set_error_message.set(Some(format!("Failed to start the install: {}", e))),
+            }
+        });
+    };
+
+    // Follow running installs every second, reloading the installed list when one finishes
+    let refresh_install_jobs = move || {
+        spawn_local(async move {
+            let running: Vec<String> = install_jobs
+                .get_untracked()
+                .into_iter()
+                .filter(|job| job.status == JobStatus::Running)
+                .map(|job| job.id)
+                .collect();
+            for id in running {
+                let Some(job) = load_job(&id).await else {
+                    continue;
+                };
+                match job.status {
+                    JobStatus::Succeeded => load_proton(set_proton, set_error_message).await,
+                    JobStatus::Failed => {
+                        set_error_message.set(Some(format!("Install of {} failed: {}", job.target, job.message)));
+                    }
+                    JobStatus::Running | JobStatus::Cancelled => {}
+                }
+                set_install_jobs.update(|jobs| {
+                    if let Some(existing) = jobs.iter_mut().find(|existing| existing.id == job.id) {
+                        *existing = job;
+                    }
+                });
+            }
+        });
+    };
+    if let Ok(handle) = set_interval_with_handle(refresh_install_jobs, std::time::Duration::from_secs(1)) {
+        on_cleanup(move || handle.clear());
+    }
+    let installing = move |tag: &str| {
+        install_jobs.with(|jobs| jobs.iter().find(|job| job.target == tag && job.status == JobStatus::Running).cloned())
+    };
+
+    let open_editor = move |profile: Option<GamingProfile>| {
+        let profile = profile.unwrap_or(GamingProfile {
+            name: String::new(),
+            description: None,
+            cpu_governor: None,
+            nice_level: None,
+            gpu_power_limit_watts: None,
+            gamescope_args: Vec::new(),
+            builtin: false,
+        });
+        set_profile_name.set(profile.name.clone());
+        set_description.set(profile.description.unwrap_or_default());
+        set_cpu_governor.set(profile.cpu_governor.unwrap_or_default());
+        set_nice_level.set(profile.nice_level.map(|nice| nice.to_string()).unwrap_or_default());
+        set_gpu_power_limit.set(profile.gpu_power_limit_watts.map(|watts| watts.to_string()).unwrap_or_default());
+        set_gamescope_args.set(profile.gamescope_args.join(" "));
+        set_editing.set(Some((!profile.name.is_empty()).then_some(profile.name)));
+    };
+
+    let save_profile = move |_| {
+        let Some(original) = editing.get_untracked() else {
+            return;
+        };
+        let request = match (
+            parse_optional::<i8>(&nice_level.get_untracked(), "Nice level"),
+            parse_optional::<u32>(&gpu_power_limit.get_untracked(), "GPU power limit"),
+        ) {
+            (Ok(nice_level), Ok(gpu_power_limit_watts)) => GamingProfileRequest {
+                name: profile_name.get_untracked().trim().to_string(),
+                description: Some(description.get_untracked().trim().to_string()).filter(|text| !text.is_empty()),
+                cpu_governor: Some(cpu_governor.get_untracked()).filter(|governor| !governor.is_empty()),
+                nice_level,
+                gpu_power_limit_watts,
+                gamescope_args: gamescope_args.get_untracked().split_whitespace().map(str::to_string).collect(),
+            },
+            (Err(e), _) | (_, Err(e)) => {
+                set_error_message.set(Some(e));
+                return;
+            }
+        };
+
+        spawn_local(async move {
+            set_loading.set(true);
+            let builder = match &original {
+                Some(name) => api_client::put(&format!("/api/v1/gaming/profiles/{}", urlencoding::encode(name))),
+                None => api_client::post("/api/v1/gaming/profiles"),
+            };
+            match builder.json(&request).unwrap().send().await {
+                Ok(response) if response.ok() => {
+                    set_error_message.set(None);
+                    set_editing.set(None);
+                    load_profiles(set_profiles, set_error_message).await;
+                }
+                Ok(response) => set_error_message.set(Some(error_message(&response).await)),
+                Err(e) => set_error_message.set(Some(format!("Failed to save gaming profile: {}", e))),
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let delete_profile = move |name: String| {
+        spawn_local(async move {
+            set_loading.set(true);
+            let url = format!("/api/v1/gaming/profiles/{}", urlencoding::encode(&name));
+            match api_client::delete(&url).send().await {
+                Ok(response) if response.ok() => {
+                    set_profiles.update(|list| list.retain(|profile| profile.name != name));
+                }
+                Ok(response) => set_error_message.set(Some(error_message(&response).await)),
+                Err(e) => set_error_message.set(Some(format!("Failed to delete gaming profile: {}", e))),
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let input_style = "width: 100%; padding: 8px; border: 1px solid #555; border-radius: 4px; background-color: #2c3e50; color: white;";
+
     view! {
         <div class="gaming-dashboard">
-            <h2>"🎮 Gaming Dashboard"</h2>
+            <div class="header-section">
+                <h2>"🎮 Gaming Dashboard"</h2>
+                <p>"Optimization profiles applied to gaming containers. Built-in profiles are read-only."</p>
+                <Show when=can_operate>
+                    <button class="btn-primary" on:click=move |_| open_editor(None)>
+                        "New Profile"
+                    </button>
+                </Show>
+            </div>
+
+            {move || error_message_signal.get().map(|message| view! {
+                <div class="error-banner" style="background-color: #e74c3c; color: white; padding: 10px; border-radius: 4px; margin-bottom: 20px;">
+                    {message}
+                    <button style="float: right; background: none; border: none; color: white; cursor: pointer;"
+                            on:click=move |_| set_error_message.set(None)>
+                        "×"
+                    </button>
+                </div>
+            })}
+
+            {move || {
+                let warnings = warnings();
+                (!warnings.is_empty()).then(|| view! {
+                    <div style="background-color: #e67e22; color: white; padding: 10px; border-radius: 4px; margin-bottom: 20px;">
+                        <strong>"⚠ GPU warnings"</strong>
+                        <ul style="margin: 5px 0 0 0;">
+                            {warnings.into_iter().map(|warning| view! { <li>{warning}</li> }).collect_view()}
+                        </ul>
+                    </div>
+                })
+            }}
+
+            <div class="container-card" style="margin-bottom: 20px;">
+                <h3>"Gaming Containers"</h3>
+                {move || match games.get() {
+                    None => view! { <p style="color: #bbb;">"Loading gaming containers..."</p> }.into_view(),
+                    Some(Err(e)) => view! { <p style="color: #e74c3c;">{e}</p> }.into_view(),
+                    Some(Ok(list)) if list.is_empty() => view! {
+                        <p style="color: #bbb;">"No containers have a gaming config. Pick a gaming profile when creating one."</p>
+                    }.into_view(),
+                    Some(Ok(list)) => list.into_iter().filter_map(|container| {
+                        let gaming = container.gaming_config.clone()?;
+                        let app_id = gaming.steam_app_id;
+                        let id = container.id.clone();
+                        let running = matches!(container.status, ContainerStatus::Running);
+                        let history_id = id.clone();
+                        let restart_id = id.clone();
+                        let logs_id = id.clone();
+                        let logs_name = container.name.clone();
+                        Some(view! {
+                            <div style="display: flex; align-items: center; flex-wrap: wrap; gap: 16px; padding: 10px 0; border-bottom: 1px solid #444;">
+                                {move || {
+                                    let app = app_id.and_then(|id| steam_apps.with(|apps| apps.get(&id).cloned()));
+                                    app.and_then(|app| app.header_image).map(|src| view! {
+                                        <img src=src style="height: 45px; border-radius: 4px;"/>
+                                    })
+                                }}
+                                <div style="min-width: 200px;">
+                                    <a href=format!("/containers/{}", container.id)><strong>{container.name.clone()}</strong></a>
+                                    <span style=format!(
+                                        "margin-left: 8px; font-size: 0.8em; color: {};",
+                                        if running { "#27ae60" } else { "#95a5a6" },
+                                    )>
+                                        {status_label(&container.status)}
+                                    </span>
+                                    <div style="color: #aaa; font-size: 0.9em;">
+                                        {move || match app_id {
+                                            Some(id) => steam_apps
+                                                .with(|apps| apps.get(&id).map(|app| app.name.clone()))
+                                                .unwrap_or_else(|| format!("Steam app {}", id)),
+                                            None => "No Steam app".to_string(),
+                                        }}
+                                        {format!(
+                                            " · {} · Proton {}",
+                                            gaming.profile_name.clone().unwrap_or_else(|| "no profile".to_string()),
+                                            gaming.proton_version.clone().unwrap_or_else(|| "none".to_string()),
+                                        )}
+                                    </div>
+                                    <div style="color: #aaa; font-size: 0.9em;">
+                                        {match &container.gpu_allocation {
+                                            Some(allocation) => format!("GPU {} ({:?})", allocation.device_id, allocation.isolation_level),
+                                            None => "No GPU allocated".to_string(),
+                                        }}
+                                    </div>
+                                </div>
+                                <div style="display: flex; gap: 16px; flex: 1;">
+                                    {move || histories.with(|histories| {
+                                        let history = histories.get(&history_id);
+                                        if !running {
+                                            return view! { <span style="color: #888;">"Not running"</span> }.into_view();
+                                        }
+                                        let reported = history.is_some_and(|history| {
+                                            history.last_sample().is_some_and(|sample| sample.gaming.is_some())
+                                        });
+                                        if history.is_some() && !reported {
+                                            return view! {
+                                                <span style="color: #888;">"This container does not report gaming metrics"</span>
+                                            }.into_view();
+                                        }
+                                        view! {
+                                            {metric_view("FPS", "", "#27ae60", history, |point| point.fps)}
+                                            {metric_view("Frame time", "ms", "#3498db", history, |point| point.frame_time_ms)}
+                                            {metric_view("Input latency", "ms", "#f39c12", history, |point| point.input_latency_ms)}
+                                        }.into_view()
+                                    })}
+                                </div>
+                                <div style="white-space: nowrap;">
+                                    <Show when=can_operate>
+                                        <button class="btn-primary" style="margin-right: 6px;"
+                                                disabled={
+                                                    let id = id.clone();
+                                                    move || restarting.get().is_some_and(|restarting| restarting == id)
+                                                }
+                                                on:click={
+                                                    let restart_id = restart_id.clone();
+                                                    move |_| restart_game(restart_id.clone())
+                                                }>
+                                            "Restart"
+                                        </button>
+                                    </Show>
+                                    <button class="btn-primary" style="background-color: #555;"
+                                            on:click={
+                                                let logs_id = logs_id.clone();
+                                                let logs_name = logs_name.clone();
+                                                move |_| show_logs(logs_id.clone(), logs_name.clone())
+                                            }>
+                                        "Logs"
+                                    </button>
+                                </div>
+                            </div>
+                        })
+                    }).collect_view(),
+                }}
+            </div>
+
+            <div class="container-card" style="margin-bottom: 20px;">
+                <h3>"GPUs"</h3>
+                {move || match gpus.get() {
+                    None => view! { <p style="color: #bbb;">"Loading GPUs..."</p> }.into_view(),
+                    Some(Err(e)) => view! { <p style="color: #e74c3c;">{e}</p> }.into_view(),
+                    Some(Ok(devices)) => {
+                        let names: HashMap<String, String> = games.with(|games| match games {
+                            Some(Ok(games)) => games.iter().map(|game| (game.id.clone(), game.name.clone())).collect(),
+                            _ => HashMap::new(),
+                        });
+                        // Allocations naming a GPU the host does not have
+                        let missing: Vec<String> = games.with(|games| match games {
+                            Some(Ok(games)) => games
+                                .iter()
+                                .filter_map(|game| {
+                                    let allocation = game.gpu_allocation.as_ref()?;
+                                    (!devices.iter().any(|device| device.id == allocation.device_id))
+                                        .then(|| format!("{} is allocated {}, which this host does not have", game.name, allocation.device_id))
+                                })
+                                .collect(),
+                            _ => Vec::new(),
+                        });
+                        view! {
+                            {devices.is_empty().then(|| view! { <p style="color: #bbb;">"No GPUs were found on this host"</p> })}
+                            {(!devices.is_empty()).then(|| view! {
+                                <table style="width: 100%; border-collapse: collapse;">
+                                    <thead>
+                                        <tr style="text-align: left; border-bottom: 1px solid #555;">
+                                            <th style="padding: 8px;">"GPU"</th>
+                                            <th style="padding: 8px;">"Name"</th>
+                                            <th style="padding: 8px;">"Memory"</th>
+                                            <th style="padding: 8px;">"Allocated To"</th>
+                                        </tr>
+                                    </thead>
+                                    <tbody>
+                                        {devices.iter().map(|device| {
+                                            let users: Vec<String> = device
+                                                .in_use_by
+                                                .iter()
+                                                .map(|id| names.get(id).cloned().unwrap_or_else(|| short_id(id).to_string()))
+                                                .collect();
+                                            view! {
+                                                <tr style="border-bottom: 1px solid #444;">
+                                                    <td style="padding: 8px; font-family: monospace;">{device.id.clone()}</td>
+                                                    <td style="padding: 8px;">{format!("{} ({:?})", device.name, device.vendor)}</td>
+                                                    <td style="padding: 8px;">
+                                                        {if device.memory_total_mb > 0 { format!("{} MiB", device.memory_total_mb) } else { "shared".to_string() }}
+                                                    </td>
+                                                    <td style="padding: 8px;">
+                                                        {if users.is_empty() { "free".to_string() } else { users.join(", ") }}
+                                                    </td>
+                                                </tr>
+                                            }
+                                        }).collect_view()}
+                                    </tbody>
+                                </table>
+                            })}
+                            {missing.into_iter().map(|message| view! {
+                                <p style="color: #f39c12;">{message}</p>
+                            }).collect_view()}
+                        }.into_view()
+                    }
+                }}
+
+                <div style="display: flex; gap: 20px; align-items: flex-end; margin-top: 15px;">
+                    <div>
+                        <label style="display: block; margin-bottom: 5px; font-weight: bold;">"Warn above (°C)"</label>
+                        <input type="number" min="1" style="width: 100px; padding: 6px; border: 1px solid #555; border-radius: 4px; background-color: #2c3e50; color: white;"
+                               prop:value=move || thresholds.get().gpu_temperature_celsius.to_string()
+                               on:change=move |ev| update_thresholds(|limits, value| limits.gpu_temperature_celsius = value, event_target_value(&ev))/>
+                    </div>
+                    <div>
+                        <label style="display: block; margin-bottom: 5px; font-weight: bold;">"Warn above (W)"</label>
+                        <input type="number" min="1" style="width: 100px; padding: 6px; border: 1px solid #555; border-radius: 4px; background-color: #2c3e50; color: white;"
+                               prop:value=move || thresholds.get().gpu_power_watts.to_string()
+                               on:change=move |ev| update_thresholds(|limits, value| limits.gpu_power_watts = value, event_target_value(&ev))/>
+                    </div>
+                    <span style="color: #888; font-size: 0.9em;">"GPU temperature and power limits, saved in this browser"</span>
+                </div>
+            </div>
+
             <div class="container-card">
-                <p>"Gaming features will be implemented here"</p>
+                <table style="width: 100%; border-collapse: collapse;">
+                    <thead>
+                        <tr style="text-align: left; border-bottom: 1px solid #555;">
+                            <th style="padding: 8px;">"Profile"</th>
+                            <th style="padding: 8px;">"CPU Governor"</th>
+                            <th style="padding: 8px;">"Nice"</th>
+                            <th style="padding: 8px;">"GPU Power Limit"</th>
+                            <th style="padding: 8px;">"Gamescope"</th>
+                            <th style="padding: 8px;"></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <For
+                            each=move || profiles.get()
+                            key=|profile| profile.name.clone()
+                            children=move |profile| {
+                                let name = profile.name.clone();
+                                let builtin = profile.builtin;
+                                let for_edit = profile.clone();
+                                view! {
+                                    <tr style="border-bottom: 1px solid #444;">
+                                        <td style="padding: 8px;">
+                                            <strong>{profile.name.clone()}</strong>
+                                            {builtin.then(|| view! {
+                                                <span style="margin-left: 8px; padding: 2px 6px; border-radius: 3px; background-color: #555; font-size: 0.8em;">
+                                                    "Built in"
+                                                </span>
+                                            })}
+                                            <div style="color: #aaa; font-size: 0.9em;">
+                                                {profile.description.clone().unwrap_or_default()}
+                                            </div>
+                                        </td>
+                                        <td style="padding: 8px;">{profile.cpu_governor.clone().unwrap_or_else(|| "host default".to_string())}</td>
+                                        <td style="padding: 8px;">{profile.nice_level.map(|nice| nice.to_string()).unwrap_or_else(|| "-".to_string())}</td>
+                                        <td style="padding: 8px;">
+                                            {profile.gpu_power_limit_watts.map(|watts| format!("{} W", watts)).unwrap_or_else(|| "-".to_string())}
+                                        </td>
+                                        <td style="padding: 8px; font-family: monospace;">{profile.gamescope_args.join(" ")}</td>
+                                        <td style="padding: 8px; text-align: right; white-space: nowrap;">
+                                            <Show when=move || can_operate() && !builtin>
+                                                <button class="btn-primary" style="margin-right: 6px;"
+                                                        on:click={
+                                                            let for_edit = for_edit.clone();
+                                                            move |_| open_editor(Some(for_edit.clone()))
+                                                        }>
+                                                    "Edit"
+                                                </button>
+                                                <button class="btn-primary" style="background-color: #e74c3c;"
+                                                        disabled=move || loading.get()
+                                                        on:click={
+                                                            let name = name.clone();
+                                                            move |_| delete_profile(name.clone())
+                                                        }>
+                                                    "Delete"
+                                                </button>
+                                            </Show>
+                                        </td>
+                                    </tr>
+                                }
+                            }
+                        />
+                    </tbody>
+                </table>
+            </div>
+
+            <div class="container-card" style="margin-top: 20px;">
+                <h3>"Proton"</h3>
+                {move || match proton.get() {
+                    None => view! { <p style="color: #bbb;">"Loading Proton versions..."</p> }.into_view(),
+                    Some(versions) => view! {
+                        <p>
+                            <strong>"Installed: "</strong>
+                            {if versions.installed.is_empty() {
+                                "none".to_string()
+                            } else {
+                                versions.installed.iter().map(|proton| proton.name.clone()).collect::<Vec<_>>().join(", ")
+                            }}
+                        </p>
+                        {versions.available_error.clone().map(|error| view! {
+                            <p style="color: #f39c12;">{format!("GE-Proton releases could not be refreshed: {}", error)}</p>
+                        })}
+                        <table style="width: 100%; border-collapse: collapse;">
+                            <thead>
+                                <tr style="text-align: left; border-bottom: 1px solid #555;">
+                                    <th style="padding: 8px;">"GE-Proton Release"</th>
+                                    <th style="padding: 8px;">"Published"</th>
+                                    <th style="padding: 8px;">"Size"</th>
+                                    <th style="padding: 8px;"></th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {versions.available.into_iter().map(|release| {
+                                    let tag = release.tag.clone();
+                                    let status_tag = tag.clone();
+                                    view! {
+                                        <tr style="border-bottom: 1px solid #444;">
+                                            <td style="padding: 8px;">{release.tag.clone()}</td>
+                                            <td style="padding: 8px;">
+                                                {release.published_at.map(|at| at.format("%Y-%m-%d").to_string()).unwrap_or_default()}
+                                            </td>
+                                            <td style="padding: 8px;">{format!("{} MiB", release.size_bytes / MIB)}</td>
+                                            <td style="padding: 8px; text-align: right;">
+                                                {move || match (release.installed, installing(&status_tag)) {
+                                                    (true, _) => view! { <span style="color: #27ae60;">"Installed"</span> }.into_view(),
+                                                    (false, Some(job)) => view! {
+                                                        <span style="color: #bbb;">{format!("{}% {}", job.percent, job.message)}</span>
+                                                    }.into_view(),
+                                                    (false, None) => {
+                                                        let tag = tag.clone();
+                                                        view! {
+                                                            <button class="btn-primary" disabled=move || !can_operate()
+                                                                    on:click=move |_| install_proton(tag.clone())>
+                                                                "Install"
+                                                            </button>
+                                                        }.into_view()
+                                                    }
+                                                }}
+                                            </td>
+                                        </tr>
+                                    }
+                                }).collect_view()}
+                            </tbody>
+                        </table>
+                    }.into_view(),
+                }}
             </div>
+
+            {move || logs.get().map(|(name, text)| view! {
+                <div style="position: fixed; top: 0; left: 0; width: 100%; height: 100%; background-color: rgba(0,0,0,0.5); z-index: 2000; display: flex; align-items: center; justify-content: center;">
+                    <div class="container-card" style="width: 800px; max-width: 90vw;">
+                        <h3>{format!("Logs of {}", name)}</h3>
+                        <pre style="max-height: 60vh; overflow: auto; background-color: #1a1a1a; padding: 10px; border-radius: 4px; font-size: 12px;">
+                            {text}
+                        </pre>
+                        <div style="display: flex; justify-content: flex-end; margin-top: 10px;">
+                            <button class="btn-primary" on:click=move |_| set_logs.set(None)>"Close"</button>
+                        </div>
+                    </div>
+                </div>
+            })}
+
+            {move || editing.get().map(|original| view! {
+                <div style="position: fixed; top: 0; left: 0; width: 100%; height: 100%; background-color: rgba(0,0,0,0.5); z-index: 2000; display: flex; align-items: center; justify-content: center;">
+                    <div class="container-card" style="width: 500px; max-width: 90vw;">
+                        <h3>{match original {
+                            Some(name) => format!("Edit Profile {}", name),
+                            None => "New Gaming Profile".to_string(),
+                        }}</h3>
+
+                        <div style="margin: 15px 0;">
+                            <label style="display: block; margin-bottom: 5px; font-weight: bold;">"Name"</label>
+                            <input type="text" placeholder="my-profile" style=input_style
+                                   prop:value=move || profile_name.get()
+                                   on:input=move |ev| set_profile_name.set(event_target_value(&ev))/>
+                        </div>
+
+                        <div style="margin: 15px 0;">
+                            <label style="display: block; margin-bottom: 5px; font-weight: bold;">"Description (optional)"</label>
+                            <input type="text" style=input_style
+                                   prop:value=move || description.get()
+                                   on:input=move |ev| set_description.set(event_target_value(&ev))/>
+                        </div>
+
+                        <div style="margin: 15px 0;">
+                            <label style="display: block; margin-bottom: 5px; font-weight: bold;">"CPU Governor"</label>
+                            <select style=input_style
+                                    prop:value=move || cpu_governor.get()
+                                    on:change=move |ev| set_cpu_governor.set(event_target_value(&ev))>
+                                <option value="">"Host default"</option>
+                                {CPU_GOVERNORS.iter().map(|governor| view! {
+                                    <option value=*governor>{*governor}</option>
+                                }).collect_view()}
+                            </select>
+                        </div>
+
+                        <div style="display: grid; grid-template-columns: 1fr 1fr; gap: 10px; margin: 15px 0;">
+                            <div>
+                                <label style="display: block; margin-bottom: 5px; font-weight: bold;">"Nice Level (-20 to 19)"</label>
+                                <input type="number" min="-20" max="19" style=input_style
+                                       prop:value=move || nice_level.get()
+                                       on:input=move |ev| set_nice_level.set(event_target_value(&ev))/>
+                            </div>
+                            <div>
+                                <label style="display: block; margin-bottom: 5px; font-weight: bold;">"GPU Power Limit (W)"</label>
+                                <input type="number" min="1" style=input_style
+                                       prop:value=move || gpu_power_limit.get()
+                                       on:input=move |ev| set_gpu_power_limit.set(event_target_value(&ev))/>
+                            </div>
+                        </div>
+
+                        <div style="margin: 15px 0;">
+                            <label style="display: block; margin-bottom: 5px; font-weight: bold;">"Gamescope Arguments"</label>
+                            <input type="text" placeholder="-W 2560 -H 1440 -r 144" style=input_style
+                                   prop:value=move || gamescope_args.get()
+                                   on:input=move |ev| set_gamescope_args.set(event_target_value(&ev))/>
+                        </div>
+
+                        <div style="display: flex; justify-content: flex-end; gap: 10px; margin-top: 20px;">
+                            <button class="btn-primary" style="background-color: #555;"
+                                    on:click=move |_| set_editing.set(None)>
+                                "Cancel"
+                            </button>
+                            <button class="btn-primary" on:click=save_profile disabled=move || loading.get()>
+                                {move || if loading.get() { "Saving..." } else { "Save Profile" }}
+                            </button>
+                        </div>
+                    </div>
+                </div>
+            })}
         </div>
     }
-}
\ No newline at end of file
+}
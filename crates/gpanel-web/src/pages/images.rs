@@ -1,58 +1,24 @@
 use leptos::*;
-use serde::{Deserialize, Serialize};
-use gloo_net::http::Request;
-
-/// Image search request
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ImageSearchRequest {
-    pub query: String,
-    pub registry: Option<String>,
+use leptos_router::*;
+use crate::auth::AuthContext;
+use crate::services::{api_client, error_message};
+use gpanel_core::{
+    short_id, ImageInfo, ImagePruneReport, ImagePruneRequest, ImagePullRequest, ImageReference, ImageSearchGroup,
+    ImageSearchRequest, ImageSearchResponse, ImageTagRequest, Job, JobAccepted, JobStatus, LocalImage,
+    LocalImageListResponse, OperationResult, RegistryConfigResponse, RegistryListResponse, Role,
+};
+
+/// Bytes a pull had downloaded when last polled, to work out its download rate
+#[derive(Debug, Clone, Copy)]
+struct PullSample {
+    bytes: u64,
+    at_ms: f64,
+    bytes_per_sec: f64,
 }
 
-/// Image search response
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ImageSearchResponse {
-    pub images: Vec<ImageSearchResult>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ImageSearchResult {
-    pub registry: String,
-    pub repository: String,
-    pub tag: String,
-    pub digest: String,
-    pub size: u64,
-    pub created: chrono::DateTime<chrono::Utc>,
-}
-
-/// Registry configuration response
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RegistryConfigResponse {
-    pub name: String,
-    pub url: String,
-    pub has_auth: bool,
-    pub insecure: bool,
-}
-
-/// Registry list response
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RegistryListResponse {
-    pub registries: Vec<RegistryConfigResponse>,
-}
-
-/// Image pull request
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ImagePullRequest {
-    pub registry: String,
-    pub repository: String,
-    pub tag: String,
-}
-
-/// Operation result response
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OperationResult {
-    pub success: bool,
-    pub message: String,
+/// Key of a search result in the fetched image details
+fn details_key(reference: &ImageReference) -> String {
+    format!("{}/{}:{}", reference.registry, reference.repository, reference.tag)
 }
 
 /// Format file size in human readable format
@@ -71,17 +37,38 @@ fn format_size(size: u64) -> String {
 
 #[component]
 pub fn ImageList() -> impl IntoView {
+    let auth_context = use_context::<AuthContext>().expect("AuthContext must be provided");
     let (search_query, set_search_query) = create_signal(String::new());
     let (selected_registry, set_selected_registry) = create_signal(None::<String>);
-    let (search_results, set_search_results) = create_signal(Vec::<ImageSearchResult>::new());
+    let (search_results, set_search_results) = create_signal(Vec::<ImageSearchGroup>::new());
+    let (search_warnings, set_search_warnings) = create_signal(Vec::<String>::new());
+    // Details of search results, fetched when asked for; keyed by `details_key`
+    let (image_details, set_image_details) = create_signal(std::collections::HashMap::<String, ImageInfo>::new());
     let (registries, set_registries) = create_signal(Vec::<RegistryConfigResponse>::new());
     let (loading, set_loading) = create_signal(false);
-    let (error_message, set_error_message) = create_signal(None::<String>);
+    let (error_message_signal, set_error_message) = create_signal(None::<String>);
+    let initial_tab = if use_query_map().get_untracked().get("tab").map(String::as_str) == Some("local") {
+        "local"
+    } else {
+        "search"
+    };
+    let (active_tab, set_active_tab) = create_signal(initial_tab);
+    let (local_images, set_local_images) = create_signal(Vec::<LocalImage>::new());
+    let (local_total_size, set_local_total_size) = create_signal(0u64);
+    let (show_prune_confirm, set_show_prune_confirm) = create_signal(false);
+    let (prune_dangling_only, set_prune_dangling_only) = create_signal(true);
+    let (tag_image_id, set_tag_image_id) = create_signal(None::<String>);
+    let (tag_repository, set_tag_repository) = create_signal(String::new());
+    let (tag_name, set_tag_name) = create_signal(String::new());
+    let (tag_force, set_tag_force) = create_signal(false);
+    let (tag_push_registry, set_tag_push_registry) = create_signal(None::<String>);
+    let (pull_jobs, set_pull_jobs) = create_signal(Vec::<Job>::new());
+    let (pull_samples, set_pull_samples) = create_signal(std::collections::HashMap::<String, PullSample>::new());
 
     // Load registries on mount
     create_effect(move |_| {
         spawn_local(async move {
-            if let Ok(response) = Request::get("http://localhost:8000/api/v1/registries")
+            if let Ok(response) = api_client::get("/api/v1/registries")
                 .send()
                 .await
             {
@@ -105,9 +92,11 @@ pub fn ImageList() -> impl IntoView {
             let request = ImageSearchRequest {
                 query: query.clone(),
                 registry: selected_registry.get(),
+                max_tags: None,
+                inspect: false,
             };
 
-            match Request::post("http://localhost:8000/api/v1/images/search")
+            match api_client::post("/api/v1/images/search")
                 .json(&request)
                 .unwrap()
                 .send()
@@ -115,7 +104,8 @@ pub fn ImageList() -> impl IntoView {
             {
                 Ok(response) => {
                     if let Ok(search_response) = response.json::<ImageSearchResponse>().await {
-                        set_search_results.set(search_response.images);
+                        set_search_results.set(search_response.groups);
+                        set_search_warnings.set(search_response.warnings);
                     } else {
                         set_error_message.set(Some("Failed to parse search results".to_string()));
                     }
@@ -128,30 +118,213 @@ pub fn ImageList() -> impl IntoView {
         });
     };
 
-    let pull_image = move |registry: String, repository: String, tag: String| {
+    // Fetch every tag of one repository and swap its group in place
+    let expand_group = move |repository: String, total_tags: usize| {
+        spawn_local(async move {
+            let request = ImageSearchRequest {
+                query: repository.clone(),
+                registry: selected_registry.get_untracked(),
+                max_tags: Some(total_tags),
+                inspect: false,
+            };
+
+            match api_client::post("/api/v1/images/search")
+                .json(&request)
+                .unwrap()
+                .send()
+                .await
+            {
+                Ok(response) => match response.json::<ImageSearchResponse>().await {
+                    Ok(search_response) => {
+                        if let Some(expanded) = search_response.groups.into_iter().find(|g| g.repository == repository) {
+                            set_search_results.update(|groups| {
+                                if let Some(group) = groups.iter_mut().find(|g| g.repository == repository) {
+                                    *group = expanded;
+                                }
+                            });
+                        }
+                    }
+                    Err(_) => set_error_message.set(Some("❌ Failed to parse search results".to_string())),
+                },
+                Err(e) => set_error_message.set(Some(format!("❌ Failed to load more tags: {}", e))),
+            }
+        });
+    };
+
+    let load_details = move |reference: ImageReference| {
+        spawn_local(async move {
+            let url = format!(
+                "/api/v1/registries/{}/repositories/{}/tags/{}",
+                urlencoding::encode(&reference.registry),
+                urlencoding::encode(&reference.repository),
+                urlencoding::encode(&reference.tag)
+            );
+            match api_client::get(&url).send().await {
+                Ok(response) if response.ok() => match response.json::<ImageInfo>().await {
+                    Ok(info) => set_image_details.update(|details| {
+                        details.insert(details_key(&reference), info);
+                    }),
+                    Err(e) => set_error_message.set(Some(format!("❌ Unexpected response: {}", e))),
+                },
+                Ok(response) => set_error_message.set(Some(format!("❌ {}", error_message(&response).await))),
+                Err(e) => set_error_message.set(Some(format!("❌ Failed to load image details: {}", e))),
+            }
+        });
+    };
+
+    let refresh_local_images = move || {
+        spawn_local(async move {
+            load_local_images(set_local_images, set_local_total_size, set_error_message).await;
+        });
+    };
+
+    // Load local images whenever the Local tab is opened
+    create_effect(move |_| {
+        if active_tab.get() == "local" {
+            refresh_local_images();
+        }
+    });
+
+    let remove_local_image = move |id: String| {
+        spawn_local(async move {
+            set_loading.set(true);
+
+            let url = format!("/api/v1/images/{}", urlencoding::encode(&id));
+
+            match api_client::delete(&url).send().await {
+                Ok(response) if !response.ok() => {
+                    set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
+                }
+                Ok(response) => match response.json::<OperationResult>().await {
+                    Ok(result) => {
+                        set_error_message.set(Some(format!("✅ {}", result.message)));
+                        load_local_images(set_local_images, set_local_total_size, set_error_message).await;
+                    }
+                    Err(_) => {
+                        set_error_message.set(Some(format!("❌ Request failed with status {}", response.status())));
+                    }
+                },
+                Err(e) => {
+                    set_error_message.set(Some(format!("❌ Delete failed: {}", e)));
+                }
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let open_tag_dialog = move |image: LocalImage| {
+        let repository = image
+            .repo_tags
+            .first()
+            .and_then(|reference| reference.rsplit_once(':'))
+            .map(|(repository, _)| repository.to_string())
+            .unwrap_or_default();
+        set_tag_repository.set(repository);
+        set_tag_name.set("stable".to_string());
+        set_tag_force.set(false);
+        set_tag_push_registry.set(None);
+        set_tag_image_id.set(Some(image.id));
+    };
+
+    let tag_image = move || {
+        let Some(id) = tag_image_id.get_untracked() else {
+            return;
+        };
+
         spawn_local(async move {
             set_loading.set(true);
 
-            let request = ImagePullRequest {
-                registry,
-                repository: repository.clone(),
-                tag: tag.clone(),
+            let request = ImageTagRequest {
+                repository: tag_repository.get_untracked().trim().to_string(),
+                tag: tag_name.get_untracked().trim().to_string(),
+                force: tag_force.get_untracked(),
+                push_registry: tag_push_registry.get_untracked(),
             };
 
-            match Request::post("http://localhost:8000/api/v1/images/pull")
+            let url = format!("/api/v1/images/{}/tag", urlencoding::encode(&id));
+
+            match api_client::post(&url).json(&request).unwrap().send().await {
+                Ok(response) if !response.ok() => {
+                    set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
+                }
+                Ok(response) => match response.json::<OperationResult>().await {
+                    Ok(result) => {
+                        set_tag_image_id.set(None);
+                        set_error_message.set(Some(format!("✅ {}", result.message)));
+                        load_local_images(set_local_images, set_local_total_size, set_error_message).await;
+                    }
+                    Err(_) => {
+                        set_error_message.set(Some(format!("❌ Request failed with status {}", response.status())));
+                    }
+                },
+                Err(e) => {
+                    set_error_message.set(Some(format!("❌ Tag failed: {}", e)));
+                }
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let prune_images = move || {
+        set_show_prune_confirm.set(false);
+        spawn_local(async move {
+            set_loading.set(true);
+
+            let request = ImagePruneRequest {
+                dangling_only: prune_dangling_only.get_untracked(),
+                until: None,
+                label_filter: None,
+            };
+
+            match api_client::post("/api/v1/images/prune")
                 .json(&request)
                 .unwrap()
                 .send()
                 .await
             {
+                Ok(response) if response.ok() => {
+                    if let Ok(report) = response.json::<ImagePruneReport>().await {
+                        set_error_message.set(Some(format!(
+                            "✅ Pruned {} images, reclaimed {}",
+                            report.deleted.len(),
+                            format_size(report.reclaimed_bytes)
+                        )));
+                        load_local_images(set_local_images, set_local_total_size, set_error_message).await;
+                    }
+                }
                 Ok(response) => {
-                    if let Ok(result) = response.json::<OperationResult>().await {
-                        if result.success {
-                            set_error_message.set(Some(format!("✅ Successfully pulled {}:{}", repository, tag)));
-                        } else {
-                            set_error_message.set(Some(format!("❌ {}", result.message)));
+                    set_error_message.set(Some(format!("❌ Prune failed: HTTP {}", response.status())));
+                }
+                Err(e) => {
+                    set_error_message.set(Some(format!("❌ Prune failed: {}", e)));
+                }
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let pull_image = move |registry: String, repository: String, tag: String| {
+        spawn_local(async move {
+            set_loading.set(true);
+
+            let request = ImagePullRequest { registry, repository, tag };
+
+            match api_client::post("/api/v1/images/pull")
+                .json(&request)
+                .unwrap()
+                .send()
+                .await
+            {
+                Ok(response) if response.ok() => match response.json::<JobAccepted>().await {
+                    Ok(accepted) => {
+                        if let Some(job) = load_job(&accepted.job_id).await {
+                            set_pull_jobs.update(|jobs| jobs.insert(0, job));
                         }
                     }
+                    Err(e) => set_error_message.set(Some(format!("❌ Unexpected response: {}", e))),
+                },
+                Ok(response) => {
+                    set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
                 }
                 Err(e) => {
                     set_error_message.set(Some(format!("❌ Pull failed: {}", e)));
@@ -161,6 +334,74 @@ pub fn ImageList() -> impl IntoView {
         });
     };
 
+    // Follow running pulls every second; report each one as it finishes
+    let refresh_pull_jobs = move || {
+        spawn_local(async move {
+            let running: Vec<String> = pull_jobs
+                .get_untracked()
+                .into_iter()
+                .filter(|job| job.status == JobStatus::Running)
+                .map(|job| job.id)
+                .collect();
+            for id in running {
+                let Some(job) = load_job(&id).await else {
+                    continue;
+                };
+                if let Some(pulled) = job.progress {
+                    let now = js_sys::Date::now();
+                    set_pull_samples.update(|samples| {
+                        let bytes_per_sec = samples
+                            .get(&job.id)
+                            .filter(|sample| now > sample.at_ms)
+                            .map(|sample| pulled.current_bytes.saturating_sub(sample.bytes) as f64 * 1000.0 / (now - sample.at_ms))
+                            .unwrap_or(0.0);
+                        samples.insert(job.id.clone(), PullSample { bytes: pulled.current_bytes, at_ms: now, bytes_per_sec });
+                    });
+                }
+                match job.status {
+                    JobStatus::Succeeded => {
+                        set_error_message.set(Some(format!("✅ {}", job.message)));
+                        load_local_images(set_local_images, set_local_total_size, set_error_message).await;
+                    }
+                    JobStatus::Failed => {
+                        set_error_message.set(Some(format!("❌ Pull of {} failed: {}", job.target, job.message)));
+                    }
+                    JobStatus::Running | JobStatus::Cancelled => {}
+                }
+                set_pull_jobs.update(|jobs| {
+                    if let Some(existing) = jobs.iter_mut().find(|existing| existing.id == job.id) {
+                        *existing = job;
+                    }
+                });
+            }
+        });
+    };
+    if let Ok(handle) = set_interval_with_handle(refresh_pull_jobs, std::time::Duration::from_secs(1)) {
+        on_cleanup(move || handle.clear());
+    }
+
+    let cancel_pull = move |id: String| {
+        spawn_local(async move {
+            match api_client::delete(&format!("/api/v1/jobs/{}", id)).send().await {
+                Ok(response) if response.ok() => {
+                    if let Ok(job) = response.json::<Job>().await {
+                        set_pull_jobs.update(|jobs| {
+                            if let Some(existing) = jobs.iter_mut().find(|existing| existing.id == job.id) {
+                                *existing = job;
+                            }
+                        });
+                    }
+                }
+                Ok(response) => {
+                    set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
+                }
+                Err(e) => {
+                    set_error_message.set(Some(format!("❌ Cancel failed: {}", e)));
+                }
+            }
+        });
+    };
+
     view! {
         <div class="image-list">
             <div class="header-section">
@@ -170,7 +411,7 @@ pub fn ImageList() -> impl IntoView {
 
             // Error/Success message display
             {move || {
-                if let Some(message) = error_message.get() {
+                if let Some(message) = error_message_signal.get() {
                     let is_success = message.starts_with("✅");
                     view! {
                         <div
@@ -192,8 +433,186 @@ pub fn ImageList() -> impl IntoView {
                 }
             }}
 
+            // Pulls started from this page
+            {move || {
+                let jobs = pull_jobs.get();
+                (!jobs.is_empty()).then(|| view! {
+                    <div class="container-card" style="margin-bottom: 20px;">
+                        <h3>"Pulls"</h3>
+                        {jobs.into_iter().map(|job| {
+                            let id = job.id.clone();
+                            let running = job.status == JobStatus::Running;
+                            let percent = match job.progress {
+                                Some(pulled) if pulled.total_bytes > 0 && job.percent < 100 => {
+                                    pulled.current_bytes * 100 / pulled.total_bytes
+                                }
+                                _ => job.percent as u64,
+                            };
+                            let detail = job.progress.filter(|_| running).map(|pulled| {
+                                let rate = pull_samples
+                                    .with_untracked(|samples| samples.get(&job.id).map(|sample| sample.bytes_per_sec))
+                                    .unwrap_or(0.0);
+                                format!(
+                                    "{}% · {} of {} · {}/s · layer {} of {}",
+                                    percent,
+                                    format_size(pulled.current_bytes),
+                                    format_size(pulled.total_bytes),
+                                    format_size(rate as u64),
+                                    pulled.current_layer,
+                                    pulled.total_layers
+                                )
+                            });
+                            let color = match job.status {
+                                JobStatus::Running => "#3498db",
+                                JobStatus::Succeeded => "#27ae60",
+                                JobStatus::Failed => "#e74c3c",
+                                JobStatus::Cancelled => "#7f8c8d",
+                            };
+                            view! {
+                                <div style="margin-top: 12px;">
+                                    <div style="display: flex; justify-content: space-between; align-items: center;">
+                                        <span><code>{job.target.clone()}</code>" "{job.message.clone()}</span>
+                                        {if running {
+                                            view! {
+                                                <button class="btn-danger" style="padding: 2px 10px;"
+                                                        on:click=move |_| cancel_pull(id.clone())
+                                                        disabled=move || !auth_context.has_role(Role::Operator)>
+                                                    "Cancel"
+                                                </button>
+                                            }.into_view()
+                                        } else {
+                                            view! {
+                                                <button style="background: none; border: none; color: #bbb; cursor: pointer;"
+                                                        on:click=move |_| {
+                                                            set_pull_jobs.update(|jobs| jobs.retain(|job| job.id != id));
+                                                            set_pull_samples.update(|samples| {
+                                                                samples.remove(&id);
+                                                            });
+                                                        }>
+                                                    "×"
+                                                </button>
+                                            }.into_view()
+                                        }}
+                                    </div>
+                                    <div style="background-color: #2c3e50; border-radius: 4px; height: 8px; margin-top: 6px; overflow: hidden;">
+                                        <div style=format!(
+                                            "background-color: {}; width: {}%; height: 100%; transition: width 0.5s;",
+                                            color, percent
+                                        )></div>
+                                    </div>
+                                    {detail.map(|detail| view! {
+                                        <div style="color: #95a5a6; font-size: 0.85em; margin-top: 4px;">{detail}</div>
+                                    })}
+                                </div>
+                            }
+                        }).collect_view()}
+                    </div>
+                })
+            }}
+
+            // Tabs
+            <div style="display: flex; gap: 10px; margin-bottom: 20px;">
+                <button
+                    class="btn-primary"
+                    style=move || if active_tab.get() == "search" { "" } else { "background: #4a5568;" }
+                    on:click=move |_| set_active_tab.set("search")
+                >
+                    "Registry Search"
+                </button>
+                <button
+                    class="btn-primary"
+                    style=move || if active_tab.get() == "local" { "" } else { "background: #4a5568;" }
+                    on:click=move |_| set_active_tab.set("local")
+                >
+                    "Local"
+                </button>
+            </div>
+
+            // Local images
+            <div class="container-card" style=move || if active_tab.get() == "local" { "" } else { "display: none;" }>
+                <div style="display: flex; justify-content: space-between; align-items: center;">
+                    <h3>"Local Images"</h3>
+                    <div style="display: flex; gap: 15px; align-items: center;">
+                        <span style="color: #bbb;">
+                            {move || format!("{} images, {}", local_images.get().len(), format_size(local_total_size.get()))}
+                        </span>
+                        <button class="btn-primary" on:click=move |_| refresh_local_images()>
+                            "Refresh"
+                        </button>
+                        <button
+                            class="btn-danger"
+                            on:click=move |_| set_show_prune_confirm.set(true)
+                            disabled=move || loading.get() || !auth_context.has_role(Role::Operator)
+                        >
+                            "Prune images"
+                        </button>
+                    </div>
+                </div>
+
+                <div style="display: grid; gap: 10px; margin-top: 15px;">
+                    <For
+                        each=move || local_images.get()
+                        key=|image| image.id.clone()
+                        children=move |image| {
+                            let id_for_remove = image.id.clone();
+                            let image_for_tag = image.clone();
+                            let in_use = !image.containers.is_empty();
+                            let title = if image.dangling {
+                                "<none>:<none>".to_string()
+                            } else {
+                                image.repo_tags.join(", ")
+                            };
+
+                            view! {
+                                <div style="background-color: #34495e; border-radius: 8px; padding: 15px; border: 1px solid #4a5568; display: grid; grid-template-columns: 1fr auto; gap: 20px; align-items: center;">
+                                    <div>
+                                        <div style="display: flex; align-items: center; gap: 10px;">
+                                            <h4 style="margin: 0; color: #3498db;">{title}</h4>
+                                            {image.dangling.then(|| view! {
+                                                <span style="background-color: #f39c12; padding: 2px 6px; border-radius: 3px; font-size: 10px;">"DANGLING"</span>
+                                            })}
+                                            {in_use.then(|| view! {
+                                                <span style="background-color: #27ae60; padding: 2px 6px; border-radius: 3px; font-size: 10px;">"IN USE"</span>
+                                            })}
+                                        </div>
+                                        <div style="display: grid; grid-template-columns: repeat(auto-fit, minmax(150px, 1fr)); gap: 10px; font-size: 14px; color: #bbb; margin-top: 8px;">
+                                            <div><strong>"Size: "</strong> {format_size(image.size)}</div>
+                                            <div><strong>"Shared: "</strong> {format_size(image.shared_size)}</div>
+                                            <div><strong>"Created: "</strong> {image.created.format("%Y-%m-%d").to_string()}</div>
+                                            <div><strong>"Containers: "</strong> {image.containers.len()}</div>
+                                        </div>
+                                        <div style="margin-top: 8px; font-size: 12px; color: #888;">
+                                            <code style="background-color: #1a1a1a; padding: 2px 4px; border-radius: 2px;">{&image.id}</code>
+                                        </div>
+                                    </div>
+                                    <div style="display: flex; gap: 10px;">
+                                        <button
+                                            class="btn-primary"
+                                            style="padding: 8px 16px;"
+                                            on:click=move |_| open_tag_dialog(image_for_tag.clone())
+                                            disabled=move || loading.get()
+                                        >
+                                            "Tag"
+                                        </button>
+                                        <button
+                                            class="btn-danger"
+                                            style="padding: 8px 16px;"
+                                            title=if in_use { "Image is used by a container" } else { "" }
+                                            on:click=move |_| remove_local_image(id_for_remove.clone())
+                                            disabled=move || in_use || loading.get()
+                                        >
+                                            "Delete"
+                                        </button>
+                                    </div>
+                                </div>
+                            }
+                        }
+                    />
+                </div>
+            </div>
+
             // Search Section
-            <div class="container-card" style="margin-bottom: 20px;">
+            <div class="container-card" style=move || if active_tab.get() == "search" { "margin-bottom: 20px;" } else { "display: none;" }>
                 <h3>"Search Images"</h3>
 
                 <div style="display: grid; grid-template-columns: 1fr 200px auto; gap: 15px; align-items: end; margin-top: 15px;">
@@ -247,8 +666,13 @@ pub fn ImageList() -> impl IntoView {
             </div>
 
             // Search Results
-            <div class="container-card">
+            <div class="container-card" style=move || if active_tab.get() == "search" { "" } else { "display: none;" }>
                 <h3>"Search Results"</h3>
+                {move || search_warnings.get().into_iter().map(|warning| view! {
+                    <div style="background-color: #f39c12; color: white; padding: 8px 12px; border-radius: 4px; margin-top: 10px;">
+                        {format!("⚠ {}", warning)}
+                    </div>
+                }).collect_view()}
 
                 {move || {
                     let results = search_results.get();
@@ -268,67 +692,114 @@ pub fn ImageList() -> impl IntoView {
                                 <div style="display: grid; gap: 15px;">
                                     <For
                                         each=move || search_results.get()
-                                        key=|image| format!("{}:{}:{}", image.registry, image.repository, image.tag)
-                                        children=move |image| {
-                                            let registry = image.registry.clone();
-                                            let repository = image.repository.clone();
-                                            let tag = image.tag.clone();
-                                            let registry_for_pull = registry.clone();
-                                            let repository_for_pull = repository.clone();
-                                            let tag_for_pull = tag.clone();
-                                            let repository_for_create = repository.clone();
-                                            let tag_for_create = tag.clone();
+                                        key=|group| format!("{}:{}", group.repository, group.tags.len())
+                                        children=move |group| {
+                                            let repository_for_expand = group.repository.clone();
+                                            let total_tags = group.total_tags;
 
                                             view! {
                                                 <div class="image-item" style="background-color: #34495e; border-radius: 8px; padding: 20px; border: 1px solid #4a5568;">
-                                                    <div style="display: grid; grid-template-columns: 1fr auto; gap: 20px; align-items: center;">
-                                                        <div>
-                                                            <div style="display: flex; align-items: center; gap: 10px; margin-bottom: 10px;">
-                                                                <h4 style="margin: 0; color: #3498db;">
-                                                                    {&image.repository} ":" {&image.tag}
-                                                                </h4>
-                                                                <span style="background-color: #2c3e50; padding: 4px 8px; border-radius: 4px; font-size: 12px; color: #bbb;">
-                                                                    {&image.registry}
-                                                                </span>
+                                                    <div style="display: flex; align-items: center; gap: 10px; margin-bottom: 15px;">
+                                                        <h4 style="margin: 0; color: #3498db;">{&group.repository}</h4>
+                                                        {group.official.then(|| view! {
+                                                            <span style="background-color: #27ae60; padding: 4px 8px; border-radius: 4px; font-size: 12px; color: white;">
+                                                                "Official"
+                                                            </span>
+                                                        })}
+                                                        {group.stars.map(|stars| view! {
+                                                            <span style="font-size: 12px; color: #f1c40f;">{format!("★ {}", stars)}</span>
+                                                        })}
+                                                        {(group.latest.is_none() || group.total_tags > 0).then(|| view! {
+                                                            <span style="font-size: 12px; color: #888;">
+                                                                {format!("{} tags", group.total_tags)}
+                                                            </span>
+                                                        })}
+                                                    </div>
+                                                    {group.description.clone().map(|description| view! {
+                                                        <p style="margin: -8px 0 15px; color: #bbb; font-size: 14px;">{description}</p>
+                                                    })}
+                                                    {group.latest.clone().filter(|_| group.tags.is_empty()).map(|latest| {
+                                                        let label = format!("{}:{} @ {}", latest.repository, latest.tag, latest.registry);
+                                                        view! {
+                                                            <div style="display: flex; justify-content: space-between; align-items: center; background-color: #2c3e50; border-radius: 6px; padding: 12px;">
+                                                                <span style="font-size: 12px; color: #bbb;">{label}</span>
+                                                                <button
+                                                                    class="btn-success"
+                                                                    style="padding: 8px 16px; white-space: nowrap;"
+                                                                    on:click=move |_| pull_image(latest.registry.clone(), latest.repository.clone(), latest.tag.clone())
+                                                                    disabled=move || loading.get()
+                                                                >
+                                                                    "Pull"
+                                                                </button>
                                                             </div>
-
-                                                            <div style="display: grid; grid-template-columns: repeat(auto-fit, minmax(150px, 1fr)); gap: 10px; font-size: 14px; color: #bbb;">
-                                                                <div>
-                                                                    <strong>"Size: "</strong> {format_size(image.size)}
-                                                                </div>
-                                                                <div>
-                                                                    <strong>"Created: "</strong> {image.created.format("%Y-%m-%d").to_string()}
+                                                        }
+                                                    })}
+
+                                                    <div style="display: grid; gap: 10px;">
+                                                        {group.tags.into_iter().map(|reference| {
+                                                            let key = details_key(&reference);
+                                                            let for_details = reference.clone();
+                                                            let for_pull = reference.clone();
+                                                            let repository_for_create = reference.repository.clone();
+                                                            let tag_for_create = reference.tag.clone();
+
+                                                            view! {
+                                                                <div style="display: grid; grid-template-columns: 1fr auto; gap: 20px; align-items: center; background-color: #2c3e50; border-radius: 6px; padding: 12px;">
+                                                                    <div>
+                                                                        <span style="background-color: #1a1a1a; padding: 4px 8px; border-radius: 4px; font-size: 12px; color: #bbb;">
+                                                                            {format!("{}:{}", reference.repository, reference.tag)}
+                                                                            <span style="color: #888;">{format!(" @ {}", reference.registry)}</span>
+                                                                        </span>
+                                                                        {move || image_details.with(|details| details.get(&key).cloned()).map(|image| view! {
+                                                                            <div style="display: flex; gap: 20px; font-size: 14px; color: #bbb; margin-top: 8px;">
+                                                                                <div><strong>"Size: "</strong> {format_size(image.size)}</div>
+                                                                                <div><strong>"Created: "</strong> {image.created.format("%Y-%m-%d").to_string()}</div>
+                                                                                <code style="background-color: #1a1a1a; padding: 2px 4px; border-radius: 2px; font-size: 12px; color: #888;">
+                                                                                    {short_id(image.digest.split(':').last().unwrap_or(&image.digest)).to_string()}
+                                                                                </code>
+                                                                            </div>
+                                                                        })}
+                                                                    </div>
+
+                                                                    <div style="display: flex; gap: 8px;">
+                                                                        <button
+                                                                            style="padding: 8px 16px; white-space: nowrap; background: #4a5568;"
+                                                                            on:click=move |_| load_details(for_details.clone())
+                                                                        >
+                                                                            "Details"
+                                                                        </button>
+                                                                        <button
+                                                                            class="btn-success"
+                                                                            style="padding: 8px 16px; white-space: nowrap;"
+                                                                            on:click=move |_| pull_image(for_pull.registry.clone(), for_pull.repository.clone(), for_pull.tag.clone())
+                                                                            disabled=move || loading.get()
+                                                                        >
+                                                                            "Pull"
+                                                                        </button>
+                                                                        <button
+                                                                            class="btn-primary"
+                                                                            style="padding: 8px 16px; white-space: nowrap;"
+                                                                            on:click=move |_| {
+                                                                                // TODO: Navigate to create container with this image pre-selected
+                                                                                web_sys::console::log_1(&format!("Create container from {}:{}", repository_for_create, tag_for_create).into());
+                                                                            }
+                                                                        >
+                                                                            "Create Container"
+                                                                        </button>
+                                                                    </div>
                                                                 </div>
-                                                            </div>
-
-                                                            <div style="margin-top: 8px; font-size: 12px; color: #888;">
-                                                                <code style="background-color: #1a1a1a; padding: 2px 4px; border-radius: 2px;">
-                                                                    {image.digest.split(':').last().unwrap_or(&image.digest)[..12].to_string()}
-                                                                </code>
-                                                            </div>
-                                                        </div>
-
-                                                        <div style="display: flex; flex-direction: column; gap: 8px;">
-                                                            <button
-                                                                class="btn-success"
-                                                                style="padding: 8px 16px; white-space: nowrap;"
-                                                                on:click=move |_| pull_image(registry_for_pull.clone(), repository_for_pull.clone(), tag_for_pull.clone())
-                                                                disabled=move || loading.get()
-                                                            >
-                                                                "Pull"
-                                                            </button>
-                                                            <button
-                                                                class="btn-primary"
-                                                                style="padding: 8px 16px; white-space: nowrap;"
-                                                                on:click=move |_| {
-                                                                    // TODO: Navigate to create container with this image pre-selected
-                                                                    web_sys::console::log_1(&format!("Create container from {}:{}", repository_for_create, tag_for_create).into());
-                                                                }
-                                                            >
-                                                                "Create Container"
-                                                            </button>
-                                                        </div>
+                                                            }
+                                                        }).collect_view()}
                                                     </div>
+
+                                                    {(group.more_tags > 0).then(|| view! {
+                                                        <button
+                                                            style="margin-top: 10px; padding: 6px 12px; background: #4a5568;"
+                                                            on:click=move |_| expand_group(repository_for_expand.clone(), total_tags)
+                                                        >
+                                                            {format!("Show {} more tags", group.more_tags)}
+                                                        </button>
+                                                    })}
                                                 </div>
                                             }
                                         }
@@ -375,6 +846,133 @@ pub fn ImageList() -> impl IntoView {
                 </div>
             </div>
 
+            // Tag dialog
+            {move || {
+                if let Some(id) = tag_image_id.get() {
+                    view! {
+                        <div style="position: fixed; top: 0; left: 0; width: 100%; height: 100%; background-color: rgba(0,0,0,0.5); z-index: 2000; display: flex; align-items: center; justify-content: center;">
+                            <div class="container-card" style="width: 90%; max-width: 480px;">
+                                <h3 style="margin-top: 0;">"Tag image"</h3>
+                                <div style="margin-bottom: 15px; font-size: 12px; color: #888;">
+                                    <code style="background-color: #1a1a1a; padding: 2px 4px; border-radius: 2px;">{id}</code>
+                                </div>
+                                <div style="display: grid; grid-template-columns: 2fr 1fr; gap: 10px; margin-bottom: 15px;">
+                                    <div>
+                                        <label style="display: block; margin-bottom: 5px; font-weight: bold;">"Repository"</label>
+                                        <input
+                                            type="text"
+                                            placeholder="gaming/steam-proton"
+                                            style="width: 100%; padding: 10px; border: 1px solid #555; border-radius: 4px; background-color: #2c3e50; color: white;"
+                                            prop:value=move || tag_repository.get()
+                                            on:input=move |ev| set_tag_repository.set(event_target_value(&ev))
+                                        />
+                                    </div>
+                                    <div>
+                                        <label style="display: block; margin-bottom: 5px; font-weight: bold;">"Tag"</label>
+                                        <input
+                                            type="text"
+                                            placeholder="stable"
+                                            style="width: 100%; padding: 10px; border: 1px solid #555; border-radius: 4px; background-color: #2c3e50; color: white;"
+                                            prop:value=move || tag_name.get()
+                                            on:input=move |ev| set_tag_name.set(event_target_value(&ev))
+                                        />
+                                    </div>
+                                </div>
+                                <div style="margin-bottom: 15px;">
+                                    <label style="display: block; margin-bottom: 5px; font-weight: bold;">"Push to registry"</label>
+                                    <select
+                                        style="width: 100%; padding: 10px; border: 1px solid #555; border-radius: 4px; background-color: #2c3e50; color: white;"
+                                        on:change=move |ev| {
+                                            let value = event_target_value(&ev);
+                                            set_tag_push_registry.set(if value.is_empty() { None } else { Some(value) });
+                                        }
+                                    >
+                                        <option value="">"Don't push"</option>
+                                        <For
+                                            each=move || registries.get()
+                                            key=|registry| registry.name.clone()
+                                            children=move |registry| {
+                                                view! {
+                                                    <option value={&registry.name}>{&registry.name}</option>
+                                                }
+                                            }
+                                        />
+                                    </select>
+                                </div>
+                                <label style="display: flex; align-items: center; gap: 8px; margin-bottom: 20px;">
+                                    <input
+                                        type="checkbox"
+                                        prop:checked=move || tag_force.get()
+                                        on:change=move |ev| set_tag_force.set(event_target_checked(&ev))
+                                    />
+                                    "Move the tag if another image already has it"
+                                </label>
+                                <div style="display: flex; justify-content: flex-end; gap: 10px;">
+                                    <button
+                                        class="btn-primary"
+                                        style="background: #4a5568;"
+                                        on:click=move |_| set_tag_image_id.set(None)
+                                    >
+                                        "Cancel"
+                                    </button>
+                                    <button
+                                        class="btn-primary"
+                                        on:click=move |_| tag_image()
+                                        disabled=move || loading.get() || tag_repository.get().trim().is_empty() || tag_name.get().trim().is_empty()
+                                    >
+                                        "Tag"
+                                    </button>
+                                </div>
+                            </div>
+                        </div>
+                    }.into_view()
+                } else {
+                    view! { <div></div> }.into_view()
+                }
+            }}
+
+            // Prune confirmation modal
+            {move || {
+                if show_prune_confirm.get() {
+                    view! {
+                        <div style="position: fixed; top: 0; left: 0; width: 100%; height: 100%; background-color: rgba(0,0,0,0.5); z-index: 2000; display: flex; align-items: center; justify-content: center;">
+                            <div class="container-card" style="width: 90%; max-width: 480px;">
+                                <h3 style="margin-top: 0;">"Prune images"</h3>
+                                <p style="color: #bbb;">
+                                    {move || if prune_dangling_only.get() {
+                                        "This removes all dangling images. Images used by containers are kept."
+                                    } else {
+                                        "This removes every image not used by a container, including tagged ones."
+                                    }}
+                                </p>
+                                <label style="display: flex; align-items: center; gap: 8px; margin-bottom: 20px;">
+                                    <input
+                                        type="checkbox"
+                                        prop:checked=move || !prune_dangling_only.get()
+                                        on:change=move |ev| set_prune_dangling_only.set(!event_target_checked(&ev))
+                                    />
+                                    "Also remove unused tagged images"
+                                </label>
+                                <div style="display: flex; justify-content: flex-end; gap: 10px;">
+                                    <button
+                                        class="btn-primary"
+                                        style="background: #4a5568;"
+                                        on:click=move |_| set_show_prune_confirm.set(false)
+                                    >
+                                        "Cancel"
+                                    </button>
+                                    <button class="btn-danger" on:click=move |_| prune_images()>
+                                        "Prune"
+                                    </button>
+                                </div>
+                            </div>
+                        </div>
+                    }.into_view()
+                } else {
+                    view! { <div></div> }.into_view()
+                }
+            }}
+
             // Loading indicator
             {move || {
                 if loading.get() {
@@ -389,4 +987,37 @@ pub fn ImageList() -> impl IntoView {
             }}
         </div>
     }
-}
\ No newline at end of file
+}
+
+/// Current state of a background job, or `None` if it has expired or cannot be read
+pub async fn load_job(id: &str) -> Option<Job> {
+    let response = api_client::get(&format!("/api/v1/jobs/{}", id)).send().await.ok()?;
+    if !response.ok() {
+        return None;
+    }
+    response.json::<Job>().await.ok()
+}
+
+/// Load local images from the agent
+async fn load_local_images(
+    set_local_images: WriteSignal<Vec<LocalImage>>,
+    set_local_total_size: WriteSignal<u64>,
+    set_error_message: WriteSignal<Option<String>>,
+) {
+    match api_client::get("/api/v1/images")
+        .send()
+        .await
+    {
+        Ok(response) => {
+            if let Ok(image_list) = response.json::<LocalImageListResponse>().await {
+                set_local_images.set(image_list.images);
+                set_local_total_size.set(image_list.total_size);
+            } else {
+                set_error_message.set(Some("Failed to parse local images".to_string()));
+            }
+        }
+        Err(e) => {
+            set_error_message.set(Some(format!("Failed to load local images: {}", e)));
+        }
+    }
+}
@@ -1,7 +1,72 @@
 use leptos::*;
 
+use crate::auth::{AuthContext, User};
+use crate::services;
+
 #[component]
 pub fn LoginPage() -> impl IntoView {
+    let auth_context = use_context::<AuthContext>().expect("AuthContext must be provided");
+
+    let (username, set_username) = create_signal(String::new());
+    let (password, set_password) = create_signal(String::new());
+    let (api_token, set_api_token) = create_signal(String::new());
+    let (error_message, set_error_message) = create_signal(None::<String>);
+    let (loading, set_loading) = create_signal(false);
+
+    let sign_in_with_password = move |_| {
+        let name = username.get_untracked();
+        let secret = password.get_untracked();
+        if name.trim().is_empty() || secret.is_empty() {
+            set_error_message.set(Some("Enter a username and password".to_string()));
+            return;
+        }
+        spawn_local(async move {
+            set_loading.set(true);
+            set_error_message.set(None);
+            match services::login(&name, &secret).await {
+                Ok(session) => {
+                    let user = User {
+                        id: session.user.id,
+                        username: session.user.username,
+                        email: session.user.email,
+                        roles: session.user.roles,
+                    };
+                    set_password.set(String::new());
+                    auth_context.login(user, session.token, Some(session.expires_in));
+                }
+                Err(message) => set_error_message.set(Some(message)),
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let sign_in_with_token = move |_| {
+        let token = api_token.get_untracked().trim().to_string();
+        if token.is_empty() {
+            set_error_message.set(Some("Enter an API token".to_string()));
+            return;
+        }
+        spawn_local(async move {
+            set_loading.set(true);
+            set_error_message.set(None);
+            match services::whoami(&token).await {
+                Ok(identity) => {
+                    let user = User {
+                        id: identity.name.clone(),
+                        username: identity.name,
+                        email: String::new(),
+                        roles: vec!["admin".to_string()],
+                    };
+                    auth_context.login(user, token, None);
+                }
+                Err(message) => set_error_message.set(Some(message)),
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let input_style = "width: 100%; padding: 10px; margin-bottom: 10px; border: 1px solid #4a5568; border-radius: 4px; background-color: #1a1a1a; color: white;";
+
     view! {
         <div class="login-page">
             <div class="login-container">
@@ -10,7 +75,41 @@ pub fn LoginPage() -> impl IntoView {
                     <p>"Sign in to manage your Bolt containers"</p>
                 </div>
                 <div class="login-form">
-                    <button class="btn-primary">"Sign in with Local Account"</button>
+                    {move || error_message.get().map(|message| view! {
+                        <div style="background-color: #e74c3c; color: white; padding: 10px; border-radius: 4px; margin-bottom: 10px;">
+                            {message}
+                        </div>
+                    })}
+                    <input
+                        type="text"
+                        placeholder="Username"
+                        autocomplete="username"
+                        prop:value=username
+                        on:input=move |ev| set_username.set(event_target_value(&ev))
+                        style=input_style
+                    />
+                    <input
+                        type="password"
+                        placeholder="Password"
+                        autocomplete="current-password"
+                        prop:value=password
+                        on:input=move |ev| set_password.set(event_target_value(&ev))
+                        style=input_style
+                    />
+                    <button class="btn-primary" on:click=sign_in_with_password disabled=loading>
+                        {move || if loading.get() { "Checking..." } else { "Sign in with Local Account" }}
+                    </button>
+                    <div class="divider">"or"</div>
+                    <input
+                        type="password"
+                        placeholder="Agent API token"
+                        prop:value=api_token
+                        on:input=move |ev| set_api_token.set(event_target_value(&ev))
+                        style=input_style
+                    />
+                    <button class="btn-primary" on:click=sign_in_with_token disabled=loading>
+                        {move || if loading.get() { "Checking..." } else { "Sign in with API token" }}
+                    </button>
                     <div class="divider">"or"</div>
                     <button class="btn-primary">"Sign in with Azure AD"</button>
                     <button class="btn-primary">"Sign in with Google"</button>
@@ -19,4 +118,4 @@ pub fn LoginPage() -> impl IntoView {
             </div>
         </div>
     }
-}
\ No newline at end of file
+}
@@ -6,4 +6,4 @@ pub mod volumes;
 pub mod gaming;
 pub mod login;
 pub mod settings;
-pub mod registries;
\ No newline at end of file
+pub mod registries;pub mod audit;
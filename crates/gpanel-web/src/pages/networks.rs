@@ -1,13 +1,329 @@
 use leptos::*;
+use crate::services::{api_client, error_message};
+use gpanel_core::{
+    Container, ContainerListResponse, ContainerStatus, CreateNetworkRequest, Network, NetworkConnectRequest,
+    NetworkListResponse, OperationResult,
+};
 
 #[component]
 pub fn NetworkList() -> impl IntoView {
+    let (networks, set_networks) = create_signal(Vec::<Network>::new());
+    let (containers, set_containers) = create_signal(Vec::<Container>::new());
+    let (loading, set_loading) = create_signal(false);
+    let (error_message_signal, set_error_message) = create_signal(None::<String>);
+    let (new_name, set_new_name) = create_signal(String::new());
+    let (new_subnet, set_new_subnet) = create_signal(String::new());
+    let (new_gateway, set_new_gateway) = create_signal(String::new());
+    let (new_gaming, set_new_gaming) = create_signal(false);
+
+    let refresh = move || {
+        spawn_local(async move {
+            load_networks(set_networks, set_error_message).await;
+            load_containers(set_containers).await;
+        });
+    };
+
+    // Load networks and containers on mount
+    create_effect(move |_| {
+        refresh();
+    });
+
+    let create_network = move || {
+        let name = new_name.get_untracked().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let optional = |value: String| Some(value.trim().to_string()).filter(|v| !v.is_empty());
+        let request = CreateNetworkRequest {
+            name: name.clone(),
+            subnet: optional(new_subnet.get_untracked()),
+            gateway: optional(new_gateway.get_untracked()),
+            gaming: new_gaming.get_untracked(),
+            ..Default::default()
+        };
+
+        spawn_local(async move {
+            set_loading.set(true);
+
+            match api_client::post("/api/v1/networks")
+                .json(&request)
+                .unwrap()
+                .send()
+                .await
+            {
+                Ok(response) if response.ok() => {
+                    set_new_name.set(String::new());
+                    set_new_subnet.set(String::new());
+                    set_new_gateway.set(String::new());
+                    set_new_gaming.set(false);
+                    set_error_message.set(Some(format!("✅ Network {} created", name)));
+                    load_networks(set_networks, set_error_message).await;
+                }
+                Ok(response) => set_error_message.set(Some(format!("❌ {}", error_message(&response).await))),
+                Err(e) => {
+                    set_error_message.set(Some(format!("❌ Create failed: {}", e)));
+                }
+            }
+            set_loading.set(false);
+        });
+    };
+
+    // DELETE for the network itself, or POST to its connect/disconnect endpoint
+    let network_operation = move |url: String, body: Option<NetworkConnectRequest>| {
+        spawn_local(async move {
+            set_loading.set(true);
+
+            let request = match &body {
+                Some(body) => api_client::post(&url).json(body).unwrap().send().await,
+                None => api_client::delete(&url).send().await,
+            };
+            match request {
+                Ok(response) if !response.ok() => {
+                    set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
+                }
+                Ok(response) => match response.json::<OperationResult>().await {
+                    Ok(result) => {
+                        set_error_message.set(Some(format!("✅ {}", result.message)));
+                        load_networks(set_networks, set_error_message).await;
+                        load_containers(set_containers).await;
+                    }
+                    Err(_) => set_error_message.set(Some(format!("❌ Request failed with status {}", response.status()))),
+                },
+                Err(e) => {
+                    set_error_message.set(Some(format!("❌ Network operation failed: {}", e)));
+                }
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let input_style = "width: 100%; padding: 10px; border: 1px solid #555; border-radius: 4px; background-color: #2c3e50; color: white;";
+
     view! {
         <div class="network-list">
-            <h2>"Networks"</h2>
+            <div class="header-section">
+                <h2>"Networks"</h2>
+                <p>"Container networks, including low-latency networks for game servers"</p>
+            </div>
+
+            // Error/Success message display
+            {move || {
+                if let Some(message) = error_message_signal.get() {
+                    let is_success = message.starts_with("✅");
+                    view! {
+                        <div
+                            class="message-banner"
+                            style=format!(
+                                "background-color: {}; color: white; padding: 10px; border-radius: 4px; margin-bottom: 20px;",
+                                if is_success { "#27ae60" } else { "#e74c3c" }
+                            )
+                        >
+                            {message}
+                            <button style="float: right; background: none; border: none; color: white; cursor: pointer;"
+                                    on:click=move |_| set_error_message.set(None)>
+                                "×"
+                            </button>
+                        </div>
+                    }.into_view()
+                } else {
+                    view! { <div></div> }.into_view()
+                }
+            }}
+
+            <div class="container-card" style="margin-bottom: 20px;">
+                <h3>"Create Network"</h3>
+                <div style="display: grid; grid-template-columns: 2fr 2fr 2fr auto auto; gap: 15px; align-items: center;">
+                    <input
+                        type="text"
+                        placeholder="game_net"
+                        style=input_style
+                        prop:value=move || new_name.get()
+                        on:input=move |ev| set_new_name.set(event_target_value(&ev))
+                    />
+                    <input
+                        type="text"
+                        placeholder="Subnet, e.g. 172.30.0.0/16"
+                        style=input_style
+                        prop:value=move || new_subnet.get()
+                        on:input=move |ev| set_new_subnet.set(event_target_value(&ev))
+                    />
+                    <input
+                        type="text"
+                        placeholder="Gateway, e.g. 172.30.0.1"
+                        style=input_style
+                        prop:value=move || new_gateway.get()
+                        on:input=move |ev| set_new_gateway.set(event_target_value(&ev))
+                    />
+                    <label style="color: #bbb; white-space: nowrap;">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || new_gaming.get()
+                            on:change=move |ev| set_new_gaming.set(event_target_checked(&ev))
+                        />
+                        " Gaming"
+                    </label>
+                    <button
+                        class="btn-primary"
+                        style="padding: 10px 20px;"
+                        on:click=move |_| create_network()
+                        disabled=move || loading.get() || new_name.get().trim().is_empty()
+                    >
+                        "Create"
+                    </button>
+                </div>
+            </div>
+
             <div class="container-card">
-                <p>"Network list will be implemented here"</p>
+                <div style="display: flex; justify-content: space-between; align-items: center;">
+                    <h3>"Networks"</h3>
+                    <div style="display: flex; gap: 15px; align-items: center;">
+                        <span style="color: #bbb;">{move || format!("{} networks", networks.get().len())}</span>
+                        <button class="btn-primary" on:click=move |_| refresh()>
+                            "Refresh"
+                        </button>
+                    </div>
+                </div>
+
+                <div style="display: grid; gap: 10px; margin-top: 15px;">
+                    <For
+                        each=move || networks.get()
+                        key=|network| (network.id.clone(), network.containers.clone())
+                        children=move |network| {
+                            let network_id = network.id.clone();
+                            let delete_url = format!("/api/v1/networks/{}", network.id);
+                            let connect_url = format!("/api/v1/networks/{}/connect", network.id);
+                            let has_members = !network.containers.is_empty();
+                            let (attach_id, set_attach_id) = create_signal(String::new());
+
+                            let members = network.containers.iter().map(|id| {
+                                let disconnect_url = format!("/api/v1/networks/{}/disconnect", network_id);
+                                let request = NetworkConnectRequest { container_id: id.clone(), aliases: Vec::new() };
+                                let container_id = id.clone();
+                                view! {
+                                    <span style="display: inline-flex; align-items: center; gap: 6px; background-color: #2c3e50; padding: 4px 8px; border-radius: 4px; margin: 0 6px 6px 0;">
+                                        {move || container_name(&containers.get(), &container_id)}
+                                        <button
+                                            style="background: none; border: none; color: #e74c3c; cursor: pointer;"
+                                            title="Detach from this network"
+                                            on:click=move |_| network_operation(disconnect_url.clone(), Some(request.clone()))
+                                            disabled=move || loading.get()
+                                        >
+                                            "×"
+                                        </button>
+                                    </span>
+                                }
+                            }).collect_view();
+
+                            // Running containers not already on this network
+                            let attached = network.containers.clone();
+                            let candidates = move || {
+                                containers
+                                    .get()
+                                    .into_iter()
+                                    .filter(|c| c.status == ContainerStatus::Running && !attached.contains(&c.id))
+                                    .collect::<Vec<_>>()
+                            };
+
+                            view! {
+                                <div style="background-color: #34495e; border-radius: 8px; padding: 15px; border: 1px solid #4a5568;">
+                                    <div style="display: flex; justify-content: space-between; align-items: center;">
+                                        <div style="display: flex; align-items: center; gap: 10px;">
+                                            <h4 style="margin: 0; color: #3498db;">{&network.name}</h4>
+                                            {network.is_gaming().then(|| view! {
+                                                <span style="background-color: #9b59b6; padding: 2px 6px; border-radius: 3px; font-size: 10px;">"GAMING"</span>
+                                            })}
+                                            {network.internal.then(|| view! {
+                                                <span style="background-color: #7f8c8d; padding: 2px 6px; border-radius: 3px; font-size: 10px;">"INTERNAL"</span>
+                                            })}
+                                        </div>
+                                        <button
+                                            class="btn-danger"
+                                            style="padding: 8px 16px;"
+                                            title=if has_members { "Detach every container first" } else { "" }
+                                            on:click=move |_| network_operation(delete_url.clone(), None)
+                                            disabled=move || has_members || loading.get()
+                                        >
+                                            "Delete"
+                                        </button>
+                                    </div>
+                                    <div style="display: grid; grid-template-columns: repeat(auto-fit, minmax(150px, 1fr)); gap: 10px; font-size: 14px; color: #bbb; margin-top: 8px;">
+                                        <div><strong>"Driver: "</strong> {&network.driver}</div>
+                                        <div><strong>"Subnet: "</strong> {network.subnet.clone().unwrap_or_else(|| "auto".to_string())}</div>
+                                        <div><strong>"Gateway: "</strong> {network.gateway.clone().unwrap_or_else(|| "auto".to_string())}</div>
+                                    </div>
+                                    <div style="margin-top: 10px; font-size: 14px; color: #bbb;">
+                                        <strong>"Containers: "</strong>
+                                        {if has_members { members } else { view! { <span>"none"</span> }.into_view() }}
+                                    </div>
+                                    <div style="display: flex; gap: 10px; margin-top: 10px;">
+                                        <select
+                                            style="padding: 6px; border: 1px solid #555; border-radius: 4px; background-color: #2c3e50; color: white;"
+                                            on:change=move |ev| set_attach_id.set(event_target_value(&ev))
+                                            prop:value=move || attach_id.get()
+                                        >
+                                            <option value="">"Attach a running container..."</option>
+                                            {move || candidates().into_iter().map(|c| view! {
+                                                <option value=c.id.clone()>{c.name.clone()}</option>
+                                            }).collect_view()}
+                                        </select>
+                                        <button
+                                            class="btn-primary"
+                                            style="padding: 6px 14px;"
+                                            on:click=move |_| {
+                                                let request = NetworkConnectRequest {
+                                                    container_id: attach_id.get_untracked(),
+                                                    aliases: Vec::new(),
+                                                };
+                                                network_operation(connect_url.clone(), Some(request));
+                                            }
+                                            disabled=move || attach_id.get().is_empty() || loading.get()
+                                        >
+                                            "Attach"
+                                        </button>
+                                    </div>
+                                </div>
+                            }
+                        }
+                    />
+                </div>
             </div>
         </div>
     }
-}
\ No newline at end of file
+}
+
+/// Name of a container for display, falling back to its ID
+fn container_name(containers: &[Container], id: &str) -> String {
+    containers
+        .iter()
+        .find(|c| c.id == id)
+        .map(|c| c.name.clone())
+        .unwrap_or_else(|| id.to_string())
+}
+
+/// Fetch networks from the API
+async fn load_networks(
+    set_networks: WriteSignal<Vec<Network>>,
+    set_error_message: WriteSignal<Option<String>>,
+) {
+    match api_client::get("/api/v1/networks").send().await {
+        Ok(response) if response.ok() => match response.json::<NetworkListResponse>().await {
+            Ok(list) => set_networks.set(list.networks),
+            Err(e) => set_error_message.set(Some(format!("❌ Failed to parse networks: {}", e))),
+        },
+        Ok(response) => {
+            set_error_message.set(Some(format!("❌ Failed to load networks: HTTP {}", response.status())));
+        }
+        Err(e) => {
+            set_error_message.set(Some(format!("❌ Failed to load networks: {}", e)));
+        }
+    }
+}
+
+/// Fetch containers for member names and the attach dropdown
+async fn load_containers(set_containers: WriteSignal<Vec<Container>>) {
+    if let Ok(response) = api_client::get("/api/v1/containers").send().await {
+        if let Ok(list) = response.json::<ContainerListResponse>().await {
+            set_containers.set(list.containers);
+        }
+    }
+}
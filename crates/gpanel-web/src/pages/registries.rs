@@ -1,70 +1,108 @@
 use leptos::*;
-use serde::{Deserialize, Serialize};
-use gloo_net::http::Request;
-
-/// Registry configuration response from API (without credentials)
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RegistryConfigResponse {
-    pub name: String,
-    pub url: String,
-    pub has_auth: bool,
-    pub insecure: bool,
-}
+use futures::future::{AbortHandle, Abortable};
+use futures::StreamExt;
+use gloo_net::eventsource::futures::EventSource;
+use crate::auth::AuthContext;
+use crate::pages::images::load_job;
+use crate::services::{api_client, error_message};
+use gpanel_core::{
+    short_id, AddRegistryRequest, DriftGcState, DriftGcStatus, DriftInfo, ImageInfo, Job, JobAccepted, JobStatus,
+    RegistryConfigResponse, RegistryHealth, RegistryListResponse, RegistryStats, RegistryStatus, RepositoryList, Role,
+    SystemEvent, TagList,
+};
+
+/// Repositories fetched per page of the repository list
+const REPOSITORY_PAGE_SIZE: u32 = 100;
 
-/// Registry list response
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RegistryListResponse {
-    pub registries: Vec<RegistryConfigResponse>,
+async fn load_registries(set_registries: WriteSignal<Vec<RegistryConfigResponse>>) {
+    if let Ok(response) = api_client::get("/api/v1/registries").send().await {
+        if let Ok(registry_list) = response.json::<RegistryListResponse>().await {
+            set_registries.set(registry_list.registries);
+        }
+    }
 }
 
-/// Add registry request
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AddRegistryRequest {
-    pub name: String,
-    pub url: String,
-    pub username: Option<String>,
-    pub password: Option<String>,
-    pub insecure: bool,
+/// Check the settings of a registry that is not saved yet
+async fn test_registry(request: &AddRegistryRequest) -> Result<RegistryHealth, String> {
+    let url = format!("/api/v1/registries/{}/test", urlencoding::encode(&request.name));
+    let response = api_client::post(&url)
+        .json(request)
+        .map_err(|e| format!("Request error: {}", e))?
+        .send()
+        .await
+        .map_err(|e| format!("Failed to check registry: {}", e))?;
+    if !response.ok() {
+        return Err(error_message(&response).await);
+    }
+    response.json::<RegistryHealth>().await.map_err(|e| format!("Unexpected response: {}", e))
 }
 
-/// Repository list response
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RepositoryList {
-    pub repositories: Vec<String>,
+/// URL of a route under a repository. The name is encoded whole, as namespaced names such as
+/// `library/nginx` contain `/`.
+fn repository_url(registry: &str, repository: &str, route: &str) -> String {
+    format!(
+        "/api/v1/registries/{}/repositories/{}/{}",
+        urlencoding::encode(registry),
+        urlencoding::encode(repository),
+        route
+    )
 }
 
-/// Tag list response
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TagList {
-    pub name: String,
-    pub tags: Vec<String>,
+fn status_label(status: Option<RegistryStatus>) -> &'static str {
+    match status {
+        Some(RegistryStatus::Ok) => "Reachable",
+        Some(RegistryStatus::AuthRequired) => "Credentials refused or missing",
+        Some(RegistryStatus::Unreachable) => "Unreachable",
+        Some(RegistryStatus::TlsError) => "TLS error",
+        None => "Not checked yet",
+    }
 }
 
-/// Image information
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ImageInfo {
-    pub repository: String,
-    pub tag: String,
-    pub digest: String,
-    pub size: u64,
-    pub created: chrono::DateTime<chrono::Utc>,
-    pub author: Option<String>,
-    pub layers: Vec<LayerInfo>,
+/// Green when reachable, yellow for credential problems, red when it cannot be reached
+fn status_color(status: Option<RegistryStatus>) -> &'static str {
+    match status {
+        Some(RegistryStatus::Ok) => "#27ae60",
+        Some(RegistryStatus::AuthRequired) => "#f1c40f",
+        Some(_) => "#e74c3c",
+        None => "#7f8c8d",
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LayerInfo {
-    pub digest: String,
-    pub size: u64,
-    pub media_type: String,
-    pub created_by: Option<String>,
+/// What to change when a registry's certificate or TLS setup is refused
+const TLS_HINT: &str = "allow insecure connections, or give the path of its CA certificate";
+
+/// Hover text of a registry's status dot
+fn status_title(registry: &RegistryConfigResponse) -> String {
+    let mut title = status_label(registry.status).to_string();
+    if let Some(error) = &registry.last_error {
+        title.push_str(&format!(": {}", error));
+    }
+    if registry.status == Some(RegistryStatus::TlsError) {
+        title.push_str(&format!("; edit the registry to {}", TLS_HINT));
+    }
+    if let Some(checked) = registry.last_checked {
+        title.push_str(&format!(" (checked {})", checked.format("%H:%M:%S")));
+    }
+    title
 }
 
-/// Operation result response
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OperationResult {
-    pub success: bool,
-    pub message: String,
+/// What the last garbage collection of a Drift registry did
+fn gc_summary(status: &DriftGcStatus) -> String {
+    let finished = status
+        .finished_at
+        .map(|at| format!(" {}", at.format("%Y-%m-%d %H:%M UTC")))
+        .unwrap_or_default();
+    match status.state {
+        DriftGcState::Running => "Collecting garbage...".to_string(),
+        DriftGcState::Succeeded => format!(
+            "Last GC{}: {} blobs, {} freed",
+            finished,
+            status.blobs_removed,
+            format_size(status.bytes_freed)
+        ),
+        DriftGcState::Failed => format!("Last GC{} failed: {}", finished, status.error.clone().unwrap_or_default()),
+        DriftGcState::Idle => "Never collected".to_string(),
+    }
 }
 
 /// Format file size in human readable format
@@ -83,16 +121,27 @@ fn format_size(size: u64) -> String {
 
 #[component]
 pub fn RegistryManagement() -> impl IntoView {
+    let auth_context = use_context::<AuthContext>().expect("AuthContext must be provided");
+    let is_admin = move || auth_context.has_role(Role::Admin);
+    let is_operator = move || auth_context.has_role(Role::Operator);
     let (registries, set_registries) = create_signal(Vec::<RegistryConfigResponse>::new());
     let (selected_registry, set_selected_registry) = create_signal(None::<String>);
     let (repositories, set_repositories) = create_signal(Vec::<String>::new());
+    // Where the next page of repositories starts, if there is one
+    let (next_repository, set_next_repository) = create_signal(None::<String>);
     let (selected_repo, set_selected_repo) = create_signal(None::<String>);
     let (tags, set_tags) = create_signal(Vec::<String>::new());
     let (selected_image_info, set_selected_image_info) = create_signal(None::<ImageInfo>);
+    // Stats of the selected registry, and the job counting them while it runs
+    let (registry_stats, set_registry_stats) = create_signal(None::<RegistryStats>);
+    let (stats_job, set_stats_job) = create_signal(None::<Job>);
+    // Set when the selected registry is Drift, with its last garbage collection
+    let (drift_info, set_drift_info) = create_signal(None::<DriftInfo>);
+    let (drift_gc, set_drift_gc) = create_signal(None::<DriftGcStatus>);
 
     let (show_add_modal, set_show_add_modal) = create_signal(false);
     let (loading, set_loading) = create_signal(false);
-    let (error_message, set_error_message) = create_signal(None::<String>);
+    let (error_message_signal, set_error_message) = create_signal(None::<String>);
 
     // Form fields for adding registry
     let (registry_name, set_registry_name) = create_signal(String::new());
@@ -100,130 +149,393 @@ pub fn RegistryManagement() -> impl IntoView {
     let (registry_username, set_registry_username) = create_signal(String::new());
     let (registry_password, set_registry_password) = create_signal(String::new());
     let (registry_insecure, set_registry_insecure) = create_signal(false);
+    let (registry_ca_cert_path, set_registry_ca_cert_path) = create_signal(String::new());
+    // Why the registry in the form failed its check
+    let (test_error, set_test_error) = create_signal(None::<String>);
+    // The registry being edited, when the modal edits rather than adds one
+    let (editing, set_editing) = create_signal(None::<RegistryConfigResponse>);
+    // Image waiting for the admin to confirm its deletion
+    let (confirm_delete, set_confirm_delete) = create_signal(None::<ImageInfo>);
+    // Image being given another tag, with the tag typed so far
+    let (retag_image, set_retag_image) = create_signal(None::<ImageInfo>);
+    let (retag_target, set_retag_target) = create_signal(String::new());
+
+    let close_modal = move || {
+        set_registry_name.set(String::new());
+        set_registry_url.set(String::new());
+        set_registry_username.set(String::new());
+        set_registry_password.set(String::new());
+        set_registry_insecure.set(false);
+        set_registry_ca_cert_path.set(String::new());
+        set_test_error.set(None);
+        set_editing.set(None);
+        set_show_add_modal.set(false);
+    };
+
+    // Open the modal on a registry's current settings; the password is left blank to keep it
+    let edit_registry = move |registry: RegistryConfigResponse| {
+        set_registry_name.set(registry.name.clone());
+        set_registry_url.set(registry.url.clone());
+        set_registry_username.set(registry.username.clone().unwrap_or_default());
+        set_registry_password.set(String::new());
+        set_registry_insecure.set(registry.insecure);
+        set_registry_ca_cert_path.set(registry.ca_cert_path.clone().unwrap_or_default());
+        set_test_error.set(None);
+        set_editing.set(Some(registry));
+        set_show_add_modal.set(true);
+    };
 
     // Load registries on mount
     create_effect(move |_| {
-        spawn_local(async move {
-            if let Ok(response) = Request::get("http://localhost:8000/api/v1/registries")
-                .send()
-                .await
-            {
-                if let Ok(registry_list) = response.json::<RegistryListResponse>().await {
-                    set_registries.set(registry_list.registries);
+        spawn_local(load_registries(set_registries));
+    });
+
+    // Reload when the agent picks up registry changes from its config file
+    create_effect(move |_| {
+        let Ok(mut source) = EventSource::new(&api_client::authorized_url("/api/v1/events")) else {
+            return;
+        };
+        let Ok(mut changes) = source.subscribe("system") else {
+            return;
+        };
+
+        let (abort, registration) = AbortHandle::new_pair();
+        let apply = async move {
+            while let Some(event) = changes.next().await {
+                let Ok((_, message)) = event else {
+                    continue;
+                };
+                let change = message
+                    .data()
+                    .as_string()
+                    .and_then(|data| serde_json::from_str::<SystemEvent>(&data).ok());
+                if let Some(SystemEvent::RegistriesChanged { removed, updated, .. }) = change {
+                    if selected_registry
+                        .get_untracked()
+                        .is_some_and(|name| removed.contains(&name) || updated.contains(&name))
+                    {
+                        set_selected_registry.set(None);
+                    }
+                    load_registries(set_registries).await;
                 }
             }
+        };
+        spawn_local(async move {
+            let _ = Abortable::new(apply, registration).await;
+        });
+
+        on_cleanup(move || {
+            abort.abort();
+            drop(source);
         });
     });
 
-    // Load repositories when registry is selected
-    create_effect(move |_| {
-        if let Some(registry_name) = selected_registry.get() {
-            spawn_local(async move {
-                set_loading.set(true);
-                let url = format!("http://localhost:8000/api/v1/registries/{}/repositories", registry_name);
+    // Load a page of repositories, appending it to the list unless it is the first
+    let load_repositories = move |registry_name: String, last: Option<String>| {
+        spawn_local(async move {
+            set_loading.set(true);
+            let mut url = format!(
+                "/api/v1/registries/{}/repositories?n={}",
+                urlencoding::encode(&registry_name),
+                REPOSITORY_PAGE_SIZE
+            );
+            if let Some(last) = &last {
+                url.push_str(&format!("&last={}", urlencoding::encode(last)));
+            }
 
-                match Request::get(&url).send().await {
-                    Ok(response) => {
-                        if let Ok(repo_list) = response.json::<RepositoryList>().await {
+            match api_client::get(&url).send().await {
+                Ok(response) if response.ok() => {
+                    if let Ok(repo_list) = response.json::<RepositoryList>().await {
+                        if last.is_some() {
+                            set_repositories.update(|repositories| repositories.extend(repo_list.repositories));
+                        } else {
                             set_repositories.set(repo_list.repositories);
                         }
+                        set_next_repository.set(repo_list.next);
                     }
-                    Err(e) => {
-                        set_error_message.set(Some(format!("Failed to load repositories: {}", e)));
+                }
+                Ok(response) => set_error_message.set(Some(error_message(&response).await)),
+                Err(e) => {
+                    set_error_message.set(Some(format!("Failed to load repositories: {}", e)));
+                }
+            }
+            set_loading.set(false);
+        });
+    };
+
+    // Cached stats of a registry, or the job counting them when there are none recent
+    let load_stats = move |registry_name: String, refresh: bool| {
+        spawn_local(async move {
+            let mut url = format!("/api/v1/registries/{}/stats", urlencoding::encode(&registry_name));
+            if refresh {
+                url.push_str("?refresh=true");
+            }
+
+            match api_client::get(&url).send().await {
+                Ok(response) if response.status() == 202 => {
+                    if let Ok(accepted) = response.json::<JobAccepted>().await {
+                        set_stats_job.set(load_job(&accepted.job_id).await);
                     }
                 }
-                set_loading.set(false);
-            });
+                Ok(response) if response.ok() => {
+                    if let Ok(stats) = response.json::<RegistryStats>().await {
+                        set_registry_stats.set(Some(stats));
+                    }
+                }
+                Ok(response) => set_error_message.set(Some(error_message(&response).await)),
+                Err(e) => set_error_message.set(Some(format!("Failed to load registry stats: {}", e))),
+            }
+        });
+    };
+
+    // Drift's info and last garbage collection; other registries answer 501 and get neither
+    let load_drift = move |registry_name: String| {
+        spawn_local(async move {
+            let url = format!("/api/v1/registries/{}/drift/info", urlencoding::encode(&registry_name));
+            let Ok(response) = api_client::get(&url).send().await else {
+                return;
+            };
+            if !response.ok() {
+                return;
+            }
+            let Ok(info) = response.json::<DriftInfo>().await else {
+                return;
+            };
+            let url = format!("/api/v1/registries/{}/drift/gc", urlencoding::encode(&registry_name));
+            if let Ok(response) = api_client::get(&url).send().await {
+                if response.ok() {
+                    set_drift_gc.set(response.json::<DriftGcStatus>().await.ok());
+                }
+            }
+            if selected_registry.get_untracked() == Some(registry_name) {
+                set_drift_info.set(Some(info));
+            }
+        });
+    };
+
+    let collect_garbage = move |registry_name: String| {
+        spawn_local(async move {
+            let url = format!("/api/v1/registries/{}/drift/gc", urlencoding::encode(&registry_name));
+            match api_client::post(&url).send().await {
+                Ok(response) if response.ok() => set_drift_gc.set(response.json::<DriftGcStatus>().await.ok()),
+                Ok(response) => set_error_message.set(Some(error_message(&response).await)),
+                Err(e) => set_error_message.set(Some(format!("Failed to start garbage collection: {}", e))),
+            }
+        });
+    };
+
+    // Follow a running garbage collection every second
+    let refresh_drift_gc = move || {
+        if drift_gc.get_untracked().map_or(true, |status| status.state != DriftGcState::Running) {
+            return;
         }
-    });
+        let Some(registry_name) = selected_registry.get_untracked() else {
+            return;
+        };
+        spawn_local(async move {
+            let url = format!("/api/v1/registries/{}/drift/gc", urlencoding::encode(&registry_name));
+            if let Ok(response) = api_client::get(&url).send().await {
+                if response.ok() && selected_registry.get_untracked() == Some(registry_name) {
+                    set_drift_gc.set(response.json::<DriftGcStatus>().await.ok());
+                }
+            }
+        });
+    };
+    if let Ok(handle) = set_interval_with_handle(refresh_drift_gc, std::time::Duration::from_secs(1)) {
+        on_cleanup(move || handle.clear());
+    }
 
-    // Load tags when repository is selected
+    // Follow the counting job every second, loading the stats once it has finished
+    let refresh_stats_job = move || {
+        let Some(job) = stats_job.get_untracked().filter(|job| job.status == JobStatus::Running) else {
+            return;
+        };
+        spawn_local(async move {
+            let Some(job) = load_job(&job.id).await else {
+                set_stats_job.set(None);
+                return;
+            };
+            match job.status {
+                JobStatus::Running => set_stats_job.set(Some(job)),
+                JobStatus::Succeeded => {
+                    set_stats_job.set(None);
+                    if selected_registry.get_untracked().as_deref() == Some(job.target.as_str()) {
+                        load_stats(job.target, false);
+                    }
+                }
+                JobStatus::Failed | JobStatus::Cancelled => {
+                    set_stats_job.set(None);
+                    set_error_message.set(Some(format!("Failed to count {}: {}", job.target, job.message)));
+                }
+            }
+        });
+    };
+    if let Ok(handle) = set_interval_with_handle(refresh_stats_job, std::time::Duration::from_secs(1)) {
+        on_cleanup(move || handle.clear());
+    }
+
+    // Load repositories when registry is selected
     create_effect(move |_| {
-        if let (Some(registry_name), Some(repo_name)) = (selected_registry.get(), selected_repo.get()) {
-            spawn_local(async move {
-                set_loading.set(true);
-                let url = format!("http://localhost:8000/api/v1/registries/{}/repositories/{}/tags",
-                                registry_name, repo_name);
+        if let Some(registry_name) = selected_registry.get() {
+            set_next_repository.set(None);
+            set_registry_stats.set(None);
+            set_stats_job.set(None);
+            set_drift_info.set(None);
+            set_drift_gc.set(None);
+            load_repositories(registry_name.clone(), None);
+            load_stats(registry_name.clone(), false);
+            load_drift(registry_name);
+        }
+    });
 
-                match Request::get(&url).send().await {
-                    Ok(response) => {
-                        if let Ok(tag_list) = response.json::<TagList>().await {
-                            set_tags.set(tag_list.tags);
-                        }
-                    }
-                    Err(e) => {
-                        set_error_message.set(Some(format!("Failed to load tags: {}", e)));
+    let load_tags = move |registry_name: String, repo_name: String| {
+        spawn_local(async move {
+            set_loading.set(true);
+            // Highest versions first, with `latest` on top
+            let url = repository_url(&registry_name, &repo_name, "tags?sort=semver");
+
+            match api_client::get(&url).send().await {
+                Ok(response) => {
+                    if let Ok(tag_list) = response.json::<TagList>().await {
+                        set_tags.set(tag_list.tags);
                     }
                 }
-                set_loading.set(false);
-            });
+                Err(e) => {
+                    set_error_message.set(Some(format!("Failed to load tags: {}", e)));
+                }
+            }
+            set_loading.set(false);
+        });
+    };
+
+    // Load tags when repository is selected
+    create_effect(move |_| {
+        if let (Some(registry_name), Some(repo_name)) = (selected_registry.get(), selected_repo.get()) {
+            load_tags(registry_name, repo_name);
         }
     });
 
-    let add_registry = move |_| {
+    let save_registry = move |_| {
         spawn_local(async move {
             set_loading.set(true);
 
+            let edited = editing.get_untracked();
             let request = AddRegistryRequest {
                 name: registry_name.get(),
                 url: registry_url.get(),
                 username: if registry_username.get().is_empty() { None } else { Some(registry_username.get()) },
                 password: if registry_password.get().is_empty() { None } else { Some(registry_password.get()) },
                 insecure: registry_insecure.get(),
+                ca_cert_path: if registry_ca_cert_path.get().is_empty() { None } else { Some(registry_ca_cert_path.get()) },
+                page_size: edited.as_ref().and_then(|registry| registry.page_size),
+                max_list_entries: edited.as_ref().and_then(|registry| registry.max_list_entries),
+                search_url: edited.as_ref().and_then(|registry| registry.search_url.clone()),
             };
 
-            match Request::post("http://localhost:8000/api/v1/registries")
+            // The agent checks edits itself, with the saved password when none is given
+            if let Some(edited) = edited {
+                let url = format!("/api/v1/registries/{}", urlencoding::encode(&edited.name));
+                match api_client::put(&url).json(&request).unwrap().send().await {
+                    Ok(response) if response.ok() => {
+                        if selected_registry.get_untracked() == Some(edited.name.clone()) {
+                            set_selected_registry.set(Some(request.name.clone()));
+                        }
+                        load_registries(set_registries).await;
+                        close_modal();
+                    }
+                    Ok(response) => set_test_error.set(Some(error_message(&response).await)),
+                    Err(e) => set_test_error.set(Some(format!("Failed to update registry: {}", e))),
+                }
+                set_loading.set(false);
+                return;
+            }
+
+            // Check the settings first, so a typo is caught before the registry is saved
+            match test_registry(&request).await {
+                Ok(health) if health.status == RegistryStatus::Ok => set_test_error.set(None),
+                Ok(health) => {
+                    let mut error = format!("{}: {}", status_label(Some(health.status)), health.message.unwrap_or_default());
+                    if health.status == RegistryStatus::TlsError {
+                        error.push_str(&format!(". To use it anyway, {}.", TLS_HINT));
+                    }
+                    set_test_error.set(Some(error));
+                    set_loading.set(false);
+                    return;
+                }
+                Err(e) => {
+                    set_test_error.set(Some(e));
+                    set_loading.set(false);
+                    return;
+                }
+            }
+
+            match api_client::post("/api/v1/registries")
                 .json(&request)
                 .unwrap()
                 .send()
                 .await
             {
+                Ok(response) if response.ok() => {
+                    load_registries(set_registries).await;
+                    close_modal();
+                }
                 Ok(response) => {
-                    if let Ok(result) = response.json::<OperationResult>().await {
-                        if result.success {
-                            // Refresh registry list
-                            if let Ok(response) = Request::get("http://localhost:8000/api/v1/registries")
-                                .send()
-                                .await
-                            {
-                                if let Ok(registry_list) = response.json::<RegistryListResponse>().await {
-                                    set_registries.set(registry_list.registries);
-                                }
-                            }
+                    set_error_message.set(Some(error_message(&response).await));
+                }
+                Err(e) => {
+                    set_error_message.set(Some(format!("Failed to add registry: {}", e)));
+                }
+            }
+            set_loading.set(false);
+        });
+    };
 
-                            // Reset form and close modal
-                            set_registry_name.set(String::new());
-                            set_registry_url.set(String::new());
-                            set_registry_username.set(String::new());
-                            set_registry_password.set(String::new());
-                            set_registry_insecure.set(false);
-                            set_show_add_modal.set(false);
-                        } else {
-                            set_error_message.set(Some(result.message));
-                        }
+    let remove_registry = move |name: String| {
+        spawn_local(async move {
+            set_loading.set(true);
+            let url = format!("/api/v1/registries/{}", urlencoding::encode(&name));
+
+            match api_client::delete(&url).send().await {
+                Ok(response) if response.ok() => {
+                    set_registries.update(|list| list.retain(|registry| registry.name != name));
+                    if selected_registry.get_untracked() == Some(name) {
+                        set_selected_registry.set(None);
+                        set_selected_repo.set(None);
+                        set_repositories.set(Vec::new());
+                        set_next_repository.set(None);
+                        set_tags.set(Vec::new());
+                        set_selected_image_info.set(None);
                     }
                 }
+                Ok(response) => {
+                    set_error_message.set(Some(error_message(&response).await));
+                }
                 Err(e) => {
-                    set_error_message.set(Some(format!("Failed to add registry: {}", e)));
+                    set_error_message.set(Some(format!("Failed to remove registry: {}", e)));
                 }
             }
             set_loading.set(false);
         });
     };
 
-    let get_image_info = move |tag: String| {
+    let get_image_info = move |tag: String, platform: Option<String>| {
         if let (Some(registry_name), Some(repo_name)) = (selected_registry.get(), selected_repo.get()) {
             spawn_local(async move {
                 set_loading.set(true);
-                let url = format!("http://localhost:8000/api/v1/registries/{}/repositories/{}/tags/{}",
-                                registry_name, repo_name, tag);
+                let mut url = repository_url(&registry_name, &repo_name, &format!("tags/{}", urlencoding::encode(&tag)));
+                if let Some(platform) = platform {
+                    url.push_str(&format!("?platform={}", urlencoding::encode(&platform)));
+                }
 
-                match Request::get(&url).send().await {
-                    Ok(response) => {
+                match api_client::get(&url).send().await {
+                    Ok(response) if response.ok() => {
                         if let Ok(image_info) = response.json::<ImageInfo>().await {
                             set_selected_image_info.set(Some(image_info));
                         }
                     }
+                    Ok(response) => {
+                        set_error_message.set(Some(error_message(&response).await));
+                    }
                     Err(e) => {
                         set_error_message.set(Some(format!("Failed to load image info: {}", e)));
                     }
@@ -233,19 +545,82 @@ pub fn RegistryManagement() -> impl IntoView {
         }
     };
 
+    // Delete the confirmed image, then reload the tags, as others may have pointed to it too
+    let delete_image = move |image: ImageInfo| {
+        let Some(registry_name) = selected_registry.get_untracked() else {
+            return;
+        };
+        spawn_local(async move {
+            set_loading.set(true);
+            let url = repository_url(&registry_name, &image.repository, &format!("tags/{}", urlencoding::encode(&image.tag)));
+
+            match api_client::delete(&url).send().await {
+                Ok(response) if response.ok() => {
+                    set_confirm_delete.set(None);
+                    set_selected_image_info.set(None);
+                    load_tags(registry_name, image.repository);
+                }
+                Ok(response) => {
+                    set_confirm_delete.set(None);
+                    set_error_message.set(Some(error_message(&response).await));
+                }
+                Err(e) => {
+                    set_confirm_delete.set(None);
+                    set_error_message.set(Some(format!("Failed to delete image: {}", e)));
+                }
+            }
+            set_loading.set(false);
+        });
+    };
+
+    // Point a new tag at the image's manifest, then reload the tags to show it
+    let retag = move |image: ImageInfo| {
+        let Some(registry_name) = selected_registry.get_untracked() else {
+            return;
+        };
+        let target = retag_target.get_untracked().trim().to_string();
+        if target.is_empty() {
+            return;
+        }
+        spawn_local(async move {
+            set_loading.set(true);
+            let url = repository_url(&registry_name, &image.repository, "tags");
+            let request = serde_json::json!({ "source": image.tag, "target": target });
+
+            match api_client::post(&url).json(&request).unwrap().send().await {
+                Ok(response) if response.ok() => {
+                    set_retag_image.set(None);
+                    set_retag_target.set(String::new());
+                    load_tags(registry_name, image.repository);
+                }
+                Ok(response) => {
+                    set_retag_image.set(None);
+                    set_error_message.set(Some(error_message(&response).await));
+                }
+                Err(e) => {
+                    set_retag_image.set(None);
+                    set_error_message.set(Some(format!("Failed to tag image: {}", e)));
+                }
+            }
+            set_loading.set(false);
+        });
+    };
+
     view! {
         <div class="registry-management">
             <div class="header-section">
                 <h2>"Registry Management"</h2>
                 <p>"Manage container image registries including Docker Hub and Drift"</p>
-                <button class="btn-primary" on:click=move |_| set_show_add_modal.set(true)>
-                    "Add Registry"
-                </button>
+                <Show when=is_admin>
+                    <button class="btn-primary" on:click=move |_| set_show_add_modal.set(true)>
+                        "Add Registry"
+                    </button>
+                </Show>
             </div>
 
             // Error message display
             {move || {
-                if let Some(error) = error_message.get() {
+                if let Some(error) = error_message_signal.get() {
                     view! {
                         <div class="error-banner" style="background-color: #e74c3c; color: white; padding: 10px; border-radius: 4px; margin-bottom: 20px;">
                             {error}
@@ -271,6 +646,8 @@ pub fn RegistryManagement() -> impl IntoView {
                             children=move |registry| {
                                 let registry_name = registry.name.clone();
                                 let registry_name_for_click = registry_name.clone();
+                                let registry_name_for_remove = registry_name.clone();
+                                let registry_for_edit = registry.clone();
                                 let is_selected = move || selected_registry.get() == Some(registry_name.clone());
 
                                 view! {
@@ -287,7 +664,16 @@ pub fn RegistryManagement() -> impl IntoView {
                                             set_selected_image_info.set(None);
                                         }
                                     >
-                                        <div style="font-weight: bold;">{&registry.name}</div>
+                                        <div style="font-weight: bold;">
+                                            <span
+                                                style=format!(
+                                                    "display: inline-block; width: 10px; height: 10px; border-radius: 50%; margin-right: 6px; background-color: {};",
+                                                    status_color(registry.status)
+                                                )
+                                                title=status_title(&registry)
+                                            ></span>
+                                            {&registry.name}
+                                        </div>
                                         <div style="font-size: 12px; opacity: 0.8;">{&registry.url}</div>
                                         {if registry.has_auth {
                                             view! { <span style="font-size: 10px; background-color: #27ae60; padding: 2px 4px; border-radius: 2px;">
@@ -296,6 +682,36 @@ pub fn RegistryManagement() -> impl IntoView {
                                         } else {
                                             view! { <div></div> }.into_view()
                                         }}
+                                        <Show when=is_admin>
+                                            <button
+                                                class="btn-primary"
+                                                style="float: right; padding: 2px 8px; font-size: 11px; margin-left: 4px;"
+                                                disabled=move || loading.get()
+                                                on:click={
+                                                    let registry = registry_for_edit.clone();
+                                                    move |ev| {
+                                                        ev.stop_propagation();
+                                                        edit_registry(registry.clone());
+                                                    }
+                                                }
+                                            >
+                                                "Edit"
+                                            </button>
+                                            <button
+                                                class="btn-danger"
+                                                style="float: right; padding: 2px 8px; font-size: 11px;"
+                                                disabled=move || loading.get()
+                                                on:click={
+                                                    let name = registry_name_for_remove.clone();
+                                                    move |ev| {
+                                                        ev.stop_propagation();
+                                                        remove_registry(name.clone());
+                                                    }
+                                                }
+                                            >
+                                                "Remove"
+                                            </button>
+                                        </Show>
                                     </div>
                                 }
                             }
@@ -306,6 +722,68 @@ pub fn RegistryManagement() -> impl IntoView {
                 // Repository List
                 <div class="container-card">
                     <h3>"Repositories"</h3>
+                    {move || selected_registry.get().map(|registry_name| view! {
+                        <div style="display: flex; align-items: center; gap: 10px; font-size: 13px; margin-bottom: 8px;">
+                            <span style="flex: 1;">
+                                {move || match (registry_stats.get(), stats_job.get()) {
+                                    (_, Some(job)) => format!("Counting... {}%", job.percent),
+                                    (Some(stats), None) => {
+                                        let mut summary = format!(
+                                            "{} repositories · {} tags · {} layers · {}",
+                                            stats.repositories,
+                                            stats.tags,
+                                            stats.unique_layers,
+                                            format_size(stats.total_bytes)
+                                        );
+                                        if stats.skipped_repositories > 0 {
+                                            summary.push_str(&format!(" ({} not counted)", stats.skipped_repositories));
+                                        }
+                                        summary
+                                    }
+                                    (None, None) => String::new(),
+                                }}
+                            </span>
+                            <button
+                                class="btn-primary"
+                                style="padding: 2px 8px; font-size: 11px;"
+                                title=move || registry_stats.get().map(|stats| format!("Counted {}", stats.computed_at.format("%Y-%m-%d %H:%M UTC"))).unwrap_or_default()
+                                disabled=move || stats_job.get().is_some()
+                                on:click={
+                                    let registry_name = registry_name.clone();
+                                    move |_| load_stats(registry_name.clone(), true)
+                                }
+                            >
+                                "Refresh"
+                            </button>
+                        </div>
+                        {move || drift_info.get().map(|info| {
+                            let registry_name = registry_name.clone();
+                            view! {
+                                <div style="display: flex; align-items: center; gap: 10px; font-size: 13px; margin-bottom: 8px;">
+                                    <span
+                                        style="background-color: #8e44ad; color: white; padding: 1px 6px; border-radius: 3px; font-size: 11px;"
+                                        title=format!("Drift {}", info.version)
+                                    >
+                                        "Drift"
+                                    </span>
+                                    <span style="flex: 1;">{move || drift_gc.get().map(|status| gc_summary(&status)).unwrap_or_default()}</span>
+                                    <Show when=is_admin>
+                                        <button
+                                            class="btn-danger"
+                                            style="padding: 2px 8px; font-size: 11px;"
+                                            disabled=move || drift_gc.get().is_some_and(|status| status.state == DriftGcState::Running)
+                                            on:click={
+                                                let registry_name = registry_name.clone();
+                                                move |_| collect_garbage(registry_name.clone())
+                                            }
+                                        >
+                                            "Garbage Collect"
+                                        </button>
+                                    </Show>
+                                </div>
+                            }
+                        })}
+                    })}
                     {move || {
                         if selected_registry.get().is_some() {
                             view! {
@@ -335,6 +813,17 @@ pub fn RegistryManagement() -> impl IntoView {
                                             }
                                         }
                                     />
+                                    {move || next_repository.get().map(|last| view! {
+                                        <button class="btn-primary" style="width: 100%; margin-top: 5px;"
+                                                disabled=move || loading.get()
+                                                on:click=move |_| {
+                                                    if let Some(registry_name) = selected_registry.get_untracked() {
+                                                        load_repositories(registry_name, Some(last.clone()));
+                                                    }
+                                                }>
+                                            "Load more"
+                                        </button>
+                                    })}
                                 </div>
                             }.into_view()
                         } else {
@@ -364,7 +853,7 @@ pub fn RegistryManagement() -> impl IntoView {
                                                 <div
                                                     class="tag-item"
                                                     style="padding: 8px; margin: 3px 0; border-radius: 4px; cursor: pointer; font-size: 14px; background-color: #34495e; display: flex; justify-content: space-between; align-items: center;"
-                                                    on:click=move |_| get_image_info(tag_name.clone())
+                                                    on:click=move |_| get_image_info(tag_name.clone(), None)
                                                 >
                                                     <span>{tag}</span>
                                                     <button class="btn-primary" style="padding: 4px 8px; font-size: 12px;">
@@ -390,6 +879,15 @@ pub fn RegistryManagement() -> impl IntoView {
             // Image Details Panel
             {move || {
                 if let Some(image_info) = selected_image_info.get() {
+                    let image_for_delete = image_info.clone();
+                    let image_for_retag = image_info.clone();
+                    let export_url = selected_registry.get_untracked().map(|registry_name| {
+                        api_client::authorized_url(&repository_url(
+                            &registry_name,
+                            &image_info.repository,
+                            &format!("tags/{}/export", urlencoding::encode(&image_info.tag)),
+                        ))
+                    });
                     view! {
                         <div class="container-card">
                             <h3>"Image Details: " {&image_info.repository} ":" {&image_info.tag}</h3>
@@ -418,6 +916,28 @@ pub fn RegistryManagement() -> impl IntoView {
                                     } else {
                                         view! { <div></div> }.into_view()
                                     }}
+                                    {if image_info.platforms.len() > 1 {
+                                        let tag = image_info.tag.clone();
+                                        let current = image_info.platform.clone().unwrap_or_default();
+                                        view! {
+                                            <div style="margin: 10px 0;">
+                                                <strong>"Platform: "</strong>
+                                                <select on:change=move |ev| get_image_info(tag.clone(), Some(event_target_value(&ev)))>
+                                                    {image_info.platforms.iter().map(|platform| view! {
+                                                        <option value=platform.clone() selected=*platform == current>{platform.clone()}</option>
+                                                    }).collect_view()}
+                                                </select>
+                                            </div>
+                                        }.into_view()
+                                    } else if let Some(platform) = &image_info.platform {
+                                        view! {
+                                            <div style="margin: 10px 0;">
+                                                <strong>"Platform: "</strong> {platform}
+                                            </div>
+                                        }.into_view()
+                                    } else {
+                                        view! { <div></div> }.into_view()
+                                    }}
                                 </div>
 
                                 <div>
@@ -430,7 +950,7 @@ pub fn RegistryManagement() -> impl IntoView {
                                                 view! {
                                                     <div style="background-color: #1a1a1a; padding: 8px; margin: 4px 0; border-radius: 4px; font-size: 12px;">
                                                         <div>
-                                                            <code>{layer.digest.split(':').last().unwrap_or(&layer.digest)[..12].to_string()}</code>
+                                                            <code>{short_id(layer.digest.split(':').last().unwrap_or(&layer.digest)).to_string()}</code>
                                                             <span style="float: right;">{format_size(layer.size)}</span>
                                                         </div>
                                                         <div style="color: #888; margin-top: 4px;">
@@ -451,6 +971,31 @@ pub fn RegistryManagement() -> impl IntoView {
                                 <button class="btn-primary">
                                     "Create Container"
                                 </button>
+                                <Show when=is_operator>
+                                    <button
+                                        class="btn-primary"
+                                        style="margin-left: 10px;"
+                                        disabled=move || loading.get()
+                                        on:click=move |_| set_retag_image.set(Some(image_for_retag.clone()))
+                                    >
+                                        "Retag"
+                                    </button>
+                                    {export_url.clone().map(|url| view! {
+                                        <a href=url class="btn-primary" style="margin-left: 10px;" download="">
+                                            "Export"
+                                        </a>
+                                    })}
+                                </Show>
+                                <Show when=is_admin>
+                                    <button
+                                        class="btn-danger"
+                                        style="margin-left: 10px;"
+                                        disabled=move || loading.get()
+                                        on:click=move |_| set_confirm_delete.set(Some(image_for_delete.clone()))
+                                    >
+                                        "Delete"
+                                    </button>
+                                </Show>
                             </div>
                         </div>
                     }.into_view()
@@ -459,13 +1004,89 @@ pub fn RegistryManagement() -> impl IntoView {
                 }
             }}
 
-            // Add Registry Modal
+            // Delete Image Confirmation
+            {move || confirm_delete.get().map(|image| {
+                let digest = image.manifest_digest.clone().unwrap_or_else(|| image.digest.clone());
+                view! {
+                    <div style="position: fixed; top: 0; left: 0; width: 100%; height: 100%; background-color: rgba(0,0,0,0.5); z-index: 2000; display: flex; align-items: center; justify-content: center;">
+                        <div class="container-card" style="width: 500px; max-width: 90vw;">
+                            <h3>{format!("Delete {}:{}?", image.repository, image.tag)}</h3>
+                            <p>"This deletes the manifest below from the registry, with every other tag pointing to it:"</p>
+                            <code style="display: block; background-color: #1a1a1a; padding: 6px; border-radius: 2px; font-size: 12px; word-break: break-all;">
+                                {digest}
+                            </code>
+                            <div style="display: flex; justify-content: flex-end; gap: 10px; margin-top: 20px;">
+                                <button
+                                    class="btn-primary"
+                                    style="background-color: #555;"
+                                    on:click=move |_| set_confirm_delete.set(None)
+                                >
+                                    "Cancel"
+                                </button>
+                                <button
+                                    class="btn-danger"
+                                    disabled=move || loading.get()
+                                    on:click={
+                                        let image = image.clone();
+                                        move |_| delete_image(image.clone())
+                                    }
+                                >
+                                    {move || if loading.get() { "Deleting..." } else { "Delete" }}
+                                </button>
+                            </div>
+                        </div>
+                    </div>
+                }
+            })}
+
+            // Retag Image
+            {move || retag_image.get().map(|image| {
+                view! {
+                    <div style="position: fixed; top: 0; left: 0; width: 100%; height: 100%; background-color: rgba(0,0,0,0.5); z-index: 2000; display: flex; align-items: center; justify-content: center;">
+                        <div class="container-card" style="width: 500px; max-width: 90vw;">
+                            <h3>{format!("Tag {}:{} as", image.repository, image.tag)}</h3>
+                            <p>"The new tag names the same manifest, so no layers are copied. An existing tag of that name is moved."</p>
+                            <input
+                                type="text"
+                                placeholder="prod"
+                                prop:value=retag_target
+                                on:input=move |ev| set_retag_target.set(event_target_value(&ev))
+                                style="width: 100%;"
+                            />
+                            <div style="display: flex; justify-content: flex-end; gap: 10px; margin-top: 20px;">
+                                <button
+                                    class="btn-primary"
+                                    style="background-color: #555;"
+                                    on:click=move |_| {
+                                        set_retag_image.set(None);
+                                        set_retag_target.set(String::new());
+                                    }
+                                >
+                                    "Cancel"
+                                </button>
+                                <button
+                                    class="btn-success"
+                                    disabled=move || loading.get() || retag_target.get().trim().is_empty()
+                                    on:click={
+                                        let image = image.clone();
+                                        move |_| retag(image.clone())
+                                    }
+                                >
+                                    {move || if loading.get() { "Tagging..." } else { "Tag" }}
+                                </button>
+                            </div>
+                        </div>
+                    </div>
+                }
+            })}
+
+            // Add or Edit Registry Modal
             {move || {
                 if show_add_modal.get() {
                     view! {
                         <div style="position: fixed; top: 0; left: 0; width: 100%; height: 100%; background-color: rgba(0,0,0,0.5); z-index: 2000; display: flex; align-items: center; justify-content: center;">
                             <div class="container-card" style="width: 500px; max-width: 90vw;">
-                                <h3>"Add Registry"</h3>
+                                <h3>{move || if editing.get().is_some() { "Edit Registry" } else { "Add Registry" }}</h3>
 
                                 <div style="margin: 15px 0;">
                                     <label style="display: block; margin-bottom: 5px; font-weight: bold;">"Registry Name"</label>
@@ -504,7 +1125,7 @@ pub fn RegistryManagement() -> impl IntoView {
                                     <label style="display: block; margin-bottom: 5px; font-weight: bold;">"Password (optional)"</label>
                                     <input
                                         type="password"
-                                        placeholder="password"
+                                        placeholder=move || if editing.get().is_some() { "leave blank to keep the current one" } else { "password" }
                                         style="width: 100%; padding: 8px; border: 1px solid #555; border-radius: 4px; background-color: #2c3e50; color: white;"
                                         prop:value=move || registry_password.get()
                                         on:input=move |ev| set_registry_password.set(event_target_value(&ev))
@@ -519,24 +1140,46 @@ pub fn RegistryManagement() -> impl IntoView {
                                             prop:checked=move || registry_insecure.get()
                                             on:change=move |ev| set_registry_insecure.set(event_target_checked(&ev))
                                         />
-                                        "Allow insecure connections (HTTP)"
+                                        "Allow insecure connections (HTTP or self-signed certificates)"
                                     </label>
                                 </div>
 
+                                <div style="margin: 15px 0;">
+                                    <label style="display: block; margin-bottom: 5px; font-weight: bold;">"CA certificate path (optional)"</label>
+                                    <input
+                                        type="text"
+                                        placeholder="/etc/ghostpanel/harbor-ca.pem on the agent's host"
+                                        style="width: 100%; padding: 8px; border: 1px solid #555; border-radius: 4px; background-color: #2c3e50; color: white;"
+                                        prop:value=move || registry_ca_cert_path.get()
+                                        on:input=move |ev| set_registry_ca_cert_path.set(event_target_value(&ev))
+                                    />
+                                </div>
+
+                                {move || test_error.get().map(|error| view! {
+                                    <div style="background-color: #e74c3c; color: white; padding: 8px 12px; border-radius: 4px;">
+                                        {format!("Registry check failed: {}", error)}
+                                    </div>
+                                })}
+
                                 <div style="display: flex; justify-content: flex-end; gap: 10px; margin-top: 20px;">
                                     <button
                                         class="btn-primary"
                                         style="background-color: #555;"
-                                        on:click=move |_| set_show_add_modal.set(false)
+                                        on:click=move |_| close_modal()
                                     >
                                         "Cancel"
                                     </button>
                                     <button
                                         class="btn-primary"
-                                        on:click=add_registry
+                                        on:click=save_registry
                                         disabled=move || loading.get()
                                     >
-                                        {move || if loading.get() { "Adding..." } else { "Add Registry" }}
+                                        {move || match (loading.get(), editing.get().is_some()) {
+                                            (true, true) => "Saving...",
+                                            (true, false) => "Adding...",
+                                            (false, true) => "Save",
+                                            (false, false) => "Add Registry",
+                                        }}
                                     </button>
                                 </div>
                             </div>
@@ -1,13 +1,606 @@
 use leptos::*;
+use crate::services::{api_client, error_message};
+use gpanel_core::{
+    AlertCondition, ChannelListResponse, CreateChannelRequest, NotificationChannel, NotificationChannelKind,
+    NotificationRule, OperationResult, RestoreReport, RuleListResponse, RuleRequest,
+};
+use wasm_bindgen::JsCast;
+
+/// One line describing where a channel sends
+fn channel_summary(kind: &NotificationChannelKind) -> String {
+    match kind {
+        NotificationChannelKind::Email { to, .. } => format!("Email to {}", to.join(", ")),
+        NotificationChannelKind::Webhook { url } => format!("Webhook {}", url),
+        NotificationChannelKind::Discord { .. } => "Discord".to_string(),
+    }
+}
 
 #[component]
 pub fn SettingsPage() -> impl IntoView {
+    let (passphrase, set_passphrase) = create_signal(String::new());
+    let (restore_archive, set_restore_archive) = create_signal(None::<Vec<u8>>);
+    let (restore_report, set_restore_report) = create_signal(None::<RestoreReport>);
+    let (loading, set_loading) = create_signal(false);
+    let (error_message_signal, set_error_message) = create_signal(None::<String>);
+
+    let download_backup = move |_| {
+        spawn_local(async move {
+            set_loading.set(true);
+
+            match api_client::get("/api/v1/system/backup")
+                .header("X-Backup-Passphrase", &passphrase.get_untracked())
+                .send()
+                .await
+            {
+                Ok(response) if response.ok() => match response.binary().await {
+                    Ok(archive) => {
+                        let filename = format!("ghostpanel-backup-{}.gpbk", chrono::Utc::now().format("%Y%m%d-%H%M%S"));
+                        save_file(&archive, &filename);
+                        set_error_message.set(Some("✅ Backup downloaded".to_string()));
+                    }
+                    Err(e) => set_error_message.set(Some(format!("❌ Backup failed: {}", e))),
+                },
+                Ok(response) => {
+                    set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
+                }
+                Err(e) => set_error_message.set(Some(format!("❌ Backup failed: {}", e))),
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let select_archive = move |ev: ev::Event| {
+        set_restore_report.set(None);
+        let input: web_sys::HtmlInputElement = event_target(&ev);
+        let Some(file) = input.files().and_then(|files| files.get(0)) else {
+            set_restore_archive.set(None);
+            return;
+        };
+
+        spawn_local(async move {
+            match wasm_bindgen_futures::JsFuture::from(file.array_buffer()).await {
+                Ok(buffer) => set_restore_archive.set(Some(js_sys::Uint8Array::new(&buffer).to_vec())),
+                Err(_) => set_error_message.set(Some("❌ Could not read backup file".to_string())),
+            }
+        });
+    };
+
+    let run_restore = move |apply: bool| {
+        let Some(archive) = restore_archive.get_untracked() else {
+            return;
+        };
+        let url = match restore_report.get_untracked() {
+            Some(report) if apply => format!(
+                "/api/v1/system/restore?dry_run=false&confirm={}",
+                report.confirmation_token
+            ),
+            _ => "/api/v1/system/restore?dry_run=true".to_string(),
+        };
+
+        spawn_local(async move {
+            set_loading.set(true);
+
+            let request = api_client::post(&url)
+                .header("X-Backup-Passphrase", &passphrase.get_untracked())
+                .header("Content-Type", "application/octet-stream")
+                .body(js_sys::Uint8Array::from(archive.as_slice()))
+                .unwrap();
+
+            match request.send().await {
+                Ok(response) if response.ok() => {
+                    if let Ok(report) = response.json::<RestoreReport>().await {
+                        if report.dry_run {
+                            set_error_message.set(None);
+                            set_restore_report.set(Some(report));
+                        } else {
+                            set_error_message.set(Some(format!(
+                                "✅ Restore applied ({} changes){}",
+                                report.changes.len(),
+                                if report.warnings.is_empty() { String::new() } else { format!(": {}", report.warnings.join("; ")) }
+                            )));
+                            set_restore_report.set(None);
+                        }
+                    }
+                }
+                Ok(response) => {
+                    set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
+                }
+                Err(e) => set_error_message.set(Some(format!("❌ Restore failed: {}", e))),
+            }
+            set_loading.set(false);
+        });
+    };
+
     view! {
         <div class="settings">
             <h2>"Settings"</h2>
+
+            // Error/Success message display
+            {move || {
+                if let Some(message) = error_message_signal.get() {
+                    let is_success = message.starts_with("✅");
+                    view! {
+                        <div
+                            class="message-banner"
+                            style=format!(
+                                "background-color: {}; color: white; padding: 10px; border-radius: 4px; margin-bottom: 20px;",
+                                if is_success { "#27ae60" } else { "#e74c3c" }
+                            )
+                        >
+                            {message}
+                            <button style="float: right; background: none; border: none; color: white; cursor: pointer;"
+                                    on:click=move |_| set_error_message.set(None)>
+                                "×"
+                            </button>
+                        </div>
+                    }.into_view()
+                } else {
+                    view! { <div></div> }.into_view()
+                }
+            }}
+
             <div class="container-card">
-                <p>"Settings will be implemented here"</p>
+                <h3>"Backup & Restore"</h3>
+                <p style="color: #bbb;">
+                    "Backups contain panel settings and registry credentials, encrypted with the passphrase below."
+                </p>
+
+                <div style="margin-bottom: 15px;">
+                    <label style="display: block; margin-bottom: 5px; font-weight: bold;">"Passphrase"</label>
+                    <input
+                        type="password"
+                        placeholder="At least 8 characters"
+                        style="width: 100%; padding: 10px; border: 1px solid #555; border-radius: 4px; background-color: #2c3e50; color: white;"
+                        prop:value=move || passphrase.get()
+                        on:input=move |ev| set_passphrase.set(event_target_value(&ev))
+                    />
+                </div>
+
+                <div style="display: grid; grid-template-columns: 1fr 1fr; gap: 20px;">
+                    <div>
+                        <h4>"Backup"</h4>
+                        <button
+                            class="btn-primary"
+                            on:click=download_backup
+                            disabled=move || loading.get() || passphrase.get().len() < 8
+                        >
+                            "Download backup"
+                        </button>
+                    </div>
+                    <div>
+                        <h4>"Restore"</h4>
+                        <input type="file" accept=".gpbk" on:change=select_archive style="margin-bottom: 10px;"/>
+                        <div>
+                            <button
+                                class="btn-primary"
+                                on:click=move |_| run_restore(false)
+                                disabled=move || loading.get() || passphrase.get().len() < 8 || restore_archive.get().is_none()
+                            >
+                                "Preview restore"
+                            </button>
+                        </div>
+                    </div>
+                </div>
+
+                {move || restore_report.get().map(|report| view! {
+                    <div style="margin-top: 20px; background-color: #34495e; border-radius: 8px; padding: 15px; border: 1px solid #4a5568;">
+                        <h4 style="margin-top: 0;">
+                            {format!("Backup from {} (format v{})", report.created_at.format("%Y-%m-%d %H:%M"), report.format_version)}
+                        </h4>
+                        {if report.changes.is_empty() {
+                            view! { <p style="color: #bbb;">"No changes: the backup matches the current configuration."</p> }.into_view()
+                        } else {
+                            view! {
+                                <ul style="color: #bbb;">
+                                    {report.changes.iter().map(|change| view! {
+                                        <li>{format!("{:?} {}: {}", change.kind, change.section, change.item)}</li>
+                                    }).collect_view()}
+                                </ul>
+                            }.into_view()
+                        }}
+                        {report.warnings.iter().map(|warning| view! {
+                            <p style="color: #f39c12;">{format!("⚠ {}", warning)}</p>
+                        }).collect_view()}
+                        <button
+                            class="btn-danger"
+                            on:click=move |_| run_restore(true)
+                            disabled=move || loading.get()
+                        >
+                            "Apply restore"
+                        </button>
+                    </div>
+                })}
             </div>
+
+            <NotificationSettings set_error_message=set_error_message/>
         </div>
     }
-}
\ No newline at end of file
+}
+
+const INPUT_STYLE: &str = "width: 100%; padding: 10px; border: 1px solid #555; border-radius: 4px; background-color: #2c3e50; color: white;";
+
+/// Notification channels and alert rules
+#[component]
+fn NotificationSettings(set_error_message: WriteSignal<Option<String>>) -> impl IntoView {
+    let (channels, set_channels) = create_signal(Vec::<NotificationChannel>::new());
+    let (rules, set_rules) = create_signal(Vec::<NotificationRule>::new());
+
+    // New channel form
+    let (channel_type, set_channel_type) = create_signal("Discord".to_string());
+    let (channel_name, set_channel_name) = create_signal(String::new());
+    let (channel_url, set_channel_url) = create_signal(String::new());
+    let (smtp_host, set_smtp_host) = create_signal(String::new());
+    let (smtp_port, set_smtp_port) = create_signal("587".to_string());
+    let (smtp_username, set_smtp_username) = create_signal(String::new());
+    let (smtp_password, set_smtp_password) = create_signal(String::new());
+    let (email_from, set_email_from) = create_signal(String::new());
+    let (email_to, set_email_to) = create_signal(String::new());
+    let (starttls, set_starttls) = create_signal(true);
+
+    // New rule form
+    let (rule_name, set_rule_name) = create_signal(String::new());
+    let (rule_condition, set_rule_condition) = create_signal("DiskUsageAbove".to_string());
+    let (rule_value, set_rule_value) = create_signal("90".to_string());
+    let (rule_channels, set_rule_channels) = create_signal(Vec::<String>::new());
+    let (rule_cooldown_minutes, set_rule_cooldown_minutes) = create_signal("60".to_string());
+
+    create_effect(move |_| {
+        spawn_local(async move {
+            load_notification_settings(set_channels, set_rules, set_error_message).await;
+        });
+    });
+
+    let create_channel = move |_| {
+        let optional = |value: String| Some(value).filter(|v| !v.is_empty());
+        let kind = match channel_type.get().as_str() {
+            "Email" => NotificationChannelKind::Email {
+                smtp_host: smtp_host.get(),
+                smtp_port: smtp_port.get().parse().unwrap_or(587),
+                username: optional(smtp_username.get()),
+                password: optional(smtp_password.get()),
+                from: email_from.get(),
+                to: email_to
+                    .get()
+                    .split(',')
+                    .map(|address| address.trim().to_string())
+                    .filter(|address| !address.is_empty())
+                    .collect(),
+                starttls: starttls.get(),
+            },
+            "Webhook" => NotificationChannelKind::Webhook { url: channel_url.get() },
+            _ => NotificationChannelKind::Discord { url: channel_url.get() },
+        };
+        let request = CreateChannelRequest { name: channel_name.get(), kind, enabled: Some(true) };
+
+        spawn_local(async move {
+            let response = match api_client::post("/api/v1/notifications/channels").json(&request) {
+                Ok(request) => request.send().await,
+                Err(e) => {
+                    set_error_message.set(Some(format!("❌ Failed to create channel: {}", e)));
+                    return;
+                }
+            };
+            match response {
+                Ok(response) if response.ok() => {
+                    set_error_message.set(Some(format!("✅ Channel {} created", request.name)));
+                    set_channel_name.set(String::new());
+                    set_channel_url.set(String::new());
+                    set_smtp_password.set(String::new());
+                    load_notification_settings(set_channels, set_rules, set_error_message).await;
+                }
+                Ok(response) => {
+                    set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
+                }
+                Err(e) => set_error_message.set(Some(format!("❌ Failed to create channel: {}", e))),
+            }
+        });
+    };
+
+    let test_channel = move |id: String| {
+        spawn_local(async move {
+            let url = format!("/api/v1/notifications/channels/{}/test", id);
+            match api_client::post(&url).send().await {
+                Ok(response) if !response.ok() => {
+                    set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
+                }
+                Ok(response) => match response.json::<OperationResult>().await {
+                    Ok(result) => set_error_message.set(Some(format!("✅ {}", result.message))),
+                    Err(_) => set_error_message.set(Some(format!("❌ Test failed with status {}", response.status()))),
+                },
+                Err(e) => set_error_message.set(Some(format!("❌ Test failed: {}", e))),
+            }
+        });
+    };
+
+    let delete_channel = move |id: String| {
+        spawn_local(async move {
+            let url = format!("/api/v1/notifications/channels/{}", id);
+            match api_client::delete(&url).send().await {
+                Ok(response) if response.ok() => {
+                    load_notification_settings(set_channels, set_rules, set_error_message).await;
+                }
+                Ok(_) => set_error_message.set(Some("❌ Failed to delete channel".to_string())),
+                Err(e) => set_error_message.set(Some(format!("❌ Failed to delete channel: {}", e))),
+            }
+        });
+    };
+
+    let create_rule = move |_| {
+        let value = rule_value.get();
+        let condition = match rule_condition.get().as_str() {
+            "BoltUnreachable" => AlertCondition::BoltUnreachable,
+            "ContainerCrashLoop" => AlertCondition::ContainerCrashLoop {
+                container: Some(value.trim().to_string()).filter(|c| !c.is_empty()),
+            },
+            "CertificateExpiring" => AlertCondition::CertificateExpiring { days: value.parse().unwrap_or(14) },
+            _ => AlertCondition::DiskUsageAbove { percent: value.parse().unwrap_or(90.0) },
+        };
+        let request = RuleRequest {
+            name: rule_name.get(),
+            condition,
+            channels: rule_channels.get(),
+            cooldown_secs: rule_cooldown_minutes.get().parse::<u64>().ok().map(|minutes| minutes * 60),
+            enabled: Some(true),
+        };
+
+        spawn_local(async move {
+            let response = match api_client::post("/api/v1/notifications/rules").json(&request) {
+                Ok(request) => request.send().await,
+                Err(e) => {
+                    set_error_message.set(Some(format!("❌ Failed to create rule: {}", e)));
+                    return;
+                }
+            };
+            match response {
+                Ok(response) if response.ok() => {
+                    set_error_message.set(Some(format!("✅ Rule {} created", request.name)));
+                    set_rule_name.set(String::new());
+                    load_notification_settings(set_channels, set_rules, set_error_message).await;
+                }
+                Ok(response) => {
+                    set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
+                }
+                Err(e) => set_error_message.set(Some(format!("❌ Failed to create rule: {}", e))),
+            }
+        });
+    };
+
+    let delete_rule = move |id: String| {
+        spawn_local(async move {
+            let url = format!("/api/v1/notifications/rules/{}", id);
+            match api_client::delete(&url).send().await {
+                Ok(response) if response.ok() => {
+                    load_notification_settings(set_channels, set_rules, set_error_message).await;
+                }
+                Ok(_) => set_error_message.set(Some("❌ Failed to delete rule".to_string())),
+                Err(e) => set_error_message.set(Some(format!("❌ Failed to delete rule: {}", e))),
+            }
+        });
+    };
+
+    let channel_name_for = move |id: &str| {
+        channels
+            .get()
+            .into_iter()
+            .find(|c| c.id == id)
+            .map(|c| c.name)
+            .unwrap_or_else(|| id.to_string())
+    };
+
+    view! {
+        <div class="container-card" style="margin-top: 20px;">
+            <h3>"Notifications"</h3>
+            <p style="color: #bbb;">
+                "Alerts for lost Bolt contact, crash-looping containers, full disks and expiring certificates. Each rule waits for its cooldown before notifying again."
+            </p>
+
+            <h4>"Channels"</h4>
+            <For
+                each=move || channels.get()
+                key=|channel| channel.id.clone()
+                children=move |channel| {
+                    let id_for_test = channel.id.clone();
+                    let id_for_delete = channel.id.clone();
+                    view! {
+                        <div style="display: flex; justify-content: space-between; align-items: center; padding: 8px 0; border-bottom: 1px solid #34495e;">
+                            <div>
+                                <strong>{&channel.name}</strong>
+                                <span style="color: #bbb; margin-left: 10px;">{channel_summary(&channel.kind)}</span>
+                            </div>
+                            <div style="display: flex; gap: 5px;">
+                                <button class="btn-primary" style="padding: 4px 10px;" on:click=move |_| test_channel(id_for_test.clone())>
+                                    "Send test"
+                                </button>
+                                <button class="btn-danger" style="padding: 4px 10px;" on:click=move |_| delete_channel(id_for_delete.clone())>
+                                    "Delete"
+                                </button>
+                            </div>
+                        </div>
+                    }
+                }
+            />
+
+            <div style="display: grid; grid-template-columns: 1fr 1fr; gap: 10px; margin: 15px 0;">
+                <select style=INPUT_STYLE on:change=move |ev| set_channel_type.set(event_target_value(&ev))>
+                    <option value="Discord" selected=true>"Discord"</option>
+                    <option value="Webhook">"Webhook"</option>
+                    <option value="Email">"Email (SMTP)"</option>
+                </select>
+                <input
+                    type="text"
+                    placeholder="Channel name"
+                    style=INPUT_STYLE
+                    prop:value=move || channel_name.get()
+                    on:input=move |ev| set_channel_name.set(event_target_value(&ev))
+                />
+                {move || if channel_type.get() == "Email" {
+                    view! {
+                        <input type="text" placeholder="SMTP host" style=INPUT_STYLE
+                            prop:value=move || smtp_host.get()
+                            on:input=move |ev| set_smtp_host.set(event_target_value(&ev))/>
+                        <input type="number" placeholder="Port" style=INPUT_STYLE
+                            prop:value=move || smtp_port.get()
+                            on:input=move |ev| set_smtp_port.set(event_target_value(&ev))/>
+                        <input type="text" placeholder="Username (optional)" style=INPUT_STYLE
+                            prop:value=move || smtp_username.get()
+                            on:input=move |ev| set_smtp_username.set(event_target_value(&ev))/>
+                        <input type="password" placeholder="Password (optional)" style=INPUT_STYLE
+                            prop:value=move || smtp_password.get()
+                            on:input=move |ev| set_smtp_password.set(event_target_value(&ev))/>
+                        <input type="email" placeholder="From address" style=INPUT_STYLE
+                            prop:value=move || email_from.get()
+                            on:input=move |ev| set_email_from.set(event_target_value(&ev))/>
+                        <input type="text" placeholder="To addresses, comma separated" style=INPUT_STYLE
+                            prop:value=move || email_to.get()
+                            on:input=move |ev| set_email_to.set(event_target_value(&ev))/>
+                        <label>
+                            <input type="checkbox"
+                                prop:checked=move || starttls.get()
+                                on:change=move |ev| set_starttls.set(event_target_checked(&ev))/>
+                            " Use STARTTLS"
+                        </label>
+                    }.into_view()
+                } else {
+                    view! {
+                        <input type="url" placeholder="Webhook URL" style=INPUT_STYLE
+                            prop:value=move || channel_url.get()
+                            on:input=move |ev| set_channel_url.set(event_target_value(&ev))/>
+                    }.into_view()
+                }}
+            </div>
+            <button class="btn-primary" on:click=create_channel disabled=move || channel_name.get().trim().is_empty()>
+                "Add channel"
+            </button>
+
+            <h4 style="margin-top: 25px;">"Rules"</h4>
+            <For
+                each=move || rules.get()
+                key=|rule| rule.id.clone()
+                children=move |rule| {
+                    let id_for_delete = rule.id.clone();
+                    let channel_names = rule.channels.iter().map(|id| channel_name_for(id)).collect::<Vec<_>>().join(", ");
+                    view! {
+                        <div style="display: flex; justify-content: space-between; align-items: center; padding: 8px 0; border-bottom: 1px solid #34495e;">
+                            <div>
+                                <strong>{&rule.name}</strong>
+                                <span style="color: #bbb; margin-left: 10px;">
+                                    {format!("{} → {} (cooldown {}m)", rule.condition, channel_names, rule.cooldown_secs / 60)}
+                                </span>
+                            </div>
+                            <button class="btn-danger" style="padding: 4px 10px;" on:click=move |_| delete_rule(id_for_delete.clone())>
+                                "Delete"
+                            </button>
+                        </div>
+                    }
+                }
+            />
+
+            <div style="display: grid; grid-template-columns: 1fr 1fr; gap: 10px; margin: 15px 0;">
+                <input
+                    type="text"
+                    placeholder="Rule name"
+                    style=INPUT_STYLE
+                    prop:value=move || rule_name.get()
+                    on:input=move |ev| set_rule_name.set(event_target_value(&ev))
+                />
+                <select style=INPUT_STYLE on:change=move |ev| set_rule_condition.set(event_target_value(&ev))>
+                    <option value="DiskUsageAbove" selected=true>"Disk usage above (%)"</option>
+                    <option value="ContainerCrashLoop">"Container crash loop (name, blank for any)"</option>
+                    <option value="CertificateExpiring">"Certificate expiring within (days)"</option>
+                    <option value="BoltUnreachable">"Bolt unreachable"</option>
+                </select>
+                <input
+                    type="text"
+                    placeholder="Threshold"
+                    style=INPUT_STYLE
+                    disabled=move || rule_condition.get() == "BoltUnreachable"
+                    prop:value=move || rule_value.get()
+                    on:input=move |ev| set_rule_value.set(event_target_value(&ev))
+                />
+                <input
+                    type="number"
+                    placeholder="Cooldown (minutes)"
+                    style=INPUT_STYLE
+                    prop:value=move || rule_cooldown_minutes.get()
+                    on:input=move |ev| set_rule_cooldown_minutes.set(event_target_value(&ev))
+                />
+            </div>
+            <div style="display: flex; gap: 15px; flex-wrap: wrap; margin-bottom: 15px;">
+                {move || channels.get().into_iter().map(|channel| {
+                    let id = channel.id.clone();
+                    let id_for_checked = channel.id.clone();
+                    view! {
+                        <label>
+                            <input
+                                type="checkbox"
+                                prop:checked=move || rule_channels.get().contains(&id_for_checked)
+                                on:change=move |ev| {
+                                    let checked = event_target_checked(&ev);
+                                    set_rule_channels.update(|selected| {
+                                        selected.retain(|c| c != &id);
+                                        if checked {
+                                            selected.push(id.clone());
+                                        }
+                                    });
+                                }
+                            />
+                            " " {channel.name}
+                        </label>
+                    }
+                }).collect_view()}
+            </div>
+            <button
+                class="btn-primary"
+                on:click=create_rule
+                disabled=move || rule_name.get().trim().is_empty() || rule_channels.get().is_empty()
+            >
+                "Add rule"
+            </button>
+        </div>
+    }
+}
+
+/// Load notification channels and rules from the agent
+async fn load_notification_settings(
+    set_channels: WriteSignal<Vec<NotificationChannel>>,
+    set_rules: WriteSignal<Vec<NotificationRule>>,
+    set_error_message: WriteSignal<Option<String>>,
+) {
+    match api_client::get("/api/v1/notifications/channels").send().await {
+        Ok(response) => {
+            if let Ok(channel_list) = response.json::<ChannelListResponse>().await {
+                set_channels.set(channel_list.channels);
+            }
+        }
+        Err(e) => {
+            set_error_message.set(Some(format!("Failed to load notification channels: {}", e)));
+            return;
+        }
+    }
+
+    if let Ok(response) = api_client::get("/api/v1/notifications/rules").send().await {
+        if let Ok(rule_list) = response.json::<RuleListResponse>().await {
+            set_rules.set(rule_list.rules);
+        }
+    }
+}
+
+/// Offer bytes to the browser as a file download
+fn save_file(data: &[u8], filename: &str) {
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(data));
+    let Ok(blob) = web_sys::Blob::new_with_u8_array_sequence(&parts) else {
+        return;
+    };
+    let Ok(url) = web_sys::Url::create_object_url_with_blob(&blob) else {
+        return;
+    };
+
+    if let Ok(anchor) = document().create_element("a") {
+        if let Ok(anchor) = anchor.dyn_into::<web_sys::HtmlAnchorElement>() {
+            anchor.set_href(&url);
+            anchor.set_download(filename);
+            anchor.click();
+        }
+    }
+    let _ = web_sys::Url::revoke_object_url(&url);
+}
@@ -1,13 +1,532 @@
 use leptos::*;
+use leptos_router::*;
+use crate::auth::AuthContext;
+use crate::services::{api_client, error_message};
+use gpanel_core::{
+    CreateVolumeRequest, OperationResult, Role, Volume, VolumeBrowseResponse, VolumeEntry, VolumeEntryType,
+    VolumeListResponse, VolumePruneReport, VolumePruneRequest,
+};
+
+/// Format file size in human readable format
+fn format_size(size: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+    let mut size = size as f64;
+    let mut unit_index = 0;
+
+    while size >= 1024.0 && unit_index < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit_index += 1;
+    }
+
+    format!("{:.1} {}", size, UNITS[unit_index])
+}
 
 #[component]
 pub fn VolumeList() -> impl IntoView {
+    let auth_context = use_context::<AuthContext>().expect("AuthContext must be provided");
+    let (volumes, set_volumes) = create_signal(Vec::<Volume>::new());
+    let (loading, set_loading) = create_signal(false);
+    let (error_message_signal, set_error_message) = create_signal(None::<String>);
+    let (new_volume_name, set_new_volume_name) = create_signal(String::new());
+    let (show_prune_confirm, set_show_prune_confirm) = create_signal(false);
+
+    let refresh_volumes = move || {
+        spawn_local(async move {
+            load_volumes(set_volumes, set_error_message).await;
+        });
+    };
+
+    // Load volumes on mount
+    create_effect(move |_| {
+        refresh_volumes();
+    });
+
+    let create_volume = move || {
+        let name = new_volume_name.get_untracked().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+
+        spawn_local(async move {
+            set_loading.set(true);
+
+            let request = CreateVolumeRequest { name: name.clone(), ..Default::default() };
+
+            match api_client::post("/api/v1/volumes")
+                .json(&request)
+                .unwrap()
+                .send()
+                .await
+            {
+                Ok(response) if response.ok() => {
+                    set_new_volume_name.set(String::new());
+                    set_error_message.set(Some(format!("✅ Volume {} created", name)));
+                    load_volumes(set_volumes, set_error_message).await;
+                }
+                Ok(response) => {
+                    set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
+                }
+                Err(e) => {
+                    set_error_message.set(Some(format!("❌ Create failed: {}", e)));
+                }
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let delete_volume = move |name: String| {
+        spawn_local(async move {
+            set_loading.set(true);
+
+            let url = format!("/api/v1/volumes/{}", urlencoding::encode(&name));
+
+            match api_client::delete(&url).send().await {
+                Ok(response) if !response.ok() => {
+                    set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
+                }
+                Ok(response) => match response.json::<OperationResult>().await {
+                    Ok(result) => {
+                        set_error_message.set(Some(format!("✅ {}", result.message)));
+                        load_volumes(set_volumes, set_error_message).await;
+                    }
+                    Err(_) => {
+                        set_error_message.set(Some(format!("❌ Request failed with status {}", response.status())));
+                    }
+                },
+                Err(e) => {
+                    set_error_message.set(Some(format!("❌ Delete failed: {}", e)));
+                }
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let prune_volumes = move || {
+        set_show_prune_confirm.set(false);
+        spawn_local(async move {
+            set_loading.set(true);
+
+            let request = VolumePruneRequest { label_filter: None };
+
+            match api_client::post("/api/v1/volumes/prune")
+                .json(&request)
+                .unwrap()
+                .send()
+                .await
+            {
+                Ok(response) if response.ok() => {
+                    if let Ok(report) = response.json::<VolumePruneReport>().await {
+                        set_error_message.set(Some(format!(
+                            "✅ Pruned {} volumes, reclaimed {}",
+                            report.deleted.len(),
+                            format_size(report.reclaimed_bytes)
+                        )));
+                        load_volumes(set_volumes, set_error_message).await;
+                    }
+                }
+                Ok(response) => {
+                    set_error_message.set(Some(format!("❌ Prune failed: HTTP {}", response.status())));
+                }
+                Err(e) => {
+                    set_error_message.set(Some(format!("❌ Prune failed: {}", e)));
+                }
+            }
+            set_loading.set(false);
+        });
+    };
+
     view! {
         <div class="volume-list">
-            <h2>"Volumes"</h2>
+            <div class="header-section">
+                <h2>"Volumes"</h2>
+                <p>"Persistent storage for containers and game saves"</p>
+            </div>
+
+            // Error/Success message display
+            {move || {
+                if let Some(message) = error_message_signal.get() {
+                    let is_success = message.starts_with("✅");
+                    view! {
+                        <div
+                            class="message-banner"
+                            style=format!(
+                                "background-color: {}; color: white; padding: 10px; border-radius: 4px; margin-bottom: 20px;",
+                                if is_success { "#27ae60" } else { "#e74c3c" }
+                            )
+                        >
+                            {message}
+                            <button style="float: right; background: none; border: none; color: white; cursor: pointer;"
+                                    on:click=move |_| set_error_message.set(None)>
+                                "×"
+                            </button>
+                        </div>
+                    }.into_view()
+                } else {
+                    view! { <div></div> }.into_view()
+                }
+            }}
+
+            <div class="container-card" style="margin-bottom: 20px;">
+                <h3>"Create Volume"</h3>
+                <div style="display: grid; grid-template-columns: 1fr auto; gap: 15px; align-items: end;">
+                    <input
+                        type="text"
+                        placeholder="minecraft_world"
+                        style="width: 100%; padding: 10px; border: 1px solid #555; border-radius: 4px; background-color: #2c3e50; color: white;"
+                        prop:value=move || new_volume_name.get()
+                        on:input=move |ev| set_new_volume_name.set(event_target_value(&ev))
+                        on:keydown=move |ev| {
+                            if ev.key() == "Enter" {
+                                create_volume();
+                            }
+                        }
+                    />
+                    <button
+                        class="btn-primary"
+                        style="padding: 10px 20px;"
+                        on:click=move |_| create_volume()
+                        disabled=move || loading.get() || new_volume_name.get().trim().is_empty()
+                    >
+                        "Create"
+                    </button>
+                </div>
+            </div>
+
+            <div class="container-card">
+                <div style="display: flex; justify-content: space-between; align-items: center;">
+                    <h3>"Volumes"</h3>
+                    <div style="display: flex; gap: 15px; align-items: center;">
+                        <span style="color: #bbb;">
+                            {move || {
+                                let volumes = volumes.get();
+                                let total: u64 = volumes.iter().filter_map(|v| v.size).sum();
+                                format!("{} volumes, {}", volumes.len(), format_size(total))
+                            }}
+                        </span>
+                        <button class="btn-primary" on:click=move |_| refresh_volumes()>
+                            "Refresh"
+                        </button>
+                        <button
+                            class="btn-danger"
+                            on:click=move |_| set_show_prune_confirm.set(true)
+                            disabled=move || loading.get() || !auth_context.has_role(Role::Operator)
+                        >
+                            "Prune unused"
+                        </button>
+                    </div>
+                </div>
+
+                <div style="display: grid; gap: 10px; margin-top: 15px;">
+                    <For
+                        each=move || volumes.get()
+                        key=|volume| volume.name.clone()
+                        children=move |volume| {
+                            let name_for_delete = volume.name.clone();
+                            let details_href = format!("/volumes/{}", volume.name);
+                            let in_use = !volume.containers.is_empty();
+
+                            view! {
+                                <div style="background-color: #34495e; border-radius: 8px; padding: 15px; border: 1px solid #4a5568; display: grid; grid-template-columns: 1fr auto; gap: 20px; align-items: center;">
+                                    <div>
+                                        <div style="display: flex; align-items: center; gap: 10px;">
+                                            <A href=details_href>
+                                                <h4 style="margin: 0; color: #3498db;">{&volume.name}</h4>
+                                            </A>
+                                            {in_use.then(|| view! {
+                                                <span style="background-color: #27ae60; padding: 2px 6px; border-radius: 3px; font-size: 10px;">"IN USE"</span>
+                                            })}
+                                        </div>
+                                        <div style="display: grid; grid-template-columns: repeat(auto-fit, minmax(150px, 1fr)); gap: 10px; font-size: 14px; color: #bbb; margin-top: 8px;">
+                                            <div><strong>"Driver: "</strong> {&volume.driver}</div>
+                                            <div><strong>"Size: "</strong> {volume.size.map(format_size).unwrap_or_else(|| "unknown".to_string())}</div>
+                                            <div><strong>"Created: "</strong> {volume.created.format("%Y-%m-%d").to_string()}</div>
+                                        </div>
+                                        <div style="margin-top: 8px; font-size: 14px; color: #bbb;">
+                                            <strong>"Used by: "</strong>
+                                            {if volume.containers.is_empty() {
+                                                view! { <span>"no containers"</span> }.into_view()
+                                            } else {
+                                                volume
+                                                    .containers
+                                                    .iter()
+                                                    .map(|id| view! {
+                                                        <A href=format!("/containers/{}", id)>
+                                                            <code style="margin-right: 6px;">{id.clone()}</code>
+                                                        </A>
+                                                    })
+                                                    .collect_view()
+                                            }}
+                                        </div>
+                                        <div style="margin-top: 8px; font-size: 12px; color: #888;">
+                                            <code style="background-color: #1a1a1a; padding: 2px 4px; border-radius: 2px;">{&volume.mountpoint}</code>
+                                        </div>
+                                    </div>
+                                    <button
+                                        class="btn-danger"
+                                        style="padding: 8px 16px;"
+                                        title=if in_use { "Volume is mounted by a container" } else { "" }
+                                        on:click=move |_| delete_volume(name_for_delete.clone())
+                                        disabled=move || in_use || loading.get()
+                                    >
+                                        "Delete"
+                                    </button>
+                                </div>
+                            }
+                        }
+                    />
+                </div>
+            </div>
+
+            // Prune confirmation modal
+            {move || {
+                if show_prune_confirm.get() {
+                    view! {
+                        <div style="position: fixed; top: 0; left: 0; width: 100%; height: 100%; background-color: rgba(0,0,0,0.5); z-index: 2000; display: flex; align-items: center; justify-content: center;">
+                            <div class="container-card" style="width: 90%; max-width: 480px;">
+                                <h3 style="margin-top: 0;">"Prune volumes"</h3>
+                                <p style="color: #bbb;">
+                                    "This permanently deletes every volume not mounted by a container, including its data."
+                                </p>
+                                <div style="display: flex; justify-content: flex-end; gap: 10px;">
+                                    <button
+                                        class="btn-primary"
+                                        style="background: #4a5568;"
+                                        on:click=move |_| set_show_prune_confirm.set(false)
+                                    >
+                                        "Cancel"
+                                    </button>
+                                    <button class="btn-danger" on:click=move |_| prune_volumes()>
+                                        "Prune"
+                                    </button>
+                                </div>
+                            </div>
+                        </div>
+                    }.into_view()
+                } else {
+                    view! { <div></div> }.into_view()
+                }
+            }}
+        </div>
+    }
+}
+
+#[component]
+pub fn VolumeDetails() -> impl IntoView {
+    let params = use_params_map();
+    let volume_name = move || params.with(|p| p.get("name").cloned().unwrap_or_default());
+
+    let (volume, set_volume) = create_signal(None::<Volume>);
+    let (current_path, set_current_path) = create_signal("/".to_string());
+    let (entries, set_entries) = create_signal(Vec::<VolumeEntry>::new());
+    let (error_message_signal, set_error_message) = create_signal(None::<String>);
+
+    // Load volume details when the route changes
+    create_effect(move |_| {
+        let name = volume_name();
+        set_current_path.set("/".to_string());
+        spawn_local(async move {
+            let url = format!("/api/v1/volumes/{}", urlencoding::encode(&name));
+            match api_client::get(&url).send().await {
+                Ok(response) if response.ok() => {
+                    if let Ok(details) = response.json::<Volume>().await {
+                        set_volume.set(Some(details));
+                    }
+                }
+                Ok(response) => {
+                    set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
+                }
+                Err(e) => {
+                    set_error_message.set(Some(format!("❌ Failed to load volume: {}", e)));
+                }
+            }
+        });
+    });
+
+    // Reload the listing whenever the browsed directory changes
+    create_effect(move |_| {
+        let name = volume_name();
+        let path = current_path.get();
+        spawn_local(async move {
+            load_volume_directory(name, path, set_entries, set_error_message).await;
+        });
+    });
+
+    let go_up = move |_| {
+        let path = current_path.get();
+        let parent = match path.trim_end_matches('/').rsplit_once('/') {
+            Some(("", _)) | None => "/".to_string(),
+            Some((parent, _)) => parent.to_string(),
+        };
+        set_current_path.set(parent);
+    };
+
+    view! {
+        <div class="volume-details">
+            <div class="header-section">
+                <h2>"Volume: " {volume_name}</h2>
+                <A href="/volumes">"← Back to volumes"</A>
+            </div>
+
+            {move || error_message_signal.get().map(|message| view! {
+                <div
+                    class="message-banner"
+                    style="background-color: #e74c3c; color: white; padding: 10px; border-radius: 4px; margin-bottom: 20px;"
+                >
+                    {message}
+                    <button style="float: right; background: none; border: none; color: white; cursor: pointer;"
+                            on:click=move |_| set_error_message.set(None)>
+                        "×"
+                    </button>
+                </div>
+            })}
+
+            {move || volume.get().map(|volume| view! {
+                <div class="container-card" style="margin-bottom: 20px;">
+                    <div style="display: grid; grid-template-columns: repeat(auto-fit, minmax(150px, 1fr)); gap: 10px; font-size: 14px; color: #bbb;">
+                        <div><strong>"Driver: "</strong> {&volume.driver}</div>
+                        <div><strong>"Size: "</strong> {volume.size.map(format_size).unwrap_or_else(|| "unknown".to_string())}</div>
+                        <div><strong>"Created: "</strong> {volume.created.format("%Y-%m-%d %H:%M").to_string()}</div>
+                        <div>
+                            <strong>"Containers: "</strong>
+                            {if volume.containers.is_empty() { "none".to_string() } else { volume.containers.join(", ") }}
+                        </div>
+                    </div>
+                    <div style="margin-top: 8px; font-size: 12px; color: #888;">
+                        <code style="background-color: #1a1a1a; padding: 2px 4px; border-radius: 2px;">{&volume.mountpoint}</code>
+                    </div>
+                </div>
+            })}
+
+            // File browser
             <div class="container-card">
-                <p>"Volume list will be implemented here"</p>
+                <div style="display: flex; justify-content: space-between; align-items: center;">
+                    <h3>"Files"</h3>
+                    <div style="display: flex; gap: 10px; align-items: center;">
+                        <code style="background-color: #1a1a1a; padding: 4px 8px; border-radius: 2px;">{move || current_path.get()}</code>
+                        <button
+                            class="btn-primary"
+                            on:click=go_up
+                            disabled=move || current_path.get() == "/"
+                        >
+                            "Up"
+                        </button>
+                    </div>
+                </div>
+
+                <table style="width: 100%; margin-top: 15px; border-collapse: collapse; font-size: 14px;">
+                    <thead>
+                        <tr style="text-align: left; color: #bbb; border-bottom: 1px solid #4a5568;">
+                            <th style="padding: 8px;">"Name"</th>
+                            <th style="padding: 8px;">"Size"</th>
+                            <th style="padding: 8px;">"Modified"</th>
+                            <th style="padding: 8px;">"Mode"</th>
+                            <th style="padding: 8px;"></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <For
+                            each=move || entries.get()
+                            key=|entry| entry.path.clone()
+                            children=move |entry| {
+                                let is_dir = entry.entry_type == VolumeEntryType::Directory;
+                                let path_for_open = entry.path.clone();
+                                let download_url = api_client::authorized_url(&format!(
+                                    "/api/v1/volumes/{}/file?path={}",
+                                    urlencoding::encode(&volume_name()),
+                                    urlencoding::encode(&entry.path)
+                                ));
+
+                                view! {
+                                    <tr style="border-bottom: 1px solid #34495e;">
+                                        <td style="padding: 8px;">
+                                            {if is_dir {
+                                                view! {
+                                                    <a
+                                                        href="#"
+                                                        style="color: #3498db;"
+                                                        on:click=move |ev| {
+                                                            ev.prevent_default();
+                                                            set_current_path.set(path_for_open.clone());
+                                                        }
+                                                    >
+                                                        "📁 " {&entry.name}
+                                                    </a>
+                                                }.into_view()
+                                            } else {
+                                                view! { <span>"📄 " {&entry.name}</span> }.into_view()
+                                            }}
+                                        </td>
+                                        <td style="padding: 8px; color: #bbb;">
+                                            {if is_dir { "-".to_string() } else { format_size(entry.size) }}
+                                        </td>
+                                        <td style="padding: 8px; color: #bbb;">{entry.modified.format("%Y-%m-%d %H:%M").to_string()}</td>
+                                        <td style="padding: 8px; color: #bbb;"><code>{format!("{:o}", entry.mode)}</code></td>
+                                        <td style="padding: 8px; text-align: right;">
+                                            {(!is_dir).then(|| view! {
+                                                <a href=download_url class="btn-primary" style="padding: 4px 10px;" download="">
+                                                    "Download"
+                                                </a>
+                                            })}
+                                        </td>
+                                    </tr>
+                                }
+                            }
+                        />
+                    </tbody>
+                </table>
             </div>
         </div>
     }
-}
\ No newline at end of file
+}
+
+/// Load volumes from the agent
+async fn load_volumes(
+    set_volumes: WriteSignal<Vec<Volume>>,
+    set_error_message: WriteSignal<Option<String>>,
+) {
+    match api_client::get("/api/v1/volumes")
+        .send()
+        .await
+    {
+        Ok(response) => {
+            if let Ok(volume_list) = response.json::<VolumeListResponse>().await {
+                set_volumes.set(volume_list.volumes);
+            } else {
+                set_error_message.set(Some("Failed to parse volumes".to_string()));
+            }
+        }
+        Err(e) => {
+            set_error_message.set(Some(format!("Failed to load volumes: {}", e)));
+        }
+    }
+}
+
+/// Load a directory listing from a volume
+async fn load_volume_directory(
+    name: String,
+    path: String,
+    set_entries: WriteSignal<Vec<VolumeEntry>>,
+    set_error_message: WriteSignal<Option<String>>,
+) {
+    let url = format!(
+        "/api/v1/volumes/{}/browse?path={}",
+        urlencoding::encode(&name),
+        urlencoding::encode(&path)
+    );
+
+    match api_client::get(&url).send().await {
+        Ok(response) if response.ok() => {
+            if let Ok(listing) = response.json::<VolumeBrowseResponse>().await {
+                set_entries.set(listing.entries);
+            } else {
+                set_error_message.set(Some("Failed to parse directory listing".to_string()));
+            }
+        }
+        Ok(response) => {
+            set_error_message.set(Some(format!("❌ {}", error_message(&response).await)));
+        }
+        Err(e) => {
+            set_error_message.set(Some(format!("❌ Failed to browse volume: {}", e)));
+        }
+    }
+}
@@ -0,0 +1,145 @@
+// Agent HTTP client: every request goes to `API_BASE`, carries the session token and the
+// chosen environment, and ends the session when the agent answers 401.
+
+use gloo_net::http::{Request, RequestBuilder, Response};
+use gloo_storage::{LocalStorage, Storage};
+use gpanel_core::ENVIRONMENT_HEADER;
+use serde::Serialize;
+use std::cell::Cell;
+use wasm_bindgen::JsValue;
+
+use crate::auth::AuthContext;
+
+/// Where the agent API is served
+pub const API_BASE: &str = "http://localhost:8000";
+
+/// localStorage key holding the environment chosen in the header
+const ENVIRONMENT_STORAGE_KEY: &str = "gpanel.environment";
+
+thread_local! {
+    /// The app's `AuthContext`, registered by `AuthProvider`
+    static AUTH: Cell<Option<AuthContext>> = const { Cell::new(None) };
+}
+
+/// Make requests built here carry the token held by `auth`, and clear it on a 401
+pub fn use_auth(auth: AuthContext) {
+    AUTH.with(|cell| cell.set(Some(auth)));
+}
+
+fn auth_token() -> Option<String> {
+    AUTH.with(|cell| cell.get()).and_then(|auth| auth.token.get_untracked())
+}
+
+/// Environment requests are made for; `None` leaves the choice to the agent's default
+pub fn environment() -> Option<String> {
+    LocalStorage::get::<String>(ENVIRONMENT_STORAGE_KEY).ok()
+}
+
+pub fn set_environment(name: Option<&str>) {
+    match name {
+        Some(name) => {
+            let _ = LocalStorage::set(ENVIRONMENT_STORAGE_KEY, name);
+        }
+        None => LocalStorage::delete(ENVIRONMENT_STORAGE_KEY),
+    }
+}
+
+/// Absolute URL of an agent path such as `/api/v1/containers`
+pub fn url(path: &str) -> String {
+    format!("{}{}", API_BASE, path)
+}
+
+/// URL of `path` with the token and environment as query parameters, for EventSource
+/// and download links, which cannot send headers
+pub fn authorized_url(path: &str) -> String {
+    let mut url = url(path);
+    let parameters = [("access_token", auth_token()), ("env", environment())];
+    for (name, value) in parameters {
+        if let Some(value) = value {
+            let separator = if url.contains('?') { '&' } else { '?' };
+            url = format!("{}{}{}={}", url, separator, name, urlencoding::encode(&value));
+        }
+    }
+    url
+}
+
+/// `authorized_url` with the scheme switched to ws:// or wss://
+pub fn websocket_url(path: &str) -> String {
+    let url = authorized_url(path);
+    match url.strip_prefix("http") {
+        Some(rest) => format!("ws{}", rest),
+        None => url,
+    }
+}
+
+/// Request builder that adds the session token; mirrors the parts of gloo-net's used here
+pub struct ApiRequestBuilder(RequestBuilder);
+
+/// Request with a body, ready to send
+pub struct ApiRequest(Request);
+
+fn build(mut request: RequestBuilder) -> ApiRequestBuilder {
+    if let Some(token) = auth_token() {
+        request = request.header("Authorization", &format!("Bearer {}", token));
+    }
+    if let Some(environment) = environment() {
+        request = request.header(ENVIRONMENT_HEADER, &environment);
+    }
+    ApiRequestBuilder(request)
+}
+
+pub fn get(path: &str) -> ApiRequestBuilder {
+    build(Request::get(&url(path)))
+}
+
+pub fn post(path: &str) -> ApiRequestBuilder {
+    build(Request::post(&url(path)))
+}
+
+pub fn put(path: &str) -> ApiRequestBuilder {
+    build(Request::put(&url(path)))
+}
+
+pub fn patch(path: &str) -> ApiRequestBuilder {
+    build(Request::patch(&url(path)))
+}
+
+pub fn delete(path: &str) -> ApiRequestBuilder {
+    build(Request::delete(&url(path)))
+}
+
+impl ApiRequestBuilder {
+    pub fn header(self, name: &str, value: &str) -> Self {
+        Self(self.0.header(name, value))
+    }
+
+    pub fn json<T: Serialize + ?Sized>(self, value: &T) -> Result<ApiRequest, gloo_net::Error> {
+        self.0.json(value).map(ApiRequest)
+    }
+
+    pub fn body(self, body: impl Into<JsValue>) -> Result<ApiRequest, gloo_net::Error> {
+        self.0.body(body).map(ApiRequest)
+    }
+
+    pub async fn send(self) -> Result<Response, gloo_net::Error> {
+        checked(self.0.send().await)
+    }
+}
+
+impl ApiRequest {
+    pub async fn send(self) -> Result<Response, gloo_net::Error> {
+        checked(self.0.send().await)
+    }
+}
+
+/// A 401 means the token expired or was revoked: sign out, which shows the login page
+fn checked(result: Result<Response, gloo_net::Error>) -> Result<Response, gloo_net::Error> {
+    if let Ok(response) = &result {
+        if response.status() == 401 {
+            if let Some(auth) = AUTH.with(|cell| cell.get()) {
+                auth.clear();
+            }
+        }
+    }
+    result
+}
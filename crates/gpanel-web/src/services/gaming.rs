@@ -0,0 +1,161 @@
+// Data behind the gaming dashboard: containers with a gaming config, their live stats, the
+// host's GPUs and the alert thresholds, which are kept in the browser.
+
+use futures::StreamExt;
+use futures::future::{AbortHandle, Abortable};
+use gloo_net::eventsource::futures::EventSource;
+use gloo_storage::{LocalStorage, Storage};
+use gpanel_core::{
+    Container, ContainerListResponse, ContainerOperationRequest, ContainerStats, GamingAlertThresholds, GpuDevice,
+    SteamApp,
+};
+use std::cell::Cell;
+use std::rc::Rc;
+
+use super::{api_client, error_message};
+
+/// localStorage key holding the alert thresholds set on the dashboard
+const THRESHOLDS_STORAGE_KEY: &str = "gpanel.gaming_thresholds";
+
+/// Every container with a gaming config, running or not
+pub async fn gaming_containers() -> Result<Vec<Container>, String> {
+    let response = api_client::get("/api/v1/containers?gaming=true")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to load gaming containers: {}", e))?;
+    if !response.ok() {
+        return Err(error_message(&response).await);
+    }
+    response
+        .json::<ContainerListResponse>()
+        .await
+        .map(|list| list.containers)
+        .map_err(|e| format!("Unexpected response: {}", e))
+}
+
+/// GPUs on the agent host, each with the running containers allocated to it
+pub async fn gpu_inventory() -> Result<Vec<GpuDevice>, String> {
+    let response = api_client::get("/api/v1/system/gpus")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to load GPUs: {}", e))?;
+    if !response.ok() {
+        return Err(error_message(&response).await);
+    }
+    response.json::<Vec<GpuDevice>>().await.map_err(|e| format!("Unexpected response: {}", e))
+}
+
+/// Store details of a Steam app; `None` when the agent cannot look it up
+pub async fn steam_app(app_id: u32) -> Option<SteamApp> {
+    let response = api_client::get(&format!("/api/v1/gaming/steam/apps/{}", app_id)).send().await.ok()?;
+    if !response.ok() {
+        return None;
+    }
+    response.json::<SteamApp>().await.ok()
+}
+
+pub async fn restart_container(id: &str) -> Result<(), String> {
+    let request = ContainerOperationRequest {
+        action: "restart".to_string(),
+        timeout: Some(30),
+        force: None,
+        remove_volumes: None,
+    };
+    let url = format!("/api/v1/containers/{}/restart", urlencoding::encode(id));
+    let response = api_client::post(&url)
+        .json(&request)
+        .map_err(|e| format!("Request error: {}", e))?
+        .send()
+        .await
+        .map_err(|e| format!("Failed to restart container: {}", e))?;
+    if !response.ok() {
+        return Err(error_message(&response).await);
+    }
+    Ok(())
+}
+
+/// The last `tail` lines of a container's logs
+pub async fn container_logs(id: &str, tail: u32) -> Result<String, String> {
+    let url = format!("/api/v1/containers/{}/logs?tail={}", urlencoding::encode(id), tail);
+    let response = api_client::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to load logs: {}", e))?;
+    if !response.ok() {
+        return Err(error_message(&response).await);
+    }
+    response.text().await.map_err(|e| format!("Failed to load logs: {}", e))
+}
+
+/// Thresholds saved in this browser, or the defaults
+pub fn alert_thresholds() -> GamingAlertThresholds {
+    LocalStorage::get(THRESHOLDS_STORAGE_KEY).unwrap_or_default()
+}
+
+pub fn set_alert_thresholds(thresholds: &GamingAlertThresholds) {
+    let _ = LocalStorage::set(THRESHOLDS_STORAGE_KEY, thresholds);
+}
+
+/// A container's stats stream; dropping it closes the EventSource and stops the reader task
+pub struct StatsStream {
+    _source: EventSource,
+    abort: AbortHandle,
+    open: Rc<Cell<bool>>,
+}
+
+impl StatsStream {
+    /// False once the agent ended the stream, e.g. because the container stopped, or the
+    /// connection was lost
+    pub fn is_open(&self) -> bool {
+        self.open.get()
+    }
+}
+
+impl Drop for StatsStream {
+    fn drop(&mut self) {
+        self.abort.abort();
+    }
+}
+
+/// Follow a container's stats, calling `on_sample` with each sample the agent sends
+pub fn stream_stats(
+    container_id: &str,
+    interval_secs: u64,
+    mut on_sample: impl FnMut(ContainerStats) + 'static,
+) -> Result<StatsStream, String> {
+    let url = format!(
+        "/api/v1/containers/{}/stats/stream?interval={}",
+        urlencoding::encode(container_id),
+        interval_secs
+    );
+    let mut source = EventSource::new(&api_client::authorized_url(&url))
+        .map_err(|e| format!("Failed to follow stats: {:?}", e))?;
+    let (Ok(samples), Ok(ends)) = (source.subscribe("message"), source.subscribe("end")) else {
+        return Err("Failed to follow stats".to_string());
+    };
+
+    let (abort, registration) = AbortHandle::new_pair();
+    let open = Rc::new(Cell::new(true));
+    let reader_open = open.clone();
+    let read = async move {
+        let mut events = futures::stream::select(samples, ends);
+        while let Some(Ok((kind, message))) = events.next().await {
+            if kind == "end" {
+                break;
+            }
+            let sample = message
+                .data()
+                .as_string()
+                .and_then(|data| serde_json::from_str::<ContainerStats>(&data).ok());
+            if let Some(sample) = sample {
+                on_sample(sample);
+            }
+        }
+        reader_open.set(false);
+    };
+    wasm_bindgen_futures::spawn_local(async move {
+        let _ = Abortable::new(read, registration).await;
+    });
+
+    Ok(StatsStream { _source: source, abort, open })
+}
@@ -1 +1,76 @@
-// Services module for API calls and business logic
\ No newline at end of file
+// Services module for API calls and business logic.
+
+pub mod api_client;
+pub mod gaming;
+
+use gloo_net::http::{Request, Response};
+use gpanel_core::{ApiErrorBody, ApiErrorResponse, LoginRequest, SessionResponse, WhoAmIResponse};
+
+/// Error body of a failed agent response. Bodies that are not the agent's
+/// `{ "error": { ... } }` envelope, e.g. from a proxy, fall back to the HTTP status.
+pub async fn api_error(response: &Response) -> ApiErrorBody {
+    match response.json::<ApiErrorResponse>().await {
+        Ok(envelope) => envelope.error,
+        Err(_) => ApiErrorBody {
+            code: "HTTP_ERROR".to_string(),
+            message: format!("Request failed with status {} {}", response.status(), response.status_text()),
+            details: None,
+        },
+    }
+}
+
+/// Message to show for a failed agent response
+pub async fn error_message(response: &Response) -> String {
+    api_error(response).await.message
+}
+
+/// Check a token with the agent before signing in with it
+pub async fn whoami(token: &str) -> Result<WhoAmIResponse, String> {
+    let response = Request::get(&api_client::url("/api/v1/auth/whoami"))
+        .header("Authorization", &format!("Bearer {}", token.trim()))
+        .send()
+        .await
+        .map_err(|e| format!("Could not reach the agent: {}", e))?;
+    if !response.ok() {
+        return Err(error_message(&response).await);
+    }
+    response.json::<WhoAmIResponse>().await.map_err(|e| format!("Unexpected response: {}", e))
+}
+
+/// Sign in with a local account
+pub async fn login(username: &str, password: &str) -> Result<SessionResponse, String> {
+    let request = LoginRequest {
+        username: username.trim().to_string(),
+        password: password.to_string(),
+    };
+    let response = Request::post(&api_client::url("/api/v1/auth/login"))
+        .json(&request)
+        .map_err(|e| format!("Request error: {}", e))?
+        .send()
+        .await
+        .map_err(|e| format!("Could not reach the agent: {}", e))?;
+    if !response.ok() {
+        return Err(error_message(&response).await);
+    }
+    response.json::<SessionResponse>().await.map_err(|e| format!("Unexpected response: {}", e))
+}
+
+/// End the session held in `token` on the agent
+pub async fn logout(token: &str) {
+    let _ = Request::post(&api_client::url("/api/v1/auth/logout"))
+        .header("Authorization", &format!("Bearer {}", token))
+        .send()
+        .await;
+}
+
+/// Exchange the current session token for a new one
+pub async fn refresh() -> Result<SessionResponse, String> {
+    let response = api_client::post("/api/v1/auth/refresh")
+        .send()
+        .await
+        .map_err(|e| format!("Could not reach the agent: {}", e))?;
+    if !response.ok() {
+        return Err(error_message(&response).await);
+    }
+    response.json::<SessionResponse>().await.map_err(|e| format!("Unexpected response: {}", e))
+}
@@ -1 +1,3 @@
-// Utility functions for the web interface
\ No newline at end of file
+// Utility functions for the web interface
+
+pub mod stats;
@@ -0,0 +1,124 @@
+use chrono::{DateTime, Utc};
+use gpanel_core::ContainerStats;
+use std::collections::VecDeque;
+
+/// One chart point derived from a stats sample
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartPoint {
+    pub timestamp: DateTime<Utc>,
+    pub cpu_percent: f64,
+    pub memory_percent: f64,
+    pub network_rx_per_sec: f64,
+    pub network_tx_per_sec: f64,
+    pub fps: Option<f32>,
+    pub frame_time_ms: Option<f32>,
+    pub input_latency_ms: Option<f32>,
+}
+
+/// Rolling chart buffer fed with stats samples as they are polled
+#[derive(Debug, Clone)]
+pub struct StatsHistory {
+    capacity: usize,
+    points: VecDeque<ChartPoint>,
+    last: Option<ContainerStats>,
+}
+
+impl StatsHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            points: VecDeque::with_capacity(capacity),
+            last: None,
+        }
+    }
+
+    /// Convert a sample into a chart point, deriving network rates from the previous sample
+    pub fn push(&mut self, sample: ContainerStats) {
+        if let Some(last) = &self.last {
+            // Polls can overlap, so ignore samples that arrive out of order
+            if sample.timestamp <= last.timestamp {
+                return;
+            }
+        }
+
+        let rate = |new: u64, old: Option<u64>, secs: f64| match old {
+            // Counters reset when the container restarts
+            Some(old) if secs > 0.0 && new >= old => (new - old) as f64 / secs,
+            _ => 0.0,
+        };
+        let secs = self
+            .last
+            .as_ref()
+            .map(|last| (sample.timestamp - last.timestamp).num_milliseconds() as f64 / 1000.0)
+            .unwrap_or(0.0);
+
+        let point = ChartPoint {
+            timestamp: sample.timestamp,
+            cpu_percent: sample.cpu_percent,
+            memory_percent: if sample.memory_limit > 0 {
+                sample.memory_usage as f64 / sample.memory_limit as f64 * 100.0
+            } else {
+                0.0
+            },
+            network_rx_per_sec: rate(sample.network_rx, self.last.as_ref().map(|l| l.network_rx), secs),
+            network_tx_per_sec: rate(sample.network_tx, self.last.as_ref().map(|l| l.network_tx), secs),
+            fps: sample.gaming.as_ref().and_then(|gaming| gaming.fps),
+            frame_time_ms: sample.gaming.as_ref().and_then(|gaming| gaming.frame_time_ms),
+            input_latency_ms: sample.gaming.as_ref().and_then(|gaming| gaming.input_latency_ms),
+        };
+
+        if self.points.len() == self.capacity {
+            self.points.pop_front();
+        }
+        self.points.push_back(point);
+        self.last = Some(sample);
+    }
+
+    pub fn latest(&self) -> Option<&ChartPoint> {
+        self.points.back()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The sample the last point was derived from
+    pub fn last_sample(&self) -> Option<&ContainerStats> {
+        self.last.as_ref()
+    }
+
+    /// SVG polyline points for one series scaled to `max`, oldest sample on the left
+    pub fn polyline(&self, value: fn(&ChartPoint) -> f64, max: f64, width: f64, height: f64) -> String {
+        let step = width / self.capacity.saturating_sub(1).max(1) as f64;
+        let offset = self.capacity.saturating_sub(self.points.len());
+        self.points
+            .iter()
+            .enumerate()
+            .map(|(i, point)| {
+                let y = height - (value(point) / max).clamp(0.0, 1.0) * height;
+                format!("{:.1},{:.1}", (offset + i) as f64 * step, y)
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Polyline for a series samples may lack, scaled to its largest value so small
+    /// sparklines show movement; `None` until some sample has it
+    pub fn sparkline(&self, value: fn(&ChartPoint) -> Option<f32>, width: f64, height: f64) -> Option<String> {
+        let max = self.points.iter().filter_map(value).reduce(f32::max)?;
+        let max = f64::from(max).max(f64::EPSILON) * 1.1;
+        let step = width / self.capacity.saturating_sub(1).max(1) as f64;
+        let offset = self.capacity.saturating_sub(self.points.len());
+        let points = self
+            .points
+            .iter()
+            .enumerate()
+            .filter_map(|(i, point)| {
+                let y = height - (f64::from(value(point)?) / max).clamp(0.0, 1.0) * height;
+                Some(format!("{:.1},{:.1}", (offset + i) as f64 * step, y))
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        Some(points)
+    }
+}